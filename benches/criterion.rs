@@ -0,0 +1,34 @@
+//! The workloads of `dhcp::bench` measured with criterion, which runs each
+//! until the timing is stable and compares it with the previous run.
+//!
+//! A workload name given as argument runs that workload only:
+//!
+//! ```text
+//! cargo bench --features bench --bench criterion -- parse_packets
+//! ```
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use dhcp::bench::{captured_packets, workloads};
+
+// The iterations of a workload timed at once, a pass over the packets.
+const ITERATIONS: usize = 64;
+
+fn bench_workloads(c: &mut Criterion) {
+    let packets = captured_packets();
+    let mut group = c.benchmark_group("workloads");
+    group.throughput(Throughput::Elements(ITERATIONS as u64));
+    for workload in workloads() {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(workload.name),
+            &packets,
+            |b, packets| b.iter(|| (workload.run)(black_box(packets), ITERATIONS)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_workloads);
+criterion_main!(benches);
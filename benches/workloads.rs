@@ -0,0 +1,35 @@
+//! Run the workloads of `dhcp::bench`, printing the time taken by each.
+//!
+//! A workload name given as argument runs that workload only.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use dhcp::bench::{captured_packets, workloads};
+
+fn main() {
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let packets = captured_packets();
+
+    for workload in workloads() {
+        if filter
+            .as_deref()
+            .is_some_and(|filter| filter != workload.name)
+        {
+            continue;
+        }
+        // A short run first, to warm the caches and the allocator.
+        black_box((workload.run)(&packets, workload.size / 100));
+
+        let start = Instant::now();
+        black_box((workload.run)(black_box(&packets), workload.size));
+        let elapsed = start.elapsed();
+        println!(
+            "{:<24} {:>9} iterations {:>10.3} ms {:>8.1} ns/iteration",
+            workload.name,
+            workload.size,
+            elapsed.as_secs_f64() * 1e3,
+            elapsed.as_nanos() as f64 / workload.size as f64
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! Obtain a lease from the DHCP servers reachable on an interface.
+//!
+//! Usage: dhcp-client INTERFACE [-j FILE] [-t SECONDS]
+//!
+//! The interface provides the client hardware address. Binding the client
+//! port usually requires elevated privileges, and no system configuration
+//! is changed: the lease is only printed, and optionally written as JSON.
+
+use std::env;
+use std::fs;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::process;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use dhcp::error::{DhcpError, NakReason};
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::net::{self, SocketConfig};
+use dhcp::option::DhcpOption;
+
+const USAGE: &str = "Usage: dhcp-client INTERFACE [-j FILE] [-t SECONDS]
+
+  -j FILE      write the obtained lease as JSON
+  -t SECONDS   give up after this many seconds (default 30)";
+
+const OPTION_PARAMETER_REQUEST_LIST: u8 = 55;
+const OPTION_MESSAGE: u8 = 56;
+
+// The client states of RFC 2131 section 4.4 this tool goes through.
+enum State {
+    Selecting,
+    Requesting { offer: DhcpMessage },
+    Bound { ack: DhcpMessage },
+}
+
+#[derive(Debug)]
+struct Lease {
+    address: Ipv4Addr,
+    server: Option<Ipv4Addr>,
+    subnet_mask: Option<Ipv4Addr>,
+    routers: Vec<Ipv4Addr>,
+    dns_servers: Vec<Ipv4Addr>,
+    domain_name: Option<String>,
+    lease_time: Option<u32>,
+}
+
+impl Lease {
+    fn from_ack(ack: &DhcpMessage) -> Lease {
+        let mut lease = Lease {
+            address: ack.yiaddr,
+            server: ack.server_identifier(),
+            subnet_mask: None,
+            routers: Vec::new(),
+            dns_servers: Vec::new(),
+            domain_name: None,
+            lease_time: None,
+        };
+        for option in &ack.options {
+            match option {
+                DhcpOption::SubnetMask(mask) => lease.subnet_mask = Some(*mask),
+                DhcpOption::Router(routers) => lease.routers = routers.to_vec(),
+                DhcpOption::DomainNameServer(servers) => lease.dns_servers = servers.to_vec(),
+                DhcpOption::DomainName(name) => lease.domain_name = Some(name.clone()),
+                DhcpOption::IpAddressLeaseTime(time) => lease.lease_time = Some(*time),
+                _ => {}
+            }
+        }
+        lease
+    }
+
+    fn to_json(&self) -> String {
+        let address = |address: &Option<Ipv4Addr>| match address {
+            Some(address) => quote(&address.to_string()),
+            None => "null".to_string(),
+        };
+        let addresses = |addresses: &[Ipv4Addr]| {
+            addresses
+                .iter()
+                .map(|address| quote(&address.to_string()))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        format!(
+            "{{\n  \"address\": \"{}\",\n  \"server\": {},\n  \"subnet_mask\": {},\n  \
+             \"routers\": [{}],\n  \"dns_servers\": [{}],\n  \"domain_name\": {},\n  \
+             \"lease_time\": {}\n}}\n",
+            self.address,
+            address(&self.server),
+            address(&self.subnet_mask),
+            addresses(&self.routers),
+            addresses(&self.dns_servers),
+            match &self.domain_name {
+                Some(name) => quote(name),
+                None => "null".to_string(),
+            },
+            match self.lease_time {
+                Some(time) => time.to_string(),
+                None => "null".to_string(),
+            }
+        )
+    }
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let mut interface = None;
+    let mut json = None;
+    let mut timeout = Duration::from_secs(30);
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-j" => json = args.next().cloned(),
+            "-t" => match args.next().and_then(|value| value.parse().ok()) {
+                Some(seconds) => timeout = Duration::from_secs(seconds),
+                None => usage(),
+            },
+            "-h" | "--help" => usage(),
+            _ if interface.is_none() && !arg.starts_with('-') => interface = Some(arg.clone()),
+            _ => usage(),
+        }
+    }
+    let interface = interface.unwrap_or_else(|| usage());
+
+    let lease = match run(&interface, timeout) {
+        Ok(lease) => lease,
+        Err(error) => {
+            eprintln!("dhcp-client: {}", error);
+            process::exit(1);
+        }
+    };
+
+    print!("{}", lease.to_json());
+    if let Some(path) = json {
+        if let Err(error) = fs::write(&path, lease.to_json()) {
+            eprintln!("dhcp-client: {}: {}", path, error);
+            process::exit(1);
+        }
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn run(interface: &str, timeout: Duration) -> Result<Lease, String> {
+    let mac = hardware_address(interface)?;
+    // Only talk to the servers on the interface, even when the host has
+    // other ones.
+    let config = SocketConfig {
+        broadcast: true,
+        reuse_address: true,
+        device: Some(interface.to_string()),
+        ..SocketConfig::default()
+    };
+    let socket = net::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 68), &config)
+        .map_err(|error| error.to_string())?;
+
+    let deadline = Instant::now() + timeout;
+    let xid = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    let mut state = State::Selecting;
+
+    loop {
+        state = match state {
+            State::Selecting => {
+                let discover = request(MessageType::Discover, xid, mac, &[]);
+                let offer = exchange(&socket, &discover, MessageType::Offer, deadline)?;
+                State::Requesting { offer }
+            }
+            State::Requesting { offer } => {
+                let mut options = vec![DhcpOption::RequestedIpAddress(offer.yiaddr)];
+                if let Some(server) = offer.server_identifier() {
+                    options.push(DhcpOption::ServerIdentifier(server));
+                }
+                let request = request(MessageType::Request, xid, mac, &options);
+                match exchange(&socket, &request, MessageType::Ack, deadline) {
+                    Ok(ack) => State::Bound { ack },
+                    Err(error) => {
+                        eprintln!("dhcp-client: {}, restarting", error);
+                        State::Selecting
+                    }
+                }
+            }
+            State::Bound { ack } => return Ok(Lease::from_ack(&ack)),
+        };
+    }
+}
+
+// Build a broadcast client message of the given type.
+fn request(
+    message_type: MessageType,
+    xid: u32,
+    mac: [u8; 6],
+    options: &[DhcpOption],
+) -> DhcpMessage {
+    let mut message = DhcpMessage::new(1, xid);
+    message.flags.set_broadcast(true);
+    message.chaddr[..6].copy_from_slice(&mac);
+    message.options = vec![
+        DhcpOption::from(message_type),
+        DhcpOption::ClientIdentifier([&[1][..], &mac].concat()),
+    ]
+    .into();
+    message.options.extend_from_slice(options);
+    message.options.push(DhcpOption::Unknown(
+        OPTION_PARAMETER_REQUEST_LIST,
+        vec![1, 3, 6, 15, 51, 54],
+    ));
+    message
+}
+
+// Broadcast a message until a reply of the expected type arrives, backing
+// off exponentially from 4 seconds (RFC 2131 section 4.1).
+fn exchange(
+    socket: &UdpSocket,
+    message: &DhcpMessage,
+    expected: MessageType,
+    deadline: Instant,
+) -> Result<DhcpMessage, String> {
+    let mut backoff = Duration::from_secs(4);
+    let mut buffer = [0; 1500];
+
+    loop {
+        socket
+            .send_to(
+                &message.serialize().map_err(|error| error.to_string())?,
+                (Ipv4Addr::BROADCAST, 67),
+            )
+            .map_err(|error| error.to_string())?;
+
+        let retransmit = (Instant::now() + backoff).min(deadline);
+        while let Some(wait) = retransmit.checked_duration_since(Instant::now()) {
+            if wait.is_zero() {
+                break;
+            }
+            socket
+                .set_read_timeout(Some(wait))
+                .map_err(|error| error.to_string())?;
+            let len = match socket.recv_from(&mut buffer) {
+                Ok((len, SocketAddr::V4(_))) => len,
+                Ok(_) => continue,
+                Err(_) => break,
+            };
+            let reply = match DhcpMessage::deserialize(&buffer[..len]) {
+                Ok(reply) if reply.op == 2 && reply.xid == message.xid => reply,
+                _ => continue,
+            };
+            match reply.message_type() {
+                Ok(Some(message_type)) if message_type == expected => return Ok(reply),
+                Ok(Some(MessageType::Nak)) => {
+                    return Err(DhcpError::Nak(nak_reason(&reply)).to_string())
+                }
+                _ => {}
+            }
+        }
+
+        if Instant::now() >= deadline {
+            return Err("Timed out waiting for a server".to_string());
+        }
+        backoff = (backoff * 2).min(Duration::from_secs(64));
+    }
+}
+
+fn hardware_address(interface: &str) -> Result<[u8; 6], String> {
+    let path = format!("/sys/class/net/{}/address", interface);
+    let address = fs::read_to_string(&path).map_err(|error| format!("{}: {}", path, error))?;
+    let bytes = address
+        .trim()
+        .split(':')
+        .map(|byte| u8::from_str_radix(byte, 16).ok())
+        .collect::<Option<Vec<u8>>>();
+    bytes
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(format!("{} has no Ethernet address", interface))
+}
+
+// Quote a JSON string, escaping quotes, backslashes and control characters
+// (RFC 8259 section 7).
+fn quote(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn nak_reason(message: &DhcpMessage) -> NakReason {
+    let text = message.options.iter().find_map(|option| match option {
+        DhcpOption::Unknown(OPTION_MESSAGE, value) => Some(String::from_utf8_lossy(value)),
+        _ => None,
+    });
+    NakReason::from_message(text.as_deref().unwrap_or("no reason given"))
+}
@@ -0,0 +1,20 @@
+#![no_main]
+
+use dhcp::message::DhcpMessage;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = dhcp::hexdump::hexdump(data);
+
+    // Messages that parse must serialize to something that parses back to
+    // the same message.
+    if let Ok(message) = DhcpMessage::deserialize(data) {
+        let serialized = match message.serialize() {
+            Ok(serialized) => serialized,
+            // Domain search lists decoded with compression may no longer
+            // fit once written out without it.
+            Err(_) => return,
+        };
+        assert_eq!(DhcpMessage::deserialize(&serialized).ok(), Some(message));
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use dhcp::option::DhcpOption;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let mut data = data;
+    while let Ok((_, rest)) = DhcpOption::deserialize(data) {
+        // Each option must consume at least its code.
+        assert!(rest.len() < data.len());
+        data = rest;
+    }
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use dhcp::v6::message::Dhcpv6Packet;
+use dhcp::v6::option::Dhcpv6Option;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Dhcpv6Packet::deserialize(data);
+    let _ = Dhcpv6Option::deserialize_all(data);
+});
@@ -0,0 +1,149 @@
+use crate::auth::{
+    constant_time_eq, find_authentication, hmac_md5, insert_authentication, mac_input,
+    ReplayDetector, ALGORITHM_HMAC_MD5, MAC_LEN, RDM_MONOTONIC_COUNTER,
+};
+use crate::error::DhcpError;
+use crate::option::{Authentication, DhcpOption};
+use crate::raw::{find_option, message_type};
+
+/// The forcerenew nonce authentication protocol (RFC 6704).
+pub const PROTOCOL_FORCERENEW_NONCE: u8 = 3;
+
+const OPTION_FORCERENEW_NONCE_CAPABLE: u8 = 145;
+const DHCPFORCERENEW: u8 = 9;
+
+// Authentication information types.
+const TYPE_NONCE: u8 = 1;
+const TYPE_HMAC_MD5: u8 = 2;
+
+// Offset of the nonce or MAC in the authentication option, after the code,
+// length, protocol, algorithm, RDM, replay detection and type fields.
+const VALUE_OFFSET: usize = 2 + 3 + 8 + 1;
+
+/// The forcerenew nonce capable option sent by clients supporting HMAC-MD5.
+pub fn nonce_capable_option() -> DhcpOption {
+    DhcpOption::ForcerenewNonceCapable(vec![ALGORITHM_HMAC_MD5])
+}
+
+/// Check whether the client that sent `packet` announced support for
+/// HMAC-MD5 forcerenew nonce authentication.
+pub fn is_nonce_capable(packet: &[u8]) -> Result<bool, DhcpError> {
+    match find_option(packet, OPTION_FORCERENEW_NONCE_CAPABLE)? {
+        Some((start, len)) => Ok(packet[start + 2..start + 2 + len].contains(&ALGORITHM_HMAC_MD5)),
+        None => Ok(false),
+    }
+}
+
+/// A nonce shared by a server and a client to authenticate FORCERENEW
+/// messages.
+///
+/// The server issues it in the DHCPACK and keys the HMAC of later
+/// FORCERENEW messages with it; the client keeps it, along with the replay
+/// detection values it has seen, to verify them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForcerenewNonce {
+    nonce: [u8; 16],
+    replay_detector: ReplayDetector,
+}
+
+impl ForcerenewNonce {
+    /// Create a nonce from 16 random bytes.
+    pub fn new(nonce: [u8; 16]) -> ForcerenewNonce {
+        ForcerenewNonce {
+            nonce,
+            replay_detector: ReplayDetector::new(),
+        }
+    }
+
+    pub fn nonce(&self) -> &[u8; 16] {
+        &self.nonce
+    }
+
+    /// Retrieve the nonce issued in a serialized DHCPACK.
+    pub fn from_ack(packet: &[u8]) -> Result<ForcerenewNonce, DhcpError> {
+        let (_, authentication) = find_authentication(packet)?;
+        check_protocol(&authentication, TYPE_NONCE)?;
+
+        let mut nonce = ForcerenewNonce::new([0; 16]);
+        nonce
+            .nonce
+            .copy_from_slice(&authentication.information[1..]);
+        nonce
+            .replay_detector
+            .accept(authentication.replay_detection)?;
+        Ok(nonce)
+    }
+
+    /// Issue the nonce in a serialized DHCPACK.
+    pub fn add_to_ack(&self, packet: &mut Vec<u8>, replay_detection: u64) -> Result<(), DhcpError> {
+        insert_authentication(
+            packet,
+            authentication(TYPE_NONCE, replay_detection, &self.nonce),
+        )?;
+        Ok(())
+    }
+
+    /// Authenticate a serialized FORCERENEW message.
+    pub fn sign(&self, packet: &mut Vec<u8>, replay_detection: u64) -> Result<(), DhcpError> {
+        let start = insert_authentication(
+            packet,
+            authentication(TYPE_HMAC_MD5, replay_detection, &[0; MAC_LEN]),
+        )?;
+
+        let mac = hmac_md5(&self.nonce, &mac_input(packet, start + VALUE_OFFSET, false));
+        packet[start + VALUE_OFFSET..start + VALUE_OFFSET + MAC_LEN].copy_from_slice(&mac);
+        Ok(())
+    }
+
+    /// Verify a serialized FORCERENEW message received by the client.
+    pub fn verify(&mut self, packet: &[u8]) -> Result<(), DhcpError> {
+        if message_type(packet)? != Some(DHCPFORCERENEW) {
+            return Err(DhcpError::AuthenticationError(
+                "Expected a DHCPFORCERENEW message".to_string(),
+            ));
+        }
+
+        let (start, authentication) = find_authentication(packet)?;
+        check_protocol(&authentication, TYPE_HMAC_MD5)?;
+
+        let mac = hmac_md5(&self.nonce, &mac_input(packet, start + VALUE_OFFSET, false));
+        if !constant_time_eq(&mac, &authentication.information[1..]) {
+            return Err(DhcpError::AuthenticationError(
+                "Invalid message authentication code".to_string(),
+            ));
+        }
+
+        self.replay_detector.accept(authentication.replay_detection)
+    }
+}
+
+fn authentication(information_type: u8, replay_detection: u64, value: &[u8]) -> Authentication {
+    let mut information = vec![information_type];
+    information.extend_from_slice(value);
+    Authentication {
+        protocol: PROTOCOL_FORCERENEW_NONCE,
+        algorithm: ALGORITHM_HMAC_MD5,
+        rdm: RDM_MONOTONIC_COUNTER,
+        replay_detection,
+        information,
+    }
+}
+
+fn check_protocol(authentication: &Authentication, information_type: u8) -> Result<(), DhcpError> {
+    if authentication.protocol != PROTOCOL_FORCERENEW_NONCE
+        || authentication.algorithm != ALGORITHM_HMAC_MD5
+        || authentication.rdm != RDM_MONOTONIC_COUNTER
+    {
+        return Err(DhcpError::AuthenticationError(
+            "Unsupported authentication protocol".to_string(),
+        ));
+    }
+    if authentication.information.len() != 1 + MAC_LEN
+        || authentication.information[0] != information_type
+    {
+        return Err(DhcpError::AuthenticationError(
+            "Could not parse forcerenew nonce authentication information".to_string(),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use crate::error::DhcpError;
+use crate::option::{Authentication, DhcpOption};
+use crate::raw::{find_option, options_end, GIADDR_OFFSET, HOPS_OFFSET};
+
+pub mod forcerenew;
+mod md5;
+
+/// The delayed authentication protocol (RFC 3118 section 5).
+pub const PROTOCOL_DELAYED: u8 = 2;
+/// The HMAC-MD5 algorithm, the only one defined for delayed authentication.
+pub const ALGORITHM_HMAC_MD5: u8 = 1;
+/// The monotonically increasing counter replay detection method.
+pub const RDM_MONOTONIC_COUNTER: u8 = 0;
+
+const OPTION_AUTHENTICATION: u8 = 90;
+const MAC_LEN: usize = 16;
+// Offset of the MAC in the authentication option, after the code, length,
+// protocol, algorithm, RDM, replay detection and secret ID fields.
+const MAC_OFFSET: usize = 2 + 3 + 8 + 4;
+
+/// Compute the HMAC-MD5 (RFC 2104) of `data` keyed with `key`.
+pub fn hmac_md5(key: &[u8], data: &[u8]) -> [u8; 16] {
+    let mut block = [0; 64];
+    if key.len() > block.len() {
+        block[..16].copy_from_slice(&md5::md5(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = block.iter().map(|byte| byte ^ 0x36).collect::<Vec<u8>>();
+    inner.extend_from_slice(data);
+    let mut outer = block.iter().map(|byte| byte ^ 0x5c).collect::<Vec<u8>>();
+    outer.extend_from_slice(&md5::md5(&inner));
+    md5::md5(&outer)
+}
+
+/// A source of the secrets shared between clients and servers, indexed by
+/// the secret ID carried in the authentication option.
+pub trait KeyStore {
+    fn key(&self, secret_id: u32) -> Option<&[u8]>;
+}
+
+impl KeyStore for HashMap<u32, Vec<u8>> {
+    fn key(&self, secret_id: u32) -> Option<&[u8]> {
+        self.get(&secret_id).map(|key| key.as_slice())
+    }
+}
+
+/// Track the replay detection field of the messages received from a peer,
+/// rejecting any value that does not strictly increase.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayDetector {
+    last: Option<u64>,
+}
+
+impl ReplayDetector {
+    pub fn new() -> ReplayDetector {
+        ReplayDetector::default()
+    }
+
+    /// The last replay detection value accepted.
+    pub fn last(&self) -> Option<u64> {
+        self.last
+    }
+
+    /// Accept `replay_detection` if it is greater than every value accepted
+    /// so far.
+    pub fn accept(&mut self, replay_detection: u64) -> Result<(), DhcpError> {
+        if let Some(last) = self.last {
+            if replay_detection <= last {
+                return Err(DhcpError::AuthenticationError(
+                    "Replayed DHCP message".to_string(),
+                ));
+            }
+        }
+        self.last = Some(replay_detection);
+        Ok(())
+    }
+}
+
+/// Add a delayed authentication option to a serialized DHCP packet and
+/// compute its MAC.
+///
+/// An authentication option already present in the packet is replaced.
+pub fn sign(
+    packet: &mut Vec<u8>,
+    secret_id: u32,
+    key: &[u8],
+    replay_detection: u64,
+) -> Result<(), DhcpError> {
+    let mut information = secret_id.to_be_bytes().to_vec();
+    information.extend_from_slice(&[0; MAC_LEN]);
+    let start = insert_authentication(
+        packet,
+        Authentication {
+            protocol: PROTOCOL_DELAYED,
+            algorithm: ALGORITHM_HMAC_MD5,
+            rdm: RDM_MONOTONIC_COUNTER,
+            replay_detection,
+            information,
+        },
+    )?;
+
+    let mac = hmac_md5(key, &mac_input(packet, start + MAC_OFFSET, true));
+    packet[start + MAC_OFFSET..start + MAC_OFFSET + MAC_LEN].copy_from_slice(&mac);
+    Ok(())
+}
+
+/// Verify the delayed authentication option of a serialized DHCP packet.
+///
+/// The replay detection value is only recorded once the MAC is known to be
+/// valid. Returns the secret ID used to authenticate the packet.
+pub fn verify(
+    packet: &[u8],
+    keys: &impl KeyStore,
+    replay_detector: &mut ReplayDetector,
+) -> Result<u32, DhcpError> {
+    let (start, authentication) = find_authentication(packet)?;
+    if authentication.protocol != PROTOCOL_DELAYED
+        || authentication.algorithm != ALGORITHM_HMAC_MD5
+        || authentication.rdm != RDM_MONOTONIC_COUNTER
+    {
+        return Err(DhcpError::AuthenticationError(
+            "Unsupported authentication protocol".to_string(),
+        ));
+    }
+    if authentication.information.len() != 4 + MAC_LEN {
+        return Err(DhcpError::AuthenticationError(
+            "Could not parse delayed authentication information".to_string(),
+        ));
+    }
+
+    let information = &authentication.information;
+    let secret_id = u32::from_be_bytes([
+        information[0],
+        information[1],
+        information[2],
+        information[3],
+    ]);
+    let key = match keys.key(secret_id) {
+        Some(key) => key,
+        None => {
+            return Err(DhcpError::AuthenticationError(format!(
+                "Unknown secret ID: {}",
+                secret_id
+            )))
+        }
+    };
+
+    let mac = hmac_md5(key, &mac_input(packet, start + MAC_OFFSET, true));
+    if !constant_time_eq(&mac, &information[4..]) {
+        return Err(DhcpError::AuthenticationError(
+            "Invalid message authentication code".to_string(),
+        ));
+    }
+
+    replay_detector.accept(authentication.replay_detection)?;
+    Ok(secret_id)
+}
+
+// Add an authentication option at the end of the options, replacing any
+// authentication option already present, and return its offset.
+fn insert_authentication(
+    packet: &mut Vec<u8>,
+    authentication: Authentication,
+) -> Result<usize, DhcpError> {
+    if let Some((start, len)) = find_option(packet, OPTION_AUTHENTICATION)? {
+        packet.drain(start..start + 2 + len);
+    }
+
+    let end = options_end(packet)?;
+    packet.splice(
+        end..end,
+        DhcpOption::Authentication(authentication).serialize()?,
+    );
+    Ok(end)
+}
+
+// Find and parse the authentication option, returning its offset.
+fn find_authentication(packet: &[u8]) -> Result<(usize, Authentication), DhcpError> {
+    let start = match find_option(packet, OPTION_AUTHENTICATION)? {
+        Some((start, _)) => start,
+        None => {
+            return Err(DhcpError::AuthenticationError(
+                "Missing authentication option".to_string(),
+            ))
+        }
+    };
+
+    match DhcpOption::deserialize(&packet[start..])? {
+        (DhcpOption::Authentication(authentication), _) => Ok((start, authentication)),
+        _ => unreachable!(),
+    }
+}
+
+// Copy the packet with the MAC starting at `mac_offset` zeroed, since it
+// cannot cover itself. Delayed authentication also zeroes 'hops' and
+// 'giaddr', which relay agents may change.
+fn mac_input(packet: &[u8], mac_offset: usize, zero_relay_fields: bool) -> Vec<u8> {
+    let mut data = packet.to_vec();
+    if zero_relay_fields {
+        data[HOPS_OFFSET] = 0;
+        data[GIADDR_OFFSET..GIADDR_OFFSET + 4].fill(0);
+    }
+    data[mac_offset..mac_offset + MAC_LEN].fill(0);
+    data
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,188 @@
+//! Representative workloads, for measuring the performance of the crate.
+//!
+//! Each workload builds its fixtures up front and returns a value derived
+//! from its work, for the caller to pass to `std::hint::black_box`. The
+//! `workloads` bench runs them all once with their default sizes, and the
+//! `criterion` bench measures them with criterion:
+//!
+//! ```text
+//! cargo bench --features bench
+//! ```
+
+use std::net::Ipv4Addr;
+
+use crate::codec::DhcpEncoder;
+use crate::message::{DhcpMessage, MessageType};
+use crate::message_ref::DhcpMessageRef;
+use crate::option::DhcpOption;
+
+/// The number of packets parsed by the parsing workloads.
+pub const PARSE_PACKETS: usize = 1_000_000;
+
+/// The number of replies built by `build_acks`.
+pub const BUILD_ACKS: usize = 100_000;
+
+/// A workload, run with a size.
+#[derive(Debug, Clone, Copy)]
+pub struct Workload {
+    pub name: &'static str,
+    pub size: usize,
+    pub run: fn(&[Vec<u8>], usize) -> usize,
+}
+
+/// The workloads with their default size.
+pub fn workloads() -> Vec<Workload> {
+    vec![
+        Workload {
+            name: "parse_packets",
+            size: PARSE_PACKETS,
+            run: parse_packets,
+        },
+        Workload {
+            name: "parse_packets_lazy",
+            size: PARSE_PACKETS,
+            run: parse_packets_lazy,
+        },
+        Workload {
+            name: "parse_packets_borrowed",
+            size: PARSE_PACKETS,
+            run: parse_packets_borrowed,
+        },
+        Workload {
+            name: "build_acks",
+            size: BUILD_ACKS,
+            run: |_, count| build_acks(count),
+        },
+    ]
+}
+
+/// Packets as captured on a busy segment: DISCOVER and REQUEST messages of
+/// PXE clients, with their long parameter request lists, and the OFFER and
+/// ACK replies of a server.
+pub fn captured_packets() -> Vec<Vec<u8>> {
+    let mut packets = Vec::new();
+    for client in 0..16u8 {
+        for message_type in [
+            MessageType::Discover,
+            MessageType::Offer,
+            MessageType::Request,
+            MessageType::Ack,
+        ] {
+            packets.push(packet(client, message_type));
+        }
+    }
+    packets
+}
+
+/// Parse `count` packets, taken in turn from `packets`, returning the
+/// number of options read.
+pub fn parse_packets(packets: &[Vec<u8>], count: usize) -> usize {
+    packets
+        .iter()
+        .cycle()
+        .take(count)
+        .filter_map(|packet| DhcpMessage::deserialize(packet).ok())
+        .map(|message| message.options.len())
+        .sum()
+}
+
+/// Parse `count` packets as `parse_packets` does, decoding every option
+/// as a `DhcpOptionRef` borrowing the packet instead of allocating its
+/// value.
+pub fn parse_packets_borrowed(packets: &[Vec<u8>], count: usize) -> usize {
+    packets
+        .iter()
+        .cycle()
+        .take(count)
+        .filter_map(|packet| DhcpMessageRef::deserialize(packet).ok())
+        .map(|message| message.options().filter(Result::is_ok).count())
+        .sum()
+}
+
+/// Parse `count` packets as `parse_packets` does, decoding only the
+/// options a server looks at: the message type, client identifier and
+/// requested address.
+pub fn parse_packets_lazy(packets: &[Vec<u8>], count: usize) -> usize {
+    packets
+        .iter()
+        .cycle()
+        .take(count)
+        .filter_map(|packet| DhcpMessageRef::deserialize(packet).ok())
+        .map(|message| {
+            [53, 61, 50]
+                .into_iter()
+                .filter(|code| matches!(message.get_option(*code), Ok(Some(_))))
+                .count()
+        })
+        .sum()
+}
+
+/// Build and serialize `count` ACK replies, returning the number of bytes
+/// written.
+pub fn build_acks(count: usize) -> usize {
+    let mut encoder = DhcpEncoder::new();
+    let mut written = 0;
+    for index in 0..count {
+        let mut ack = reply(index as u8, MessageType::Ack);
+        ack.xid = index as u32;
+        written += encoder.encode(&ack).map_or(0, |data| data.len());
+    }
+    written
+}
+
+// The options of a PXE client, and the replies of a server.
+fn packet(client: u8, message_type: MessageType) -> Vec<u8> {
+    let message = match message_type {
+        MessageType::Discover | MessageType::Request => {
+            let mut message = DhcpMessage::new(1, 0x3903F326 + client as u32);
+            message.chaddr[..6].copy_from_slice(&[0x02, 0, 0, 0, 0, client]);
+            message.options = vec![
+                DhcpOption::from(message_type),
+                DhcpOption::ClientIdentifier(vec![1, 0x02, 0, 0, 0, 0, client]),
+                DhcpOption::Unknown(57, vec![0x05, 0xc0]),
+                DhcpOption::Unknown(55, (1..=67).collect()),
+                DhcpOption::Unknown(93, vec![0, 7]),
+                DhcpOption::Unknown(94, vec![1, 3, 16]),
+                DhcpOption::Unknown(97, vec![0; 17]),
+                DhcpOption::Unknown(60, b"PXEClient:Arch:00007:UNDI:003016".to_vec()),
+                DhcpOption::HostName(format!("client-{}", client)),
+            ]
+            .into();
+            if message_type == MessageType::Request {
+                message
+                    .options
+                    .push(DhcpOption::RequestedIpAddress(address(client)));
+            }
+            message
+        }
+        _ => reply(client, message_type),
+    };
+    message.serialize().unwrap_or_default()
+}
+
+fn reply(client: u8, message_type: MessageType) -> DhcpMessage {
+    let server = Ipv4Addr::new(192, 168, 1, 1);
+    let mut message = DhcpMessage::new(2, 0x3903F326 + client as u32);
+    message.chaddr[..6].copy_from_slice(&[0x02, 0, 0, 0, 0, client]);
+    message.yiaddr = address(client);
+    message.siaddr = server;
+    message.options = vec![
+        DhcpOption::from(message_type),
+        DhcpOption::ServerIdentifier(server),
+        DhcpOption::IpAddressLeaseTime(86400),
+        DhcpOption::Unknown(58, 43200u32.to_be_bytes().to_vec()),
+        DhcpOption::Unknown(59, 75600u32.to_be_bytes().to_vec()),
+        DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+        DhcpOption::Router(vec![server].into()),
+        DhcpOption::DomainNameServer(vec![server, Ipv4Addr::new(192, 168, 1, 2)].into()),
+        DhcpOption::DomainName("example.com".to_string()),
+        DhcpOption::Unknown(66, b"192.168.1.1".to_vec()),
+        DhcpOption::Unknown(67, b"pxelinux.0".to_vec()),
+    ]
+    .into();
+    message
+}
+
+fn address(client: u8) -> Ipv4Addr {
+    Ipv4Addr::new(192, 168, 1, 100 + client)
+}
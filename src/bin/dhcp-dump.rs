@@ -0,0 +1,258 @@
+//! Print the DHCP messages of a capture file, or received on a socket.
+//!
+//! Usage: dhcp-dump [-r FILE | -l ADDRESS] [-m MAC] [-t TYPE] [-o CODE]...
+
+use std::env;
+use std::net::{SocketAddr, SocketAddrV4};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dhcp::capture::{CaptureReader, CapturedMessage};
+use dhcp::error::DhcpError;
+use dhcp::message::DhcpMessage;
+use dhcp::net::{self, SocketConfig};
+use dhcp::option::DhcpOption;
+
+const USAGE: &str = "Usage: dhcp-dump [-r FILE | -l ADDRESS] [-m MAC] [-t TYPE] [-o CODE]...
+
+  -r FILE      read messages from a pcap or pcapng file
+  -l ADDRESS   receive messages on a UDP socket (default 0.0.0.0:67)
+  -m MAC       only show messages with this client hardware address
+  -t TYPE      only show messages of this type (name or number)
+  -o CODE      only show messages carrying this option";
+
+const MESSAGE_TYPES: [&str; 18] = [
+    "DHCPDISCOVER",
+    "DHCPOFFER",
+    "DHCPREQUEST",
+    "DHCPDECLINE",
+    "DHCPACK",
+    "DHCPNAK",
+    "DHCPRELEASE",
+    "DHCPINFORM",
+    "DHCPFORCERENEW",
+    "DHCPLEASEQUERY",
+    "DHCPLEASEUNASSIGNED",
+    "DHCPLEASEUNKNOWN",
+    "DHCPLEASEACTIVE",
+    "DHCPBULKLEASEQUERY",
+    "DHCPLEASEQUERYDONE",
+    "DHCPACTIVELEASEQUERY",
+    "DHCPLEASEQUERYSTATUS",
+    "DHCPTLS",
+];
+
+enum Source {
+    File(String),
+    Socket(SocketAddr),
+}
+
+#[derive(Default)]
+struct Filter {
+    mac: Option<[u8; 6]>,
+    message_type: Option<u8>,
+    options: Vec<u8>,
+}
+
+impl Filter {
+    fn matches(&self, message: &DhcpMessage) -> bool {
+        if let Some(mac) = self.mac {
+            if message.chaddr[..6] != mac {
+                return false;
+            }
+        }
+        if self.message_type.is_some() && message_type(message) != self.message_type {
+            return false;
+        }
+        let codes = message.options.iter().map(option_code).collect::<Vec<u8>>();
+        self.options.iter().all(|code| codes.contains(code))
+    }
+}
+
+fn main() {
+    let (source, filter) = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let result = match source {
+        Source::File(path) => dump_file(&path, &filter),
+        Source::Socket(address) => dump_socket(address, &filter),
+    };
+    if let Err(error) = result {
+        eprintln!("dhcp-dump: {}", error);
+        process::exit(1);
+    }
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<(Source, Filter), String> {
+    let mut source = Source::Socket(SocketAddr::from(([0, 0, 0, 0], 67)));
+    let mut filter = Filter::default();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "-r" => source = Source::File(value()?),
+            "-l" => {
+                let address = value()?;
+                source = Source::Socket(
+                    address
+                        .parse()
+                        .map_err(|_| format!("Invalid address {}", address))?,
+                )
+            }
+            "-m" => {
+                let mac = value()?;
+                filter.mac = Some(parse_mac(&mac).ok_or(format!("Invalid MAC {}", mac))?);
+            }
+            "-t" => {
+                let message_type = value()?;
+                filter.message_type = Some(
+                    parse_message_type(&message_type)
+                        .ok_or(format!("Invalid message type {}", message_type))?,
+                );
+            }
+            "-o" => {
+                let code = value()?;
+                filter.options.push(
+                    code.parse()
+                        .map_err(|_| format!("Invalid option {}", code))?,
+                );
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+
+    Ok((source, filter))
+}
+
+fn parse_mac(mac: &str) -> Option<[u8; 6]> {
+    let bytes = mac
+        .split([':', '-'])
+        .map(|byte| u8::from_str_radix(byte, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    bytes.try_into().ok()
+}
+
+fn parse_message_type(name: &str) -> Option<u8> {
+    if let Ok(value) = name.parse() {
+        return Some(value);
+    }
+    let name = name.to_ascii_uppercase();
+    MESSAGE_TYPES
+        .iter()
+        .position(|known| *known == name || known[4..] == name)
+        .map(|index| index as u8 + 1)
+}
+
+fn dump_file(path: &str, filter: &Filter) -> Result<(), String> {
+    let reader = CaptureReader::open(path).map_err(|error| error.to_string())?;
+    for captured in reader {
+        match captured {
+            Ok(captured) => print_message(&captured, filter),
+            Err(error) => eprintln!("dhcp-dump: {}", error),
+        }
+    }
+    Ok(())
+}
+
+fn dump_socket(address: SocketAddr, filter: &Filter) -> Result<(), String> {
+    let address = match address {
+        SocketAddr::V4(address) => address,
+        SocketAddr::V6(_) => return Err("Only IPv4 addresses are supported".to_string()),
+    };
+    // Share the port with a running server, and learn the actual
+    // destination of broadcast messages where possible.
+    let config = SocketConfig {
+        reuse_address: true,
+        packet_info: net::SOCKET_OPTIONS,
+        ..SocketConfig::default()
+    };
+    let socket = net::bind(address, &config).map_err(|error| error.to_string())?;
+    let port = socket
+        .local_addr()
+        .map_err(|error| error.to_string())?
+        .port();
+
+    let mut buffer = [0; 65536];
+    loop {
+        let (len, source, info) =
+            net::recv_with_info(&socket, &mut buffer).map_err(|error| error.to_string())?;
+        let destination = match info {
+            Some(info) => SocketAddrV4::new(info.destination, port),
+            None => SocketAddrV4::new(*address.ip(), port),
+        };
+        match DhcpMessage::deserialize(&buffer[..len]) {
+            Ok(message) => print_message(
+                &CapturedMessage {
+                    timestamp: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default(),
+                    source,
+                    destination,
+                    message,
+                },
+                filter,
+            ),
+            Err(error) => eprintln!("dhcp-dump: {}: {}", source, error),
+        }
+    }
+}
+
+fn print_message(captured: &CapturedMessage, filter: &Filter) {
+    let message = &captured.message;
+    if !filter.matches(message) {
+        return;
+    }
+
+    let name = match message_type(message) {
+        Some(value) => (value as usize)
+            .checked_sub(1)
+            .and_then(|index| MESSAGE_TYPES.get(index))
+            .map(|name| name.to_string())
+            .unwrap_or(format!("type {}", value)),
+        None if message.op == 1 => "BOOTREQUEST".to_string(),
+        None => "BOOTREPLY".to_string(),
+    };
+    println!(
+        "{}.{:06} {} > {} {} xid 0x{:08x} chaddr {}",
+        captured.timestamp.as_secs(),
+        captured.timestamp.subsec_micros(),
+        captured.source,
+        captured.destination,
+        name,
+        message.xid,
+        message.chaddr[..message.hlen.min(16) as usize]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<Vec<String>>()
+            .join(":")
+    );
+    println!(
+        "  ciaddr {} yiaddr {} siaddr {} giaddr {}",
+        message.ciaddr, message.yiaddr, message.siaddr, message.giaddr
+    );
+    for option in &message.options {
+        println!("  {}", option);
+    }
+}
+
+fn message_type(message: &DhcpMessage) -> Option<u8> {
+    match message.message_type() {
+        Ok(message_type) => message_type.map(u8::from),
+        // Types of later RFCs, such as DHCPFORCERENEW.
+        Err(DhcpError::UnknownMessageType(value)) => Some(value),
+        Err(_) => None,
+    }
+}
+
+fn option_code(option: &DhcpOption) -> u8 {
+    u8::from(option.code())
+}
@@ -0,0 +1,100 @@
+//! Serve addresses from the pool of a TOML configuration file, see
+//! `ServerConfig::from_config`, with the async engine.
+//!
+//! Usage: dhcp-server CONFIG [-l ADDRESS] [-i INTERFACE]
+
+use std::env;
+use std::fs;
+use std::net::SocketAddrV4;
+use std::process;
+
+use dhcp::engine::Engine;
+use dhcp::lease::{LeaseEvent, LeaseEventSink};
+use dhcp::net::{self, SocketConfig};
+use dhcp::server::{Server, ServerConfig};
+
+const USAGE: &str = "Usage: dhcp-server CONFIG [-l ADDRESS] [-i INTERFACE]
+
+  -l ADDRESS     listen on this address (default 0.0.0.0:67)
+  -i INTERFACE   only serve clients on this interface";
+
+// Log lease changes to the standard output.
+struct Log;
+
+impl LeaseEventSink for Log {
+    fn lease_event(&mut self, event: &LeaseEvent) {
+        println!(
+            "{:?} {} {}",
+            event.state,
+            event.ip,
+            event
+                .mac
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<String>>()
+                .join(":")
+        );
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let mut config = None;
+    let mut address = SocketAddrV4::new([0, 0, 0, 0].into(), 67);
+    let mut interface = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-l" => match args.next().and_then(|value| value.parse().ok()) {
+                Some(value) => address = value,
+                None => usage(),
+            },
+            "-i" => match args.next() {
+                Some(value) => interface = Some(value),
+                None => usage(),
+            },
+            "-h" | "--help" => usage(),
+            _ if config.is_none() && !arg.starts_with('-') => config = Some(arg),
+            _ => usage(),
+        }
+    }
+    let config = config.unwrap_or_else(|| usage());
+
+    if let Err(error) = run(&config, address, interface).await {
+        eprintln!("dhcp-server: {}", error);
+        process::exit(1);
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+async fn run(path: &str, address: SocketAddrV4, interface: Option<String>) -> Result<(), String> {
+    let config = fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+    let config =
+        ServerConfig::from_config(&config).map_err(|error| format!("{}: {}", path, error))?;
+
+    let socket_config = SocketConfig {
+        broadcast: true,
+        reuse_address: true,
+        device: interface,
+        ..SocketConfig::default()
+    };
+    let socket = net::bind(address, &socket_config).map_err(|error| error.to_string())?;
+    let mut engine = Engine::new(Server::new(config), socket).map_err(|error| error.to_string())?;
+    loop {
+        engine
+            .serve_batch(&mut Log)
+            .await
+            .map_err(|error| error.to_string())?;
+        for (peer, error) in engine.take_errors() {
+            eprintln!("dhcp-server: {}: {}", peer, error);
+        }
+        for error in engine.server().take_errors() {
+            eprintln!("dhcp-server: {}", error);
+        }
+    }
+}
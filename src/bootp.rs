@@ -0,0 +1,179 @@
+//! Plain BOOTP messages (RFC 951, RFC 1542), as sent by old netboot
+//! clients.
+//!
+//! BOOTP messages share the header of DHCP messages, but end with a vend
+//! field of 64 bytes instead of the options. The vend field holds options
+//! after the magic cookie when the client follows RFC 1048, and is free
+//! for vendors otherwise. `Packet::deserialize` tells both protocols
+//! apart, for servers answering either.
+
+use std::net::Ipv4Addr;
+
+use crate::error::{DhcpError, SerializationError};
+use crate::message::{deserialize_options, DhcpMessage, Flags, MAGIC_COOKIE};
+use crate::option::DhcpOption;
+
+/// A BOOTP message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BootpMessage {
+    pub op: u8,
+    pub htype: u8,
+    pub hlen: u8,
+    pub hops: u8,
+    pub xid: u32,
+    pub secs: u16,
+    // Unused by RFC 951, defined by RFC 1542 section 3.1.
+    pub flags: Flags,
+    pub ciaddr: Ipv4Addr,
+    pub yiaddr: Ipv4Addr,
+    pub siaddr: Ipv4Addr,
+    pub giaddr: Ipv4Addr,
+    pub chaddr: [u8; 16],
+    pub sname: [u8; 64],
+    pub file: [u8; 128],
+    pub vend: [u8; 64],
+}
+
+impl BootpMessage {
+    /// The length of a serialized message.
+    pub const LEN: usize = 300;
+
+    /// Create an empty message for an Ethernet client.
+    pub fn new(op: u8, xid: u32) -> BootpMessage {
+        BootpMessage {
+            op,
+            htype: 1,
+            hlen: 6,
+            hops: 0,
+            xid,
+            secs: 0,
+            flags: Flags::from_bits(0),
+            ciaddr: Ipv4Addr::UNSPECIFIED,
+            yiaddr: Ipv4Addr::UNSPECIFIED,
+            siaddr: Ipv4Addr::UNSPECIFIED,
+            giaddr: Ipv4Addr::UNSPECIFIED,
+            chaddr: [0; 16],
+            sname: [0; 64],
+            file: [0; 128],
+            vend: [0; 64],
+        }
+    }
+
+    /// Deserialize a message. A vend field shorter than 64 bytes is padded
+    /// with zeros, and bytes past the 64 are ignored.
+    pub fn deserialize(data: &[u8]) -> Result<BootpMessage, DhcpError> {
+        if data.len() < 236 {
+            return Err(DhcpError::UnexpectedEof {
+                field: "header",
+                needed: 236,
+                available: data.len(),
+            });
+        }
+
+        let address = |offset: usize| {
+            Ipv4Addr::new(
+                data[offset],
+                data[offset + 1],
+                data[offset + 2],
+                data[offset + 3],
+            )
+        };
+        let mut message = BootpMessage::new(data[0], 0);
+        message.htype = data[1];
+        message.hlen = data[2];
+        message.hops = data[3];
+        message.xid = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+        message.secs = u16::from_be_bytes([data[8], data[9]]);
+        message.flags = Flags::from_bits(u16::from_be_bytes([data[10], data[11]]));
+        message.ciaddr = address(12);
+        message.yiaddr = address(16);
+        message.siaddr = address(20);
+        message.giaddr = address(24);
+        message.chaddr.copy_from_slice(&data[28..44]);
+        message.sname.copy_from_slice(&data[44..108]);
+        message.file.copy_from_slice(&data[108..236]);
+        let vend = &data[236..data.len().min(BootpMessage::LEN)];
+        message.vend[..vend.len()].copy_from_slice(vend);
+        Ok(message)
+    }
+
+    /// Serialize the message, always `BootpMessage::LEN` bytes long.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(BootpMessage::LEN);
+        result.extend_from_slice(&[self.op, self.htype, self.hlen, self.hops]);
+        result.extend_from_slice(&self.xid.to_be_bytes());
+        result.extend_from_slice(&self.secs.to_be_bytes());
+        result.extend_from_slice(&self.flags.bits().to_be_bytes());
+        result.extend_from_slice(&self.ciaddr.octets());
+        result.extend_from_slice(&self.yiaddr.octets());
+        result.extend_from_slice(&self.siaddr.octets());
+        result.extend_from_slice(&self.giaddr.octets());
+        result.extend_from_slice(&self.chaddr);
+        result.extend_from_slice(&self.sname);
+        result.extend_from_slice(&self.file);
+        result.extend_from_slice(&self.vend);
+        result
+    }
+
+    /// The options of the vend field (RFC 1048), or None if it does not
+    /// start with the magic cookie.
+    pub fn vend_options(&self) -> Result<Option<Vec<DhcpOption>>, DhcpError> {
+        if self.vend[..4] != MAGIC_COOKIE {
+            return Ok(None);
+        }
+        deserialize_options(&self.vend).map(Some)
+    }
+
+    /// Write `options` to the vend field after the magic cookie, ended by
+    /// an End option, failing with `SerializationError::MessageTooLong` if
+    /// they do not fit.
+    pub fn set_vend_options(&mut self, options: &[DhcpOption]) -> Result<(), DhcpError> {
+        let mut vend = MAGIC_COOKIE.to_vec();
+        for option in options {
+            option.serialize_into(&mut vend)?;
+        }
+        if options.last() != Some(&DhcpOption::End) {
+            vend.push(255);
+        }
+        if vend.len() > self.vend.len() {
+            return Err(DhcpError::SerializationError(
+                SerializationError::MessageTooLong {
+                    len: vend.len(),
+                    max: self.vend.len(),
+                },
+            ));
+        }
+        self.vend = [0; 64];
+        self.vend[..vend.len()].copy_from_slice(&vend);
+        Ok(())
+    }
+}
+
+/// A DHCP or BOOTP message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Packet {
+    Dhcp(Box<DhcpMessage>),
+    Bootp(Box<BootpMessage>),
+}
+
+impl Packet {
+    /// Deserialize a message, as a DHCP message if it has a DHCP Message
+    /// Type option, and as a BOOTP message otherwise.
+    pub fn deserialize(data: &[u8]) -> Result<Packet, DhcpError> {
+        if data.len() >= DhcpMessage::HEADER_LEN && data[236..240] == MAGIC_COOKIE {
+            let message = DhcpMessage::deserialize(data)?;
+            if let Ok(Some(_)) = message.message_type() {
+                return Ok(Packet::Dhcp(Box::new(message)));
+            }
+        }
+        BootpMessage::deserialize(data).map(|message| Packet::Bootp(Box::new(message)))
+    }
+
+    /// Serialize the message.
+    pub fn serialize(&self) -> Result<Vec<u8>, DhcpError> {
+        match self {
+            Packet::Dhcp(message) => message.serialize(),
+            Packet::Bootp(message) => Ok(message.serialize()),
+        }
+    }
+}
@@ -0,0 +1,307 @@
+//! Building messages field by field.
+//!
+//! `DhcpMessageBuilder` starts from a message type, filling the header
+//! with its defaults: the op code, an Ethernet hardware type and the
+//! message type option. `build` checks that the fields and options the
+//! type requires (RFC 2131 section 4.3 and 4.4) were given:
+//!
+//! ```
+//! use std::net::Ipv4Addr;
+//!
+//! use dhcp::builder::DhcpMessageBuilder;
+//!
+//! let request = DhcpMessageBuilder::request()
+//!     .xid(0x3903F326)
+//!     .chaddr([0x02, 0, 0, 0, 0, 1])
+//!     .requested_ip(Ipv4Addr::new(192, 168, 1, 100))
+//!     .server_identifier(Ipv4Addr::new(192, 168, 1, 1))
+//!     .parameter_request(&[1, 3, 6])
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(request.xid, 0x3903F326);
+//! ```
+//!
+//! For the exchange of RFC 2131 section 3.1, `DhcpMessage::discover`,
+//! `offer`, `request`, `ack` and `nak` build each message from the one it
+//! answers, copying the fields the RFC requires.
+
+use std::net::Ipv4Addr;
+
+use crate::error::{DhcpError, SerializationError};
+use crate::hardware::HardwareAddress;
+use crate::message::{DhcpMessage, MessageType, OpCode};
+use crate::option::DhcpOption;
+use crate::validation::Violation;
+
+const OPTION_SERVER_IDENTIFIER: u8 = 54;
+const OPTION_PARAMETER_REQUEST_LIST: u8 = 55;
+const OPTION_MESSAGE: u8 = 56;
+
+/// A message being built, see the module documentation.
+#[derive(Debug, Clone)]
+pub struct DhcpMessageBuilder {
+    message: DhcpMessage,
+    hardware_address: bool,
+}
+
+impl DhcpMessageBuilder {
+    /// A message of type `message_type`, sent by a client or a server as
+    /// the type implies.
+    pub fn new(message_type: MessageType) -> DhcpMessageBuilder {
+        let op = match message_type {
+            MessageType::Offer | MessageType::Ack | MessageType::Nak => OpCode::BootReply,
+            _ => OpCode::BootRequest,
+        };
+        let mut message = DhcpMessage::new(op.into(), 0);
+        message.options.push(message_type.into());
+        DhcpMessageBuilder {
+            message,
+            hardware_address: false,
+        }
+    }
+
+    pub fn discover() -> DhcpMessageBuilder {
+        DhcpMessageBuilder::new(MessageType::Discover)
+    }
+
+    pub fn offer() -> DhcpMessageBuilder {
+        DhcpMessageBuilder::new(MessageType::Offer)
+    }
+
+    pub fn request() -> DhcpMessageBuilder {
+        DhcpMessageBuilder::new(MessageType::Request)
+    }
+
+    pub fn decline() -> DhcpMessageBuilder {
+        DhcpMessageBuilder::new(MessageType::Decline)
+    }
+
+    pub fn ack() -> DhcpMessageBuilder {
+        DhcpMessageBuilder::new(MessageType::Ack)
+    }
+
+    pub fn nak() -> DhcpMessageBuilder {
+        DhcpMessageBuilder::new(MessageType::Nak)
+    }
+
+    pub fn release() -> DhcpMessageBuilder {
+        DhcpMessageBuilder::new(MessageType::Release)
+    }
+
+    pub fn inform() -> DhcpMessageBuilder {
+        DhcpMessageBuilder::new(MessageType::Inform)
+    }
+
+    pub fn xid(mut self, xid: u32) -> DhcpMessageBuilder {
+        self.message.xid = xid;
+        self
+    }
+
+    pub fn secs(mut self, secs: u16) -> DhcpMessageBuilder {
+        self.message.secs = secs;
+        self
+    }
+
+    /// Ask for replies to be broadcast.
+    pub fn broadcast(mut self, broadcast: bool) -> DhcpMessageBuilder {
+        self.message.flags.set_broadcast(broadcast);
+        self
+    }
+
+    /// Set the htype, hlen and chaddr fields, see
+    /// `DhcpMessage::set_hardware_address`.
+    pub fn chaddr(mut self, address: impl Into<HardwareAddress>) -> DhcpMessageBuilder {
+        self.message.set_hardware_address(&address.into());
+        self.hardware_address = true;
+        self
+    }
+
+    pub fn ciaddr(mut self, ciaddr: Ipv4Addr) -> DhcpMessageBuilder {
+        self.message.ciaddr = ciaddr;
+        self
+    }
+
+    pub fn yiaddr(mut self, yiaddr: Ipv4Addr) -> DhcpMessageBuilder {
+        self.message.yiaddr = yiaddr;
+        self
+    }
+
+    pub fn siaddr(mut self, siaddr: Ipv4Addr) -> DhcpMessageBuilder {
+        self.message.siaddr = siaddr;
+        self
+    }
+
+    pub fn giaddr(mut self, giaddr: Ipv4Addr) -> DhcpMessageBuilder {
+        self.message.giaddr = giaddr;
+        self
+    }
+
+    pub fn requested_ip(self, ip: Ipv4Addr) -> DhcpMessageBuilder {
+        self.option(DhcpOption::RequestedIpAddress(ip))
+    }
+
+    pub fn server_identifier(self, ip: Ipv4Addr) -> DhcpMessageBuilder {
+        self.option(DhcpOption::ServerIdentifier(ip))
+    }
+
+    pub fn lease_time(self, seconds: u32) -> DhcpMessageBuilder {
+        self.option(DhcpOption::IpAddressLeaseTime(seconds))
+    }
+
+    /// Ask for the options with codes `codes`.
+    pub fn parameter_request(self, codes: &[u8]) -> DhcpMessageBuilder {
+        self.option(DhcpOption::Unknown(
+            OPTION_PARAMETER_REQUEST_LIST,
+            codes.to_vec(),
+        ))
+    }
+
+    pub fn client_identifier(self, identifier: &[u8]) -> DhcpMessageBuilder {
+        self.option(DhcpOption::ClientIdentifier(identifier.to_vec()))
+    }
+
+    pub fn host_name(self, name: &str) -> DhcpMessageBuilder {
+        self.option(DhcpOption::HostName(name.to_string()))
+    }
+
+    /// An error message, as sent with a DHCPNAK or DHCPDECLINE.
+    pub fn message(self, message: &str) -> DhcpMessageBuilder {
+        self.option(DhcpOption::Unknown(
+            OPTION_MESSAGE,
+            message.as_bytes().to_vec(),
+        ))
+    }
+
+    /// Append any option, after those already given.
+    pub fn option(mut self, option: DhcpOption) -> DhcpMessageBuilder {
+        self.message.options.push(option);
+        self
+    }
+
+    /// The message, failing with a `SerializationError` if a field or
+    /// option required by its type is missing.
+    pub fn build(self) -> Result<DhcpMessage, DhcpError> {
+        let message = self.message;
+        let missing_field = |field| {
+            Err(DhcpError::SerializationError(
+                SerializationError::MissingField { field },
+            ))
+        };
+        let missing_option = |code| {
+            Err(DhcpError::SerializationError(
+                SerializationError::MissingValue { code },
+            ))
+        };
+
+        // Replies echo the hardware address of the client, as requests
+        // carry it.
+        if !self.hardware_address {
+            return missing_field("chaddr");
+        }
+        // Only what is missing fails: options the type forbids are left to
+        // the caller, see `DhcpMessage::validate`.
+        for violation in message.validate() {
+            match violation {
+                Violation::MissingField { field } => return missing_field(field),
+                Violation::MissingOption { code } => return missing_option(code),
+                _ => {}
+            }
+        }
+        Ok(message)
+    }
+}
+
+impl DhcpMessage {
+    /// A DHCPDISCOVER from the client with hardware address `chaddr`.
+    pub fn discover(xid: u32, chaddr: impl Into<HardwareAddress>) -> DhcpMessage {
+        let mut discover = DhcpMessage::new(OpCode::BootRequest.into(), xid);
+        discover.set_hardware_address(&chaddr.into());
+        discover.options.push(MessageType::Discover.into());
+        discover
+    }
+
+    /// A DHCPOFFER of `yiaddr` answering `discover`.
+    pub fn offer(
+        discover: &DhcpMessage,
+        yiaddr: Ipv4Addr,
+        server_identifier: Ipv4Addr,
+        lease_time: u32,
+    ) -> DhcpMessage {
+        let mut offer = DhcpMessage::reply(discover, MessageType::Offer, server_identifier);
+        offer.yiaddr = yiaddr;
+        offer
+            .options
+            .push(DhcpOption::IpAddressLeaseTime(lease_time));
+        offer
+    }
+
+    /// A DHCPREQUEST accepting `offer`, selecting its server by its server
+    /// identifier. Fails with a `SerializationError` if it has none.
+    pub fn request(offer: &DhcpMessage) -> Result<DhcpMessage, DhcpError> {
+        let server_identifier = match offer.server_identifier() {
+            Some(server_identifier) => DhcpOption::ServerIdentifier(server_identifier),
+            None => {
+                return Err(DhcpError::SerializationError(
+                    SerializationError::MissingValue {
+                        code: OPTION_SERVER_IDENTIFIER,
+                    },
+                ))
+            }
+        };
+        let mut request = DhcpMessage::new(OpCode::BootRequest.into(), offer.xid);
+        request.htype = offer.htype;
+        request.hlen = offer.hlen;
+        request.chaddr = offer.chaddr;
+        request.flags = offer.flags;
+        request.options.push(MessageType::Request.into());
+        request
+            .options
+            .push(DhcpOption::RequestedIpAddress(offer.yiaddr));
+        request.options.push(server_identifier);
+        Ok(request)
+    }
+
+    /// A DHCPACK of `yiaddr` answering `request`, keeping its ciaddr.
+    pub fn ack(
+        request: &DhcpMessage,
+        yiaddr: Ipv4Addr,
+        server_identifier: Ipv4Addr,
+        lease_time: u32,
+    ) -> DhcpMessage {
+        let mut ack = DhcpMessage::reply(request, MessageType::Ack, server_identifier);
+        ack.ciaddr = request.ciaddr;
+        ack.yiaddr = yiaddr;
+        ack.options.push(DhcpOption::IpAddressLeaseTime(lease_time));
+        ack
+    }
+
+    /// A DHCPNAK answering `request`, explaining why with `message`.
+    pub fn nak(request: &DhcpMessage, server_identifier: Ipv4Addr, message: &str) -> DhcpMessage {
+        let mut nak = DhcpMessage::reply(request, MessageType::Nak, server_identifier);
+        nak.options.push(DhcpOption::Unknown(
+            OPTION_MESSAGE,
+            message.as_bytes().to_vec(),
+        ));
+        nak
+    }
+
+    // A reply to `request`, echoing the fields RFC 2131 table 3 copies
+    // from it: xid, flags, giaddr and the hardware address.
+    fn reply(
+        request: &DhcpMessage,
+        message_type: MessageType,
+        server_identifier: Ipv4Addr,
+    ) -> DhcpMessage {
+        let mut reply = DhcpMessage::new(OpCode::BootReply.into(), request.xid);
+        reply.htype = request.htype;
+        reply.hlen = request.hlen;
+        reply.chaddr = request.chaddr;
+        reply.flags = request.flags;
+        reply.giaddr = request.giaddr;
+        reply.options.push(message_type.into());
+        reply
+            .options
+            .push(DhcpOption::ServerIdentifier(server_identifier));
+        reply
+    }
+}
@@ -0,0 +1,75 @@
+//! A canonical form of messages, for comparing them byte for byte.
+//!
+//! Many encodings carry the same message: options in another order, Pad
+//! options, options split in several parts (RFC 3396) or spilled into sname
+//! and file, known options held as `DhcpOption::Unknown`. The canonical
+//! form of a message keeps one of them:
+//!
+//! - options are sorted by code, keeping the order of those with the same
+//!   code,
+//! - Pad, End and Option Overload options are dropped, all options being
+//!   written to the options field,
+//! - options with the same code are concatenated as RFC 3396 requires,
+//!   when the result is a valid value,
+//! - each option is decoded as the parser would decode it.
+//!
+//! A canonical message survives a round trip:
+//! `DhcpMessage::deserialize(&message.serialize()?)? == message`. Hence
+//! the canonical encoding of a message, `to_canonical_bytes`, is that of
+//! any of its serializations.
+
+use crate::error::DhcpError;
+use crate::message::DhcpMessage;
+use crate::option::DhcpOption;
+
+impl DhcpMessage {
+    /// Put the message in its canonical form, see the module
+    /// documentation.
+    pub fn canonicalize(&mut self) {
+        let mut options: Vec<DhcpOption> = self
+            .options
+            .iter()
+            .filter(|option| {
+                !matches!(
+                    option,
+                    DhcpOption::Pad | DhcpOption::End | DhcpOption::OptionOverload(_)
+                )
+            })
+            .map(|option| decode(option).unwrap_or_else(|| option.clone()))
+            .collect();
+        options.sort_by_key(DhcpOption::raw_code);
+
+        let mut canonical: Vec<DhcpOption> = Vec::with_capacity(options.len());
+        for option in options {
+            if let Some(last) = canonical.last_mut() {
+                if last.raw_code() == option.raw_code() {
+                    if let Some(concatenated) = DhcpOption::concatenate(last, &option) {
+                        *last = concatenated;
+                        continue;
+                    }
+                }
+            }
+            canonical.push(option);
+        }
+        self.options = canonical.into();
+    }
+
+    /// Whether the message is in its canonical form.
+    pub fn is_canonical(&self) -> bool {
+        let mut canonical = self.clone();
+        canonical.canonicalize();
+        canonical == *self
+    }
+
+    /// Serialize the canonical form of the message.
+    pub fn to_canonical_bytes(&self) -> Result<Vec<u8>, DhcpError> {
+        let mut canonical = self.clone();
+        canonical.canonicalize();
+        canonical.serialize()
+    }
+}
+
+// The option as the parser would decode it, if its value is valid.
+fn decode(option: &DhcpOption) -> Option<DhcpOption> {
+    DhcpOption::deserialize_value(option.raw_code(), &option.encode()[2..]).ok()
+}
@@ -0,0 +1,183 @@
+//! Reading and writing DHCP traffic as packet captures.
+
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::time::Duration;
+
+use crate::error::DhcpError;
+use crate::message::DhcpMessage;
+
+#[cfg(feature = "parallel")]
+mod parallel;
+mod reader;
+mod writer;
+
+#[cfg(feature = "parallel")]
+pub use parallel::ParallelReader;
+pub use reader::CaptureReader;
+pub use writer::CaptureWriter;
+
+/// The BOOTP server and client UDP ports.
+pub const SERVER_PORT: u16 = 67;
+pub const CLIENT_PORT: u16 = 68;
+
+// Link-layer header types (https://www.tcpdump.org/linktypes.html).
+pub(crate) const LINKTYPE_ETHERNET: u32 = 1;
+pub(crate) const LINKTYPE_RAW: u32 = 101;
+pub(crate) const LINKTYPE_LINUX_SLL: u32 = 113;
+
+// The link-layer address used for the server side of generated frames.
+const SERVER_MAC: [u8; 6] = [0x02, 0, 0, 0, 0, 1];
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+const ETHERTYPE_QINQ: u16 = 0x88A8;
+const IP_PROTOCOL_UDP: u8 = 17;
+
+/// A DHCP message found in a capture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedMessage {
+    // Capture time, since the Unix epoch.
+    pub timestamp: Duration,
+    pub source: SocketAddrV4,
+    pub destination: SocketAddrV4,
+    pub message: DhcpMessage,
+}
+
+// Wrap a message in Ethernet, IPv4 and UDP headers.
+//
+// The client side of the exchange uses the client hardware address of the
+// message, and the server side a fixed locally administered address.
+pub(crate) fn ethernet_frame(
+    source: SocketAddrV4,
+    destination: SocketAddrV4,
+    message: &DhcpMessage,
+) -> Result<Vec<u8>, DhcpError> {
+    let client_mac = client_mac(message);
+    let (source_mac, destination_mac) = match message.op {
+        1 => (client_mac, SERVER_MAC),
+        _ => (SERVER_MAC, client_mac),
+    };
+    let destination_mac = if destination.ip().is_broadcast() {
+        [0xFF; 6]
+    } else {
+        destination_mac
+    };
+    Ok(udp_frame(
+        source_mac,
+        destination_mac,
+        source,
+        destination,
+        &message.serialize()?,
+    ))
+}
+
+// The first six bytes of the client hardware address of a message.
+pub(crate) fn client_mac(message: &DhcpMessage) -> [u8; 6] {
+    let mut mac = [0; 6];
+    mac.copy_from_slice(&message.chaddr[..6]);
+    mac
+}
+
+// Wrap a UDP payload in Ethernet and IPv4 headers. The UDP checksum is
+// left out, as IPv4 allows.
+pub(crate) fn udp_frame(
+    source_mac: [u8; 6],
+    destination_mac: [u8; 6],
+    source: SocketAddrV4,
+    destination: SocketAddrV4,
+    payload: &[u8],
+) -> Vec<u8> {
+    let udp_len = 8 + payload.len();
+    let total_len = 20 + udp_len;
+    let mut frame = Vec::with_capacity(14 + total_len);
+    frame.extend_from_slice(&destination_mac);
+    frame.extend_from_slice(&source_mac);
+    frame.extend_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+
+    let ip_start = frame.len();
+    frame.extend_from_slice(&[0x45, 0]);
+    frame.extend_from_slice(&(total_len as u16).to_be_bytes());
+    frame.extend_from_slice(&[0, 0, 0, 0, 64, IP_PROTOCOL_UDP, 0, 0]);
+    frame.extend_from_slice(&source.ip().octets());
+    frame.extend_from_slice(&destination.ip().octets());
+    let checksum = ipv4_checksum(&frame[ip_start..]);
+    frame[ip_start + 10..ip_start + 12].copy_from_slice(&checksum.to_be_bytes());
+
+    frame.extend_from_slice(&source.port().to_be_bytes());
+    frame.extend_from_slice(&destination.port().to_be_bytes());
+    frame.extend_from_slice(&(udp_len as u16).to_be_bytes());
+    frame.extend_from_slice(&[0, 0]);
+    frame.extend_from_slice(payload);
+    frame
+}
+
+fn ipv4_checksum(header: &[u8]) -> u16 {
+    let mut sum = header
+        .chunks(2)
+        .map(|word| u16::from_be_bytes([word[0], *word.get(1).unwrap_or(&0)]) as u32)
+        .sum::<u32>();
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+// Extract the source, destination and payload of a BOOTP datagram from a
+// captured frame, or `None` if the frame carries other traffic.
+pub(crate) fn bootp_payload(
+    linktype: u32,
+    frame: &[u8],
+) -> Option<(SocketAddrV4, SocketAddrV4, &[u8])> {
+    let packet = match linktype {
+        LINKTYPE_ETHERNET => {
+            let mut offset = 12;
+            let mut ethertype = u16::from_be_bytes([*frame.get(12)?, *frame.get(13)?]);
+            while ethertype == ETHERTYPE_VLAN || ethertype == ETHERTYPE_QINQ {
+                offset += 4;
+                ethertype = u16::from_be_bytes([*frame.get(offset)?, *frame.get(offset + 1)?]);
+            }
+            if ethertype != ETHERTYPE_IPV4 {
+                return None;
+            }
+            frame.get(offset + 2..)?
+        }
+        LINKTYPE_LINUX_SLL => {
+            if u16::from_be_bytes([*frame.get(14)?, *frame.get(15)?]) != ETHERTYPE_IPV4 {
+                return None;
+            }
+            frame.get(16..)?
+        }
+        LINKTYPE_RAW => frame,
+        _ => return None,
+    };
+
+    // IPv4 header, skipping fragments which cannot be decoded alone.
+    if packet.first()? >> 4 != 4 || *packet.get(9)? != IP_PROTOCOL_UDP {
+        return None;
+    }
+    let header_len = ((packet[0] & 0x0F) as usize) * 4;
+    let fragment = u16::from_be_bytes([packet[6], packet[7]]);
+    if fragment & 0x3FFF != 0 {
+        return None;
+    }
+    let total_len = (u16::from_be_bytes([packet[2], packet[3]]) as usize).min(packet.len());
+    let source = Ipv4Addr::new(packet[12], packet[13], packet[14], packet[15]);
+    let destination = Ipv4Addr::new(*packet.get(16)?, packet[17], packet[18], *packet.get(19)?);
+
+    // UDP header.
+    let datagram = packet.get(header_len..total_len)?;
+    let source_port = u16::from_be_bytes([*datagram.first()?, *datagram.get(1)?]);
+    let destination_port = u16::from_be_bytes([*datagram.get(2)?, *datagram.get(3)?]);
+    let ports = [SERVER_PORT, CLIENT_PORT];
+    if !ports.contains(&source_port) && !ports.contains(&destination_port) {
+        return None;
+    }
+    let udp_len = (u16::from_be_bytes([*datagram.get(4)?, *datagram.get(5)?]) as usize)
+        .clamp(8, datagram.len());
+
+    Some((
+        SocketAddrV4::new(source, source_port),
+        SocketAddrV4::new(destination, destination_port),
+        &datagram[8..udp_len],
+    ))
+}
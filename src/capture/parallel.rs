@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+use std::io::Read;
+
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::capture::reader::decode;
+use crate::capture::{CaptureReader, CapturedMessage};
+use crate::error::DhcpError;
+
+// The number of frames read before decoding them.
+const BATCH_LEN: usize = 4096;
+
+/// An iterator over the DHCP messages of a capture, decoding them on
+/// several threads with rayon.
+///
+/// Frames are read in batches, and the frames of a batch decoded in
+/// parallel. The messages come out in capture order, as from
+/// `CaptureReader`, so the messages of each flow keep their order.
+#[derive(Debug)]
+pub struct ParallelReader<R: Read> {
+    reader: CaptureReader<R>,
+    // The pool of the reader, or the global pool of rayon if None.
+    pool: Option<ThreadPool>,
+    decoded: VecDeque<Result<CapturedMessage, DhcpError>>,
+    done: bool,
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Decode the messages on a pool of `threads` threads, or on the
+    /// global pool of rayon if zero, or if the pool cannot be started.
+    pub fn parallel(self, threads: usize) -> ParallelReader<R> {
+        let pool = match threads {
+            0 => None,
+            threads => ThreadPoolBuilder::new().num_threads(threads).build().ok(),
+        };
+        ParallelReader {
+            reader: self,
+            pool,
+            decoded: VecDeque::new(),
+            done: false,
+        }
+    }
+}
+
+impl<R: Read> ParallelReader<R> {
+    // Read and decode the next batch of frames.
+    fn next_batch(&mut self) {
+        let mut frames = Vec::with_capacity(BATCH_LEN);
+        let mut error = None;
+        while frames.len() < BATCH_LEN {
+            match self.reader.next_frame() {
+                Ok(Some(frame)) => frames.push(frame),
+                Ok(None) => {
+                    self.done = true;
+                    break;
+                }
+                Err(next_error) => {
+                    // The stream position is lost after a framing error.
+                    error = Some(next_error);
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+
+        // Collecting keeps the order of the frames.
+        let decode_batch = || {
+            frames
+                .par_iter()
+                .filter_map(|(timestamp, linktype, frame)| decode(*timestamp, *linktype, frame))
+                .collect::<Vec<_>>()
+        };
+        let messages = match &self.pool {
+            Some(pool) => pool.install(decode_batch),
+            None => decode_batch(),
+        };
+        self.decoded.extend(messages);
+        self.decoded.extend(error.map(Err));
+    }
+}
+
+impl<R: Read> Iterator for ParallelReader<R> {
+    type Item = Result<CapturedMessage, DhcpError>;
+
+    fn next(&mut self) -> Option<Result<CapturedMessage, DhcpError>> {
+        while self.decoded.is_empty() && !self.done {
+            self.next_batch();
+        }
+        self.decoded.pop_front()
+    }
+}
@@ -0,0 +1,379 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::capture::{bootp_payload, CapturedMessage};
+use crate::error::DhcpError;
+use crate::message::DhcpMessage;
+
+const PCAP_MAGIC_MICROS: u32 = 0xA1B2C3D4;
+const PCAP_MAGIC_NANOS: u32 = 0xA1B23C4D;
+
+const PCAPNG_SECTION_HEADER: u32 = 0x0A0D0D0A;
+const PCAPNG_INTERFACE_DESCRIPTION: u32 = 1;
+const PCAPNG_SIMPLE_PACKET: u32 = 3;
+const PCAPNG_ENHANCED_PACKET: u32 = 6;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1A2B3C4D;
+const PCAPNG_OPTION_END: u16 = 0;
+const PCAPNG_OPTION_IF_TSRESOL: u16 = 9;
+
+// The timestamp resolution of a capture interface.
+#[derive(Debug, Clone, Copy)]
+enum Resolution {
+    // Units are 10^-n seconds.
+    Decimal(u32),
+    // Units are 2^-n seconds.
+    Binary(u32),
+}
+
+impl Resolution {
+    fn duration(&self, timestamp: u64) -> Duration {
+        let units_per_second: u128 = match self {
+            Resolution::Decimal(exponent) => 10u128.pow(*exponent),
+            Resolution::Binary(exponent) => 1u128 << exponent,
+        };
+        let timestamp = timestamp as u128;
+        let nanos = (timestamp % units_per_second) * 1_000_000_000 / units_per_second;
+        Duration::new((timestamp / units_per_second) as u64, nanos as u32)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Interface {
+    linktype: u32,
+    resolution: Resolution,
+}
+
+#[derive(Debug)]
+enum Format {
+    Pcap {
+        big_endian: bool,
+        interface: Interface,
+    },
+    Pcapng {
+        big_endian: bool,
+        interfaces: Vec<Interface>,
+    },
+}
+
+/// An iterator over the DHCP messages of a pcap or pcapng capture.
+///
+/// Frames that do not carry BOOTP traffic (UDP port 67 or 68) over IPv4
+/// are skipped. Ethernet, with or without VLAN tags, Linux cooked and raw
+/// IP captures are supported.
+#[derive(Debug)]
+pub struct CaptureReader<R: Read> {
+    reader: R,
+    format: Format,
+    done: bool,
+}
+
+impl CaptureReader<BufReader<File>> {
+    /// Open a capture file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<CaptureReader<BufReader<File>>, DhcpError> {
+        let file = File::open(path)?;
+        CaptureReader::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Read the capture header, detecting the capture format.
+    pub fn new(mut reader: R) -> Result<CaptureReader<R>, DhcpError> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+
+        let format = if u32::from_le_bytes(magic) == PCAPNG_SECTION_HEADER {
+            let big_endian = read_section_header(&mut reader)?;
+            Format::Pcapng {
+                big_endian,
+                interfaces: Vec::new(),
+            }
+        } else {
+            let (big_endian, nanos) = match (u32::from_le_bytes(magic), u32::from_be_bytes(magic)) {
+                (PCAP_MAGIC_MICROS, _) => (false, false),
+                (PCAP_MAGIC_NANOS, _) => (false, true),
+                (_, PCAP_MAGIC_MICROS) => (true, false),
+                (_, PCAP_MAGIC_NANOS) => (true, true),
+                _ => {
+                    return Err(DhcpError::ParsingError(
+                        "Unknown capture file format".into(),
+                    ))
+                }
+            };
+            let mut header = [0; 20];
+            reader.read_exact(&mut header)?;
+            Format::Pcap {
+                big_endian,
+                interface: Interface {
+                    linktype: read_u32(&header[16..20], big_endian) & 0x0FFF_FFFF,
+                    resolution: Resolution::Decimal(if nanos { 9 } else { 6 }),
+                },
+            }
+        };
+
+        Ok(CaptureReader {
+            reader,
+            format,
+            done: false,
+        })
+    }
+
+    // Read the next captured frame, with its timestamp and link type.
+    pub(super) fn next_frame(&mut self) -> Result<Option<(Duration, u32, Vec<u8>)>, DhcpError> {
+        match &mut self.format {
+            Format::Pcap {
+                big_endian,
+                interface,
+            } => {
+                let mut header = [0; 16];
+                if !read_or_eof(&mut self.reader, &mut header)? {
+                    return Ok(None);
+                }
+                let seconds = read_u32(&header[0..4], *big_endian) as u64;
+                let fraction = read_u32(&header[4..8], *big_endian) as u64;
+                let captured_len = read_u32(&header[8..12], *big_endian) as usize;
+
+                let mut frame = vec![0; captured_len];
+                self.reader.read_exact(&mut frame)?;
+
+                let timestamp = Duration::from_secs(seconds)
+                    + match interface.resolution {
+                        Resolution::Decimal(9) => Duration::from_nanos(fraction),
+                        _ => Duration::from_micros(fraction),
+                    };
+                Ok(Some((timestamp, interface.linktype, frame)))
+            }
+            Format::Pcapng {
+                big_endian,
+                interfaces,
+            } => loop {
+                let mut header = [0; 8];
+                if !read_or_eof(&mut self.reader, &mut header)? {
+                    return Ok(None);
+                }
+
+                let block_type = read_u32(&header[0..4], *big_endian);
+                if block_type == PCAPNG_SECTION_HEADER {
+                    // A new section may change the byte order and
+                    // restarts interface numbering.
+                    let mut body = [0; 4];
+                    self.reader.read_exact(&mut body)?;
+                    *big_endian = u32::from_le_bytes(body) != PCAPNG_BYTE_ORDER_MAGIC;
+                    let total_len = read_u32(&header[4..8], *big_endian) as usize;
+                    skip(&mut self.reader, total_len.saturating_sub(12))?;
+                    interfaces.clear();
+                    continue;
+                }
+
+                let total_len = read_u32(&header[4..8], *big_endian) as usize;
+                if total_len < 12 || !total_len.is_multiple_of(4) {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse pcapng block length".into(),
+                    ));
+                }
+                let mut body = vec![0; total_len - 8];
+                self.reader.read_exact(&mut body)?;
+                let body = &body[..body.len() - 4];
+
+                match block_type {
+                    PCAPNG_INTERFACE_DESCRIPTION => {
+                        interfaces.push(read_interface(body, *big_endian)?);
+                    }
+                    PCAPNG_ENHANCED_PACKET => {
+                        if body.len() < 20 {
+                            return Err(DhcpError::ParsingError(
+                                "Could not parse pcapng enhanced packet block".into(),
+                            ));
+                        }
+                        let interface = read_u32(&body[0..4], *big_endian) as usize;
+                        let interface = match interfaces.get(interface) {
+                            Some(interface) => *interface,
+                            None => {
+                                return Err(DhcpError::ParsingError(
+                                    "Unknown pcapng interface".into(),
+                                ))
+                            }
+                        };
+                        let timestamp = ((read_u32(&body[4..8], *big_endian) as u64) << 32)
+                            | read_u32(&body[8..12], *big_endian) as u64;
+                        let captured_len = read_u32(&body[12..16], *big_endian) as usize;
+                        let frame = match body.get(20..20 + captured_len) {
+                            Some(frame) => frame.to_vec(),
+                            None => {
+                                return Err(DhcpError::ParsingError(
+                                    "Could not parse pcapng enhanced packet block".into(),
+                                ))
+                            }
+                        };
+                        return Ok(Some((
+                            interface.resolution.duration(timestamp),
+                            interface.linktype,
+                            frame,
+                        )));
+                    }
+                    PCAPNG_SIMPLE_PACKET => {
+                        // Simple packets have no timestamp and belong to
+                        // the first interface.
+                        let interface = match interfaces.first() {
+                            Some(interface) => *interface,
+                            None => {
+                                return Err(DhcpError::ParsingError(
+                                    "Unknown pcapng interface".into(),
+                                ))
+                            }
+                        };
+                        if body.len() < 4 {
+                            return Err(DhcpError::ParsingError(
+                                "Could not parse pcapng simple packet block".into(),
+                            ));
+                        }
+                        let original_len = read_u32(&body[0..4], *big_endian) as usize;
+                        let frame = body[4..].iter().take(original_len).copied().collect();
+                        return Ok(Some((Duration::ZERO, interface.linktype, frame)));
+                    }
+                    _ => {}
+                }
+            },
+        }
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = Result<CapturedMessage, DhcpError>;
+
+    fn next(&mut self) -> Option<Result<CapturedMessage, DhcpError>> {
+        while !self.done {
+            let (timestamp, linktype, frame) = match self.next_frame() {
+                Ok(Some(frame)) => frame,
+                Ok(None) => {
+                    self.done = true;
+                    return None;
+                }
+                Err(error) => {
+                    // The stream position is lost after a framing error.
+                    self.done = true;
+                    return Some(Err(error));
+                }
+            };
+
+            if let Some(message) = decode(timestamp, linktype, &frame) {
+                return Some(message);
+            }
+        }
+        None
+    }
+}
+
+// Decode the DHCP message of a captured frame, or `None` if the frame
+// carries other traffic.
+pub(super) fn decode(
+    timestamp: Duration,
+    linktype: u32,
+    frame: &[u8],
+) -> Option<Result<CapturedMessage, DhcpError>> {
+    let (source, destination, payload) = bootp_payload(linktype, frame)?;
+    Some(
+        DhcpMessage::deserialize(payload).map(|message| CapturedMessage {
+            timestamp,
+            source,
+            destination,
+            message,
+        }),
+    )
+}
+
+// Read the rest of a section header block following its type, returning
+// whether the section is big endian.
+fn read_section_header<R: Read>(reader: &mut R) -> Result<bool, DhcpError> {
+    let mut header = [0; 8];
+    reader.read_exact(&mut header)?;
+    let big_endian = match (
+        u32::from_le_bytes([header[4], header[5], header[6], header[7]]),
+        u32::from_be_bytes([header[4], header[5], header[6], header[7]]),
+    ) {
+        (PCAPNG_BYTE_ORDER_MAGIC, _) => false,
+        (_, PCAPNG_BYTE_ORDER_MAGIC) => true,
+        _ => {
+            return Err(DhcpError::ParsingError(
+                "Could not parse pcapng byte order".into(),
+            ))
+        }
+    };
+    let total_len = read_u32(&header[0..4], big_endian) as usize;
+    skip(reader, total_len.saturating_sub(12))?;
+    Ok(big_endian)
+}
+
+fn read_interface(body: &[u8], big_endian: bool) -> Result<Interface, DhcpError> {
+    if body.len() < 8 {
+        return Err(DhcpError::ParsingError(
+            "Could not parse pcapng interface description block".into(),
+        ));
+    }
+    let mut interface = Interface {
+        linktype: read_u16(&body[0..2], big_endian) as u32,
+        resolution: Resolution::Decimal(6),
+    };
+
+    let mut options = &body[8..];
+    while options.len() >= 4 {
+        let code = read_u16(&options[0..2], big_endian);
+        let len = read_u16(&options[2..4], big_endian) as usize;
+        if code == PCAPNG_OPTION_END || options.len() < 4 + len {
+            break;
+        }
+        if code == PCAPNG_OPTION_IF_TSRESOL && len == 1 {
+            let value = options[4];
+            interface.resolution = if value & 0x80 == 0 {
+                Resolution::Decimal(value as u32)
+            } else {
+                Resolution::Binary((value & 0x7F) as u32)
+            };
+        }
+        options = &options[(4 + len.div_ceil(4) * 4).min(options.len())..];
+    }
+
+    Ok(interface)
+}
+
+// Fill `buffer`, returning false if the stream ended before any byte.
+fn read_or_eof<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<bool, DhcpError> {
+    let mut read = 0;
+    while read < buffer.len() {
+        match reader.read(&mut buffer[read..]) {
+            Ok(0) if read == 0 => return Ok(false),
+            Ok(0) => return Err(DhcpError::ParsingError("Truncated capture file".into())),
+            Ok(n) => read += n,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error.into()),
+        }
+    }
+    Ok(true)
+}
+
+fn skip<R: Read>(reader: &mut R, len: usize) -> Result<(), DhcpError> {
+    let skipped = io::copy(&mut reader.take(len as u64), &mut io::sink())?;
+    if skipped as usize != len {
+        return Err(DhcpError::ParsingError("Truncated capture file".into()));
+    }
+    Ok(())
+}
+
+fn read_u16(data: &[u8], big_endian: bool) -> u16 {
+    let bytes = [data[0], data[1]];
+    if big_endian {
+        u16::from_be_bytes(bytes)
+    } else {
+        u16::from_le_bytes(bytes)
+    }
+}
+
+fn read_u32(data: &[u8], big_endian: bool) -> u32 {
+    let bytes = [data[0], data[1], data[2], data[3]];
+    if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    }
+}
@@ -0,0 +1,74 @@
+use std::io::{self, Write};
+
+use crate::capture::{ethernet_frame, CapturedMessage, LINKTYPE_ETHERNET};
+
+const PCAPNG_SECTION_HEADER: u32 = 0x0A0D0D0A;
+const PCAPNG_INTERFACE_DESCRIPTION: u32 = 1;
+const PCAPNG_ENHANCED_PACKET: u32 = 6;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1A2B3C4D;
+const SNAP_LEN: u32 = 65535;
+
+/// A writer emitting DHCP messages as a pcapng capture.
+///
+/// Messages are wrapped in Ethernet, IPv4 and UDP headers on a single
+/// interface with microsecond timestamps, so they can be opened in
+/// Wireshark or read back with `CaptureReader`.
+#[derive(Debug)]
+pub struct CaptureWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> CaptureWriter<W> {
+    /// Write the section header and interface description.
+    pub fn new(mut writer: W) -> io::Result<CaptureWriter<W>> {
+        // Section header: byte order magic, version 1.0, unknown length.
+        let mut section = PCAPNG_BYTE_ORDER_MAGIC.to_le_bytes().to_vec();
+        section.extend_from_slice(&1u16.to_le_bytes());
+        section.extend_from_slice(&0u16.to_le_bytes());
+        section.extend_from_slice(&u64::MAX.to_le_bytes());
+        write_block(&mut writer, PCAPNG_SECTION_HEADER, &section)?;
+
+        let mut interface = (LINKTYPE_ETHERNET as u16).to_le_bytes().to_vec();
+        interface.extend_from_slice(&[0, 0]);
+        interface.extend_from_slice(&SNAP_LEN.to_le_bytes());
+        write_block(&mut writer, PCAPNG_INTERFACE_DESCRIPTION, &interface)?;
+
+        Ok(CaptureWriter { writer })
+    }
+
+    /// Write a message as an enhanced packet block.
+    pub fn write(&mut self, captured: &CapturedMessage) -> io::Result<()> {
+        let frame = ethernet_frame(captured.source, captured.destination, &captured.message)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error.to_string()))?;
+        let timestamp = captured.timestamp.as_micros() as u64;
+
+        let mut packet = Vec::with_capacity(20 + frame.len());
+        packet.extend_from_slice(&0u32.to_le_bytes());
+        packet.extend_from_slice(&((timestamp >> 32) as u32).to_le_bytes());
+        packet.extend_from_slice(&(timestamp as u32).to_le_bytes());
+        packet.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        packet.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        packet.extend_from_slice(&frame);
+        write_block(&mut self.writer, PCAPNG_ENHANCED_PACKET, &packet)
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+fn write_block<W: Write>(writer: &mut W, block_type: u32, body: &[u8]) -> io::Result<()> {
+    let padding = body.len().next_multiple_of(4) - body.len();
+    let total_len = (12 + body.len() + padding) as u32;
+    writer.write_all(&block_type.to_le_bytes())?;
+    writer.write_all(&total_len.to_le_bytes())?;
+    writer.write_all(body)?;
+    writer.write_all(&[0; 3][..padding])?;
+    writer.write_all(&total_len.to_le_bytes())
+}
@@ -0,0 +1,370 @@
+//! An async DHCPv4 client, obtaining a lease and keeping it through the
+//! states of RFC 2131 section 4.4.
+//!
+//! The client runs on no particular executor. The caller gives it a socket
+//! and a monotonic clock through `ClientSocket` and `Clock`: firmware
+//! implements them over the UDP socket of its network stack, bound to port
+//! 68, and over its timer. Each `step` moves the client to its next state,
+//! so that the caller configures or deconfigures its interface in between:
+//!
+//! ```text
+//! Init -> Requesting -> Bound -> Renewing -> Rebinding -> Init
+//!             |                     |             |
+//!             +-- NAK: Init         +-- ACK: Bound+
+//! ```
+
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use crate::error::{DhcpError, NakReason};
+use crate::message::{DhcpMessage, MessageType, OpCode};
+use crate::option::DhcpOption;
+
+const OPTION_PARAMETER_REQUEST_LIST: u8 = 55;
+const OPTION_MESSAGE: u8 = 56;
+const OPTION_RENEWAL_TIME: u8 = 58;
+const OPTION_REBINDING_TIME: u8 = 59;
+
+// Subnet mask, router, DNS servers, domain name and the times of the lease.
+const PARAMETERS: [u8; 8] = [1, 3, 6, 15, 51, 54, 58, 59];
+
+// Retransmissions back off from 4 to 64 seconds (RFC 2131 section 4.1).
+const INITIAL_BACKOFF: Duration = Duration::from_secs(4);
+const MAX_BACKOFF: Duration = Duration::from_secs(64);
+
+// The DISCOVER and REQUEST messages sent before giving up on the servers.
+const MAX_ATTEMPTS: usize = 5;
+
+/// The UDP socket of a client, bound to port 68 and allowed to broadcast.
+#[allow(async_fn_in_trait)]
+pub trait ClientSocket {
+    /// Send `data` to port 67 of `destination`, the broadcast address or a
+    /// server.
+    async fn send_to(&mut self, data: &[u8], destination: Ipv4Addr) -> Result<(), DhcpError>;
+
+    /// Receive a datagram into `buffer`, returning its length, or None once
+    /// the clock of the client reaches `deadline`.
+    async fn recv_until(
+        &mut self,
+        buffer: &mut [u8],
+        deadline: Duration,
+    ) -> Result<Option<usize>, DhcpError>;
+}
+
+/// A monotonic clock.
+pub trait Clock {
+    /// The time elapsed since an arbitrary origin, such as the boot.
+    fn now(&self) -> Duration;
+}
+
+/// A lease obtained by the client.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ClientLease {
+    /// The DHCPACK granting the lease, with the configuration of the
+    /// network.
+    pub ack: DhcpMessage,
+    /// When the acknowledged DHCPREQUEST was sent, on the clock of the
+    /// client, from which the times of the lease count.
+    pub obtained: Duration,
+}
+
+impl ClientLease {
+    pub fn address(&self) -> Ipv4Addr {
+        self.ack.yiaddr
+    }
+
+    pub fn server(&self) -> Option<Ipv4Addr> {
+        self.ack.server_identifier()
+    }
+
+    /// The lease time, forever if the server gave none.
+    pub fn lease_time(&self) -> Duration {
+        let seconds = self.ack.options.iter().find_map(|option| match option {
+            DhcpOption::IpAddressLeaseTime(seconds) => Some(*seconds),
+            _ => None,
+        });
+        Duration::from_secs(seconds.unwrap_or(u32::MAX).into())
+    }
+
+    /// The time after which the client renews the lease with its server,
+    /// half the lease time unless the server says otherwise (RFC 2131
+    /// section 4.4.5).
+    pub fn renewal_time(&self) -> Duration {
+        self.time(OPTION_RENEWAL_TIME)
+            .unwrap_or(self.lease_time() / 2)
+    }
+
+    /// The time after which the client asks any server to extend the
+    /// lease, seven eighths of the lease time by default.
+    pub fn rebinding_time(&self) -> Duration {
+        self.time(OPTION_REBINDING_TIME)
+            .unwrap_or(self.lease_time() * 7 / 8)
+    }
+
+    fn time(&self, code: u8) -> Option<Duration> {
+        match self.ack.get_option(code)? {
+            DhcpOption::Unknown(_, value) => {
+                let seconds = u32::from_be_bytes(value.as_slice().try_into().ok()?);
+                Some(Duration::from_secs(seconds.into()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The state of a client (RFC 2131 section 4.4).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ClientState {
+    /// No lease: the next step looks for a server.
+    Init,
+    /// An offer was received: the next step requests its address.
+    Requesting { offer: DhcpMessage },
+    /// The address is leased: the next step waits for the renewal time.
+    Bound { lease: ClientLease },
+    /// The next step asks the server of the lease to extend it, until the
+    /// rebinding time.
+    Renewing { lease: ClientLease },
+    /// The next step asks any server to extend the lease, until it
+    /// expires.
+    Rebinding { lease: ClientLease },
+}
+
+/// A client obtaining and keeping a lease, see the module documentation.
+#[derive(Debug)]
+pub struct Client<S: ClientSocket, C: Clock> {
+    socket: S,
+    clock: C,
+    mac: [u8; 6],
+    xid: u32,
+    state: ClientState,
+    buffer: Vec<u8>,
+}
+
+impl<S: ClientSocket, C: Clock> Client<S, C> {
+    /// A client with the hardware address `mac`. The transaction IDs of its
+    /// exchanges follow `xid`, which should be random (RFC 2131 section
+    /// 4.4.1).
+    pub fn new(socket: S, clock: C, mac: [u8; 6], xid: u32) -> Client<S, C> {
+        Client {
+            socket,
+            clock,
+            mac,
+            xid,
+            state: ClientState::Init,
+            buffer: vec![0; 1500],
+        }
+    }
+
+    pub fn state(&self) -> &ClientState {
+        &self.state
+    }
+
+    /// The current lease, if any.
+    pub fn lease(&self) -> Option<&ClientLease> {
+        match &self.state {
+            ClientState::Bound { lease }
+            | ClientState::Renewing { lease }
+            | ClientState::Rebinding { lease } => Some(lease),
+            ClientState::Init | ClientState::Requesting { .. } => None,
+        }
+    }
+
+    /// Step until a lease is obtained, from the Init state.
+    pub async fn obtain(&mut self) -> Result<&ClientLease, DhcpError> {
+        while !matches!(self.state, ClientState::Bound { .. }) {
+            self.step().await?;
+        }
+        Ok(self.lease().expect("bound"))
+    }
+
+    /// Move to the next state, returning it.
+    ///
+    /// Fails with `DhcpError::Timeout` when no server answers, or with
+    /// `DhcpError::Nak` when the server refuses the request, the client
+    /// going back to Init. Errors of the socket leave the state unchanged.
+    pub async fn step(&mut self) -> Result<&ClientState, DhcpError> {
+        self.state = match self.state.clone() {
+            ClientState::Init => {
+                let discover = self.message(MessageType::Discover);
+                let broadcast = Ipv4Addr::BROADCAST;
+                match self.exchange(&discover, broadcast, None).await? {
+                    Some(offer) => ClientState::Requesting { offer },
+                    None => return Err(DhcpError::Timeout),
+                }
+            }
+            ClientState::Requesting { offer } => {
+                // In the transaction of the DISCOVER (RFC 2131 section
+                // 4.4.1).
+                let mut request = DhcpMessage::request(&offer)?;
+                self.complete(&mut request);
+                let sent = self.clock.now();
+                match self.exchange(&request, Ipv4Addr::BROADCAST, None).await? {
+                    Some(ack) => self.acknowledged(ack, sent, None)?,
+                    None => {
+                        self.state = ClientState::Init;
+                        return Err(DhcpError::Timeout);
+                    }
+                }
+            }
+            ClientState::Bound { lease } => {
+                self.wait_until(lease.obtained + lease.renewal_time())
+                    .await?;
+                ClientState::Renewing { lease }
+            }
+            ClientState::Renewing { lease } => {
+                let server = lease.server().unwrap_or(Ipv4Addr::BROADCAST);
+                let until = lease.obtained + lease.rebinding_time();
+                match self.extend(&lease, server, until).await? {
+                    Some(state) => state,
+                    None => ClientState::Rebinding { lease },
+                }
+            }
+            ClientState::Rebinding { lease } => {
+                let until = lease.obtained + lease.lease_time();
+                match self.extend(&lease, Ipv4Addr::BROADCAST, until).await? {
+                    Some(state) => state,
+                    None => ClientState::Init,
+                }
+            }
+        };
+        Ok(&self.state)
+    }
+
+    // Ask `server` to extend `lease` until the clock reaches `until`,
+    // returning the state following its reply, or None without reply.
+    async fn extend(
+        &mut self,
+        lease: &ClientLease,
+        server: Ipv4Addr,
+        until: Duration,
+    ) -> Result<Option<ClientState>, DhcpError> {
+        // The client has its address: the request carries it in ciaddr,
+        // without server identifier nor requested address (RFC 2131
+        // section 4.3.2).
+        let mut request = self.message(MessageType::Request);
+        request.flags.set_broadcast(false);
+        request.ciaddr = lease.address();
+        let sent = self.clock.now();
+        match self.exchange(&request, server, Some(until)).await? {
+            Some(ack) => self
+                .acknowledged(ack, sent, Some(lease.address()))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    // The state following the reply `ack` to a request sent at `sent`.
+    fn acknowledged(
+        &mut self,
+        ack: DhcpMessage,
+        sent: Duration,
+        ciaddr: Option<Ipv4Addr>,
+    ) -> Result<ClientState, DhcpError> {
+        if !is_type(&ack, MessageType::Ack) {
+            self.state = ClientState::Init;
+            return Err(DhcpError::Nak(nak_reason(&ack)));
+        }
+        let mut ack = ack;
+        // Some servers leave yiaddr empty when extending a lease.
+        if ack.yiaddr.is_unspecified() {
+            ack.yiaddr = ciaddr.unwrap_or(ack.yiaddr);
+        }
+        Ok(ClientState::Bound {
+            lease: ClientLease {
+                ack,
+                obtained: sent,
+            },
+        })
+    }
+
+    // Send `message` to `destination` until a reply arrives: after
+    // `MAX_ATTEMPTS` messages, or until the clock reaches `until` if
+    // given. Returns the first OFFER to a DISCOVER, or ACK or NAK to a
+    // REQUEST.
+    async fn exchange(
+        &mut self,
+        message: &DhcpMessage,
+        destination: Ipv4Addr,
+        until: Option<Duration>,
+    ) -> Result<Option<DhcpMessage>, DhcpError> {
+        let data = message.serialize()?;
+        let mut backoff = INITIAL_BACKOFF;
+        for attempt in 1.. {
+            let now = self.clock.now();
+            match until {
+                Some(until) if now >= until => return Ok(None),
+                None if attempt > MAX_ATTEMPTS => return Ok(None),
+                _ => {}
+            }
+            self.socket.send_to(&data, destination).await?;
+            let retransmit = until.map_or(now + backoff, |until| (now + backoff).min(until));
+            while let Some(len) = self.socket.recv_until(&mut self.buffer, retransmit).await? {
+                let reply = match DhcpMessage::deserialize(&self.buffer[..len]) {
+                    Ok(reply) if reply.op == 2 && reply.xid == message.xid => reply,
+                    _ => continue,
+                };
+                let expected = match message.message_type() {
+                    Ok(Some(MessageType::Discover)) => is_type(&reply, MessageType::Offer),
+                    _ => is_type(&reply, MessageType::Ack) || is_type(&reply, MessageType::Nak),
+                };
+                if expected {
+                    return Ok(Some(reply));
+                }
+            }
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+        Ok(None)
+    }
+
+    // Wait until the clock reaches `deadline`, dropping the datagrams
+    // received meanwhile.
+    async fn wait_until(&mut self, deadline: Duration) -> Result<(), DhcpError> {
+        while self
+            .socket
+            .recv_until(&mut self.buffer, deadline)
+            .await?
+            .is_some()
+        {}
+        Ok(())
+    }
+
+    // A new broadcast message of the client, in a new transaction.
+    fn message(&mut self, message_type: MessageType) -> DhcpMessage {
+        let mut message = DhcpMessage::new(OpCode::BootRequest.into(), self.next_xid());
+        message.set_hardware_address(&self.mac.into());
+        message.options.push(message_type.into());
+        self.complete(&mut message);
+        message
+    }
+
+    // Add the options every message of the client carries.
+    fn complete(&self, message: &mut DhcpMessage) {
+        message.flags.set_broadcast(true);
+        message
+            .options
+            .push(DhcpOption::ClientIdentifier([&[1][..], &self.mac].concat()));
+        message.options.push(DhcpOption::Unknown(
+            OPTION_PARAMETER_REQUEST_LIST,
+            PARAMETERS.to_vec(),
+        ));
+    }
+
+    fn next_xid(&mut self) -> u32 {
+        self.xid = self.xid.wrapping_add(1);
+        self.xid
+    }
+}
+
+fn is_type(message: &DhcpMessage, message_type: MessageType) -> bool {
+    matches!(message.message_type(), Ok(Some(found)) if found == message_type)
+}
+
+fn nak_reason(nak: &DhcpMessage) -> NakReason {
+    let text = match nak.get_option(OPTION_MESSAGE) {
+        Some(DhcpOption::Unknown(_, value)) => String::from_utf8_lossy(value),
+        _ => "no reason given".into(),
+    };
+    NakReason::from_message(&text)
+}
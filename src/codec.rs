@@ -0,0 +1,126 @@
+//! Message encoding and decoding with reusable buffers.
+//!
+//! A long-running server or client keeps one `DhcpEncoder` and one
+//! `DhcpDecoder`, configured once, instead of allocating buffers and
+//! passing settings around for every message.
+
+use crate::error::{DhcpError, SerializationError};
+use crate::message::{DhcpMessage, ParserConfig};
+
+/// The size of the decoder receive buffer, enough for any message fitting
+/// in an Ethernet frame.
+pub const DEFAULT_BUFFER_LEN: usize = 1500;
+
+/// Serializes messages into a buffer reused from one message to the next.
+#[derive(Debug, Clone, Default)]
+pub struct DhcpEncoder {
+    buffer: Vec<u8>,
+    // The size budget of the encoded messages.
+    max_len: Option<usize>,
+}
+
+impl DhcpEncoder {
+    pub fn new() -> DhcpEncoder {
+        DhcpEncoder::default()
+    }
+
+    /// Fail to encode messages longer than `max_len` bytes, see
+    /// `DhcpMessage::serialize_within`.
+    pub fn with_max_len(mut self, max_len: usize) -> DhcpEncoder {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Serialize a message, returning its bytes until the next call.
+    pub fn encode(&mut self, message: &DhcpMessage) -> Result<&[u8], DhcpError> {
+        self.buffer.clear();
+        if let Some(max) = self.max_len {
+            let len = message.wire_len();
+            if len > max {
+                return Err(DhcpError::SerializationError(
+                    SerializationError::MessageTooLong { len, max },
+                ));
+            }
+        }
+        if let Err(error) = message.serialize_into(&mut self.buffer) {
+            self.buffer.clear();
+            return Err(error);
+        }
+        Ok(&self.buffer)
+    }
+}
+
+/// Deserializes messages with fixed settings, holding a receive buffer and
+/// the warnings of the last message.
+#[derive(Debug)]
+pub struct DhcpDecoder {
+    config: ParserConfig,
+    // Skip the options that cannot be parsed, see
+    // `DhcpMessage::deserialize_partial`.
+    lenient: bool,
+    buffer: Vec<u8>,
+    warnings: Vec<DhcpError>,
+}
+
+impl Default for DhcpDecoder {
+    fn default() -> DhcpDecoder {
+        DhcpDecoder::new(ParserConfig::default())
+    }
+}
+
+impl DhcpDecoder {
+    pub fn new(config: ParserConfig) -> DhcpDecoder {
+        DhcpDecoder {
+            config,
+            lenient: false,
+            buffer: vec![0; DEFAULT_BUFFER_LEN],
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Skip the options that cannot be parsed instead of failing, the
+    /// errors met being available from `warnings`.
+    pub fn lenient(mut self) -> DhcpDecoder {
+        self.lenient = true;
+        self
+    }
+
+    /// Use a receive buffer of `len` bytes.
+    pub fn with_buffer_len(mut self, len: usize) -> DhcpDecoder {
+        self.buffer = vec![0; len];
+        self
+    }
+
+    /// The buffer to receive a message into, before `decode_buffer`.
+    pub fn buffer(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+
+    /// Deserialize a message.
+    pub fn decode(&mut self, data: &[u8]) -> Result<DhcpMessage, DhcpError> {
+        self.warnings.clear();
+        let warnings = match self.lenient {
+            true => Some(&mut self.warnings),
+            false => None,
+        };
+        DhcpMessage::parse(data, &self.config, warnings)
+    }
+
+    /// Deserialize the first `len` bytes of the receive buffer.
+    pub fn decode_buffer(&mut self, len: usize) -> Result<DhcpMessage, DhcpError> {
+        let buffer = std::mem::take(&mut self.buffer);
+        let result = self.decode(&buffer[..len.min(buffer.len())]);
+        self.buffer = buffer;
+        result
+    }
+
+    /// The errors met while decoding the last message in lenient mode.
+    pub fn warnings(&self) -> &[DhcpError] {
+        &self.warnings
+    }
+
+    /// Take the errors met while decoding the last message in lenient mode.
+    pub fn take_warnings(&mut self) -> Vec<DhcpError> {
+        std::mem::take(&mut self.warnings)
+    }
+}
@@ -0,0 +1,394 @@
+//! Protocol conformance checks against a DHCP server.
+//!
+//! The checks drive a server through an `Exchange`, which may be a server
+//! in the same process or a remote one reached over UDP, and collect the
+//! outcome of each check in a `Report`.
+
+use std::fmt;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+
+use crate::error::DhcpError;
+use crate::message::{DhcpMessage, Flags, MessageType};
+use crate::option::DhcpOption;
+
+const OPTION_OVERLOAD: u8 = 52;
+
+// An address no server should consider on its network (RFC 5737).
+const FOREIGN_ADDRESS: Ipv4Addr = Ipv4Addr::new(192, 0, 2, 1);
+
+/// A way to send a message to the server under test and get its reply.
+pub trait Exchange {
+    /// Send `request`, returning the reply if one arrives.
+    fn exchange(&mut self, request: &DhcpMessage) -> Result<Option<DhcpMessage>, DhcpError>;
+}
+
+impl<F: FnMut(&DhcpMessage) -> Option<DhcpMessage>> Exchange for F {
+    fn exchange(&mut self, request: &DhcpMessage) -> Result<Option<DhcpMessage>, DhcpError> {
+        Ok(self(request))
+    }
+}
+
+/// An exchange with a remote server over UDP.
+///
+/// The socket is set up by the caller, usually bound to the client port
+/// with broadcast enabled, and with a read timeout bounding the wait for
+/// each reply.
+#[derive(Debug)]
+pub struct UdpExchange {
+    socket: UdpSocket,
+    server: SocketAddr,
+}
+
+impl UdpExchange {
+    pub fn new(socket: UdpSocket, server: SocketAddr) -> UdpExchange {
+        UdpExchange { socket, server }
+    }
+}
+
+impl Exchange for UdpExchange {
+    fn exchange(&mut self, request: &DhcpMessage) -> Result<Option<DhcpMessage>, DhcpError> {
+        self.socket.send_to(&request.serialize()?, self.server)?;
+
+        let mut buffer = [0; 1500];
+        loop {
+            let len = match self.socket.recv_from(&mut buffer) {
+                Ok((len, _)) => len,
+                Err(error)
+                    if error.kind() == io::ErrorKind::WouldBlock
+                        || error.kind() == io::ErrorKind::TimedOut =>
+                {
+                    return Ok(None)
+                }
+                Err(error) => return Err(error.into()),
+            };
+            // Skip traffic for other clients.
+            match DhcpMessage::deserialize(&buffer[..len]) {
+                Ok(reply) if reply.op == 2 && reply.xid == request.xid => return Ok(Some(reply)),
+                _ => continue,
+            }
+        }
+    }
+}
+
+/// The outcome of a check.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail(String),
+    // The check could not run, for example because an earlier exchange
+    // failed.
+    Skipped(String),
+}
+
+/// The outcome of every check run.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Report {
+    pub results: Vec<(&'static str, Outcome)>,
+}
+
+impl Report {
+    /// Whether no check failed.
+    pub fn passed(&self) -> bool {
+        !self
+            .results
+            .iter()
+            .any(|(_, outcome)| matches!(outcome, Outcome::Fail(_)))
+    }
+
+    pub fn outcome(&self, check: &str) -> Option<&Outcome> {
+        self.results
+            .iter()
+            .find(|(name, _)| *name == check)
+            .map(|(_, outcome)| outcome)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, outcome) in &self.results {
+            match outcome {
+                Outcome::Pass => writeln!(f, "PASS {}", name)?,
+                Outcome::Fail(reason) => writeln!(f, "FAIL {}: {}", name, reason)?,
+                Outcome::Skipped(reason) => writeln!(f, "SKIP {}: {}", name, reason)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run the server checks, as a client with hardware address `mac`:
+///
+/// - `discover_offer`: a DHCPDISCOVER gets a DHCPOFFER with an address, a
+///   lease time and a server identifier (RFC 2131 table 3).
+/// - `request_ack`: a DHCPREQUEST for the offered address gets a DHCPACK
+///   for it, with a lease time and a server identifier.
+/// - `request_nak`: a DHCPREQUEST for an address of another network gets
+///   a DHCPNAK without an address or lease time (RFC 2131 section 4.3.2).
+/// - `inform_ack`: a DHCPINFORM gets a DHCPACK without an address or lease
+///   time (RFC 2131 section 4.3.5).
+/// - `release_silent`: a DHCPRELEASE gets no reply.
+/// - `overload`: replies using option overload (RFC 2132 section 9.3)
+///   carry well-formed options in the fields they overload.
+///
+/// The lease obtained by the checks is released at the end.
+pub fn check_server<E: Exchange>(exchange: &mut E, mac: [u8; 6]) -> Report {
+    let mut report = Report::default();
+    let mut replies = Vec::new();
+    let xid = u32::from_be_bytes([mac[2], mac[3], mac[4], mac[5]]);
+
+    // DISCOVER / OFFER.
+    let discover = client_message(MessageType::Discover, xid, mac, vec![]);
+    let offer = match exchange.exchange(&discover) {
+        Ok(Some(offer)) => {
+            report.results.push((
+                "discover_offer",
+                check_reply(&discover, &offer, MessageType::Offer),
+            ));
+            replies.push(offer.clone());
+            Some(offer)
+        }
+        Ok(None) => {
+            report
+                .results
+                .push(("discover_offer", Outcome::Fail("No reply".to_string())));
+            None
+        }
+        Err(error) => {
+            report
+                .results
+                .push(("discover_offer", Outcome::Fail(error.to_string())));
+            None
+        }
+    };
+
+    // REQUEST / ACK.
+    let mut lease = None;
+    let outcome = match &offer {
+        Some(offer) if !offer.yiaddr.is_unspecified() => {
+            let mut options = vec![DhcpOption::RequestedIpAddress(offer.yiaddr)];
+            if let Some(server) = offer.server_identifier() {
+                options.push(DhcpOption::ServerIdentifier(server));
+            }
+            let request = client_message(MessageType::Request, xid.wrapping_add(1), mac, options);
+            match exchange.exchange(&request) {
+                Ok(Some(ack)) => {
+                    let mut outcome = check_reply(&request, &ack, MessageType::Ack);
+                    if outcome == Outcome::Pass && ack.yiaddr != offer.yiaddr {
+                        outcome = Outcome::Fail(format!(
+                            "Acknowledged {} instead of {}",
+                            ack.yiaddr, offer.yiaddr
+                        ));
+                    }
+                    if outcome == Outcome::Pass {
+                        lease = Some((ack.yiaddr, ack.server_identifier()));
+                    }
+                    replies.push(ack);
+                    outcome
+                }
+                Ok(None) => Outcome::Fail("No reply".to_string()),
+                Err(error) => Outcome::Fail(error.to_string()),
+            }
+        }
+        _ => Outcome::Skipped("No address offered".to_string()),
+    };
+    report.results.push(("request_ack", outcome));
+
+    // REQUEST for a foreign address / NAK, as a rebooting client.
+    let reboot = client_message(
+        MessageType::Request,
+        xid.wrapping_add(2),
+        mac,
+        vec![DhcpOption::RequestedIpAddress(FOREIGN_ADDRESS)],
+    );
+    let outcome = match exchange.exchange(&reboot) {
+        Ok(Some(nak)) => {
+            let mut outcome = check_header(&reboot, &nak, MessageType::Nak);
+            if outcome == Outcome::Pass && !nak.yiaddr.is_unspecified() {
+                outcome = Outcome::Fail(format!("DHCPNAK assigns {}", nak.yiaddr));
+            }
+            if outcome == Outcome::Pass && has_lease_time(&nak) {
+                outcome = Outcome::Fail("DHCPNAK carries a lease time".to_string());
+            }
+            replies.push(nak);
+            outcome
+        }
+        Ok(None) => Outcome::Fail("No reply".to_string()),
+        Err(error) => Outcome::Fail(error.to_string()),
+    };
+    report.results.push(("request_nak", outcome));
+
+    // INFORM / ACK, from the leased address.
+    let outcome = match &lease {
+        Some((address, _)) => {
+            let mut inform = client_message(MessageType::Inform, xid.wrapping_add(3), mac, vec![]);
+            inform.ciaddr = *address;
+            match exchange.exchange(&inform) {
+                Ok(Some(ack)) => {
+                    let mut outcome = check_header(&inform, &ack, MessageType::Ack);
+                    if outcome == Outcome::Pass && !ack.yiaddr.is_unspecified() {
+                        outcome = Outcome::Fail(format!("DHCPACK assigns {}", ack.yiaddr));
+                    }
+                    if outcome == Outcome::Pass && has_lease_time(&ack) {
+                        outcome = Outcome::Fail("DHCPACK carries a lease time".to_string());
+                    }
+                    replies.push(ack);
+                    outcome
+                }
+                Ok(None) => Outcome::Fail("No reply".to_string()),
+                Err(error) => Outcome::Fail(error.to_string()),
+            }
+        }
+        None => Outcome::Skipped("No lease obtained".to_string()),
+    };
+    report.results.push(("inform_ack", outcome));
+
+    // RELEASE, which must not be answered.
+    let outcome = match lease {
+        Some((address, server)) => {
+            let mut options = Vec::new();
+            if let Some(server) = server {
+                options.push(DhcpOption::ServerIdentifier(server));
+            }
+            let mut release =
+                client_message(MessageType::Release, xid.wrapping_add(4), mac, options);
+            release.flags = Flags::default();
+            release.ciaddr = address;
+            match exchange.exchange(&release) {
+                Ok(None) => Outcome::Pass,
+                Ok(Some(_)) => Outcome::Fail("DHCPRELEASE was answered".to_string()),
+                Err(error) => Outcome::Fail(error.to_string()),
+            }
+        }
+        None => Outcome::Skipped("No lease obtained".to_string()),
+    };
+    report.results.push(("release_silent", outcome));
+
+    // Option overload, in every reply received.
+    let overloaded = replies
+        .iter()
+        .filter(|reply| find_option(reply, OPTION_OVERLOAD).is_some())
+        .collect::<Vec<&DhcpMessage>>();
+    let outcome = if overloaded.is_empty() {
+        Outcome::Skipped("No reply used option overload".to_string())
+    } else {
+        overloaded
+            .iter()
+            .map(|reply| check_overload(reply))
+            .find(|outcome| *outcome != Outcome::Pass)
+            .unwrap_or(Outcome::Pass)
+    };
+    report.results.push(("overload", outcome));
+
+    report
+}
+
+// Build a broadcast client message.
+fn client_message(
+    message_type: MessageType,
+    xid: u32,
+    mac: [u8; 6],
+    options: Vec<DhcpOption>,
+) -> DhcpMessage {
+    let mut message = DhcpMessage::new(1, xid);
+    message.flags.set_broadcast(true);
+    message.chaddr[..6].copy_from_slice(&mac);
+    message.options = vec![DhcpOption::from(message_type)].into();
+    message.options.extend(options);
+    message
+}
+
+// Check the header of a reply, and its message type.
+fn check_header(request: &DhcpMessage, reply: &DhcpMessage, message_type: MessageType) -> Outcome {
+    if reply.op != 2 {
+        return Outcome::Fail(format!("Reply op is {}", reply.op));
+    }
+    if reply.xid != request.xid {
+        return Outcome::Fail(format!("Reply xid is 0x{:08x}", reply.xid));
+    }
+    if reply.chaddr != request.chaddr {
+        return Outcome::Fail("Reply chaddr differs from the request".to_string());
+    }
+    match reply.message_type() {
+        Ok(Some(reply_type)) if reply_type == message_type => {}
+        Ok(Some(reply_type)) => {
+            return Outcome::Fail(format!(
+                "Expected message type {}, got {}",
+                message_type, reply_type
+            ))
+        }
+        Ok(None) => return Outcome::Fail("Reply has no message type".to_string()),
+        Err(error) => return Outcome::Fail(error.to_string()),
+    }
+    if reply.server_identifier().is_none() {
+        return Outcome::Fail("Reply has no server identifier".to_string());
+    }
+    Outcome::Pass
+}
+
+// Check a reply assigning an address.
+fn check_reply(request: &DhcpMessage, reply: &DhcpMessage, message_type: MessageType) -> Outcome {
+    let outcome = check_header(request, reply, message_type);
+    if outcome != Outcome::Pass {
+        return outcome;
+    }
+    if reply.yiaddr.is_unspecified() {
+        return Outcome::Fail("Reply assigns no address".to_string());
+    }
+    if !has_lease_time(reply) {
+        return Outcome::Fail("Reply has no lease time".to_string());
+    }
+    Outcome::Pass
+}
+
+// Check that the fields overloaded by a reply hold options ending with an
+// End option. They are read from the serialized reply, as the parser moves
+// the options of received replies out of these fields.
+fn check_overload(reply: &DhcpMessage) -> Outcome {
+    let overload = match find_option(reply, OPTION_OVERLOAD).as_deref() {
+        Some(&[overload]) if (1..=3).contains(&overload) => overload,
+        value => return Outcome::Fail(format!("Invalid option overload value {:?}", value)),
+    };
+    let data = match reply.serialize() {
+        Ok(data) => data,
+        Err(error) => return Outcome::Fail(format!("Could not serialize reply: {}", error)),
+    };
+
+    let mut fields = Vec::new();
+    if overload & 1 != 0 {
+        fields.push(("file", &data[108..236]));
+    }
+    if overload & 2 != 0 {
+        fields.push(("sname", &data[44..108]));
+    }
+    for (name, mut data) in fields {
+        loop {
+            match DhcpOption::deserialize(data) {
+                Ok((DhcpOption::End, _)) => break,
+                Ok((_, rest)) => data = rest,
+                Err(error) => {
+                    return Outcome::Fail(format!("Overloaded {} field: {}", name, error))
+                }
+            }
+        }
+    }
+    Outcome::Pass
+}
+
+fn has_lease_time(message: &DhcpMessage) -> bool {
+    message
+        .options
+        .iter()
+        .any(|option| matches!(option, DhcpOption::IpAddressLeaseTime(_)))
+}
+
+// Find the raw value of an option by code.
+fn find_option(message: &DhcpMessage, code: u8) -> Option<Vec<u8>> {
+    message.options.iter().find_map(|option| {
+        let data = option.encode();
+        match data.first() {
+            Some(option_code) if *option_code == code => Some(data[2..].to_vec()),
+            _ => None,
+        }
+    })
+}
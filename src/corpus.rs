@@ -0,0 +1,234 @@
+//! A corpus of valid and malformed DHCP packets.
+//!
+//! The corpus seeds fuzzers and exercises other implementations with the
+//! packets parsers get wrong most often: truncated data, bad lengths,
+//! repeated options and oversized messages.
+//!
+//! Packets captured from real clients can be kept as fixtures, loaded with
+//! `load_fixtures` and checked with `Fixture::check`, so that regressions
+//! against real-world traffic are caught.
+
+use std::fs;
+use std::io;
+use std::net::Ipv4Addr;
+use std::path::Path;
+
+use crate::error::DhcpError;
+use crate::message::{parse_hex, DhcpMessage, MessageType};
+use crate::option::DhcpOption;
+
+/// A packet of the corpus.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorpusEntry {
+    pub name: &'static str,
+    // Whether the packet follows RFC 2131 and RFC 2132.
+    pub valid: bool,
+    pub data: Vec<u8>,
+}
+
+/// Build the corpus.
+pub fn corpus() -> Vec<CorpusEntry> {
+    let client = [0x02, 0, 0, 0, 0, 0x01];
+    let server = Ipv4Addr::new(192, 168, 1, 1);
+    let address = Ipv4Addr::new(192, 168, 1, 100);
+
+    let message = |op: u8, message_type: MessageType, options: Vec<DhcpOption>| {
+        let mut message = DhcpMessage::new(op, 0x3903F326);
+        message.chaddr[..6].copy_from_slice(&client);
+        message.options = vec![DhcpOption::from(message_type)].into();
+        message.options.extend(options);
+        message
+    };
+    let server_identifier = || DhcpOption::ServerIdentifier(server);
+    let reply_options = || {
+        vec![
+            server_identifier(),
+            DhcpOption::IpAddressLeaseTime(86400),
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+            DhcpOption::Router(vec![server].into()),
+            DhcpOption::DomainNameServer(vec![server, Ipv4Addr::new(192, 168, 1, 2)].into()),
+            DhcpOption::DomainName("example.com".to_string()),
+        ]
+    };
+
+    let discover = message(
+        1,
+        MessageType::Discover,
+        vec![
+            DhcpOption::ClientIdentifier([&[1][..], &client].concat()),
+            DhcpOption::Unknown(55, vec![1, 3, 6, 15, 51, 54]),
+        ],
+    );
+    let mut offer = message(2, MessageType::Offer, reply_options());
+    offer.yiaddr = address;
+    let request = message(
+        1,
+        MessageType::Request,
+        vec![DhcpOption::RequestedIpAddress(address), server_identifier()],
+    );
+    let mut ack = message(2, MessageType::Ack, reply_options());
+    ack.yiaddr = address;
+    let nak = message(2, MessageType::Nak, vec![server_identifier()]);
+    let mut release = message(1, MessageType::Release, vec![server_identifier()]);
+    release.ciaddr = address;
+    let mut inform = message(1, MessageType::Inform, vec![]);
+    inform.ciaddr = address;
+    let mut bootp = DhcpMessage::new(1, 0x3903F326);
+    bootp.chaddr[..6].copy_from_slice(&client);
+
+    // The messages above are all valid.
+    let bytes = |message: &DhcpMessage| message.serialize().expect("valid message");
+    let valid = |name: &'static str, data: Vec<u8>| CorpusEntry {
+        name,
+        valid: true,
+        data,
+    };
+    let malformed = |name: &'static str, data: Vec<u8>| CorpusEntry {
+        name,
+        valid: false,
+        data,
+    };
+    // A DISCOVER with raw option bytes in place of its options.
+    let with_options = |options: &[u8]| {
+        let mut data = bytes(&discover);
+        data.truncate(DhcpMessage::HEADER_LEN);
+        data.extend_from_slice(options);
+        data
+    };
+
+    let mut padded = bytes(&discover);
+    padded.resize(300, 0);
+
+    let mut giant = discover.clone();
+    giant.options.pop();
+    for code in 224..=254 {
+        giant
+            .options
+            .push(DhcpOption::Unknown(code, vec![code; 255]));
+    }
+    let giant = bytes(&giant);
+
+    let mut bad_cookie = bytes(&discover);
+    bad_cookie[236..240].copy_from_slice(&[0, 0, 0, 0]);
+
+    vec![
+        valid("discover", bytes(&discover)),
+        valid("offer", bytes(&offer)),
+        valid("request", bytes(&request)),
+        valid("ack", bytes(&ack)),
+        valid("nak", bytes(&nak)),
+        valid("release", bytes(&release)),
+        valid("inform", bytes(&inform)),
+        valid("bootp", bytes(&bootp)),
+        valid("padded", padded),
+        valid("pad-options", with_options(&[0, 0, 53, 1, 1, 0, 255])),
+        valid("giant", giant),
+        malformed("truncated-header", bytes(&discover)[..100].to_vec()),
+        malformed("bad-magic-cookie", bad_cookie),
+        malformed("missing-end", with_options(&[53, 1, 1])),
+        malformed("truncated-option-length", with_options(&[53])),
+        malformed(
+            "truncated-option-value",
+            with_options(&[53, 1, 1, 3, 8, 192, 168]),
+        ),
+        malformed(
+            "bad-fixed-length",
+            with_options(&[53, 1, 1, 1, 2, 255, 255, 255]),
+        ),
+        malformed(
+            "bad-list-length",
+            with_options(&[53, 1, 1, 3, 6, 1, 2, 3, 4, 5, 6, 255]),
+        ),
+        malformed("empty-address-list", with_options(&[53, 1, 1, 6, 0, 255])),
+        malformed(
+            "duplicate-message-type",
+            with_options(&[53, 1, 1, 53, 1, 3, 255]),
+        ),
+        malformed("invalid-message-type", with_options(&[53, 1, 0, 255])),
+        malformed("invalid-overload", with_options(&[53, 1, 1, 52, 1, 4, 255])),
+        malformed(
+            "non-utf8-host-name",
+            with_options(&[53, 1, 1, 12, 2, 0xC3, 0x28, 255]),
+        ),
+        malformed(
+            "data-after-end",
+            with_options(&[53, 1, 1, 255, 3, 4, 1, 2, 3, 4]),
+        ),
+    ]
+}
+
+/// Write the corpus to `directory`, one file per packet named after its
+/// validity and name, and return the number of files written.
+pub fn write_corpus<P: AsRef<Path>>(directory: P) -> io::Result<usize> {
+    let directory = directory.as_ref();
+    fs::create_dir_all(directory)?;
+
+    let corpus = corpus();
+    for entry in &corpus {
+        let prefix = if entry.valid { "valid" } else { "malformed" };
+        fs::write(
+            directory.join(format!("{}-{}.bin", prefix, entry.name)),
+            &entry.data,
+        )?;
+    }
+    Ok(corpus.len())
+}
+
+/// A packet loaded from a fixture file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fixture {
+    // The file name, without its extension.
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+impl Fixture {
+    /// Decode the packet, encode it again and decode the result, failing
+    /// unless both decoded messages are equal. Padding, Pad options and the
+    /// split of long options are not compared, as they are not kept once
+    /// decoded.
+    pub fn check(&self) -> Result<DhcpMessage, DhcpError> {
+        let message = DhcpMessage::deserialize(&self.data)?;
+        let reencoded = DhcpMessage::deserialize(&message.serialize()?)?;
+        if reencoded != message {
+            return Err(DhcpError::ParsingError(
+                format!("Fixture {} does not round-trip", self.name).into(),
+            ));
+        }
+        Ok(message)
+    }
+}
+
+/// Load the fixtures of `directory`, sorted by name: binary packets from
+/// `.bin` files, as written by `write_corpus`, and packets in hexadecimal
+/// from `.hex` files, as copied from a hexdump. Lines of `.hex` files
+/// starting with `#` are comments, such as the client that sent the
+/// packet. Other files are ignored.
+pub fn load_fixtures<P: AsRef<Path>>(directory: P) -> Result<Vec<Fixture>, DhcpError> {
+    let mut fixtures = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let name = match path.file_stem().and_then(|name| name.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        let data = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("bin") => fs::read(&path)?,
+            Some("hex") => {
+                let text = fs::read_to_string(&path)?;
+                let digits: String = text
+                    .lines()
+                    .filter(|line| !line.trim_start().starts_with('#'))
+                    .collect::<Vec<&str>>()
+                    .join("\n");
+                parse_hex(&digits).map_err(|_| {
+                    DhcpError::ParsingError(format!("Could not parse fixture {}", name).into())
+                })?
+            }
+            _ => continue,
+        };
+        fixtures.push(Fixture { name, data });
+    }
+    fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(fixtures)
+}
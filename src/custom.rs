@@ -0,0 +1,217 @@
+//! Typed site-specific options.
+//!
+//! Options this crate does not know are parsed as `DhcpOption::Unknown`.
+//! The `dhcp_options!` macro defines a type for each of them, converting
+//! to and from their raw value, so that an organization can model its
+//! private options (codes 224 to 254, RFC 3942) without patching
+//! `DhcpOption`:
+//!
+//! ```
+//! use std::net::Ipv4Addr;
+//!
+//! use dhcp::custom::CustomOption;
+//! use dhcp::dhcp_options;
+//! use dhcp::message::DhcpMessage;
+//!
+//! dhcp_options! {
+//!     224 => BackupServer(Ipv4Addr),
+//!     230 => SiteTag(String),
+//! }
+//!
+//! let mut message = DhcpMessage::new(2, 1);
+//! message.options.push(SiteTag("lab".to_string()).into());
+//! assert_eq!(SiteTag::get(&message).unwrap(), Some(SiteTag("lab".to_string())));
+//! assert_eq!(BackupServer::get(&message).unwrap(), None);
+//! ```
+
+use std::net::Ipv4Addr;
+use std::str::from_utf8;
+
+use crate::error::DhcpError;
+use crate::message::DhcpMessage;
+use crate::option::DhcpOption;
+
+/// An option carried as `DhcpOption::Unknown`, usually defined with
+/// `dhcp_options!`.
+pub trait CustomOption: Sized {
+    const CODE: u8;
+
+    /// Decode the value of the option.
+    fn decode(value: &[u8]) -> Result<Self, DhcpError>;
+
+    /// Append the value of the option to `value`.
+    fn encode(&self, value: &mut Vec<u8>);
+
+    fn to_option(&self) -> DhcpOption {
+        let mut value = Vec::new();
+        self.encode(&mut value);
+        DhcpOption::Unknown(Self::CODE, value)
+    }
+
+    /// Decode `option`, if it has the code of this option.
+    fn from_option(option: &DhcpOption) -> Result<Option<Self>, DhcpError> {
+        match option {
+            DhcpOption::Unknown(code, value) if *code == Self::CODE => {
+                Self::decode(value).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Decode the first option of `message` with the code of this option.
+    fn get(message: &DhcpMessage) -> Result<Option<Self>, DhcpError> {
+        match message.get_option(Self::CODE) {
+            Some(option) => Self::from_option(option),
+            None => Ok(None),
+        }
+    }
+}
+
+/// A type usable as the value of an option defined with `dhcp_options!`.
+pub trait OptionValue: Sized {
+    /// Decode the value of the option with code `code`.
+    fn decode(code: u8, value: &[u8]) -> Result<Self, DhcpError>;
+
+    fn encode(&self, value: &mut Vec<u8>);
+}
+
+impl OptionValue for Vec<u8> {
+    fn decode(_: u8, value: &[u8]) -> Result<Vec<u8>, DhcpError> {
+        Ok(value.to_vec())
+    }
+
+    fn encode(&self, value: &mut Vec<u8>) {
+        value.extend_from_slice(self);
+    }
+}
+
+impl OptionValue for String {
+    fn decode(code: u8, value: &[u8]) -> Result<String, DhcpError> {
+        match from_utf8(value) {
+            Ok(value) => Ok(value.to_string()),
+            Err(_) => Err(DhcpError::InvalidUtf8 { code }),
+        }
+    }
+
+    fn encode(&self, value: &mut Vec<u8>) {
+        value.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl OptionValue for bool {
+    fn decode(code: u8, value: &[u8]) -> Result<bool, DhcpError> {
+        match value {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            [_] => Err(DhcpError::InvalidValue {
+                code,
+                reason: "flag neither 0 nor 1",
+            }),
+            _ => Err(DhcpError::InvalidLength {
+                code,
+                len: value.len(),
+            }),
+        }
+    }
+
+    fn encode(&self, value: &mut Vec<u8>) {
+        value.push(*self as u8);
+    }
+}
+
+impl OptionValue for Ipv4Addr {
+    fn decode(code: u8, value: &[u8]) -> Result<Ipv4Addr, DhcpError> {
+        <[u8; 4]>::decode(code, value).map(Ipv4Addr::from)
+    }
+
+    fn encode(&self, value: &mut Vec<u8>) {
+        value.extend_from_slice(&self.octets());
+    }
+}
+
+impl OptionValue for Vec<Ipv4Addr> {
+    fn decode(code: u8, value: &[u8]) -> Result<Vec<Ipv4Addr>, DhcpError> {
+        if !value.len().is_multiple_of(4) {
+            return Err(DhcpError::InvalidLength {
+                code,
+                len: value.len(),
+            });
+        }
+        Ok(value
+            .chunks_exact(4)
+            .map(|octets| Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]))
+            .collect())
+    }
+
+    fn encode(&self, value: &mut Vec<u8>) {
+        for address in self {
+            address.encode(value);
+        }
+    }
+}
+
+impl<const N: usize> OptionValue for [u8; N] {
+    fn decode(code: u8, value: &[u8]) -> Result<[u8; N], DhcpError> {
+        value.try_into().map_err(|_| DhcpError::InvalidLength {
+            code,
+            len: value.len(),
+        })
+    }
+
+    fn encode(&self, value: &mut Vec<u8>) {
+        value.extend_from_slice(self);
+    }
+}
+
+macro_rules! integer_value {
+    ($($integer:ty),*) => {
+        $(
+            impl OptionValue for $integer {
+                fn decode(code: u8, value: &[u8]) -> Result<$integer, DhcpError> {
+                    OptionValue::decode(code, value).map(<$integer>::from_be_bytes)
+                }
+
+                fn encode(&self, value: &mut Vec<u8>) {
+                    value.extend_from_slice(&self.to_be_bytes());
+                }
+            }
+        )*
+    };
+}
+
+integer_value!(u8, u16, u32, u64, i32);
+
+/// Define a type for each of the given options, holding its value and
+/// implementing `CustomOption`, along with conversions to `DhcpOption`.
+///
+/// Values are any `OptionValue`: addresses and lists of addresses,
+/// strings, flags, integers sent in network byte order, fixed-size arrays
+/// and raw bytes.
+#[macro_export]
+macro_rules! dhcp_options {
+    ($($(#[$attr:meta])* $code:literal => $name:ident($value:ty)),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, PartialEq)]
+            pub struct $name(pub $value);
+
+            impl $crate::custom::CustomOption for $name {
+                const CODE: u8 = $code;
+
+                fn decode(value: &[u8]) -> Result<$name, $crate::error::DhcpError> {
+                    <$value as $crate::custom::OptionValue>::decode($code, value).map($name)
+                }
+
+                fn encode(&self, value: &mut Vec<u8>) {
+                    $crate::custom::OptionValue::encode(&self.0, value)
+                }
+            }
+
+            impl From<$name> for $crate::option::DhcpOption {
+                fn from(option: $name) -> $crate::option::DhcpOption {
+                    $crate::custom::CustomOption::to_option(&option)
+                }
+            }
+        )*
+    };
+}
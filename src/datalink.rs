@@ -0,0 +1,159 @@
+//! Sending and receiving DHCP messages as raw Ethernet frames, through the
+//! datalink channels of pnet.
+//!
+//! Clients without an address yet, and relays answering them, cannot use
+//! a UDP socket: the frames carry their own IPv4 and UDP headers instead.
+//! pnet opens the channel with the backend of the platform, such as
+//! AF_PACKET on Linux or BPF on BSD and macOS, and usually needs the
+//! privileges of raw sockets.
+
+use std::fmt;
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::time::Duration;
+
+use pnet_datalink::{Channel, DataLinkReceiver, DataLinkSender};
+
+use crate::capture::{
+    bootp_payload, client_mac, udp_frame, CLIENT_PORT, LINKTYPE_ETHERNET, SERVER_PORT,
+};
+use crate::conformance::Exchange;
+use crate::error::DhcpError;
+use crate::message::DhcpMessage;
+
+/// A BOOTP datagram received in a frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Datagram {
+    pub source: SocketAddrV4,
+    pub destination: SocketAddrV4,
+    pub message: DhcpMessage,
+}
+
+/// A transport of DHCP messages over the datalink channel of an interface.
+pub struct DatalinkTransport {
+    sender: Box<dyn DataLinkSender>,
+    receiver: Box<dyn DataLinkReceiver>,
+    mac: [u8; 6],
+}
+
+impl DatalinkTransport {
+    /// Open a channel on the interface `name`, waiting at most `timeout`
+    /// for each frame, or forever if `None`.
+    pub fn open(name: &str, timeout: Option<Duration>) -> Result<DatalinkTransport, DhcpError> {
+        let interface = pnet_datalink::interfaces()
+            .into_iter()
+            .find(|interface| interface.name == name)
+            .ok_or_else(|| DhcpError::Transport(format!("Unknown interface {}", name)))?;
+        let mac = interface
+            .mac
+            .ok_or_else(|| DhcpError::Transport(format!("Interface {} has no MAC", name)))?;
+        let config = pnet_datalink::Config {
+            read_timeout: timeout,
+            ..pnet_datalink::Config::default()
+        };
+        DatalinkTransport::new(pnet_datalink::channel(&interface, config)?, mac.octets())
+    }
+
+    /// Use a channel opened by the caller, sending frames from `mac`.
+    pub fn new(channel: Channel, mac: [u8; 6]) -> Result<DatalinkTransport, DhcpError> {
+        match channel {
+            Channel::Ethernet(sender, receiver) => Ok(DatalinkTransport {
+                sender,
+                receiver,
+                mac,
+            }),
+            _ => Err(DhcpError::Transport("Not an Ethernet channel".to_string())),
+        }
+    }
+
+    /// The link-layer address frames are sent from.
+    pub fn mac(&self) -> [u8; 6] {
+        self.mac
+    }
+
+    /// Send `message` from `source` to `destination`.
+    ///
+    /// Frames to the broadcast address go to every host of the link, and
+    /// replies to the client hardware address of the message. Requests to
+    /// other addresses are rejected: their link-layer address is unknown
+    /// without ARP, and a UDP socket serves them.
+    pub fn send(
+        &mut self,
+        source: SocketAddrV4,
+        destination: SocketAddrV4,
+        message: &DhcpMessage,
+    ) -> Result<(), DhcpError> {
+        let destination_mac = if destination.ip().is_broadcast() {
+            [0xFF; 6]
+        } else if message.op == 2 {
+            client_mac(message)
+        } else {
+            return Err(DhcpError::Transport(format!(
+                "No link-layer address for {}",
+                destination.ip()
+            )));
+        };
+        let frame = udp_frame(
+            self.mac,
+            destination_mac,
+            source,
+            destination,
+            &message.serialize()?,
+        );
+        match self.sender.send_to(&frame, None) {
+            Some(result) => Ok(result?),
+            None => Err(DhcpError::Transport(
+                "The frame does not fit the send buffer".to_string(),
+            )),
+        }
+    }
+
+    /// Wait for the next BOOTP datagram, skipping other traffic and frames
+    /// that do not hold a DHCP message. Fails with `DhcpError::Timeout`
+    /// when the timeout of the channel expires.
+    pub fn recv(&mut self) -> Result<Datagram, DhcpError> {
+        loop {
+            let frame = self.receiver.next()?;
+            let (source, destination, payload) = match bootp_payload(LINKTYPE_ETHERNET, frame) {
+                Some(datagram) => datagram,
+                None => continue,
+            };
+            if let Ok(message) = DhcpMessage::deserialize(payload) {
+                return Ok(Datagram {
+                    source,
+                    destination,
+                    message,
+                });
+            }
+        }
+    }
+}
+
+impl fmt::Debug for DatalinkTransport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DatalinkTransport")
+            .field("mac", &self.mac)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Exchanges of a client without an address: requests are broadcast from
+/// 0.0.0.0, and the first reply with the same transaction ID is taken.
+impl Exchange for DatalinkTransport {
+    fn exchange(&mut self, request: &DhcpMessage) -> Result<Option<DhcpMessage>, DhcpError> {
+        self.send(
+            SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, CLIENT_PORT),
+            SocketAddrV4::new(Ipv4Addr::BROADCAST, SERVER_PORT),
+            request,
+        )?;
+        loop {
+            match self.recv() {
+                Ok(datagram) if datagram.message.op == 2 && datagram.message.xid == request.xid => {
+                    return Ok(Some(datagram.message))
+                }
+                Ok(_) => continue,
+                Err(DhcpError::Timeout) => return Ok(None),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
@@ -0,0 +1,551 @@
+//! Dynamic DNS updates (RFC 2136) of the names of leased addresses.
+//!
+//! While a lease is active, the client name points at its address with an
+//! A record, and the address points back at the name with a PTR record.
+//! Both records are removed when the lease ends. Updates can be signed with
+//! TSIG (RFC 8945) using HMAC-MD5.
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+
+use crate::auth::hmac_md5;
+use crate::dns::{decode_name, encode_name};
+use crate::error::DhcpError;
+use crate::lease::{LeaseEvent, LeaseEventSink, LeaseState};
+
+/// The name of the HMAC-MD5 TSIG algorithm.
+pub const HMAC_MD5: &str = "hmac-md5.sig-alg.reg.int";
+
+const OPCODE_UPDATE: u16 = 5 << 11;
+const FLAG_RESPONSE: u16 = 0x8000;
+const TYPE_A: u16 = 1;
+const TYPE_SOA: u16 = 6;
+const TYPE_PTR: u16 = 12;
+const TYPE_TSIG: u16 = 250;
+const CLASS_IN: u16 = 1;
+const CLASS_NONE: u16 = 254;
+const CLASS_ANY: u16 = 255;
+// Seconds of clock difference allowed between the signer and the checker.
+const FUDGE: u16 = 300;
+const HEADER_LEN: usize = 12;
+const ADDITIONAL_COUNT_OFFSET: usize = 10;
+
+/// The data of a resource record.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordData {
+    A(Ipv4Addr),
+    Ptr(String),
+}
+
+impl RecordData {
+    fn rtype(&self) -> u16 {
+        match self {
+            RecordData::A(_) => TYPE_A,
+            RecordData::Ptr(_) => TYPE_PTR,
+        }
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, DhcpError> {
+        match self {
+            RecordData::A(ip) => Ok(ip.octets().to_vec()),
+            RecordData::Ptr(name) => {
+                let mut buffer = Vec::new();
+                encode_name(name, &mut buffer)?;
+                Ok(buffer)
+            }
+        }
+    }
+}
+
+/// A change to the records of a zone (RFC 2136 section 2.5).
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordChange {
+    Add {
+        name: String,
+        ttl: u32,
+        data: RecordData,
+    },
+    // Delete every record of a type.
+    DeleteAll {
+        name: String,
+        rtype: u16,
+    },
+    Delete {
+        name: String,
+        data: RecordData,
+    },
+}
+
+impl RecordChange {
+    fn serialize(&self, buffer: &mut Vec<u8>) -> Result<(), DhcpError> {
+        let (name, rtype, class, ttl, data) = match self {
+            RecordChange::Add { name, ttl, data } => {
+                (name, data.rtype(), CLASS_IN, *ttl, data.serialize()?)
+            }
+            RecordChange::DeleteAll { name, rtype } => (name, *rtype, CLASS_ANY, 0, Vec::new()),
+            RecordChange::Delete { name, data } => {
+                (name, data.rtype(), CLASS_NONE, 0, data.serialize()?)
+            }
+        };
+        encode_name(name, buffer)?;
+        buffer.extend_from_slice(&rtype.to_be_bytes());
+        buffer.extend_from_slice(&class.to_be_bytes());
+        buffer.extend_from_slice(&ttl.to_be_bytes());
+        buffer.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        buffer.extend_from_slice(&data);
+        Ok(())
+    }
+}
+
+/// Build an update message applying `changes` to `zone`, without
+/// prerequisites.
+///
+/// Fails if a name is too long for DNS.
+pub fn update_message(id: u16, zone: &str, changes: &[RecordChange]) -> Result<Vec<u8>, DhcpError> {
+    let mut buffer = Vec::with_capacity(512);
+    buffer.extend_from_slice(&id.to_be_bytes());
+    buffer.extend_from_slice(&OPCODE_UPDATE.to_be_bytes());
+    // One zone, no prerequisites, the updates and no additional records.
+    buffer.extend_from_slice(&1u16.to_be_bytes());
+    buffer.extend_from_slice(&0u16.to_be_bytes());
+    buffer.extend_from_slice(&(changes.len() as u16).to_be_bytes());
+    buffer.extend_from_slice(&0u16.to_be_bytes());
+
+    encode_name(zone, &mut buffer)?;
+    buffer.extend_from_slice(&TYPE_SOA.to_be_bytes());
+    buffer.extend_from_slice(&CLASS_IN.to_be_bytes());
+    for change in changes {
+        change.serialize(&mut buffer)?;
+    }
+    Ok(buffer)
+}
+
+/// Check the response to the update message `id`, returning an error for
+/// any response code other than NOERROR.
+pub fn check_response(response: &[u8], id: u16) -> Result<(), DhcpError> {
+    if response.len() < HEADER_LEN {
+        return Err(DhcpError::ParsingError(
+            "Could not parse DNS response header".into(),
+        ));
+    }
+    let flags = u16::from_be_bytes([response[2], response[3]]);
+    if u16::from_be_bytes([response[0], response[1]]) != id || flags & FLAG_RESPONSE == 0 {
+        return Err(DhcpError::Transport(
+            "DNS response does not match the update".to_string(),
+        ));
+    }
+    match flags & 0x000F {
+        0 => Ok(()),
+        code => Err(DhcpError::Transport(format!(
+            "DNS update failed with response code {}",
+            code
+        ))),
+    }
+}
+
+/// A TSIG key shared with the DNS server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TsigKey {
+    pub name: String,
+    pub secret: Vec<u8>,
+}
+
+impl TsigKey {
+    pub fn new(name: &str, secret: &[u8]) -> TsigKey {
+        TsigKey {
+            name: name.to_string(),
+            secret: secret.to_vec(),
+        }
+    }
+
+    /// Sign `message` at `time` (seconds since the Unix epoch), appending a
+    /// TSIG record, and return the MAC.
+    ///
+    /// Responses are signed with the MAC of the request they answer. Fails,
+    /// leaving `message` unchanged, if the key name is too long for DNS.
+    pub fn sign(
+        &self,
+        message: &mut Vec<u8>,
+        request_mac: Option<&[u8]>,
+        time: u32,
+    ) -> Result<[u8; 16], DhcpError> {
+        let mac = hmac_md5(
+            &self.secret,
+            &self.digest_input(message, request_mac, time, &[])?,
+        );
+
+        let id = [message[0], message[1]];
+        encode_name(&self.name.to_lowercase(), message)?;
+        message.extend_from_slice(&TYPE_TSIG.to_be_bytes());
+        message.extend_from_slice(&CLASS_ANY.to_be_bytes());
+        message.extend_from_slice(&0u32.to_be_bytes());
+        let mut data = Vec::new();
+        encode_name(HMAC_MD5, &mut data)?;
+        data.extend_from_slice(&time_signed(time));
+        data.extend_from_slice(&FUDGE.to_be_bytes());
+        data.extend_from_slice(&(mac.len() as u16).to_be_bytes());
+        data.extend_from_slice(&mac);
+        data.extend_from_slice(&id);
+        // No error and no other data.
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        message.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        message.extend_from_slice(&data);
+
+        let count = u16::from_be_bytes([
+            message[ADDITIONAL_COUNT_OFFSET],
+            message[ADDITIONAL_COUNT_OFFSET + 1],
+        ]) + 1;
+        message[ADDITIONAL_COUNT_OFFSET..ADDITIONAL_COUNT_OFFSET + 2]
+            .copy_from_slice(&count.to_be_bytes());
+        Ok(mac)
+    }
+
+    /// Verify the TSIG record ending `message`, signed within the allowed
+    /// clock difference of `time`.
+    pub fn verify(
+        &self,
+        message: &[u8],
+        request_mac: Option<&[u8]>,
+        time: u32,
+    ) -> Result<(), DhcpError> {
+        let error = |reason: &str| DhcpError::AuthenticationError(reason.to_string());
+        let malformed = || DhcpError::ParsingError("Could not parse DNS message".into());
+
+        if message.len() < HEADER_LEN {
+            return Err(error("Missing TSIG record"));
+        }
+        let count = |offset: usize| u16::from_be_bytes([message[offset], message[offset + 1]]);
+        let records = count(6) as usize + count(8) as usize + count(10) as usize;
+        if count(ADDITIONAL_COUNT_OFFSET) == 0 {
+            return Err(error("Missing TSIG record"));
+        }
+
+        // Skip to the last record.
+        let mut offset = HEADER_LEN;
+        for _ in 0..count(4) {
+            offset = decode_name(message, offset).map_err(|_| malformed())?.1 + 4;
+        }
+        for _ in 0..records - 1 {
+            offset = decode_name(message, offset).map_err(|_| malformed())?.1 + 8;
+            let len = read_u16(message, offset)? as usize;
+            offset += 2 + len;
+        }
+        let start = offset;
+
+        let (name, offset) = decode_name(message, offset).map_err(|_| malformed())?;
+        if read_u16(message, offset)? != TYPE_TSIG {
+            return Err(error("Missing TSIG record"));
+        }
+        if !name.eq_ignore_ascii_case(&self.name) {
+            return Err(error("Unknown TSIG key"));
+        }
+        let rdata = offset + 10;
+        let (algorithm, offset) = decode_name(message, rdata).map_err(|_| malformed())?;
+        if !algorithm.eq_ignore_ascii_case(HMAC_MD5) {
+            return Err(error("Unsupported TSIG algorithm"));
+        }
+        if message.len() < offset + 10 {
+            return Err(malformed());
+        }
+        let signed = u64::from_be_bytes([
+            0,
+            0,
+            message[offset],
+            message[offset + 1],
+            message[offset + 2],
+            message[offset + 3],
+            message[offset + 4],
+            message[offset + 5],
+        ]);
+        let fudge = read_u16(message, offset + 6)?;
+        let mac_len = read_u16(message, offset + 8)? as usize;
+        let mac = message
+            .get(offset + 10..offset + 10 + mac_len)
+            .ok_or_else(malformed)?;
+        let offset = offset + 10 + mac_len;
+        let status = read_u16(message, offset + 2)?;
+        let other_len = read_u16(message, offset + 4)? as usize;
+        let other = message
+            .get(offset + 6..offset + 6 + other_len)
+            .ok_or_else(malformed)?;
+
+        if status != 0 {
+            return Err(DhcpError::AuthenticationError(format!(
+                "TSIG error {}",
+                status
+            )));
+        }
+        if signed.abs_diff(time as u64) > fudge as u64 {
+            return Err(error("TSIG time outside the allowed window"));
+        }
+
+        let mut unsigned = message[..start].to_vec();
+        let count = count(ADDITIONAL_COUNT_OFFSET) - 1;
+        unsigned[ADDITIONAL_COUNT_OFFSET..ADDITIONAL_COUNT_OFFSET + 2]
+            .copy_from_slice(&count.to_be_bytes());
+        let expected = hmac_md5(
+            &self.secret,
+            &self.digest_input(&unsigned, request_mac, signed as u32, other)?,
+        );
+        if mac != expected {
+            return Err(error("Invalid TSIG MAC"));
+        }
+        Ok(())
+    }
+
+    // The data covered by the MAC (RFC 8945 section 4.3).
+    fn digest_input(
+        &self,
+        message: &[u8],
+        request_mac: Option<&[u8]>,
+        time: u32,
+        other: &[u8],
+    ) -> Result<Vec<u8>, DhcpError> {
+        let mut input = Vec::with_capacity(message.len() + 128);
+        if let Some(request_mac) = request_mac {
+            input.extend_from_slice(&(request_mac.len() as u16).to_be_bytes());
+            input.extend_from_slice(request_mac);
+        }
+        input.extend_from_slice(message);
+        encode_name(&self.name.to_lowercase(), &mut input)?;
+        input.extend_from_slice(&CLASS_ANY.to_be_bytes());
+        input.extend_from_slice(&0u32.to_be_bytes());
+        encode_name(HMAC_MD5, &mut input)?;
+        input.extend_from_slice(&time_signed(time));
+        input.extend_from_slice(&FUDGE.to_be_bytes());
+        input.extend_from_slice(&0u16.to_be_bytes());
+        input.extend_from_slice(&(other.len() as u16).to_be_bytes());
+        input.extend_from_slice(other);
+        Ok(input)
+    }
+}
+
+fn time_signed(time: u32) -> [u8; 6] {
+    let bytes = (time as u64).to_be_bytes();
+    [bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, DhcpError> {
+    match data.get(offset..offset + 2) {
+        Some(bytes) => Ok(u16::from_be_bytes([bytes[0], bytes[1]])),
+        None => Err(DhcpError::ParsingError(
+            "Could not parse DNS message".into(),
+        )),
+    }
+}
+
+/// The name of the PTR record of `ip`, such as "100.1.168.192.in-addr.arpa".
+pub fn reverse_name(ip: Ipv4Addr) -> String {
+    let [a, b, c, d] = ip.octets();
+    format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+}
+
+/// Something keeping the DNS records of leased addresses up to date.
+pub trait DnsUpdater {
+    /// Point the host `name` at `ip` and `ip` back at the name, for `ttl`
+    /// seconds. `time` is the current time in seconds since the Unix epoch.
+    fn add(&mut self, name: &str, ip: Ipv4Addr, ttl: u32, time: u32) -> Result<(), DhcpError>;
+
+    /// Remove the records added for `name` and `ip`.
+    fn remove(&mut self, name: &str, ip: Ipv4Addr, time: u32) -> Result<(), DhcpError>;
+}
+
+/// A DNS updater sending RFC 2136 updates over UDP to the primary server
+/// of the forward and reverse zones.
+///
+/// The socket is set up by the caller, with a read timeout bounding the
+/// wait for each response.
+#[derive(Debug)]
+pub struct Rfc2136Updater {
+    socket: UdpSocket,
+    server: SocketAddr,
+    zone: String,
+    reverse_zone: String,
+    key: Option<TsigKey>,
+    id: u16,
+}
+
+impl Rfc2136Updater {
+    /// Update the host names of `zone`, such as "example.com", and the
+    /// addresses of `reverse_zone`, such as "1.168.192.in-addr.arpa".
+    pub fn new(
+        socket: UdpSocket,
+        server: SocketAddr,
+        zone: &str,
+        reverse_zone: &str,
+    ) -> Rfc2136Updater {
+        Rfc2136Updater {
+            socket,
+            server,
+            zone: zone.to_string(),
+            reverse_zone: reverse_zone.to_string(),
+            key: None,
+            id: 0,
+        }
+    }
+
+    /// Sign the updates with `key`, and require signed responses.
+    pub fn with_key(mut self, key: TsigKey) -> Rfc2136Updater {
+        self.key = Some(key);
+        self
+    }
+
+    fn send(&mut self, zone: &str, changes: &[RecordChange], time: u32) -> Result<(), DhcpError> {
+        self.id = self.id.wrapping_add(1);
+        let mut message = update_message(self.id, zone, changes)?;
+        let mac = match &self.key {
+            Some(key) => Some(key.sign(&mut message, None, time)?),
+            None => None,
+        };
+        self.socket.send_to(&message, self.server)?;
+
+        let mut buffer = [0; 1500];
+        let response = loop {
+            let (len, source) = self.socket.recv_from(&mut buffer)?;
+            // Skip stray traffic and responses to earlier updates.
+            if source == self.server && len >= 2 && buffer[..2] == self.id.to_be_bytes() {
+                break &buffer[..len];
+            }
+        };
+
+        check_response(response, self.id)?;
+        match (&self.key, mac) {
+            (Some(key), Some(mac)) => key.verify(response, Some(&mac), time),
+            _ => Ok(()),
+        }
+    }
+
+    fn fqdn(&self, name: &str) -> String {
+        format!("{}.{}", name, self.zone)
+    }
+}
+
+impl DnsUpdater for Rfc2136Updater {
+    fn add(&mut self, name: &str, ip: Ipv4Addr, ttl: u32, time: u32) -> Result<(), DhcpError> {
+        let fqdn = self.fqdn(name);
+        let zone = self.zone.clone();
+        self.send(
+            &zone,
+            &[
+                RecordChange::DeleteAll {
+                    name: fqdn.clone(),
+                    rtype: TYPE_A,
+                },
+                RecordChange::Add {
+                    name: fqdn.clone(),
+                    ttl,
+                    data: RecordData::A(ip),
+                },
+            ],
+            time,
+        )?;
+
+        let reverse_zone = self.reverse_zone.clone();
+        self.send(
+            &reverse_zone,
+            &[
+                RecordChange::DeleteAll {
+                    name: reverse_name(ip),
+                    rtype: TYPE_PTR,
+                },
+                RecordChange::Add {
+                    name: reverse_name(ip),
+                    ttl,
+                    data: RecordData::Ptr(fqdn),
+                },
+            ],
+            time,
+        )
+    }
+
+    fn remove(&mut self, name: &str, ip: Ipv4Addr, time: u32) -> Result<(), DhcpError> {
+        let fqdn = self.fqdn(name);
+        let zone = self.zone.clone();
+        self.send(
+            &zone,
+            &[RecordChange::Delete {
+                name: fqdn.clone(),
+                data: RecordData::A(ip),
+            }],
+            time,
+        )?;
+
+        let reverse_zone = self.reverse_zone.clone();
+        self.send(
+            &reverse_zone,
+            &[RecordChange::Delete {
+                name: reverse_name(ip),
+                data: RecordData::Ptr(fqdn),
+            }],
+            time,
+        )
+    }
+}
+
+/// A lease event sink keeping DNS records up to date: records are added
+/// when a lease becomes active, and removed when it ends.
+///
+/// Clients are named after the host names registered with
+/// `set_host_name`, or else after their hardware address, such as
+/// "dhcp-020000000001". Failed updates are kept until taken with
+/// `take_errors`.
+#[derive(Debug)]
+pub struct DdnsSink<U: DnsUpdater> {
+    updater: U,
+    names: HashMap<[u8; 6], String>,
+    errors: Vec<DhcpError>,
+}
+
+impl<U: DnsUpdater> DdnsSink<U> {
+    pub fn new(updater: U) -> DdnsSink<U> {
+        DdnsSink {
+            updater,
+            names: HashMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn updater(&self) -> &U {
+        &self.updater
+    }
+
+    pub fn set_host_name(&mut self, mac: [u8; 6], name: &str) {
+        self.names.insert(mac, name.to_string());
+    }
+
+    pub fn take_errors(&mut self) -> Vec<DhcpError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    fn host_name(&self, mac: &[u8; 6]) -> String {
+        match self.names.get(mac) {
+            Some(name) => name.clone(),
+            None => format!(
+                "dhcp-{}",
+                mac.iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect::<String>()
+            ),
+        }
+    }
+}
+
+impl<U: DnsUpdater> LeaseEventSink for DdnsSink<U> {
+    fn lease_event(&mut self, event: &LeaseEvent) {
+        let name = self.host_name(&event.mac);
+        let result = match event.state {
+            LeaseState::Active => {
+                self.updater
+                    .add(&name, event.ip, event.lease_time, event.timestamp)
+            }
+            LeaseState::Expired
+            | LeaseState::Released
+            | LeaseState::Abandoned
+            | LeaseState::Reset => self.updater.remove(&name, event.ip, event.timestamp),
+            _ => Ok(()),
+        };
+        if let Err(error) = result {
+            self.errors.push(error);
+        }
+    }
+}
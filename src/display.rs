@@ -0,0 +1,258 @@
+// Human-readable formatting of messages and options, in the spirit of
+// `tcpdump -vvv`: one header field per line, then one option per line with
+// its name, code, length and decoded value.
+
+use std::fmt;
+
+use crate::message::{DhcpMessage, MessageType, OpCode};
+use crate::option::{self, DhcpOption, NetBiosOverTcpIpNodeType, OptionOverload};
+
+impl fmt::Display for MessageType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            MessageType::Discover => "DHCPDISCOVER",
+            MessageType::Offer => "DHCPOFFER",
+            MessageType::Request => "DHCPREQUEST",
+            MessageType::Decline => "DHCPDECLINE",
+            MessageType::Ack => "DHCPACK",
+            MessageType::Nak => "DHCPNAK",
+            MessageType::Release => "DHCPRELEASE",
+            MessageType::Inform => "DHCPINFORM",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for DhcpMessage {
+    /// Display the header fields, each on its own line, then the options:
+    ///
+    /// ```text
+    /// BOOTREQUEST (1), htype 1, hlen 6, hops 0, xid 0x3903f326, secs 0, flags 0x8000 (broadcast)
+    ///   ciaddr 0.0.0.0
+    ///   ...
+    ///   options:
+    ///     DHCP Message Type (53), length 1: DHCPDISCOVER
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match OpCode::try_from(self.op) {
+            Ok(OpCode::BootRequest) => write!(f, "BOOTREQUEST")?,
+            Ok(OpCode::BootReply) => write!(f, "BOOTREPLY")?,
+            Err(_) => write!(f, "Unknown op")?,
+        }
+        write!(
+            f,
+            " ({}), htype {}, hlen {}, hops {}, xid 0x{:08x}, secs {}, flags 0x{:04x}",
+            self.op, self.htype, self.hlen, self.hops, self.xid, self.secs, self.flags
+        )?;
+        if self.flags.broadcast() {
+            write!(f, " (broadcast)")?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "  ciaddr {}", self.ciaddr)?;
+        writeln!(f, "  yiaddr {}", self.yiaddr)?;
+        writeln!(f, "  siaddr {}", self.siaddr)?;
+        writeln!(f, "  giaddr {}", self.giaddr)?;
+        write!(f, "  chaddr ")?;
+        match self.hardware_address() {
+            Ok(Some(address)) => writeln!(f, "{}", address)?,
+            _ => {
+                hex(
+                    f,
+                    &self.chaddr[..(self.hlen as usize).min(self.chaddr.len())],
+                )?;
+                writeln!(f)?;
+            }
+        }
+        if let Some(sname) = null_terminated(&self.sname) {
+            writeln!(f, "  sname {:?}", sname)?;
+        }
+        if let Some(file) = null_terminated(&self.file) {
+            writeln!(f, "  file {:?}", file)?;
+        }
+
+        write!(f, "  options:")?;
+        for option in &self.options {
+            write!(f, "\n    {}", option)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DhcpOption {
+    /// Display the name and code of the option, the length and the decoded
+    /// value, such as "Subnet Mask (1), length 4: 255.255.255.0".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let code = self.raw_code();
+        let name = option::info(code).map_or("Unknown", |info| info.name);
+        write!(f, "{} ({})", name, code)?;
+        if matches!(self, DhcpOption::Pad | DhcpOption::End) {
+            return Ok(());
+        }
+        write!(f, ", length {}: ", self.value_len())?;
+
+        match self {
+            DhcpOption::Pad | DhcpOption::End => Ok(()),
+            DhcpOption::SubnetMask(address)
+            | DhcpOption::SwapServer(address)
+            | DhcpOption::BroadcastAddress(address)
+            | DhcpOption::RouterSolicitationAddress(address)
+            | DhcpOption::RequestedIpAddress(address)
+            | DhcpOption::ServerIdentifier(address) => write!(f, "{}", address),
+            DhcpOption::TimeOffset(offset) => write!(f, "{}s", *offset as i32),
+            DhcpOption::PathMtuAgingTimeout(seconds)
+            | DhcpOption::ArpCacheTimeout(seconds)
+            | DhcpOption::TcpKeepaliveInterval(seconds)
+            | DhcpOption::IpAddressLeaseTime(seconds) => write!(f, "{}s", seconds),
+            DhcpOption::BootFileSize(value)
+            | DhcpOption::MaximumDatagramReassemblySize(value)
+            | DhcpOption::InterfaceMtu(value) => write!(f, "{}", value),
+            DhcpOption::DefaultIpTimeToLive(value) | DhcpOption::TcpDefaultTtl(value) => {
+                write!(f, "{}", value)
+            }
+            DhcpOption::IpForwarding(flag)
+            | DhcpOption::NonLocalSourceRouting(flag)
+            | DhcpOption::AllSubnetsAreLocal(flag)
+            | DhcpOption::PerformMaskDiscovery(flag)
+            | DhcpOption::MaskSupplier(flag)
+            | DhcpOption::PerformRouterDiscovery(flag)
+            | DhcpOption::TrailerEncapsulation(flag)
+            | DhcpOption::EthernetEncapsulation(flag)
+            | DhcpOption::TcpKeepaliveGarbage(flag) => {
+                write!(f, "{}", if *flag { "yes" } else { "no" })
+            }
+            DhcpOption::Router(addresses)
+            | DhcpOption::TimeServer(addresses)
+            | DhcpOption::NameServer(addresses)
+            | DhcpOption::DomainNameServer(addresses)
+            | DhcpOption::LogServer(addresses)
+            | DhcpOption::CookieServer(addresses)
+            | DhcpOption::LprServer(addresses)
+            | DhcpOption::ImpressServer(addresses)
+            | DhcpOption::ResourceLocationServer(addresses)
+            | DhcpOption::NetworkInformationServers(addresses)
+            | DhcpOption::NetworkTimeProtocolServers(addresses)
+            | DhcpOption::NetBiosOverTcpIpNameServer(addresses)
+            | DhcpOption::NetBiosOverTcpIpDatagramDistributionServer(addresses)
+            | DhcpOption::XWindowSystemFontServer(addresses)
+            | DhcpOption::XWindowSystemDisplayManager(addresses)
+            | DhcpOption::NetworkInformationServicePlusServers(addresses)
+            | DhcpOption::MobileIpHomeAgent(addresses)
+            | DhcpOption::SimpleMailTransportProtocolServer(addresses)
+            | DhcpOption::PostOfficeProtocolServer(addresses)
+            | DhcpOption::NetworkNewsTransportProtocolServer(addresses)
+            | DhcpOption::DefaultWorldWideWebServer(addresses)
+            | DhcpOption::DefaultFingerServer(addresses)
+            | DhcpOption::DefaultInternetRelayChatServer(addresses)
+            | DhcpOption::StreetTalkServer(addresses)
+            | DhcpOption::StreetTalkDirectoryAssistanceServer(addresses) => {
+                list(f, addresses.iter())
+            }
+            DhcpOption::PolicyFilter(pairs) => list(
+                f,
+                pairs
+                    .iter()
+                    .map(|(address, mask)| format!("{}/{}", address, mask)),
+            ),
+            DhcpOption::StaticRoute(pairs) => list(
+                f,
+                pairs
+                    .iter()
+                    .map(|(destination, router)| format!("{} via {}", destination, router)),
+            ),
+            DhcpOption::PathMtuPlateauTable(sizes) => list(f, sizes.iter()),
+            DhcpOption::HostName(value)
+            | DhcpOption::MeritDumpFile(value)
+            | DhcpOption::DomainName(value)
+            | DhcpOption::RootPath(value)
+            | DhcpOption::ExtensionsPath(value)
+            | DhcpOption::NetworkInformationServiceDomain(value)
+            | DhcpOption::NetworkInformationServicePlusDomain(value) => write!(f, "{:?}", value),
+            DhcpOption::VendorSpecificInformation(value)
+            | DhcpOption::NetBiosOverTcpIpScope(value)
+            | DhcpOption::ClientIdentifier(value)
+            | DhcpOption::ForcerenewNonceCapable(value) => hex(f, value),
+            DhcpOption::NetBiosOverTcpIpNodeType(node_type) => {
+                let name = match node_type {
+                    NetBiosOverTcpIpNodeType::BNode => "B-node",
+                    NetBiosOverTcpIpNodeType::PNode => "P-node",
+                    NetBiosOverTcpIpNodeType::MNode => "M-node",
+                    NetBiosOverTcpIpNodeType::HNode => "H-node",
+                };
+                write!(f, "{}", name)
+            }
+            DhcpOption::OptionOverload(overload) => {
+                let fields = match overload {
+                    OptionOverload::File => "file",
+                    OptionOverload::Sname => "sname",
+                    OptionOverload::Both => "file and sname",
+                };
+                write!(f, "{}", fields)
+            }
+            DhcpOption::DhcpMessageType(message_type) => write!(f, "{}", message_type),
+            DhcpOption::DomainSearch(names) => list(f, names.iter()),
+            DhcpOption::Authentication(authentication) => {
+                write!(
+                    f,
+                    "protocol {}, algorithm {}, rdm {}, replay detection 0x{:016x}, information ",
+                    authentication.protocol,
+                    authentication.algorithm,
+                    authentication.rdm,
+                    authentication.replay_detection
+                )?;
+                hex(f, &authentication.information)
+            }
+            DhcpOption::Unknown(code, value) => unknown(f, *code, value),
+        }
+    }
+}
+
+// Decode the value of the options parsed as `DhcpOption::Unknown` whose
+// format is known, and dump the others in hex.
+fn unknown(f: &mut fmt::Formatter, code: u8, value: &[u8]) -> fmt::Result {
+    match (code, value) {
+        (55, codes) => list(
+            f,
+            codes.iter().map(|code| match option::info(*code) {
+                Some(info) => format!("{} ({})", info.name, code),
+                None => code.to_string(),
+            }),
+        ),
+        (56 | 60, text) => write!(f, "{:?}", String::from_utf8_lossy(text)),
+        (57, [high, low]) => write!(f, "{}", u16::from_be_bytes([*high, *low])),
+        (58 | 59, [a, b, c, d]) => write!(f, "{}s", u32::from_be_bytes([*a, *b, *c, *d])),
+        _ => hex(f, value),
+    }
+}
+
+fn list<T: fmt::Display>(f: &mut fmt::Formatter, items: impl Iterator<Item = T>) -> fmt::Result {
+    for (index, item) in items.enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+fn hex(f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+    for (index, byte) in bytes.iter().enumerate() {
+        if index > 0 {
+            write!(f, ":")?;
+        }
+        write!(f, "{:02x}", byte)?;
+    }
+    Ok(())
+}
+
+// The text of a null-terminated field, or None if it is empty.
+fn null_terminated(field: &[u8]) -> Option<String> {
+    let end = field
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(field.len());
+    match end {
+        0 => None,
+        end => Some(String::from_utf8_lossy(&field[..end]).into_owned()),
+    }
+}
@@ -0,0 +1,167 @@
+use crate::error::{DhcpError, SerializationError};
+
+// Maximum number of compression pointers followed while decoding a name,
+// protecting against pointer loops.
+const MAX_POINTERS: usize = 64;
+
+/// Encode a domain name in DNS wire format (RFC 1035 section 3.1),
+/// appending it to `buffer`.
+///
+/// Empty labels are skipped, so "example.com." and "example.com" encode
+/// identically. The name is terminated by the zero-length root label.
+///
+/// Fails, leaving `buffer` unchanged, with
+/// `SerializationError::ValueTooLong` if a label is longer than 63 bytes,
+/// giving its length, or if the encoded name is longer than 255 bytes,
+/// giving that length (RFC 1035 section 2.3.4). Names are not options, so
+/// the code of the error is 0.
+pub fn encode_name(name: &str, buffer: &mut Vec<u8>) -> Result<(), DhcpError> {
+    check_name(name)?;
+    write_name(name, buffer);
+    Ok(())
+}
+
+/// The length of a domain name once encoded by `encode_name`.
+pub fn encoded_name_len(name: &str) -> usize {
+    name.split('.')
+        .filter(|label| !label.is_empty())
+        .map(|label| 1 + label.len())
+        .sum::<usize>()
+        + 1
+}
+
+/// Encode a list of domain names, without compression.
+pub fn encode_names(names: &[String]) -> Result<Vec<u8>, DhcpError> {
+    let mut buffer = Vec::new();
+    for name in names {
+        encode_name(name, &mut buffer)?;
+    }
+    Ok(buffer)
+}
+
+// Check that `encode_name` can encode `name`.
+pub(crate) fn check_name(name: &str) -> Result<(), DhcpError> {
+    let too_long = |len| {
+        Err(DhcpError::SerializationError(
+            SerializationError::ValueTooLong { code: 0, len },
+        ))
+    };
+    if let Some(label) = name.split('.').find(|label| label.len() > 63) {
+        return too_long(label.len());
+    }
+    match encoded_name_len(name) {
+        len if len > 255 => too_long(len),
+        _ => Ok(()),
+    }
+}
+
+// Encode a domain name as `encode_name` does, without checking it.
+pub(crate) fn write_name(name: &str, buffer: &mut Vec<u8>) {
+    for label in name.split('.').filter(|label| !label.is_empty()) {
+        buffer.push(label.len() as u8);
+        buffer.extend_from_slice(label.as_bytes());
+    }
+    buffer.push(0);
+}
+
+/// Decode the domain name starting at `offset` in `data`, following
+/// compression pointers relative to the start of `data`.
+///
+/// Returns the name and the offset right after it.
+pub fn decode_name(data: &[u8], offset: usize) -> Result<(String, usize), DhcpError> {
+    let mut labels = Vec::new();
+    let end = walk_name(data, offset, |label| labels.push(label.to_string()))?;
+    Ok((labels.join("."), end))
+}
+
+// Walk the labels of the domain name starting at `offset` in `data`,
+// returning the offset right after it.
+fn walk_name(
+    data: &[u8],
+    offset: usize,
+    mut on_label: impl FnMut(&str),
+) -> Result<usize, DhcpError> {
+    let mut index = offset;
+    let mut end = None;
+    let mut pointers = 0;
+
+    loop {
+        let len = match data.get(index) {
+            Some(len) => *len as usize,
+            None => {
+                return Err(DhcpError::ParsingError(
+                    "Could not parse domain name".into(),
+                ))
+            }
+        };
+
+        if len == 0 {
+            index += 1;
+            break;
+        }
+
+        // Compression pointer.
+        if len & 0xC0 == 0xC0 {
+            let low = match data.get(index + 1) {
+                Some(low) => *low as usize,
+                None => {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse domain name pointer".into(),
+                    ))
+                }
+            };
+            pointers += 1;
+            if pointers > MAX_POINTERS {
+                return Err(DhcpError::ParsingError(
+                    "Too many domain name pointers".into(),
+                ));
+            }
+            if end.is_none() {
+                end = Some(index + 2);
+            }
+            index = ((len & 0x3F) << 8) | low;
+            continue;
+        }
+
+        if len > 63 || index + 1 + len > data.len() {
+            return Err(DhcpError::ParsingError(
+                "Could not parse domain name label".into(),
+            ));
+        }
+
+        match std::str::from_utf8(&data[index + 1..index + 1 + len]) {
+            Ok(label) => on_label(label),
+            Err(_) => {
+                return Err(DhcpError::ParsingError(
+                    "Could not parse domain name label".into(),
+                ))
+            }
+        }
+        index += 1 + len;
+    }
+
+    Ok(end.unwrap_or(index))
+}
+
+/// Decode a list of domain names filling `data`, as found in the domain
+/// search options.
+pub fn decode_names(data: &[u8]) -> Result<Vec<String>, DhcpError> {
+    let mut names = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let (name, next) = decode_name(data, offset)?;
+        names.push(name);
+        offset = next;
+    }
+    Ok(names)
+}
+
+/// Check a list of domain names as `decode_names` does, without decoding
+/// them.
+pub fn check_names(data: &[u8]) -> Result<(), DhcpError> {
+    let mut offset = 0;
+    while offset < data.len() {
+        offset = walk_name(data, offset, |_| {})?;
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::option::DhcpOption;
+use crate::v6::option::{Dhcpv6Option, NtpServer};
+
+/// Network configuration shared by the DHCPv4 and DHCPv6 sides of a
+/// dual-stack server, so it can be written once and rendered into both
+/// option models.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DualStackConfig {
+    pub dns_servers_v4: Vec<Ipv4Addr>,
+    pub dns_servers_v6: Vec<Ipv6Addr>,
+    pub domain_search: Vec<String>,
+    pub ntp_servers_v4: Vec<Ipv4Addr>,
+    pub ntp_servers_v6: Vec<Ipv6Addr>,
+    pub ntp_server_names: Vec<String>,
+}
+
+impl DualStackConfig {
+    pub fn new() -> DualStackConfig {
+        DualStackConfig::default()
+    }
+
+    /// Render the configuration as DHCPv4 options.
+    ///
+    /// The first search domain is also sent as the domain name option for
+    /// clients that do not support the domain search option. DHCPv4 has no
+    /// way to carry NTP server names, so only addresses are sent.
+    pub fn v4_options(&self) -> Vec<DhcpOption> {
+        let mut options = Vec::new();
+        if !self.dns_servers_v4.is_empty() {
+            options.push(DhcpOption::DomainNameServer(
+                self.dns_servers_v4.iter().copied().collect(),
+            ));
+        }
+        if let Some(domain_name) = self.domain_search.first() {
+            options.push(DhcpOption::DomainName(domain_name.clone()));
+            options.push(DhcpOption::DomainSearch(self.domain_search.clone()));
+        }
+        if !self.ntp_servers_v4.is_empty() {
+            options.push(DhcpOption::NetworkTimeProtocolServers(
+                self.ntp_servers_v4.iter().copied().collect(),
+            ));
+        }
+        options
+    }
+
+    /// Render the configuration as DHCPv6 options.
+    pub fn v6_options(&self) -> Vec<Dhcpv6Option> {
+        let mut options = Vec::new();
+        if !self.dns_servers_v6.is_empty() {
+            options.push(Dhcpv6Option::DnsServers(self.dns_servers_v6.clone()));
+        }
+        if !self.domain_search.is_empty() {
+            options.push(Dhcpv6Option::DomainList(self.domain_search.clone()));
+        }
+        let ntp_servers = self
+            .ntp_servers_v6
+            .iter()
+            .map(|address| NtpServer::Address(*address))
+            .chain(
+                self.ntp_server_names
+                    .iter()
+                    .map(|name| NtpServer::Fqdn(name.clone())),
+            )
+            .map(|server| Dhcpv6Option::NtpServer(vec![server]))
+            .collect::<Vec<Dhcpv6Option>>();
+        options.extend(ntp_servers);
+        options
+    }
+
+    /// Merge the configuration found in DHCPv4 options into this one.
+    pub fn merge_v4_options(&mut self, options: &[DhcpOption]) {
+        for option in options {
+            match option {
+                DhcpOption::DomainNameServer(servers) => {
+                    extend_unique(&mut self.dns_servers_v4, servers)
+                }
+                DhcpOption::DomainName(domain_name) => {
+                    extend_unique(&mut self.domain_search, std::slice::from_ref(domain_name))
+                }
+                DhcpOption::DomainSearch(names) => extend_unique(&mut self.domain_search, names),
+                DhcpOption::NetworkTimeProtocolServers(servers) => {
+                    extend_unique(&mut self.ntp_servers_v4, servers)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Merge the configuration found in DHCPv6 options into this one.
+    pub fn merge_v6_options(&mut self, options: &[Dhcpv6Option]) {
+        for option in options {
+            match option {
+                Dhcpv6Option::DnsServers(servers) => {
+                    extend_unique(&mut self.dns_servers_v6, servers)
+                }
+                Dhcpv6Option::DomainList(names) => extend_unique(&mut self.domain_search, names),
+                Dhcpv6Option::NtpServer(servers) => {
+                    for server in servers {
+                        match server {
+                            NtpServer::Address(address) | NtpServer::Multicast(address) => {
+                                extend_unique(&mut self.ntp_servers_v6, &[*address])
+                            }
+                            NtpServer::Fqdn(name) => extend_unique(
+                                &mut self.ntp_server_names,
+                                std::slice::from_ref(name),
+                            ),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Build a configuration from DHCPv4 options.
+    pub fn from_v4_options(options: &[DhcpOption]) -> DualStackConfig {
+        let mut config = DualStackConfig::new();
+        config.merge_v4_options(options);
+        config
+    }
+
+    /// Build a configuration from DHCPv6 options.
+    pub fn from_v6_options(options: &[Dhcpv6Option]) -> DualStackConfig {
+        let mut config = DualStackConfig::new();
+        config.merge_v6_options(options);
+        config
+    }
+}
+
+fn extend_unique<T: Clone + PartialEq>(values: &mut Vec<T>, others: &[T]) {
+    for other in others {
+        if !values.contains(other) {
+            values.push(other.clone());
+        }
+    }
+}
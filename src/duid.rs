@@ -0,0 +1,258 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::DhcpError;
+
+/// A DHCP Unique Identifier, as defined by RFC 8415 section 11.
+///
+/// DUIDs identify DHCPv6 clients and servers, and are also carried in
+/// DHCPv4 client identifiers as described in RFC 4361.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Duid {
+    // DUID Based on Link-Layer Address Plus Time (DUID-LLT)
+    //
+    // The time value is the time that the DUID is generated, represented in
+    // seconds since midnight (UTC), January 1, 2000, modulo 2^32.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |         DUID-Type (1)         |    hardware type (16 bits)    |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                        time (32 bits)                         |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                                                               .
+    // .             link-layer address (variable length)              .
+    // .                                                               .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    LinkLayerTime {
+        hardware_type: u16,
+        time: u32,
+        link_layer_address: Vec<u8>,
+    },
+    // DUID Assigned by Vendor Based on Enterprise Number (DUID-EN)
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |         DUID-Type (2)         |       enterprise-number       |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |   enterprise-number (contd)   |                               |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               |
+    // .                           identifier                          .
+    // .                       (variable length)                       .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    Enterprise {
+        enterprise_number: u32,
+        identifier: Vec<u8>,
+    },
+    // DUID Based on Link-Layer Address (DUID-LL)
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |         DUID-Type (3)         |    hardware type (16 bits)    |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                                                               .
+    // .             link-layer address (variable length)              .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    LinkLayer {
+        hardware_type: u16,
+        link_layer_address: Vec<u8>,
+    },
+    // DUID Based on Universally Unique Identifier (DUID-UUID)
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |          DUID-Type (4)        |            UUID               |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               |
+    // |                   UUID (128 bits long)                        |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    Uuid([u8; 16]),
+    // A DUID of a type this crate does not know, kept as raw bytes.
+    Unknown {
+        duid_type: u16,
+        data: Vec<u8>,
+    },
+}
+
+impl Duid {
+    /// The maximum length of a DUID, not including the type code.
+    pub const MAX_LEN: usize = 128;
+
+    /// Retrieve the DUID type code.
+    pub fn duid_type(&self) -> u16 {
+        match self {
+            Duid::LinkLayerTime { .. } => 1,
+            Duid::Enterprise { .. } => 2,
+            Duid::LinkLayer { .. } => 3,
+            Duid::Uuid(_) => 4,
+            Duid::Unknown { duid_type, .. } => *duid_type,
+        }
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result = self.duid_type().to_be_bytes().to_vec();
+        match self {
+            Duid::LinkLayerTime {
+                hardware_type,
+                time,
+                link_layer_address,
+            } => {
+                result.extend_from_slice(&hardware_type.to_be_bytes());
+                result.extend_from_slice(&time.to_be_bytes());
+                result.extend_from_slice(link_layer_address);
+            }
+            Duid::Enterprise {
+                enterprise_number,
+                identifier,
+            } => {
+                result.extend_from_slice(&enterprise_number.to_be_bytes());
+                result.extend_from_slice(identifier);
+            }
+            Duid::LinkLayer {
+                hardware_type,
+                link_layer_address,
+            } => {
+                result.extend_from_slice(&hardware_type.to_be_bytes());
+                result.extend_from_slice(link_layer_address);
+            }
+            Duid::Uuid(uuid) => result.extend_from_slice(uuid),
+            Duid::Unknown { data, .. } => result.extend_from_slice(data),
+        }
+        result
+    }
+
+    pub fn deserialize(data: &[u8]) -> Result<Duid, DhcpError> {
+        if data.len() < 3 || data.len() > 2 + Duid::MAX_LEN {
+            return Err(DhcpError::ParsingError(
+                "Could not parse DUID length".into(),
+            ));
+        }
+
+        let duid_type = u16::from_be_bytes([data[0], data[1]]);
+        let data = &data[2..];
+
+        match duid_type {
+            1 => {
+                if data.len() < 6 {
+                    return Err(DhcpError::ParsingError("Could not parse DUID-LLT".into()));
+                }
+                Ok(Duid::LinkLayerTime {
+                    hardware_type: u16::from_be_bytes([data[0], data[1]]),
+                    time: u32::from_be_bytes([data[2], data[3], data[4], data[5]]),
+                    link_layer_address: data[6..].to_vec(),
+                })
+            }
+            2 => {
+                if data.len() < 4 {
+                    return Err(DhcpError::ParsingError("Could not parse DUID-EN".into()));
+                }
+                Ok(Duid::Enterprise {
+                    enterprise_number: u32::from_be_bytes([data[0], data[1], data[2], data[3]]),
+                    identifier: data[4..].to_vec(),
+                })
+            }
+            3 => {
+                if data.len() < 2 {
+                    return Err(DhcpError::ParsingError("Could not parse DUID-LL".into()));
+                }
+                Ok(Duid::LinkLayer {
+                    hardware_type: u16::from_be_bytes([data[0], data[1]]),
+                    link_layer_address: data[2..].to_vec(),
+                })
+            }
+            4 => {
+                if data.len() != 16 {
+                    return Err(DhcpError::ParsingError("Could not parse DUID-UUID".into()));
+                }
+                let mut uuid = [0; 16];
+                uuid.copy_from_slice(data);
+                Ok(Duid::Uuid(uuid))
+            }
+            _ => Ok(Duid::Unknown {
+                duid_type,
+                data: data.to_vec(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Duid {
+    /// Display a DUID as colon separated hexadecimal octets.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let octets = self
+            .serialize()
+            .iter()
+            .map(|octet| format!("{:02x}", octet))
+            .collect::<Vec<String>>();
+        write!(f, "{}", octets.join(":"))
+    }
+}
+
+impl FromStr for Duid {
+    type Err = DhcpError;
+
+    /// Parse a DUID from hexadecimal octets, optionally separated by colons.
+    fn from_str(s: &str) -> Result<Duid, DhcpError> {
+        let digits = s.replace([':', '-'], "");
+        if !digits.len().is_multiple_of(2) || !digits.is_ascii() {
+            return Err(DhcpError::ParsingError(
+                format!("Invalid DUID: {}", s).into(),
+            ));
+        }
+
+        let mut data = Vec::with_capacity(digits.len() / 2);
+        for index in (0..digits.len()).step_by(2) {
+            match u8::from_str_radix(&digits[index..index + 2], 16) {
+                Ok(octet) => data.push(octet),
+                Err(_) => {
+                    return Err(DhcpError::ParsingError(
+                        format!("Invalid DUID: {}", s).into(),
+                    ))
+                }
+            }
+        }
+
+        Duid::deserialize(&data)
+    }
+}
+
+/// A node-specific DHCPv4 client identifier, as defined by RFC 4361.
+///
+/// It lets a dual-stack host use the same DUID for DHCPv4 and DHCPv6, and
+/// is carried in the client identifier option (61) with type 255.
+//
+//  Code  Len  Type  IAID                DUID
+// +----+----+-----+----+----+----+----+----+----+---
+// | 61 | n  | 255 | i1 | i2 | i3 | i4 | d1 | d2 |...
+// +----+----+-----+----+----+----+----+----+----+---
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NodeSpecificClientId {
+    pub iaid: u32,
+    pub duid: Duid,
+}
+
+impl NodeSpecificClientId {
+    /// The client identifier type used by RFC 4361 identifiers.
+    pub const TYPE: u8 = 255;
+
+    pub fn new(iaid: u32, duid: Duid) -> NodeSpecificClientId {
+        NodeSpecificClientId { iaid, duid }
+    }
+
+    /// Serialize into the value of a client identifier option.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result = vec![NodeSpecificClientId::TYPE];
+        result.extend_from_slice(&self.iaid.to_be_bytes());
+        result.extend_from_slice(&self.duid.serialize());
+        result
+    }
+
+    /// Deserialize from the value of a client identifier option.
+    pub fn deserialize(data: &[u8]) -> Result<NodeSpecificClientId, DhcpError> {
+        if data.len() < 5 || data[0] != NodeSpecificClientId::TYPE {
+            return Err(DhcpError::ParsingError(
+                "Could not parse node-specific client identifier".into(),
+            ));
+        }
+
+        Ok(NodeSpecificClientId {
+            iaid: u32::from_be_bytes([data[1], data[2], data[3], data[4]]),
+            duid: Duid::deserialize(&data[5..])?,
+        })
+    }
+}
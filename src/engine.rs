@@ -0,0 +1,132 @@
+//! An async engine serving a `Server` on a tokio UDP socket.
+//!
+//! The engine waits for a datagram, takes the others already queued up to
+//! a batch, and handles them together with `Server::handle_batch` before
+//! sending the replies. Unlike the codec modules, it reads the system
+//! clock.
+
+use std::io;
+use std::net::SocketAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::net::UdpSocket;
+
+use crate::codec::DhcpDecoder;
+use crate::error::DhcpError;
+use crate::lease::LeaseEventSink;
+use crate::message::DhcpMessage;
+use crate::server::{reply_destination, Server};
+use crate::trace::event;
+
+/// A server answering the clients of a UDP socket.
+#[derive(Debug)]
+pub struct Engine {
+    server: Server,
+    socket: UdpSocket,
+    decoder: DhcpDecoder,
+    batch_len: usize,
+    // Problems with single datagrams not yet taken by the caller.
+    errors: Vec<(SocketAddr, DhcpError)>,
+}
+
+impl Engine {
+    pub const DEFAULT_BATCH_LEN: usize = 32;
+
+    /// Serve the clients of `socket`, set up by the caller, such as with
+    /// `net::bind`. Must be called within a tokio runtime.
+    pub fn new(server: Server, socket: std::net::UdpSocket) -> Result<Engine, DhcpError> {
+        socket.set_nonblocking(true)?;
+        Ok(Engine {
+            server,
+            socket: UdpSocket::from_std(socket)?,
+            // Serve clients sending a few broken options all the same.
+            decoder: DhcpDecoder::default().lenient(),
+            batch_len: Engine::DEFAULT_BATCH_LEN,
+            errors: Vec::new(),
+        })
+    }
+
+    /// Handle at most `batch_len` datagrams at once, at least one.
+    pub fn with_batch_len(mut self, batch_len: usize) -> Engine {
+        self.batch_len = batch_len.max(1);
+        self
+    }
+
+    pub fn server(&self) -> &Server {
+        &self.server
+    }
+
+    /// Take the datagrams that could not be decoded, the warnings of the
+    /// lenient decoder and the replies that could not be sent since the
+    /// last call, with the address of the peer.
+    pub fn take_errors(&mut self) -> Vec<(SocketAddr, DhcpError)> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Serve clients until the socket fails.
+    pub async fn run<S: LeaseEventSink>(&mut self, events: &mut S) -> Result<(), DhcpError> {
+        loop {
+            self.serve_batch(events).await?;
+        }
+    }
+
+    /// Wait for a datagram, then handle it with the others already
+    /// received, returning the number of replies. Lease changes are
+    /// reported to `events`.
+    pub async fn serve_batch<S: LeaseEventSink>(
+        &mut self,
+        events: &mut S,
+    ) -> Result<usize, DhcpError> {
+        let mut requests = Vec::with_capacity(self.batch_len);
+        let (len, source) = self.socket.recv_from(self.decoder.buffer()).await?;
+        self.decode(len, source, &mut requests);
+        for _ in 1..self.batch_len {
+            match self.socket.try_recv_from(self.decoder.buffer()) {
+                Ok((len, source)) => self.decode(len, source, &mut requests),
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as u32;
+        event!(debug, requests = requests.len(), "handling batch");
+        let replies = self.server.handle_batch(&requests, now, events);
+        for (index, reply) in &replies {
+            let destination = reply_destination(&requests[*index], reply);
+            let sent = match reply.serialize() {
+                Ok(reply) => self
+                    .socket
+                    .send_to(&reply, destination)
+                    .await
+                    .map_err(From::from),
+                Err(error) => Err(error),
+            };
+            match sent {
+                Ok(_) => {
+                    event!(trace, %destination, "sent reply");
+                }
+                Err(error) => self.error(destination.into(), error),
+            }
+        }
+        Ok(replies.len())
+    }
+
+    fn decode(&mut self, len: usize, source: SocketAddr, requests: &mut Vec<DhcpMessage>) {
+        event!(trace, %source, len, "received datagram");
+        match self.decoder.decode_buffer(len) {
+            Ok(request) => requests.push(request),
+            Err(error) => self.error(source, error),
+        }
+        for warning in self.decoder.take_warnings() {
+            self.error(source, warning);
+        }
+    }
+
+    fn error(&mut self, peer: SocketAddr, error: DhcpError) {
+        event!(warn, %peer, %error, "datagram dropped or damaged");
+        self.errors.push((peer, error));
+    }
+}
@@ -1,18 +1,378 @@
+use std::borrow::Cow;
 use std::fmt;
+use std::io;
+use std::net::Ipv4Addr;
 
 /// An Error type for the dhcp lib.
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum DhcpError {
-    ParsingError(String),
+    // Borrowed for fixed messages, so that parsing garbage does not
+    // allocate, and owned for messages formatted with their context.
+    ParsingError(Cow<'static, str>),
+    AuthenticationError(String),
+    SerializationError(SerializationError),
+    Io(#[cfg_attr(feature = "defmt", defmt(Debug2Format))] io::Error),
+    // No reply arrived in time.
+    Timeout,
+    // A peer answered, but not as expected.
+    Transport(String),
+    // A request refused by a server with a DHCPNAK.
+    Nak(NakReason),
+    // No free address left in a server pool, named by its address range.
+    PoolExhausted {
+        scope: String,
+    },
+    // A requested address leased to another client.
+    AddressConflict {
+        ip: Ipv4Addr,
+    },
+    // A requested address reserved for another client.
+    ReservationMismatch {
+        ip: Ipv4Addr,
+    },
+    // The storage of the leases could not be read or written.
+    LeaseStoreUnavailable(String),
+    // A request relayed more times than relay agents allow, see
+    // `DhcpMessage::increment_hops`.
+    TooManyHops {
+        hops: u8,
+    },
+    // An option needing more bytes than available after its code.
+    Truncated {
+        code: u8,
+        needed: usize,
+        available: usize,
+    },
+    // An option whose length does not fit its format.
+    InvalidLength {
+        code: u8,
+        len: usize,
+    },
+    UnknownOptionCode(u8),
+    // Data ending before a fixed-size part of a message, in `field`.
+    UnexpectedEof {
+        field: &'static str,
+        needed: usize,
+        available: usize,
+    },
+    // A message whose options do not start with the magic cookie.
+    MissingMagicCookie,
+    // A text option whose value is not valid UTF-8.
+    InvalidUtf8 {
+        code: u8,
+    },
+    UnknownOpCode(u8),
+    UnknownMessageType(u8),
+    // Bytes other than padding after the End option.
+    DataAfterEnd,
+    // An option not fitting in a fixed-capacity message, see the
+    // `heapless` module: too long for its buffer, or past the last slot.
+    CapacityExceeded {
+        code: u8,
+        capacity: usize,
+    },
+    // An option whose value does not fit its format.
+    InvalidValue {
+        code: u8,
+        reason: &'static str,
+    },
+    // An error located in a message: the offset where the failing field or
+    // option starts, the field holding it ("header", "options", "sname" or
+    // "file") and the code of the option being parsed.
+    Context {
+        offset: usize,
+        field: &'static str,
+        code: Option<u8>,
+        #[cfg_attr(feature = "defmt", defmt(Display2Format))]
+        error: Box<DhcpError>,
+    },
 }
 
+/// The category of a DhcpError, for callers deciding how to handle it
+/// without matching every variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ErrorKind {
+    // Bytes that do not form a valid message or option.
+    Malformed,
+    // A valid message using something this lib does not implement.
+    Unsupported,
+    // The network or filesystem failed, or a reply did not arrive in time.
+    Io,
+    // A peer did not behave as the protocol requires.
+    Protocol,
+    // A resource, such as an address pool, ran out.
+    Exhausted,
+}
+
+impl ErrorKind {
+    /// Whether retrying the operation later may succeed.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, ErrorKind::Io | ErrorKind::Exhausted)
+    }
+}
+
+impl DhcpError {
+    /// The category of the error, that of the located error for
+    /// `DhcpError::Context`.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            DhcpError::ParsingError(_)
+            | DhcpError::SerializationError(_)
+            | DhcpError::Truncated { .. }
+            | DhcpError::InvalidLength { .. }
+            | DhcpError::InvalidValue { .. }
+            | DhcpError::UnexpectedEof { .. }
+            | DhcpError::MissingMagicCookie
+            | DhcpError::InvalidUtf8 { .. }
+            | DhcpError::UnknownOpCode(_)
+            | DhcpError::DataAfterEnd => ErrorKind::Malformed,
+            DhcpError::UnknownOptionCode(_)
+            | DhcpError::UnknownMessageType(_)
+            | DhcpError::CapacityExceeded { .. } => ErrorKind::Unsupported,
+            DhcpError::Io(_) | DhcpError::Timeout | DhcpError::LeaseStoreUnavailable(_) => {
+                ErrorKind::Io
+            }
+            DhcpError::PoolExhausted { .. } => ErrorKind::Exhausted,
+            DhcpError::AddressConflict { .. }
+            | DhcpError::ReservationMismatch { .. }
+            | DhcpError::TooManyHops { .. } => ErrorKind::Protocol,
+            DhcpError::AuthenticationError(_) | DhcpError::Transport(_) | DhcpError::Nak(_) => {
+                ErrorKind::Protocol
+            }
+            DhcpError::Context { error, .. } => error.kind(),
+        }
+    }
+
+    /// Whether retrying the operation later may succeed.
+    pub fn is_transient(&self) -> bool {
+        self.kind().is_transient()
+    }
+}
+
+/// Why a server refuses a client request with a DHCPNAK. Its message is
+/// sent to the client in the Message option (56, RFC 2132 section 9.9).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NakReason {
+    // A DHCPREQUEST with neither a requested address nor ciaddr.
+    NoAddressRequested,
+    // A requested address outside of the served network.
+    WrongNetwork,
+    // A requested address leased to another client.
+    AddressInUse,
+    // A message unknown to this lib, as sent by another server.
+    Other(String),
+}
+
+impl NakReason {
+    /// The message to send to the client.
+    pub fn message(&self) -> &str {
+        match self {
+            NakReason::NoAddressRequested => "no address requested",
+            NakReason::WrongNetwork => "requested address not on this network",
+            NakReason::AddressInUse => "requested address already in use",
+            NakReason::Other(message) => message,
+        }
+    }
+
+    /// The reason of a message received from a server.
+    pub fn from_message(message: &str) -> NakReason {
+        [
+            NakReason::NoAddressRequested,
+            NakReason::WrongNetwork,
+            NakReason::AddressInUse,
+        ]
+        .into_iter()
+        .find(|reason| reason.message() == message)
+        .unwrap_or_else(|| NakReason::Other(message.to_string()))
+    }
+}
+
+impl fmt::Display for NakReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+/// A value that cannot be serialized into a valid message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SerializationError {
+    // An option value longer than its length byte allows, or a domain
+    // name or label longer than DNS allows (code 0).
+    ValueTooLong { code: u8, len: usize },
+    // A message longer than the size allowed for it.
+    MessageTooLong { len: usize, max: usize },
+    // An option with an empty value where its format requires one, or
+    // missing from a message whose type requires it.
+    MissingValue { code: u8 },
+    // A header field left unset in a message whose type requires it.
+    MissingField { field: &'static str },
+    // An option value of a length its format does not allow, or a value
+    // given to pad or end.
+    InvalidLength { code: u8, len: usize },
+    // An unknown option with the code of a known one, and a value the
+    // known option represents.
+    KnownCode { code: u8 },
+}
+
+impl fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SerializationError::ValueTooLong { code, len } => write!(
+                f,
+                "Option {} has a value of {} bytes, longer than its format allows",
+                code, len
+            ),
+            SerializationError::MessageTooLong { len, max } => write!(
+                f,
+                "Message of {} bytes is longer than the maximum of {}",
+                len, max
+            ),
+            SerializationError::MissingValue { code } => {
+                write!(f, "Option {} is missing its value", code)
+            }
+            SerializationError::MissingField { field } => {
+                write!(f, "Field {} is missing", field)
+            }
+            SerializationError::InvalidLength { code, len } => write!(
+                f,
+                "Option {} has a value of {} bytes, which its format does not allow",
+                code, len
+            ),
+            SerializationError::KnownCode { code } => {
+                write!(f, "Option {} is known, and cannot be sent as unknown", code)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SerializationError {}
+
 impl fmt::Display for DhcpError {
     /// Display a DhcpError.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             DhcpError::ParsingError(message) => write!(f, "Parsing Error: {}", message),
+            DhcpError::AuthenticationError(message) => {
+                write!(f, "Authentication Error: {}", message)
+            }
+            DhcpError::SerializationError(error) => {
+                write!(f, "Serialization Error: {}", error)
+            }
+            DhcpError::Io(error) => write!(f, "IO Error: {}", error),
+            DhcpError::Timeout => write!(f, "Timeout"),
+            DhcpError::Transport(message) => write!(f, "Transport Error: {}", message),
+            DhcpError::Nak(reason) => write!(f, "NAK: {}", reason),
+            DhcpError::PoolExhausted { scope } => {
+                write!(f, "Pool Error: No free address in pool {}", scope)
+            }
+            DhcpError::AddressConflict { ip } => {
+                write!(f, "Pool Error: Address {} is leased to another client", ip)
+            }
+            DhcpError::ReservationMismatch { ip } => {
+                write!(
+                    f,
+                    "Pool Error: Address {} is reserved for another client",
+                    ip
+                )
+            }
+            DhcpError::LeaseStoreUnavailable(message) => {
+                write!(f, "Lease Store Error: {}", message)
+            }
+            DhcpError::TooManyHops { hops } => {
+                write!(f, "Relay Error: Message already relayed {} times", hops)
+            }
+            DhcpError::Truncated {
+                code,
+                needed,
+                available,
+            } => write!(
+                f,
+                "Parsing Error: Option {} is truncated, {} bytes needed but {} available",
+                code, needed, available
+            ),
+            DhcpError::InvalidLength { code, len } => write!(
+                f,
+                "Parsing Error: Option {} has an invalid length of {}",
+                code, len
+            ),
+            DhcpError::UnknownOptionCode(code) => {
+                write!(f, "Parsing Error: Unknown option code {}", code)
+            }
+            DhcpError::UnexpectedEof {
+                field,
+                needed,
+                available,
+            } => write!(
+                f,
+                "Parsing Error: The {} field is truncated, {} bytes needed but {} available",
+                field, needed, available
+            ),
+            DhcpError::MissingMagicCookie => {
+                write!(f, "Parsing Error: Could not find DHCP magic cookie")
+            }
+            DhcpError::InvalidUtf8 { code } => {
+                write!(f, "Parsing Error: Option {} is not valid UTF-8", code)
+            }
+            DhcpError::UnknownOpCode(op) => {
+                write!(f, "Parsing Error: Unknown BOOTP op code: {}", op)
+            }
+            DhcpError::UnknownMessageType(message_type) => {
+                write!(
+                    f,
+                    "Parsing Error: Unknown DHCP message type: {}",
+                    message_type
+                )
+            }
+            DhcpError::DataAfterEnd => write!(f, "Parsing Error: Data after the End option"),
+            DhcpError::CapacityExceeded { code, capacity } => write!(
+                f,
+                "Parsing Error: Option {} does not fit in a capacity of {}",
+                code, capacity
+            ),
+            DhcpError::InvalidValue { code, reason } => write!(
+                f,
+                "Parsing Error: Option {} has an invalid value, {}",
+                code, reason
+            ),
+            DhcpError::Context {
+                offset,
+                field,
+                code,
+                error,
+            } => {
+                write!(f, "{} (", error)?;
+                if let Some(code) = code {
+                    write!(f, "option {} ", code)?;
+                }
+                write!(f, "at offset {} in the {} field)", offset, field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DhcpError {
+    /// The error this one wraps, if any.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DhcpError::SerializationError(error) => Some(error),
+            DhcpError::Io(error) => Some(error),
+            DhcpError::Context { error, .. } => Some(error.as_ref()),
+            _ => None,
         }
     }
 }
 
-impl std::error::Error for DhcpError {}
+impl From<io::Error> for DhcpError {
+    /// Convert an IO error, timeouts of sockets with a read timeout
+    /// becoming `DhcpError::Timeout`.
+    fn from(error: io::Error) -> DhcpError {
+        match error.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => DhcpError::Timeout,
+            _ => DhcpError::Io(error),
+        }
+    }
+}
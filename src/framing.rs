@@ -0,0 +1,90 @@
+use std::io::{self, Read, Write};
+
+use crate::error::DhcpError;
+
+/// The largest message a frame can carry.
+pub const MAX_FRAME_LEN: usize = u16::MAX as usize;
+
+// Message framing for DHCP over TCP, as used by bulk and active
+// leasequery for both DHCPv4 (RFC 6926) and DHCPv6 (RFC 5460): each
+// message is preceded by its length as a two-octet integer.
+//
+//  0                   1                   2                   3
+//  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |          message-size         |                               |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               |
+// .                    DHCP message (variable)                    .
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+
+/// Prefix a message with its length.
+pub fn encode_frame(message: &[u8]) -> Result<Vec<u8>, DhcpError> {
+    if message.len() > MAX_FRAME_LEN {
+        return Err(DhcpError::ParsingError(
+            "Message too long to be framed".into(),
+        ));
+    }
+    let mut frame = (message.len() as u16).to_be_bytes().to_vec();
+    frame.extend_from_slice(message);
+    Ok(frame)
+}
+
+/// Write a framed message to a stream.
+pub fn write_frame<W: Write>(writer: &mut W, message: &[u8]) -> io::Result<()> {
+    let frame = encode_frame(message)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error.to_string()))?;
+    writer.write_all(&frame)
+}
+
+/// Read a framed message from a stream, returning `None` when the stream
+/// is closed between two messages.
+pub fn read_frame<R: Read>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0; 2];
+    match reader.read_exact(&mut len[..1]) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+    reader.read_exact(&mut len[1..])?;
+
+    let mut message = vec![0; u16::from_be_bytes(len) as usize];
+    reader.read_exact(&mut message)?;
+    Ok(Some(message))
+}
+
+/// Reassemble framed messages from data received in arbitrary chunks, as
+/// returned by non-blocking reads.
+#[derive(Debug, Clone, Default)]
+pub struct FrameDecoder {
+    buffer: Vec<u8>,
+}
+
+impl FrameDecoder {
+    pub fn new() -> FrameDecoder {
+        FrameDecoder::default()
+    }
+
+    /// Append received data.
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Retrieve the next complete message, if any.
+    pub fn next_frame(&mut self) -> Option<Vec<u8>> {
+        if self.buffer.len() < 2 {
+            return None;
+        }
+        let len = u16::from_be_bytes([self.buffer[0], self.buffer[1]]) as usize;
+        if self.buffer.len() < 2 + len {
+            return None;
+        }
+        let message = self.buffer[2..2 + len].to_vec();
+        self.buffer.drain(..2 + len);
+        Some(message)
+    }
+
+    /// Whether part of a message is still waiting for more data.
+    pub fn has_partial_frame(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+}
@@ -0,0 +1,176 @@
+//! Hardware addresses, as sent in the htype, hlen and chaddr fields of a
+//! message and in client identifiers.
+
+use std::fmt;
+
+use crate::error::DhcpError;
+use crate::message::DhcpMessage;
+use crate::option::DhcpOption;
+
+/// The type of a hardware address, the htype field (ARP hardware types,
+/// assigned by IANA).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HardwareType {
+    Ethernet,
+    Ieee802,
+    Infiniband,
+    Other(u8),
+}
+
+impl HardwareType {
+    /// The length of the addresses of this type, if known.
+    pub fn address_len(&self) -> Option<usize> {
+        match self {
+            HardwareType::Ethernet | HardwareType::Ieee802 => Some(6),
+            HardwareType::Infiniband => Some(20),
+            HardwareType::Other(_) => None,
+        }
+    }
+}
+
+impl From<HardwareType> for u8 {
+    fn from(htype: HardwareType) -> u8 {
+        match htype {
+            HardwareType::Ethernet => 1,
+            HardwareType::Ieee802 => 6,
+            HardwareType::Infiniband => 32,
+            HardwareType::Other(htype) => htype,
+        }
+    }
+}
+
+impl From<u8> for HardwareType {
+    fn from(htype: u8) -> HardwareType {
+        match htype {
+            1 => HardwareType::Ethernet,
+            6 => HardwareType::Ieee802,
+            32 => HardwareType::Infiniband,
+            htype => HardwareType::Other(htype),
+        }
+    }
+}
+
+/// A hardware address of up to `HardwareAddress::MAX_LEN` bytes, with its
+/// type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HardwareAddress {
+    htype: HardwareType,
+    len: u8,
+    // Zero past `len`.
+    bytes: [u8; HardwareAddress::MAX_LEN],
+}
+
+impl HardwareAddress {
+    /// The length of the longest address, the 20 bytes of InfiniBand.
+    pub const MAX_LEN: usize = 20;
+
+    /// An address of type `htype`, failing if its length is not that of
+    /// the type.
+    pub fn new(htype: HardwareType, address: &[u8]) -> Result<HardwareAddress, DhcpError> {
+        let valid = match htype.address_len() {
+            Some(len) => address.len() == len,
+            None => address.len() <= HardwareAddress::MAX_LEN,
+        };
+        if !valid {
+            return Err(DhcpError::ParsingError(
+                format!(
+                    "Invalid length {} for a hardware address of type {}",
+                    address.len(),
+                    u8::from(htype)
+                )
+                .into(),
+            ));
+        }
+        let mut bytes = [0; HardwareAddress::MAX_LEN];
+        bytes[..address.len()].copy_from_slice(address);
+        Ok(HardwareAddress {
+            htype,
+            len: address.len() as u8,
+            bytes,
+        })
+    }
+
+    pub fn ethernet(mac: [u8; 6]) -> HardwareAddress {
+        let mut bytes = [0; HardwareAddress::MAX_LEN];
+        bytes[..6].copy_from_slice(&mac);
+        HardwareAddress {
+            htype: HardwareType::Ethernet,
+            len: 6,
+            bytes,
+        }
+    }
+
+    pub fn htype(&self) -> HardwareType {
+        self.htype
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+
+    /// The address of a client identifier made of a hardware type and
+    /// address, as RFC 2132 section 9.14 suggests, if it is one.
+    pub fn from_client_identifier(identifier: &[u8]) -> Option<HardwareAddress> {
+        match identifier.split_first() {
+            Some((0, _)) | None => None,
+            Some((htype, address)) => HardwareAddress::new((*htype).into(), address).ok(),
+        }
+    }
+
+    /// A client identifier made of the hardware type and address.
+    pub fn client_identifier(&self) -> DhcpOption {
+        let mut identifier = Vec::with_capacity(1 + self.len as usize);
+        identifier.push(self.htype.into());
+        identifier.extend_from_slice(self.as_bytes());
+        DhcpOption::ClientIdentifier(identifier)
+    }
+}
+
+impl From<[u8; 6]> for HardwareAddress {
+    fn from(mac: [u8; 6]) -> HardwareAddress {
+        HardwareAddress::ethernet(mac)
+    }
+}
+
+impl fmt::Display for HardwareAddress {
+    /// Display the address as colon-separated hexadecimal bytes.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, byte) in self.as_bytes().iter().enumerate() {
+            if index > 0 {
+                write!(f, ":")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl DhcpMessage {
+    /// The hardware address in the header, or None if hlen is zero, as
+    /// sent by InfiniBand clients (RFC 4390 section 2.1), which identify
+    /// themselves with a client identifier instead.
+    pub fn hardware_address(&self) -> Result<Option<HardwareAddress>, DhcpError> {
+        match self.hlen as usize {
+            0 => Ok(None),
+            len if len > self.chaddr.len() => Err(DhcpError::ParsingError(
+                format!("Invalid hardware address length {}", len).into(),
+            )),
+            len => HardwareAddress::new(self.htype.into(), &self.chaddr[..len]).map(Some),
+        }
+    }
+
+    /// Set the htype, hlen and chaddr fields. Addresses longer than
+    /// chaddr, those of InfiniBand, are left out with a zero hlen, as RFC
+    /// 4390 requires.
+    pub fn set_hardware_address(&mut self, address: &HardwareAddress) {
+        self.htype = address.htype.into();
+        self.chaddr = [0; 16];
+        match address.as_bytes() {
+            bytes if bytes.len() > self.chaddr.len() => self.hlen = 0,
+            bytes => {
+                self.hlen = bytes.len() as u8;
+                self.chaddr[..bytes.len()].copy_from_slice(bytes);
+            }
+        }
+    }
+}
@@ -0,0 +1,522 @@
+//! Messages of a fixed capacity, parsed and serialized without allocating.
+//!
+//! For targets that must not allocate while handling messages, such as
+//! firmware with a fixed memory budget, `FixedMessage` holds up to
+//! `OPTIONS` options, the value of each in a buffer of `LEN` bytes, both
+//! set as const generics. An option that does not fit fails with
+//! `DhcpError::CapacityExceeded` rather than being allocated for, and
+//! errors are returned without the `DhcpError::Context` locating them,
+//! which is boxed.
+//!
+//! The options a client needs to configure itself are decoded; the others
+//! are kept as `FixedOption::Unknown` once their length is checked. The
+//! module still builds against `std`, as the whole crate does, and is not
+//! for `no_std` targets: `DhcpError` itself may hold a `String` or an
+//! `io::Error`, though none of the errors returned here allocate.
+
+use std::fmt;
+use std::net::Ipv4Addr;
+use std::ops::Deref;
+use std::str::from_utf8;
+
+use crate::error::{DhcpError, SerializationError};
+use crate::message::{DhcpMessage, Flags, MAGIC_COOKIE};
+use crate::option::value_len;
+
+const OPTION_PAD: u8 = 0;
+const OPTION_END: u8 = 255;
+
+/// Up to `N` bytes, stored inline.
+#[derive(Clone, Copy)]
+pub struct FixedBytes<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> FixedBytes<N> {
+    pub const fn new() -> FixedBytes<N> {
+        FixedBytes {
+            bytes: [0; N],
+            len: 0,
+        }
+    }
+
+    /// A copy of `bytes`, if there are no more than `N` of them.
+    pub fn from_slice(bytes: &[u8]) -> Option<FixedBytes<N>> {
+        let mut fixed = FixedBytes::new();
+        match fixed.extend_from_slice(bytes) {
+            true => Some(fixed),
+            false => None,
+        }
+    }
+
+    /// Append `bytes`, leaving the buffer unchanged if they do not fit.
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) -> bool {
+        if bytes.len() > N - self.len {
+            return false;
+        }
+        self.bytes[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+        true
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<const N: usize> Default for FixedBytes<N> {
+    fn default() -> FixedBytes<N> {
+        FixedBytes::new()
+    }
+}
+
+impl<const N: usize> Deref for FixedBytes<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<const N: usize> PartialEq for FixedBytes<N> {
+    fn eq(&self, other: &FixedBytes<N>) -> bool {
+        **self == **other
+    }
+}
+
+impl<const N: usize> Eq for FixedBytes<N> {}
+
+/// A UTF-8 string of up to `N` bytes, stored inline.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct FixedString<const N: usize>(FixedBytes<N>);
+
+impl<const N: usize> FixedString<N> {
+    pub const fn new() -> FixedString<N> {
+        FixedString(FixedBytes::new())
+    }
+
+    /// A copy of `value`, if it is no longer than `N` bytes.
+    pub fn try_new(value: &str) -> Option<FixedString<N>> {
+        FixedBytes::from_slice(value.as_bytes()).map(FixedString)
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only ever built from strings, or from checked UTF-8.
+        from_utf8(&self.0).unwrap_or_default()
+    }
+}
+
+impl<const N: usize> Deref for FixedString<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Display for FixedString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> PartialEq<str> for FixedString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+/// A list of addresses taking up to `N` bytes, that is `N / 4` addresses,
+/// stored inline.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct FixedAddresses<const N: usize>(FixedBytes<N>);
+
+impl<const N: usize> FixedAddresses<N> {
+    pub const fn new() -> FixedAddresses<N> {
+        FixedAddresses(FixedBytes::new())
+    }
+
+    /// A copy of `addresses`, if they fit.
+    pub fn from_slice(addresses: &[Ipv4Addr]) -> Option<FixedAddresses<N>> {
+        let mut fixed = FixedAddresses::new();
+        match addresses.iter().all(|address| fixed.push(*address)) {
+            true => Some(fixed),
+            false => None,
+        }
+    }
+
+    /// Append `address`, if it fits.
+    pub fn push(&mut self, address: Ipv4Addr) -> bool {
+        self.0.extend_from_slice(&address.octets())
+    }
+
+    pub fn get(&self, index: usize) -> Option<Ipv4Addr> {
+        self.0.chunks_exact(4).nth(index).map(address)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len() / 4
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Ipv4Addr> + '_ {
+        self.0.chunks_exact(4).map(address)
+    }
+
+    /// The addresses as sent on the wire.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedAddresses<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An option whose value takes up to `N` bytes.
+///
+/// The options needed by a client to configure itself are decoded, the
+/// others are kept as `Unknown`, with their code and value, as are the
+/// message type and server identifier in `DhcpOption`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixedOption<const N: usize> {
+    SubnetMask(Ipv4Addr),
+    Router(FixedAddresses<N>),
+    DomainNameServer(FixedAddresses<N>),
+    HostName(FixedString<N>),
+    DomainName(FixedString<N>),
+    RequestedIpAddress(Ipv4Addr),
+    IpAddressLeaseTime(u32),
+    ClientIdentifier(FixedBytes<N>),
+    Unknown(u8, FixedBytes<N>),
+}
+
+impl<const N: usize> FixedOption<N> {
+    pub fn code(&self) -> u8 {
+        match self {
+            FixedOption::SubnetMask(_) => 1,
+            FixedOption::Router(_) => 3,
+            FixedOption::DomainNameServer(_) => 6,
+            FixedOption::HostName(_) => 12,
+            FixedOption::DomainName(_) => 15,
+            FixedOption::RequestedIpAddress(_) => 50,
+            FixedOption::IpAddressLeaseTime(_) => 51,
+            FixedOption::ClientIdentifier(_) => 61,
+            FixedOption::Unknown(code, _) => *code,
+        }
+    }
+
+    /// The length of the serialized option, code and length included.
+    pub fn wire_len(&self) -> usize {
+        2 + self.with_value(|value| value.len())
+    }
+
+    // Decode the value of an option whose length was checked.
+    fn decode(code: u8, value: &[u8]) -> Result<FixedOption<N>, DhcpError> {
+        let bytes = || {
+            FixedBytes::from_slice(value).ok_or(DhcpError::CapacityExceeded { code, capacity: N })
+        };
+        let string = || match from_utf8(value) {
+            Ok(_) => Ok(FixedString(bytes()?)),
+            Err(_) => Err(DhcpError::InvalidUtf8 { code }),
+        };
+        Ok(match code {
+            1 => FixedOption::SubnetMask(address(value)),
+            3 => FixedOption::Router(FixedAddresses(bytes()?)),
+            6 => FixedOption::DomainNameServer(FixedAddresses(bytes()?)),
+            12 => FixedOption::HostName(string()?),
+            15 => FixedOption::DomainName(string()?),
+            50 => FixedOption::RequestedIpAddress(address(value)),
+            51 => FixedOption::IpAddressLeaseTime(u32::from_be_bytes([
+                value[0], value[1], value[2], value[3],
+            ])),
+            61 if value.len() < 2 => {
+                return Err(DhcpError::InvalidLength {
+                    code,
+                    len: value.len(),
+                })
+            }
+            61 => FixedOption::ClientIdentifier(bytes()?),
+            _ => FixedOption::Unknown(code, bytes()?),
+        })
+    }
+
+    // Call `f` with the value of the option, as sent on the wire.
+    fn with_value<T>(&self, f: impl FnOnce(&[u8]) -> T) -> T {
+        match self {
+            FixedOption::SubnetMask(address) | FixedOption::RequestedIpAddress(address) => {
+                f(&address.octets())
+            }
+            FixedOption::IpAddressLeaseTime(time) => f(&time.to_be_bytes()),
+            FixedOption::Router(addresses) | FixedOption::DomainNameServer(addresses) => {
+                f(addresses.as_bytes())
+            }
+            FixedOption::HostName(value) | FixedOption::DomainName(value) => f(value.as_bytes()),
+            FixedOption::ClientIdentifier(value) | FixedOption::Unknown(_, value) => f(value),
+        }
+    }
+}
+
+/// A list of up to `OPTIONS` options, whose values take up to `N` bytes.
+#[derive(Clone)]
+pub struct FixedOptions<const OPTIONS: usize, const N: usize> {
+    options: [FixedOption<N>; OPTIONS],
+    len: usize,
+}
+
+impl<const OPTIONS: usize, const N: usize> FixedOptions<OPTIONS, N> {
+    pub const fn new() -> FixedOptions<OPTIONS, N> {
+        FixedOptions {
+            options: [FixedOption::Unknown(0, FixedBytes::new()); OPTIONS],
+            len: 0,
+        }
+    }
+
+    /// The first option with code `code`.
+    pub fn get(&self, code: u8) -> Option<&FixedOption<N>> {
+        self.iter().find(|option| option.code() == code)
+    }
+
+    /// Append `option`, failing with `DhcpError::CapacityExceeded` when
+    /// the list is full.
+    pub fn push(&mut self, option: FixedOption<N>) -> Result<(), DhcpError> {
+        if self.len == OPTIONS {
+            return Err(DhcpError::CapacityExceeded {
+                code: option.code(),
+                capacity: OPTIONS,
+            });
+        }
+        self.options[self.len] = option;
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<FixedOption<N>> {
+        self.len = self.len.checked_sub(1)?;
+        Some(self.options[self.len])
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    pub const fn capacity(&self) -> usize {
+        OPTIONS
+    }
+}
+
+impl<const OPTIONS: usize, const N: usize> Default for FixedOptions<OPTIONS, N> {
+    fn default() -> FixedOptions<OPTIONS, N> {
+        FixedOptions::new()
+    }
+}
+
+impl<const OPTIONS: usize, const N: usize> Deref for FixedOptions<OPTIONS, N> {
+    type Target = [FixedOption<N>];
+
+    fn deref(&self) -> &[FixedOption<N>] {
+        &self.options[..self.len]
+    }
+}
+
+impl<const OPTIONS: usize, const N: usize> fmt::Debug for FixedOptions<OPTIONS, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<const OPTIONS: usize, const N: usize> PartialEq for FixedOptions<OPTIONS, N> {
+    fn eq(&self, other: &FixedOptions<OPTIONS, N>) -> bool {
+        **self == **other
+    }
+}
+
+/// A message holding up to `OPTIONS` options, whose values take up to `N`
+/// bytes, with the same header as `DhcpMessage`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedMessage<const OPTIONS: usize, const N: usize> {
+    pub op: u8,
+    pub htype: u8,
+    pub hlen: u8,
+    pub hops: u8,
+    pub xid: u32,
+    pub secs: u16,
+    pub flags: Flags,
+    pub ciaddr: Ipv4Addr,
+    pub yiaddr: Ipv4Addr,
+    pub siaddr: Ipv4Addr,
+    pub giaddr: Ipv4Addr,
+    pub chaddr: [u8; 16],
+    pub sname: [u8; 64],
+    pub file: [u8; 128],
+    pub options: FixedOptions<OPTIONS, N>,
+}
+
+impl<const OPTIONS: usize, const N: usize> FixedMessage<OPTIONS, N> {
+    /// Create an empty message for an Ethernet client.
+    pub const fn new(op: u8, xid: u32) -> FixedMessage<OPTIONS, N> {
+        FixedMessage {
+            op,
+            htype: 1,
+            hlen: 6,
+            hops: 0,
+            xid,
+            secs: 0,
+            flags: Flags::from_bits(0),
+            ciaddr: Ipv4Addr::UNSPECIFIED,
+            yiaddr: Ipv4Addr::UNSPECIFIED,
+            siaddr: Ipv4Addr::UNSPECIFIED,
+            giaddr: Ipv4Addr::UNSPECIFIED,
+            chaddr: [0; 16],
+            sname: [0; 64],
+            file: [0; 128],
+            options: FixedOptions::new(),
+        }
+    }
+
+    /// Retrieve the first option with the given code.
+    pub fn get_option(&self, code: u8) -> Option<&FixedOption<N>> {
+        self.options.get(code)
+    }
+
+    /// Deserialize a message. Pad options are skipped, and parsing stops at
+    /// the End option, as in `DhcpMessage::deserialize`.
+    pub fn deserialize(data: &[u8]) -> Result<FixedMessage<OPTIONS, N>, DhcpError> {
+        if data.len() < DhcpMessage::HEADER_LEN {
+            return Err(DhcpError::UnexpectedEof {
+                field: "header",
+                needed: DhcpMessage::HEADER_LEN,
+                available: data.len(),
+            });
+        }
+        if data[236..240] != MAGIC_COOKIE {
+            return Err(DhcpError::MissingMagicCookie);
+        }
+
+        let mut message = FixedMessage::new(data[0], 0);
+        message.htype = data[1];
+        message.hlen = data[2];
+        message.hops = data[3];
+        message.xid = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+        message.secs = u16::from_be_bytes([data[8], data[9]]);
+        message.flags = Flags::from_bits(u16::from_be_bytes([data[10], data[11]]));
+        message.ciaddr = address(&data[12..16]);
+        message.yiaddr = address(&data[16..20]);
+        message.siaddr = address(&data[20..24]);
+        message.giaddr = address(&data[24..28]);
+        message.chaddr.copy_from_slice(&data[28..44]);
+        message.sname.copy_from_slice(&data[44..108]);
+        message.file.copy_from_slice(&data[108..236]);
+
+        let mut options = &data[DhcpMessage::HEADER_LEN..];
+        while let Some(&code) = options.first() {
+            match code {
+                OPTION_PAD => {
+                    options = &options[1..];
+                    continue;
+                }
+                OPTION_END => break,
+                _ => {}
+            }
+            let len = value_len(code, &options[1..])?;
+            let option = FixedOption::decode(code, &options[2..2 + len])?;
+            message.options.push(option)?;
+            options = &options[2 + len..];
+        }
+        Ok(message)
+    }
+
+    /// The length of the serialized message.
+    pub fn wire_len(&self) -> usize {
+        DhcpMessage::HEADER_LEN
+            + self
+                .options
+                .iter()
+                .map(FixedOption::wire_len)
+                .sum::<usize>()
+            + 1
+    }
+
+    /// Serialize the message into `buffer`, terminating the options with an
+    /// End option. Returns the length of the message.
+    pub fn serialize_into(&self, buffer: &mut [u8]) -> Result<usize, DhcpError> {
+        let len = self.wire_len();
+        if len > buffer.len() {
+            return Err(DhcpError::SerializationError(
+                SerializationError::MessageTooLong {
+                    len,
+                    max: buffer.len(),
+                },
+            ));
+        }
+
+        buffer[0..4].copy_from_slice(&[self.op, self.htype, self.hlen, self.hops]);
+        buffer[4..8].copy_from_slice(&self.xid.to_be_bytes());
+        buffer[8..10].copy_from_slice(&self.secs.to_be_bytes());
+        buffer[10..12].copy_from_slice(&self.flags.bits().to_be_bytes());
+        buffer[12..16].copy_from_slice(&self.ciaddr.octets());
+        buffer[16..20].copy_from_slice(&self.yiaddr.octets());
+        buffer[20..24].copy_from_slice(&self.siaddr.octets());
+        buffer[24..28].copy_from_slice(&self.giaddr.octets());
+        buffer[28..44].copy_from_slice(&self.chaddr);
+        buffer[44..108].copy_from_slice(&self.sname);
+        buffer[108..236].copy_from_slice(&self.file);
+        buffer[236..240].copy_from_slice(&MAGIC_COOKIE);
+
+        let mut offset = DhcpMessage::HEADER_LEN;
+        for option in self.options.iter() {
+            let code = option.code();
+            offset = option.with_value(|value| {
+                if value.len() > u8::MAX as usize {
+                    return Err(DhcpError::SerializationError(
+                        SerializationError::ValueTooLong {
+                            code,
+                            len: value.len(),
+                        },
+                    ));
+                }
+                buffer[offset] = code;
+                buffer[offset + 1] = value.len() as u8;
+                buffer[offset + 2..offset + 2 + value.len()].copy_from_slice(value);
+                Ok(offset + 2 + value.len())
+            })?;
+        }
+        buffer[offset] = OPTION_END;
+        Ok(offset + 1)
+    }
+
+    /// Convert to a `DhcpMessage`, decoding all the options. Unlike the rest
+    /// of this module, this allocates.
+    pub fn to_message(&self) -> Result<DhcpMessage, DhcpError> {
+        let mut data = vec![0; self.wire_len()];
+        self.serialize_into(&mut data)?;
+        DhcpMessage::deserialize(&data)
+    }
+
+    /// Convert from a `DhcpMessage`, failing if it does not fit.
+    pub fn from_message(message: &DhcpMessage) -> Result<FixedMessage<OPTIONS, N>, DhcpError> {
+        FixedMessage::deserialize(&message.serialize()?)
+    }
+}
+
+fn address(value: &[u8]) -> Ipv4Addr {
+    Ipv4Addr::new(value[0], value[1], value[2], value[3])
+}
@@ -0,0 +1,175 @@
+//! Annotated hexdumps of DHCP packets, to investigate parsing failures.
+
+use std::fmt::Write;
+use std::net::Ipv4Addr;
+
+use crate::message::{DhcpMessage, MAGIC_COOKIE};
+use crate::option::DhcpOption;
+
+const BYTES_PER_LINE: usize = 16;
+
+// The fixed header fields, as (name, offset, length).
+const HEADER_FIELDS: [(&str, usize, usize); 14] = [
+    ("op", 0, 1),
+    ("htype", 1, 1),
+    ("hlen", 2, 1),
+    ("hops", 3, 1),
+    ("xid", 4, 4),
+    ("secs", 8, 2),
+    ("flags", 10, 2),
+    ("ciaddr", 12, 4),
+    ("yiaddr", 16, 4),
+    ("siaddr", 20, 4),
+    ("giaddr", 24, 4),
+    ("chaddr", 28, 16),
+    ("sname", 44, 64),
+    ("file", 108, 128),
+];
+
+/// Dump a packet in hex, each header field and option on its own lines
+/// with its byte range and decoded value.
+///
+/// Decoding goes as far as the packet allows: the first field or option
+/// that cannot be decoded is annotated with the reason, and the bytes after
+/// it are dumped without annotation.
+///
+/// ```text
+/// 0000  01                                               op: 1 [0x00..0x01]
+/// 0001  01                                               htype: 1 [0x01..0x02]
+/// ...
+/// 00f0  35 01 01                                         option 53: DhcpMessageType(Discover) [0xf0..0xf3]
+/// ```
+pub fn hexdump(packet: &[u8]) -> String {
+    let mut result = String::new();
+
+    for (name, offset, len) in HEADER_FIELDS {
+        if packet.len() < offset + len {
+            dump(
+                &mut result,
+                packet,
+                offset,
+                packet.len(),
+                &format!("{}: truncated", name),
+            );
+            return result;
+        }
+        let data = &packet[offset..offset + len];
+        let value = match len {
+            1 => data[0].to_string(),
+            2 => u16::from_be_bytes([data[0], data[1]]).to_string(),
+            4 if name == "xid" => format!(
+                "0x{:08x}",
+                u32::from_be_bytes([data[0], data[1], data[2], data[3]])
+            ),
+            4 => Ipv4Addr::new(data[0], data[1], data[2], data[3]).to_string(),
+            _ if name == "chaddr" => data
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<String>>()
+                .join(":"),
+            _ => format!("{:?}", null_terminated(data)),
+        };
+        dump(
+            &mut result,
+            packet,
+            offset,
+            offset + len,
+            &format!("{}: {}", name, value),
+        );
+    }
+
+    let cookie_end = DhcpMessage::HEADER_LEN;
+    if packet.len() < cookie_end {
+        dump(
+            &mut result,
+            packet,
+            236,
+            packet.len(),
+            "magic cookie: truncated",
+        );
+        return result;
+    }
+    if packet[236..cookie_end] != MAGIC_COOKIE {
+        dump(
+            &mut result,
+            packet,
+            236,
+            packet.len(),
+            "magic cookie: invalid",
+        );
+        return result;
+    }
+    dump(&mut result, packet, 236, cookie_end, "magic cookie");
+
+    let mut offset = cookie_end;
+    while offset < packet.len() {
+        let code = packet[offset];
+        match DhcpOption::deserialize(&packet[offset..]) {
+            Ok((DhcpOption::End, _)) => {
+                dump(&mut result, packet, offset, offset + 1, "end");
+                if offset + 1 < packet.len() {
+                    dump(&mut result, packet, offset + 1, packet.len(), "after end");
+                }
+                return result;
+            }
+            Ok((option, rest)) => {
+                let end = packet.len() - rest.len();
+                let annotation = match option {
+                    DhcpOption::Pad => "pad".to_string(),
+                    option => format!("option {}: {:?}", code, option),
+                };
+                dump(&mut result, packet, offset, end, &annotation);
+                offset = end;
+            }
+            Err(error) => {
+                dump(
+                    &mut result,
+                    packet,
+                    offset,
+                    packet.len(),
+                    &format!("option {}: {}", code, error),
+                );
+                return result;
+            }
+        }
+    }
+
+    result
+}
+
+// Dump `packet[start..end]`, annotating its first line.
+fn dump(result: &mut String, packet: &[u8], start: usize, end: usize, annotation: &str) {
+    if start >= end {
+        let _ = writeln!(
+            result,
+            "{:04x}  {:47}  {} [{:#04x}..{:#04x}]",
+            start, "", annotation, start, start
+        );
+        return;
+    }
+    for (index, line) in packet[start..end].chunks(BYTES_PER_LINE).enumerate() {
+        let hex = line
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<Vec<String>>()
+            .join(" ");
+        let offset = start + index * BYTES_PER_LINE;
+        if index == 0 {
+            let _ = writeln!(
+                result,
+                "{:04x}  {:47}  {} [{:#04x}..{:#04x}]",
+                offset, hex, annotation, start, end
+            );
+        } else {
+            let _ = writeln!(result, "{:04x}  {}", offset, hex);
+        }
+    }
+}
+
+fn null_terminated(data: &[u8]) -> String {
+    let end = data
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).into_owned()
+}
@@ -0,0 +1,40 @@
+//! A stable JSON representation of DHCP messages, the serde representation
+//! of `DhcpMessage` written with `serde_json`.
+//!
+//! Header fields keep their RFC 2131 names, addresses are written as
+//! strings, and each option is an object holding its code, its name and
+//! its value:
+//!
+//! ```json
+//! {"op":1,"htype":1,"hlen":6,"hops":0,"xid":42,"secs":0,"flags":32768,
+//!  "ciaddr":"0.0.0.0","yiaddr":"0.0.0.0","siaddr":"0.0.0.0",
+//!  "giaddr":"0.0.0.0","chaddr":"00:11:22:33:44:55","sname":"","file":"",
+//!  "options":[{"code":53,"name":"dhcp_message_type","value":1},
+//!             {"code":3,"name":"router","value":["192.168.1.1"]}]}
+//! ```
+//!
+//! Option names are those of the text representation. Values follow the
+//! type of their option code; raw data, such as text that is not UTF-8, is
+//! written as an object like `{"hex":"63e9"}`.
+
+use crate::error::DhcpError;
+use crate::message::DhcpMessage;
+
+impl DhcpMessage {
+    /// Write the message as a single line of JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("messages serialize to JSON")
+    }
+
+    /// Read a message written by `to_json`.
+    ///
+    /// Options may be given by code or by name. Missing header fields take
+    /// the values of `DhcpMessage::new`.
+    pub fn from_json(json: &str) -> Result<DhcpMessage, DhcpError> {
+        serde_json::from_str(json).map_err(json_error)
+    }
+}
+
+pub(crate) fn json_error(error: serde_json::Error) -> DhcpError {
+    DhcpError::ParsingError(format!("Could not parse JSON: {}", error).into())
+}
@@ -0,0 +1,71 @@
+use std::net::Ipv4Addr;
+
+/// The state of a lease, as reported by leasequery (RFC 6926).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaseState {
+    Available,
+    Active,
+    Expired,
+    Released,
+    Abandoned,
+    Reset,
+    Remote,
+    Transitioning,
+}
+
+impl From<LeaseState> for u8 {
+    fn from(state: LeaseState) -> u8 {
+        match state {
+            LeaseState::Available => 1,
+            LeaseState::Active => 2,
+            LeaseState::Expired => 3,
+            LeaseState::Released => 4,
+            LeaseState::Abandoned => 5,
+            LeaseState::Reset => 6,
+            LeaseState::Remote => 7,
+            LeaseState::Transitioning => 8,
+        }
+    }
+}
+
+impl TryFrom<u8> for LeaseState {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<LeaseState, u8> {
+        match value {
+            1 => Ok(LeaseState::Available),
+            2 => Ok(LeaseState::Active),
+            3 => Ok(LeaseState::Expired),
+            4 => Ok(LeaseState::Released),
+            5 => Ok(LeaseState::Abandoned),
+            6 => Ok(LeaseState::Reset),
+            7 => Ok(LeaseState::Remote),
+            8 => Ok(LeaseState::Transitioning),
+            value => Err(value),
+        }
+    }
+}
+
+/// A change in the state of a lease.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeaseEvent {
+    pub ip: Ipv4Addr,
+    pub mac: [u8; 6],
+    pub client_identifier: Option<Vec<u8>>,
+    pub state: LeaseState,
+    // Remaining lease time in seconds, for active leases.
+    pub lease_time: u32,
+    // Time of the change, in seconds since the Unix epoch.
+    pub timestamp: u32,
+}
+
+/// A consumer of the lease changes made by a server.
+pub trait LeaseEventSink {
+    fn lease_event(&mut self, event: &LeaseEvent);
+}
+
+impl LeaseEventSink for Vec<LeaseEvent> {
+    fn lease_event(&mut self, event: &LeaseEvent) {
+        self.push(event.clone());
+    }
+}
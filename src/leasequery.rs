@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+
+use crate::error::DhcpError;
+use crate::framing::encode_frame;
+use crate::lease::{LeaseEvent, LeaseEventSink, LeaseState};
+use crate::raw::{
+    find_option, message_type, new_packet, BOOTREPLY, BOOTREQUEST, CHADDR_OFFSET, CIADDR_OFFSET,
+    XID_OFFSET,
+};
+
+const OPTION_MESSAGE_TYPE: u8 = 53;
+const OPTION_LEASE_TIME: u8 = 51;
+const OPTION_CLIENT_IDENTIFIER: u8 = 61;
+const OPTION_STATUS_CODE: u8 = 151;
+const OPTION_BASE_TIME: u8 = 152;
+const OPTION_QUERY_START_TIME: u8 = 154;
+const OPTION_DHCP_STATE: u8 = 156;
+
+const DHCPLEASEUNASSIGNED: u8 = 11;
+const DHCPLEASEACTIVE: u8 = 13;
+const DHCPLEASEQUERYDONE: u8 = 15;
+const DHCPACTIVELEASEQUERY: u8 = 16;
+const DHCPLEASEQUERYSTATUS: u8 = 17;
+
+/// The status codes of leasequery (RFC 6926, RFC 7724).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeasequeryStatus {
+    Success,
+    UnspecFail,
+    QueryTerminated,
+    MalformedQuery,
+    NotAllowed,
+    DataMissing,
+    ConnectionActive,
+    CatchUpComplete,
+    TlsConnectionRefused,
+    Other(u8),
+}
+
+impl From<u8> for LeasequeryStatus {
+    fn from(value: u8) -> LeasequeryStatus {
+        match value {
+            0 => LeasequeryStatus::Success,
+            1 => LeasequeryStatus::UnspecFail,
+            2 => LeasequeryStatus::QueryTerminated,
+            3 => LeasequeryStatus::MalformedQuery,
+            4 => LeasequeryStatus::NotAllowed,
+            5 => LeasequeryStatus::DataMissing,
+            6 => LeasequeryStatus::ConnectionActive,
+            7 => LeasequeryStatus::CatchUpComplete,
+            8 => LeasequeryStatus::TlsConnectionRefused,
+            value => LeasequeryStatus::Other(value),
+        }
+    }
+}
+
+impl From<LeasequeryStatus> for u8 {
+    fn from(status: LeasequeryStatus) -> u8 {
+        match status {
+            LeasequeryStatus::Success => 0,
+            LeasequeryStatus::UnspecFail => 1,
+            LeasequeryStatus::QueryTerminated => 2,
+            LeasequeryStatus::MalformedQuery => 3,
+            LeasequeryStatus::NotAllowed => 4,
+            LeasequeryStatus::DataMissing => 5,
+            LeasequeryStatus::ConnectionActive => 6,
+            LeasequeryStatus::CatchUpComplete => 7,
+            LeasequeryStatus::TlsConnectionRefused => 8,
+            LeasequeryStatus::Other(value) => value,
+        }
+    }
+}
+
+/// A message received by an active leasequery requestor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LeaseUpdate {
+    Event(LeaseEvent),
+    Status(LeasequeryStatus, String),
+    Done,
+}
+
+/// Build a DHCPACTIVELEASEQUERY message opening a subscription.
+pub fn active_leasequery(xid: u32) -> Vec<u8> {
+    let mut packet = new_packet(BOOTREQUEST, xid);
+    packet.extend_from_slice(&[OPTION_MESSAGE_TYPE, 1, DHCPACTIVELEASEQUERY, 255]);
+    packet
+}
+
+/// Parse a message received by an active leasequery requestor.
+pub fn parse_update(packet: &[u8]) -> Result<LeaseUpdate, DhcpError> {
+    let option = |code| -> Result<Option<&[u8]>, DhcpError> {
+        Ok(find_option(packet, code)?.map(|(start, len)| &packet[start + 2..start + 2 + len]))
+    };
+    let u32_option = |code| -> Result<Option<u32>, DhcpError> {
+        match option(code)? {
+            Some(&[a, b, c, d]) => Ok(Some(u32::from_be_bytes([a, b, c, d]))),
+            Some(_) => Err(DhcpError::ParsingError(
+                format!("Could not parse leasequery option {}", code).into(),
+            )),
+            None => Ok(None),
+        }
+    };
+
+    match message_type(packet)? {
+        Some(DHCPLEASEACTIVE) | Some(DHCPLEASEUNASSIGNED) => {
+            let state = match option(OPTION_DHCP_STATE)? {
+                Some(&[state]) => LeaseState::try_from(state).map_err(|state| {
+                    DhcpError::ParsingError(format!("Unknown DHCP state: {}", state).into())
+                })?,
+                _ => return Err(DhcpError::ParsingError("Could not parse DHCP state".into())),
+            };
+            let mut ip = [0; 4];
+            ip.copy_from_slice(&packet[CIADDR_OFFSET..CIADDR_OFFSET + 4]);
+            let mut mac = [0; 6];
+            mac.copy_from_slice(&packet[CHADDR_OFFSET..CHADDR_OFFSET + 6]);
+
+            Ok(LeaseUpdate::Event(LeaseEvent {
+                ip: Ipv4Addr::from(ip),
+                mac,
+                client_identifier: option(OPTION_CLIENT_IDENTIFIER)?.map(|id| id.to_vec()),
+                state,
+                lease_time: u32_option(OPTION_LEASE_TIME)?.unwrap_or(0),
+                timestamp: u32_option(OPTION_BASE_TIME)?.unwrap_or(0),
+            }))
+        }
+        Some(DHCPLEASEQUERYSTATUS) => match option(OPTION_STATUS_CODE)? {
+            Some(value) if !value.is_empty() => Ok(LeaseUpdate::Status(
+                LeasequeryStatus::from(value[0]),
+                String::from_utf8_lossy(&value[1..]).to_string(),
+            )),
+            _ => Err(DhcpError::ParsingError(
+                "Could not parse leasequery status code".into(),
+            )),
+        },
+        Some(DHCPLEASEQUERYDONE) => Ok(LeaseUpdate::Done),
+        _ => Err(DhcpError::ParsingError(
+            "Not an active leasequery message".into(),
+        )),
+    }
+}
+
+/// An identifier of an active leasequery subscription.
+pub type SubscriptionId = u64;
+
+#[derive(Debug)]
+struct Subscription {
+    xid: u32,
+    output: Vec<u8>,
+}
+
+/// The server side of active leasequery (RFC 7724).
+///
+/// Each accepted DHCPACTIVELEASEQUERY opens a subscription. Lease events
+/// received as a `LeaseEventSink` are encoded for every subscription as
+/// framed DHCPLEASEACTIVE or DHCPLEASEUNASSIGNED messages, which the caller
+/// drains and writes to the matching TCP connection.
+#[derive(Debug, Default)]
+pub struct ActiveLeasequery {
+    subscriptions: HashMap<SubscriptionId, Subscription>,
+    next_id: SubscriptionId,
+}
+
+impl ActiveLeasequery {
+    pub fn new() -> ActiveLeasequery {
+        ActiveLeasequery::default()
+    }
+
+    /// Open a subscription from a DHCPACTIVELEASEQUERY message.
+    ///
+    /// No lease history is kept, so a query asking for the changes made
+    /// since a start time is answered with a DataMissing status before
+    /// updates start flowing.
+    pub fn subscribe(&mut self, request: &[u8]) -> Result<SubscriptionId, DhcpError> {
+        if message_type(request)? != Some(DHCPACTIVELEASEQUERY) {
+            return Err(DhcpError::ParsingError(
+                "Expected a DHCPACTIVELEASEQUERY message".into(),
+            ));
+        }
+        let xid = u32::from_be_bytes([
+            request[XID_OFFSET],
+            request[XID_OFFSET + 1],
+            request[XID_OFFSET + 2],
+            request[XID_OFFSET + 3],
+        ]);
+
+        let mut subscription = Subscription {
+            xid,
+            output: Vec::new(),
+        };
+        if find_option(request, OPTION_QUERY_START_TIME)?.is_some() {
+            let status = status_message(
+                xid,
+                LeasequeryStatus::DataMissing,
+                "lease history unavailable",
+            );
+            subscription
+                .output
+                .extend_from_slice(&encode_frame(&status)?);
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.subscriptions.insert(id, subscription);
+        Ok(id)
+    }
+
+    /// Close a subscription, returning its pending output followed by a
+    /// DHCPLEASEQUERYDONE message.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> Option<Vec<u8>> {
+        let mut subscription = self.subscriptions.remove(&id)?;
+        let mut done = new_packet(BOOTREPLY, subscription.xid);
+        done.extend_from_slice(&[OPTION_MESSAGE_TYPE, 1, DHCPLEASEQUERYDONE, 255]);
+        subscription
+            .output
+            .extend_from_slice(&encode_frame(&done).ok()?);
+        Some(subscription.output)
+    }
+
+    /// Take the data waiting to be written to a subscription's connection.
+    pub fn take_output(&mut self, id: SubscriptionId) -> Vec<u8> {
+        match self.subscriptions.get_mut(&id) {
+            Some(subscription) => std::mem::take(&mut subscription.output),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+}
+
+impl LeaseEventSink for ActiveLeasequery {
+    fn lease_event(&mut self, event: &LeaseEvent) {
+        for subscription in self.subscriptions.values_mut() {
+            let message = lease_message(subscription.xid, event);
+            if let Ok(frame) = encode_frame(&message) {
+                subscription.output.extend_from_slice(&frame);
+            }
+        }
+    }
+}
+
+fn lease_message(xid: u32, event: &LeaseEvent) -> Vec<u8> {
+    let active = event.state == LeaseState::Active;
+    let mut packet = new_packet(BOOTREPLY, xid);
+    packet[CIADDR_OFFSET..CIADDR_OFFSET + 4].copy_from_slice(&event.ip.octets());
+    packet[CHADDR_OFFSET..CHADDR_OFFSET + 6].copy_from_slice(&event.mac);
+
+    let message_type = if active {
+        DHCPLEASEACTIVE
+    } else {
+        DHCPLEASEUNASSIGNED
+    };
+    packet.extend_from_slice(&[OPTION_MESSAGE_TYPE, 1, message_type]);
+    packet.extend_from_slice(&[OPTION_DHCP_STATE, 1, u8::from(event.state)]);
+    packet.extend_from_slice(&[OPTION_BASE_TIME, 4]);
+    packet.extend_from_slice(&event.timestamp.to_be_bytes());
+    if active {
+        packet.extend_from_slice(&[OPTION_LEASE_TIME, 4]);
+        packet.extend_from_slice(&event.lease_time.to_be_bytes());
+    }
+    if let Some(client_identifier) = &event.client_identifier {
+        packet.extend_from_slice(&[OPTION_CLIENT_IDENTIFIER, client_identifier.len() as u8]);
+        packet.extend_from_slice(client_identifier);
+    }
+    packet.push(255);
+    packet
+}
+
+fn status_message(xid: u32, status: LeasequeryStatus, message: &str) -> Vec<u8> {
+    let mut packet = new_packet(BOOTREPLY, xid);
+    packet.extend_from_slice(&[OPTION_MESSAGE_TYPE, 1, DHCPLEASEQUERYSTATUS]);
+    packet.extend_from_slice(&[
+        OPTION_STATUS_CODE,
+        1 + message.len() as u8,
+        u8::from(status),
+    ]);
+    packet.extend_from_slice(message.as_bytes());
+    packet.push(255);
+    packet
+}
@@ -1,2 +1,71 @@
+//! DHCPv4 and DHCPv6 message encoding and decoding.
+//!
+//! The codec modules never read the clock or open sockets: the current
+//! time is passed in by the caller wherever it matters. Only `net`, the
+//! `UdpExchange` of `conformance`, the `ddns` feature, the `engine` of the
+//! `tokio` feature and the `datalink` channels of the `pnet` feature open
+//! sockets, for tools running on a host. All but the last two do so
+//! through `std::net`, whose sockets fail at run time where the target
+//! has none. The crate therefore builds for targets such
+//! as `wasm32-unknown-unknown`, where the `capture` feature can still
+//! decode in-memory captures.
+
+pub mod auth;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod bootp;
+pub mod builder;
+pub mod canonical;
+#[cfg(feature = "capture")]
+pub mod capture;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod codec;
+pub mod conformance;
+pub mod corpus;
+pub mod custom;
+#[cfg(feature = "pnet")]
+pub mod datalink;
+#[cfg(feature = "ddns")]
+pub mod ddns;
+mod display;
+pub mod dns;
+pub mod dualstack;
+pub mod duid;
+#[cfg(feature = "tokio")]
+pub mod engine;
 pub mod error;
+pub mod framing;
+pub mod hardware;
+#[cfg(feature = "heapless")]
+pub mod heapless;
+pub mod hexdump;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod lease;
+pub mod leasequery;
+pub mod message;
+pub mod message_ref;
+pub mod metrics;
+pub mod net;
 pub mod option;
+pub mod option_code;
+pub mod option_ref;
+pub mod options;
+mod raw;
+pub mod relay;
+#[cfg(feature = "json")]
+pub mod replay;
+#[cfg(feature = "serde")]
+mod schema;
+pub mod secs;
+pub mod server;
+pub mod snooping;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+pub mod testing;
+pub mod text;
+mod trace;
+pub mod v6;
+pub mod validation;
+pub mod vendor;
+pub mod xid;
@@ -0,0 +1,1039 @@
+use std::fmt;
+use std::net::Ipv4Addr;
+use std::str::FromStr;
+
+use crate::error::{DhcpError, SerializationError};
+use crate::option::{is_known_code, DhcpOption, OptionOverload};
+use crate::options::{DhcpOptions, DuplicatePolicy};
+use crate::trace::event;
+
+/// The magic cookie starting the options field (RFC 2131 section 3).
+pub const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+
+const OPTION_SERVER_IDENTIFIER: u8 = 54;
+const OPTION_PARAMETER_REQUEST_LIST: u8 = 55;
+const OPTION_MAXIMUM_MESSAGE_SIZE: u8 = 57;
+
+/// The op field of the header (RFC 2131 section 2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OpCode {
+    BootRequest,
+    BootReply,
+}
+
+impl From<OpCode> for u8 {
+    fn from(op: OpCode) -> u8 {
+        match op {
+            OpCode::BootRequest => 1,
+            OpCode::BootReply => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for OpCode {
+    type Error = DhcpError;
+
+    fn try_from(value: u8) -> Result<OpCode, DhcpError> {
+        match value {
+            1 => Ok(OpCode::BootRequest),
+            2 => Ok(OpCode::BootReply),
+            _ => Err(DhcpError::UnknownOpCode(value)),
+        }
+    }
+}
+
+/// The type of a message, sent in the DHCP Message Type option (53, RFC
+/// 2132 section 9.6).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MessageType {
+    Discover,
+    Offer,
+    Request,
+    Decline,
+    Ack,
+    Nak,
+    Release,
+    Inform,
+}
+
+impl MessageType {
+    /// The code of the DHCP Message Type option.
+    pub const OPTION_CODE: u8 = 53;
+}
+
+impl From<MessageType> for u8 {
+    fn from(message_type: MessageType) -> u8 {
+        match message_type {
+            MessageType::Discover => 1,
+            MessageType::Offer => 2,
+            MessageType::Request => 3,
+            MessageType::Decline => 4,
+            MessageType::Ack => 5,
+            MessageType::Nak => 6,
+            MessageType::Release => 7,
+            MessageType::Inform => 8,
+        }
+    }
+}
+
+impl TryFrom<u8> for MessageType {
+    type Error = DhcpError;
+
+    fn try_from(value: u8) -> Result<MessageType, DhcpError> {
+        match value {
+            1 => Ok(MessageType::Discover),
+            2 => Ok(MessageType::Offer),
+            3 => Ok(MessageType::Request),
+            4 => Ok(MessageType::Decline),
+            5 => Ok(MessageType::Ack),
+            6 => Ok(MessageType::Nak),
+            7 => Ok(MessageType::Release),
+            8 => Ok(MessageType::Inform),
+            _ => Err(DhcpError::UnknownMessageType(value)),
+        }
+    }
+}
+
+impl From<MessageType> for DhcpOption {
+    fn from(message_type: MessageType) -> DhcpOption {
+        DhcpOption::DhcpMessageType(message_type)
+    }
+}
+
+/// The flags field of the header (RFC 2131 section 2). Only the leftmost
+/// bit, broadcast, is defined; the others are reserved, and kept as
+/// received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Flags(u16);
+
+impl Flags {
+    /// Set by clients that cannot receive unicast datagrams before their
+    /// address is configured, asking for replies to be broadcast.
+    pub const BROADCAST: u16 = 0x8000;
+
+    pub const fn from_bits(bits: u16) -> Flags {
+        Flags(bits)
+    }
+
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub fn broadcast(&self) -> bool {
+        self.0 & Flags::BROADCAST != 0
+    }
+
+    pub fn set_broadcast(&mut self, broadcast: bool) {
+        match broadcast {
+            true => self.0 |= Flags::BROADCAST,
+            false => self.0 &= !Flags::BROADCAST,
+        }
+    }
+
+    /// The reserved bits, which senders must set to zero.
+    pub fn reserved(&self) -> u16 {
+        self.0 & !Flags::BROADCAST
+    }
+}
+
+impl From<u16> for Flags {
+    fn from(bits: u16) -> Flags {
+        Flags(bits)
+    }
+}
+
+impl From<Flags> for u16 {
+    fn from(flags: Flags) -> u16 {
+        flags.0
+    }
+}
+
+impl fmt::LowerHex for Flags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+/// How options with an unknown code (see `is_known_code`) are handled
+/// while parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownOptions {
+    // Fail with `DhcpError::UnknownOptionCode`.
+    Error,
+    // Drop the option.
+    Skip,
+    // Keep the option as `DhcpOption::Unknown`.
+    #[default]
+    Capture,
+}
+
+/// How text options (12, 14, 15, 17, 18, 40 and 64) whose value is not
+/// valid UTF-8 are handled while parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextPolicy {
+    // Fail with `DhcpError::InvalidUtf8`.
+    #[default]
+    Strict,
+    // Replace the invalid sequences with U+FFFD.
+    Lossy,
+    // Keep the option as `DhcpOption::Unknown`, with its bytes unchanged.
+    Raw,
+}
+
+/// The settings of message parsing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParserConfig {
+    pub unknown_options: UnknownOptions,
+    // Applied to the options once all are parsed, see
+    // `DhcpOptions::dedupe`.
+    pub duplicates: DuplicatePolicy,
+    pub text: TextPolicy,
+}
+
+/// The settings of message serialization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerializerConfig {
+    // Shorter messages are padded with Pad options after the End option,
+    // as relays and BOOTP servers may drop them (RFC 1542 section 2.1).
+    pub min_len: usize,
+    // Longer messages fail with `SerializationError::MessageTooLong`.
+    pub max_len: usize,
+    // Whether options not fitting in `max_len` spill into the sname and
+    // file fields, when those are empty, with an Option Overload option.
+    pub overload: bool,
+    // Whether the options are serialized in the order of
+    // `DhcpOptions::order` rather than their own.
+    pub order: bool,
+}
+
+impl SerializerConfig {
+    /// The settings of a reply to `request`, as long as the Maximum DHCP
+    /// Message Size option of the request allows. Invalid sizes, and those
+    /// below the minimum of 576, are ignored. The options are ordered as
+    /// replies are expected to be.
+    pub fn for_request(request: &DhcpMessage) -> SerializerConfig {
+        let mut config = SerializerConfig {
+            order: true,
+            ..SerializerConfig::default()
+        };
+        if let Ok(Some(size)) = request.max_message_size() {
+            config.max_len = config.max_len.max(size as usize);
+        }
+        config
+    }
+}
+
+impl Default for SerializerConfig {
+    fn default() -> SerializerConfig {
+        SerializerConfig {
+            min_len: DhcpMessage::BOOTP_MIN_LEN,
+            max_len: DhcpMessage::DEFAULT_MAX_LEN,
+            overload: true,
+            order: false,
+        }
+    }
+}
+
+// DHCP Message
+//
+// The fixed BOOTP header, followed by the magic cookie and the options
+// (RFC 2131 section 2).
+//
+//  0                   1                   2                   3
+//  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+// +---------------+---------------+---------------+---------------+
+// |     op (1)    |   htype (1)   |   hlen (1)    |   hops (1)    |
+// +---------------+---------------+---------------+---------------+
+// |                            xid (4)                            |
+// +-------------------------------+-------------------------------+
+// |           secs (2)            |           flags (2)           |
+// +-------------------------------+-------------------------------+
+// |                          ciaddr  (4)                          |
+// +---------------------------------------------------------------+
+// |                          yiaddr  (4)                          |
+// +---------------------------------------------------------------+
+// |                          siaddr  (4)                          |
+// +---------------------------------------------------------------+
+// |                          giaddr  (4)                          |
+// +---------------------------------------------------------------+
+// |                          chaddr  (16)                         |
+// +---------------------------------------------------------------+
+// |                          sname   (64)                         |
+// +---------------------------------------------------------------+
+// |                          file    (128)                        |
+// +---------------------------------------------------------------+
+// |                          options (variable)                   |
+// +---------------------------------------------------------------+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DhcpMessage {
+    pub op: u8,
+    pub htype: u8,
+    pub hlen: u8,
+    pub hops: u8,
+    pub xid: u32,
+    pub secs: u16,
+    pub flags: Flags,
+    pub ciaddr: Ipv4Addr,
+    pub yiaddr: Ipv4Addr,
+    pub siaddr: Ipv4Addr,
+    pub giaddr: Ipv4Addr,
+    pub chaddr: [u8; 16],
+    pub sname: [u8; 64],
+    pub file: [u8; 128],
+    pub options: DhcpOptions,
+}
+
+impl DhcpMessage {
+    /// The length of the fixed header, magic cookie included.
+    pub const HEADER_LEN: usize = 240;
+
+    /// The length of the shortest BOOTP message, with a 64 bytes vend
+    /// field.
+    pub const BOOTP_MIN_LEN: usize = 300;
+
+    /// The length of the longest message every host accepts (RFC 2131
+    /// section 2), unless it sends a Maximum DHCP Message Size option.
+    pub const DEFAULT_MAX_LEN: usize = 576;
+
+    /// Create an empty message for an Ethernet client.
+    pub fn new(op: u8, xid: u32) -> DhcpMessage {
+        DhcpMessage {
+            op,
+            htype: 1,
+            hlen: 6,
+            hops: 0,
+            xid,
+            secs: 0,
+            flags: Flags(0),
+            ciaddr: Ipv4Addr::UNSPECIFIED,
+            yiaddr: Ipv4Addr::UNSPECIFIED,
+            siaddr: Ipv4Addr::UNSPECIFIED,
+            giaddr: Ipv4Addr::UNSPECIFIED,
+            chaddr: [0; 16],
+            sname: [0; 64],
+            file: [0; 128],
+            options: DhcpOptions::new(),
+        }
+    }
+
+    /// Retrieve the first option with the given code.
+    pub fn get_option(&self, code: u8) -> Option<&DhcpOption> {
+        self.options.get(code)
+    }
+
+    /// The type of the message, from its first DHCP Message Type option.
+    /// BOOTP messages have none.
+    pub fn message_type(&self) -> Result<Option<MessageType>, DhcpError> {
+        match self.get_option(MessageType::OPTION_CODE) {
+            Some(DhcpOption::DhcpMessageType(message_type)) => Ok(Some(*message_type)),
+            // Types of later RFCs, such as DHCPFORCERENEW.
+            Some(DhcpOption::Unknown(_, value)) => Err(DhcpError::UnknownMessageType(
+                value.first().copied().unwrap_or_default(),
+            )),
+            _ => Ok(None),
+        }
+    }
+
+    /// The address of the server, from the Server Identifier option: the
+    /// server making an offer, or the one whose offer a DHCPREQUEST
+    /// accepts.
+    pub fn server_identifier(&self) -> Option<Ipv4Addr> {
+        match self.get_option(OPTION_SERVER_IDENTIFIER) {
+            Some(DhcpOption::ServerIdentifier(address)) => Some(*address),
+            _ => None,
+        }
+    }
+
+    /// The fields overloaded with options, from the Option Overload option.
+    pub fn overload(&self) -> Option<OptionOverload> {
+        match self.get_option(52) {
+            Some(DhcpOption::OptionOverload(overload)) => Some(*overload),
+            _ => None,
+        }
+    }
+
+    /// The longest message the sender accepts, from its Maximum DHCP
+    /// Message Size option (57, RFC 2132 section 9.10).
+    pub fn max_message_size(&self) -> Result<Option<u16>, DhcpError> {
+        match self.get_option(OPTION_MAXIMUM_MESSAGE_SIZE) {
+            Some(DhcpOption::Unknown(_, value)) if value.len() == 2 => {
+                Ok(Some(u16::from_be_bytes([value[0], value[1]])))
+            }
+            Some(DhcpOption::Unknown(code, value)) => Err(DhcpError::InvalidLength {
+                code: *code,
+                len: value.len(),
+            }),
+            _ => Ok(None),
+        }
+    }
+
+    /// The codes of the Parameter Request List option, if any.
+    pub fn parameter_request_list(&self) -> Option<&[u8]> {
+        match self.get_option(OPTION_PARAMETER_REQUEST_LIST) {
+            Some(DhcpOption::Unknown(_, codes)) => Some(codes),
+            _ => None,
+        }
+    }
+
+    /// Serialize the message, terminating the options with an End option.
+    pub fn serialize(&self) -> Result<Vec<u8>, DhcpError> {
+        let mut result = Vec::with_capacity(self.wire_len());
+        self.serialize_into(&mut result)?;
+        Ok(result)
+    }
+
+    /// Serialize the message with `config`, failing if it is longer than
+    /// `config.max_len` even with its options spilled into sname and file.
+    pub fn serialize_with(&self, config: &SerializerConfig) -> Result<Vec<u8>, DhcpError> {
+        if config.order {
+            let mut ordered = self.clone();
+            ordered.options.order();
+            if ordered.options != self.options {
+                let config = SerializerConfig {
+                    order: false,
+                    ..config.clone()
+                };
+                return ordered.serialize_with(&config);
+            }
+        }
+        let overloaded = match config.overload && self.wire_len() > config.max_len {
+            true => self.serialize_overloaded(config.max_len)?,
+            false => None,
+        };
+        let mut result = match overloaded {
+            Some(result) => result,
+            None => self.serialize_within(config.max_len)?,
+        };
+        if result.len() < config.min_len {
+            result.resize(config.min_len, 0);
+        }
+        Ok(result)
+    }
+
+    /// Append the serialized message to `result`, as `serialize` does,
+    /// leaving it unchanged on error. Clearing and reusing `result` from
+    /// one message to the next saves allocating each time.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip_all, fields(xid = self.xid), err(level = "debug"))
+    )]
+    pub fn serialize_into(&self, result: &mut Vec<u8>) -> Result<(), DhcpError> {
+        let start = result.len();
+        result.reserve(self.wire_len());
+        self.serialize_header_to(result);
+        // Overloaded fields hold options only, all of which are written to
+        // the options field: they are left with an End option.
+        match self.overload() {
+            Some(overload) => {
+                let mut overloaded = [0; 128];
+                overloaded[0] = 255;
+                let sname = if overload.sname() {
+                    &overloaded[..64]
+                } else {
+                    &self.sname[..]
+                };
+                let file = if overload.file() {
+                    &overloaded[..]
+                } else {
+                    &self.file[..]
+                };
+                result.extend_from_slice(sname);
+                result.extend_from_slice(file);
+            }
+            None => {
+                result.extend_from_slice(&self.sname);
+                result.extend_from_slice(&self.file);
+            }
+        }
+        result.extend_from_slice(&MAGIC_COOKIE);
+        for option in &self.options {
+            if let Err(error) = option.serialize_into(result) {
+                result.truncate(start);
+                return Err(error);
+            }
+        }
+        if self.options.last() != Some(&DhcpOption::End) {
+            result.push(255);
+        }
+        Ok(())
+    }
+
+    // Append the fields up to chaddr to `result`.
+    fn serialize_header_to(&self, result: &mut Vec<u8>) {
+        result.extend_from_slice(&[self.op, self.htype, self.hlen, self.hops]);
+        result.extend_from_slice(&self.xid.to_be_bytes());
+        result.extend_from_slice(&self.secs.to_be_bytes());
+        result.extend_from_slice(&self.flags.bits().to_be_bytes());
+        result.extend_from_slice(&self.ciaddr.octets());
+        result.extend_from_slice(&self.yiaddr.octets());
+        result.extend_from_slice(&self.siaddr.octets());
+        result.extend_from_slice(&self.giaddr.octets());
+        result.extend_from_slice(&self.chaddr);
+    }
+
+    // Serialize the message within `max` bytes, writing the options that
+    // do not fit in the options field to file, then sname (RFC 2131
+    // section 4.1), in order. None if the message already overloads them,
+    // if they are not empty or if the options do not fit anyway.
+    fn serialize_overloaded(&self, max: usize) -> Result<Option<Vec<u8>>, DhcpError> {
+        if self.overload().is_some() || max < DhcpMessage::HEADER_LEN + 4 {
+            return Ok(None);
+        }
+        // The options field, less the Option Overload and End options, and
+        // the fields, less their End option.
+        let mut areas = vec![(max - DhcpMessage::HEADER_LEN - 4, Vec::new())];
+        let file = self.file.iter().all(|byte| *byte == 0);
+        let sname = self.sname.iter().all(|byte| *byte == 0);
+        if file {
+            areas.push((self.file.len() - 1, Vec::new()));
+        }
+        if sname {
+            areas.push((self.sname.len() - 1, Vec::new()));
+        }
+
+        let mut area = 0;
+        let mut option = Vec::new();
+        for dhcp_option in &self.options {
+            if matches!(dhcp_option, DhcpOption::Pad | DhcpOption::End) {
+                continue;
+            }
+            option.clear();
+            dhcp_option.serialize_into(&mut option)?;
+            while area < areas.len() && areas[area].1.len() + option.len() > areas[area].0 {
+                area += 1;
+            }
+            match areas.get_mut(area) {
+                Some((_, bytes)) => bytes.extend_from_slice(&option),
+                None => return Ok(None),
+            }
+        }
+        let mut areas = areas.into_iter().map(|(_, bytes)| bytes);
+        let options = areas.next().unwrap_or_default();
+        let file_options = match file {
+            true => areas.next().filter(|bytes| !bytes.is_empty()),
+            false => None,
+        };
+        let sname_options = match sname {
+            true => areas.next().filter(|bytes| !bytes.is_empty()),
+            false => None,
+        };
+        let overload = match (&file_options, &sname_options) {
+            (Some(_), Some(_)) => OptionOverload::Both,
+            (Some(_), None) => OptionOverload::File,
+            (None, Some(_)) => OptionOverload::Sname,
+            (None, None) => return Ok(None),
+        };
+
+        let mut result = Vec::with_capacity(max);
+        self.serialize_header_to(&mut result);
+        for (field, field_options) in [
+            (&self.sname[..], sname_options),
+            (&self.file[..], file_options),
+        ] {
+            match field_options {
+                Some(mut bytes) => {
+                    bytes.push(255);
+                    bytes.resize(field.len(), 0);
+                    result.extend_from_slice(&bytes);
+                }
+                None => result.extend_from_slice(field),
+            }
+        }
+        result.extend_from_slice(&MAGIC_COOKIE);
+        result.extend_from_slice(&options);
+        DhcpOption::OptionOverload(overload).serialize_into(&mut result)?;
+        result.push(255);
+        Ok(Some(result))
+    }
+
+    /// Serialize a message, failing if it is longer than `max` bytes.
+    pub fn serialize_within(&self, max: usize) -> Result<Vec<u8>, DhcpError> {
+        let len = self.wire_len();
+        if len > max {
+            return Err(DhcpError::SerializationError(
+                SerializationError::MessageTooLong { len, max },
+            ));
+        }
+        self.serialize()
+    }
+
+    /// The length of the serialized message, computed without serializing
+    /// it, before padding to a minimum length or overloading. It sizes the
+    /// buffer of `serialize_into`, and tells whether the options need the
+    /// sname and file fields, in `serialize_with`.
+    pub fn wire_len(&self) -> usize {
+        let end_len = match self.options.last() {
+            Some(DhcpOption::End) => 0,
+            _ => 1,
+        };
+        DhcpMessage::HEADER_LEN
+            + self.options.iter().map(DhcpOption::wire_len).sum::<usize>()
+            + end_len
+    }
+
+    /// Deserialize a message. Pad options are skipped, and parsing stops at
+    /// the End option. The options of the fields overloaded by the Option
+    /// Overload option follow those of the options field.
+    ///
+    /// Errors are wrapped in `DhcpError::Context`, locating the failure in
+    /// the message.
+    pub fn deserialize(data: &[u8]) -> Result<DhcpMessage, DhcpError> {
+        DhcpMessage::deserialize_with(data, &ParserConfig::default())
+    }
+
+    /// Deserialize a message with the settings of `config`.
+    pub fn deserialize_with(data: &[u8], config: &ParserConfig) -> Result<DhcpMessage, DhcpError> {
+        DhcpMessage::parse(data, config, None)
+    }
+
+    /// Deserialize a message, skipping the options that cannot be parsed.
+    ///
+    /// The errors met on the way are returned along with the message. An
+    /// option whose length runs past the end of the data ends the options.
+    /// Only an invalid header fails the whole message.
+    pub fn deserialize_partial(
+        data: &[u8],
+        config: &ParserConfig,
+    ) -> Result<(DhcpMessage, Vec<DhcpError>), DhcpError> {
+        let mut warnings = Vec::new();
+        let message = DhcpMessage::parse(data, config, Some(&mut warnings))?;
+        Ok((message, warnings))
+    }
+
+    // Parse a message, collecting the option errors into `warnings` if
+    // given, or else failing on the first one.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip_all, fields(len = data.len()), err(level = "debug"))
+    )]
+    pub(crate) fn parse(
+        data: &[u8],
+        config: &ParserConfig,
+        mut warnings: Option<&mut Vec<DhcpError>>,
+    ) -> Result<DhcpMessage, DhcpError> {
+        check_header(data)?;
+
+        let address = |offset: usize| {
+            Ipv4Addr::new(
+                data[offset],
+                data[offset + 1],
+                data[offset + 2],
+                data[offset + 3],
+            )
+        };
+        let mut message = DhcpMessage::new(data[0], 0);
+        message.htype = data[1];
+        message.hlen = data[2];
+        message.hops = data[3];
+        message.xid = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+        message.secs = u16::from_be_bytes([data[8], data[9]]);
+        message.flags = Flags(u16::from_be_bytes([data[10], data[11]]));
+        message.ciaddr = address(12);
+        message.yiaddr = address(16);
+        message.siaddr = address(20);
+        message.giaddr = address(24);
+        message.chaddr.copy_from_slice(&data[28..44]);
+        message.sname.copy_from_slice(&data[44..108]);
+        message.file.copy_from_slice(&data[108..236]);
+
+        parse_options(
+            data,
+            DhcpMessage::HEADER_LEN,
+            "options",
+            config,
+            warnings.as_deref_mut(),
+            &mut message.options,
+        )?;
+
+        // Overloaded fields are read after the options field, file first
+        // (RFC 2131 section 4.1). They hold no name, and are cleared.
+        if let Some(overload) = message.overload() {
+            if overload.file() {
+                message.file = [0; 128];
+                parse_options(
+                    &data[..236],
+                    108,
+                    "file",
+                    config,
+                    warnings.as_deref_mut(),
+                    &mut message.options,
+                )?;
+            }
+            if overload.sname() {
+                message.sname = [0; 64];
+                parse_options(
+                    &data[..108],
+                    44,
+                    "sname",
+                    config,
+                    warnings,
+                    &mut message.options,
+                )?;
+            }
+        }
+        message.options.dedupe(config.duplicates);
+
+        event!(
+            trace,
+            xid = message.xid,
+            options = message.options.len(),
+            "parsed message"
+        );
+        Ok(message)
+    }
+
+    /// List the header fields and options differing between this message
+    /// and `other`.
+    ///
+    /// Options are paired by code, in order of appearance, so repeated
+    /// options are compared with their counterpart of the same rank.
+    pub fn diff(&self, other: &DhcpMessage) -> Vec<Difference> {
+        let mut differences = Vec::new();
+
+        let hardware_address = |chaddr: &[u8; 16]| {
+            chaddr
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<String>>()
+                .join(":")
+        };
+        let fields = [
+            ("op", self.op.to_string(), other.op.to_string()),
+            ("htype", self.htype.to_string(), other.htype.to_string()),
+            ("hlen", self.hlen.to_string(), other.hlen.to_string()),
+            ("hops", self.hops.to_string(), other.hops.to_string()),
+            (
+                "xid",
+                format!("0x{:08x}", self.xid),
+                format!("0x{:08x}", other.xid),
+            ),
+            ("secs", self.secs.to_string(), other.secs.to_string()),
+            (
+                "flags",
+                format!("0x{:04x}", self.flags),
+                format!("0x{:04x}", other.flags),
+            ),
+            ("ciaddr", self.ciaddr.to_string(), other.ciaddr.to_string()),
+            ("yiaddr", self.yiaddr.to_string(), other.yiaddr.to_string()),
+            ("siaddr", self.siaddr.to_string(), other.siaddr.to_string()),
+            ("giaddr", self.giaddr.to_string(), other.giaddr.to_string()),
+            (
+                "chaddr",
+                hardware_address(&self.chaddr),
+                hardware_address(&other.chaddr),
+            ),
+            (
+                "sname",
+                format!("{:?}", &self.sname[..]),
+                format!("{:?}", &other.sname[..]),
+            ),
+            (
+                "file",
+                format!("{:?}", &self.file[..]),
+                format!("{:?}", &other.file[..]),
+            ),
+        ];
+        for (name, left, right) in fields {
+            if left != right {
+                differences.push(Difference::Field { name, left, right });
+            }
+        }
+
+        let mut unmatched = other.options.iter().collect::<Vec<&DhcpOption>>();
+        for option in &self.options {
+            let code = option.raw_code();
+            match unmatched
+                .iter()
+                .position(|candidate| candidate.raw_code() == code)
+            {
+                Some(index) => {
+                    let candidate = unmatched.remove(index);
+                    if candidate != option {
+                        differences.push(Difference::OptionChanged {
+                            left: option.clone(),
+                            right: candidate.clone(),
+                        });
+                    }
+                }
+                None => differences.push(Difference::OptionRemoved(option.clone())),
+            }
+        }
+        differences.extend(
+            unmatched
+                .into_iter()
+                .map(|option| Difference::OptionAdded(option.clone())),
+        );
+
+        differences
+    }
+}
+
+impl TryFrom<&[u8]> for DhcpMessage {
+    type Error = DhcpError;
+
+    /// Deserialize a message, see `DhcpMessage::deserialize`.
+    fn try_from(data: &[u8]) -> Result<DhcpMessage, DhcpError> {
+        DhcpMessage::deserialize(data)
+    }
+}
+
+impl TryFrom<&DhcpMessage> for Vec<u8> {
+    type Error = DhcpError;
+
+    /// Serialize a message, see `DhcpMessage::serialize`.
+    fn try_from(message: &DhcpMessage) -> Result<Vec<u8>, DhcpError> {
+        message.serialize()
+    }
+}
+
+impl FromStr for DhcpMessage {
+    type Err = DhcpError;
+
+    /// Deserialize a message from hexadecimal octets, optionally separated
+    /// by whitespace, colons or dashes, as copied from a hexdump.
+    fn from_str(s: &str) -> Result<DhcpMessage, DhcpError> {
+        DhcpMessage::deserialize(&parse_hex(s)?)
+    }
+}
+
+// Parse hexadecimal octets, optionally separated by whitespace, colons or
+// dashes.
+pub(crate) fn parse_hex(s: &str) -> Result<Vec<u8>, DhcpError> {
+    let digits: String = s
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && *c != ':' && *c != '-')
+        .collect();
+    let invalid = || DhcpError::ParsingError("Invalid hexadecimal message".into());
+    if !digits.len().is_multiple_of(2) || !digits.is_ascii() {
+        return Err(invalid());
+    }
+
+    let mut data = Vec::with_capacity(digits.len() / 2);
+    for index in (0..digits.len()).step_by(2) {
+        match u8::from_str_radix(&digits[index..index + 2], 16) {
+            Ok(octet) => data.push(octet),
+            Err(_) => return Err(invalid()),
+        }
+    }
+    Ok(data)
+}
+
+/// A difference between two messages, from the first to the second.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difference {
+    // A header field with different values.
+    Field {
+        name: &'static str,
+        left: String,
+        right: String,
+    },
+    // An option only found in the first message.
+    OptionRemoved(DhcpOption),
+    // An option only found in the second message.
+    OptionAdded(DhcpOption),
+    // An option with a different value in each message.
+    OptionChanged {
+        left: DhcpOption,
+        right: DhcpOption,
+    },
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Difference::Field { name, left, right } => write!(f, "{}: {} -> {}", name, left, right),
+            Difference::OptionRemoved(option) => write!(f, "- {:?}", option),
+            Difference::OptionAdded(option) => write!(f, "+ {:?}", option),
+            Difference::OptionChanged { left, right } => {
+                write!(f, "option {}: {:?} -> {:?}", left.raw_code(), left, right)
+            }
+        }
+    }
+}
+
+/// Deserialize the options field of a message, starting with the magic
+/// cookie. Pad options are skipped, and parsing stops at the End option.
+///
+/// Errors are wrapped in `DhcpError::Context`, located from the start of
+/// the field.
+pub fn deserialize_options(data: &[u8]) -> Result<Vec<DhcpOption>, DhcpError> {
+    if !data.starts_with(&MAGIC_COOKIE) {
+        return Err(DhcpError::Context {
+            offset: 0,
+            field: "options",
+            code: None,
+            error: Box::new(DhcpError::MissingMagicCookie),
+        });
+    }
+    let mut options = DhcpOptions::new();
+    parse_options(
+        data,
+        MAGIC_COOKIE.len(),
+        "options",
+        &ParserConfig::default(),
+        None,
+        &mut options,
+    )?;
+    Ok(options.into())
+}
+
+impl DhcpOption {
+    /// Deserialize a block of options, such as the options field without
+    /// its magic cookie or an overloaded field. Pad options are skipped,
+    /// split options concatenated, and parsing stops at the End option, or
+    /// at the end of `data` without one.
+    ///
+    /// Only Pad options may follow the End option: other data fails with
+    /// a `DhcpError::Context` locating it. Errors are located from the
+    /// start of `data`.
+    pub fn deserialize_all(data: &[u8]) -> Result<Vec<DhcpOption>, DhcpError> {
+        DhcpOption::deserialize_all_with(data, &ParserConfig::default())
+    }
+
+    /// Deserialize a block of options as `deserialize_all` does, following
+    /// `config`.
+    pub fn deserialize_all_with(
+        data: &[u8],
+        config: &ParserConfig,
+    ) -> Result<Vec<DhcpOption>, DhcpError> {
+        let mut options = DhcpOptions::new();
+        let end = parse_options(data, 0, "options", config, None, &mut options)?;
+        if let Some(garbage) = data.iter().skip(end + 1).position(|byte| *byte != 0) {
+            return Err(DhcpError::Context {
+                offset: end + 1 + garbage,
+                field: "options",
+                code: None,
+                error: Box::new(DhcpError::DataAfterEnd),
+            });
+        }
+        options.dedupe(config.duplicates);
+        Ok(options.into())
+    }
+}
+
+// Parse the options of `field`, from `start` to the end of `data`,
+// appending them to `parsed`. Option errors are collected into `warnings`
+// if given, or else fail the parsing. Returns the offset of the End
+// option, or the length of `data` without one.
+fn parse_options(
+    data: &[u8],
+    start: usize,
+    field: &'static str,
+    config: &ParserConfig,
+    mut warnings: Option<&mut Vec<DhcpError>>,
+    parsed: &mut DhcpOptions,
+) -> Result<usize, DhcpError> {
+    let mut options = &data[start..];
+    while !options.is_empty() {
+        let context = |error| DhcpError::Context {
+            offset: data.len() - options.len(),
+            field,
+            code: Some(options[0]),
+            error: Box::new(error),
+        };
+        // Consecutive options of the same code are the parts of a single
+        // one, concatenated before parsing (RFC 3396 section 7).
+        let split = split_option(options);
+        let result = match &split {
+            Some((code, value, rest)) => {
+                DhcpOption::deserialize_value_with(*code, value, config.text)
+                    .map(|option| (option, *rest))
+            }
+            None => DhcpOption::deserialize_with(options, config.text),
+        };
+        let result = result.and_then(|(option, rest)| match option {
+            DhcpOption::Unknown(code, _)
+                if !is_known_code(code) && config.unknown_options == UnknownOptions::Error =>
+            {
+                Err(DhcpError::UnknownOptionCode(code))
+            }
+            option => Ok((option, rest)),
+        });
+        let (option, rest) = match (result, warnings.as_deref_mut()) {
+            (Ok(result), _) => result,
+            (Err(error), None) => return Err(context(error)),
+            (Err(error), Some(warnings)) => {
+                warnings.push(context(error));
+                // Resume after the option when its length fits.
+                if let Some((_, _, rest)) = split {
+                    options = rest;
+                    continue;
+                }
+                match options.get(1) {
+                    Some(len) if options.len() >= 2 + *len as usize => {
+                        options = &options[2 + *len as usize..];
+                        continue;
+                    }
+                    _ => break,
+                }
+            }
+        };
+        match option {
+            DhcpOption::Pad => {}
+            DhcpOption::End => return Ok(data.len() - options.len()),
+            DhcpOption::Unknown(code, _)
+                if !is_known_code(code) && config.unknown_options == UnknownOptions::Skip => {}
+            option => parsed.push(option),
+        }
+        options = rest;
+    }
+    Ok(data.len())
+}
+
+// The code and value of an option split into consecutive parts, and the
+// data following them, or None if `data` does not start with at least two
+// complete parts.
+fn split_option(data: &[u8]) -> Option<(u8, Vec<u8>, &[u8])> {
+    let code = *data.first()?;
+    if code == 0 || code == 255 {
+        return None;
+    }
+    let mut value = Vec::new();
+    let mut parts = 0;
+    let mut rest = data;
+    while let [part_code, len, tail @ ..] = rest {
+        let len = *len as usize;
+        if *part_code != code || tail.len() < len {
+            break;
+        }
+        value.extend_from_slice(&tail[..len]);
+        rest = &tail[len..];
+        parts += 1;
+    }
+    match parts {
+        0 | 1 => None,
+        _ => Some((code, value, rest)),
+    }
+}
+
+// Check that the data holds a header ending with the magic cookie.
+pub(crate) fn check_header(data: &[u8]) -> Result<(), DhcpError> {
+    if data.len() < DhcpMessage::HEADER_LEN {
+        return Err(DhcpError::Context {
+            offset: data.len(),
+            field: "header",
+            code: None,
+            error: Box::new(DhcpError::UnexpectedEof {
+                field: "header",
+                needed: DhcpMessage::HEADER_LEN,
+                available: data.len(),
+            }),
+        });
+    }
+    if data[236..240] != MAGIC_COOKIE {
+        return Err(DhcpError::Context {
+            offset: 236,
+            field: "header",
+            code: None,
+            error: Box::new(DhcpError::MissingMagicCookie),
+        });
+    }
+    Ok(())
+}
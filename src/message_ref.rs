@@ -0,0 +1,232 @@
+//! Messages decoded on access.
+//!
+//! `DhcpMessageRef` checks the header and walks the option boundaries of a
+//! message without decoding any option value: the options are decoded, as
+//! `DhcpOptionRef`, only when they are asked for. A server looking at a few
+//! options of each request, such as the message type, client identifier and
+//! requested address, skips the work of decoding the others.
+//!
+//! Options split into several parts (RFC 3396) are seen part by part, as
+//! concatenating them would allocate: `to_owned` concatenates them.
+
+use std::net::Ipv4Addr;
+
+use crate::error::DhcpError;
+use crate::message::{check_header, DhcpMessage};
+use crate::option_ref::{DhcpOptionRef, OptionsIter};
+
+const OPTION_PAD: u8 = 0;
+const OPTION_END: u8 = 255;
+
+/// A message borrowing its data, its options being decoded on access.
+#[derive(Clone, Copy)]
+pub struct DhcpMessageRef<'a> {
+    data: &'a [u8],
+    // The offset of the End option, or the length of the data.
+    end: usize,
+    // The offset of the first option of each code, or zero for the codes
+    // with no option. Options past `u16::MAX` are not indexed, and
+    // searched for instead.
+    index: [u16; 256],
+}
+
+impl<'a> DhcpMessageRef<'a> {
+    /// Check the header and the option boundaries of a message. Option
+    /// values are not checked until decoded.
+    pub fn deserialize(data: &'a [u8]) -> Result<DhcpMessageRef<'a>, DhcpError> {
+        check_header(data)?;
+
+        let mut index = [0; 256];
+        let mut offset = DhcpMessage::HEADER_LEN;
+        while offset < data.len() {
+            let code = data[offset];
+            match code {
+                OPTION_PAD => {
+                    offset += 1;
+                    continue;
+                }
+                OPTION_END => break,
+                _ => {}
+            }
+            let available = data.len() - offset - 1;
+            let needed = match data.get(offset + 1) {
+                Some(len) => 1 + *len as usize,
+                None => 1,
+            };
+            if available < needed {
+                return Err(DhcpError::Context {
+                    offset,
+                    field: "options",
+                    code: Some(code),
+                    error: Box::new(DhcpError::Truncated {
+                        code,
+                        needed,
+                        available,
+                    }),
+                });
+            }
+            if index[code as usize] == 0 && offset <= u16::MAX as usize {
+                index[code as usize] = offset as u16;
+            }
+            offset += 1 + needed;
+        }
+
+        Ok(DhcpMessageRef {
+            data,
+            end: offset,
+            index,
+        })
+    }
+
+    pub fn op(&self) -> u8 {
+        self.data[0]
+    }
+
+    pub fn htype(&self) -> u8 {
+        self.data[1]
+    }
+
+    pub fn hlen(&self) -> u8 {
+        self.data[2]
+    }
+
+    pub fn hops(&self) -> u8 {
+        self.data[3]
+    }
+
+    pub fn xid(&self) -> u32 {
+        u32::from_be_bytes([self.data[4], self.data[5], self.data[6], self.data[7]])
+    }
+
+    pub fn secs(&self) -> u16 {
+        u16::from_be_bytes([self.data[8], self.data[9]])
+    }
+
+    pub fn flags(&self) -> u16 {
+        u16::from_be_bytes([self.data[10], self.data[11]])
+    }
+
+    pub fn ciaddr(&self) -> Ipv4Addr {
+        self.address(12)
+    }
+
+    pub fn yiaddr(&self) -> Ipv4Addr {
+        self.address(16)
+    }
+
+    pub fn siaddr(&self) -> Ipv4Addr {
+        self.address(20)
+    }
+
+    pub fn giaddr(&self) -> Ipv4Addr {
+        self.address(24)
+    }
+
+    pub fn chaddr(&self) -> &'a [u8] {
+        &self.data[28..44]
+    }
+
+    pub fn sname(&self) -> &'a [u8] {
+        &self.data[44..108]
+    }
+
+    pub fn file(&self) -> &'a [u8] {
+        &self.data[108..236]
+    }
+
+    /// The value of the first option with code `code`, not decoded.
+    pub fn raw_option(&self, code: u8) -> Option<&'a [u8]> {
+        let offset = self.find(code)?;
+        let len = self.data[offset + 1] as usize;
+        Some(&self.data[offset + 2..offset + 2 + len])
+    }
+
+    /// Decode the first option with code `code`.
+    pub fn get_option(&self, code: u8) -> Result<Option<DhcpOptionRef<'a>>, DhcpError> {
+        match self.find(code) {
+            Some(offset) => self.decode(offset).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Decode the options in order, pad options excluded, the parts of
+    /// split options being decoded separately.
+    pub fn options(&self) -> impl Iterator<Item = Result<DhcpOptionRef<'a>, DhcpError>> + '_ {
+        self.offsets().map(|offset| self.decode(offset))
+    }
+
+    /// The options in order, as code and undecoded value, pad options
+    /// excluded.
+    pub fn raw_options(&self) -> OptionsIter<'a> {
+        OptionsIter::new(&self.data[DhcpMessage::HEADER_LEN..self.end])
+    }
+
+    /// Decode the whole message.
+    pub fn to_owned(&self) -> Result<DhcpMessage, DhcpError> {
+        DhcpMessage::deserialize(self.data)
+    }
+
+    // The offsets of the options, pad options excluded.
+    fn offsets(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut offset = DhcpMessage::HEADER_LEN;
+        std::iter::from_fn(move || {
+            while offset < self.end && self.data[offset] == OPTION_PAD {
+                offset += 1;
+            }
+            if offset >= self.end {
+                return None;
+            }
+            let option = offset;
+            offset += 2 + self.data[offset + 1] as usize;
+            Some(option)
+        })
+    }
+
+    // The offset of the first option with code `code`.
+    fn find(&self, code: u8) -> Option<usize> {
+        match self.index[code as usize] {
+            0 if self.end > u16::MAX as usize => self
+                .offsets()
+                .find(|offset| *offset > u16::MAX as usize && self.data[*offset] == code),
+            0 => None,
+            offset => Some(offset as usize),
+        }
+    }
+
+    fn decode(&self, offset: usize) -> Result<DhcpOptionRef<'a>, DhcpError> {
+        // As in `DhcpMessage::deserialize`, the option is read from the
+        // rest of the data.
+        let data: &'a [u8] = self.data;
+        DhcpOptionRef::deserialize(&data[offset..])
+            .map(|(option, _)| option)
+            .map_err(|error| DhcpError::Context {
+                offset,
+                field: "options",
+                code: Some(data[offset]),
+                error: Box::new(error),
+            })
+    }
+
+    fn address(&self, offset: usize) -> Ipv4Addr {
+        Ipv4Addr::new(
+            self.data[offset],
+            self.data[offset + 1],
+            self.data[offset + 2],
+            self.data[offset + 3],
+        )
+    }
+}
+
+impl std::fmt::Debug for DhcpMessageRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let codes = self
+            .offsets()
+            .map(|offset| self.data[offset])
+            .collect::<Vec<u8>>();
+        f.debug_struct("DhcpMessageRef")
+            .field("op", &self.op())
+            .field("xid", &self.xid())
+            .field("options", &codes)
+            .finish()
+    }
+}
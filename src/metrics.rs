@@ -0,0 +1,94 @@
+//! Runtime metrics, and their Prometheus text exposition.
+
+use std::fmt::Write;
+
+/// The type of a metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    // A value that only increases.
+    Counter,
+    // A value that can go up and down.
+    Gauge,
+}
+
+/// A value of a metric, with its labels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub labels: Vec<(&'static str, String)>,
+    pub value: f64,
+}
+
+impl Sample {
+    pub fn new(value: f64) -> Sample {
+        Sample {
+            labels: Vec::new(),
+            value,
+        }
+    }
+
+    pub fn with_label(mut self, name: &'static str, value: &str) -> Sample {
+        self.labels.push((name, value.to_string()));
+        self
+    }
+}
+
+/// A named metric and its current values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metric {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: MetricKind,
+    pub samples: Vec<Sample>,
+}
+
+/// A component exposing metrics, such as a server.
+pub trait Metrics {
+    fn metrics(&self) -> Vec<Metric>;
+}
+
+/// Render metrics in the Prometheus text exposition format, ready to be
+/// served on a scrape endpoint.
+pub fn prometheus<M: Metrics + ?Sized>(source: &M) -> String {
+    let mut result = String::new();
+    for metric in source.metrics() {
+        let kind = match metric.kind {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+        };
+        let _ = writeln!(
+            result,
+            "# HELP {} {}",
+            metric.name,
+            escape(metric.help, false)
+        );
+        let _ = writeln!(result, "# TYPE {} {}", metric.name, kind);
+
+        for sample in &metric.samples {
+            result.push_str(metric.name);
+            if !sample.labels.is_empty() {
+                let labels = sample
+                    .labels
+                    .iter()
+                    .map(|(name, value)| format!("{}=\"{}\"", name, escape(value, true)))
+                    .collect::<Vec<String>>();
+                let _ = write!(result, "{{{}}}", labels.join(","));
+            }
+            let _ = writeln!(result, " {}", sample.value);
+        }
+    }
+    result
+}
+
+// Escape help text, or label values which also escape quotes.
+fn escape(text: &str, quotes: bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '"' if quotes => result.push_str("\\\""),
+            c => result.push(c),
+        }
+    }
+    result
+}
@@ -0,0 +1,520 @@
+//! Setup of the UDP sockets used by DHCP clients, servers and relays.
+//!
+//! DHCP traffic goes to and from addresses the host may not have yet, so
+//! its sockets need options the standard library does not expose:
+//! address and port reuse, binding to an interface, and learning on which
+//! interface and address each datagram arrived. These are only available
+//! on 64-bit Linux with glibc, whose structure layouts are declared here
+//! in the absence of a libc dependency; elsewhere, requesting them fails
+//! with `io::ErrorKind::Unsupported`, see `SOCKET_OPTIONS`.
+
+use std::io;
+use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+use std::time::Duration;
+
+/// Whether address and port reuse, interface binding and packet
+/// information are available on this target.
+pub const SOCKET_OPTIONS: bool = cfg!(all(
+    target_os = "linux",
+    target_env = "gnu",
+    target_pointer_width = "64"
+));
+
+/// The options of a socket.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SocketConfig {
+    // Allow sending to broadcast addresses (SO_BROADCAST).
+    pub broadcast: bool,
+    // Allow binding while another socket uses the address (SO_REUSEADDR).
+    pub reuse_address: bool,
+    // Allow several sockets to bind the same port (SO_REUSEPORT).
+    pub reuse_port: bool,
+    // Only use this interface, such as "eth0" (SO_BINDTODEVICE).
+    pub device: Option<String>,
+    // Report where datagrams were received (IP_PKTINFO), see
+    // `recv_with_info`.
+    pub packet_info: bool,
+    pub read_timeout: Option<Duration>,
+}
+
+/// Where a datagram was received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketInfo {
+    // The index of the receiving interface.
+    pub interface: u32,
+    // The local address the datagram was routed to.
+    pub local: Ipv4Addr,
+    // The destination address of the datagram, possibly broadcast.
+    pub destination: Ipv4Addr,
+}
+
+/// Open a UDP socket with the options of `config`, bound to `address`.
+pub fn bind(address: SocketAddrV4, config: &SocketConfig) -> io::Result<UdpSocket> {
+    let socket = sys::bind(address, config)?;
+    socket.set_broadcast(config.broadcast)?;
+    socket.set_read_timeout(config.read_timeout)?;
+    Ok(socket)
+}
+
+/// Receive a datagram, with where it was received if the socket was set
+/// up with `packet_info`.
+pub fn recv_with_info(
+    socket: &UdpSocket,
+    buffer: &mut [u8],
+) -> io::Result<(usize, SocketAddrV4, Option<PacketInfo>)> {
+    sys::recv_with_info(socket, buffer)
+}
+
+/// Buffers receiving several datagrams with a single system call, see
+/// `RecvBatch::recv`.
+#[derive(Debug)]
+pub struct RecvBatch {
+    // The largest datagram received whole.
+    size: usize,
+    data: Vec<u8>,
+    // The length and source of each datagram received by the last call.
+    received: Vec<(usize, SocketAddrV4)>,
+}
+
+impl RecvBatch {
+    /// Buffers for up to `count` datagrams of `size` bytes.
+    pub fn new(count: usize, size: usize) -> RecvBatch {
+        let size = size.max(1);
+        RecvBatch {
+            size,
+            data: vec![0; count * size],
+            received: Vec::with_capacity(count),
+        }
+    }
+
+    /// Receive the datagrams available, waiting for the first one only,
+    /// and return their number. On Linux, this takes a single system call
+    /// (recvmmsg); elsewhere, a single datagram is received.
+    pub fn recv(&mut self, socket: &UdpSocket) -> io::Result<usize> {
+        self.received.clear();
+        sys::recv_batch(socket, self)?;
+        Ok(self.received.len())
+    }
+
+    /// The datagrams received by the last call, with their source.
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8], SocketAddrV4)> {
+        self.data
+            .chunks_exact(self.size)
+            .zip(&self.received)
+            .map(|(buffer, (len, source))| (&buffer[..*len], *source))
+    }
+
+    pub fn len(&self) -> usize {
+        self.received.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.received.is_empty()
+    }
+}
+
+/// Send datagrams, with a single system call (sendmmsg) per batch the
+/// kernel accepts on Linux. Returns the number sent, all of them unless an
+/// error occurs after some were.
+pub fn send_batch(socket: &UdpSocket, datagrams: &[(&[u8], SocketAddrV4)]) -> io::Result<usize> {
+    let mut sent = 0;
+    while sent < datagrams.len() {
+        match sys::send_batch(socket, &datagrams[sent..]) {
+            Ok(0) => break,
+            Ok(count) => sent += count,
+            Err(error) if sent == 0 => return Err(error),
+            Err(_) => break,
+        }
+    }
+    Ok(sent)
+}
+
+// The structures below follow the layout of glibc on 64-bit targets, with
+// `size_t` lengths in `msghdr` and `cmsghdr`.
+#[cfg(all(target_os = "linux", target_env = "gnu", target_pointer_width = "64"))]
+mod sys {
+    use std::io;
+    use std::mem;
+    use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+    use std::os::raw::{c_int, c_void};
+
+    use super::{PacketInfo, RecvBatch, SocketConfig};
+
+    const AF_INET: c_int = 2;
+    const SOCK_DGRAM: c_int = 2;
+    const SOCK_CLOEXEC: c_int = 0o2000000;
+    const SOL_SOCKET: c_int = 1;
+    const SO_REUSEADDR: c_int = 2;
+    const SO_REUSEPORT: c_int = 15;
+    const SO_BINDTODEVICE: c_int = 25;
+    const IPPROTO_IP: c_int = 0;
+    const IP_PKTINFO: c_int = 8;
+    const MSG_WAITFORONE: c_int = 0x10000;
+
+    #[repr(C)]
+    struct SockaddrIn {
+        sin_family: u16,
+        sin_port: u16,
+        sin_addr: [u8; 4],
+        sin_zero: [u8; 8],
+    }
+
+    #[repr(C)]
+    struct Iovec {
+        iov_base: *mut c_void,
+        iov_len: usize,
+    }
+
+    #[repr(C)]
+    struct Msghdr {
+        msg_name: *mut c_void,
+        msg_namelen: u32,
+        msg_iov: *mut Iovec,
+        msg_iovlen: usize,
+        msg_control: *mut c_void,
+        msg_controllen: usize,
+        msg_flags: c_int,
+    }
+
+    #[repr(C)]
+    struct Mmsghdr {
+        msg_hdr: Msghdr,
+        msg_len: u32,
+    }
+
+    #[repr(C)]
+    struct Cmsghdr {
+        cmsg_len: usize,
+        cmsg_level: c_int,
+        cmsg_type: c_int,
+    }
+
+    #[repr(C)]
+    struct InPktinfo {
+        ipi_ifindex: c_int,
+        ipi_spec_dst: [u8; 4],
+        ipi_addr: [u8; 4],
+    }
+
+    extern "C" {
+        fn socket(domain: c_int, kind: c_int, protocol: c_int) -> c_int;
+        fn setsockopt(
+            fd: c_int,
+            level: c_int,
+            name: c_int,
+            value: *const c_void,
+            len: u32,
+        ) -> c_int;
+        #[link_name = "bind"]
+        fn bind_socket(fd: c_int, address: *const SockaddrIn, len: u32) -> c_int;
+        fn recvmsg(fd: c_int, message: *mut Msghdr, flags: c_int) -> isize;
+        fn recvmmsg(
+            fd: c_int,
+            messages: *mut Mmsghdr,
+            len: u32,
+            flags: c_int,
+            timeout: *mut c_void,
+        ) -> c_int;
+        fn sendmmsg(fd: c_int, messages: *mut Mmsghdr, len: u32, flags: c_int) -> c_int;
+    }
+
+    fn empty_address() -> SockaddrIn {
+        SockaddrIn {
+            sin_family: 0,
+            sin_port: 0,
+            sin_addr: [0; 4],
+            sin_zero: [0; 8],
+        }
+    }
+
+    fn sockaddr(address: &SocketAddrV4) -> SockaddrIn {
+        SockaddrIn {
+            sin_family: AF_INET as u16,
+            sin_port: address.port().to_be(),
+            sin_addr: address.ip().octets(),
+            sin_zero: [0; 8],
+        }
+    }
+
+    fn message_header(name: &mut SockaddrIn, iov: &mut Iovec) -> Mmsghdr {
+        Mmsghdr {
+            msg_hdr: Msghdr {
+                msg_name: name as *mut SockaddrIn as *mut c_void,
+                msg_namelen: mem::size_of::<SockaddrIn>() as u32,
+                msg_iov: iov,
+                msg_iovlen: 1,
+                msg_control: std::ptr::null_mut(),
+                msg_controllen: 0,
+                msg_flags: 0,
+            },
+            msg_len: 0,
+        }
+    }
+
+    fn check(result: c_int) -> io::Result<()> {
+        match result {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+
+    fn set_flag(fd: RawFd, level: c_int, name: c_int) -> io::Result<()> {
+        let value: c_int = 1;
+        // SAFETY: the value outlives the call and its size is passed along.
+        check(unsafe {
+            setsockopt(
+                fd,
+                level,
+                name,
+                &value as *const c_int as *const c_void,
+                mem::size_of::<c_int>() as u32,
+            )
+        })
+    }
+
+    pub(super) fn bind(address: SocketAddrV4, config: &SocketConfig) -> io::Result<UdpSocket> {
+        // SAFETY: socket takes no pointers, and the descriptor it returns
+        // is owned from then on.
+        let fd = unsafe { socket(AF_INET, SOCK_DGRAM | SOCK_CLOEXEC, 0) };
+        if fd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the descriptor was just opened and is not owned elsewhere.
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        let raw = fd.as_raw_fd();
+
+        if config.reuse_address {
+            set_flag(raw, SOL_SOCKET, SO_REUSEADDR)?;
+        }
+        if config.reuse_port {
+            set_flag(raw, SOL_SOCKET, SO_REUSEPORT)?;
+        }
+        if config.packet_info {
+            set_flag(raw, IPPROTO_IP, IP_PKTINFO)?;
+        }
+        if let Some(device) = &config.device {
+            // SAFETY: the name outlives the call and its length is passed
+            // along.
+            check(unsafe {
+                setsockopt(
+                    raw,
+                    SOL_SOCKET,
+                    SO_BINDTODEVICE,
+                    device.as_ptr() as *const c_void,
+                    device.len() as u32,
+                )
+            })?;
+        }
+
+        let address = sockaddr(&address);
+        // SAFETY: the address outlives the call and its size is passed
+        // along.
+        check(unsafe { bind_socket(raw, &address, mem::size_of::<SockaddrIn>() as u32) })?;
+
+        Ok(UdpSocket::from(fd))
+    }
+
+    pub(super) fn recv_with_info(
+        socket: &UdpSocket,
+        buffer: &mut [u8],
+    ) -> io::Result<(usize, SocketAddrV4, Option<PacketInfo>)> {
+        let mut source = empty_address();
+        let mut iov = Iovec {
+            iov_base: buffer.as_mut_ptr() as *mut c_void,
+            iov_len: buffer.len(),
+        };
+        // Aligned for the control message headers.
+        let mut control = [0u64; 8];
+        let mut message = Msghdr {
+            msg_name: &mut source as *mut SockaddrIn as *mut c_void,
+            msg_namelen: mem::size_of::<SockaddrIn>() as u32,
+            msg_iov: &mut iov,
+            msg_iovlen: 1,
+            msg_control: control.as_mut_ptr() as *mut c_void,
+            msg_controllen: mem::size_of_val(&control),
+            msg_flags: 0,
+        };
+
+        // SAFETY: every pointer in the message refers to a live buffer of
+        // the length given next to it.
+        let len = unsafe { recvmsg(socket.as_raw_fd(), &mut message, 0) };
+        if len < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let source = SocketAddrV4::new(
+            Ipv4Addr::from(source.sin_addr),
+            u16::from_be(source.sin_port),
+        );
+
+        let mut info = None;
+        let align = mem::size_of::<usize>();
+        let header_len = mem::size_of::<Cmsghdr>().next_multiple_of(align);
+        let mut offset = 0;
+        while offset + header_len <= message.msg_controllen {
+            // SAFETY: the header lies within the control buffer, at an
+            // offset aligned by the kernel.
+            let header =
+                unsafe { &*((control.as_ptr() as *const u8).add(offset) as *const Cmsghdr) };
+            if header.cmsg_len < header_len {
+                break;
+            }
+            if header.cmsg_level == IPPROTO_IP
+                && header.cmsg_type == IP_PKTINFO
+                && header.cmsg_len >= header_len + mem::size_of::<InPktinfo>()
+            {
+                // SAFETY: the kernel wrote an in_pktinfo after the header,
+                // as checked with its length.
+                let pktinfo = unsafe {
+                    &*((control.as_ptr() as *const u8).add(offset + header_len) as *const InPktinfo)
+                };
+                info = Some(PacketInfo {
+                    interface: pktinfo.ipi_ifindex as u32,
+                    local: Ipv4Addr::from(pktinfo.ipi_spec_dst),
+                    destination: Ipv4Addr::from(pktinfo.ipi_addr),
+                });
+            }
+            offset += header.cmsg_len.next_multiple_of(align);
+        }
+
+        Ok((len as usize, source, info))
+    }
+
+    pub(super) fn recv_batch(socket: &UdpSocket, batch: &mut RecvBatch) -> io::Result<()> {
+        let count = batch.data.len() / batch.size;
+        let mut sources = (0..count).map(|_| empty_address()).collect::<Vec<_>>();
+        let mut iovs = batch
+            .data
+            .chunks_exact_mut(batch.size)
+            .map(|buffer| Iovec {
+                iov_base: buffer.as_mut_ptr() as *mut c_void,
+                iov_len: buffer.len(),
+            })
+            .collect::<Vec<_>>();
+        let mut messages = sources
+            .iter_mut()
+            .zip(&mut iovs)
+            .map(|(source, iov)| message_header(source, iov))
+            .collect::<Vec<_>>();
+
+        // SAFETY: every header points to a live address and buffer of the
+        // length given next to it.
+        let received = unsafe {
+            recvmmsg(
+                socket.as_raw_fd(),
+                messages.as_mut_ptr(),
+                messages.len() as u32,
+                MSG_WAITFORONE,
+                std::ptr::null_mut(),
+            )
+        };
+        if received < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        for (message, source) in messages.iter().zip(&sources).take(received as usize) {
+            let source = SocketAddrV4::new(
+                Ipv4Addr::from(source.sin_addr),
+                u16::from_be(source.sin_port),
+            );
+            batch.received.push((message.msg_len as usize, source));
+        }
+        Ok(())
+    }
+
+    pub(super) fn send_batch(
+        socket: &UdpSocket,
+        datagrams: &[(&[u8], SocketAddrV4)],
+    ) -> io::Result<usize> {
+        let mut destinations = datagrams
+            .iter()
+            .map(|(_, destination)| sockaddr(destination))
+            .collect::<Vec<_>>();
+        // The kernel does not write through the buffers of sent messages.
+        let mut iovs = datagrams
+            .iter()
+            .map(|(data, _)| Iovec {
+                iov_base: data.as_ptr() as *mut c_void,
+                iov_len: data.len(),
+            })
+            .collect::<Vec<_>>();
+        let mut messages = destinations
+            .iter_mut()
+            .zip(&mut iovs)
+            .map(|(destination, iov)| message_header(destination, iov))
+            .collect::<Vec<_>>();
+
+        // SAFETY: every header points to a live address and buffer of the
+        // length given next to it.
+        let sent = unsafe {
+            sendmmsg(
+                socket.as_raw_fd(),
+                messages.as_mut_ptr(),
+                messages.len() as u32,
+                0,
+            )
+        };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(sent as usize)
+    }
+}
+
+#[cfg(not(all(target_os = "linux", target_env = "gnu", target_pointer_width = "64")))]
+mod sys {
+    use std::io;
+    use std::net::{SocketAddr, SocketAddrV4, UdpSocket};
+
+    use super::{PacketInfo, RecvBatch, SocketConfig};
+
+    pub(super) fn bind(address: SocketAddrV4, config: &SocketConfig) -> io::Result<UdpSocket> {
+        if config.reuse_address
+            || config.reuse_port
+            || config.device.is_some()
+            || config.packet_info
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "socket option not supported on this platform",
+            ));
+        }
+        UdpSocket::bind(address)
+    }
+
+    pub(super) fn recv_with_info(
+        socket: &UdpSocket,
+        buffer: &mut [u8],
+    ) -> io::Result<(usize, SocketAddrV4, Option<PacketInfo>)> {
+        match socket.recv_from(buffer)? {
+            (len, SocketAddr::V4(source)) => Ok((len, source, None)),
+            (_, SocketAddr::V6(_)) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "IPv6 source on an IPv4 socket",
+            )),
+        }
+    }
+
+    pub(super) fn recv_batch(socket: &UdpSocket, batch: &mut RecvBatch) -> io::Result<()> {
+        let size = batch.size;
+        if let Some(buffer) = batch.data.get_mut(..size) {
+            let (len, source, _) = recv_with_info(socket, buffer)?;
+            batch.received.push((len, source));
+        }
+        Ok(())
+    }
+
+    pub(super) fn send_batch(
+        socket: &UdpSocket,
+        datagrams: &[(&[u8], SocketAddrV4)],
+    ) -> io::Result<usize> {
+        for (sent, (data, destination)) in datagrams.iter().enumerate() {
+            if let Err(error) = socket.send_to(data, destination) {
+                return match sent {
+                    0 => Err(error),
+                    _ => Ok(sent),
+                };
+            }
+        }
+        Ok(datagrams.len())
+    }
+}
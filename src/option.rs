@@ -1,9 +1,15 @@
+use std::borrow::Cow;
 use std::net::Ipv4Addr;
 use std::str::from_utf8;
 
-use crate::error::DhcpError;
+use crate::dns;
+use crate::error::{DhcpError, SerializationError};
+use crate::message::{MessageType, TextPolicy};
+use crate::option_code::OptionCode;
+use crate::text::Kind;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum DhcpOption {
     // Pad Option
     //
@@ -72,7 +78,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // |  3  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    Router(Vec<Ipv4Addr>),
+    Router(Addresses),
     // Time Server Option
     //
     // The time server option specifies a list of RFC 868 time servers
@@ -87,7 +93,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // |  4  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    TimeServer(Vec<Ipv4Addr>),
+    TimeServer(Addresses),
     // Name Server Option
     //
     // The name server option specifies a list of IEN 116 name servers
@@ -102,7 +108,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // |  5  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    NameServer(Vec<Ipv4Addr>),
+    NameServer(Addresses),
     // Domain Name Server Option
     //
     // The domain name server option specifies a list of Domain Name System
@@ -117,7 +123,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // |  6  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    DomainNameServer(Vec<Ipv4Addr>),
+    DomainNameServer(Addresses),
     // Log Server Option
     //
     // The log server option specifies a list of MIT-LCS UDP log servers
@@ -131,7 +137,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // |  7  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    LogServer(Vec<Ipv4Addr>),
+    LogServer(Addresses),
     // Cookie Server Option
     //
     // The cookie server option specifies a list of RFC 865 cookie
@@ -145,7 +151,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // |  8  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    CookieServer(Vec<Ipv4Addr>),
+    CookieServer(Addresses),
     // LPR Server Option
     //
     // The LPR server option specifies a list of RFC 1179 line printer
@@ -159,7 +165,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // |  9  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    LprServer(Vec<Ipv4Addr>),
+    LprServer(Addresses),
     // Impress Server Option
     //
     // The Impress server option specifies a list of Imagen Impress servers
@@ -174,7 +180,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // |  10 |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    ImpressServer(Vec<Ipv4Addr>),
+    ImpressServer(Addresses),
     // Resource Location Server Option
     //
     // This option specifies a list of RFC 887 Resource Location
@@ -188,7 +194,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // |  11 |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    ResourceLocationServer(Vec<Ipv4Addr>),
+    ResourceLocationServer(Addresses),
     // Host Name Option
     //
     // This option specifies the name of the client. The name may or may
@@ -618,7 +624,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // |  41 |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    NetworkInformationServers(Vec<Ipv4Addr>),
+    NetworkInformationServers(Addresses),
     // Network Time Protocol Servers Option
     //
     // This option specifies a list of IP addresses indicating NTP
@@ -632,7 +638,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // |  42 |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    NetworkTimeProtocolServers(Vec<Ipv4Addr>),
+    NetworkTimeProtocolServers(Addresses),
     // Vendor Specific Information
     //
     // This option is used by clients and servers to exchange vendor-
@@ -694,7 +700,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+-----+-----+----
     // |  44 |  n  |  a1 |  a2 |  a3 |  a4 |  b1 |  b2 |  b3 |  b4 | ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+-----+-----+----
-    NetBiosOverTcpIpNameServer(Vec<Ipv4Addr>),
+    NetBiosOverTcpIpNameServer(Addresses),
     // NetBIOS over TCP/IP Datagram Distribution Server Option
     //
     // The NetBIOS datagram distribution server (NBDD) option specifies a
@@ -706,7 +712,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+-----+-----+----
     // |  45 |  n  |  a1 |  a2 |  a3 |  a4 |  b1 |  b2 |  b3 |  b4 | ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+-----+-----+----
-    NetBiosOverTcpIpDatagramDistributionServer(Vec<Ipv4Addr>),
+    NetBiosOverTcpIpDatagramDistributionServer(Addresses),
     // NetBIOS over TCP/IP Node Type Option
     //
     // The NetBIOS node type option allows NetBIOS over TCP/IP clients which
@@ -758,7 +764,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+---
     // |  48 |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |   ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+---
-    XWindowSystemFontServer(Vec<Ipv4Addr>),
+    XWindowSystemFontServer(Addresses),
     // X Window System Display Manager Option
     //
     // This option specifies a list of IP addresses of systems that are
@@ -774,7 +780,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+---
     // |  49 |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |   ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+---
-    XWindowSystemDisplayManager(Vec<Ipv4Addr>),
+    XWindowSystemDisplayManager(Addresses),
     // Network Information Service+ Domain Option
     //
     // This option specifies the name of the client's NIS+ domain. The
@@ -801,7 +807,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // |  65 |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    NetworkInformationServicePlusServers(Vec<Ipv4Addr>),
+    NetworkInformationServicePlusServers(Addresses),
     // Mobile IP Home Agent option
     //
     // This option specifies a list of IP addresses indicating mobile IP
@@ -817,7 +823,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+--
     // | 68  |  n  | a1  | a2  | a3  | a4  | ...
     // +-----+-----+-----+-----+-----+-----+--
-    MobileIpHomeAgent(Vec<Ipv4Addr>),
+    MobileIpHomeAgent(Addresses),
     // Simple Mail Transport Protocol (SMTP) Server Option
     //
     // The SMTP server option specifies a list of SMTP servers available to
@@ -831,7 +837,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // | 69  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    SimpleMailTransportProtocolServer(Vec<Ipv4Addr>),
+    SimpleMailTransportProtocolServer(Addresses),
     // Post Office Protocol (POP3) Server Option
     //
     // The POP3 server option specifies a list of POP3 available to the
@@ -845,7 +851,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // | 70  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    PostOfficeProtocolServer(Vec<Ipv4Addr>),
+    PostOfficeProtocolServer(Addresses),
     // Network News Transport Protocol (NNTP) Server Option
     //
     // The NNTP server option specifies a list of NNTP available to the
@@ -859,7 +865,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // | 71  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    NetworkNewsTransportProtocolServer(Vec<Ipv4Addr>),
+    NetworkNewsTransportProtocolServer(Addresses),
     // Default World Wide Web (WWW) Server Option
     //
     // The WWW server option specifies a list of WWW available to the
@@ -873,7 +879,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // | 72  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    DefaultWorldWideWebServer(Vec<Ipv4Addr>),
+    DefaultWorldWideWebServer(Addresses),
     // Default Finger Server Option
     //
     // The Finger server option specifies a list of Finger available to the
@@ -887,7 +893,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // | 73  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    DefaultFingerServer(Vec<Ipv4Addr>),
+    DefaultFingerServer(Addresses),
     // Default Internet Relay Chat (IRC) Server Option
     //
     // The IRC server option specifies a list of IRC available to the
@@ -901,7 +907,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // | 74  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    DefaultInternetRelayChatServer(Vec<Ipv4Addr>),
+    DefaultInternetRelayChatServer(Addresses),
     // StreetTalk Server Option
     //
     // The StreetTalk server option specifies a list of StreetTalk servers
@@ -912,7 +918,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // | 75  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    StreetTalkServer(Vec<Ipv4Addr>),
+    StreetTalkServer(Addresses),
     // StreetTalk Directory Assistance (STDA) Server Option
 
     // The StreetTalk Directory Assistance (STDA) server option specifies a
@@ -927,7 +933,7 @@ pub enum DhcpOption {
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
     // | 76  |  n  |  a1 |  a2 |  a3 |  a4 |  a1 |  a2 |  ...
     // +-----+-----+-----+-----+-----+-----+-----+-----+--
-    StreetTalkDirectoryAssistanceServer(Vec<Ipv4Addr>),
+    StreetTalkDirectoryAssistanceServer(Addresses),
     // Requested IP Address
     //
     // This option is used in a client request (DHCPDISCOVER) to allow the
@@ -957,177 +963,542 @@ pub enum DhcpOption {
     // |  51 |  4  |  t1 |  t2 |  t3 |  t4 |
     // +-----+-----+-----+-----+-----+-----+
     IpAddressLeaseTime(u32),
+    // Option Overload
+    //
+    // This option is used to indicate that the DHCP 'sname' or 'file'
+    // fields are being overloaded by using them to carry DHCP options. A
+    // DHCP server inserts this option if the returned parameters will
+    // exceed the usual space allotted for options.
+    //
+    // If this option is present, the client interprets the specified
+    // additional fields after it concludes interpretation of the standard
+    // option fields.
+    //
+    // The code for this option is 52, and its length is 1. Legal values
+    // for this option are 1 (the 'file' field is used to hold options), 2
+    // (the 'sname' field is used to hold options) and 3 (both fields are
+    // used to hold options).
+    //
+    //  Code   Len  Value
+    // +-----+-----+-----+
+    // |  52 |  1  |1/2/3|
+    // +-----+-----+-----+
+    OptionOverload(OptionOverload),
+    // DHCP Message Type
+    //
+    // This option is used to convey the type of the DHCP message. The code
+    // for this option is 53, and its length is 1. Legal values for this
+    // option are:
+    //
+    //         Value   Message Type
+    //         -----   ------------
+    //           1     DHCPDISCOVER
+    //           2     DHCPOFFER
+    //           3     DHCPREQUEST
+    //           4     DHCPDECLINE
+    //           5     DHCPACK
+    //           6     DHCPNAK
+    //           7     DHCPRELEASE
+    //           8     DHCPINFORM
+    //
+    //  Code   Len  Type
+    // +-----+-----+-----+
+    // |  53 |  1  | 1-8 |
+    // +-----+-----+-----+
+    //
+    // Types defined by later RFCs, such as DHCPFORCERENEW or the
+    // leasequery messages, are kept as `DhcpOption::Unknown`.
+    DhcpMessageType(MessageType),
+    // Server Identifier
+    //
+    // This option is used in DHCPOFFER and DHCPREQUEST messages, and may
+    // optionally be included in the DHCPACK and DHCPNAK messages. DHCP
+    // servers include this option in the DHCPOFFER in order to allow the
+    // client to distinguish between lease offers. DHCP clients use the
+    // contents of the 'server identifier' field as the destination address
+    // for any DHCP messages unicast to the DHCP server. DHCP clients also
+    // indicate which of several lease offers is being accepted by including
+    // this option in a DHCPREQUEST message.
+    //
+    // The identifier is the IP address of the selected server.
+    //
+    // The code for this option is 54, and its length is 4.
+    //
+    //  Code   Len            Address
+    // +-----+-----+-----+-----+-----+-----+
+    // |  54 |  4  |  a1 |  a2 |  a3 |  a4 |
+    // +-----+-----+-----+-----+-----+-----+
+    ServerIdentifier(Ipv4Addr),
+    // Client-identifier
+    //
+    // This option is used by DHCP clients to specify their unique
+    // identifier. DHCP servers use this value to index their database of
+    // address bindings. This value is expected to be unique for all
+    // clients in an administrative domain.
+    //
+    // The client identifier MAY consist of type-value pairs similar to the
+    // 'htype'/'chaddr' fields defined in [3]. For instance, it MAY consist
+    // of a hardware type and hardware address. In this case the type field
+    // SHOULD be one of the ARP hardware types defined in STD2 [22]. A
+    // hardware type of 0 (zero) should be used when the value field
+    // contains an identifier other than a hardware address (e.g. a fully
+    // qualified domain name).
+    //
+    // The code for this option is 61, and its minimum length is 2.
+    //
+    //  Code   Len   Type  Client-Identifier
+    // +-----+-----+-----+-----+-----+---
+    // |  61 |  n  |  t1 |  i1 |  i2 | ...
+    // +-----+-----+-----+-----+-----+---
+    ClientIdentifier(Vec<u8>),
+    // Domain Search Option
+    //
+    // This option specifies a list of domain names to be used by the
+    // client to locate not-fully-qualified domain names. The list is
+    // encoded as a sequence of DNS names in wire format, and MAY use DNS
+    // compression pointers relative to the start of the option data
+    // (RFC 3397).
+    //
+    // The code for this option is 119.
+    //
+    //  Code  Len         Searchstring
+    // +-----+-----+-----+-----+-----+-----+-----+--
+    // | 119 | Len |  s1 |  s2 |  s3 |  s4 |  s5 | ...
+    // +-----+-----+-----+-----+-----+-----+-----+--
+    DomainSearch(Vec<String>),
+    // Authentication Option
+    //
+    // This option carries the information needed to authenticate the
+    // identity and contents of a DHCP message (RFC 3118). The content of
+    // the authentication information field depends on the protocol and
+    // algorithm in use.
+    //
+    // The code for this option is 90, and its minimum length is 11.
+    //
+    //  0                   1                   2                   3
+    //  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |     Code      |    Length     |  Protocol     |   Algorithm   |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |     RDM       | Replay Detection (64 bits)                    |
+    // +-+-+-+-+-+-+-+-+                                               +
+    // | Replay cont.                                                  |
+    // +-+-+-+-+-+-+-+-+                                               +
+    // | Replay cont.  |                                               |
+    // +-+-+-+-+-+-+-+-+                                               |
+    // |                                                               |
+    // |           Authentication Information                          |
+    // |                                                               |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    Authentication(Authentication),
+    // Forcerenew Nonce Capable Option
+    //
+    // This option is sent by clients supporting the forcerenew nonce
+    // authentication protocol (RFC 6704), listing the algorithms they can
+    // use to authenticate FORCERENEW messages.
+    //
+    // The code for this option is 145, and its minimum length is 1.
+    //
+    //  Code  Len  Algorithm 1  Algorithm 2
+    // +-----+-----+-----+-----+---
+    // | 145 |  n  |  a1 |  a2 | ...
+    // +-----+-----+-----+-----+---
+    ForcerenewNonceCapable(Vec<u8>),
+    // Any option this crate does not decode, kept as raw bytes.
+    Unknown(u8, Vec<u8>),
 }
 
 impl DhcpOption {
-    pub fn serialize(&self) -> Vec<u8> {
+    /// Serialize an option. Values longer than 255 bytes are split into
+    /// several options of the same code, as RFC 3396 requires.
+    ///
+    /// Fails if the value is empty where the option format requires one,
+    /// such as a router option without addresses.
+    pub fn serialize(&self) -> Result<Vec<u8>, DhcpError> {
+        let mut result = Vec::with_capacity(self.wire_len());
+        self.serialize_into(&mut result)?;
+        Ok(result)
+    }
+
+    /// The code of the option.
+    pub fn code(&self) -> OptionCode {
+        OptionCode::from(self.raw_code())
+    }
+
+    /// The code of the option, as a byte.
+    pub(crate) fn raw_code(&self) -> u8 {
         match self {
-            DhcpOption::Pad => vec![0],
-            DhcpOption::End => vec![255],
+            DhcpOption::Pad => 0,
+            DhcpOption::SubnetMask(..) => 1,
+            DhcpOption::TimeOffset(..) => 2,
+            DhcpOption::Router(..) => 3,
+            DhcpOption::TimeServer(..) => 4,
+            DhcpOption::NameServer(..) => 5,
+            DhcpOption::DomainNameServer(..) => 6,
+            DhcpOption::LogServer(..) => 7,
+            DhcpOption::CookieServer(..) => 8,
+            DhcpOption::LprServer(..) => 9,
+            DhcpOption::ImpressServer(..) => 10,
+            DhcpOption::ResourceLocationServer(..) => 11,
+            DhcpOption::HostName(..) => 12,
+            DhcpOption::BootFileSize(..) => 13,
+            DhcpOption::MeritDumpFile(..) => 14,
+            DhcpOption::DomainName(..) => 15,
+            DhcpOption::SwapServer(..) => 16,
+            DhcpOption::RootPath(..) => 17,
+            DhcpOption::ExtensionsPath(..) => 18,
+            DhcpOption::IpForwarding(..) => 19,
+            DhcpOption::NonLocalSourceRouting(..) => 20,
+            DhcpOption::PolicyFilter(..) => 21,
+            DhcpOption::MaximumDatagramReassemblySize(..) => 22,
+            DhcpOption::DefaultIpTimeToLive(..) => 23,
+            DhcpOption::PathMtuAgingTimeout(..) => 24,
+            DhcpOption::PathMtuPlateauTable(..) => 25,
+            DhcpOption::InterfaceMtu(..) => 26,
+            DhcpOption::AllSubnetsAreLocal(..) => 27,
+            DhcpOption::BroadcastAddress(..) => 28,
+            DhcpOption::PerformMaskDiscovery(..) => 29,
+            DhcpOption::MaskSupplier(..) => 30,
+            DhcpOption::PerformRouterDiscovery(..) => 31,
+            DhcpOption::RouterSolicitationAddress(..) => 32,
+            DhcpOption::StaticRoute(..) => 33,
+            DhcpOption::TrailerEncapsulation(..) => 34,
+            DhcpOption::ArpCacheTimeout(..) => 35,
+            DhcpOption::EthernetEncapsulation(..) => 36,
+            DhcpOption::TcpDefaultTtl(..) => 37,
+            DhcpOption::TcpKeepaliveInterval(..) => 38,
+            DhcpOption::TcpKeepaliveGarbage(..) => 39,
+            DhcpOption::NetworkInformationServiceDomain(..) => 40,
+            DhcpOption::NetworkInformationServers(..) => 41,
+            DhcpOption::NetworkTimeProtocolServers(..) => 42,
+            DhcpOption::VendorSpecificInformation(..) => 43,
+            DhcpOption::NetBiosOverTcpIpNameServer(..) => 44,
+            DhcpOption::NetBiosOverTcpIpDatagramDistributionServer(..) => 45,
+            DhcpOption::NetBiosOverTcpIpNodeType(..) => 46,
+            DhcpOption::NetBiosOverTcpIpScope(..) => 47,
+            DhcpOption::XWindowSystemFontServer(..) => 48,
+            DhcpOption::XWindowSystemDisplayManager(..) => 49,
+            DhcpOption::RequestedIpAddress(..) => 50,
+            DhcpOption::IpAddressLeaseTime(..) => 51,
+            DhcpOption::OptionOverload(..) => 52,
+            DhcpOption::DhcpMessageType(..) => 53,
+            DhcpOption::ServerIdentifier(..) => 54,
+            DhcpOption::ClientIdentifier(..) => 61,
+            DhcpOption::NetworkInformationServicePlusDomain(..) => 64,
+            DhcpOption::NetworkInformationServicePlusServers(..) => 65,
+            DhcpOption::MobileIpHomeAgent(..) => 68,
+            DhcpOption::SimpleMailTransportProtocolServer(..) => 69,
+            DhcpOption::PostOfficeProtocolServer(..) => 70,
+            DhcpOption::NetworkNewsTransportProtocolServer(..) => 71,
+            DhcpOption::DefaultWorldWideWebServer(..) => 72,
+            DhcpOption::DefaultFingerServer(..) => 73,
+            DhcpOption::DefaultInternetRelayChatServer(..) => 74,
+            DhcpOption::StreetTalkServer(..) => 75,
+            DhcpOption::StreetTalkDirectoryAssistanceServer(..) => 76,
+            DhcpOption::Authentication(..) => 90,
+            DhcpOption::DomainSearch(..) => 119,
+            DhcpOption::ForcerenewNonceCapable(..) => 145,
+            DhcpOption::End => 255,
+            DhcpOption::Unknown(code, _) => *code,
+        }
+    }
+
+    /// Append the serialized option to `result`, as `serialize` does,
+    /// leaving it unchanged on error. Reusing `result` from one option to
+    /// the next saves allocating each time.
+    pub fn serialize_into(&self, result: &mut Vec<u8>) -> Result<(), DhcpError> {
+        if let DhcpOption::DomainSearch(names) = self {
+            for name in names {
+                dns::check_name(name).map_err(|error| match error {
+                    DhcpError::SerializationError(SerializationError::ValueTooLong {
+                        len, ..
+                    }) => DhcpError::SerializationError(SerializationError::ValueTooLong {
+                        code: 119,
+                        len,
+                    }),
+                    error => error,
+                })?;
+            }
+        }
+        let start = result.len();
+        self.encode_into(result);
+        let (code, len) = match &result[start..] {
+            [_] => return Ok(()),
+            [code, ..] => (*code, result.len() - start - 2),
+            [] => unreachable!(),
+        };
+        // Only some options may have an empty value, such as the list of
+        // Mobile IP home agents. Pad and end have none at all.
+        if len == 0 && info(code).is_some_and(|info| info.len.min_len() != 0) {
+            result.truncate(start);
+            return Err(DhcpError::SerializationError(
+                SerializationError::MissingValue { code },
+            ));
+        }
+        if let Err(error) = self.check_value(code, &result[start + 2..]) {
+            result.truncate(start);
+            return Err(DhcpError::SerializationError(error));
+        }
+        // Values longer than 255 bytes are split into consecutive options
+        // of the same code (RFC 3396 section 5).
+        if len > 255 {
+            let value = result.split_off(start + 2);
+            result.truncate(start);
+            for part in value.chunks(255) {
+                result.extend_from_slice(&[code, part.len() as u8]);
+                result.extend_from_slice(part);
+            }
+        }
+        Ok(())
+    }
+
+    // Check the serialized `value` of the option against the length rule
+    // of `code`, so that no option is sent that a receiver would reject.
+    // Unknown options may only hold values the known option of the same
+    // code cannot represent, such as message types newer than this crate
+    // or text that is not UTF-8: pad and end never have a value.
+    fn check_value(&self, code: u8, value: &[u8]) -> Result<(), SerializationError> {
+        let len = value.len();
+        let rule = info(code).map_or(LengthRule::Min(0), |info| info.len);
+        if rule == LengthRule::Empty || !rule.allows(len) {
+            return Err(SerializationError::InvalidLength { code, len });
+        }
+        if !matches!(self, DhcpOption::Unknown(..)) || info(code).is_none() {
+            return Ok(());
+        }
+        match DhcpOption::deserialize_value(code, value) {
+            Ok(DhcpOption::Unknown(..)) | Err(_) => Ok(()),
+            Ok(_) => Err(SerializationError::KnownCode { code }),
+        }
+    }
+
+    /// The length of the serialized option, its code and length included,
+    /// computed without serializing it. Values longer than 255 bytes take
+    /// a code and length for each part of 255 bytes.
+    pub fn wire_len(&self) -> usize {
+        match self {
+            DhcpOption::Pad | DhcpOption::End => 1,
+            _ => {
+                let value_len = self.value_len();
+                value_len + 2 * value_len.div_ceil(255).max(1)
+            }
+        }
+    }
+
+    // The length of the value of the option, 0 for Pad and End.
+    pub(crate) fn value_len(&self) -> usize {
+        match self {
+            DhcpOption::Pad | DhcpOption::End => 0,
+            DhcpOption::SubnetMask(_)
+            | DhcpOption::TimeOffset(_)
+            | DhcpOption::SwapServer(_)
+            | DhcpOption::PathMtuAgingTimeout(_)
+            | DhcpOption::BroadcastAddress(_)
+            | DhcpOption::RouterSolicitationAddress(_)
+            | DhcpOption::ArpCacheTimeout(_)
+            | DhcpOption::TcpKeepaliveInterval(_)
+            | DhcpOption::RequestedIpAddress(_)
+            | DhcpOption::ServerIdentifier(_)
+            | DhcpOption::IpAddressLeaseTime(_) => 4,
+            DhcpOption::BootFileSize(_)
+            | DhcpOption::MaximumDatagramReassemblySize(_)
+            | DhcpOption::InterfaceMtu(_) => 2,
+            DhcpOption::IpForwarding(_)
+            | DhcpOption::NonLocalSourceRouting(_)
+            | DhcpOption::DefaultIpTimeToLive(_)
+            | DhcpOption::AllSubnetsAreLocal(_)
+            | DhcpOption::PerformMaskDiscovery(_)
+            | DhcpOption::MaskSupplier(_)
+            | DhcpOption::PerformRouterDiscovery(_)
+            | DhcpOption::TrailerEncapsulation(_)
+            | DhcpOption::EthernetEncapsulation(_)
+            | DhcpOption::TcpDefaultTtl(_)
+            | DhcpOption::TcpKeepaliveGarbage(_)
+            | DhcpOption::NetBiosOverTcpIpNodeType(_)
+            | DhcpOption::OptionOverload(_)
+            | DhcpOption::DhcpMessageType(_) => 1,
+            DhcpOption::Router(addresses)
+            | DhcpOption::TimeServer(addresses)
+            | DhcpOption::NameServer(addresses)
+            | DhcpOption::DomainNameServer(addresses)
+            | DhcpOption::LogServer(addresses)
+            | DhcpOption::CookieServer(addresses)
+            | DhcpOption::LprServer(addresses)
+            | DhcpOption::ImpressServer(addresses)
+            | DhcpOption::ResourceLocationServer(addresses)
+            | DhcpOption::NetworkInformationServers(addresses)
+            | DhcpOption::NetworkTimeProtocolServers(addresses)
+            | DhcpOption::NetBiosOverTcpIpNameServer(addresses)
+            | DhcpOption::NetBiosOverTcpIpDatagramDistributionServer(addresses)
+            | DhcpOption::XWindowSystemFontServer(addresses)
+            | DhcpOption::XWindowSystemDisplayManager(addresses)
+            | DhcpOption::NetworkInformationServicePlusServers(addresses)
+            | DhcpOption::MobileIpHomeAgent(addresses)
+            | DhcpOption::SimpleMailTransportProtocolServer(addresses)
+            | DhcpOption::PostOfficeProtocolServer(addresses)
+            | DhcpOption::NetworkNewsTransportProtocolServer(addresses)
+            | DhcpOption::DefaultWorldWideWebServer(addresses)
+            | DhcpOption::DefaultFingerServer(addresses)
+            | DhcpOption::DefaultInternetRelayChatServer(addresses)
+            | DhcpOption::StreetTalkServer(addresses)
+            | DhcpOption::StreetTalkDirectoryAssistanceServer(addresses) => addresses.len() * 4,
+            DhcpOption::PolicyFilter(pairs) | DhcpOption::StaticRoute(pairs) => pairs.len() * 8,
+            DhcpOption::PathMtuPlateauTable(sizes) => sizes.len() * 2,
+            DhcpOption::HostName(value)
+            | DhcpOption::MeritDumpFile(value)
+            | DhcpOption::DomainName(value)
+            | DhcpOption::RootPath(value)
+            | DhcpOption::ExtensionsPath(value)
+            | DhcpOption::NetworkInformationServiceDomain(value)
+            | DhcpOption::NetworkInformationServicePlusDomain(value) => value.len(),
+            DhcpOption::VendorSpecificInformation(value)
+            | DhcpOption::NetBiosOverTcpIpScope(value)
+            | DhcpOption::ClientIdentifier(value)
+            | DhcpOption::ForcerenewNonceCapable(value)
+            | DhcpOption::Unknown(_, value) => value.len(),
+            DhcpOption::DomainSearch(names) => {
+                names.iter().map(|name| dns::encoded_name_len(name)).sum()
+            }
+            DhcpOption::Authentication(authentication) => 11 + authentication.information.len(),
+        }
+    }
+
+    // Encode an option into a new buffer, see `encode_into`.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.wire_len());
+        self.encode_into(&mut result);
+        result
+    }
+
+    // Encode an option without checking its value, the length byte being
+    // wrong for values longer than 255 bytes.
+    pub(crate) fn encode_into(&self, result: &mut Vec<u8>) {
+        match self {
+            DhcpOption::Pad => result.push(0),
+            DhcpOption::End => result.push(255),
             DhcpOption::SubnetMask(subnet_mask) => {
-                let mut result = Vec::new();
                 result.push(1);
                 result.push(4);
                 result.extend_from_slice(&subnet_mask.octets());
-                result
-            }
-            DhcpOption::TimeOffset(time_offset) => {
-                let mut result = Vec::new();
-                result.push(2);
-                result.push(4);
-                result.push(((time_offset >> 24) & 0xFF) as u8);
-                result.push(((time_offset >> 16) & 0xFF) as u8);
-                result.push(((time_offset >> 8) & 0xFF) as u8);
-                result.push((time_offset & 0xFF) as u8);
-                result
             }
+            DhcpOption::TimeOffset(time_offset) => result.extend_from_slice(&[
+                2,
+                4,
+                ((time_offset >> 24) & 0xFF) as u8,
+                ((time_offset >> 16) & 0xFF) as u8,
+                ((time_offset >> 8) & 0xFF) as u8,
+                (time_offset & 0xFF) as u8,
+            ]),
             DhcpOption::Router(routers) => {
-                let mut result = Vec::new();
                 result.push(3);
                 result.push((routers.len() * 4) as u8);
                 for router in routers {
                     result.extend_from_slice(&router.octets());
                 }
-                result
             }
             DhcpOption::TimeServer(time_servers) => {
-                let mut result = Vec::new();
                 result.push(4);
                 result.push((time_servers.len() * 4) as u8);
                 for time_server in time_servers {
                     result.extend_from_slice(&time_server.octets());
                 }
-                result
             }
             DhcpOption::NameServer(name_servers) => {
-                let mut result = Vec::new();
                 result.push(5);
                 result.push((name_servers.len() * 4) as u8);
                 for name_server in name_servers {
                     result.extend_from_slice(&name_server.octets());
                 }
-                result
             }
             DhcpOption::DomainNameServer(domain_name_servers) => {
-                let mut result = Vec::new();
                 result.push(6);
                 result.push((domain_name_servers.len() * 4) as u8);
                 for domain_name_server in domain_name_servers {
                     result.extend_from_slice(&domain_name_server.octets());
                 }
-                result
             }
             DhcpOption::LogServer(log_servers) => {
-                let mut result = Vec::new();
                 result.push(7);
                 result.push((log_servers.len() * 4) as u8);
                 for log_server in log_servers {
                     result.extend_from_slice(&log_server.octets());
                 }
-                result
             }
             DhcpOption::CookieServer(cookie_servers) => {
-                let mut result = Vec::new();
                 result.push(8);
                 result.push((cookie_servers.len() * 4) as u8);
                 for cookie_server in cookie_servers {
                     result.extend_from_slice(&cookie_server.octets());
                 }
-                result
             }
             DhcpOption::LprServer(lpr_servers) => {
-                let mut result = Vec::new();
                 result.push(9);
                 result.push((lpr_servers.len() * 4) as u8);
                 for lpr_server in lpr_servers {
                     result.extend_from_slice(&lpr_server.octets());
                 }
-                result
             }
             DhcpOption::ImpressServer(impress_servers) => {
-                let mut result = Vec::new();
                 result.push(10);
                 result.push((impress_servers.len() * 4) as u8);
                 for impress_server in impress_servers {
                     result.extend_from_slice(&impress_server.octets());
                 }
-                result
             }
             DhcpOption::ResourceLocationServer(resource_location_servers) => {
-                let mut result = Vec::new();
                 result.push(11);
                 result.push((resource_location_servers.len() * 4) as u8);
                 for resource_location_server in resource_location_servers {
                     result.extend_from_slice(&resource_location_server.octets());
                 }
-                result
             }
             DhcpOption::HostName(host_name) => {
-                let mut result = Vec::new();
                 result.push(12);
                 result.push(host_name.len() as u8);
                 result.extend_from_slice(host_name.as_bytes());
-                result
-            }
-            DhcpOption::BootFileSize(boot_file_size) => {
-                let mut result = Vec::new();
-                result.push(13);
-                result.push(2);
-                result.push(((boot_file_size >> 8) & 0xFF) as u8);
-                result.push((boot_file_size & 0xFF) as u8);
-                result
             }
+            DhcpOption::BootFileSize(boot_file_size) => result.extend_from_slice(&[
+                13,
+                2,
+                ((boot_file_size >> 8) & 0xFF) as u8,
+                (boot_file_size & 0xFF) as u8,
+            ]),
             DhcpOption::MeritDumpFile(merit_dump_file) => {
-                let mut result = Vec::new();
                 result.push(14);
                 result.push(merit_dump_file.len() as u8);
                 result.extend_from_slice(merit_dump_file.as_bytes());
-                result
             }
             DhcpOption::DomainName(domain_name) => {
-                let mut result = Vec::new();
                 result.push(15);
                 result.push(domain_name.len() as u8);
                 result.extend_from_slice(domain_name.as_bytes());
-                result
             }
             DhcpOption::SwapServer(swap_server) => {
-                let mut result = Vec::new();
                 result.push(16);
                 result.push(4);
                 result.extend_from_slice(&swap_server.octets());
-                result
             }
             DhcpOption::RootPath(root_path) => {
-                let mut result = Vec::new();
                 result.push(17);
                 result.push(root_path.len() as u8);
                 result.extend_from_slice(root_path.as_bytes());
-                result
             }
             DhcpOption::ExtensionsPath(extensions_path) => {
-                let mut result = Vec::new();
                 result.push(18);
                 result.push(extensions_path.len() as u8);
                 result.extend_from_slice(extensions_path.as_bytes());
-                result
             }
             DhcpOption::IpForwarding(ip_forwarding) => {
-                let mut result = Vec::new();
                 result.push(19);
                 result.push(1);
                 result.push(if *ip_forwarding { 1 } else { 0 });
-                result
             }
             DhcpOption::NonLocalSourceRouting(non_local_source_routing) => {
-                let mut result = Vec::new();
                 result.push(20);
                 result.push(1);
                 result.push(if *non_local_source_routing { 1 } else { 0 });
-                result
             }
             DhcpOption::PolicyFilter(policy_filter) => {
-                let mut result = Vec::new();
                 result.push(21);
                 result.push((policy_filter.len() * 8) as u8);
                 for policy_filter in policy_filter {
@@ -1140,95 +1511,70 @@ impl DhcpOption {
                     result.push(policy_filter.1.octets()[2]);
                     result.push(policy_filter.1.octets()[3]);
                 }
-                result
-            }
-            DhcpOption::MaximumDatagramReassemblySize(maximum_datagram_reassembly_size) => {
-                let mut result = Vec::new();
-                result.push(22);
-                result.push(2);
-                result.push(((maximum_datagram_reassembly_size >> 8) & 0xFF) as u8);
-                result.push((maximum_datagram_reassembly_size & 0xFF) as u8);
-                result
             }
+            DhcpOption::MaximumDatagramReassemblySize(maximum_datagram_reassembly_size) => result
+                .extend_from_slice(&[
+                    22,
+                    2,
+                    ((maximum_datagram_reassembly_size >> 8) & 0xFF) as u8,
+                    (maximum_datagram_reassembly_size & 0xFF) as u8,
+                ]),
             DhcpOption::DefaultIpTimeToLive(default_ip_ttl) => {
-                let mut result = Vec::new();
-                result.push(23);
-                result.push(1);
-                result.push(*default_ip_ttl);
-                result
-            }
-            DhcpOption::PathMtuAgingTimeout(path_mtu_aging_timeout) => {
-                let mut result = Vec::new();
-                result.push(24);
-                result.push(4);
-                result.push(((path_mtu_aging_timeout >> 24) & 0xFF) as u8);
-                result.push(((path_mtu_aging_timeout >> 16) & 0xFF) as u8);
-                result.push(((path_mtu_aging_timeout >> 8) & 0xFF) as u8);
-                result.push((path_mtu_aging_timeout & 0xFF) as u8);
-                result
-            }
+                result.extend_from_slice(&[23, 1, *default_ip_ttl])
+            }
+            DhcpOption::PathMtuAgingTimeout(path_mtu_aging_timeout) => result.extend_from_slice(&[
+                24,
+                4,
+                ((path_mtu_aging_timeout >> 24) & 0xFF) as u8,
+                ((path_mtu_aging_timeout >> 16) & 0xFF) as u8,
+                ((path_mtu_aging_timeout >> 8) & 0xFF) as u8,
+                (path_mtu_aging_timeout & 0xFF) as u8,
+            ]),
             DhcpOption::PathMtuPlateauTable(path_mtu_plateau_table) => {
-                let mut result = Vec::new();
                 result.push(25);
                 result.push((path_mtu_plateau_table.len() * 2) as u8);
                 for path_mtu_plateau in path_mtu_plateau_table {
                     result.push(((path_mtu_plateau >> 8) & 0xFF) as u8);
                     result.push((path_mtu_plateau & 0xFF) as u8);
                 }
-                result
-            }
-            DhcpOption::InterfaceMtu(interface_mtu) => {
-                let mut result = Vec::new();
-                result.push(26);
-                result.push(2);
-                result.push(((interface_mtu >> 8) & 0xFF) as u8);
-                result.push((interface_mtu & 0xFF) as u8);
-                result
             }
+            DhcpOption::InterfaceMtu(interface_mtu) => result.extend_from_slice(&[
+                26,
+                2,
+                ((interface_mtu >> 8) & 0xFF) as u8,
+                (interface_mtu & 0xFF) as u8,
+            ]),
             DhcpOption::AllSubnetsAreLocal(all_subnets_are_local) => {
-                let mut result = Vec::new();
                 result.push(27);
                 result.push(1);
                 result.push(if *all_subnets_are_local { 1 } else { 0 });
-                result
             }
             DhcpOption::BroadcastAddress(broadcast_address) => {
-                let mut result = Vec::new();
                 result.push(28);
                 result.push(4);
                 result.extend_from_slice(&broadcast_address.octets());
-                result
             }
             DhcpOption::PerformMaskDiscovery(perform_mask_discovery) => {
-                let mut result = Vec::new();
                 result.push(29);
                 result.push(1);
                 result.push(if *perform_mask_discovery { 1 } else { 0 });
-                result
             }
             DhcpOption::MaskSupplier(mask_supplier) => {
-                let mut result = Vec::new();
                 result.push(30);
                 result.push(1);
                 result.push(if *mask_supplier { 1 } else { 0 });
-                result
             }
             DhcpOption::PerformRouterDiscovery(perform_router_discovery) => {
-                let mut result = Vec::new();
                 result.push(31);
                 result.push(1);
                 result.push(if *perform_router_discovery { 1 } else { 0 });
-                result
             }
             DhcpOption::RouterSolicitationAddress(router_solicitation_address) => {
-                let mut result = Vec::new();
                 result.push(32);
                 result.push(4);
                 result.extend_from_slice(&router_solicitation_address.octets());
-                result
             }
             DhcpOption::StaticRoute(static_route) => {
-                let mut result = Vec::new();
                 result.push(33);
                 result.push((static_route.len() * 8) as u8);
                 for static_route in static_route {
@@ -1241,65 +1587,49 @@ impl DhcpOption {
                     result.push(static_route.1.octets()[2]);
                     result.push(static_route.1.octets()[3]);
                 }
-                result
             }
             DhcpOption::TrailerEncapsulation(trailer_encapsulation) => {
-                let mut result = Vec::new();
                 result.push(34);
                 result.push(1);
                 result.push(if *trailer_encapsulation { 1 } else { 0 });
-                result
-            }
-            DhcpOption::ArpCacheTimeout(arp_cache_timeout) => {
-                let mut result = Vec::new();
-                result.push(35);
-                result.push(4);
-                result.push(((arp_cache_timeout >> 24) & 0xFF) as u8);
-                result.push(((arp_cache_timeout >> 16) & 0xFF) as u8);
-                result.push(((arp_cache_timeout >> 8) & 0xFF) as u8);
-                result.push((arp_cache_timeout & 0xFF) as u8);
-                result
             }
+            DhcpOption::ArpCacheTimeout(arp_cache_timeout) => result.extend_from_slice(&[
+                35,
+                4,
+                ((arp_cache_timeout >> 24) & 0xFF) as u8,
+                ((arp_cache_timeout >> 16) & 0xFF) as u8,
+                ((arp_cache_timeout >> 8) & 0xFF) as u8,
+                (arp_cache_timeout & 0xFF) as u8,
+            ]),
             DhcpOption::EthernetEncapsulation(ethernet_encapsulation) => {
-                let mut result = Vec::new();
                 result.push(36);
                 result.push(1);
                 result.push(if *ethernet_encapsulation { 1 } else { 0 });
-                result
             }
             DhcpOption::TcpDefaultTtl(tcp_default_ttl) => {
-                let mut result = Vec::new();
-                result.push(37);
-                result.push(1);
-                result.push(*tcp_default_ttl);
-                result
+                result.extend_from_slice(&[37, 1, *tcp_default_ttl])
             }
             DhcpOption::TcpKeepaliveInterval(tcp_keepalive_interval) => {
-                let mut result = Vec::new();
-                result.push(38);
-                result.push(4);
-                result.push(((tcp_keepalive_interval >> 24) & 0xFF) as u8);
-                result.push(((tcp_keepalive_interval >> 16) & 0xFF) as u8);
-                result.push(((tcp_keepalive_interval >> 8) & 0xFF) as u8);
-                result.push((tcp_keepalive_interval & 0xFF) as u8);
-                result
+                result.extend_from_slice(&[
+                    38,
+                    4,
+                    ((tcp_keepalive_interval >> 24) & 0xFF) as u8,
+                    ((tcp_keepalive_interval >> 16) & 0xFF) as u8,
+                    ((tcp_keepalive_interval >> 8) & 0xFF) as u8,
+                    (tcp_keepalive_interval & 0xFF) as u8,
+                ])
             }
             DhcpOption::TcpKeepaliveGarbage(tcp_keepalive_garbage) => {
-                let mut result = Vec::new();
                 result.push(39);
                 result.push(1);
                 result.push(if *tcp_keepalive_garbage { 1 } else { 0 });
-                result
             }
             DhcpOption::NetworkInformationServiceDomain(network_information_service_domain) => {
-                let mut result = Vec::new();
                 result.push(40);
                 result.push(network_information_service_domain.len() as u8);
                 result.extend_from_slice(network_information_service_domain.as_bytes());
-                result
             }
             DhcpOption::NetworkInformationServers(network_information_servers) => {
-                let mut result = Vec::new();
                 result.push(41);
                 result.push((network_information_servers.len() * 4) as u8);
                 for network_information_server in network_information_servers {
@@ -1308,10 +1638,8 @@ impl DhcpOption {
                     result.push(network_information_server.octets()[2]);
                     result.push(network_information_server.octets()[3]);
                 }
-                result
             }
             DhcpOption::NetworkTimeProtocolServers(network_time_protocol_servers) => {
-                let mut result = Vec::new();
                 result.push(42);
                 result.push((network_time_protocol_servers.len() * 4) as u8);
                 for network_time_protocol_server in network_time_protocol_servers {
@@ -1320,17 +1648,13 @@ impl DhcpOption {
                     result.push(network_time_protocol_server.octets()[2]);
                     result.push(network_time_protocol_server.octets()[3]);
                 }
-                result
             }
             DhcpOption::VendorSpecificInformation(vendor_specific_information) => {
-                let mut result = Vec::new();
                 result.push(43);
                 result.push(vendor_specific_information.len() as u8);
                 result.extend_from_slice(vendor_specific_information);
-                result
             }
             DhcpOption::NetBiosOverTcpIpNameServer(netbios_over_tcpip_name_server) => {
-                let mut result = Vec::new();
                 result.push(44);
                 result.push((netbios_over_tcpip_name_server.len() * 4) as u8);
                 for netbios_over_tcpip_name_server in netbios_over_tcpip_name_server {
@@ -1339,12 +1663,10 @@ impl DhcpOption {
                     result.push(netbios_over_tcpip_name_server.octets()[2]);
                     result.push(netbios_over_tcpip_name_server.octets()[3]);
                 }
-                result
             }
             DhcpOption::NetBiosOverTcpIpDatagramDistributionServer(
                 netbios_over_tcpip_datagram_distribution_server,
             ) => {
-                let mut result = Vec::new();
                 result.push(45);
                 result.push((netbios_over_tcpip_datagram_distribution_server.len() * 4) as u8);
                 for netbios_over_tcpip_datagram_distribution_server in
@@ -1355,10 +1677,8 @@ impl DhcpOption {
                     result.push(netbios_over_tcpip_datagram_distribution_server.octets()[2]);
                     result.push(netbios_over_tcpip_datagram_distribution_server.octets()[3]);
                 }
-                result
             }
             DhcpOption::NetBiosOverTcpIpNodeType(netbios_over_tcpip_node_type) => {
-                let mut result = Vec::new();
                 result.push(46);
                 result.push(1);
                 match netbios_over_tcpip_node_type {
@@ -1367,17 +1687,13 @@ impl DhcpOption {
                     NetBiosOverTcpIpNodeType::MNode => result.push(4),
                     NetBiosOverTcpIpNodeType::HNode => result.push(8),
                 }
-                result
             }
             DhcpOption::NetBiosOverTcpIpScope(netbios_over_tcpip_scope) => {
-                let mut result = Vec::new();
                 result.push(47);
                 result.push(netbios_over_tcpip_scope.len() as u8);
-                result.extend_from_slice(&netbios_over_tcpip_scope);
-                result
+                result.extend_from_slice(netbios_over_tcpip_scope);
             }
             DhcpOption::XWindowSystemFontServer(x_window_system_font_server) => {
-                let mut result = Vec::new();
                 result.push(48);
                 result.push((x_window_system_font_server.len() * 4) as u8);
                 for x_window_system_font_server in x_window_system_font_server {
@@ -1386,10 +1702,8 @@ impl DhcpOption {
                     result.push(x_window_system_font_server.octets()[2]);
                     result.push(x_window_system_font_server.octets()[3]);
                 }
-                result
             }
             DhcpOption::XWindowSystemDisplayManager(x_window_system_display_manager) => {
-                let mut result = Vec::new();
                 result.push(49);
                 result.push((x_window_system_display_manager.len() * 4) as u8);
                 for x_window_system_display_manager in x_window_system_display_manager {
@@ -1398,21 +1712,17 @@ impl DhcpOption {
                     result.push(x_window_system_display_manager.octets()[2]);
                     result.push(x_window_system_display_manager.octets()[3]);
                 }
-                result
             }
             DhcpOption::NetworkInformationServicePlusDomain(
                 network_information_service_plus_domain,
             ) => {
-                let mut result = Vec::new();
                 result.push(64);
                 result.push(network_information_service_plus_domain.len() as u8);
                 result.extend_from_slice(network_information_service_plus_domain.as_bytes());
-                result
             }
             DhcpOption::NetworkInformationServicePlusServers(
                 network_information_service_plus_servers,
             ) => {
-                let mut result = Vec::new();
                 result.push(65);
                 result.push((network_information_service_plus_servers.len() * 4) as u8);
                 for network_information_service_plus_server in
@@ -1423,10 +1733,8 @@ impl DhcpOption {
                     result.push(network_information_service_plus_server.octets()[2]);
                     result.push(network_information_service_plus_server.octets()[3]);
                 }
-                result
             }
             DhcpOption::MobileIpHomeAgent(mobile_ip_home_agent) => {
-                let mut result = Vec::new();
                 result.push(68);
                 result.push((mobile_ip_home_agent.len() * 4) as u8);
                 for mobile_ip_home_agent in mobile_ip_home_agent {
@@ -1435,12 +1743,10 @@ impl DhcpOption {
                     result.push(mobile_ip_home_agent.octets()[2]);
                     result.push(mobile_ip_home_agent.octets()[3]);
                 }
-                result
             }
             DhcpOption::SimpleMailTransportProtocolServer(
                 simple_mail_transport_protocol_server,
             ) => {
-                let mut result = Vec::new();
                 result.push(69);
                 result.push((simple_mail_transport_protocol_server.len() * 4) as u8);
                 for simple_mail_transport_protocol_server in simple_mail_transport_protocol_server {
@@ -1449,10 +1755,8 @@ impl DhcpOption {
                     result.push(simple_mail_transport_protocol_server.octets()[2]);
                     result.push(simple_mail_transport_protocol_server.octets()[3]);
                 }
-                result
             }
             DhcpOption::PostOfficeProtocolServer(post_office_protocol_server) => {
-                let mut result = Vec::new();
                 result.push(70);
                 result.push((post_office_protocol_server.len() * 4) as u8);
                 for post_office_protocol_server in post_office_protocol_server {
@@ -1461,12 +1765,10 @@ impl DhcpOption {
                     result.push(post_office_protocol_server.octets()[2]);
                     result.push(post_office_protocol_server.octets()[3]);
                 }
-                result
             }
             DhcpOption::NetworkNewsTransportProtocolServer(
                 network_news_transport_protocol_server,
             ) => {
-                let mut result = Vec::new();
                 result.push(71);
                 result.push((network_news_transport_protocol_server.len() * 4) as u8);
                 for network_news_transport_protocol_server in network_news_transport_protocol_server
@@ -1476,10 +1778,8 @@ impl DhcpOption {
                     result.push(network_news_transport_protocol_server.octets()[2]);
                     result.push(network_news_transport_protocol_server.octets()[3]);
                 }
-                result
             }
             DhcpOption::DefaultWorldWideWebServer(default_world_wide_web_server) => {
-                let mut result = Vec::new();
                 result.push(72);
                 result.push((default_world_wide_web_server.len() * 4) as u8);
                 for default_world_wide_web_server in default_world_wide_web_server {
@@ -1488,10 +1788,8 @@ impl DhcpOption {
                     result.push(default_world_wide_web_server.octets()[2]);
                     result.push(default_world_wide_web_server.octets()[3]);
                 }
-                result
             }
             DhcpOption::DefaultFingerServer(default_finger_server) => {
-                let mut result = Vec::new();
                 result.push(73);
                 result.push((default_finger_server.len() * 4) as u8);
                 for default_finger_server in default_finger_server {
@@ -1500,10 +1798,8 @@ impl DhcpOption {
                     result.push(default_finger_server.octets()[2]);
                     result.push(default_finger_server.octets()[3]);
                 }
-                result
             }
             DhcpOption::DefaultInternetRelayChatServer(default_internet_relay_chat_server) => {
-                let mut result = Vec::new();
                 result.push(74);
                 result.push((default_internet_relay_chat_server.len() * 4) as u8);
                 for default_internet_relay_chat_server in default_internet_relay_chat_server {
@@ -1512,10 +1808,8 @@ impl DhcpOption {
                     result.push(default_internet_relay_chat_server.octets()[2]);
                     result.push(default_internet_relay_chat_server.octets()[3]);
                 }
-                result
             }
             DhcpOption::StreetTalkServer(street_talk_server) => {
-                let mut result = Vec::new();
                 result.push(75);
                 result.push((street_talk_server.len() * 4) as u8);
                 for street_talk_server in street_talk_server {
@@ -1524,12 +1818,10 @@ impl DhcpOption {
                     result.push(street_talk_server.octets()[2]);
                     result.push(street_talk_server.octets()[3]);
                 }
-                result
             }
             DhcpOption::StreetTalkDirectoryAssistanceServer(
                 street_talk_directory_assistance_server,
             ) => {
-                let mut result = Vec::new();
                 result.push(76);
                 result.push((street_talk_directory_assistance_server.len() * 4) as u8);
                 for street_talk_directory_assistance_server in
@@ -1540,27 +1832,66 @@ impl DhcpOption {
                     result.push(street_talk_directory_assistance_server.octets()[2]);
                     result.push(street_talk_directory_assistance_server.octets()[3]);
                 }
-                result
             }
-            DhcpOption::RequestedIpAddress(requested_ip_address) => {
-                let mut result = Vec::new();
-                result.push(50);
+            DhcpOption::RequestedIpAddress(requested_ip_address) => result.extend_from_slice(&[
+                50,
+                4,
+                requested_ip_address.octets()[0],
+                requested_ip_address.octets()[1],
+                requested_ip_address.octets()[2],
+                requested_ip_address.octets()[3],
+            ]),
+            DhcpOption::IpAddressLeaseTime(ip_address_lease_time) => result.extend_from_slice(&[
+                51,
+                4,
+                ((ip_address_lease_time >> 24) & 0xFF) as u8,
+                ((ip_address_lease_time >> 16) & 0xFF) as u8,
+                ((ip_address_lease_time >> 8) & 0xFF) as u8,
+                (ip_address_lease_time & 0xFF) as u8,
+            ]),
+            DhcpOption::OptionOverload(overload) => {
+                result.extend_from_slice(&[52, 1, (*overload).into()])
+            }
+            DhcpOption::DhcpMessageType(message_type) => {
+                result.extend_from_slice(&[53, 1, (*message_type).into()])
+            }
+            DhcpOption::ServerIdentifier(server_identifier) => {
+                result.push(54);
                 result.push(4);
-                result.push(requested_ip_address.octets()[0]);
-                result.push(requested_ip_address.octets()[1]);
-                result.push(requested_ip_address.octets()[2]);
-                result.push(requested_ip_address.octets()[3]);
-                result
-            }
-            DhcpOption::IpAddressLeaseTime(ip_address_lease_time) => {
-                let mut result = Vec::new();
-                result.push(51);
-                result.push(4);
-                result.push(((ip_address_lease_time >> 24) & 0xFF) as u8);
-                result.push(((ip_address_lease_time >> 16) & 0xFF) as u8);
-                result.push(((ip_address_lease_time >> 8) & 0xFF) as u8);
-                result.push((ip_address_lease_time & 0xFF) as u8);
-                result
+                result.extend_from_slice(&server_identifier.octets());
+            }
+            DhcpOption::ClientIdentifier(client_identifier) => {
+                result.push(61);
+                result.push(client_identifier.len() as u8);
+                result.extend_from_slice(client_identifier);
+            }
+            DhcpOption::DomainSearch(domain_search) => {
+                result.push(119);
+                result.push(0);
+                let start = result.len();
+                for name in domain_search {
+                    dns::write_name(name, result);
+                }
+                result[start - 1] = (result.len() - start) as u8;
+            }
+            DhcpOption::Authentication(authentication) => {
+                result.extend_from_slice(&[
+                    90,
+                    (11 + authentication.information.len()) as u8,
+                    authentication.protocol,
+                    authentication.algorithm,
+                    authentication.rdm,
+                ]);
+                result.extend_from_slice(&authentication.replay_detection.to_be_bytes());
+                result.extend_from_slice(&authentication.information);
+            }
+            DhcpOption::ForcerenewNonceCapable(algorithms) => {
+                result.extend_from_slice(&[145, algorithms.len() as u8]);
+                result.extend_from_slice(algorithms);
+            }
+            DhcpOption::Unknown(code, data) => {
+                result.extend_from_slice(&[*code, data.len() as u8]);
+                result.extend_from_slice(data);
             }
         }
     }
@@ -1569,2097 +1900,905 @@ impl DhcpOption {
         // Retrieve the option code.
         let (code, data) = match data.split_first() {
             Some((code, data)) => (*code, data),
-            None => return Err(DhcpError::ParsingError("No option code found".to_string())),
+            None => {
+                return Err(DhcpError::UnexpectedEof {
+                    field: "options",
+                    needed: 1,
+                    available: 0,
+                })
+            }
         };
 
-        //
-        match code {
-            0 => Ok((DhcpOption::Pad, data)),
-            255 => Ok((DhcpOption::End, data)),
-            1 => {
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse subnet mask".to_string(),
-                    ));
-                }
-
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse subnet mask".to_string(),
-                        ))
-                    }
-                };
-
-                let (subnet_mask, data) = data.split_at(4);
-                let subnet_mask = Ipv4Addr::new(
-                    subnet_mask[0],
-                    subnet_mask[1],
-                    subnet_mask[2],
-                    subnet_mask[3],
-                );
-
-                Ok((DhcpOption::SubnetMask(subnet_mask), data))
-            }
-            2 => {
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse time offset".to_string(),
-                    ));
-                }
-
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse time offset".to_string(),
-                        ))
-                    }
-                };
-
-                let (time_offset, data) = data.split_at(4);
-                let time_offset = ((time_offset[0] as u32) << 24)
-                    + ((time_offset[1] as u32) << 16)
-                    + ((time_offset[2] as u32) << 8)
-                    + (time_offset[3] as u32);
-
-                Ok((DhcpOption::TimeOffset(time_offset), data))
-            }
-            3 => {
-                // Check that the data is long enough to contain the length and at least one address.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse router".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse router".to_string(),
-                        ))
-                    }
-                };
-
-                // Check that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse router".to_string(),
-                    ));
-                }
-
-                // Retreive the addresses.
-                let (addresses, data) = data.split_at(len as usize);
-                let addresses = addresses
-                    .chunks_exact(4)
-                    .map(|address| Ipv4Addr::new(address[0], address[1], address[2], address[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::Router(addresses), data))
-            }
-            4 => {
-                // Check that the data is long enough to contain the length and at least one address.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse time servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse time servers".to_string(),
-                        ))
-                    }
-                };
-
-                // Check that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse time servers".to_string(),
-                    ));
-                }
-
-                // Retreive the addresses.
-                let (addresses, data) = data.split_at(len as usize);
-                let addresses = addresses
-                    .chunks_exact(4)
-                    .map(|address| Ipv4Addr::new(address[0], address[1], address[2], address[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::TimeServer(addresses), data))
-            }
-            5 => {
-                // Check that the data is long enough to contain the length and at least one address.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse name servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse name servers".to_string(),
-                        ))
-                    }
-                };
-
-                // Check that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse name servers".to_string(),
-                    ));
-                }
+        let (value, data) = split_value(code, data)?;
 
-                // Retreive the addresses.
-                let (addresses, data) = data.split_at(len as usize);
-                let addresses = addresses
-                    .chunks_exact(4)
-                    .map(|address| Ipv4Addr::new(address[0], address[1], address[2], address[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::NameServer(addresses), data))
-            }
-            6 => {
-                // Check that the data is long enough to contain the length and at least one address.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse domain name servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse domain name servers".to_string(),
-                        ))
-                    }
-                };
-
-                // Check that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse domain name servers".to_string(),
-                    ));
-                }
-
-                // Retreive the addresses.
-                let (addresses, data) = data.split_at(len as usize);
-                let addresses = addresses
-                    .chunks_exact(4)
-                    .map(|address| Ipv4Addr::new(address[0], address[1], address[2], address[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::DomainNameServer(addresses), data))
-            }
-            7 => {
-                // Check that the data is long enough to contain the length and at least one address.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse log servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse log servers".to_string(),
-                        ))
-                    }
-                };
-
-                // Check that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse log servers".to_string(),
-                    ));
-                }
-
-                // Retreive the addresses.
-                let (addresses, data) = data.split_at(len as usize);
-                let addresses = addresses
-                    .chunks_exact(4)
-                    .map(|address| Ipv4Addr::new(address[0], address[1], address[2], address[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::LogServer(addresses), data))
-            }
-            8 => {
-                // Check that the data is long enough to contain the length and at least one address.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse cookie servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse cookie servers".to_string(),
-                        ))
-                    }
-                };
-
-                // Check that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse cookie servers".to_string(),
-                    ));
-                }
-
-                // Retreive the addresses.
-                let (addresses, data) = data.split_at(len as usize);
-                let addresses = addresses
-                    .chunks_exact(4)
-                    .map(|address| Ipv4Addr::new(address[0], address[1], address[2], address[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::CookieServer(addresses), data))
-            }
-            9 => {
-                // Check that the data is long enough to contain the length and at least one address.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse lpr servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse lpr servers".to_string(),
-                        ))
-                    }
-                };
-
-                // Check that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse lpr servers".to_string(),
-                    ));
-                }
-
-                // Retreive the addresses.
-                let (addresses, data) = data.split_at(len as usize);
-                let addresses = addresses
-                    .chunks_exact(4)
-                    .map(|address| Ipv4Addr::new(address[0], address[1], address[2], address[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::LprServer(addresses), data))
-            }
-            10 => {
-                // Check that the data is long enough to contain the length and at least one address.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse impress servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse impress servers".to_string(),
-                        ))
-                    }
-                };
-
-                // Check that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse impress servers".to_string(),
-                    ));
-                }
-
-                // Retreive the addresses.
-                let (addresses, data) = data.split_at(len as usize);
-                let addresses = addresses
-                    .chunks_exact(4)
-                    .map(|address| Ipv4Addr::new(address[0], address[1], address[2], address[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::ImpressServer(addresses), data))
-            }
-            11 => {
-                // Check that the data is long enough to contain the length and at least one address.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse resource location servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse resource location servers".to_string(),
-                        ))
-                    }
-                };
-
-                // Check that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse resource location servers".to_string(),
-                    ));
-                }
-
-                // Retreive the addresses.
-                let (addresses, data) = data.split_at(len as usize);
-                let addresses = addresses
-                    .chunks_exact(4)
-                    .map(|address| Ipv4Addr::new(address[0], address[1], address[2], address[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::ResourceLocationServer(addresses), data))
-            }
-            12 => {
-                // Check that the data is long enough to contain a name with at least 1 character.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse host name".to_string(),
-                    ));
-                }
+        let option = match info(code) {
+            Some(info) => (info.parse)(code, value)?,
+            None => DhcpOption::Unknown(code, value.to_vec()),
+        };
+        Ok((option, data))
+    }
 
-                // Retrieve the length of the name.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse host name".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if len > data.len() as u8 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse host name".to_string(),
-                    ));
-                }
+    /// Deserialize an option as `deserialize` does, text options that are
+    /// not valid UTF-8 being decoded following `policy`.
+    pub fn deserialize_with(
+        data: &[u8],
+        policy: TextPolicy,
+    ) -> Result<(DhcpOption, &[u8]), DhcpError> {
+        match DhcpOption::deserialize(data) {
+            Err(DhcpError::InvalidUtf8 { code }) => {
+                let (value, data) = split_value(code, &data[1..])?;
+                Ok((invalid_text(code, value, policy)?, data))
+            }
+            result => result,
+        }
+    }
 
-                // Retrieve the name.
-                let (hostname, data) = data.split_at(len as usize);
-
-                // Convert the name to a string.
-                let hostname = match from_utf8(hostname) {
-                    Ok(hostname) => hostname,
-                    Err(_) => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse host name".to_string(),
-                        ))
-                    }
-                };
-
-                Ok((DhcpOption::HostName(hostname.to_string()), data))
-            }
-            13 => {
-                // Check that the data is long enough to contain a short.
-                if data.len() < 3 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse boot file size".to_string(),
-                    ));
-                }
+    // The option whose value is that of `first` followed by that of
+    // `second`, if it is valid.
+    pub(crate) fn concatenate(first: &DhcpOption, second: &DhcpOption) -> Option<DhcpOption> {
+        let mut value = first.encode().split_off(2);
+        value.extend_from_slice(&second.encode()[2..]);
+        DhcpOption::deserialize_value(first.raw_code(), &value).ok()
+    }
 
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse boot file size".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the size.
-                let (size, data) = match data.split_at(2) {
-                    (size, data) => (u16::from_be_bytes([size[0], size[1]]), data),
-                };
-
-                Ok((DhcpOption::BootFileSize(size), data))
-            }
-            14 => {
-                // Check that the data is long enough to contain at least a character.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse merit dump file".to_string(),
-                    ));
-                }
+    /// Deserialize the value of an option of code `code`, such as the
+    /// concatenation of the parts of a split option (RFC 3396), which may
+    /// be longer than 255 bytes.
+    pub fn deserialize_value(code: u8, value: &[u8]) -> Result<DhcpOption, DhcpError> {
+        let rule = info(code).map_or(LengthRule::Min(0), |info| info.len);
+        let len = value.len();
+        if !rule.allows(len) {
+            return Err(DhcpError::InvalidLength { code, len });
+        }
+        match info(code) {
+            Some(info) => (info.parse)(code, value),
+            None => Ok(DhcpOption::Unknown(code, value.to_vec())),
+        }
+    }
 
-                // Retrieve the length of the name.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse merit dump file".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if len > data.len() as u8 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse merit dump file".to_string(),
-                    ));
-                }
+    /// Deserialize the value of an option as `deserialize_value` does,
+    /// text options that are not valid UTF-8 being decoded following
+    /// `policy`.
+    pub fn deserialize_value_with(
+        code: u8,
+        value: &[u8],
+        policy: TextPolicy,
+    ) -> Result<DhcpOption, DhcpError> {
+        match DhcpOption::deserialize_value(code, value) {
+            Err(DhcpError::InvalidUtf8 { code }) => invalid_text(code, value, policy),
+            result => result,
+        }
+    }
+}
 
-                // Retrieve the name.
-                let (filename, data) = data.split_at(len as usize);
-
-                // Convert the name to a string.
-                let filename = match from_utf8(filename) {
-                    Ok(filename) => filename,
-                    Err(_) => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse merit dump file".to_string(),
-                        ))
-                    }
-                };
-
-                Ok((DhcpOption::MeritDumpFile(filename.to_string()), data))
-            }
-            15 => {
-                // Check that the data is long enough to contain at least a character.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse domain name".to_string(),
-                    ));
-                }
+// Decode the text option `code`, whose value is not valid UTF-8,
+// following `policy`.
+fn invalid_text(code: u8, value: &[u8], policy: TextPolicy) -> Result<DhcpOption, DhcpError> {
+    match decode_text(code, value, policy)? {
+        Some(text) => DhcpOption::deserialize_value(code, text.as_bytes()),
+        None => Ok(DhcpOption::Unknown(code, value.to_vec())),
+    }
+}
 
-                // Retrieve the length of the name.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse domain name".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if len > data.len() as u8 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse domain name".to_string(),
-                    ));
-                }
+/// Decode the value of the text option `code` (12, 14, 15, 17, 18, 40 or
+/// 64). A value that is not valid UTF-8 fails, is replaced or gives None,
+/// to be kept as raw bytes, following `policy`.
+pub(crate) fn decode_text(
+    code: u8,
+    value: &[u8],
+    policy: TextPolicy,
+) -> Result<Option<Cow<'_, str>>, DhcpError> {
+    match (from_utf8(value), policy) {
+        (Ok(text), _) => Ok(Some(Cow::Borrowed(text))),
+        (Err(_), TextPolicy::Strict) => Err(DhcpError::InvalidUtf8 { code }),
+        (Err(_), TextPolicy::Lossy) => Ok(Some(String::from_utf8_lossy(value))),
+        (Err(_), TextPolicy::Raw) => Ok(None),
+    }
+}
 
-                // Retrieve the name.
-                let (domain, data) = data.split_at(len as usize);
-
-                // Convert the name to a string.
-                let domain = match from_utf8(domain) {
-                    Ok(domain) => domain,
-                    Err(_) => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse domain name".to_string(),
-                        ))
-                    }
-                };
-
-                Ok((DhcpOption::DomainName(domain.to_string()), data))
-            }
-            16 => {
-                // Check that the data is long enough to contain the address.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse swap server".to_string(),
-                    ));
-                }
+/// How the length of an option value is constrained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthRule {
+    /// No length nor value follows the code (pad and end).
+    Empty,
+    /// A value of exactly this many bytes.
+    Fixed(usize),
+    /// A value of at least this many bytes.
+    Min(usize),
+    /// A list of items of `unit` bytes, of at least `min` bytes.
+    List { min: usize, unit: usize },
+}
 
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse swap server".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the address.
-                let (address, data) = data.split_at(4);
-                let address = Ipv4Addr::new(address[0], address[1], address[2], address[3]);
-
-                Ok((DhcpOption::SwapServer(address), data))
-            }
-            17 => {
-                // Check that the data has at least one byte.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse root path".to_string(),
-                    ));
-                }
+impl LengthRule {
+    /// The minimum length of a value.
+    pub fn min_len(&self) -> usize {
+        match self {
+            LengthRule::Empty => 0,
+            LengthRule::Fixed(len) => *len,
+            LengthRule::Min(min) | LengthRule::List { min, .. } => *min,
+        }
+    }
 
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse root path".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if len > data.len() as u8 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse root path".to_string(),
-                    ));
-                }
+    /// Whether a value of `len` bytes follows the rule.
+    pub fn allows(&self, len: usize) -> bool {
+        match *self {
+            LengthRule::Empty => len == 0,
+            LengthRule::Fixed(fixed) => len == fixed,
+            LengthRule::Min(min) => len >= min,
+            LengthRule::List { min, unit } => len >= min && len.is_multiple_of(unit),
+        }
+    }
 
-                // Retrieve the path.
-                let (path, data) = data.split_at(len as usize);
-
-                // Convert the path to a string.
-                let path = match from_utf8(path) {
-                    Ok(path) => path,
-                    Err(_) => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse root path".to_string(),
-                        ))
-                    }
-                };
-
-                Ok((DhcpOption::RootPath(path.to_string()), data))
-            }
-            18 => {
-                // Check that the data has at least one byte.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse extension path".to_string(),
-                    ));
-                }
+    /// Whether an option may have a length byte of 0, with no value
+    /// following it. Pad and End have no length byte at all.
+    pub fn allows_empty(&self) -> bool {
+        *self != LengthRule::Empty && self.min_len() == 0
+    }
+}
 
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse extension path".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if len > data.len() as u8 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse extension path".to_string(),
-                    ));
-                }
+/// What the crate knows of an option code: its names, the length of its
+/// value and how to parse it.
+#[derive(Clone, Copy)]
+pub struct OptionInfo {
+    pub code: u8,
+    // As given by `OptionCode::name`.
+    pub name: &'static str,
+    pub len: LengthRule,
+    // The name and value type of the option in the text and JSON
+    // representations, such as `host_name`.
+    pub(crate) key: &'static str,
+    pub(crate) kind: Kind,
+    parse: Parser,
+}
 
-                // Retrieve the path.
-                let (path, data) = data.split_at(len as usize);
-
-                // Convert the path to a string.
-                let path = match from_utf8(path) {
-                    Ok(path) => path,
-                    Err(_) => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse extension path".to_string(),
-                        ))
-                    }
-                };
-
-                Ok((DhcpOption::ExtensionsPath(path.to_string()), data))
-            }
-            19 => {
-                // Check that the data has at least one byte.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse IP forwarding".to_string(),
-                    ));
-                }
+type Parser = fn(u8, &[u8]) -> Result<DhcpOption, DhcpError>;
 
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse IP forwarding".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (value, data) = data.split_at(1);
-
-                Ok((DhcpOption::IpForwarding(value[0] == 1), data))
-            }
-            20 => {
-                // Check that the data has at least one byte.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse non-local source routing".to_string(),
-                    ));
-                }
+impl std::fmt::Debug for OptionInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("OptionInfo")
+            .field("code", &self.code)
+            .field("name", &self.name)
+            .field("len", &self.len)
+            .finish()
+    }
+}
 
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse non-local source routing".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (value, data) = data.split_at(1);
-
-                Ok((DhcpOption::NonLocalSourceRouting(value[0] == 1), data))
-            }
-            21 => {
-                // Check that the data cans at least hold a filter.
-                if data.len() < 9 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse policy filter".to_string(),
-                    ));
-                }
+/// The known option with code `code`, if any.
+pub fn info(code: u8) -> Option<&'static OptionInfo> {
+    OPTIONS[code as usize].as_ref()
+}
 
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse policy filter".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if len > data.len() as u8 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse policy filter".to_string(),
-                    ));
-                }
+// The known option named `key` in the text and JSON representations.
+pub(crate) fn info_by_key(key: &str) -> Option<&'static OptionInfo> {
+    DEFINITIONS.iter().find(|info| info.key == key)
+}
 
-                // Verify that the length is a multiple of 8.
-                if len % 8 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse policy filter".to_string(),
-                    ));
-                }
+/// Whether `code` is a known option: one defined by RFC 2132, or one of
+/// the later options handled by this crate (relay agent information,
+/// authentication, domain search and forcerenew nonce capable).
+pub fn is_known_code(code: u8) -> bool {
+    info(code).is_some()
+}
 
-                // Retrieve the filters.
-                let (filters, data) = data.split_at(len as usize);
-                let filters = filters
-                    .chunks_exact(8)
-                    .map(|filter| {
-                        (
-                            Ipv4Addr::new(filter[0], filter[1], filter[2], filter[3]),
-                            Ipv4Addr::new(filter[4], filter[5], filter[6], filter[7]),
-                        )
-                    })
-                    .collect::<Vec<(Ipv4Addr, Ipv4Addr)>>();
-
-                Ok((DhcpOption::PolicyFilter(filters), data))
-            }
-            22 => {
-                // Check that the data has at least 2 bytes.
-                if data.len() < 3 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse maximum datagram reassembly size".to_string(),
-                    ));
-                }
+// Check the length byte following an option code against the data and
+// the length rule of the option, so that no option can read past its own
+// value. Returns the length of the value.
+pub(crate) fn value_len(code: u8, data: &[u8]) -> Result<usize, DhcpError> {
+    let rule = info(code).map_or(LengthRule::Min(0), |info| info.len);
+    if rule == LengthRule::Empty {
+        return Ok(0);
+    }
+    let len = match data.first() {
+        Some(len) => *len as usize,
+        None => {
+            return Err(DhcpError::Truncated {
+                code,
+                needed: 1,
+                available: 0,
+            })
+        }
+    };
+    if data.len() < 1 + len {
+        return Err(DhcpError::Truncated {
+            code,
+            needed: 1 + len,
+            available: data.len(),
+        });
+    }
 
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse maximum datagram reassembly size".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (value, data) = data.split_at(2);
-
-                Ok((
-                    DhcpOption::MaximumDatagramReassemblySize(u16::from_be_bytes([
-                        value[0], value[1],
-                    ])),
-                    data,
-                ))
-            }
-            23 => {
-                // Check that the data has at least one byte.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse default IP TTL".to_string(),
-                    ));
-                }
+    match rule {
+        LengthRule::Fixed(fixed) if len != fixed => Err(DhcpError::InvalidLength { code, len }),
+        LengthRule::Min(min) | LengthRule::List { min, .. } if len < min => {
+            Err(DhcpError::InvalidLength { code, len })
+        }
+        LengthRule::List { unit, .. } if len % unit != 0 => {
+            Err(DhcpError::InvalidLength { code, len })
+        }
+        _ => Ok(len),
+    }
+}
 
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse default IP TTL".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (value, data) = data.split_at(1);
-
-                Ok((DhcpOption::DefaultIpTimeToLive(value[0]), data))
-            }
-            24 => {
-                // Check that the data has at least 5 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse path MTU aging timeout".to_string(),
-                    ));
-                }
+// Check the length of an option, and split its value from the data
+// following it. `data` follows the code. Never panics, whatever the data.
+pub(crate) fn split_value(code: u8, data: &[u8]) -> Result<(&[u8], &[u8]), DhcpError> {
+    let len = value_len(code, data)?;
+    if matches!(code, 0 | 255) {
+        return Ok((&[], data));
+    }
+    data.get(1..)
+        .and_then(|data| data.split_at_checked(len))
+        .ok_or(DhcpError::Truncated {
+            code,
+            needed: 1 + len,
+            available: data.len(),
+        })
+}
 
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse path MTU aging timeout".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (value, data) = data.split_at(4);
-
-                Ok((
-                    DhcpOption::PathMtuAgingTimeout(u32::from_be_bytes([
-                        value[0], value[1], value[2], value[3],
-                    ])),
-                    data,
-                ))
-            }
-            25 => {
-                // Check that the data has at least 2 bytes.
-                if data.len() < 3 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse path MTU plateau table".to_string(),
-                    ));
-                }
+// The known options by code.
+static OPTIONS: [Option<OptionInfo>; 256] = {
+    let mut options = [None; 256];
+    let mut i = 0;
+    while i < DEFINITIONS.len() {
+        options[DEFINITIONS[i].code as usize] = Some(DEFINITIONS[i]);
+        i += 1;
+    }
+    options
+};
+
+const fn option(
+    code: u8,
+    key: &'static str,
+    kind: Kind,
+    len: LengthRule,
+    parse: Parser,
+) -> OptionInfo {
+    OptionInfo {
+        code,
+        name: OptionCode::new(code).name(),
+        key,
+        kind,
+        len,
+        parse,
+    }
+}
 
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse path MTU plateau table".to_string(),
-                        ))
-                    }
-                };
-
-                let (mtu_sizes, data) = data.split_at(len as usize);
-                let mtu_sizes = mtu_sizes
+const ADDRESS: LengthRule = LengthRule::Fixed(4);
+const ADDRESSES: LengthRule = LengthRule::List { min: 4, unit: 4 };
+const PAIRS: LengthRule = LengthRule::List { min: 8, unit: 8 };
+const STRING: LengthRule = LengthRule::Min(1);
+const FLAG: LengthRule = LengthRule::Fixed(1);
+const U16: LengthRule = LengthRule::Fixed(2);
+const U32: LengthRule = LengthRule::Fixed(4);
+const ANY: LengthRule = LengthRule::Min(0);
+
+const DEFINITIONS: &[OptionInfo] = &[
+    option(0, "pad", Kind::Bytes, LengthRule::Empty, |_, _| {
+        Ok(DhcpOption::Pad)
+    }),
+    option(1, "subnet_mask", Kind::Address, ADDRESS, |_, value| {
+        Ok(DhcpOption::SubnetMask(address(value)))
+    }),
+    option(2, "time_offset", Kind::U32, U32, |_, value| {
+        Ok(DhcpOption::TimeOffset(u32_value(value)))
+    }),
+    option(3, "router", Kind::Addresses, ADDRESSES, |_, value| {
+        Ok(DhcpOption::Router(addresses(value)))
+    }),
+    option(4, "time_server", Kind::Addresses, ADDRESSES, |_, value| {
+        Ok(DhcpOption::TimeServer(addresses(value)))
+    }),
+    option(5, "name_server", Kind::Addresses, ADDRESSES, |_, value| {
+        Ok(DhcpOption::NameServer(addresses(value)))
+    }),
+    option(
+        6,
+        "domain_name_server",
+        Kind::Addresses,
+        ADDRESSES,
+        |_, value| Ok(DhcpOption::DomainNameServer(addresses(value))),
+    ),
+    option(7, "log_server", Kind::Addresses, ADDRESSES, |_, value| {
+        Ok(DhcpOption::LogServer(addresses(value)))
+    }),
+    option(
+        8,
+        "cookie_server",
+        Kind::Addresses,
+        ADDRESSES,
+        |_, value| Ok(DhcpOption::CookieServer(addresses(value))),
+    ),
+    option(9, "lpr_server", Kind::Addresses, ADDRESSES, |_, value| {
+        Ok(DhcpOption::LprServer(addresses(value)))
+    }),
+    option(
+        10,
+        "impress_server",
+        Kind::Addresses,
+        ADDRESSES,
+        |_, value| Ok(DhcpOption::ImpressServer(addresses(value))),
+    ),
+    option(
+        11,
+        "resource_location_server",
+        Kind::Addresses,
+        ADDRESSES,
+        |_, value| Ok(DhcpOption::ResourceLocationServer(addresses(value))),
+    ),
+    option(12, "host_name", Kind::Text, STRING, |code, value| {
+        Ok(DhcpOption::HostName(string(code, value)?))
+    }),
+    option(13, "boot_file_size", Kind::U16, U16, |_, value| {
+        Ok(DhcpOption::BootFileSize(u16_value(value)))
+    }),
+    option(14, "merit_dump_file", Kind::Text, STRING, |code, value| {
+        Ok(DhcpOption::MeritDumpFile(string(code, value)?))
+    }),
+    option(15, "domain_name", Kind::Text, STRING, |code, value| {
+        Ok(DhcpOption::DomainName(string(code, value)?))
+    }),
+    option(16, "swap_server", Kind::Address, ADDRESS, |_, value| {
+        Ok(DhcpOption::SwapServer(address(value)))
+    }),
+    option(17, "root_path", Kind::Text, STRING, |code, value| {
+        Ok(DhcpOption::RootPath(string(code, value)?))
+    }),
+    option(18, "extensions_path", Kind::Text, STRING, |code, value| {
+        Ok(DhcpOption::ExtensionsPath(string(code, value)?))
+    }),
+    option(19, "ip_forwarding", Kind::Bool, FLAG, |_, value| {
+        Ok(DhcpOption::IpForwarding(value[0] == 1))
+    }),
+    option(
+        20,
+        "non_local_source_routing",
+        Kind::Bool,
+        FLAG,
+        |_, value| Ok(DhcpOption::NonLocalSourceRouting(value[0] == 1)),
+    ),
+    option(21, "policy_filter", Kind::Addresses, PAIRS, |_, value| {
+        Ok(DhcpOption::PolicyFilter(pairs(value)))
+    }),
+    option(
+        22,
+        "maximum_datagram_reassembly_size",
+        Kind::U16,
+        U16,
+        |_, value| Ok(DhcpOption::MaximumDatagramReassemblySize(u16_value(value))),
+    ),
+    option(23, "default_ip_time_to_live", Kind::U8, FLAG, |_, value| {
+        Ok(DhcpOption::DefaultIpTimeToLive(value[0]))
+    }),
+    option(24, "path_mtu_aging_timeout", Kind::U32, U32, |_, value| {
+        Ok(DhcpOption::PathMtuAgingTimeout(u32_value(value)))
+    }),
+    option(
+        25,
+        "path_mtu_plateau_table",
+        Kind::U16s,
+        LengthRule::List { min: 2, unit: 2 },
+        |_, value| {
+            Ok(DhcpOption::PathMtuPlateauTable(
+                value
                     .chunks_exact(2)
-                    .map(|filters| u16::from_be_bytes([filters[0], filters[1]]))
-                    .collect::<Vec<u16>>();
-
-                Ok((DhcpOption::PathMtuPlateauTable(mtu_sizes), data))
-            }
-            26 => {
-                // Check that the data has at least 2 bytes.
-                if data.len() < 3 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse interface MTU".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse interface MTU".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (value, data) = data.split_at(2);
-
-                Ok((
-                    DhcpOption::InterfaceMtu(u16::from_be_bytes([value[0], value[1]])),
-                    data,
-                ))
-            }
-            27 => {
-                // Check that the data has at least 1 byte.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse all subnets are local".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse all subnets are local".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (value, data) = data.split_at(1);
-
-                Ok((DhcpOption::AllSubnetsAreLocal(value[0] != 0), data))
-            }
-            28 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse broadcast address".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse broadcast address".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (address, data) = data.split_at(4);
-
-                Ok((
-                    DhcpOption::BroadcastAddress(Ipv4Addr::new(
-                        address[0], address[1], address[2], address[3],
-                    )),
-                    data,
-                ))
-            }
-            29 => {
-                // Check that the data has at least 1 byte.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse perform mask discovery".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse perform mask discovery".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (address, data) = data.split_at(1);
-
-                Ok((DhcpOption::PerformMaskDiscovery(address[0] != 0), data))
-            }
-            30 => {
-                // Check that the data has at least 1 byte.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse mask supplier".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse mask supplier".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (address, data) = data.split_at(1);
-
-                Ok((DhcpOption::MaskSupplier(address[0] != 0), data))
-            }
-            31 => {
-                // Check that the data has at least 1byte.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse perform router discovery".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse perform router discovery".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (address, data) = data.split_at(1);
-
-                Ok((DhcpOption::PerformRouterDiscovery(address[0] != 0), data))
-            }
-            32 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse router solicitation address".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse router solicitation address".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (address, data) = data.split_at(4);
-
-                Ok((
-                    DhcpOption::RouterSolicitationAddress(Ipv4Addr::new(
-                        address[0], address[1], address[2], address[3],
-                    )),
-                    data,
-                ))
-            }
-            33 => {
-                // Check that the data has at least 8 bytes.
-                if data.len() < 9 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse static route".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse static route".to_string(),
-                        ))
-                    }
-                };
-
-                // Check that the length is a multiple of 8.
-                if len % 8 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse static route".to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                let (routes, data) = data.split_at(len as usize);
-                let routes = routes
-                    .chunks_exact(8)
-                    .map(|route| {
-                        (
-                            Ipv4Addr::new(route[0], route[1], route[2], route[3]),
-                            Ipv4Addr::new(route[4], route[5], route[6], route[7]),
-                        )
-                    })
-                    .collect::<Vec<(Ipv4Addr, Ipv4Addr)>>();
-
-                Ok((DhcpOption::StaticRoute(routes), data))
-            }
-            34 => {
-                // Check that the data has at least 1 bytes.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse trailer encapsulation".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse trailer encapsulation".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (value, data) = data.split_at(1);
-
-                Ok((DhcpOption::TrailerEncapsulation(value[0] != 0), data))
-            }
-            35 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse arp cache timeout".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse arp cache timeout".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (timeout, data) = data.split_at(4);
-
-                Ok((
-                    DhcpOption::ArpCacheTimeout(u32::from_be_bytes([
-                        timeout[0], timeout[1], timeout[2], timeout[3],
-                    ])),
-                    data,
-                ))
-            }
-            36 => {
-                // Check that the data has at least 1 bytes.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse ethernet encapsulation".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse ethernet encapsulation".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (value, data) = data.split_at(1);
-
-                Ok((DhcpOption::EthernetEncapsulation(value[0] != 0), data))
-            }
-            37 => {
-                // Check that the data has at least 1 bytes.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse tcp default ttl".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse tcp default ttl".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (ttl, data) = data.split_at(1);
-
-                Ok((DhcpOption::TcpDefaultTtl(ttl[0]), data))
-            }
-            38 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse tcp keepalive interval".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse tcp keepalive interval".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (interval, data) = data.split_at(4);
-
-                Ok((
-                    DhcpOption::TcpKeepaliveInterval(u32::from_be_bytes([
-                        interval[0],
-                        interval[1],
-                        interval[2],
-                        interval[3],
-                    ])),
-                    data,
-                ))
-            }
-            39 => {
-                // Check that the data has at least 1 bytes.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse tcp keepalive garbage".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse tcp keepalive garbage".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (garbage, data) = data.split_at(1);
-
-                Ok((DhcpOption::TcpKeepaliveGarbage(garbage[0] != 0), data))
-            }
-            40 => {
-                // Check that the data has at least 1 bytes.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse network information service domain domain".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse network information service domain domain".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse network information service domain domain".to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                let (domain, data) = data.split_at(len as usize);
-
-                Ok((
-                    DhcpOption::NetworkInformationServiceDomain(
-                        String::from_utf8_lossy(domain).to_string(),
-                    ),
-                    data,
-                ))
-            }
-            41 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse network information service servers server address"
-                            .to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) =
-                    match data.split_first() {
-                        Some((len, data)) => (*len, data),
-                        None => return Err(DhcpError::ParsingError(
-                            "Could not parse network information service servers server address"
-                                .to_string(),
-                        )),
-                    };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse network information service servers server address"
-                            .to_string(),
-                    ));
-                }
-
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse network information service servers server address"
-                            .to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::NetworkInformationServers(servers), data))
-            }
-            42 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse network time protocol servers server address".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse network time protocol servers server address"
-                                .to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse network time protocol servers server address".to_string(),
-                    ));
-                }
-
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse network time protocol servers server address".to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::NetworkTimeProtocolServers(servers), data))
-            }
-            43 => {
-                // Check that the data has at least 1 bytes.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse vendor specific information".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse vendor specific information".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse vendor specific information".to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                let (info, data) = data.split_at(len as usize);
-
-                Ok((DhcpOption::VendorSpecificInformation(info.to_vec()), data))
-            }
-            44 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse netbios over tcp/ip name servers server address"
-                            .to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse netbios over tcp/ip name servers server address"
-                                .to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse netbios over tcp/ip name servers server address"
-                            .to_string(),
-                    ));
-                }
-
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse netbios over tcp/ip name servers server address"
-                            .to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::NetBiosOverTcpIpNameServer(servers), data))
-            }
-            45 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse netbios over tcp/ip datagram distribution server address"
-                            .to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => return Err(DhcpError::ParsingError(
-                        "Could not parse netbios over tcp/ip datagram distribution server address"
-                            .to_string(),
-                    )),
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse netbios over tcp/ip datagram distribution server address"
-                            .to_string(),
-                    ));
-                }
-
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse netbios over tcp/ip datagram distribution server address"
-                            .to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((
-                    DhcpOption::NetBiosOverTcpIpDatagramDistributionServer(servers),
-                    data,
-                ))
-            }
-            46 => {
-                // Check that the data has at least 1 byte.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse netbios over tcp/ip node type".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (_len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse netbios over tcp/ip node type".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (node_type, data) = data.split_at(1);
-                let node_type = match node_type[0] {
-                    1 => NetBiosOverTcpIpNodeType::BNode,
-                    2 => NetBiosOverTcpIpNodeType::PNode,
-                    4 => NetBiosOverTcpIpNodeType::MNode,
-                    8 => NetBiosOverTcpIpNodeType::HNode,
-                    _ => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse netbios over tcp/ip node type".to_string(),
-                        ))
-                    }
-                };
-
-                Ok((DhcpOption::NetBiosOverTcpIpNodeType(node_type), data))
-            }
-            47 => {
-                // Check that the data has at least 1 byte.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse netbios over tcp/ip scope".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse netbios over tcp/ip scope".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse netbios over tcp/ip scope".to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                let (scope, data) = data.split_at(len as usize);
-
-                Ok((DhcpOption::NetBiosOverTcpIpScope(scope.to_vec()), data))
-            }
-            48 => {
-                // Check that the data has at least 4 byte.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse X Window System Font server".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse X Window System Font server".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse X Window System Font server".to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::XWindowSystemFontServer(servers), data))
-            }
-            49 => {
-                // Check that the data has at least 4 byte.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse X Window System Display Manager".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse X Window System Display Manager".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse X Window System Display Manager".to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::XWindowSystemDisplayManager(servers), data))
-            }
-            64 => {
-                // Check that the data has at least 1 byte.
-                if data.len() < 2 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Network Information Service+ domain".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse Network Information Service+ domain".to_string(),
-                        ))
-                    }
-                };
-
-                // Retrieve the value.
-                let (domain, data) = data.split_at(len as usize);
-
-                Ok((
-                    DhcpOption::NetworkInformationServicePlusDomain(
-                        String::from_utf8_lossy(domain).to_string(),
-                    ),
-                    data,
-                ))
-            }
-            65 => {
-                // Check that the data has at least 4 byte.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Network Information Service+ servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse Network Information Service+ servers".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Network Information Service+ servers".to_string(),
-                    ));
-                }
-
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Network Information Service+ servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((
-                    DhcpOption::NetworkInformationServicePlusServers(servers),
-                    data,
-                ))
-            }
-            68 => {
-                // Check that the data has at least the length.
-                if data.len() < 1 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Mobile Ip Home Agent".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse Mobile Ip Home Agent".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Mobile Ip Home Agent".to_string(),
-                    ));
-                }
-
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Mobile Ip Home Agent".to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                if len != 0 {
-                    let (servers, data) = data.split_at(len as usize);
-                    let servers = servers
-                        .chunks_exact(4)
-                        .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                        .collect::<Vec<Ipv4Addr>>();
-
-                    Ok((DhcpOption::MobileIpHomeAgent(servers), data))
-                } else {
-                    Ok((DhcpOption::MobileIpHomeAgent(Vec::new()), data))
-                }
-            }
-            69 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Simple Mail Transport Protocol Server servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse Simple Mail Transport Protocol Server servers"
-                                .to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Simple Mail Transport Protocol Server servers".to_string(),
-                    ));
-                }
-
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Simple Mail Transport Protocol Server servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((DhcpOption::SimpleMailTransportProtocolServer(servers), data))
-            }
-            70 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Post Office Protocol Server servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse Post Office Protocol Server servers".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Post Office Protocol Server servers".to_string(),
-                    ));
-                }
-
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Post Office Protocol Server servers".to_string(),
-                    ));
-                }
-
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
+                    .map(|size| u16::from_be_bytes([size[0], size[1]]))
+                    .collect(),
+            ))
+        },
+    ),
+    option(26, "interface_mtu", Kind::U16, U16, |_, value| {
+        Ok(DhcpOption::InterfaceMtu(u16_value(value)))
+    }),
+    option(27, "all_subnets_are_local", Kind::Bool, FLAG, |_, value| {
+        Ok(DhcpOption::AllSubnetsAreLocal(value[0] != 0))
+    }),
+    option(
+        28,
+        "broadcast_address",
+        Kind::Address,
+        ADDRESS,
+        |_, value| Ok(DhcpOption::BroadcastAddress(address(value))),
+    ),
+    option(
+        29,
+        "perform_mask_discovery",
+        Kind::Bool,
+        FLAG,
+        |_, value| Ok(DhcpOption::PerformMaskDiscovery(value[0] != 0)),
+    ),
+    option(30, "mask_supplier", Kind::Bool, FLAG, |_, value| {
+        Ok(DhcpOption::MaskSupplier(value[0] != 0))
+    }),
+    option(
+        31,
+        "perform_router_discovery",
+        Kind::Bool,
+        FLAG,
+        |_, value| Ok(DhcpOption::PerformRouterDiscovery(value[0] != 0)),
+    ),
+    option(
+        32,
+        "router_solicitation_address",
+        Kind::Address,
+        ADDRESS,
+        |_, value| Ok(DhcpOption::RouterSolicitationAddress(address(value))),
+    ),
+    option(33, "static_route", Kind::Addresses, PAIRS, |_, value| {
+        Ok(DhcpOption::StaticRoute(pairs(value)))
+    }),
+    option(34, "trailer_encapsulation", Kind::Bool, FLAG, |_, value| {
+        Ok(DhcpOption::TrailerEncapsulation(value[0] != 0))
+    }),
+    option(35, "arp_cache_timeout", Kind::U32, U32, |_, value| {
+        Ok(DhcpOption::ArpCacheTimeout(u32_value(value)))
+    }),
+    option(
+        36,
+        "ethernet_encapsulation",
+        Kind::Bool,
+        FLAG,
+        |_, value| Ok(DhcpOption::EthernetEncapsulation(value[0] != 0)),
+    ),
+    option(37, "tcp_default_ttl", Kind::U8, FLAG, |_, value| {
+        Ok(DhcpOption::TcpDefaultTtl(value[0]))
+    }),
+    option(38, "tcp_keepalive_interval", Kind::U32, U32, |_, value| {
+        Ok(DhcpOption::TcpKeepaliveInterval(u32_value(value)))
+    }),
+    option(39, "tcp_keepalive_garbage", Kind::Bool, FLAG, |_, value| {
+        Ok(DhcpOption::TcpKeepaliveGarbage(value[0] != 0))
+    }),
+    option(40, "nis_domain", Kind::Text, STRING, |code, value| {
+        Ok(DhcpOption::NetworkInformationServiceDomain(string(
+            code, value,
+        )?))
+    }),
+    option(41, "nis_servers", Kind::Addresses, ADDRESSES, |_, value| {
+        Ok(DhcpOption::NetworkInformationServers(addresses(value)))
+    }),
+    option(42, "ntp_servers", Kind::Addresses, ADDRESSES, |_, value| {
+        Ok(DhcpOption::NetworkTimeProtocolServers(addresses(value)))
+    }),
+    option(
+        43,
+        "vendor_specific_information",
+        Kind::Bytes,
+        STRING,
+        |_, value| Ok(DhcpOption::VendorSpecificInformation(value.to_vec())),
+    ),
+    option(
+        44,
+        "netbios_name_server",
+        Kind::Addresses,
+        ADDRESSES,
+        |_, value| Ok(DhcpOption::NetBiosOverTcpIpNameServer(addresses(value))),
+    ),
+    option(
+        45,
+        "netbios_datagram_distribution_server",
+        Kind::Addresses,
+        ADDRESSES,
+        |_, value| {
+            Ok(DhcpOption::NetBiosOverTcpIpDatagramDistributionServer(
+                addresses(value),
+            ))
+        },
+    ),
+    option(46, "netbios_node_type", Kind::U8, FLAG, |code, value| {
+        Ok(DhcpOption::NetBiosOverTcpIpNodeType(node_type(
+            code, value,
+        )?))
+    }),
+    option(47, "netbios_scope", Kind::Bytes, STRING, |_, value| {
+        Ok(DhcpOption::NetBiosOverTcpIpScope(value.to_vec()))
+    }),
+    option(
+        48,
+        "x_window_font_server",
+        Kind::Addresses,
+        ADDRESSES,
+        |_, value| Ok(DhcpOption::XWindowSystemFontServer(addresses(value))),
+    ),
+    option(
+        49,
+        "x_window_display_manager",
+        Kind::Addresses,
+        ADDRESSES,
+        |_, value| Ok(DhcpOption::XWindowSystemDisplayManager(addresses(value))),
+    ),
+    option(
+        50,
+        "requested_ip_address",
+        Kind::Address,
+        ADDRESS,
+        |_, value| Ok(DhcpOption::RequestedIpAddress(address(value))),
+    ),
+    option(51, "ip_address_lease_time", Kind::U32, U32, |_, value| {
+        Ok(DhcpOption::IpAddressLeaseTime(u32_value(value)))
+    }),
+    option(52, "option_overload", Kind::U8, FLAG, |code, value| {
+        Ok(DhcpOption::OptionOverload(overload(code, value)?))
+    }),
+    option(
+        53,
+        "dhcp_message_type",
+        Kind::U8,
+        FLAG,
+        |code, value| match MessageType::try_from(value[0]) {
+            Ok(message_type) => Ok(DhcpOption::DhcpMessageType(message_type)),
+            Err(_) => unknown(code, value),
+        },
+    ),
+    option(
+        54,
+        "server_identifier",
+        Kind::Address,
+        ADDRESS,
+        |_, value| Ok(DhcpOption::ServerIdentifier(address(value))),
+    ),
+    option(55, "parameter_request_list", Kind::Bytes, STRING, unknown),
+    option(56, "message", Kind::Text, STRING, unknown),
+    option(57, "maximum_dhcp_message_size", Kind::U16, U16, unknown),
+    option(58, "renewal_time", Kind::U32, U32, unknown),
+    option(59, "rebinding_time", Kind::U32, U32, unknown),
+    option(60, "vendor_class_identifier", Kind::Bytes, STRING, unknown),
+    option(
+        61,
+        "client_identifier",
+        Kind::Bytes,
+        LengthRule::Min(2),
+        |_, value| Ok(DhcpOption::ClientIdentifier(value.to_vec())),
+    ),
+    option(62, "netware_ip_domain_name", Kind::Text, STRING, unknown),
+    option(63, "netware_ip_information", Kind::Bytes, ANY, unknown),
+    option(64, "nis_plus_domain", Kind::Text, STRING, |code, value| {
+        Ok(DhcpOption::NetworkInformationServicePlusDomain(string(
+            code, value,
+        )?))
+    }),
+    option(
+        65,
+        "nis_plus_servers",
+        Kind::Addresses,
+        ADDRESSES,
+        |_, value| {
+            Ok(DhcpOption::NetworkInformationServicePlusServers(addresses(
+                value,
+            )))
+        },
+    ),
+    option(66, "tftp_server_name", Kind::Text, STRING, unknown),
+    option(67, "bootfile_name", Kind::Text, STRING, unknown),
+    option(
+        68,
+        "mobile_ip_home_agent",
+        Kind::Addresses,
+        LengthRule::List { min: 0, unit: 4 },
+        |_, value| Ok(DhcpOption::MobileIpHomeAgent(addresses(value))),
+    ),
+    option(69, "smtp_server", Kind::Addresses, ADDRESSES, |_, value| {
+        Ok(DhcpOption::SimpleMailTransportProtocolServer(addresses(
+            value,
+        )))
+    }),
+    option(70, "pop3_server", Kind::Addresses, ADDRESSES, |_, value| {
+        Ok(DhcpOption::PostOfficeProtocolServer(addresses(value)))
+    }),
+    option(71, "nntp_server", Kind::Addresses, ADDRESSES, |_, value| {
+        Ok(DhcpOption::NetworkNewsTransportProtocolServer(addresses(
+            value,
+        )))
+    }),
+    option(72, "www_server", Kind::Addresses, ADDRESSES, |_, value| {
+        Ok(DhcpOption::DefaultWorldWideWebServer(addresses(value)))
+    }),
+    option(
+        73,
+        "finger_server",
+        Kind::Addresses,
+        ADDRESSES,
+        |_, value| Ok(DhcpOption::DefaultFingerServer(addresses(value))),
+    ),
+    option(74, "irc_server", Kind::Addresses, ADDRESSES, |_, value| {
+        Ok(DhcpOption::DefaultInternetRelayChatServer(addresses(value)))
+    }),
+    option(
+        75,
+        "streettalk_server",
+        Kind::Addresses,
+        ADDRESSES,
+        |_, value| Ok(DhcpOption::StreetTalkServer(addresses(value))),
+    ),
+    option(
+        76,
+        "streettalk_directory_assistance_server",
+        Kind::Addresses,
+        ADDRESSES,
+        |_, value| {
+            Ok(DhcpOption::StreetTalkDirectoryAssistanceServer(addresses(
+                value,
+            )))
+        },
+    ),
+    option(
+        82,
+        "relay_agent_information",
+        Kind::Bytes,
+        LengthRule::Min(2),
+        unknown,
+    ),
+    option(
+        90,
+        "authentication",
+        Kind::Bytes,
+        LengthRule::Min(11),
+        |_, value| {
+            let mut replay_detection = [0; 8];
+            replay_detection.copy_from_slice(&value[3..11]);
+            Ok(DhcpOption::Authentication(Authentication {
+                protocol: value[0],
+                algorithm: value[1],
+                rdm: value[2],
+                replay_detection: u64::from_be_bytes(replay_detection),
+                information: value[11..].to_vec(),
+            }))
+        },
+    ),
+    option(119, "domain_search", Kind::Names, STRING, |_, value| {
+        Ok(DhcpOption::DomainSearch(dns::decode_names(value)?))
+    }),
+    option(
+        145,
+        "forcerenew_nonce_capable",
+        Kind::Bytes,
+        LengthRule::Min(1),
+        |_, value| Ok(DhcpOption::ForcerenewNonceCapable(value.to_vec())),
+    ),
+    option(255, "end", Kind::Bytes, LengthRule::Empty, |_, _| {
+        Ok(DhcpOption::End)
+    }),
+];
+
+// The parsers below are given values of the length their rule allows,
+// and shared with `DhcpOptionRef`.
+
+fn unknown(code: u8, value: &[u8]) -> Result<DhcpOption, DhcpError> {
+    Ok(DhcpOption::Unknown(code, value.to_vec()))
+}
 
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
+pub(crate) fn node_type(code: u8, value: &[u8]) -> Result<NetBiosOverTcpIpNodeType, DhcpError> {
+    match value[0] {
+        1 => Ok(NetBiosOverTcpIpNodeType::BNode),
+        2 => Ok(NetBiosOverTcpIpNodeType::PNode),
+        4 => Ok(NetBiosOverTcpIpNodeType::MNode),
+        8 => Ok(NetBiosOverTcpIpNodeType::HNode),
+        _ => Err(DhcpError::InvalidValue {
+            code,
+            reason: "unknown node type",
+        }),
+    }
+}
 
-                Ok((DhcpOption::PostOfficeProtocolServer(servers), data))
-            }
-            71 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Network News Transport Protocol Server servers"
-                            .to_string(),
-                    ));
-                }
+pub(crate) fn overload(code: u8, value: &[u8]) -> Result<OptionOverload, DhcpError> {
+    OptionOverload::try_from(value[0]).map_err(|_| DhcpError::InvalidValue {
+        code,
+        reason: "overloads neither file nor sname",
+    })
+}
 
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse Network News Transport Protocol Server servers"
-                                .to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Network News Transport Protocol Server servers"
-                            .to_string(),
-                    ));
-                }
+pub(crate) fn address(value: &[u8]) -> Ipv4Addr {
+    Ipv4Addr::new(value[0], value[1], value[2], value[3])
+}
 
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Network News Transport Protocol Server servers"
-                            .to_string(),
-                    ));
-                }
+fn addresses(value: &[u8]) -> Addresses {
+    value.chunks_exact(4).map(address).collect()
+}
 
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
-
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((
-                    DhcpOption::NetworkNewsTransportProtocolServer(servers),
-                    data,
-                ))
-            }
-            72 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Default World Wide Web Server servers".to_string(),
-                    ));
-                }
+fn pairs(value: &[u8]) -> Vec<(Ipv4Addr, Ipv4Addr)> {
+    value
+        .chunks_exact(8)
+        .map(|pair| (address(&pair[..4]), address(&pair[4..])))
+        .collect()
+}
 
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse Default World Wide Web Server servers".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Default World Wide Web Server servers".to_string(),
-                    ));
-                }
+fn string(code: u8, value: &[u8]) -> Result<String, DhcpError> {
+    match decode_text(code, value, TextPolicy::Strict)? {
+        Some(text) => Ok(text.into_owned()),
+        None => Err(DhcpError::InvalidUtf8 { code }),
+    }
+}
 
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Default World Wide Web Server servers".to_string(),
-                    ));
-                }
+pub(crate) fn u16_value(value: &[u8]) -> u16 {
+    u16::from_be_bytes([value[0], value[1]])
+}
 
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
+pub(crate) fn u32_value(value: &[u8]) -> u32 {
+    u32::from_be_bytes([value[0], value[1], value[2], value[3]])
+}
 
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NetBiosOverTcpIpNodeType {
+    BNode,
+    PNode,
+    MNode,
+    HNode,
+}
 
-                Ok((DhcpOption::DefaultWorldWideWebServer(servers), data))
-            }
-            73 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Default Finger Server servers".to_string(),
-                    ));
-                }
+/// The header fields holding options in addition to the options field,
+/// as set by the Option Overload option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OptionOverload {
+    File,
+    Sname,
+    Both,
+}
 
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse Default Finger Server servers".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Default Finger Server servers".to_string(),
-                    ));
-                }
+impl OptionOverload {
+    pub fn file(&self) -> bool {
+        matches!(self, OptionOverload::File | OptionOverload::Both)
+    }
 
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Default Finger Server servers".to_string(),
-                    ));
-                }
+    pub fn sname(&self) -> bool {
+        matches!(self, OptionOverload::Sname | OptionOverload::Both)
+    }
+}
 
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
+impl From<OptionOverload> for u8 {
+    fn from(overload: OptionOverload) -> u8 {
+        match overload {
+            OptionOverload::File => 1,
+            OptionOverload::Sname => 2,
+            OptionOverload::Both => 3,
+        }
+    }
+}
 
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
+impl TryFrom<u8> for OptionOverload {
+    type Error = u8;
 
-                Ok((DhcpOption::DefaultFingerServer(servers), data))
-            }
-            74 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Default Internet Relay Chat Server servers".to_string(),
-                    ));
-                }
+    fn try_from(value: u8) -> Result<OptionOverload, u8> {
+        match value {
+            1 => Ok(OptionOverload::File),
+            2 => Ok(OptionOverload::Sname),
+            3 => Ok(OptionOverload::Both),
+            value => Err(value),
+        }
+    }
+}
 
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse Default Internet Relay Chat Server servers"
-                                .to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Default Internet Relay Chat Server servers".to_string(),
-                    ));
-                }
+/// The content of the authentication option (RFC 3118).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Authentication {
+    pub protocol: u8,
+    pub algorithm: u8,
+    // Replay detection method.
+    pub rdm: u8,
+    pub replay_detection: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::schema::bytes"))]
+    pub information: Vec<u8>,
+}
 
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Default Internet Relay Chat Server servers".to_string(),
-                    ));
-                }
+/// The addresses of an address list option, such as routers or name
+/// servers.
+///
+/// With the `smallvec` feature, lists of up to two addresses, the most
+/// common, are stored inline in a `SmallVec` instead of allocating.
+#[derive(Clone, Default)]
+pub struct Addresses(Storage);
 
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
+#[cfg(not(feature = "smallvec"))]
+type Storage = Vec<Ipv4Addr>;
 
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
+#[cfg(feature = "smallvec")]
+type Storage = smallvec::SmallVec<[Ipv4Addr; 2]>;
 
-                Ok((DhcpOption::DefaultInternetRelayChatServer(servers), data))
-            }
-            75 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse StreetTalk Server servers".to_string(),
-                    ));
-                }
+impl Addresses {
+    pub fn new() -> Addresses {
+        Addresses::default()
+    }
 
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse StreetTalk Server servers".to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse StreetTalk Server servers".to_string(),
-                    ));
-                }
+    pub fn push(&mut self, address: Ipv4Addr) {
+        self.0.push(address);
+    }
 
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse StreetTalk Server servers".to_string(),
-                    ));
-                }
+    /// Whether the addresses are stored inline, without a heap allocation.
+    #[cfg(feature = "smallvec")]
+    pub fn is_inline(&self) -> bool {
+        !self.0.spilled()
+    }
+}
 
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
+impl std::ops::Deref for Addresses {
+    type Target = [Ipv4Addr];
 
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
+    fn deref(&self) -> &[Ipv4Addr] {
+        &self.0
+    }
+}
 
-                Ok((DhcpOption::StreetTalkServer(servers), data))
-            }
-            76 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse StreetTalk Directory Assistance Server servers"
-                            .to_string(),
-                    ));
-                }
+impl std::ops::DerefMut for Addresses {
+    fn deref_mut(&mut self) -> &mut [Ipv4Addr] {
+        &mut self.0
+    }
+}
 
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse StreetTalk Directory Assistance Server servers"
-                                .to_string(),
-                        ))
-                    }
-                };
-
-                // Verify that the length is possible.
-                if data.len() < len as usize {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse StreetTalk Directory Assistance Server servers"
-                            .to_string(),
-                    ));
-                }
+impl std::fmt::Debug for Addresses {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
 
-                // Verify that the length is a multiple of 4.
-                if len % 4 != 0 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse StreetTalk Directory Assistance Server servers"
-                            .to_string(),
-                    ));
-                }
+#[cfg(feature = "defmt")]
+impl defmt::Format for Addresses {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{}", &**self)
+    }
+}
 
-                // Retrieve the value.
-                let (servers, data) = data.split_at(len as usize);
-
-                let servers = servers
-                    .chunks_exact(4)
-                    .map(|server| Ipv4Addr::new(server[0], server[1], server[2], server[3]))
-                    .collect::<Vec<Ipv4Addr>>();
-
-                Ok((
-                    DhcpOption::StreetTalkDirectoryAssistanceServer(servers),
-                    data,
-                ))
-            }
-            50 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Requested IP Address".to_string(),
-                    ));
-                }
+#[cfg(feature = "serde")]
+impl serde::Serialize for Addresses {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
 
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse Requested IP Address".to_string(),
-                        ))
-                    }
-                };
-
-                // Check that the length is 4.
-                if len != 4 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse Requested IP Address".to_string(),
-                    ));
-                }
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Addresses {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Addresses, D::Error> {
+        Ok(Vec::<Ipv4Addr>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
 
-                // Retrieve the value.
-                let (addr, data) = data.split_at(4);
+impl PartialEq for Addresses {
+    fn eq(&self, other: &Addresses) -> bool {
+        **self == **other
+    }
+}
 
-                let addr = Ipv4Addr::new(addr[0], addr[1], addr[2], addr[3]);
+impl Eq for Addresses {}
 
-                Ok((DhcpOption::RequestedIpAddress(addr), data))
-            }
-            51 => {
-                // Check that the data has at least 4 bytes.
-                if data.len() < 5 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse IP Address Lease Time".to_string(),
-                    ));
-                }
+impl PartialEq<Vec<Ipv4Addr>> for Addresses {
+    fn eq(&self, other: &Vec<Ipv4Addr>) -> bool {
+        **self == **other
+    }
+}
 
-                // Retrieve the length of the option.
-                let (len, data) = match data.split_first() {
-                    Some((len, data)) => (*len, data),
-                    None => {
-                        return Err(DhcpError::ParsingError(
-                            "Could not parse IP Address Lease Time".to_string(),
-                        ))
-                    }
-                };
-
-                // Check that the length is 4.
-                if len != 4 {
-                    return Err(DhcpError::ParsingError(
-                        "Could not parse IP Address Lease Time".to_string(),
-                    ));
-                }
+impl From<Vec<Ipv4Addr>> for Addresses {
+    fn from(addresses: Vec<Ipv4Addr>) -> Addresses {
+        Addresses(Storage::from(addresses))
+    }
+}
 
-                // Retrieve the value.
-                let (time, data) = data.split_at(4);
+impl<const N: usize> From<[Ipv4Addr; N]> for Addresses {
+    fn from(addresses: [Ipv4Addr; N]) -> Addresses {
+        addresses.into_iter().collect()
+    }
+}
 
-                let time = u32::from_be_bytes([time[0], time[1], time[2], time[3]]);
+impl From<Addresses> for Vec<Ipv4Addr> {
+    fn from(addresses: Addresses) -> Vec<Ipv4Addr> {
+        addresses.to_vec()
+    }
+}
 
-                Ok((DhcpOption::IpAddressLeaseTime(time), data))
-            }
-            _ => Err(DhcpError::ParsingError(format!(
-                "Unknown option code: {}",
-                code
-            ))),
-        }
+impl FromIterator<Ipv4Addr> for Addresses {
+    fn from_iter<I: IntoIterator<Item = Ipv4Addr>>(iter: I) -> Addresses {
+        Addresses(iter.into_iter().collect())
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub enum NetBiosOverTcpIpNodeType {
-    BNode,
-    PNode,
-    MNode,
-    HNode,
+impl<'a> IntoIterator for &'a Addresses {
+    type Item = &'a Ipv4Addr;
+    type IntoIter = std::slice::Iter<'a, Ipv4Addr>;
+
+    fn into_iter(self) -> std::slice::Iter<'a, Ipv4Addr> {
+        self.iter()
+    }
 }
@@ -0,0 +1,221 @@
+//! Option codes, as assigned by IANA in the BOOTP Vendor Extensions and
+//! DHCP Options registry.
+
+use std::fmt;
+
+macro_rules! option_codes {
+    ($($code:literal => $variant:ident, $name:literal;)*) => {
+        /// The code of an option. Codes not assigned by IANA, those removed
+        /// from the registry, those of vendor-specific PXE options (128 to
+        /// 135) and those of site-specific options (224 to 254, RFC 3942)
+        /// are `OptionCode::Other`.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum OptionCode {
+            $($variant,)*
+            Other(u8),
+        }
+
+        impl From<u8> for OptionCode {
+            fn from(code: u8) -> OptionCode {
+                OptionCode::new(code)
+            }
+        }
+
+        impl From<OptionCode> for u8 {
+            fn from(code: OptionCode) -> u8 {
+                match code {
+                    $(OptionCode::$variant => $code,)*
+                    OptionCode::Other(code) => code,
+                }
+            }
+        }
+
+        impl OptionCode {
+            // As `From<u8>`, in constants such as the option definitions.
+            pub(crate) const fn new(code: u8) -> OptionCode {
+                match code {
+                    $($code => OptionCode::$variant,)*
+                    code => OptionCode::Other(code),
+                }
+            }
+
+            /// The name of the option.
+            pub const fn name(&self) -> &'static str {
+                match self {
+                    $(OptionCode::$variant => $name,)*
+                    OptionCode::Other(128..=135) => "PXE (vendor specific)",
+                    OptionCode::Other(224..=254) => "Site-specific",
+                    OptionCode::Other(_) => "Unassigned",
+                }
+            }
+        }
+    };
+}
+
+option_codes! {
+    0 => Pad, "Pad";
+    1 => SubnetMask, "Subnet Mask";
+    2 => TimeOffset, "Time Offset";
+    3 => Router, "Router";
+    4 => TimeServer, "Time Server";
+    5 => NameServer, "Name Server";
+    6 => DomainNameServer, "Domain Name Server";
+    7 => LogServer, "Log Server";
+    8 => CookieServer, "Cookie Server";
+    9 => LprServer, "LPR Server";
+    10 => ImpressServer, "Impress Server";
+    11 => ResourceLocationServer, "Resource Location Server";
+    12 => HostName, "Host Name";
+    13 => BootFileSize, "Boot File Size";
+    14 => MeritDumpFile, "Merit Dump File";
+    15 => DomainName, "Domain Name";
+    16 => SwapServer, "Swap Server";
+    17 => RootPath, "Root Path";
+    18 => ExtensionsPath, "Extensions Path";
+    19 => IpForwarding, "IP Forwarding";
+    20 => NonLocalSourceRouting, "Non-Local Source Routing";
+    21 => PolicyFilter, "Policy Filter";
+    22 => MaximumDatagramReassemblySize, "Maximum Datagram Reassembly Size";
+    23 => DefaultIpTimeToLive, "Default IP Time-to-live";
+    24 => PathMtuAgingTimeout, "Path MTU Aging Timeout";
+    25 => PathMtuPlateauTable, "Path MTU Plateau Table";
+    26 => InterfaceMtu, "Interface MTU";
+    27 => AllSubnetsAreLocal, "All Subnets are Local";
+    28 => BroadcastAddress, "Broadcast Address";
+    29 => PerformMaskDiscovery, "Perform Mask Discovery";
+    30 => MaskSupplier, "Mask Supplier";
+    31 => PerformRouterDiscovery, "Perform Router Discovery";
+    32 => RouterSolicitationAddress, "Router Solicitation Address";
+    33 => StaticRoute, "Static Route";
+    34 => TrailerEncapsulation, "Trailer Encapsulation";
+    35 => ArpCacheTimeout, "ARP Cache Timeout";
+    36 => EthernetEncapsulation, "Ethernet Encapsulation";
+    37 => TcpDefaultTtl, "TCP Default TTL";
+    38 => TcpKeepaliveInterval, "TCP Keepalive Interval";
+    39 => TcpKeepaliveGarbage, "TCP Keepalive Garbage";
+    40 => NetworkInformationServiceDomain, "Network Information Service Domain";
+    41 => NetworkInformationServers, "Network Information Servers";
+    42 => NetworkTimeProtocolServers, "Network Time Protocol Servers";
+    43 => VendorSpecificInformation, "Vendor Specific Information";
+    44 => NetBiosOverTcpIpNameServer, "NetBIOS over TCP/IP Name Server";
+    45 => NetBiosOverTcpIpDatagramDistributionServer, "NetBIOS over TCP/IP Datagram Distribution Server";
+    46 => NetBiosOverTcpIpNodeType, "NetBIOS over TCP/IP Node Type";
+    47 => NetBiosOverTcpIpScope, "NetBIOS over TCP/IP Scope";
+    48 => XWindowSystemFontServer, "X Window System Font Server";
+    49 => XWindowSystemDisplayManager, "X Window System Display Manager";
+    50 => RequestedIpAddress, "Requested IP Address";
+    51 => IpAddressLeaseTime, "IP Address Lease Time";
+    52 => OptionOverload, "Option Overload";
+    53 => DhcpMessageType, "DHCP Message Type";
+    54 => ServerIdentifier, "Server Identifier";
+    55 => ParameterRequestList, "Parameter Request List";
+    56 => Message, "Message";
+    57 => MaximumDhcpMessageSize, "Maximum DHCP Message Size";
+    58 => RenewalTimeValue, "Renewal (T1) Time Value";
+    59 => RebindingTimeValue, "Rebinding (T2) Time Value";
+    60 => VendorClassIdentifier, "Vendor Class Identifier";
+    61 => ClientIdentifier, "Client Identifier";
+    62 => NetWareIpDomainName, "NetWare/IP Domain Name";
+    63 => NetWareIpInformation, "NetWare/IP Information";
+    64 => NetworkInformationServicePlusDomain, "Network Information Service+ Domain";
+    65 => NetworkInformationServicePlusServers, "Network Information Service+ Servers";
+    66 => TftpServerName, "TFTP Server Name";
+    67 => BootfileName, "Bootfile Name";
+    68 => MobileIpHomeAgent, "Mobile IP Home Agent";
+    69 => SmtpServer, "SMTP Server";
+    70 => Pop3Server, "POP3 Server";
+    71 => NntpServer, "NNTP Server";
+    72 => DefaultWwwServer, "Default WWW Server";
+    73 => DefaultFingerServer, "Default Finger Server";
+    74 => DefaultIrcServer, "Default IRC Server";
+    75 => StreetTalkServer, "StreetTalk Server";
+    76 => StreetTalkDirectoryAssistanceServer, "StreetTalk Directory Assistance Server";
+    77 => UserClass, "User Class";
+    78 => DirectoryAgent, "SLP Directory Agent";
+    79 => ServiceScope, "SLP Service Scope";
+    80 => RapidCommit, "Rapid Commit";
+    81 => ClientFqdn, "Client FQDN";
+    82 => RelayAgentInformation, "Relay Agent Information";
+    83 => Isns, "iSNS";
+    85 => NdsServers, "NDS Servers";
+    86 => NdsTreeName, "NDS Tree Name";
+    87 => NdsContext, "NDS Context";
+    88 => BcmcsControllerDomainNameList, "BCMCS Controller Domain Name List";
+    89 => BcmcsControllerAddress, "BCMCS Controller IPv4 Address";
+    90 => Authentication, "Authentication";
+    91 => ClientLastTransactionTime, "Client Last Transaction Time";
+    92 => AssociatedIp, "Associated IP";
+    93 => ClientSystemArchitecture, "Client System Architecture";
+    94 => ClientNetworkInterface, "Client Network Interface Identifier";
+    95 => Ldap, "LDAP";
+    97 => ClientMachineIdentifier, "Client Machine Identifier";
+    98 => UserAuthentication, "User Authentication";
+    99 => GeoconfCivic, "GEOCONF_CIVIC";
+    100 => PosixTimezone, "IEEE 1003.1 TZ String";
+    101 => TzDatabase, "TZ Database String";
+    108 => Ipv6OnlyPreferred, "IPv6-Only Preferred";
+    109 => Dhcp4o6SourceAddress, "DHCP 4o6 Softwire Source Address";
+    112 => NetinfoAddress, "NetInfo Parent Server Address";
+    113 => NetinfoTag, "NetInfo Parent Server Tag";
+    114 => CaptivePortal, "Captive Portal";
+    116 => AutoConfigure, "Auto-Configure";
+    117 => NameServiceSearch, "Name Service Search";
+    118 => SubnetSelection, "Subnet Selection";
+    119 => DomainSearch, "Domain Search";
+    120 => SipServers, "SIP Servers";
+    121 => ClasslessStaticRoute, "Classless Static Route";
+    122 => Ccc, "CableLabs Client Configuration";
+    123 => GeoConf, "GeoConf";
+    124 => VendorIdentifyingVendorClass, "Vendor-Identifying Vendor Class";
+    125 => VendorIdentifyingVendorSpecificInformation, "Vendor-Identifying Vendor-Specific Information";
+    136 => PanaAgent, "PANA Authentication Agent";
+    137 => V4Lost, "LoST Server";
+    138 => CapwapAccessController, "CAPWAP Access Controller";
+    139 => MosAddress, "MoS IPv4 Address";
+    140 => MosFqdn, "MoS FQDN";
+    141 => SipUaConfigurationDomains, "SIP UA Configuration Service Domains";
+    142 => AndsfAddress, "ANDSF IPv4 Address";
+    143 => SztpRedirect, "SZTP Redirect";
+    144 => GeoLoc, "GeoLoc";
+    145 => ForcerenewNonceCapable, "Forcerenew Nonce Capable";
+    146 => RdnssSelection, "RDNSS Selection";
+    147 => DotsReferenceIdentifier, "DOTS Reference Identifier";
+    148 => DotsAddress, "DOTS Address";
+    150 => TftpServerAddress, "TFTP Server Address";
+    151 => StatusCode, "Status Code";
+    152 => BaseTime, "Base Time";
+    153 => StartTimeOfState, "Start Time of State";
+    154 => QueryStartTime, "Query Start Time";
+    155 => QueryEndTime, "Query End Time";
+    156 => DhcpState, "DHCP State";
+    157 => DataSource, "Data Source";
+    158 => PcpServer, "PCP Server";
+    159 => PortParameters, "Port Parameters";
+    161 => MudUrl, "MUD URL";
+    162 => Dnr, "Encrypted DNS Server";
+    175 => Etherboot, "Etherboot";
+    176 => IpTelephone, "IP Telephone";
+    177 => PacketCable, "PacketCable and CableHome";
+    208 => PxelinuxMagic, "PXELINUX Magic";
+    209 => ConfigurationFile, "Configuration File";
+    210 => PathPrefix, "Path Prefix";
+    211 => RebootTime, "Reboot Time";
+    212 => SixRd, "6RD";
+    213 => AccessDomain, "Access Network Domain Name";
+    220 => SubnetAllocation, "Subnet Allocation";
+    221 => VirtualSubnetSelection, "Virtual Subnet Selection";
+    255 => End, "End";
+}
+
+impl fmt::Display for OptionCode {
+    /// Display the name and number of the code, such as "Router (3)".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.name(), u8::from(*self))
+    }
+}
+
+impl PartialEq<u8> for OptionCode {
+    fn eq(&self, code: &u8) -> bool {
+        u8::from(*self) == *code
+    }
+}
@@ -0,0 +1,546 @@
+//! Options borrowing their value from the parsed data.
+//!
+//! `DhcpOptionRef` mirrors `DhcpOption` without allocating: strings are
+//! borrowed, unless invalid UTF-8 is replaced, opaque values are `&[u8]`,
+//! and lists are read from the raw value as they are iterated. It accepts
+//! and rejects exactly the options `DhcpOption::deserialize` does, and
+//! `to_owned` converts it to the equivalent `DhcpOption`.
+//!
+//! `OptionsIter` goes further, walking the options without decoding them
+//! at all, for tools looking for one option, such as a relay extracting
+//! the relay agent information option.
+
+use std::borrow::Cow;
+use std::net::Ipv4Addr;
+
+use crate::dns;
+use crate::error::DhcpError;
+use crate::message::{check_header, DhcpMessage, MessageType, TextPolicy};
+use crate::option::{
+    self, decode_text, node_type, overload, split_value, Authentication, DhcpOption,
+    NetBiosOverTcpIpNodeType, OptionOverload,
+};
+
+/// A list of IPv4 addresses, read from the option value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddressList<'a>(&'a [u8]);
+
+impl<'a> AddressList<'a> {
+    pub fn iter(&self) -> impl Iterator<Item = Ipv4Addr> + 'a {
+        self.0
+            .chunks_exact(4)
+            .map(|address| Ipv4Addr::new(address[0], address[1], address[2], address[3]))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len() / 4
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn to_vec(&self) -> Vec<Ipv4Addr> {
+        self.iter().collect()
+    }
+}
+
+/// A list of IPv4 address pairs, such as destinations and routers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddressPairList<'a>(&'a [u8]);
+
+impl<'a> AddressPairList<'a> {
+    pub fn iter(&self) -> impl Iterator<Item = (Ipv4Addr, Ipv4Addr)> + 'a {
+        self.0.chunks_exact(8).map(|pair| {
+            (
+                Ipv4Addr::new(pair[0], pair[1], pair[2], pair[3]),
+                Ipv4Addr::new(pair[4], pair[5], pair[6], pair[7]),
+            )
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len() / 8
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn to_vec(&self) -> Vec<(Ipv4Addr, Ipv4Addr)> {
+        self.iter().collect()
+    }
+}
+
+/// A list of 16-bit integers in network byte order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct U16List<'a>(&'a [u8]);
+
+impl<'a> U16List<'a> {
+    pub fn iter(&self) -> impl Iterator<Item = u16> + 'a {
+        self.0
+            .chunks_exact(2)
+            .map(|value| u16::from_be_bytes([value[0], value[1]]))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len() / 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn to_vec(&self) -> Vec<u16> {
+        self.iter().collect()
+    }
+}
+
+/// The content of the authentication option, see `Authentication`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthenticationRef<'a> {
+    pub protocol: u8,
+    pub algorithm: u8,
+    pub rdm: u8,
+    pub replay_detection: u64,
+    pub information: &'a [u8],
+}
+
+/// A borrowed `DhcpOption`, see the variants of `DhcpOption` for their
+/// meaning.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DhcpOptionRef<'a> {
+    Pad,
+    End,
+    SubnetMask(Ipv4Addr),
+    TimeOffset(u32),
+    Router(AddressList<'a>),
+    TimeServer(AddressList<'a>),
+    NameServer(AddressList<'a>),
+    DomainNameServer(AddressList<'a>),
+    LogServer(AddressList<'a>),
+    CookieServer(AddressList<'a>),
+    LprServer(AddressList<'a>),
+    ImpressServer(AddressList<'a>),
+    ResourceLocationServer(AddressList<'a>),
+    // Text is borrowed, unless invalid UTF-8 is replaced under
+    // `TextPolicy::Lossy`.
+    HostName(Cow<'a, str>),
+    BootFileSize(u16),
+    MeritDumpFile(Cow<'a, str>),
+    DomainName(Cow<'a, str>),
+    SwapServer(Ipv4Addr),
+    RootPath(Cow<'a, str>),
+    ExtensionsPath(Cow<'a, str>),
+    IpForwarding(bool),
+    NonLocalSourceRouting(bool),
+    PolicyFilter(AddressPairList<'a>),
+    MaximumDatagramReassemblySize(u16),
+    DefaultIpTimeToLive(u8),
+    PathMtuAgingTimeout(u32),
+    PathMtuPlateauTable(U16List<'a>),
+    InterfaceMtu(u16),
+    AllSubnetsAreLocal(bool),
+    BroadcastAddress(Ipv4Addr),
+    PerformMaskDiscovery(bool),
+    MaskSupplier(bool),
+    PerformRouterDiscovery(bool),
+    RouterSolicitationAddress(Ipv4Addr),
+    StaticRoute(AddressPairList<'a>),
+    TrailerEncapsulation(bool),
+    ArpCacheTimeout(u32),
+    EthernetEncapsulation(bool),
+    TcpDefaultTtl(u8),
+    TcpKeepaliveInterval(u32),
+    TcpKeepaliveGarbage(bool),
+    NetworkInformationServiceDomain(Cow<'a, str>),
+    NetworkInformationServers(AddressList<'a>),
+    NetworkTimeProtocolServers(AddressList<'a>),
+    VendorSpecificInformation(&'a [u8]),
+    NetBiosOverTcpIpNameServer(AddressList<'a>),
+    NetBiosOverTcpIpDatagramDistributionServer(AddressList<'a>),
+    NetBiosOverTcpIpNodeType(NetBiosOverTcpIpNodeType),
+    NetBiosOverTcpIpScope(&'a [u8]),
+    XWindowSystemFontServer(AddressList<'a>),
+    XWindowSystemDisplayManager(AddressList<'a>),
+    NetworkInformationServicePlusDomain(Cow<'a, str>),
+    NetworkInformationServicePlusServers(AddressList<'a>),
+    MobileIpHomeAgent(AddressList<'a>),
+    SimpleMailTransportProtocolServer(AddressList<'a>),
+    PostOfficeProtocolServer(AddressList<'a>),
+    NetworkNewsTransportProtocolServer(AddressList<'a>),
+    DefaultWorldWideWebServer(AddressList<'a>),
+    DefaultFingerServer(AddressList<'a>),
+    DefaultInternetRelayChatServer(AddressList<'a>),
+    StreetTalkServer(AddressList<'a>),
+    StreetTalkDirectoryAssistanceServer(AddressList<'a>),
+    RequestedIpAddress(Ipv4Addr),
+    IpAddressLeaseTime(u32),
+    OptionOverload(OptionOverload),
+    DhcpMessageType(MessageType),
+    ServerIdentifier(Ipv4Addr),
+    ClientIdentifier(&'a [u8]),
+    // The names as encoded in the option, possibly compressed. They are
+    // checked when parsing, and only decoded by `to_owned`.
+    DomainSearch(&'a [u8]),
+    Authentication(AuthenticationRef<'a>),
+    ForcerenewNonceCapable(&'a [u8]),
+    Unknown(u8, &'a [u8]),
+}
+
+impl<'a> DhcpOptionRef<'a> {
+    /// Deserialize an option, returning it with the data following it.
+    pub fn deserialize(data: &'a [u8]) -> Result<(DhcpOptionRef<'a>, &'a [u8]), DhcpError> {
+        DhcpOptionRef::deserialize_with(data, TextPolicy::Strict)
+    }
+
+    /// Deserialize an option as `deserialize` does, text options that are
+    /// not valid UTF-8 being decoded following `policy`, as by
+    /// `DhcpOption::deserialize_with`.
+    pub fn deserialize_with(
+        data: &'a [u8],
+        policy: TextPolicy,
+    ) -> Result<(DhcpOptionRef<'a>, &'a [u8]), DhcpError> {
+        // Retrieve the option code.
+        let (code, data) = match data.split_first() {
+            Some((code, data)) => (*code, data),
+            None => {
+                return Err(DhcpError::UnexpectedEof {
+                    field: "options",
+                    needed: 1,
+                    available: 0,
+                })
+            }
+        };
+        // Check the length against the rule of the option, as
+        // `DhcpOption::deserialize` does, so that the values given to the
+        // parsers of `option` have the length they expect.
+        let (value, data) = split_value(code, data)?;
+
+        let text = |option: fn(Cow<'a, str>) -> DhcpOptionRef<'a>| {
+            decode_text(code, value, policy).map(|text| match text {
+                Some(text) => option(text),
+                None => DhcpOptionRef::Unknown(code, value),
+            })
+        };
+        let address = || option::address(value);
+        let u16_value = || option::u16_value(value);
+        let u32_value = || option::u32_value(value);
+
+        let option = match code {
+            0 => DhcpOptionRef::Pad,
+            255 => DhcpOptionRef::End,
+            1 => DhcpOptionRef::SubnetMask(address()),
+            2 => DhcpOptionRef::TimeOffset(u32_value()),
+            3 => DhcpOptionRef::Router(AddressList(value)),
+            4 => DhcpOptionRef::TimeServer(AddressList(value)),
+            5 => DhcpOptionRef::NameServer(AddressList(value)),
+            6 => DhcpOptionRef::DomainNameServer(AddressList(value)),
+            7 => DhcpOptionRef::LogServer(AddressList(value)),
+            8 => DhcpOptionRef::CookieServer(AddressList(value)),
+            9 => DhcpOptionRef::LprServer(AddressList(value)),
+            10 => DhcpOptionRef::ImpressServer(AddressList(value)),
+            11 => DhcpOptionRef::ResourceLocationServer(AddressList(value)),
+            12 => text(DhcpOptionRef::HostName)?,
+            13 => DhcpOptionRef::BootFileSize(u16_value()),
+            14 => text(DhcpOptionRef::MeritDumpFile)?,
+            15 => text(DhcpOptionRef::DomainName)?,
+            16 => DhcpOptionRef::SwapServer(address()),
+            17 => text(DhcpOptionRef::RootPath)?,
+            18 => text(DhcpOptionRef::ExtensionsPath)?,
+            19 => DhcpOptionRef::IpForwarding(value[0] == 1),
+            20 => DhcpOptionRef::NonLocalSourceRouting(value[0] == 1),
+            21 => DhcpOptionRef::PolicyFilter(AddressPairList(value)),
+            22 => DhcpOptionRef::MaximumDatagramReassemblySize(u16_value()),
+            23 => DhcpOptionRef::DefaultIpTimeToLive(value[0]),
+            24 => DhcpOptionRef::PathMtuAgingTimeout(u32_value()),
+            25 => DhcpOptionRef::PathMtuPlateauTable(U16List(value)),
+            26 => DhcpOptionRef::InterfaceMtu(u16_value()),
+            27 => DhcpOptionRef::AllSubnetsAreLocal(value[0] != 0),
+            28 => DhcpOptionRef::BroadcastAddress(address()),
+            29 => DhcpOptionRef::PerformMaskDiscovery(value[0] != 0),
+            30 => DhcpOptionRef::MaskSupplier(value[0] != 0),
+            31 => DhcpOptionRef::PerformRouterDiscovery(value[0] != 0),
+            32 => DhcpOptionRef::RouterSolicitationAddress(address()),
+            33 => DhcpOptionRef::StaticRoute(AddressPairList(value)),
+            34 => DhcpOptionRef::TrailerEncapsulation(value[0] != 0),
+            35 => DhcpOptionRef::ArpCacheTimeout(u32_value()),
+            36 => DhcpOptionRef::EthernetEncapsulation(value[0] != 0),
+            37 => DhcpOptionRef::TcpDefaultTtl(value[0]),
+            38 => DhcpOptionRef::TcpKeepaliveInterval(u32_value()),
+            39 => DhcpOptionRef::TcpKeepaliveGarbage(value[0] != 0),
+            40 => text(DhcpOptionRef::NetworkInformationServiceDomain)?,
+            41 => DhcpOptionRef::NetworkInformationServers(AddressList(value)),
+            42 => DhcpOptionRef::NetworkTimeProtocolServers(AddressList(value)),
+            43 => DhcpOptionRef::VendorSpecificInformation(value),
+            44 => DhcpOptionRef::NetBiosOverTcpIpNameServer(AddressList(value)),
+            45 => DhcpOptionRef::NetBiosOverTcpIpDatagramDistributionServer(AddressList(value)),
+            46 => DhcpOptionRef::NetBiosOverTcpIpNodeType(node_type(code, value)?),
+            47 => DhcpOptionRef::NetBiosOverTcpIpScope(value),
+            48 => DhcpOptionRef::XWindowSystemFontServer(AddressList(value)),
+            49 => DhcpOptionRef::XWindowSystemDisplayManager(AddressList(value)),
+            50 => DhcpOptionRef::RequestedIpAddress(address()),
+            51 => DhcpOptionRef::IpAddressLeaseTime(u32_value()),
+            52 => DhcpOptionRef::OptionOverload(overload(code, value)?),
+            53 => match MessageType::try_from(value[0]) {
+                Ok(message_type) => DhcpOptionRef::DhcpMessageType(message_type),
+                Err(_) => DhcpOptionRef::Unknown(code, value),
+            },
+            54 => DhcpOptionRef::ServerIdentifier(address()),
+            61 => DhcpOptionRef::ClientIdentifier(value),
+            64 => text(DhcpOptionRef::NetworkInformationServicePlusDomain)?,
+            65 => DhcpOptionRef::NetworkInformationServicePlusServers(AddressList(value)),
+            68 => DhcpOptionRef::MobileIpHomeAgent(AddressList(value)),
+            69 => DhcpOptionRef::SimpleMailTransportProtocolServer(AddressList(value)),
+            70 => DhcpOptionRef::PostOfficeProtocolServer(AddressList(value)),
+            71 => DhcpOptionRef::NetworkNewsTransportProtocolServer(AddressList(value)),
+            72 => DhcpOptionRef::DefaultWorldWideWebServer(AddressList(value)),
+            73 => DhcpOptionRef::DefaultFingerServer(AddressList(value)),
+            74 => DhcpOptionRef::DefaultInternetRelayChatServer(AddressList(value)),
+            75 => DhcpOptionRef::StreetTalkServer(AddressList(value)),
+            76 => DhcpOptionRef::StreetTalkDirectoryAssistanceServer(AddressList(value)),
+            90 => {
+                let mut replay_detection = [0; 8];
+                replay_detection.copy_from_slice(&value[3..11]);
+                DhcpOptionRef::Authentication(AuthenticationRef {
+                    protocol: value[0],
+                    algorithm: value[1],
+                    rdm: value[2],
+                    replay_detection: u64::from_be_bytes(replay_detection),
+                    information: &value[11..],
+                })
+            }
+            119 => {
+                // Compressed names are checked by walking them.
+                dns::check_names(value)?;
+                DhcpOptionRef::DomainSearch(value)
+            }
+            145 => DhcpOptionRef::ForcerenewNonceCapable(value),
+            _ => DhcpOptionRef::Unknown(code, value),
+        };
+        Ok((option, data))
+    }
+
+    /// Convert to the equivalent owned option.
+    pub fn to_owned(&self) -> DhcpOption {
+        match self {
+            DhcpOptionRef::Pad => DhcpOption::Pad,
+            DhcpOptionRef::End => DhcpOption::End,
+            DhcpOptionRef::SubnetMask(mask) => DhcpOption::SubnetMask(*mask),
+            DhcpOptionRef::TimeOffset(offset) => DhcpOption::TimeOffset(*offset),
+            DhcpOptionRef::Router(list) => DhcpOption::Router(list.iter().collect()),
+            DhcpOptionRef::TimeServer(list) => DhcpOption::TimeServer(list.iter().collect()),
+            DhcpOptionRef::NameServer(list) => DhcpOption::NameServer(list.iter().collect()),
+            DhcpOptionRef::DomainNameServer(list) => {
+                DhcpOption::DomainNameServer(list.iter().collect())
+            }
+            DhcpOptionRef::LogServer(list) => DhcpOption::LogServer(list.iter().collect()),
+            DhcpOptionRef::CookieServer(list) => DhcpOption::CookieServer(list.iter().collect()),
+            DhcpOptionRef::LprServer(list) => DhcpOption::LprServer(list.iter().collect()),
+            DhcpOptionRef::ImpressServer(list) => DhcpOption::ImpressServer(list.iter().collect()),
+            DhcpOptionRef::ResourceLocationServer(list) => {
+                DhcpOption::ResourceLocationServer(list.iter().collect())
+            }
+            DhcpOptionRef::HostName(name) => DhcpOption::HostName(name.to_string()),
+            DhcpOptionRef::BootFileSize(size) => DhcpOption::BootFileSize(*size),
+            DhcpOptionRef::MeritDumpFile(file) => DhcpOption::MeritDumpFile(file.to_string()),
+            DhcpOptionRef::DomainName(name) => DhcpOption::DomainName(name.to_string()),
+            DhcpOptionRef::SwapServer(address) => DhcpOption::SwapServer(*address),
+            DhcpOptionRef::RootPath(path) => DhcpOption::RootPath(path.to_string()),
+            DhcpOptionRef::ExtensionsPath(path) => DhcpOption::ExtensionsPath(path.to_string()),
+            DhcpOptionRef::IpForwarding(value) => DhcpOption::IpForwarding(*value),
+            DhcpOptionRef::NonLocalSourceRouting(value) => {
+                DhcpOption::NonLocalSourceRouting(*value)
+            }
+            DhcpOptionRef::PolicyFilter(list) => DhcpOption::PolicyFilter(list.to_vec()),
+            DhcpOptionRef::MaximumDatagramReassemblySize(size) => {
+                DhcpOption::MaximumDatagramReassemblySize(*size)
+            }
+            DhcpOptionRef::DefaultIpTimeToLive(ttl) => DhcpOption::DefaultIpTimeToLive(*ttl),
+            DhcpOptionRef::PathMtuAgingTimeout(timeout) => {
+                DhcpOption::PathMtuAgingTimeout(*timeout)
+            }
+            DhcpOptionRef::PathMtuPlateauTable(list) => {
+                DhcpOption::PathMtuPlateauTable(list.to_vec())
+            }
+            DhcpOptionRef::InterfaceMtu(mtu) => DhcpOption::InterfaceMtu(*mtu),
+            DhcpOptionRef::AllSubnetsAreLocal(value) => DhcpOption::AllSubnetsAreLocal(*value),
+            DhcpOptionRef::BroadcastAddress(address) => DhcpOption::BroadcastAddress(*address),
+            DhcpOptionRef::PerformMaskDiscovery(value) => DhcpOption::PerformMaskDiscovery(*value),
+            DhcpOptionRef::MaskSupplier(value) => DhcpOption::MaskSupplier(*value),
+            DhcpOptionRef::PerformRouterDiscovery(value) => {
+                DhcpOption::PerformRouterDiscovery(*value)
+            }
+            DhcpOptionRef::RouterSolicitationAddress(address) => {
+                DhcpOption::RouterSolicitationAddress(*address)
+            }
+            DhcpOptionRef::StaticRoute(list) => DhcpOption::StaticRoute(list.to_vec()),
+            DhcpOptionRef::TrailerEncapsulation(value) => DhcpOption::TrailerEncapsulation(*value),
+            DhcpOptionRef::ArpCacheTimeout(timeout) => DhcpOption::ArpCacheTimeout(*timeout),
+            DhcpOptionRef::EthernetEncapsulation(value) => {
+                DhcpOption::EthernetEncapsulation(*value)
+            }
+            DhcpOptionRef::TcpDefaultTtl(ttl) => DhcpOption::TcpDefaultTtl(*ttl),
+            DhcpOptionRef::TcpKeepaliveInterval(interval) => {
+                DhcpOption::TcpKeepaliveInterval(*interval)
+            }
+            DhcpOptionRef::TcpKeepaliveGarbage(value) => DhcpOption::TcpKeepaliveGarbage(*value),
+            DhcpOptionRef::NetworkInformationServiceDomain(domain) => {
+                DhcpOption::NetworkInformationServiceDomain(domain.to_string())
+            }
+            DhcpOptionRef::NetworkInformationServers(list) => {
+                DhcpOption::NetworkInformationServers(list.iter().collect())
+            }
+            DhcpOptionRef::NetworkTimeProtocolServers(list) => {
+                DhcpOption::NetworkTimeProtocolServers(list.iter().collect())
+            }
+            DhcpOptionRef::VendorSpecificInformation(value) => {
+                DhcpOption::VendorSpecificInformation(value.to_vec())
+            }
+            DhcpOptionRef::NetBiosOverTcpIpNameServer(list) => {
+                DhcpOption::NetBiosOverTcpIpNameServer(list.iter().collect())
+            }
+            DhcpOptionRef::NetBiosOverTcpIpDatagramDistributionServer(list) => {
+                DhcpOption::NetBiosOverTcpIpDatagramDistributionServer(list.iter().collect())
+            }
+            DhcpOptionRef::NetBiosOverTcpIpNodeType(node_type) => {
+                DhcpOption::NetBiosOverTcpIpNodeType(node_type.clone())
+            }
+            DhcpOptionRef::NetBiosOverTcpIpScope(scope) => {
+                DhcpOption::NetBiosOverTcpIpScope(scope.to_vec())
+            }
+            DhcpOptionRef::XWindowSystemFontServer(list) => {
+                DhcpOption::XWindowSystemFontServer(list.iter().collect())
+            }
+            DhcpOptionRef::XWindowSystemDisplayManager(list) => {
+                DhcpOption::XWindowSystemDisplayManager(list.iter().collect())
+            }
+            DhcpOptionRef::NetworkInformationServicePlusDomain(domain) => {
+                DhcpOption::NetworkInformationServicePlusDomain(domain.to_string())
+            }
+            DhcpOptionRef::NetworkInformationServicePlusServers(list) => {
+                DhcpOption::NetworkInformationServicePlusServers(list.iter().collect())
+            }
+            DhcpOptionRef::MobileIpHomeAgent(list) => {
+                DhcpOption::MobileIpHomeAgent(list.iter().collect())
+            }
+            DhcpOptionRef::SimpleMailTransportProtocolServer(list) => {
+                DhcpOption::SimpleMailTransportProtocolServer(list.iter().collect())
+            }
+            DhcpOptionRef::PostOfficeProtocolServer(list) => {
+                DhcpOption::PostOfficeProtocolServer(list.iter().collect())
+            }
+            DhcpOptionRef::NetworkNewsTransportProtocolServer(list) => {
+                DhcpOption::NetworkNewsTransportProtocolServer(list.iter().collect())
+            }
+            DhcpOptionRef::DefaultWorldWideWebServer(list) => {
+                DhcpOption::DefaultWorldWideWebServer(list.iter().collect())
+            }
+            DhcpOptionRef::DefaultFingerServer(list) => {
+                DhcpOption::DefaultFingerServer(list.iter().collect())
+            }
+            DhcpOptionRef::DefaultInternetRelayChatServer(list) => {
+                DhcpOption::DefaultInternetRelayChatServer(list.iter().collect())
+            }
+            DhcpOptionRef::StreetTalkServer(list) => {
+                DhcpOption::StreetTalkServer(list.iter().collect())
+            }
+            DhcpOptionRef::StreetTalkDirectoryAssistanceServer(list) => {
+                DhcpOption::StreetTalkDirectoryAssistanceServer(list.iter().collect())
+            }
+            DhcpOptionRef::RequestedIpAddress(address) => DhcpOption::RequestedIpAddress(*address),
+            DhcpOptionRef::IpAddressLeaseTime(time) => DhcpOption::IpAddressLeaseTime(*time),
+            DhcpOptionRef::OptionOverload(overload) => DhcpOption::OptionOverload(*overload),
+            DhcpOptionRef::DhcpMessageType(message_type) => {
+                DhcpOption::DhcpMessageType(*message_type)
+            }
+            DhcpOptionRef::ServerIdentifier(address) => DhcpOption::ServerIdentifier(*address),
+            DhcpOptionRef::ClientIdentifier(identifier) => {
+                DhcpOption::ClientIdentifier(identifier.to_vec())
+            }
+            DhcpOptionRef::DomainSearch(names) => DhcpOption::DomainSearch(
+                dns::decode_names(names).expect("names checked when parsing"),
+            ),
+            DhcpOptionRef::Authentication(authentication) => {
+                DhcpOption::Authentication(Authentication {
+                    protocol: authentication.protocol,
+                    algorithm: authentication.algorithm,
+                    rdm: authentication.rdm,
+                    replay_detection: authentication.replay_detection,
+                    information: authentication.information.to_vec(),
+                })
+            }
+            DhcpOptionRef::ForcerenewNonceCapable(algorithms) => {
+                DhcpOption::ForcerenewNonceCapable(algorithms.to_vec())
+            }
+            DhcpOptionRef::Unknown(code, value) => DhcpOption::Unknown(*code, value.to_vec()),
+        }
+    }
+}
+
+/// The options of a buffer, as code and undecoded value, read as they are
+/// iterated. Pad options are skipped, and the iteration stops at the End
+/// option. An option running past the end of the buffer yields a
+/// `DhcpError::Truncated` error, and ends the iteration.
+#[derive(Debug, Clone)]
+pub struct OptionsIter<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> OptionsIter<'a> {
+    /// Walk the options of `data`, which starts with the first option.
+    pub fn new(data: &'a [u8]) -> OptionsIter<'a> {
+        OptionsIter { data }
+    }
+
+    /// Walk the options field of a message, failing if the header or the
+    /// magic cookie is missing.
+    pub fn from_message(data: &'a [u8]) -> Result<OptionsIter<'a>, DhcpError> {
+        check_header(data)?;
+        Ok(OptionsIter::new(&data[DhcpMessage::HEADER_LEN..]))
+    }
+
+    /// The value of the first option with code `code`, if any before an
+    /// error.
+    pub fn find_code(self, code: u8) -> Option<&'a [u8]> {
+        self.map_while(Result::ok)
+            .find(|(option, _)| *option == code)
+            .map(|(_, value)| value)
+    }
+}
+
+impl<'a> Iterator for OptionsIter<'a> {
+    type Item = Result<(u8, &'a [u8]), DhcpError>;
+
+    fn next(&mut self) -> Option<Result<(u8, &'a [u8]), DhcpError>> {
+        let start = self.data.iter().position(|code| *code != 0)?;
+        let (code, data) = match &self.data[start..] {
+            [255, ..] | [] => {
+                self.data = &[];
+                return None;
+            }
+            [code, data @ ..] => (*code, data),
+        };
+        self.data = &[];
+        let len = match data.first() {
+            Some(len) => *len as usize,
+            None => {
+                return Some(Err(DhcpError::Truncated {
+                    code,
+                    needed: 1,
+                    available: 0,
+                }))
+            }
+        };
+        if data.len() < 1 + len {
+            return Some(Err(DhcpError::Truncated {
+                code,
+                needed: 1 + len,
+                available: data.len(),
+            }));
+        }
+        let (value, rest) = data[1..].split_at(len);
+        self.data = rest;
+        Some(Ok((code, value)))
+    }
+}
@@ -0,0 +1,389 @@
+//! The options of a DHCPv4 message.
+
+use std::ops::Deref;
+
+use crate::option::DhcpOption;
+
+/// The options of a message, in order.
+///
+/// Alongside the list, the position of the first option of each code is
+/// kept up to date, so that `get` is a lookup rather than a scan of the
+/// list. The list can be read as a slice, and changed through the methods
+/// below only. Those keyed by code, `contains`, `insert` and `remove`, make
+/// it usable as a map from codes to options.
+#[derive(Clone)]
+pub struct DhcpOptions {
+    options: Vec<DhcpOption>,
+    // The position of the first option of each code plus one, or zero for
+    // the codes with no option. Positions from `INDEXED` on are not
+    // indexed, and searched for instead.
+    index: [u16; 256],
+}
+
+const INDEXED: usize = u16::MAX as usize;
+
+const OPTION_SUBNET_MASK: u8 = 1;
+const OPTION_ROUTER: u8 = 3;
+const OPTION_LEASE_TIME: u8 = 51;
+const OPTION_OPTION_OVERLOAD: u8 = 52;
+const OPTION_MESSAGE_TYPE: u8 = 53;
+const OPTION_SERVER_IDENTIFIER: u8 = 54;
+const OPTION_MESSAGE: u8 = 56;
+const OPTION_RENEWAL_TIME: u8 = 58;
+const OPTION_REBINDING_TIME: u8 = 59;
+const OPTION_RELAY_AGENT_INFORMATION: u8 = 82;
+
+/// How options whose code appears more than once in a message are handled.
+/// Pad and End options are left alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicatePolicy {
+    // Keep every option, in order.
+    #[default]
+    Keep,
+    // Keep the first option of each code.
+    FirstWins,
+    // Keep the last option of each code.
+    LastWins,
+    // Append the value of each option to that of the previous option of
+    // the same code, as RFC 3396 does for split options. Options whose
+    // concatenation is not a valid value are kept apart.
+    Concatenate,
+}
+
+impl DhcpOptions {
+    pub fn new() -> DhcpOptions {
+        DhcpOptions {
+            options: Vec::new(),
+            index: [0; 256],
+        }
+    }
+
+    /// The first option with code `code`.
+    pub fn get(&self, code: u8) -> Option<&DhcpOption> {
+        self.position(code).map(|position| &self.options[position])
+    }
+
+    /// Whether there is an option with code `code`.
+    pub fn contains(&self, code: u8) -> bool {
+        self.get(code).is_some()
+    }
+
+    /// Replace the first option with the code of `option`, returning it, or
+    /// append `option` if there is none.
+    pub fn insert(&mut self, option: DhcpOption) -> Option<DhcpOption> {
+        match self.position(option.raw_code()) {
+            Some(position) => Some(std::mem::replace(&mut self.options[position], option)),
+            None => {
+                self.push(option);
+                None
+            }
+        }
+    }
+
+    /// Remove the options with code `code`, returning the first.
+    pub fn remove(&mut self, code: u8) -> Option<DhcpOption> {
+        let position = self.position(code)?;
+        let option = self.options.remove(position);
+        self.options.retain(|option| option.raw_code() != code);
+        self.reindex();
+        Some(option)
+    }
+
+    /// Leave at most one option of each code, as `policy` says, except
+    /// for the options `DuplicatePolicy::Concatenate` cannot concatenate.
+    pub fn dedupe(&mut self, policy: DuplicatePolicy) {
+        let duplicated = |option: &DhcpOption| !matches!(option, DhcpOption::Pad | DhcpOption::End);
+        match policy {
+            DuplicatePolicy::Keep => {}
+            DuplicatePolicy::FirstWins => {
+                let mut seen = [false; 256];
+                self.retain(|option| {
+                    let code = option.raw_code() as usize;
+                    !duplicated(option) || !std::mem::replace(&mut seen[code], true)
+                });
+            }
+            DuplicatePolicy::LastWins => {
+                let mut last = [0; 256];
+                for (position, option) in self.options.iter().enumerate() {
+                    last[option.raw_code() as usize] = position;
+                }
+                let mut position = 0;
+                self.retain(|option| {
+                    position += 1;
+                    !duplicated(option) || last[option.raw_code() as usize] == position - 1
+                });
+            }
+            DuplicatePolicy::Concatenate => {
+                // The position of the option each code is appended to.
+                let mut targets = [None; 256];
+                let mut options: Vec<DhcpOption> = Vec::with_capacity(self.options.len());
+                for option in std::mem::take(&mut self.options) {
+                    let code = option.raw_code() as usize;
+                    if duplicated(&option) {
+                        if let Some(target) = targets[code] {
+                            let concatenated = DhcpOption::concatenate(&options[target], &option);
+                            if let Some(concatenated) = concatenated {
+                                options[target] = concatenated;
+                                continue;
+                            }
+                        }
+                        targets[code] = Some(options.len());
+                    }
+                    options.push(option);
+                }
+                self.options = options;
+                self.reindex();
+            }
+        }
+    }
+
+    /// Sort the options in the order replies are expected to have:
+    ///
+    /// - the DHCP Message Type option first, then the Server Identifier,
+    ///   then the lease times (codes 51, 58 and 59), which clients look for
+    ///   first,
+    /// - then the others by code, so that the Subnet Mask precedes the
+    ///   Router option (RFC 2132 section 3.3),
+    /// - then the Relay Agent Information option, which relays append
+    ///   last (RFC 3046 section 2.1),
+    /// - then Pad and End options.
+    ///
+    /// The sort is stable: options of the same code keep their order.
+    pub fn order(&mut self) {
+        self.options.sort_by_key(|option| {
+            let code = option.raw_code();
+            let group = match option {
+                DhcpOption::Pad => 5,
+                DhcpOption::End => 6,
+                _ => match code {
+                    OPTION_MESSAGE_TYPE => 0,
+                    OPTION_SERVER_IDENTIFIER => 1,
+                    OPTION_LEASE_TIME | OPTION_RENEWAL_TIME | OPTION_REBINDING_TIME => 2,
+                    OPTION_RELAY_AGENT_INFORMATION => 4,
+                    _ => 3,
+                },
+            };
+            (group, code)
+        });
+        self.reindex();
+    }
+
+    pub fn push(&mut self, option: DhcpOption) {
+        let code = option.raw_code() as usize;
+        if self.index[code] == 0 && self.options.len() < INDEXED {
+            self.index[code] = self.options.len() as u16 + 1;
+        }
+        self.options.push(option);
+    }
+
+    pub fn pop(&mut self) -> Option<DhcpOption> {
+        let option = self.options.pop()?;
+        let code = option.raw_code() as usize;
+        if self.index[code] as usize == self.options.len() + 1 {
+            self.index[code] = 0;
+        }
+        Some(option)
+    }
+
+    pub fn extend_from_slice(&mut self, options: &[DhcpOption]) {
+        self.extend(options.iter().cloned());
+    }
+
+    /// Keep only the options for which `keep` returns true.
+    pub fn retain<F: FnMut(&DhcpOption) -> bool>(&mut self, keep: F) {
+        self.options.retain(keep);
+        self.reindex();
+    }
+
+    pub fn clear(&mut self) {
+        self.options.clear();
+        self.index = [0; 256];
+    }
+
+    // The position of the first option with code `code`.
+    fn position(&self, code: u8) -> Option<usize> {
+        match self.index[code as usize] {
+            0 if self.options.len() > INDEXED => self.options[INDEXED..]
+                .iter()
+                .position(|option| option.raw_code() == code)
+                .map(|position| INDEXED + position),
+            0 => None,
+            position => Some(position as usize - 1),
+        }
+    }
+
+    fn reindex(&mut self) {
+        self.index = [0; 256];
+        for (position, option) in self.options.iter().enumerate().take(INDEXED) {
+            let code = option.raw_code() as usize;
+            if self.index[code] == 0 {
+                self.index[code] = position as u16 + 1;
+            }
+        }
+    }
+}
+
+/// The options of a reply to a client whose Parameter Request List is
+/// `prl`, taken from `options`.
+///
+/// The options every reply carries whatever the client asks for come
+/// first, in the order of `DhcpOptions::order`: the message type, server
+/// identifier, lease times, option overload and message. The options the
+/// client asks for follow, in the order it asks for them (RFC 2131 section
+/// 4.3.1), except that a requested Subnet Mask always precedes the Router
+/// option (RFC 2132 section 3.3). The Relay Agent Information option echoed
+/// to relays comes last. The other options are dropped.
+pub fn filter_by_prl(options: &DhcpOptions, prl: &[u8]) -> DhcpOptions {
+    let mut filtered: DhcpOptions = options
+        .iter()
+        .filter(|option| {
+            matches!(
+                option.raw_code(),
+                OPTION_MESSAGE_TYPE
+                    | OPTION_SERVER_IDENTIFIER
+                    | OPTION_LEASE_TIME
+                    | OPTION_RENEWAL_TIME
+                    | OPTION_REBINDING_TIME
+                    | OPTION_OPTION_OVERLOAD
+                    | OPTION_MESSAGE
+            )
+        })
+        .cloned()
+        .collect();
+    filtered.order();
+
+    let mut requested = [false; 256];
+    for code in prl {
+        let codes = match *code {
+            OPTION_ROUTER if prl.contains(&OPTION_SUBNET_MASK) => {
+                &[OPTION_SUBNET_MASK, OPTION_ROUTER][..]
+            }
+            _ => std::slice::from_ref(code),
+        };
+        for code in codes {
+            if std::mem::replace(&mut requested[*code as usize], true)
+                || filtered.contains(*code)
+                || matches!(*code, 0 | 255 | OPTION_RELAY_AGENT_INFORMATION)
+            {
+                continue;
+            }
+            filtered.extend(
+                options
+                    .iter()
+                    .filter(|option| option.raw_code() == *code)
+                    .cloned(),
+            );
+        }
+    }
+    filtered.extend(
+        options
+            .iter()
+            .filter(|option| option.raw_code() == OPTION_RELAY_AGENT_INFORMATION)
+            .cloned(),
+    );
+    filtered
+}
+
+impl Default for DhcpOptions {
+    fn default() -> DhcpOptions {
+        DhcpOptions::new()
+    }
+}
+
+impl Deref for DhcpOptions {
+    type Target = [DhcpOption];
+
+    fn deref(&self) -> &[DhcpOption] {
+        &self.options
+    }
+}
+
+impl std::fmt::Debug for DhcpOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(&self.options).finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for DhcpOptions {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{}", self.options.as_slice())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for DhcpOptions {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for DhcpOptions {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<DhcpOptions, D::Error> {
+        Vec::<DhcpOption>::deserialize(deserializer).map(DhcpOptions::from)
+    }
+}
+
+impl PartialEq for DhcpOptions {
+    fn eq(&self, other: &DhcpOptions) -> bool {
+        self.options == other.options
+    }
+}
+
+impl PartialEq<Vec<DhcpOption>> for DhcpOptions {
+    fn eq(&self, other: &Vec<DhcpOption>) -> bool {
+        &self.options == other
+    }
+}
+
+impl From<Vec<DhcpOption>> for DhcpOptions {
+    fn from(options: Vec<DhcpOption>) -> DhcpOptions {
+        let mut options = DhcpOptions {
+            options,
+            index: [0; 256],
+        };
+        options.reindex();
+        options
+    }
+}
+
+impl From<DhcpOptions> for Vec<DhcpOption> {
+    fn from(options: DhcpOptions) -> Vec<DhcpOption> {
+        options.options
+    }
+}
+
+impl FromIterator<DhcpOption> for DhcpOptions {
+    fn from_iter<I: IntoIterator<Item = DhcpOption>>(iter: I) -> DhcpOptions {
+        let mut options = DhcpOptions::new();
+        options.extend(iter);
+        options
+    }
+}
+
+impl Extend<DhcpOption> for DhcpOptions {
+    fn extend<I: IntoIterator<Item = DhcpOption>>(&mut self, iter: I) {
+        for option in iter {
+            self.push(option);
+        }
+    }
+}
+
+impl IntoIterator for DhcpOptions {
+    type Item = DhcpOption;
+    type IntoIter = std::vec::IntoIter<DhcpOption>;
+
+    fn into_iter(self) -> std::vec::IntoIter<DhcpOption> {
+        self.options.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a DhcpOptions {
+    type Item = &'a DhcpOption;
+    type IntoIter = std::slice::Iter<'a, DhcpOption>;
+
+    fn into_iter(self) -> std::slice::Iter<'a, DhcpOption> {
+        self.options.iter()
+    }
+}
@@ -0,0 +1,94 @@
+use crate::error::DhcpError;
+use crate::message::MAGIC_COOKIE;
+
+// Offsets of the fixed BOOTP header fields.
+pub(crate) const OP_OFFSET: usize = 0;
+pub(crate) const HOPS_OFFSET: usize = 3;
+pub(crate) const XID_OFFSET: usize = 4;
+pub(crate) const CIADDR_OFFSET: usize = 12;
+pub(crate) const YIADDR_OFFSET: usize = 16;
+pub(crate) const GIADDR_OFFSET: usize = 24;
+pub(crate) const CHADDR_OFFSET: usize = 28;
+pub(crate) const MAGIC_COOKIE_OFFSET: usize = 236;
+pub(crate) const OPTIONS_OFFSET: usize = 240;
+
+pub(crate) const BOOTREQUEST: u8 = 1;
+pub(crate) const BOOTREPLY: u8 = 2;
+
+const OPTION_PAD: u8 = 0;
+const OPTION_END: u8 = 255;
+const OPTION_MESSAGE_TYPE: u8 = 53;
+
+// Build the fixed header of an Ethernet DHCP packet, ready for options.
+pub(crate) fn new_packet(op: u8, xid: u32) -> Vec<u8> {
+    let mut packet = vec![0; OPTIONS_OFFSET];
+    packet[OP_OFFSET] = op;
+    packet[1] = 1;
+    packet[2] = 6;
+    packet[XID_OFFSET..XID_OFFSET + 4].copy_from_slice(&xid.to_be_bytes());
+    packet[MAGIC_COOKIE_OFFSET..OPTIONS_OFFSET].copy_from_slice(&MAGIC_COOKIE);
+    packet
+}
+
+// Retrieve the value of the DHCP message type option.
+pub(crate) fn message_type(packet: &[u8]) -> Result<Option<u8>, DhcpError> {
+    match find_option(packet, OPTION_MESSAGE_TYPE)? {
+        Some((start, 1)) => Ok(Some(packet[start + 2])),
+        Some((_, len)) => Err(DhcpError::InvalidLength {
+            code: OPTION_MESSAGE_TYPE,
+            len,
+        }),
+        None => Ok(None),
+    }
+}
+
+// Validate the header and walk the options, returning the offset of the End option.
+pub(crate) fn options_end(packet: &[u8]) -> Result<usize, DhcpError> {
+    if packet.len() < OPTIONS_OFFSET || packet[MAGIC_COOKIE_OFFSET..OPTIONS_OFFSET] != MAGIC_COOKIE
+    {
+        return Err(DhcpError::ParsingError(
+            "Could not parse DHCP packet header".into(),
+        ));
+    }
+
+    let mut index = OPTIONS_OFFSET;
+    while index < packet.len() {
+        match packet[index] {
+            OPTION_PAD => index += 1,
+            OPTION_END => return Ok(index),
+            _ => {
+                if index + 1 >= packet.len()
+                    || index + 2 + packet[index + 1] as usize > packet.len()
+                {
+                    break;
+                }
+                index += 2 + packet[index + 1] as usize;
+            }
+        }
+    }
+
+    Err(DhcpError::ParsingError(
+        "Could not find end of DHCP options".into(),
+    ))
+}
+
+// Find an option in the options field, returning its offset and value length.
+pub(crate) fn find_option(packet: &[u8], code: u8) -> Result<Option<(usize, usize)>, DhcpError> {
+    let end = options_end(packet)?;
+
+    let mut index = OPTIONS_OFFSET;
+    while index < end {
+        match packet[index] {
+            OPTION_PAD => index += 1,
+            option => {
+                let len = packet[index + 1] as usize;
+                if option == code {
+                    return Ok(Some((index, len)));
+                }
+                index += 2 + len;
+            }
+        }
+    }
+
+    Ok(None)
+}
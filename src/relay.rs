@@ -0,0 +1,51 @@
+//! Relay agent semantics of the giaddr and hops fields (RFC 1542 section
+//! 4, RFC 2131 section 4.1), shared by relay agents and servers.
+
+use std::net::{Ipv4Addr, SocketAddrV4};
+
+use crate::error::DhcpError;
+use crate::message::{DhcpMessage, MessageType};
+
+const SERVER_PORT: u16 = 67;
+const CLIENT_PORT: u16 = 68;
+
+impl DhcpMessage {
+    /// The most relay agents a request may cross (RFC 1542 section
+    /// 4.1.1).
+    pub const MAX_HOPS: u8 = 16;
+
+    /// Whether the message went through a relay agent, which set giaddr.
+    pub fn is_relayed(&self) -> bool {
+        !self.giaddr.is_unspecified()
+    }
+
+    /// Count one more relay agent in hops, returning the new count. Fails
+    /// with `DhcpError::TooManyHops` if the message already crossed
+    /// `DhcpMessage::MAX_HOPS` agents: relay agents must then drop it.
+    pub fn increment_hops(&mut self) -> Result<u8, DhcpError> {
+        if self.hops >= DhcpMessage::MAX_HOPS {
+            return Err(DhcpError::TooManyHops { hops: self.hops });
+        }
+        self.hops += 1;
+        Ok(self.hops)
+    }
+
+    /// Where a server sends `reply` to this request (RFC 2131 section
+    /// 4.1): to the relay agent if any, to the client address if it has
+    /// one, and broadcast otherwise. A DHCPNAK is broadcast to clients, as
+    /// they may have lost their address.
+    ///
+    /// Unicasting to yiaddr, when the client did not set the broadcast
+    /// flag, needs an ARP entry the server cannot add from a UDP socket:
+    /// those replies are broadcast too.
+    pub fn reply_destination(&self, reply: &DhcpMessage) -> SocketAddrV4 {
+        if self.is_relayed() {
+            return SocketAddrV4::new(self.giaddr, SERVER_PORT);
+        }
+        let is_nak = matches!(reply.message_type(), Ok(Some(MessageType::Nak)));
+        if !self.ciaddr.is_unspecified() && !is_nak {
+            return SocketAddrV4::new(self.ciaddr, CLIENT_PORT);
+        }
+        SocketAddrV4::new(Ipv4Addr::BROADCAST, CLIENT_PORT)
+    }
+}
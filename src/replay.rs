@@ -0,0 +1,203 @@
+//! Replaying declarative message exchanges against a DHCP engine.
+//!
+//! A scenario is a JSON document listing the messages to send, in order,
+//! with the reply expected for each of them:
+//!
+//! ```json
+//! {"steps": [
+//!   {"time": 100,
+//!    "send": {"op": 1, "xid": 1, "chaddr": "00:00:00:00:00:01",
+//!             "options": [{"name": "dhcp_message_type", "value": 1}]},
+//!    "expect": {"yiaddr": "10.0.0.10",
+//!               "options": [{"name": "dhcp_message_type", "value": 2}]}},
+//!   {"send": {"options": [{"name": "dhcp_message_type", "value": 7}]},
+//!    "expect": null}
+//! ]}
+//! ```
+//!
+//! Messages use the JSON representation of `DhcpMessage`. Expectations are
+//! partial: only the header fields they list are compared, and the options
+//! they list must be present in the reply. An expectation of `null` means
+//! no reply, and a step without one is not checked. Steps without a time
+//! happen at the time of the previous step.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::error::DhcpError;
+use crate::json::json_error;
+use crate::message::{DhcpMessage, Difference};
+use crate::server::Server;
+
+/// Something answering DHCP messages, such as a server.
+pub trait Engine {
+    /// Handle a message received at `time` (seconds since the Unix epoch),
+    /// returning the reply if any.
+    fn handle(&mut self, message: &DhcpMessage, time: u32) -> Option<DhcpMessage>;
+}
+
+impl Engine for Server {
+    fn handle(&mut self, message: &DhcpMessage, time: u32) -> Option<DhcpMessage> {
+        Server::handle(self, message, time, &mut Vec::new())
+    }
+}
+
+/// The reply expected for a step.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expectation {
+    // The reply is not checked.
+    Any,
+    // No reply is sent.
+    Nothing,
+    // A reply is sent, matching the listed header fields and options.
+    Reply {
+        message: Box<DhcpMessage>,
+        fields: Vec<String>,
+    },
+}
+
+/// A message to send, with the reply expected for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub time: u32,
+    pub send: DhcpMessage,
+    pub expect: Expectation,
+}
+
+/// A sequence of steps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    pub steps: Vec<Step>,
+}
+
+// A scenario, as read.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioFields {
+    steps: Vec<StepFields>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StepFields {
+    time: Option<u32>,
+    send: DhcpMessage,
+    // `None` when missing, and `Some(Value::Null)` for `null`.
+    #[serde(default, deserialize_with = "present")]
+    expect: Option<Value>,
+}
+
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// A reply not matching its expectation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    // The index of the failing step.
+    pub step: usize,
+    // The header field or option that differs.
+    pub field: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Step {}: expected {} {}, got {}",
+            self.step, self.field, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+impl Scenario {
+    /// Parse a scenario from JSON.
+    pub fn from_json(json: &str) -> Result<Scenario, DhcpError> {
+        let document: ScenarioFields = serde_json::from_str(json).map_err(json_error)?;
+        let mut time = 0;
+        let mut steps = Vec::with_capacity(document.steps.len());
+        for step in document.steps {
+            time = step.time.unwrap_or(time);
+            let expect = match step.expect {
+                None => Expectation::Any,
+                Some(Value::Null) => Expectation::Nothing,
+                Some(value) => {
+                    let fields = match &value {
+                        Value::Object(fields) => fields.keys().cloned().collect(),
+                        _ => Vec::new(),
+                    };
+                    let message = serde_json::from_value(value).map_err(json_error)?;
+                    Expectation::Reply {
+                        message: Box::new(message),
+                        fields,
+                    }
+                }
+            };
+            steps.push(Step {
+                time,
+                send: step.send,
+                expect,
+            });
+        }
+
+        Ok(Scenario { steps })
+    }
+
+    /// Send every step to `engine`, stopping at the first reply not
+    /// matching its expectation.
+    pub fn run<E: Engine>(&self, engine: &mut E) -> Result<(), Mismatch> {
+        for (index, step) in self.steps.iter().enumerate() {
+            let reply = engine.handle(&step.send, step.time);
+            let mismatch = |field: &str, expected: String, actual: String| Mismatch {
+                step: index,
+                field: field.to_string(),
+                expected,
+                actual,
+            };
+
+            match (&step.expect, reply) {
+                (Expectation::Any, _) | (Expectation::Nothing, None) => {}
+                (Expectation::Nothing, Some(reply)) => {
+                    return Err(mismatch("reply", "none".into(), reply.to_json()))
+                }
+                (Expectation::Reply { .. }, None) => {
+                    return Err(mismatch("reply", "a reply".into(), "none".into()))
+                }
+                (Expectation::Reply { message, fields }, Some(reply)) => {
+                    let checks_options = fields.iter().any(|field| field == "options");
+                    for difference in message.diff(&reply) {
+                        match difference {
+                            Difference::Field { name, left, right }
+                                if fields.iter().any(|field| field == name) =>
+                            {
+                                return Err(mismatch(name, left, right))
+                            }
+                            Difference::OptionRemoved(option) if checks_options => {
+                                return Err(mismatch(
+                                    "option",
+                                    format!("{:?}", option),
+                                    "none".into(),
+                                ))
+                            }
+                            Difference::OptionChanged { left, right } if checks_options => {
+                                return Err(mismatch(
+                                    "option",
+                                    format!("{:?}", left),
+                                    format!("{:?}", right),
+                                ))
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
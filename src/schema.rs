@@ -0,0 +1,503 @@
+//! Serde support for messages and options, with the `serde` feature.
+//!
+//! Header fields keep their RFC 2131 names, and each option is a struct
+//! holding its code, its name and its value. In human-readable formats
+//! such as JSON, addresses are written as strings and option values are
+//! typed by option code:
+//!
+//! ```json
+//! {"op":1,"htype":1,"hlen":6,"hops":0,"xid":42,"secs":0,"flags":32768,
+//!  "ciaddr":"0.0.0.0","yiaddr":"0.0.0.0","siaddr":"0.0.0.0",
+//!  "giaddr":"0.0.0.0","chaddr":"00:11:22:33:44:55","sname":"","file":"",
+//!  "options":[{"code":53,"name":"dhcp_message_type","value":1},
+//!             {"code":3,"name":"router","value":["192.168.1.1"]}]}
+//! ```
+//!
+//! Values are typed by option code rather than by the variant used to
+//! decode them, so the representation does not change as the crate learns
+//! new options. Options without a known type carry their data as a hex
+//! string. Data not matching the type of its option, and sname or file
+//! fields that are not null-terminated text, are written as a struct such
+//! as `{"hex":"63e9"}`, which no typed value can be mistaken for.
+//!
+//! Binary formats such as CBOR get the same structure, with the hardware
+//! address, the sname and file fields and option values as byte strings.
+//!
+//! Options may be read by code or by name, and missing header fields take
+//! the values of `DhcpMessage::new`.
+
+use std::fmt;
+use std::net::Ipv4Addr;
+
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::DhcpError;
+use crate::message::{DhcpMessage, Flags};
+use crate::option::DhcpOption;
+use crate::options::DhcpOptions;
+use crate::text::{code_of, describe, Kind};
+
+impl Serialize for DhcpMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Bytes past hlen are only written when set, so that they are kept.
+        let len = self
+            .chaddr
+            .iter()
+            .rposition(|byte| *byte != 0)
+            .map_or(0, |last| last + 1)
+            .max((self.hlen as usize).min(16));
+
+        let mut message = serializer.serialize_struct("DhcpMessage", 15)?;
+        message.serialize_field("op", &self.op)?;
+        message.serialize_field("htype", &self.htype)?;
+        message.serialize_field("hlen", &self.hlen)?;
+        message.serialize_field("hops", &self.hops)?;
+        message.serialize_field("xid", &self.xid)?;
+        message.serialize_field("secs", &self.secs)?;
+        message.serialize_field("flags", &self.flags)?;
+        message.serialize_field("ciaddr", &self.ciaddr)?;
+        message.serialize_field("yiaddr", &self.yiaddr)?;
+        message.serialize_field("siaddr", &self.siaddr)?;
+        message.serialize_field("giaddr", &self.giaddr)?;
+        message.serialize_field("chaddr", &HardwareAddress(&self.chaddr[..len]))?;
+        message.serialize_field("sname", &Field(&self.sname))?;
+        message.serialize_field("file", &Field(&self.file))?;
+        message.serialize_field("options", &self.options)?;
+        message.end()
+    }
+}
+
+// The fields of a message, as read.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MessageFields {
+    op: Option<u8>,
+    htype: Option<u8>,
+    hlen: Option<u8>,
+    hops: Option<u8>,
+    xid: Option<u32>,
+    secs: Option<u16>,
+    flags: Option<Flags>,
+    ciaddr: Option<Ipv4Addr>,
+    yiaddr: Option<Ipv4Addr>,
+    siaddr: Option<Ipv4Addr>,
+    giaddr: Option<Ipv4Addr>,
+    chaddr: Option<Data>,
+    sname: Option<Data>,
+    file: Option<Data>,
+    options: Option<DhcpOptions>,
+}
+
+impl<'de> Deserialize<'de> for DhcpMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DhcpMessage, D::Error> {
+        let fields = MessageFields::deserialize(deserializer)?;
+        let mut message = DhcpMessage::new(1, 0);
+        message.op = fields.op.unwrap_or(message.op);
+        message.htype = fields.htype.unwrap_or(message.htype);
+        message.hlen = fields.hlen.unwrap_or(message.hlen);
+        message.hops = fields.hops.unwrap_or(message.hops);
+        message.xid = fields.xid.unwrap_or(message.xid);
+        message.secs = fields.secs.unwrap_or(message.secs);
+        message.flags = fields.flags.unwrap_or(message.flags);
+        message.ciaddr = fields.ciaddr.unwrap_or(message.ciaddr);
+        message.yiaddr = fields.yiaddr.unwrap_or(message.yiaddr);
+        message.siaddr = fields.siaddr.unwrap_or(message.siaddr);
+        message.giaddr = fields.giaddr.unwrap_or(message.giaddr);
+        if let Some(chaddr) = fields.chaddr {
+            message.chaddr = chaddr.into_array("chaddr", true)?;
+        }
+        if let Some(sname) = fields.sname {
+            message.sname = sname.into_array("sname", false)?;
+        }
+        if let Some(file) = fields.file {
+            message.file = file.into_array("file", false)?;
+        }
+        message.options = fields.options.unwrap_or_default();
+        Ok(message)
+    }
+}
+
+impl Serialize for DhcpOption {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let data = self.encode();
+        let code = data[0];
+        let (name, kind) = describe(code);
+        let value = OptionValue {
+            option: self,
+            kind,
+            data: data.get(2..).unwrap_or(&[]),
+        };
+
+        let len = 2 + name.is_some() as usize;
+        let mut option = serializer.serialize_struct("DhcpOption", len)?;
+        option.serialize_field("code", &code)?;
+        match name {
+            Some(name) => option.serialize_field("name", name)?,
+            None => option.skip_field("name")?,
+        }
+        option.serialize_field("value", &value)?;
+        option.end()
+    }
+}
+
+// The fields of an option, as read.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OptionFields {
+    code: Option<u8>,
+    name: Option<String>,
+    #[serde(default)]
+    value: Value,
+}
+
+impl<'de> Deserialize<'de> for DhcpOption {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DhcpOption, D::Error> {
+        let fields = OptionFields::deserialize(deserializer)?;
+        let code = match (fields.code, fields.name) {
+            (Some(code), _) => code,
+            (None, Some(name)) => code_of(&name)
+                .ok_or_else(|| de::Error::custom(format!("unknown option name {}", name)))?,
+            (None, None) => return Err(de::Error::missing_field("code")),
+        };
+        match code {
+            0 => return Ok(DhcpOption::Pad),
+            255 => return Ok(DhcpOption::End),
+            _ => {}
+        }
+
+        let raw = matches!(fields.value, Value::Data(_));
+        let data = match (describe(code).1, fields.value) {
+            // Data not matching the type of its option is kept raw.
+            (_, Value::Data(data)) => data,
+            (Kind::Address, Value::Text(text)) => match text.parse::<Ipv4Addr>() {
+                Ok(address) => address.octets().to_vec(),
+                Err(_) => parse_hex(&text)?,
+            },
+            (Kind::Text, Value::Text(text)) => text.into_bytes(),
+            (_, Value::Text(hex)) => parse_hex(&hex)?,
+            (Kind::Addresses, Value::List(addresses)) => addresses
+                .into_iter()
+                .map(|address| match address {
+                    Value::Text(text) => text.parse::<Ipv4Addr>().ok(),
+                    _ => None,
+                })
+                .map(|address| address.map(|address| address.octets()))
+                .collect::<Option<Vec<[u8; 4]>>>()
+                .ok_or_else(|| invalid_value(code))?
+                .concat(),
+            (Kind::Bool, Value::Bool(value)) => vec![value as u8],
+            (Kind::U8, Value::Integer(value)) => {
+                vec![u8::try_from(value).map_err(|_| invalid_value(code))?]
+            }
+            (Kind::U16, Value::Integer(value)) => u16::try_from(value)
+                .map_err(|_| invalid_value(code))?
+                .to_be_bytes()
+                .to_vec(),
+            (Kind::U32, Value::Integer(value)) => u32::try_from(value)
+                .map_err(|_| invalid_value(code))?
+                .to_be_bytes()
+                .to_vec(),
+            (Kind::U16s, Value::List(words)) => words
+                .into_iter()
+                .map(|word| match word {
+                    Value::Integer(word) => u16::try_from(word).ok(),
+                    _ => None,
+                })
+                .map(|word| word.map(u16::to_be_bytes))
+                .collect::<Option<Vec<[u8; 2]>>>()
+                .ok_or_else(|| invalid_value(code))?
+                .concat(),
+            (Kind::Names, Value::List(names)) => {
+                let names = names
+                    .into_iter()
+                    .map(|name| match name {
+                        Value::Text(name) => Some(name),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<String>>>()
+                    .ok_or_else(|| invalid_value(code))?;
+                return Ok(DhcpOption::DomainSearch(names));
+            }
+            _ => return Err(invalid_value(code)),
+        };
+
+        // Values longer than 255 bytes are split when serialized (RFC 3396).
+        match DhcpOption::deserialize_value(code, &data) {
+            Ok(option) => Ok(option),
+            // Raw text that is not UTF-8, as kept by `TextPolicy::Raw`.
+            Err(DhcpError::InvalidUtf8 { .. }) if raw => Ok(DhcpOption::Unknown(code, data)),
+            Err(error) => Err(de::Error::custom(error)),
+        }
+    }
+}
+
+fn invalid_value<E: de::Error>(code: u8) -> E {
+    E::custom(format!("invalid value for option {}", code))
+}
+
+// The value of an option, typed by its code in human-readable formats.
+struct OptionValue<'a> {
+    option: &'a DhcpOption,
+    kind: Kind,
+    data: &'a [u8],
+}
+
+impl Serialize for OptionValue<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let data = self.data;
+        let addresses = || data.chunks(4).map(address);
+        match (self.kind, self.option) {
+            (_, DhcpOption::Pad) | (_, DhcpOption::End) => serializer.serialize_none(),
+            _ if !serializer.is_human_readable() => serializer.serialize_bytes(data),
+            (Kind::Names, DhcpOption::DomainSearch(names)) => serializer.collect_seq(names),
+            (Kind::Address, _) if data.len() == 4 => address(data).serialize(serializer),
+            (Kind::Addresses, _) if data.len().is_multiple_of(4) => {
+                serializer.collect_seq(addresses())
+            }
+            (Kind::Text, _) if std::str::from_utf8(data).is_ok() => {
+                serializer.serialize_str(std::str::from_utf8(data).unwrap_or_default())
+            }
+            (Kind::Bool, _) if data.len() == 1 && data[0] <= 1 => {
+                serializer.serialize_bool(data[0] == 1)
+            }
+            (Kind::U8, _) if data.len() == 1 => serializer.serialize_u8(data[0]),
+            (Kind::U16, _) if data.len() == 2 => {
+                serializer.serialize_u16(u16::from_be_bytes([data[0], data[1]]))
+            }
+            (Kind::U32, _) if data.len() == 4 => {
+                serializer.serialize_u32(u32::from_be_bytes([data[0], data[1], data[2], data[3]]))
+            }
+            (Kind::U16s, _) if data.len().is_multiple_of(2) => serializer.collect_seq(
+                data.chunks(2)
+                    .map(|word| u16::from_be_bytes([word[0], word[1]])),
+            ),
+            (Kind::Bytes, _) => serializer.serialize_str(&format_hex(data)),
+            // Data not matching the type of its option is kept raw.
+            _ => Hex(data).serialize(serializer),
+        }
+    }
+}
+
+fn address(octets: &[u8]) -> Ipv4Addr {
+    Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3])
+}
+
+// An option value as read, before it is typed by the option code.
+#[derive(Default)]
+enum Value {
+    #[default]
+    Null,
+    Bool(bool),
+    Integer(u64),
+    Text(String),
+    List(Vec<Value>),
+    // Raw data, from a byte string or a hex struct.
+    Data(Vec<u8>),
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an option value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Value, E> {
+        Ok(Value::Integer(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Value, E> {
+        u64::try_from(value)
+            .map(Value::Integer)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::Text(value.to_string()))
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Value, E> {
+        Ok(Value::Data(value.to_vec()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Value::List(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Value, A::Error> {
+        let hex = HexFields::deserialize(de::value::MapAccessDeserializer::new(map))?;
+        Ok(Value::Data(parse_hex(&hex.hex)?))
+    }
+}
+
+// Raw data written as a struct with a single hex string field.
+struct Hex<'a>(&'a [u8]);
+
+impl Serialize for Hex<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut hex = serializer.serialize_struct("Hex", 1)?;
+        hex.serialize_field("hex", &format_hex(self.0))?;
+        hex.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HexFields {
+    hex: String,
+}
+
+// The hardware address, as colon-separated hex in human-readable formats.
+struct HardwareAddress<'a>(&'a [u8]);
+
+impl Serialize for HardwareAddress<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return serializer.serialize_bytes(self.0);
+        }
+        let hex = self
+            .0
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<Vec<String>>()
+            .join(":");
+        serializer.serialize_str(&hex)
+    }
+}
+
+// The sname or file field, as text when it is null-terminated UTF-8
+// followed only by zeros, and as raw data otherwise.
+struct Field<'a>(&'a [u8]);
+
+impl Serialize for Field<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let data = self.0;
+        let end = data
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(data.len());
+        if !serializer.is_human_readable() {
+            // Trailing zeros are restored when reading.
+            let len = data
+                .iter()
+                .rposition(|byte| *byte != 0)
+                .map_or(0, |last| last + 1);
+            return serializer.serialize_bytes(&data[..len]);
+        }
+        match std::str::from_utf8(&data[..end]) {
+            Ok(text) if data[end..].iter().all(|byte| *byte == 0) => serializer.serialize_str(text),
+            _ => Hex(data).serialize(serializer),
+        }
+    }
+}
+
+// A header field holding bytes, as read: text, a hex struct or a byte
+// string.
+enum Data {
+    Text(String),
+    Raw(Vec<u8>),
+}
+
+impl Data {
+    // The bytes of the field, zero-padded; the text of the hardware
+    // address is hex.
+    fn into_array<E: de::Error, const N: usize>(
+        self,
+        field: &str,
+        hex: bool,
+    ) -> Result<[u8; N], E> {
+        let data = match self {
+            Data::Text(text) if hex => parse_hex(&text)?,
+            Data::Text(text) => text.into_bytes(),
+            Data::Raw(data) => data,
+        };
+        if data.len() > N {
+            return Err(E::custom(format!("{} is longer than {} bytes", field, N)));
+        }
+        let mut result = [0; N];
+        result[..data.len()].copy_from_slice(&data);
+        Ok(result)
+    }
+}
+
+impl<'de> Deserialize<'de> for Data {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Data, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::Text(text) => Ok(Data::Text(text)),
+            Value::Data(data) => Ok(Data::Raw(data)),
+            _ => Err(de::Error::custom("expected text, hex or bytes")),
+        }
+    }
+}
+
+fn format_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Parse hex data, with or without colon separators.
+fn parse_hex<E: de::Error>(hex: &str) -> Result<Vec<u8>, E> {
+    let hex = hex.replace(':', "");
+    let invalid = || E::invalid_value(de::Unexpected::Str(&hex), &"hex data");
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).map_err(|_| invalid()))
+        .collect()
+}
+
+/// Raw data as a hex string in human-readable formats and as a byte
+/// string otherwise, for `#[serde(with = "crate::schema::bytes")]`.
+pub(crate) mod bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::{format_hex, parse_hex, Value};
+
+    pub(crate) fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        match serializer.is_human_readable() {
+            true => serializer.serialize_str(&format_hex(data)),
+            false => serializer.serialize_bytes(data),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::Text(hex) => parse_hex(&hex),
+            Value::Data(data) => Ok(data),
+            _ => Err(serde::de::Error::custom("expected hex or bytes")),
+        }
+    }
+}
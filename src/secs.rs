@@ -0,0 +1,42 @@
+//! The secs field of client messages: the seconds elapsed since the
+//! client began acquiring or renewing its address (RFC 2131 section 2).
+//!
+//! Retransmissions of a DHCPDISCOVER, and those of a DHCPREQUEST renewing
+//! a lease, carry the time elapsed since the first message of the
+//! exchange, which servers may use to answer clients waiting for long
+//! first. The DHCPREQUEST answering a DHCPOFFER keeps the secs value of
+//! the DHCPDISCOVER (RFC 2131 section 4.4.1).
+
+use std::time::Instant;
+
+use crate::message::DhcpMessage;
+
+/// The start of an address acquisition or renewal, from which the secs
+/// fields of its messages are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecsTracker {
+    start: Instant,
+}
+
+impl SecsTracker {
+    /// Start tracking at `start`, when the first message is sent.
+    pub fn new(start: Instant) -> SecsTracker {
+        SecsTracker { start }
+    }
+
+    pub fn start(&self) -> Instant {
+        self.start
+    }
+
+    /// The seconds elapsed at `now`, clamped to the range of the secs
+    /// field, and zero if `now` is before the start.
+    pub fn secs_at(&self, now: Instant) -> u16 {
+        let elapsed = now.saturating_duration_since(self.start).as_secs();
+        elapsed.min(u16::MAX as u64) as u16
+    }
+
+    /// Set the secs field of `message`, sent at `now`.
+    pub fn stamp(&self, message: &mut DhcpMessage, now: Instant) {
+        message.secs = self.secs_at(now);
+    }
+}
@@ -0,0 +1,1071 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+
+use crate::error::{DhcpError, NakReason};
+use crate::lease::{LeaseEvent, LeaseEventSink, LeaseState};
+use crate::message::{DhcpMessage, MessageType};
+use crate::metrics::{Metric, MetricKind, Metrics, Sample};
+use crate::option::DhcpOption;
+use crate::options::DhcpOptions;
+use crate::trace::event;
+
+const OPTION_REQUESTED_IP_ADDRESS: u8 = 50;
+const OPTION_SERVER_IDENTIFIER: u8 = 54;
+const OPTION_MESSAGE: u8 = 56;
+const OPTION_CLIENT_IDENTIFIER: u8 = 61;
+
+/// The configuration of a single subnet server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    pub server_identifier: Ipv4Addr,
+    pub pool_start: Ipv4Addr,
+    pub pool_end: Ipv4Addr,
+    pub subnet_mask: Ipv4Addr,
+    pub routers: Vec<Ipv4Addr>,
+    pub dns_servers: Vec<Ipv4Addr>,
+    pub domain_name: Option<String>,
+    // Lease time in seconds.
+    pub lease_time: u32,
+    // Seconds a declined address is kept from every client.
+    pub decline_hold_time: u32,
+}
+
+impl ServerConfig {
+    /// Parse a TOML configuration of the keys below. Only
+    /// `server_identifier`, `pool_start`, `pool_end` and `subnet_mask` are
+    /// required; the times default to a day. Unknown keys are rejected.
+    ///
+    /// ```toml
+    /// server_identifier = "192.168.1.1"
+    /// pool_start = "192.168.1.100"
+    /// pool_end = "192.168.1.200"
+    /// subnet_mask = "255.255.255.0"
+    /// routers = ["192.168.1.1"]
+    /// dns_servers = ["192.168.1.1"]
+    /// domain_name = "lan"
+    /// lease_time = 86400
+    /// decline_hold_time = 86400
+    /// ```
+    ///
+    /// With the `toml` feature the whole of TOML is accepted. Without it,
+    /// only `key = value` lines with basic strings without escapes,
+    /// integers and single-line string arrays, and `#` comments; tables,
+    /// other value types and multi-line values are rejected.
+    pub fn from_config(config: &str) -> Result<ServerConfig, DhcpError> {
+        let mut values = config_values(config)?;
+        let mut take = |key: &str| values.remove(key);
+        let invalid = |key: &str| {
+            DhcpError::ParsingError(format!("Invalid configuration key {}", key).into())
+        };
+        let mut address = |key: &str| match take(key) {
+            Some(ConfigValue::String(value)) => parse_address(key, &value),
+            Some(_) => Err(invalid(key)),
+            None => Err(DhcpError::ParsingError(
+                format!("Missing configuration key {}", key).into(),
+            )),
+        };
+        let server_identifier = address("server_identifier")?;
+        let pool_start = address("pool_start")?;
+        let pool_end = address("pool_end")?;
+        let subnet_mask = address("subnet_mask")?;
+
+        let mut addresses = |key: &str| match take(key) {
+            Some(ConfigValue::Strings(values)) => values
+                .iter()
+                .map(|address| parse_address(key, address))
+                .collect(),
+            Some(_) => Err(invalid(key)),
+            None => Ok(Vec::new()),
+        };
+        let routers = addresses("routers")?;
+        let dns_servers = addresses("dns_servers")?;
+
+        let domain_name = match take("domain_name") {
+            Some(ConfigValue::String(value)) => Some(value),
+            Some(_) => return Err(invalid("domain_name")),
+            None => None,
+        };
+        let mut seconds = |key: &str| match take(key) {
+            Some(ConfigValue::Integer(value)) => u32::try_from(value).map_err(|_| invalid(key)),
+            Some(_) => Err(invalid(key)),
+            None => Ok(86400),
+        };
+        let lease_time = seconds("lease_time")?;
+        let decline_hold_time = seconds("decline_hold_time")?;
+
+        if let Some(key) = values.keys().min() {
+            return Err(DhcpError::ParsingError(
+                format!("Unknown configuration key {}", key).into(),
+            ));
+        }
+        if u32::from(pool_start) > u32::from(pool_end) {
+            return Err(DhcpError::ParsingError(
+                "The pool start address is after its end address".into(),
+            ));
+        }
+        Ok(ServerConfig {
+            server_identifier,
+            pool_start,
+            pool_end,
+            subnet_mask,
+            routers,
+            dns_servers,
+            domain_name,
+            lease_time,
+            decline_hold_time,
+        })
+    }
+}
+
+// A configuration value, of one of the types the keys take.
+enum ConfigValue {
+    String(String),
+    Integer(i64),
+    Strings(Vec<String>),
+    Other,
+}
+
+#[cfg(feature = "toml")]
+fn config_values(config: &str) -> Result<HashMap<String, ConfigValue>, DhcpError> {
+    let table = config.parse::<toml::Table>().map_err(|error| {
+        DhcpError::ParsingError(
+            format!("Could not parse configuration: {}", error.message()).into(),
+        )
+    })?;
+    Ok(table
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                toml::Value::String(value) => ConfigValue::String(value),
+                toml::Value::Integer(value) => ConfigValue::Integer(value),
+                toml::Value::Array(values) => values
+                    .into_iter()
+                    .map(|value| match value {
+                        toml::Value::String(value) => Some(value),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<String>>>()
+                    .map_or(ConfigValue::Other, ConfigValue::Strings),
+                _ => ConfigValue::Other,
+            };
+            (key, value)
+        })
+        .collect())
+}
+
+#[cfg(not(feature = "toml"))]
+fn config_values(config: &str) -> Result<HashMap<String, ConfigValue>, DhcpError> {
+    let mut values = HashMap::new();
+    for (number, line) in config.lines().enumerate() {
+        let error = || {
+            DhcpError::ParsingError(
+                format!("Could not parse configuration line {}", number + 1).into(),
+            )
+        };
+        // Strip the comment, from the first '#' outside a string.
+        let mut quotes = 0;
+        let comment = line.find(|c| {
+            quotes += (c == '"') as usize;
+            c == '#' && quotes % 2 == 0
+        });
+        let line = line[..comment.unwrap_or(line.len())].trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or_else(error)?;
+        let key = key.trim();
+        let value = value.trim();
+        if key.is_empty()
+            || !key
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_')
+        {
+            return Err(error());
+        }
+        let value = if value.starts_with('"') {
+            ConfigValue::String(parse_string(value).ok_or_else(error)?)
+        } else if value.starts_with('[') {
+            ConfigValue::Strings(parse_array(value).ok_or_else(error)?)
+        } else {
+            value
+                .parse()
+                .map_or(ConfigValue::Other, ConfigValue::Integer)
+        };
+        if values.insert(key.to_string(), value).is_some() {
+            return Err(DhcpError::ParsingError(
+                format!("Duplicate configuration key {}", key).into(),
+            ));
+        }
+    }
+    Ok(values)
+}
+
+// A basic string without escape sequences.
+#[cfg(not(feature = "toml"))]
+fn parse_string(value: &str) -> Option<String> {
+    let value = value.strip_prefix('"')?.strip_suffix('"')?;
+    if value.contains(['"', '\\']) {
+        return None;
+    }
+    Some(value.to_string())
+}
+
+#[cfg(not(feature = "toml"))]
+fn parse_array(value: &str) -> Option<Vec<String>> {
+    let items = value.strip_prefix('[')?.strip_suffix(']')?.trim();
+    // A trailing comma is allowed.
+    let items = items.strip_suffix(',').unwrap_or(items);
+    if items.is_empty() {
+        return Some(Vec::new());
+    }
+    items
+        .split(',')
+        .map(|item| parse_string(item.trim()))
+        .collect()
+}
+
+fn parse_address(key: &str, value: &str) -> Result<Ipv4Addr, DhcpError> {
+    value.parse().map_err(|_| {
+        DhcpError::ParsingError(format!("Could not parse configuration key {}", key).into())
+    })
+}
+
+/// A lease held by a client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lease {
+    pub ip: Ipv4Addr,
+    pub mac: [u8; 6],
+    pub client_identifier: Option<Vec<u8>>,
+    // Expiry, in seconds since the Unix epoch.
+    pub expires: u32,
+}
+
+// A shard of the client index, and of the address index.
+type ClientShard = RwLock<HashMap<Vec<u8>, Lease>>;
+type AddressShard = RwLock<HashMap<Ipv4Addr, (Option<Vec<u8>>, u32)>>;
+
+/// The leases of a server, safe to share between threads.
+///
+/// Leases are spread over shards by client key, and indexed by address in
+/// shards of their own, so that clients in different shards are served
+/// without waiting on each other. No two locks are ever held at once.
+#[derive(Debug)]
+pub struct LeaseTable {
+    // Leases by client key, in the shard of the key's hash.
+    clients: Vec<ClientShard>,
+    // The client key and expiry of each leased address, in the shard of
+    // the address. Abandoned addresses have no client key.
+    addresses: Vec<AddressShard>,
+}
+
+impl LeaseTable {
+    pub const DEFAULT_SHARDS: usize = 16;
+
+    pub fn new() -> LeaseTable {
+        LeaseTable::with_shards(LeaseTable::DEFAULT_SHARDS)
+    }
+
+    /// A table spread over `shards` shards, at least one.
+    pub fn with_shards(shards: usize) -> LeaseTable {
+        let shards = shards.max(1);
+        LeaseTable {
+            clients: (0..shards).map(|_| RwLock::default()).collect(),
+            addresses: (0..shards).map(|_| RwLock::default()).collect(),
+        }
+    }
+
+    /// The lease of a client.
+    pub fn get(&self, key: &[u8]) -> Option<Lease> {
+        read(self.client_shard(key)).get(key).cloned()
+    }
+
+    /// Whether `ip` is free for the client `key` at `now`: not leased, or
+    /// leased to that client, or leased to another client until before
+    /// `now`.
+    pub fn is_free(&self, ip: Ipv4Addr, key: &[u8], now: u32) -> bool {
+        match read(self.address_shard(ip)).get(&ip) {
+            Some((holder, expires)) => holder.as_deref() == Some(key) || *expires <= now,
+            None => true,
+        }
+    }
+
+    /// Record the lease of client `key`, replacing its previous lease.
+    ///
+    /// Returns false, leaving the table unchanged, when the address is
+    /// held by another client at `now`.
+    pub fn try_insert(&self, key: Vec<u8>, lease: Lease, now: u32) -> bool {
+        let ip = lease.ip;
+        {
+            let mut addresses = write(self.address_shard(ip));
+            if let Some((holder, expires)) = addresses.get(&ip) {
+                if holder.as_ref() != Some(&key) && *expires > now {
+                    return false;
+                }
+            }
+            addresses.insert(ip, (Some(key.clone()), lease.expires));
+        }
+        let previous = write(self.client_shard(&key)).insert(key.clone(), lease);
+        if let Some(previous) = previous {
+            if previous.ip != ip {
+                self.release_address(previous.ip, &key);
+            }
+        }
+        true
+    }
+
+    /// Remove the lease of a client.
+    pub fn remove(&self, key: &[u8]) -> Option<Lease> {
+        let lease = write(self.client_shard(key)).remove(key)?;
+        self.release_address(lease.ip, key);
+        Some(lease)
+    }
+
+    /// Remove the lease of a client, keeping its address from every client
+    /// until `until`.
+    pub fn abandon(&self, key: &[u8], until: u32) -> Option<Lease> {
+        let lease = write(self.client_shard(key)).remove(key)?;
+        let mut addresses = write(self.address_shard(lease.ip));
+        if let Some((holder, expires)) = addresses.get_mut(&lease.ip) {
+            if holder.as_deref() == Some(key) {
+                *holder = None;
+                *expires = until;
+            }
+        }
+        Some(lease)
+    }
+
+    /// The number of leases.
+    pub fn len(&self) -> usize {
+        self.clients.iter().map(|shard| read(shard).len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The addresses held at `now`, with the key of their client, or None
+    /// for abandoned addresses, taking each shard of the address index
+    /// once.
+    pub fn held(&self, now: u32) -> HashMap<Ipv4Addr, Option<Vec<u8>>> {
+        let mut held = HashMap::new();
+        for shard in &self.addresses {
+            held.extend(
+                read(shard)
+                    .iter()
+                    .filter(|(_, (_, expires))| *expires > now)
+                    .map(|(ip, (holder, _))| (*ip, holder.clone())),
+            );
+        }
+        held
+    }
+
+    /// A copy of every lease, shard by shard.
+    pub fn leases(&self) -> Vec<Lease> {
+        self.clients
+            .iter()
+            .flat_map(|shard| read(shard).values().cloned().collect::<Vec<_>>())
+            .collect()
+    }
+
+    // Forget that `ip` is leased, unless another client took it since.
+    fn release_address(&self, ip: Ipv4Addr, key: &[u8]) {
+        let mut addresses = write(self.address_shard(ip));
+        if addresses.get(&ip).and_then(|(holder, _)| holder.as_deref()) == Some(key) {
+            addresses.remove(&ip);
+        }
+    }
+
+    fn client_shard(&self, key: &[u8]) -> &ClientShard {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.clients[hasher.finish() as usize % self.clients.len()]
+    }
+
+    fn address_shard(&self, ip: Ipv4Addr) -> &AddressShard {
+        &self.addresses[u32::from(ip) as usize % self.addresses.len()]
+    }
+}
+
+impl Default for LeaseTable {
+    fn default() -> LeaseTable {
+        LeaseTable::new()
+    }
+}
+
+// A panic while a shard was locked leaves whole leases behind, so a
+// poisoned lock is still used.
+fn read<T>(lock: &RwLock<T>) -> std::sync::RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|error| error.into_inner())
+}
+
+fn write<T>(lock: &RwLock<T>) -> std::sync::RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|error| error.into_inner())
+}
+
+// The free addresses of the pool while handling a batch, from a single
+// pass over the lease table. The search for a free address resumes where
+// the previous one stopped instead of starting over from the beginning of
+// the pool, and addresses offered earlier in the batch are not offered
+// again.
+struct FreeAddresses {
+    held: HashMap<Ipv4Addr, Option<Vec<u8>>>,
+    // Every address of the pool before it is held.
+    next: u32,
+}
+
+impl FreeAddresses {
+    fn new(leases: &LeaseTable, pool_start: Ipv4Addr, now: u32) -> FreeAddresses {
+        let mut free = FreeAddresses {
+            held: leases.held(now),
+            next: u32::from(pool_start),
+        };
+        free.advance();
+        free
+    }
+
+    fn is_free(&self, ip: Ipv4Addr, key: &[u8]) -> bool {
+        self.held
+            .get(&ip)
+            .is_none_or(|holder| holder.as_deref() == Some(key))
+    }
+
+    // The first address up to `end` free for the client `key`, both here
+    // and in the lease table as told by `table_free`. Addresses taken by
+    // other threads since the start of the batch are skipped from then on.
+    fn first(
+        &mut self,
+        key: &[u8],
+        end: u32,
+        table_free: impl Fn(Ipv4Addr) -> bool,
+    ) -> Option<Ipv4Addr> {
+        let found = (self.next..=end).map(Ipv4Addr::from).find(|ip| {
+            let free = self.is_free(*ip, key) && table_free(*ip);
+            if !free {
+                self.held.entry(*ip).or_insert(None);
+            }
+            free
+        });
+        self.advance();
+        found
+    }
+
+    // Hold `ip` for the client `key`, or for none, until the end of the
+    // batch.
+    fn hold(&mut self, ip: Ipv4Addr, key: Option<&[u8]>) {
+        self.held.insert(ip, key.map(<[u8]>::to_vec));
+        self.advance();
+    }
+
+    fn advance(&mut self) {
+        while self.next < u32::MAX && self.held.contains_key(&Ipv4Addr::from(self.next)) {
+            self.next += 1;
+        }
+    }
+}
+
+// The answer to a client message.
+#[derive(Debug)]
+enum Answer {
+    // An OFFER or ACK of an address.
+    Lease(MessageType, Ipv4Addr),
+    // The ACK of an INFORM, without lease time.
+    Inform,
+    Nak(NakReason),
+}
+
+impl Answer {
+    fn message_type(&self) -> MessageType {
+        match self {
+            Answer::Lease(message_type, _) => *message_type,
+            Answer::Inform => MessageType::Ack,
+            Answer::Nak(_) => MessageType::Nak,
+        }
+    }
+}
+
+/// A DHCPv4 server handing out addresses from a single pool.
+///
+/// The server only computes replies: the caller owns the socket, and sends
+/// each reply to the address given by `reply_destination`. It can be
+/// shared between threads, each handling its own messages.
+#[derive(Debug)]
+pub struct Server {
+    config: ServerConfig,
+    // Leases by client key, the client identifier or hardware address.
+    leases: LeaseTable,
+    // Messages received and sent, indexed by message type.
+    received: Vec<AtomicU64>,
+    sent: Vec<AtomicU64>,
+    // Allocation failures not yet taken by the caller.
+    errors: Mutex<Vec<DhcpError>>,
+    // The serialized `config_options`, if they can be serialized, and
+    // those of INFORM replies, without the lease time.
+    reply_options: Option<Vec<u8>>,
+    inform_options: Option<Vec<u8>>,
+}
+
+impl Server {
+    pub fn new(config: ServerConfig) -> Server {
+        let options = config_options(&config);
+        Server {
+            reply_options: serialize_options(&options, |_| true),
+            inform_options: serialize_options(&options, |option| {
+                !matches!(option, DhcpOption::IpAddressLeaseTime(_))
+            }),
+            config,
+            leases: LeaseTable::new(),
+            received: (0..=u8::MAX).map(|_| AtomicU64::new(0)).collect(),
+            sent: (0..=u8::MAX).map(|_| AtomicU64::new(0)).collect(),
+            errors: Mutex::default(),
+        }
+    }
+
+    pub fn config(&self) -> &ServerConfig {
+        &self.config
+    }
+
+    /// The leases currently known to the server.
+    pub fn leases(&self) -> impl Iterator<Item = Lease> {
+        self.leases.leases().into_iter()
+    }
+
+    /// Take the allocation failures met since the last call, such as
+    /// `DhcpError::PoolExhausted`, for the caller to log or alert on.
+    pub fn take_errors(&self) -> Vec<DhcpError> {
+        std::mem::take(
+            &mut *self
+                .errors
+                .lock()
+                .unwrap_or_else(|error| error.into_inner()),
+        )
+    }
+
+    /// Handle a client message received at `now` (seconds since the Unix
+    /// epoch), returning the reply to send if any. Lease changes are
+    /// reported to `events`.
+    pub fn handle<S: LeaseEventSink>(
+        &self,
+        request: &DhcpMessage,
+        now: u32,
+        events: &mut S,
+    ) -> Option<DhcpMessage> {
+        self.respond(request, now, events, None)
+    }
+
+    /// Handle a client message received as `packet`, as `handle` does,
+    /// writing the reply to send into `reply`. Returns false, leaving
+    /// `reply` empty, if there is none.
+    ///
+    /// OFFER and ACK replies are written by patching a copy of the request
+    /// header, followed by the options computed once from the
+    /// configuration, without building a `DhcpMessage`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(len = packet.len()))
+    )]
+    pub fn handle_packet<S: LeaseEventSink>(
+        &self,
+        packet: &[u8],
+        reply: &mut Vec<u8>,
+        now: u32,
+        events: &mut S,
+    ) -> Result<bool, DhcpError> {
+        reply.clear();
+        let request = DhcpMessage::deserialize(packet)?;
+        self.received[message_type(&request).map_or(0, u8::from) as usize]
+            .fetch_add(1, Ordering::Relaxed);
+        let answer = match self.answer(&request, now, events, None) {
+            Some(answer) => answer,
+            None => return Ok(false),
+        };
+
+        let options = match (&answer, &self.reply_options, &self.inform_options) {
+            (Answer::Lease(..), Some(options), _) | (Answer::Inform, _, Some(options)) => {
+                options.as_slice()
+            }
+            _ => {
+                self.build(&request, &answer).serialize_into(reply)?;
+                self.sent[u8::from(answer.message_type()) as usize].fetch_add(1, Ordering::Relaxed);
+                return Ok(true);
+            }
+        };
+        let ip = match answer {
+            Answer::Lease(_, ip) => ip,
+            _ => Ipv4Addr::UNSPECIFIED,
+        };
+        reply.extend_from_slice(&packet[..DhcpMessage::HEADER_LEN]);
+        // op, hops, secs, yiaddr and siaddr, then sname and file.
+        reply[0] = 2;
+        reply[3] = 0;
+        reply[8..10].fill(0);
+        reply[16..20].copy_from_slice(&ip.octets());
+        reply[20..24].fill(0);
+        reply[44..236].fill(0);
+        reply.extend_from_slice(&[MessageType::OPTION_CODE, 1, answer.message_type().into()]);
+        reply.extend_from_slice(&[OPTION_SERVER_IDENTIFIER, 4]);
+        reply.extend_from_slice(&self.config.server_identifier.octets());
+        reply.extend_from_slice(options);
+        reply.push(255);
+        self.sent[u8::from(answer.message_type()) as usize].fetch_add(1, Ordering::Relaxed);
+        Ok(true)
+    }
+
+    /// Handle messages received together at `now`, as `handle` does in
+    /// turn, returning the replies with the index of their request.
+    ///
+    /// The addresses held are read from the lease table once for the whole
+    /// batch, and the free addresses offered in a single pass over the
+    /// pool, instead of a pass for each DISCOVER. Clients of the same batch
+    /// are offered different addresses. Leases are still recorded one
+    /// request at a time, so that other threads see them at once.
+    pub fn handle_batch<S: LeaseEventSink>(
+        &self,
+        requests: &[DhcpMessage],
+        now: u32,
+        events: &mut S,
+    ) -> Vec<(usize, DhcpMessage)> {
+        let mut free = requests
+            .iter()
+            .any(|request| {
+                matches!(
+                    message_type(request),
+                    Some(MessageType::Discover | MessageType::Request)
+                )
+            })
+            .then(|| FreeAddresses::new(&self.leases, self.config.pool_start, now));
+        requests
+            .iter()
+            .enumerate()
+            .filter_map(|(index, request)| {
+                let reply = self.respond(request, now, events, free.as_mut())?;
+                Some((index, reply))
+            })
+            .collect()
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "handle", level = "debug", skip_all, fields(xid = request.xid))
+    )]
+    fn respond<S: LeaseEventSink>(
+        &self,
+        request: &DhcpMessage,
+        now: u32,
+        events: &mut S,
+        free: Option<&mut FreeAddresses>,
+    ) -> Option<DhcpMessage> {
+        self.received[message_type(request).map_or(0, u8::from) as usize]
+            .fetch_add(1, Ordering::Relaxed);
+        let answer = self.answer(request, now, events, free)?;
+        self.sent[u8::from(answer.message_type()) as usize].fetch_add(1, Ordering::Relaxed);
+        Some(self.build(request, &answer))
+    }
+
+    // Decide the answer to a request, updating the leases.
+    fn answer<S: LeaseEventSink>(
+        &self,
+        request: &DhcpMessage,
+        now: u32,
+        events: &mut S,
+        free: Option<&mut FreeAddresses>,
+    ) -> Option<Answer> {
+        if request.op != 1 {
+            return None;
+        }
+        let key = client_key(request);
+        event!(
+            debug,
+            xid = request.xid,
+            message_type = ?message_type(request),
+            "received"
+        );
+
+        let answer = self.decide(request, &key, now, events, free);
+        event!(debug, answer = ?answer, "answering");
+        answer
+    }
+
+    fn decide<S: LeaseEventSink>(
+        &self,
+        request: &DhcpMessage,
+        key: &[u8],
+        now: u32,
+        events: &mut S,
+        free: Option<&mut FreeAddresses>,
+    ) -> Option<Answer> {
+        match message_type(request)? {
+            MessageType::Discover => {
+                let ip = match self.allocate(key, requested_address(request), now, free) {
+                    Some(ip) => ip,
+                    None => {
+                        event!(warn, "no free address to offer");
+                        self.error(DhcpError::PoolExhausted {
+                            scope: format!("{}-{}", self.config.pool_start, self.config.pool_end),
+                        });
+                        return None;
+                    }
+                };
+                Some(Answer::Lease(MessageType::Offer, ip))
+            }
+            MessageType::Request => {
+                // A request answering another server's offer.
+                if let Some(server) = request.server_identifier() {
+                    if server != self.config.server_identifier {
+                        return None;
+                    }
+                }
+                let requested = requested_address(request).or(match request.ciaddr {
+                    Ipv4Addr::UNSPECIFIED => None,
+                    ciaddr => Some(ciaddr),
+                });
+                let ip = match requested {
+                    Some(ip) => ip,
+                    None => return Some(Answer::Nak(NakReason::NoAddressRequested)),
+                };
+                if !self.in_pool(ip) {
+                    return Some(Answer::Nak(NakReason::WrongNetwork));
+                }
+
+                let lease = Lease {
+                    ip,
+                    mac: hardware_address(request),
+                    client_identifier: client_identifier(request),
+                    expires: now.saturating_add(self.config.lease_time),
+                };
+                // The address is checked and leased at once, as another
+                // thread may take it in between otherwise.
+                if !self.leases.try_insert(key.to_vec(), lease.clone(), now) {
+                    event!(warn, %ip, "requested address in use");
+                    self.error(DhcpError::AddressConflict { ip });
+                    return Some(Answer::Nak(NakReason::AddressInUse));
+                }
+                event!(debug, %ip, "allocating the requested address");
+                if let Some(free) = free {
+                    free.hold(ip, Some(key));
+                }
+                self.transition(&lease, LeaseState::Active, now, events);
+                Some(Answer::Lease(MessageType::Ack, ip))
+            }
+            message_type @ (MessageType::Decline | MessageType::Release) => {
+                // Only the holder of a lease of this server may give it
+                // back: the address is the requested one for a DECLINE, and
+                // ciaddr for a RELEASE.
+                if request.server_identifier() != Some(self.config.server_identifier) {
+                    return None;
+                }
+                let ip = match message_type {
+                    MessageType::Decline => requested_address(request)?,
+                    _ => request.ciaddr,
+                };
+                if self.leases.get(key)?.ip != ip {
+                    return None;
+                }
+                let (lease, state) = match message_type {
+                    // The address is in use by another host: keep it from
+                    // the clients for a while (RFC 2131 section 4.3.3).
+                    MessageType::Decline => (
+                        self.leases
+                            .abandon(key, now.saturating_add(self.config.decline_hold_time))?,
+                        LeaseState::Abandoned,
+                    ),
+                    _ => (self.leases.remove(key)?, LeaseState::Released),
+                };
+                self.transition(&lease, state, now, events);
+                None
+            }
+            MessageType::Inform => Some(Answer::Inform),
+            MessageType::Offer | MessageType::Ack | MessageType::Nak => None,
+        }
+    }
+
+    // Report a change of `lease` to `events`.
+    fn transition<S: LeaseEventSink>(
+        &self,
+        lease: &Lease,
+        state: LeaseState,
+        now: u32,
+        events: &mut S,
+    ) {
+        event!(info, ip = %lease.ip, state = ?state, "lease changed");
+        let lease_time = match state {
+            LeaseState::Active => self.config.lease_time,
+            _ => 0,
+        };
+        events.lease_event(&event(lease, state, lease_time, now));
+    }
+
+    // Pick the address to offer a client: its current lease, the address
+    // it asked for if free, or the first free address of the pool, found
+    // with `free` in a batch. The address picked is held in `free` for the
+    // rest of the batch.
+    fn allocate(
+        &self,
+        key: &[u8],
+        requested: Option<Ipv4Addr>,
+        now: u32,
+        free: Option<&mut FreeAddresses>,
+    ) -> Option<Ipv4Addr> {
+        let free = match free {
+            Some(free) => free,
+            None => return self.pick(key, requested, now, None),
+        };
+        let ip = self.pick(key, requested, now, Some(free)).or_else(|| {
+            let end = u32::from(self.config.pool_end);
+            let ip = free.first(key, end, |ip| self.leases.is_free(ip, key, now));
+            event!(debug, ip = ?ip, "allocating the first free address");
+            ip
+        })?;
+        free.hold(ip, Some(key));
+        Some(ip)
+    }
+
+    // Pick the address to offer a client as `allocate` does. In a batch,
+    // the pool past `free.next` is left for `allocate` to search.
+    fn pick(
+        &self,
+        key: &[u8],
+        requested: Option<Ipv4Addr>,
+        now: u32,
+        free: Option<&FreeAddresses>,
+    ) -> Option<Ipv4Addr> {
+        let is_free = |ip: Ipv4Addr| {
+            self.leases.is_free(ip, key, now) && free.is_none_or(|free| free.is_free(ip, key))
+        };
+        let current = self.leases.get(key).map(|lease| lease.ip);
+        if let Some(ip) = current {
+            if (requested.is_none() || requested == Some(ip)) && is_free(ip) {
+                event!(debug, %ip, "allocating the current lease");
+                return Some(ip);
+            }
+        }
+        if let Some(ip) = requested {
+            if self.in_pool(ip) && is_free(ip) {
+                event!(debug, %ip, "allocating the requested address");
+                return Some(ip);
+            }
+        }
+
+        let start = u32::from(self.config.pool_start);
+        let end = u32::from(self.config.pool_end);
+        let ip = match free {
+            // Every address before `next` is held by other clients, but
+            // for the current lease of this one.
+            Some(free) => current.filter(|ip| {
+                (start..free.next).contains(&u32::from(*ip)) && self.in_pool(*ip) && is_free(*ip)
+            }),
+            None => (start..=end).map(Ipv4Addr::from).find(|ip| is_free(*ip)),
+        };
+        if free.is_none() || ip.is_some() {
+            event!(debug, ip = ?ip, "allocating the first free address");
+        }
+        ip
+    }
+
+    fn error(&self, error: DhcpError) {
+        self.errors
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .push(error);
+    }
+
+    fn in_pool(&self, ip: Ipv4Addr) -> bool {
+        (self.config.pool_start..=self.config.pool_end).contains(&ip)
+    }
+
+    fn build(&self, request: &DhcpMessage, answer: &Answer) -> DhcpMessage {
+        match answer {
+            Answer::Lease(message_type, ip) => self.reply(request, *message_type, *ip),
+            Answer::Inform => {
+                let mut reply = self.reply(request, MessageType::Ack, Ipv4Addr::UNSPECIFIED);
+                reply
+                    .options
+                    .retain(|option| !matches!(option, DhcpOption::IpAddressLeaseTime(_)));
+                reply
+            }
+            Answer::Nak(reason) => self.nak(request, reason),
+        }
+    }
+
+    fn reply(&self, request: &DhcpMessage, message_type: MessageType, ip: Ipv4Addr) -> DhcpMessage {
+        let mut reply = DhcpMessage::new(2, request.xid);
+        reply.htype = request.htype;
+        reply.hlen = request.hlen;
+        reply.flags = request.flags;
+        reply.ciaddr = request.ciaddr;
+        reply.yiaddr = ip;
+        reply.giaddr = request.giaddr;
+        reply.chaddr = request.chaddr;
+
+        reply.options = vec![
+            DhcpOption::from(message_type),
+            DhcpOption::ServerIdentifier(self.config.server_identifier),
+        ]
+        .into();
+        reply.options.extend(config_options(&self.config));
+        reply.options.order();
+        reply
+    }
+
+    fn nak(&self, request: &DhcpMessage, reason: &NakReason) -> DhcpMessage {
+        let mut reply = DhcpMessage::new(2, request.xid);
+        reply.htype = request.htype;
+        reply.hlen = request.hlen;
+        reply.flags = request.flags;
+        reply.giaddr = request.giaddr;
+        reply.chaddr = request.chaddr;
+        reply.options = vec![
+            DhcpOption::from(MessageType::Nak),
+            DhcpOption::ServerIdentifier(self.config.server_identifier),
+            DhcpOption::Unknown(OPTION_MESSAGE, reason.message().as_bytes().to_vec()),
+        ]
+        .into();
+        reply
+    }
+}
+
+impl Metrics for Server {
+    fn metrics(&self) -> Vec<Metric> {
+        let messages = |counts: &[AtomicU64]| {
+            counts
+                .iter()
+                .enumerate()
+                .map(|(message_type, count)| (message_type, count.load(Ordering::Relaxed)))
+                .filter(|(_, count)| *count > 0)
+                .map(|(message_type, count)| {
+                    Sample::new(count as f64)
+                        .with_label("type", message_type_name(message_type as u8))
+                })
+                .collect()
+        };
+        let pool_size = u32::from(self.config.pool_end) - u32::from(self.config.pool_start) + 1;
+
+        vec![
+            Metric {
+                name: "dhcp_server_messages_received_total",
+                help: "Messages received, by message type.",
+                kind: MetricKind::Counter,
+                samples: messages(&self.received),
+            },
+            Metric {
+                name: "dhcp_server_messages_sent_total",
+                help: "Replies sent, by message type.",
+                kind: MetricKind::Counter,
+                samples: messages(&self.sent),
+            },
+            Metric {
+                name: "dhcp_server_pool_addresses",
+                help: "Addresses in the pool.",
+                kind: MetricKind::Gauge,
+                samples: vec![Sample::new(pool_size as f64)],
+            },
+            Metric {
+                name: "dhcp_server_leases",
+                help: "Leases held by clients.",
+                kind: MetricKind::Gauge,
+                samples: vec![Sample::new(self.leases.len() as f64)],
+            },
+        ]
+    }
+}
+
+fn message_type_name(message_type: u8) -> &'static str {
+    match MessageType::try_from(message_type) {
+        Ok(MessageType::Discover) => "discover",
+        Ok(MessageType::Offer) => "offer",
+        Ok(MessageType::Request) => "request",
+        Ok(MessageType::Decline) => "decline",
+        Ok(MessageType::Ack) => "ack",
+        Ok(MessageType::Nak) => "nak",
+        Ok(MessageType::Release) => "release",
+        Ok(MessageType::Inform) => "inform",
+        Err(_) => "other",
+    }
+}
+
+/// Where to send a reply, see `DhcpMessage::reply_destination`.
+pub fn reply_destination(request: &DhcpMessage, reply: &DhcpMessage) -> SocketAddrV4 {
+    request.reply_destination(reply)
+}
+
+// The options given to every client, from the lease time on, in the order
+// of `DhcpOptions::order`.
+fn config_options(config: &ServerConfig) -> Vec<DhcpOption> {
+    let mut options = vec![
+        DhcpOption::IpAddressLeaseTime(config.lease_time),
+        DhcpOption::SubnetMask(config.subnet_mask),
+    ];
+    if !config.routers.is_empty() {
+        options.push(DhcpOption::Router(config.routers.iter().copied().collect()));
+    }
+    if !config.dns_servers.is_empty() {
+        options.push(DhcpOption::DomainNameServer(
+            config.dns_servers.iter().copied().collect(),
+        ));
+    }
+    if let Some(domain_name) = &config.domain_name {
+        options.push(DhcpOption::DomainName(domain_name.clone()));
+    }
+    let mut options = DhcpOptions::from(options);
+    options.order();
+    options.into()
+}
+
+// Serialize the options kept by `keep`, if they can be serialized.
+fn serialize_options(
+    options: &[DhcpOption],
+    keep: impl Fn(&DhcpOption) -> bool,
+) -> Option<Vec<u8>> {
+    let mut serialized = Vec::new();
+    options
+        .iter()
+        .filter(|option| keep(option))
+        .try_for_each(|option| option.serialize_into(&mut serialized))
+        .ok()?;
+    Some(serialized)
+}
+
+fn event(lease: &Lease, state: LeaseState, lease_time: u32, now: u32) -> LeaseEvent {
+    LeaseEvent {
+        ip: lease.ip,
+        mac: lease.mac,
+        client_identifier: lease.client_identifier.clone(),
+        state,
+        lease_time,
+        timestamp: now,
+    }
+}
+
+fn message_type(message: &DhcpMessage) -> Option<MessageType> {
+    message.message_type().ok().flatten()
+}
+
+fn requested_address(message: &DhcpMessage) -> Option<Ipv4Addr> {
+    match message.get_option(OPTION_REQUESTED_IP_ADDRESS)? {
+        DhcpOption::RequestedIpAddress(ip) => Some(*ip),
+        _ => None,
+    }
+}
+
+fn client_identifier(message: &DhcpMessage) -> Option<Vec<u8>> {
+    match message.get_option(OPTION_CLIENT_IDENTIFIER)? {
+        DhcpOption::ClientIdentifier(identifier) => Some(identifier.clone()),
+        _ => None,
+    }
+}
+
+fn hardware_address(message: &DhcpMessage) -> [u8; 6] {
+    let mut mac = [0; 6];
+    mac.copy_from_slice(&message.chaddr[..6]);
+    mac
+}
+
+// Clients are identified by their client identifier if they send one, and
+// by their hardware address otherwise (RFC 2131 section 4.2).
+fn client_key(message: &DhcpMessage) -> Vec<u8> {
+    client_identifier(message)
+        .unwrap_or_else(|| message.chaddr[..message.hlen.min(16) as usize].to_vec())
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+use crate::error::DhcpError;
+
+use crate::raw::{
+    find_option, message_type, options_end, BOOTREPLY, BOOTREQUEST, CHADDR_OFFSET, OP_OFFSET,
+    YIADDR_OFFSET,
+};
+
+const OPTION_LEASE_TIME: u8 = 51;
+const OPTION_RELAY_AGENT_INFORMATION: u8 = 82;
+
+const SUBOPTION_CIRCUIT_ID: u8 = 1;
+const SUBOPTION_REMOTE_ID: u8 = 2;
+
+const DHCPDECLINE: u8 = 4;
+const DHCPACK: u8 = 5;
+const DHCPNAK: u8 = 6;
+const DHCPRELEASE: u8 = 7;
+
+/// A MAC to IP address binding learned from a snooped DHCPACK.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub mac: [u8; 6],
+    pub ip: Ipv4Addr,
+    pub circuit_id: Vec<u8>,
+    pub expires_at: Instant,
+}
+
+/// The table of bindings built by the snooper, usable for source guard and
+/// dynamic ARP inspection style filtering.
+#[derive(Debug, Default)]
+pub struct BindingTable {
+    bindings: HashMap<[u8; 6], Binding>,
+}
+
+impl BindingTable {
+    pub fn new() -> BindingTable {
+        BindingTable::default()
+    }
+
+    /// Insert a binding, replacing any previous binding for the same MAC.
+    pub fn insert(&mut self, binding: Binding) {
+        self.bindings.insert(binding.mac, binding);
+    }
+
+    /// Remove the binding of a MAC address.
+    pub fn remove(&mut self, mac: &[u8; 6]) -> Option<Binding> {
+        self.bindings.remove(mac)
+    }
+
+    /// Retrieve the binding of a MAC address.
+    pub fn get(&self, mac: &[u8; 6]) -> Option<&Binding> {
+        self.bindings.get(mac)
+    }
+
+    /// Retrieve the binding holding an IP address.
+    pub fn get_by_ip(&self, ip: Ipv4Addr) -> Option<&Binding> {
+        self.bindings.values().find(|binding| binding.ip == ip)
+    }
+
+    /// Check whether traffic sourced from `ip` by `mac` is backed by a live binding.
+    pub fn is_permitted(&self, mac: &[u8; 6], ip: Ipv4Addr, now: Instant) -> bool {
+        match self.bindings.get(mac) {
+            Some(binding) => binding.ip == ip && binding.expires_at > now,
+            None => false,
+        }
+    }
+
+    /// Drop every binding whose lease has expired.
+    pub fn expire(&mut self, now: Instant) {
+        self.bindings.retain(|_, binding| binding.expires_at > now);
+    }
+
+    pub fn len(&self) -> usize {
+        self.bindings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Binding> {
+        self.bindings.values()
+    }
+}
+
+/// What the snooper decided to do with a packet.
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    // The (possibly rewritten) packet should be forwarded.
+    Forward,
+    // The packet violates the snooping policy and must be dropped.
+    Drop,
+}
+
+/// A transparent layer-2 DHCP snooper.
+///
+/// Unlike a relay agent, the snooper never touches giaddr or hops: it only
+/// inserts the relay agent information option (82) on packets coming from
+/// untrusted client ports, strips it from packets coming back from the
+/// trusted server side, and learns bindings from the DHCPACKs it sees.
+/// Packets are edited in place so every other byte is forwarded unchanged.
+#[derive(Debug, Default)]
+pub struct Snooper {
+    remote_id: Option<Vec<u8>>,
+    bindings: BindingTable,
+}
+
+impl Snooper {
+    pub fn new() -> Snooper {
+        Snooper::default()
+    }
+
+    /// Set the remote ID sub-option inserted alongside the circuit ID.
+    pub fn with_remote_id(mut self, remote_id: Vec<u8>) -> Snooper {
+        self.remote_id = Some(remote_id);
+        self
+    }
+
+    pub fn bindings(&self) -> &BindingTable {
+        &self.bindings
+    }
+
+    pub fn bindings_mut(&mut self) -> &mut BindingTable {
+        &mut self.bindings
+    }
+
+    /// Process a packet received on an untrusted client port.
+    ///
+    /// Server replies and requests already carrying option 82 are dropped.
+    /// Otherwise option 82 is inserted with the given circuit ID, and
+    /// DHCPRELEASE/DHCPDECLINE remove the client binding.
+    pub fn client_packet(
+        &mut self,
+        packet: &mut Vec<u8>,
+        circuit_id: &[u8],
+    ) -> Result<Verdict, DhcpError> {
+        let end = options_end(packet)?;
+
+        if packet[OP_OFFSET] != BOOTREQUEST {
+            return Ok(Verdict::Drop);
+        }
+
+        if find_option(packet, OPTION_RELAY_AGENT_INFORMATION)?.is_some() {
+            return Ok(Verdict::Drop);
+        }
+
+        let mac = chaddr(packet);
+        match message_type(packet)? {
+            Some(DHCPRELEASE) | Some(DHCPDECLINE) => {
+                self.bindings.remove(&mac);
+            }
+            _ => {}
+        }
+
+        let mut option = vec![OPTION_RELAY_AGENT_INFORMATION, 0];
+        push_suboption(&mut option, SUBOPTION_CIRCUIT_ID, circuit_id)?;
+        if let Some(remote_id) = &self.remote_id {
+            push_suboption(&mut option, SUBOPTION_REMOTE_ID, remote_id)?;
+        }
+        if option.len() - 2 > 255 {
+            return Err(DhcpError::ParsingError(
+                "Relay agent information option is too long".into(),
+            ));
+        }
+        option[1] = (option.len() - 2) as u8;
+
+        packet.splice(end..end, option);
+
+        Ok(Verdict::Forward)
+    }
+
+    /// Process a packet received from the trusted server side.
+    ///
+    /// Option 82 is stripped and its circuit ID returned so the caller knows
+    /// which port to forward the reply to. DHCPACKs create bindings and
+    /// DHCPNAKs remove them.
+    pub fn server_packet(
+        &mut self,
+        packet: &mut Vec<u8>,
+        now: Instant,
+    ) -> Result<(Verdict, Option<Vec<u8>>), DhcpError> {
+        options_end(packet)?;
+
+        if packet[OP_OFFSET] != BOOTREPLY {
+            return Ok((Verdict::Drop, None));
+        }
+
+        let circuit_id = match find_option(packet, OPTION_RELAY_AGENT_INFORMATION)? {
+            Some((start, len)) => {
+                let circuit_id =
+                    find_suboption(&packet[start + 2..start + 2 + len], SUBOPTION_CIRCUIT_ID)
+                        .map(|circuit_id| circuit_id.to_vec());
+                packet.drain(start..start + 2 + len);
+                circuit_id
+            }
+            None => None,
+        };
+
+        let mac = chaddr(packet);
+        match message_type(packet)? {
+            Some(DHCPACK) => {
+                let ip = Ipv4Addr::new(
+                    packet[YIADDR_OFFSET],
+                    packet[YIADDR_OFFSET + 1],
+                    packet[YIADDR_OFFSET + 2],
+                    packet[YIADDR_OFFSET + 3],
+                );
+                // A DHCPACK to a DHCPINFORM carries no address to bind.
+                if !ip.is_unspecified() {
+                    let lease_time = match find_option(packet, OPTION_LEASE_TIME)? {
+                        Some((start, 4)) => u32::from_be_bytes([
+                            packet[start + 2],
+                            packet[start + 3],
+                            packet[start + 4],
+                            packet[start + 5],
+                        ]),
+                        _ => 0,
+                    };
+                    self.bindings.insert(Binding {
+                        mac,
+                        ip,
+                        circuit_id: circuit_id.clone().unwrap_or_default(),
+                        expires_at: now + Duration::from_secs(lease_time as u64),
+                    });
+                }
+            }
+            Some(DHCPNAK) => {
+                self.bindings.remove(&mac);
+            }
+            _ => {}
+        }
+
+        Ok((Verdict::Forward, circuit_id))
+    }
+}
+
+fn chaddr(packet: &[u8]) -> [u8; 6] {
+    let mut mac = [0; 6];
+    mac.copy_from_slice(&packet[CHADDR_OFFSET..CHADDR_OFFSET + 6]);
+    mac
+}
+
+fn push_suboption(option: &mut Vec<u8>, code: u8, value: &[u8]) -> Result<(), DhcpError> {
+    if value.len() > 255 {
+        return Err(DhcpError::ParsingError(
+            "Relay agent information sub-option is too long".into(),
+        ));
+    }
+    option.push(code);
+    option.push(value.len() as u8);
+    option.extend_from_slice(value);
+    Ok(())
+}
+
+fn find_suboption(data: &[u8], code: u8) -> Option<&[u8]> {
+    let mut index = 0;
+    while index + 2 <= data.len() {
+        let len = data[index + 1] as usize;
+        if index + 2 + len > data.len() {
+            return None;
+        }
+        if data[index] == code {
+            return Some(&data[index + 2..index + 2 + len]);
+        }
+        index += 2 + len;
+    }
+    None
+}
@@ -0,0 +1,163 @@
+//! Generating options and messages for fuzzing and property tests.
+//!
+//! With the `arbitrary` feature, `DhcpOption` and `DhcpMessage` implement
+//! `arbitrary::Arbitrary`, for fuzzers such as `cargo fuzz`. With the
+//! `proptest` feature, `options` and `messages` are the matching proptest
+//! strategies.
+//!
+//! Options are decoded from an arbitrary code and value, the value first
+//! fitted to the length of the option, so that they are options the
+//! decoder produces. Values it still rejects, such as text that is not
+//! UTF-8, are kept in a site-specific option (RFC 3942). Messages hold no
+//! Option Overload option, and no two consecutive options of the same
+//! code, which parse as one (RFC 3396): they serialize and parse back
+//! unchanged.
+
+use std::net::Ipv4Addr;
+
+use crate::message::{DhcpMessage, Flags};
+use crate::option::{info, DhcpOption, LengthRule};
+use crate::options::DhcpOptions;
+
+const OPTION_OVERLOAD: u8 = 52;
+
+// The first site-specific option code (RFC 3942).
+const SITE_SPECIFIC: u8 = 224;
+
+// The option decoded from `value` for `code`.
+fn option(code: u8, mut value: Vec<u8>) -> DhcpOption {
+    // Pad and End have no value.
+    let code = code.clamp(1, 254);
+    if let Some(info) = info(code) {
+        match info.len {
+            LengthRule::Empty => value.clear(),
+            LengthRule::Fixed(len) => value.resize(len, 0),
+            LengthRule::Min(min) => value.resize(value.len().max(min), 0),
+            LengthRule::List { min, unit } => {
+                value.truncate(value.len() - value.len() % unit);
+                value.resize(value.len().max(min), 0);
+            }
+        }
+    }
+    match DhcpOption::deserialize_value(code, &value) {
+        // Names decoded through compression pointers may be too long to
+        // write out without them.
+        Ok(option) if option.serialize().is_ok() => option,
+        _ => DhcpOption::Unknown(SITE_SPECIFIC + code % (255 - SITE_SPECIFIC), value),
+    }
+}
+
+// The header fields of a message, and its options.
+struct Parts {
+    header: [u8; 4],
+    xid: u32,
+    secs: u16,
+    flags: u16,
+    addresses: [Ipv4Addr; 4],
+    chaddr: [u8; 16],
+    sname: [u8; 64],
+    file: [u8; 128],
+    options: Vec<DhcpOption>,
+}
+
+impl Parts {
+    fn message(self) -> DhcpMessage {
+        let [op, htype, hlen, hops] = self.header;
+        let [ciaddr, yiaddr, siaddr, giaddr] = self.addresses;
+        let mut options = DhcpOptions::new();
+        for option in self.options {
+            let code = option.code();
+            if u8::from(code) != OPTION_OVERLOAD
+                && options.last().map(DhcpOption::code) != Some(code)
+            {
+                options.push(option);
+            }
+        }
+        DhcpMessage {
+            op,
+            htype,
+            hlen,
+            hops,
+            xid: self.xid,
+            secs: self.secs,
+            flags: Flags::from_bits(self.flags),
+            ciaddr,
+            yiaddr,
+            siaddr,
+            giaddr,
+            chaddr: self.chaddr,
+            sname: self.sname,
+            file: self.file,
+            options,
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for DhcpOption {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<DhcpOption> {
+        Ok(option(u.arbitrary()?, u.arbitrary()?))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for DhcpMessage {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<DhcpMessage> {
+        let parts = Parts {
+            header: u.arbitrary()?,
+            xid: u.arbitrary()?,
+            secs: u.arbitrary()?,
+            flags: u.arbitrary()?,
+            addresses: u.arbitrary()?,
+            chaddr: u.arbitrary()?,
+            sname: u.arbitrary()?,
+            file: u.arbitrary()?,
+            options: u.arbitrary()?,
+        };
+        Ok(parts.message())
+    }
+}
+
+/// Options decoded from arbitrary codes and values of up to 64 bytes.
+#[cfg(feature = "proptest")]
+pub fn options() -> impl proptest::strategy::Strategy<Value = DhcpOption> {
+    use proptest::prelude::*;
+
+    (any::<u8>(), proptest::collection::vec(any::<u8>(), 0..=64))
+        .prop_map(|(code, value)| option(code, value))
+}
+
+/// Messages with arbitrary header fields and up to 16 options.
+#[cfg(feature = "proptest")]
+pub fn messages() -> impl proptest::strategy::Strategy<Value = DhcpMessage> {
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    (
+        any::<[u8; 4]>(),
+        any::<(u32, u16, u16)>(),
+        any::<[u32; 4]>(),
+        any::<[u8; 16]>(),
+        vec(any::<u8>(), 64),
+        vec(any::<u8>(), 128),
+        vec(options(), 0..=16),
+    )
+        .prop_map(
+            |(header, (xid, secs, flags), addresses, chaddr, sname, file, options)| {
+                let mut parts = Parts {
+                    header,
+                    xid,
+                    secs,
+                    flags,
+                    addresses: addresses.map(Ipv4Addr::from),
+                    chaddr,
+                    sname: [0; 64],
+                    file: [0; 128],
+                    options,
+                };
+                parts.sname.copy_from_slice(&sname);
+                parts.file.copy_from_slice(&file);
+                parts.message()
+            },
+        )
+}
@@ -0,0 +1,252 @@
+//! A human-editable text representation of options, for configuration
+//! files and command line tools:
+//!
+//! ```text
+//! router=192.168.1.1,192.168.1.2
+//! host_name="printer-3"
+//! ip_address_lease_time=86400
+//! 224=0x0a000001
+//! ```
+//!
+//! Options are named as in the JSON representation, or given by code.
+//! Lists are separated by commas, text is quoted, and data not matching the
+//! type of its option, such as text that is not valid UTF-8, is written as
+//! hex after `0x` and read back unchanged. Pad and End are written as `pad`
+//! and `end`.
+
+use std::fmt::Write;
+use std::net::Ipv4Addr;
+use std::str::FromStr;
+
+use crate::error::DhcpError;
+use crate::message::TextPolicy;
+use crate::option::{info, info_by_key, DhcpOption};
+
+// The type of an option value, in the text and JSON representations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Kind {
+    Address,
+    // A list of addresses.
+    Addresses,
+    // UTF-8 text.
+    Text,
+    Bool,
+    U8,
+    U16,
+    U32,
+    // A list of 16-bit integers.
+    U16s,
+    // A list of domain names.
+    Names,
+    // Raw data, in hex.
+    Bytes,
+}
+
+// The name and value type of an option code.
+pub(crate) fn describe(code: u8) -> (Option<&'static str>, Kind) {
+    match info(code) {
+        Some(info) => (Some(info.key), info.kind),
+        None => (None, Kind::Bytes),
+    }
+}
+
+// The code of the option named `name`.
+pub(crate) fn code_of(name: &str) -> Option<u8> {
+    info_by_key(name).map(|info| info.code)
+}
+
+impl DhcpOption {
+    /// Write the option as `name=value`, read back by `DhcpOption::from_str`.
+    pub fn to_text(&self) -> String {
+        let data = self.encode();
+        let code = data[0];
+        let value = data.get(2..).unwrap_or(&[]);
+        let (name, kind) = describe(code);
+
+        let mut text = match (self, name) {
+            (DhcpOption::Pad, _) => return "pad".to_string(),
+            (DhcpOption::End, _) => return "end".to_string(),
+            (_, Some(name)) => format!("{}=", name),
+            (_, None) => format!("{}=", code),
+        };
+        let join = |items: Vec<String>| items.join(",");
+        let _ = match (kind, self) {
+            (Kind::Names, DhcpOption::DomainSearch(names)) => {
+                write!(
+                    text,
+                    "{}",
+                    join(names.iter().map(|name| quote(name)).collect())
+                )
+            }
+            (Kind::Address, _) if value.len() == 4 => write!(text, "{}", address(value)),
+            (Kind::Addresses, _) if !value.is_empty() && value.len().is_multiple_of(4) => {
+                write!(text, "{}", join(value.chunks(4).map(address).collect()))
+            }
+            (Kind::Text, _) if std::str::from_utf8(value).is_ok() => {
+                write!(
+                    text,
+                    "{}",
+                    quote(std::str::from_utf8(value).unwrap_or_default())
+                )
+            }
+            (Kind::Bool, _) if value.len() == 1 && value[0] <= 1 => {
+                write!(text, "{}", value[0] == 1)
+            }
+            (Kind::U8, _) if value.len() == 1 => write!(text, "{}", value[0]),
+            (Kind::U16, _) if value.len() == 2 => {
+                write!(text, "{}", u16::from_be_bytes([value[0], value[1]]))
+            }
+            (Kind::U32, _) if value.len() == 4 => write!(
+                text,
+                "{}",
+                u32::from_be_bytes([value[0], value[1], value[2], value[3]])
+            ),
+            (Kind::U16s, _) if !value.is_empty() && value.len().is_multiple_of(2) => write!(
+                text,
+                "{}",
+                join(
+                    value
+                        .chunks(2)
+                        .map(|word| u16::from_be_bytes([word[0], word[1]]).to_string())
+                        .collect()
+                )
+            ),
+            // Data not matching the type of its option is kept raw.
+            _ => {
+                text.push_str("0x");
+                value
+                    .iter()
+                    .try_for_each(|byte| write!(text, "{:02x}", byte))
+            }
+        };
+        text
+    }
+}
+
+impl FromStr for DhcpOption {
+    type Err = DhcpError;
+
+    /// Read an option written by `DhcpOption::to_text`.
+    fn from_str(s: &str) -> Result<DhcpOption, DhcpError> {
+        let error = || DhcpError::ParsingError(format!("Could not parse option {}", s).into());
+        let (key, value) = match s.trim() {
+            "pad" => return Ok(DhcpOption::Pad),
+            "end" => return Ok(DhcpOption::End),
+            s => s.split_once('=').ok_or_else(error)?,
+        };
+        let key = key.trim();
+        let code = match key.parse::<u8>() {
+            Ok(code) => code,
+            Err(_) => code_of(key).ok_or_else(|| {
+                DhcpError::ParsingError(format!("Unknown option name {}", key).into())
+            })?,
+        };
+        let value = value.trim();
+        let (_, kind) = describe(code);
+
+        let data = match (kind, value.strip_prefix("0x")) {
+            (_, Some(hex)) => parse_hex(hex),
+            (Kind::Address, None) => value.parse::<Ipv4Addr>().ok().map(|a| a.octets().to_vec()),
+            (Kind::Addresses, None) => value
+                .split(',')
+                .map(|item| item.trim().parse::<Ipv4Addr>().ok().map(|a| a.octets()))
+                .collect::<Option<Vec<[u8; 4]>>>()
+                .map(|addresses| addresses.concat()),
+            (Kind::Text, None) => match unquote(value) {
+                Some((text, "")) => Some(text.into_bytes()),
+                _ => None,
+            },
+            (Kind::Bool, None) => value.parse::<bool>().ok().map(|flag| vec![flag as u8]),
+            (Kind::U8, None) => value.parse::<u8>().ok().map(|byte| vec![byte]),
+            (Kind::U16, None) => value
+                .parse::<u16>()
+                .ok()
+                .map(|word| word.to_be_bytes().to_vec()),
+            (Kind::U32, None) => value
+                .parse::<u32>()
+                .ok()
+                .map(|word| word.to_be_bytes().to_vec()),
+            (Kind::U16s, None) => value
+                .split(',')
+                .map(|item| item.trim().parse::<u16>().ok().map(u16::to_be_bytes))
+                .collect::<Option<Vec<[u8; 2]>>>()
+                .map(|words| words.concat()),
+            (Kind::Names, None) => {
+                let mut names = Vec::new();
+                let mut rest = value;
+                loop {
+                    let (name, after) = unquote(rest).ok_or_else(error)?;
+                    names.push(name);
+                    match after.trim_start().strip_prefix(',') {
+                        Some(after) => rest = after.trim_start(),
+                        None if after.trim().is_empty() => break,
+                        None => return Err(error()),
+                    }
+                }
+                return Ok(DhcpOption::DomainSearch(names));
+            }
+            (Kind::Bytes, None) => None,
+        };
+        // Text that is not UTF-8, written as hex, is kept raw.
+        DhcpOption::deserialize_value_with(code, &data.ok_or_else(error)?, TextPolicy::Raw)
+    }
+}
+
+// Parse hex data, with or without colon separators.
+fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.replace(':', "");
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).ok())
+        .collect()
+}
+
+fn address(octets: &[u8]) -> String {
+    Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]).to_string()
+}
+
+// Quote text, escaping quotes, backslashes and control characters.
+fn quote(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(result, "\\u{{{:x}}}", c as u32);
+            }
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+// Read quoted text from the start of `value`, returning it with the data
+// following the closing quote.
+fn unquote(value: &str) -> Option<(String, &str)> {
+    let mut chars = value.strip_prefix('"')?.char_indices();
+    let mut result = String::new();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Some((result, &value[index + 2..])),
+            '\\' => match chars.next()?.1 {
+                'u' => {
+                    let rest = chars.as_str().strip_prefix('{')?;
+                    let (hex, _) = rest.split_once('}')?;
+                    result.push(char::from_u32(u32::from_str_radix(hex, 16).ok()?)?);
+                    for _ in 0..hex.len() + 2 {
+                        chars.next();
+                    }
+                }
+                c => result.push(c),
+            },
+            c => result.push(c),
+        }
+    }
+    None
+}
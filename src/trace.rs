@@ -0,0 +1,13 @@
+// Events of the `tracing` feature, compiled out without it.
+//
+// `event!(debug, ip = %ip, "offering")` expands to `tracing::debug!` with
+// the feature, and to nothing without it: the fields should not be the
+// only use of a variable.
+macro_rules! event {
+    ($level:ident, $($field:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($field)+);
+    };
+}
+
+pub(crate) use event;
@@ -0,0 +1,66 @@
+use crate::duid::Duid;
+use crate::v6::message::Dhcpv6Message;
+use crate::v6::option::Dhcpv6Option;
+
+// Options a client following the anonymity profile may request: DNS
+// servers, domain list, NTP server, SOL_MAX_RT and INF_MAX_RT.
+const ALLOWED_REQUESTS: [u16; 5] = [23, 24, 56, 82, 83];
+
+// Options revealing the identity of the client: user class, vendor class,
+// vendor-specific information and client FQDN.
+const IDENTIFYING_OPTIONS: [u16; 4] = [15, 16, 17, 39];
+
+/// The DHCPv6 anonymity profile of RFC 7844, applied to the messages sent
+/// by a client.
+///
+/// The client identifies itself with a DUID-LL built from a randomized
+/// link-layer address, requests a minimal set of options and never sends
+/// options that could identify it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnonymityProfile {
+    duid: Duid,
+}
+
+impl AnonymityProfile {
+    /// Create a profile for an Ethernet interface using `link_layer_address`,
+    /// which should be randomized along with the interface MAC address.
+    pub fn new(link_layer_address: [u8; 6]) -> AnonymityProfile {
+        AnonymityProfile {
+            duid: Duid::LinkLayer {
+                hardware_type: 1,
+                link_layer_address: link_layer_address.to_vec(),
+            },
+        }
+    }
+
+    /// The DUID the client uses while the profile is active.
+    pub fn duid(&self) -> &Duid {
+        &self.duid
+    }
+
+    /// Reduce a list of requested options to those allowed by the profile.
+    pub fn option_request(&self, codes: &[u16]) -> Vec<u16> {
+        codes
+            .iter()
+            .copied()
+            .filter(|code| ALLOWED_REQUESTS.contains(code))
+            .collect::<Vec<u16>>()
+    }
+
+    /// Rewrite a client message to follow the profile: the client identifier
+    /// uses the anonymous DUID, the option request list is minimized and
+    /// identifying options are removed.
+    pub fn apply(&self, message: &mut Dhcpv6Message) {
+        message
+            .options
+            .retain(|option| !IDENTIFYING_OPTIONS.contains(&option.code()));
+
+        for option in message.options.iter_mut() {
+            match option {
+                Dhcpv6Option::ClientId(duid) => *duid = self.duid.clone(),
+                Dhcpv6Option::OptionRequest(codes) => *codes = self.option_request(codes),
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::duid::Duid;
+use crate::error::DhcpError;
+use crate::v6::message::{Dhcpv6Message, MessageType};
+use crate::v6::option::{Dhcpv6Option, LqQuery, QueryType, Status, StatusCode};
+
+const OPTION_CLIENTID: u16 = 1;
+const OPTION_IAADDR: u16 = 5;
+const OPTION_REMOTE_ID: u16 = 37;
+const OPTION_LQ_QUERY: u16 = 44;
+const OPTION_RELAY_ID: u16 = 53;
+
+/// The bindings of a server, as exposed to leasequery requestors.
+pub trait LeaseStore {
+    /// Retrieve the bindings matching a validated query, one entry per
+    /// client and link, each being the options of a Client Data option.
+    fn query(&self, query: &LqQuery) -> Vec<Vec<Dhcpv6Option>>;
+}
+
+/// Build a LEASEQUERY message sent by a requestor.
+pub fn leasequery(transaction_id: [u8; 3], client_id: Duid, query: LqQuery) -> Dhcpv6Message {
+    let mut message = Dhcpv6Message::new(MessageType::Leasequery, transaction_id);
+    message.options = vec![
+        Dhcpv6Option::ClientId(client_id),
+        Dhcpv6Option::LqQuery(query),
+    ];
+    message
+}
+
+/// Answer a LEASEQUERY received over a bulk leasequery connection
+/// (RFC 5460).
+///
+/// The first binding is returned in a LEASEQUERY-REPLY, every other one in
+/// a LEASEQUERY-DATA, and a LEASEQUERY-DONE closes the answer when more
+/// than one message was needed. Each message is to be sent with
+/// `framing::write_frame`.
+pub fn bulk_leasequery(
+    request: &Dhcpv6Message,
+    server_id: &Duid,
+    store: &impl LeaseStore,
+) -> Result<Vec<Dhcpv6Message>, DhcpError> {
+    if request.message_type != MessageType::Leasequery {
+        return Err(DhcpError::ParsingError(
+            "Expected a DHCPv6 LEASEQUERY message".into(),
+        ));
+    }
+
+    let mut reply = response(request, MessageType::LeasequeryReply, server_id);
+
+    let query = match request.get_option(OPTION_LQ_QUERY) {
+        Some(Dhcpv6Option::LqQuery(query)) => query,
+        _ => {
+            reply.options.push(Dhcpv6Option::StatusCode(StatusCode::new(
+                Status::MalformedQuery,
+                "missing query",
+            )));
+            return Ok(vec![reply]);
+        }
+    };
+    if let Err(status_code) = validate(query) {
+        reply.options.push(Dhcpv6Option::StatusCode(status_code));
+        return Ok(vec![reply]);
+    }
+
+    let mut bindings = store.query(query).into_iter();
+    if let Some(client_data) = bindings.next() {
+        reply.options.push(Dhcpv6Option::ClientData(client_data));
+    }
+
+    let mut messages = vec![reply];
+    for client_data in bindings {
+        let mut data = Dhcpv6Message::new(MessageType::LeasequeryData, request.transaction_id);
+        data.options.push(Dhcpv6Option::ClientData(client_data));
+        messages.push(data);
+    }
+    if messages.len() > 1 {
+        messages.push(response(request, MessageType::LeasequeryDone, server_id));
+    }
+    Ok(messages)
+}
+
+// Check that the query carries the options its type requires.
+fn validate(query: &LqQuery) -> Result<(), StatusCode> {
+    let required = match query.query_type {
+        QueryType::ByAddress => Some(OPTION_IAADDR),
+        QueryType::ByClientId => Some(OPTION_CLIENTID),
+        QueryType::ByRelayId => Some(OPTION_RELAY_ID),
+        QueryType::ByLinkAddress => None,
+        QueryType::ByRemoteId => Some(OPTION_REMOTE_ID),
+        QueryType::Other(_) => {
+            return Err(StatusCode::new(
+                Status::UnknownQueryType,
+                "unknown query type",
+            ))
+        }
+    };
+
+    match required {
+        Some(code) if !query.options.iter().any(|option| option.code() == code) => Err(
+            StatusCode::new(Status::MalformedQuery, "missing query option"),
+        ),
+        _ => Ok(()),
+    }
+}
+
+fn response(request: &Dhcpv6Message, message_type: MessageType, server_id: &Duid) -> Dhcpv6Message {
+    let mut message = Dhcpv6Message::new(message_type, request.transaction_id);
+    message
+        .options
+        .push(Dhcpv6Option::ServerId(server_id.clone()));
+    if let Some(client_id) = request.get_option(OPTION_CLIENTID) {
+        message.options.push(client_id.clone());
+    }
+    message
+}
@@ -0,0 +1,278 @@
+use std::net::Ipv6Addr;
+
+use crate::error::DhcpError;
+use crate::v6::option::Dhcpv6Option;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageType {
+    Solicit,
+    Advertise,
+    Request,
+    Confirm,
+    Renew,
+    Rebind,
+    Reply,
+    Release,
+    Decline,
+    Reconfigure,
+    InformationRequest,
+    RelayForw,
+    RelayRepl,
+    Leasequery,
+    LeasequeryReply,
+    LeasequeryDone,
+    LeasequeryData,
+}
+
+impl MessageType {
+    /// Whether this type uses the relay agent message format.
+    pub fn is_relay(&self) -> bool {
+        matches!(self, MessageType::RelayForw | MessageType::RelayRepl)
+    }
+}
+
+impl From<MessageType> for u8 {
+    fn from(message_type: MessageType) -> u8 {
+        match message_type {
+            MessageType::Solicit => 1,
+            MessageType::Advertise => 2,
+            MessageType::Request => 3,
+            MessageType::Confirm => 4,
+            MessageType::Renew => 5,
+            MessageType::Rebind => 6,
+            MessageType::Reply => 7,
+            MessageType::Release => 8,
+            MessageType::Decline => 9,
+            MessageType::Reconfigure => 10,
+            MessageType::InformationRequest => 11,
+            MessageType::RelayForw => 12,
+            MessageType::RelayRepl => 13,
+            MessageType::Leasequery => 14,
+            MessageType::LeasequeryReply => 15,
+            MessageType::LeasequeryDone => 16,
+            MessageType::LeasequeryData => 17,
+        }
+    }
+}
+
+impl TryFrom<u8> for MessageType {
+    type Error = DhcpError;
+
+    fn try_from(value: u8) -> Result<MessageType, DhcpError> {
+        match value {
+            1 => Ok(MessageType::Solicit),
+            2 => Ok(MessageType::Advertise),
+            3 => Ok(MessageType::Request),
+            4 => Ok(MessageType::Confirm),
+            5 => Ok(MessageType::Renew),
+            6 => Ok(MessageType::Rebind),
+            7 => Ok(MessageType::Reply),
+            8 => Ok(MessageType::Release),
+            9 => Ok(MessageType::Decline),
+            10 => Ok(MessageType::Reconfigure),
+            11 => Ok(MessageType::InformationRequest),
+            12 => Ok(MessageType::RelayForw),
+            13 => Ok(MessageType::RelayRepl),
+            14 => Ok(MessageType::Leasequery),
+            15 => Ok(MessageType::LeasequeryReply),
+            16 => Ok(MessageType::LeasequeryDone),
+            17 => Ok(MessageType::LeasequeryData),
+            _ => Err(DhcpError::ParsingError(
+                format!("Unknown DHCPv6 message type: {}", value).into(),
+            )),
+        }
+    }
+}
+
+// Client/Server Message
+//
+// All DHCP messages sent between clients and servers share an identical
+// fixed-format header and a variable-format area for options.
+//
+//  0                   1                   2                   3
+//  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |    msg-type   |               transaction-id                  |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |                                                               |
+// .                            options                            .
+// .                 (variable number and length)                  .
+// |                                                               |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dhcpv6Message {
+    pub message_type: MessageType,
+    pub transaction_id: [u8; 3],
+    pub options: Vec<Dhcpv6Option>,
+}
+
+impl Dhcpv6Message {
+    pub fn new(message_type: MessageType, transaction_id: [u8; 3]) -> Dhcpv6Message {
+        Dhcpv6Message {
+            message_type,
+            transaction_id,
+            options: Vec::new(),
+        }
+    }
+
+    /// Retrieve the first option with the given code.
+    pub fn get_option(&self, code: u16) -> Option<&Dhcpv6Option> {
+        self.options.iter().find(|option| option.code() == code)
+    }
+
+    /// Serialize the message, failing if an option cannot be, such as
+    /// one holding a domain name longer than DNS allows.
+    pub fn serialize(&self) -> Result<Vec<u8>, DhcpError> {
+        let mut result = vec![self.message_type.into()];
+        result.extend_from_slice(&self.transaction_id);
+        for option in &self.options {
+            result.extend_from_slice(&option.serialize()?);
+        }
+        Ok(result)
+    }
+
+    pub fn deserialize(data: &[u8]) -> Result<Dhcpv6Message, DhcpError> {
+        if data.len() < 4 {
+            return Err(DhcpError::ParsingError(
+                "Could not parse DHCPv6 message header".into(),
+            ));
+        }
+
+        let message_type = MessageType::try_from(data[0])?;
+        if message_type.is_relay() {
+            return Err(DhcpError::ParsingError(
+                "DHCPv6 relay message is not a client/server message".into(),
+            ));
+        }
+
+        Ok(Dhcpv6Message {
+            message_type,
+            transaction_id: [data[1], data[2], data[3]],
+            options: Dhcpv6Option::deserialize_all(&data[4..])?,
+        })
+    }
+}
+
+// Relay Agent/Server Message
+//
+// Relay agents exchange messages with other relay agents and servers to
+// relay messages between clients and servers that are not connected to
+// the same link.
+//
+//  0                   1                   2                   3
+//  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |    msg-type   |   hop-count   |                               |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               |
+// |                                                               |
+// |                         link-address                          |
+// |                                                               |
+// |                               +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |                               |                               |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               |
+// |                                                               |
+// |                         peer-address                          |
+// |                                                               |
+// |                               +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |                               |                               |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               |
+// .                                                               .
+// .            options (variable number and length)   ....        .
+// |                                                               |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dhcpv6RelayMessage {
+    pub message_type: MessageType,
+    pub hop_count: u8,
+    pub link_address: Ipv6Addr,
+    pub peer_address: Ipv6Addr,
+    pub options: Vec<Dhcpv6Option>,
+}
+
+impl Dhcpv6RelayMessage {
+    /// Retrieve the first option with the given code.
+    pub fn get_option(&self, code: u16) -> Option<&Dhcpv6Option> {
+        self.options.iter().find(|option| option.code() == code)
+    }
+
+    pub fn serialize(&self) -> Result<Vec<u8>, DhcpError> {
+        let mut result = vec![self.message_type.into(), self.hop_count];
+        result.extend_from_slice(&self.link_address.octets());
+        result.extend_from_slice(&self.peer_address.octets());
+        for option in &self.options {
+            result.extend_from_slice(&option.serialize()?);
+        }
+        Ok(result)
+    }
+
+    pub fn deserialize(data: &[u8]) -> Result<Dhcpv6RelayMessage, DhcpError> {
+        if data.len() < 34 {
+            return Err(DhcpError::ParsingError(
+                "Could not parse DHCPv6 relay message header".into(),
+            ));
+        }
+
+        let message_type = MessageType::try_from(data[0])?;
+        if !message_type.is_relay() {
+            return Err(DhcpError::ParsingError(
+                "DHCPv6 client/server message is not a relay message".into(),
+            ));
+        }
+
+        let mut link_address = [0; 16];
+        link_address.copy_from_slice(&data[2..18]);
+        let mut peer_address = [0; 16];
+        peer_address.copy_from_slice(&data[18..34]);
+
+        Ok(Dhcpv6RelayMessage {
+            message_type,
+            hop_count: data[1],
+            link_address: Ipv6Addr::from(link_address),
+            peer_address: Ipv6Addr::from(peer_address),
+            options: Dhcpv6Option::deserialize_all(&data[34..])?,
+        })
+    }
+}
+
+/// Either kind of DHCPv6 message, as found on the wire.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dhcpv6Packet {
+    Message(Dhcpv6Message),
+    Relay(Dhcpv6RelayMessage),
+}
+
+impl Dhcpv6Packet {
+    pub fn message_type(&self) -> MessageType {
+        match self {
+            Dhcpv6Packet::Message(message) => message.message_type,
+            Dhcpv6Packet::Relay(relay) => relay.message_type,
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip_all, err(level = "debug"))
+    )]
+    pub fn serialize(&self) -> Result<Vec<u8>, DhcpError> {
+        match self {
+            Dhcpv6Packet::Message(message) => message.serialize(),
+            Dhcpv6Packet::Relay(relay) => relay.serialize(),
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip_all, fields(len = data.len()), err(level = "debug"))
+    )]
+    pub fn deserialize(data: &[u8]) -> Result<Dhcpv6Packet, DhcpError> {
+        match data.first() {
+            Some(&message_type) if MessageType::try_from(message_type)?.is_relay() => {
+                Ok(Dhcpv6Packet::Relay(Dhcpv6RelayMessage::deserialize(data)?))
+            }
+            Some(_) => Ok(Dhcpv6Packet::Message(Dhcpv6Message::deserialize(data)?)),
+            None => Err(DhcpError::ParsingError(
+                "No DHCPv6 message type found".into(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod anonymity;
+pub mod leasequery;
+pub mod message;
+pub mod option;
+pub mod reconfigure;
+pub mod relay;
+pub mod retransmission;
@@ -0,0 +1,1453 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::dns;
+use crate::duid::Duid;
+use crate::error::DhcpError;
+use crate::option::Authentication;
+use crate::v6::message::MessageType;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dhcpv6Option {
+    // Client Identifier Option
+    //
+    // The Client Identifier option is used to carry a DUID identifying a
+    // client between a client and a server.
+    //
+    //  0                   1                   2                   3
+    //  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |        OPTION_CLIENTID        |          option-len           |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                                                               .
+    // .                              DUID                             .
+    // .                        (variable length)                      .
+    // .                                                               .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ClientId(Duid),
+    // Server Identifier Option
+    //
+    // The Server Identifier option is used to carry a DUID identifying a
+    // server between a client and a server.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |        OPTION_SERVERID        |          option-len           |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                              DUID                             .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ServerId(Duid),
+    // Identity Association for Non-temporary Addresses Option
+    //
+    // The Identity Association for Non-temporary Addresses (IA_NA) option
+    // is used to carry an IA_NA, the parameters associated with the IA_NA,
+    // and the non-temporary addresses associated with the IA_NA.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |          OPTION_IA_NA         |          option-len           |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                        IAID (4 octets)                        |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                              T1                               |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                              T2                               |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                         IA_NA-options                         .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    IaNa(IaNa),
+    // IA Address Option
+    //
+    // The IA Address option is used to specify an address associated with
+    // an IA_NA or an IA_TA. The IA Address option must be encapsulated in
+    // the IA_NA-options field of an IA_NA option.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |          OPTION_IAADDR        |          option-len           |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                                                               |
+    // |                         IPv6-address                          |
+    // |                                                               |
+    // |                                                               |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                      preferred-lifetime                       |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                        valid-lifetime                         |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                        IAaddr-options                         .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    IaAddr(IaAddr),
+    // Option Request Option
+    //
+    // The Option Request option is used to identify a list of options in a
+    // message between a client and a server.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |           OPTION_ORO          |           option-len          |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |    requested-option-code-1    |    requested-option-code-2    |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                              ...                              |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    OptionRequest(Vec<u16>),
+    // Preference Option
+    //
+    // The Preference option is sent by a server to a client to control the
+    // selection of a server by the client.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |       OPTION_PREFERENCE       |          option-len           |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |  pref-value   |
+    // +-+-+-+-+-+-+-+-+
+    Preference(u8),
+    // Elapsed Time Option
+    //
+    // A client MUST include an Elapsed Time option in messages to indicate
+    // how long the client has been trying to complete a DHCP message
+    // exchange. The elapsed time is expressed in hundredths of a second.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |      OPTION_ELAPSED_TIME      |           option-len          |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |          elapsed-time         |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ElapsedTime(u16),
+    // Relay Message Option
+    //
+    // The Relay Message option carries a DHCP message in a Relay-forward or
+    // Relay-reply message.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |        OPTION_RELAY_MSG       |           option-len          |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                       DHCP-relay-message                      .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    RelayMessage(Vec<u8>),
+    // Status Code Option
+    //
+    // This option returns a status indication related to the DHCP message
+    // or option in which it appears. The status-message is a UTF-8 encoded
+    // text string suitable for display to an end user.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |       OPTION_STATUS_CODE      |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |          status-code          |                               |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               |
+    // .                        status-message                         .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    StatusCode(StatusCode),
+    // Rapid Commit Option
+    //
+    // The Rapid Commit option is used to signal the use of the two-message
+    // exchange for address assignment.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |      OPTION_RAPID_COMMIT      |           option-len          |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    RapidCommit,
+    // Interface-Id Option
+    //
+    // The relay agent MAY send the Interface-Id option to identify the
+    // interface on which the client message was received. If a relay agent
+    // receives a Relay-reply message with an Interface-Id option, the relay
+    // agent relays the message to the client through the interface
+    // identified by the option.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |      OPTION_INTERFACE_ID      |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                         interface-id                          .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    InterfaceId(Vec<u8>),
+    // Relay Agent Remote-ID Option
+    //
+    // This option may be added by DHCPv6 relay agents that terminate
+    // switched or permanent circuits and have mechanisms to identify the
+    // remote host end of the circuit (RFC 4649).
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |       OPTION_REMOTE_ID        |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                       enterprise-number                       |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                          remote-id                            .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    RemoteId(RemoteId),
+    // DNS Recursive Name Server Option
+    //
+    // The DNS Recursive Name Server option provides a list of one or more
+    // IPv6 addresses of DNS recursive name servers to which a client's DNS
+    // resolver MAY send DNS queries (RFC 3646).
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |      OPTION_DNS_SERVERS       |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |            DNS-recursive-name-server (IPv6 address)           |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                              ...                              |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    DnsServers(Vec<Ipv6Addr>),
+    // Domain Search List Option
+    //
+    // The Domain Search List option specifies the domain search list the
+    // client is to use when resolving hostnames with DNS. The list is
+    // encoded as uncompressed DNS names (RFC 3646).
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |      OPTION_DOMAIN_LIST       |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                          searchlist                           |
+    // |                              ...                              |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    DomainList(Vec<String>),
+    // NTP Server Option
+    //
+    // This option serves as a container for server location information
+    // related to one NTP server or Simple Network Time Protocol (SNTP)
+    // server, carried as server address, multicast address or server FQDN
+    // sub-options (RFC 5908).
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |      OPTION_NTP_SERVER        |          option-len           |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                         suboption-1                           |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                            ...                                .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    NtpServer(Vec<NtpServer>),
+    // Authentication Option
+    //
+    // The Authentication option carries authentication information to
+    // authenticate the identity and contents of DHCP messages, using the
+    // same fields as the DHCPv4 authentication option.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |          OPTION_AUTH          |          option-len           |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |   protocol    |   algorithm   |      RDM      |               |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+               |
+    // |                                                               |
+    // |          replay detection (64 bits)           +-+-+-+-+-+-+-+-+
+    // |                                               |               |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+               |
+    // .                   authentication information                  .
+    // .                       (variable length)                       .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    Authentication(Authentication),
+    // Reconfigure Message Option
+    //
+    // A server includes a Reconfigure Message option in a Reconfigure
+    // message to indicate to the client whether the client responds with a
+    // Renew message, a Rebind message, or an Information-request message.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |      OPTION_RECONF_MSG        |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |    msg-type   |
+    // +-+-+-+-+-+-+-+-+
+    ReconfigureMessage(MessageType),
+    // Reconfigure Accept Option
+    //
+    // A client uses the Reconfigure Accept option to announce to the server
+    // whether the client is willing to accept Reconfigure messages, and a
+    // server uses this option to tell the client whether or not to accept
+    // Reconfigure messages.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |     OPTION_RECONF_ACCEPT      |               0               |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ReconfigureAccept,
+    // Client FQDN Option
+    //
+    // The Client FQDN option is used by clients and servers to exchange
+    // information about the client's fully qualified domain name and about
+    // who has the responsibility for updating DNS with the associated
+    // AAAA and PTR RRs (RFC 4704).
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |          OPTION_FQDN          |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |   flags       |                                               |
+    // +-+-+-+-+-+-+-+-+                                               |
+    // .                                                               .
+    // .                          domain-name                          .
+    // .                                                               .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ClientFqdn(ClientFqdn),
+    // SOL_MAX_RT Option
+    //
+    // A DHCPv6 server sends the SOL_MAX_RT option to a client to override
+    // the default value of SOL_MAX_RT, in seconds. The value is valid
+    // between 60 and 86400.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |       option-code             |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                       SOL_MAX_RT value                        |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    SolMaxRt(u32),
+    // INF_MAX_RT Option
+    //
+    // A DHCPv6 server sends the INF_MAX_RT option to a client to override
+    // the default value of INF_MAX_RT, in seconds. The value is valid
+    // between 60 and 86400.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |       option-code             |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                       INF_MAX_RT value                        |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    InfMaxRt(u32),
+    // Query Option
+    //
+    // The Query option is used only in a LEASEQUERY message and identifies
+    // the query being performed (RFC 5007, RFC 5460).
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |         OPTION_LQ_QUERY       |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |   query-type  |                                               |
+    // +-+-+-+-+-+-+-+-+                                               |
+    // |                                                               |
+    // |                         link-address                          |
+    // |                                                               |
+    // |               +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |               |                                               .
+    // +-+-+-+-+-+-+-+-+                                               .
+    // .                         query-options                         .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    LqQuery(LqQuery),
+    // Client Data Option
+    //
+    // The Client Data option is used to encapsulate the data for a single
+    // client on a single link in a LEASEQUERY-REPLY or LEASEQUERY-DATA
+    // message.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |        OPTION_CLIENT_DATA     |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                        client-options                         .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ClientData(Vec<Dhcpv6Option>),
+    // Client Last Transaction Time Option
+    //
+    // The client last transaction time is the number of seconds since the
+    // server last communicated with the client, on that link.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |         OPTION_CLT_TIME       |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                 client-last-transaction-time                  |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    CltTime(u32),
+    // Relay Data Option
+    //
+    // The Relay Data option is used only in a LEASEQUERY-REPLY or
+    // LEASEQUERY-DATA message and provides the relay agent information
+    // used when the client last communicated with the server.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |      OPTION_LQ_RELAY_DATA     |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                                                               |
+    // |                peer-address (IPv6 address)                    |
+    // |                                                               |
+    // |                                                               |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                                                               |
+    // |                       DHCP-relay-message                      |
+    // .                                                               .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    LqRelayData(LqRelayData),
+    // Client Link Option
+    //
+    // The Client Link option is used only in a LEASEQUERY-REPLY message and
+    // identifies the links on which the client has one or more bindings.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |     OPTION_LQ_CLIENT_LINK     |         option-len            |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                                                               |
+    // |                 link-address (IPv6 address)                   |
+    // |                              ...                              |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    LqClientLink(Vec<Ipv6Addr>),
+    // Relay-ID Option
+    //
+    // The Relay-ID option carries a DUID identifying a relay agent, and is
+    // used to query the bindings of the clients behind it (RFC 5460).
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |       OPTION_RELAY_ID         |          option-len           |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                              DUID                             .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    RelayId(Duid),
+    // AFTR-Name Option
+    //
+    // The AFTR-Name option carries the fully qualified domain name of the
+    // Address Family Transition Router a DS-Lite client tunnels its IPv4
+    // traffic to (RFC 6334).
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |    OPTION_AFTR_NAME: 64       |          option-len           |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                                                               |
+    // |                  tunnel-endpoint-name (FQDN)                  |
+    // |                                                               |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    AftrName(String),
+    // S46 Rule Option
+    //
+    // The S46 Rule option conveys a Basic Mapping Rule or a Forwarding
+    // Mapping Rule of a MAP-E or MAP-T domain (RFC 7598).
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |          OPTION_S46_RULE      |         option-length         |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |     flags     |     ea-len    |  prefix4-len  | ipv4-prefix   |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                  (continued)                  |  prefix6-len  |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                           ipv6-prefix                         |
+    // |                       (variable length)                       |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                        S46_RULE-options                       .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    S46Rule(S46Rule),
+    // S46 BR Option
+    //
+    // The S46 BR option conveys the IPv6 address of the Border Relay of a
+    // MAP-E or lightweight 4over6 domain.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |         OPTION_S46_BR         |         option-length         |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                       br-ipv6-address                         |
+    // |                                                               |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    S46Br(Ipv6Addr),
+    // S46 DMR Option
+    //
+    // The S46 DMR option conveys the Default Mapping Rule prefix of a
+    // MAP-T domain.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |         OPTION_S46_DMR        |         option-length         |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |dmr-prefix6-len|            dmr-ipv6-prefix                    |
+    // +-+-+-+-+-+-+-+-+           (variable length)                   |
+    // .                                                               .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    S46Dmr(Ipv6Prefix),
+    // S46 IPv4/IPv6 Address Binding Option
+    //
+    // The S46 IPv4/IPv6 Address Binding option conveys the full or shared
+    // IPv4 address of a lightweight 4over6 client, and the IPv6 prefix
+    // used for its tunnel endpoint.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |      OPTION_S46_V4V6BIND      |         option-length         |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |                         ipv4-address                          |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |bindprefix6-len|             bind-ipv6-prefix                  |
+    // +-+-+-+-+-+-+-+-+             (variable length)                 |
+    // .                                                               .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .                      S46_V4V6BIND-options                     .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    S46V4V6Bind(S46V4V6Bind),
+    // S46 Port Parameters Option
+    //
+    // The S46 Port Parameters option specifies the port set a client
+    // sharing an IPv4 address may use.
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |      OPTION_S46_PORTPARAMS    |         option-length         |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |   offset      |    PSID-len   |              PSID             |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    S46PortParams(S46PortParams),
+    // S46 MAP-E, MAP-T and Lightweight 4over6 Container Options
+    //
+    // The container options group the S46 options of a softwire
+    // mechanism (codes 94, 95 and 96 respectively).
+    //
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // |      OPTION_S46_CONT_MAPE     |         option-length         |
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    // .            encapsulated-options (variable length)             .
+    // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    S46ContMapE(Vec<Dhcpv6Option>),
+    S46ContMapT(Vec<Dhcpv6Option>),
+    S46ContLw(Vec<Dhcpv6Option>),
+    // Any option this crate does not decode, kept as raw bytes.
+    Unknown(u16, Vec<u8>),
+}
+
+impl Dhcpv6Option {
+    /// Retrieve the option code.
+    pub fn code(&self) -> u16 {
+        match self {
+            Dhcpv6Option::ClientId(_) => 1,
+            Dhcpv6Option::ServerId(_) => 2,
+            Dhcpv6Option::IaNa(_) => 3,
+            Dhcpv6Option::IaAddr(_) => 5,
+            Dhcpv6Option::OptionRequest(_) => 6,
+            Dhcpv6Option::Preference(_) => 7,
+            Dhcpv6Option::ElapsedTime(_) => 8,
+            Dhcpv6Option::RelayMessage(_) => 9,
+            Dhcpv6Option::StatusCode(_) => 13,
+            Dhcpv6Option::RapidCommit => 14,
+            Dhcpv6Option::InterfaceId(_) => 18,
+            Dhcpv6Option::RemoteId(_) => 37,
+            Dhcpv6Option::DnsServers(_) => 23,
+            Dhcpv6Option::DomainList(_) => 24,
+            Dhcpv6Option::NtpServer(_) => 56,
+            Dhcpv6Option::Authentication(_) => 11,
+            Dhcpv6Option::ReconfigureMessage(_) => 19,
+            Dhcpv6Option::ReconfigureAccept => 20,
+            Dhcpv6Option::ClientFqdn(_) => 39,
+            Dhcpv6Option::SolMaxRt(_) => 82,
+            Dhcpv6Option::InfMaxRt(_) => 83,
+            Dhcpv6Option::LqQuery(_) => 44,
+            Dhcpv6Option::ClientData(_) => 45,
+            Dhcpv6Option::CltTime(_) => 46,
+            Dhcpv6Option::LqRelayData(_) => 47,
+            Dhcpv6Option::LqClientLink(_) => 48,
+            Dhcpv6Option::RelayId(_) => 53,
+            Dhcpv6Option::AftrName(_) => 64,
+            Dhcpv6Option::S46Rule(_) => 89,
+            Dhcpv6Option::S46Br(_) => 90,
+            Dhcpv6Option::S46Dmr(_) => 91,
+            Dhcpv6Option::S46V4V6Bind(_) => 92,
+            Dhcpv6Option::S46PortParams(_) => 93,
+            Dhcpv6Option::S46ContMapE(_) => 94,
+            Dhcpv6Option::S46ContMapT(_) => 95,
+            Dhcpv6Option::S46ContLw(_) => 96,
+            Dhcpv6Option::Unknown(code, _) => *code,
+        }
+    }
+
+    pub fn serialize(&self) -> Result<Vec<u8>, DhcpError> {
+        let value = match self {
+            Dhcpv6Option::ClientId(duid) => duid.serialize(),
+            Dhcpv6Option::ServerId(duid) => duid.serialize(),
+            Dhcpv6Option::IaNa(ia_na) => {
+                let mut value = Vec::new();
+                value.extend_from_slice(&ia_na.iaid.to_be_bytes());
+                value.extend_from_slice(&ia_na.t1.to_be_bytes());
+                value.extend_from_slice(&ia_na.t2.to_be_bytes());
+                for option in &ia_na.options {
+                    value.extend_from_slice(&option.serialize()?);
+                }
+                value
+            }
+            Dhcpv6Option::IaAddr(ia_addr) => {
+                let mut value = Vec::new();
+                value.extend_from_slice(&ia_addr.address.octets());
+                value.extend_from_slice(&ia_addr.preferred_lifetime.to_be_bytes());
+                value.extend_from_slice(&ia_addr.valid_lifetime.to_be_bytes());
+                for option in &ia_addr.options {
+                    value.extend_from_slice(&option.serialize()?);
+                }
+                value
+            }
+            Dhcpv6Option::OptionRequest(codes) => codes
+                .iter()
+                .flat_map(|code| code.to_be_bytes())
+                .collect::<Vec<u8>>(),
+            Dhcpv6Option::Preference(preference) => vec![*preference],
+            Dhcpv6Option::ElapsedTime(elapsed_time) => elapsed_time.to_be_bytes().to_vec(),
+            Dhcpv6Option::RelayMessage(message) => message.clone(),
+            Dhcpv6Option::StatusCode(status_code) => {
+                let mut value = u16::from(status_code.status).to_be_bytes().to_vec();
+                value.extend_from_slice(status_code.message.as_bytes());
+                value
+            }
+            Dhcpv6Option::RapidCommit => Vec::new(),
+            Dhcpv6Option::InterfaceId(interface_id) => interface_id.clone(),
+            Dhcpv6Option::RemoteId(remote_id) => {
+                let mut value = remote_id.enterprise_number.to_be_bytes().to_vec();
+                value.extend_from_slice(&remote_id.remote_id);
+                value
+            }
+            Dhcpv6Option::DnsServers(servers) => servers
+                .iter()
+                .flat_map(|server| server.octets())
+                .collect::<Vec<u8>>(),
+            Dhcpv6Option::DomainList(names) => dns::encode_names(names)?,
+            Dhcpv6Option::NtpServer(servers) => {
+                let mut value = Vec::new();
+                for server in servers {
+                    let (code, suboption) = match server {
+                        NtpServer::Address(address) => (1u16, address.octets().to_vec()),
+                        NtpServer::Multicast(address) => (2u16, address.octets().to_vec()),
+                        NtpServer::Fqdn(name) => {
+                            let mut suboption = Vec::new();
+                            dns::encode_name(name, &mut suboption)?;
+                            (3u16, suboption)
+                        }
+                    };
+                    value.extend_from_slice(&code.to_be_bytes());
+                    value.extend_from_slice(&(suboption.len() as u16).to_be_bytes());
+                    value.extend_from_slice(&suboption);
+                }
+                value
+            }
+            Dhcpv6Option::Authentication(authentication) => {
+                let mut value = vec![
+                    authentication.protocol,
+                    authentication.algorithm,
+                    authentication.rdm,
+                ];
+                value.extend_from_slice(&authentication.replay_detection.to_be_bytes());
+                value.extend_from_slice(&authentication.information);
+                value
+            }
+            Dhcpv6Option::ReconfigureMessage(message_type) => vec![u8::from(*message_type)],
+            Dhcpv6Option::ReconfigureAccept => Vec::new(),
+            Dhcpv6Option::ClientFqdn(client_fqdn) => {
+                let mut value = vec![client_fqdn.flags()];
+                if !client_fqdn.name.is_empty() {
+                    dns::encode_name(&client_fqdn.name, &mut value)?;
+                    // Partial names are not terminated by the root label.
+                    if client_fqdn.partial {
+                        value.pop();
+                    }
+                }
+                value
+            }
+            Dhcpv6Option::SolMaxRt(value) => value.to_be_bytes().to_vec(),
+            Dhcpv6Option::InfMaxRt(value) => value.to_be_bytes().to_vec(),
+            Dhcpv6Option::LqQuery(lq_query) => {
+                let mut value = vec![u8::from(lq_query.query_type)];
+                value.extend_from_slice(&lq_query.link_address.octets());
+                for option in &lq_query.options {
+                    value.extend_from_slice(&option.serialize()?);
+                }
+                value
+            }
+            Dhcpv6Option::ClientData(options) => options
+                .iter()
+                .map(|option| option.serialize())
+                .collect::<Result<Vec<Vec<u8>>, DhcpError>>()?
+                .concat(),
+            Dhcpv6Option::CltTime(time) => time.to_be_bytes().to_vec(),
+            Dhcpv6Option::LqRelayData(relay_data) => {
+                let mut value = relay_data.peer_address.octets().to_vec();
+                value.extend_from_slice(&relay_data.relay_message);
+                value
+            }
+            Dhcpv6Option::LqClientLink(links) => links
+                .iter()
+                .flat_map(|link| link.octets())
+                .collect::<Vec<u8>>(),
+            Dhcpv6Option::RelayId(duid) => duid.serialize(),
+            Dhcpv6Option::AftrName(name) => {
+                let mut value = Vec::new();
+                dns::encode_name(name, &mut value)?;
+                value
+            }
+            Dhcpv6Option::S46Rule(rule) => {
+                let mut value = vec![rule.flags, rule.ea_len, rule.prefix4_len];
+                value.extend_from_slice(&rule.ipv4_prefix.octets());
+                value.extend_from_slice(&rule.ipv6_prefix.serialize());
+                for option in &rule.options {
+                    value.extend_from_slice(&option.serialize()?);
+                }
+                value
+            }
+            Dhcpv6Option::S46Br(address) => address.octets().to_vec(),
+            Dhcpv6Option::S46Dmr(prefix) => prefix.serialize(),
+            Dhcpv6Option::S46V4V6Bind(bind) => {
+                let mut value = bind.ipv4_address.octets().to_vec();
+                value.extend_from_slice(&bind.ipv6_prefix.serialize());
+                for option in &bind.options {
+                    value.extend_from_slice(&option.serialize()?);
+                }
+                value
+            }
+            Dhcpv6Option::S46PortParams(port_params) => {
+                let mut value = vec![port_params.offset, port_params.psid_len];
+                value.extend_from_slice(&port_params.psid.to_be_bytes());
+                value
+            }
+            Dhcpv6Option::S46ContMapE(options)
+            | Dhcpv6Option::S46ContMapT(options)
+            | Dhcpv6Option::S46ContLw(options) => options
+                .iter()
+                .map(|option| option.serialize())
+                .collect::<Result<Vec<Vec<u8>>, DhcpError>>()?
+                .concat(),
+            Dhcpv6Option::Unknown(_, data) => data.clone(),
+        };
+
+        let mut result = Vec::with_capacity(4 + value.len());
+        result.extend_from_slice(&self.code().to_be_bytes());
+        result.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        result.extend_from_slice(&value);
+        Ok(result)
+    }
+
+    pub fn deserialize(data: &[u8]) -> Result<(Dhcpv6Option, &[u8]), DhcpError> {
+        // Retrieve the option code and length.
+        if data.len() < 4 {
+            return Err(DhcpError::ParsingError(
+                "Could not parse DHCPv6 option header".into(),
+            ));
+        }
+        let code = u16::from_be_bytes([data[0], data[1]]);
+        let len = u16::from_be_bytes([data[2], data[3]]) as usize;
+        let data = &data[4..];
+
+        // Verify that the length is possible.
+        if data.len() < len {
+            return Err(DhcpError::ParsingError(
+                format!("Could not parse DHCPv6 option {}", code).into(),
+            ));
+        }
+        let (value, data) = data.split_at(len);
+
+        let option = match code {
+            1 => Dhcpv6Option::ClientId(Duid::deserialize(value)?),
+            2 => Dhcpv6Option::ServerId(Duid::deserialize(value)?),
+            3 => {
+                if len < 12 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 IA_NA".into(),
+                    ));
+                }
+                Dhcpv6Option::IaNa(IaNa {
+                    iaid: u32::from_be_bytes([value[0], value[1], value[2], value[3]]),
+                    t1: u32::from_be_bytes([value[4], value[5], value[6], value[7]]),
+                    t2: u32::from_be_bytes([value[8], value[9], value[10], value[11]]),
+                    options: Dhcpv6Option::deserialize_all(&value[12..])?,
+                })
+            }
+            5 => {
+                if len < 24 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 IA address".into(),
+                    ));
+                }
+                let mut address = [0; 16];
+                address.copy_from_slice(&value[..16]);
+                Dhcpv6Option::IaAddr(IaAddr {
+                    address: Ipv6Addr::from(address),
+                    preferred_lifetime: u32::from_be_bytes([
+                        value[16], value[17], value[18], value[19],
+                    ]),
+                    valid_lifetime: u32::from_be_bytes([
+                        value[20], value[21], value[22], value[23],
+                    ]),
+                    options: Dhcpv6Option::deserialize_all(&value[24..])?,
+                })
+            }
+            6 => {
+                if !len.is_multiple_of(2) {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 option request".into(),
+                    ));
+                }
+                Dhcpv6Option::OptionRequest(
+                    value
+                        .chunks_exact(2)
+                        .map(|code| u16::from_be_bytes([code[0], code[1]]))
+                        .collect::<Vec<u16>>(),
+                )
+            }
+            7 => {
+                if len != 1 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 preference".into(),
+                    ));
+                }
+                Dhcpv6Option::Preference(value[0])
+            }
+            8 => {
+                if len != 2 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 elapsed time".into(),
+                    ));
+                }
+                Dhcpv6Option::ElapsedTime(u16::from_be_bytes([value[0], value[1]]))
+            }
+            9 => Dhcpv6Option::RelayMessage(value.to_vec()),
+            13 => {
+                if len < 2 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 status code".into(),
+                    ));
+                }
+                let message = match std::str::from_utf8(&value[2..]) {
+                    Ok(message) => message.to_string(),
+                    Err(_) => {
+                        return Err(DhcpError::ParsingError(
+                            "Could not parse DHCPv6 status message".into(),
+                        ))
+                    }
+                };
+                Dhcpv6Option::StatusCode(StatusCode {
+                    status: Status::from(u16::from_be_bytes([value[0], value[1]])),
+                    message,
+                })
+            }
+            14 => {
+                if len != 0 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 rapid commit".into(),
+                    ));
+                }
+                Dhcpv6Option::RapidCommit
+            }
+            18 => Dhcpv6Option::InterfaceId(value.to_vec()),
+            37 => {
+                if len < 5 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 remote ID".into(),
+                    ));
+                }
+                Dhcpv6Option::RemoteId(RemoteId {
+                    enterprise_number: u32::from_be_bytes([value[0], value[1], value[2], value[3]]),
+                    remote_id: value[4..].to_vec(),
+                })
+            }
+            23 => {
+                if !len.is_multiple_of(16) {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 DNS servers".into(),
+                    ));
+                }
+                Dhcpv6Option::DnsServers(ipv6_addresses(value))
+            }
+            24 => Dhcpv6Option::DomainList(dns::decode_names(value)?),
+            56 => {
+                let mut servers = Vec::new();
+                let mut suboptions = value;
+                while !suboptions.is_empty() {
+                    if suboptions.len() < 4 {
+                        return Err(DhcpError::ParsingError(
+                            "Could not parse DHCPv6 NTP server".into(),
+                        ));
+                    }
+                    let code = u16::from_be_bytes([suboptions[0], suboptions[1]]);
+                    let len = u16::from_be_bytes([suboptions[2], suboptions[3]]) as usize;
+                    if suboptions.len() < 4 + len {
+                        return Err(DhcpError::ParsingError(
+                            "Could not parse DHCPv6 NTP server".into(),
+                        ));
+                    }
+                    let suboption = &suboptions[4..4 + len];
+                    servers.push(match (code, len) {
+                        (1, 16) => NtpServer::Address(ipv6_addresses(suboption)[0]),
+                        (2, 16) => NtpServer::Multicast(ipv6_addresses(suboption)[0]),
+                        (3, _) => NtpServer::Fqdn(dns::decode_name(suboption, 0)?.0),
+                        _ => {
+                            return Err(DhcpError::ParsingError(
+                                "Could not parse DHCPv6 NTP server".into(),
+                            ))
+                        }
+                    });
+                    suboptions = &suboptions[4 + len..];
+                }
+                Dhcpv6Option::NtpServer(servers)
+            }
+            11 => {
+                if len < 11 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 authentication".into(),
+                    ));
+                }
+                let mut replay_detection = [0; 8];
+                replay_detection.copy_from_slice(&value[3..11]);
+                Dhcpv6Option::Authentication(Authentication {
+                    protocol: value[0],
+                    algorithm: value[1],
+                    rdm: value[2],
+                    replay_detection: u64::from_be_bytes(replay_detection),
+                    information: value[11..].to_vec(),
+                })
+            }
+            19 => {
+                if len != 1 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 reconfigure message".into(),
+                    ));
+                }
+                Dhcpv6Option::ReconfigureMessage(MessageType::try_from(value[0])?)
+            }
+            20 => {
+                if len != 0 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 reconfigure accept".into(),
+                    ));
+                }
+                Dhcpv6Option::ReconfigureAccept
+            }
+            39 => {
+                if len < 1 || value[0] & !0x07 != 0 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 client FQDN".into(),
+                    ));
+                }
+                let (name, partial) = match value[1..].last() {
+                    None => (String::new(), true),
+                    Some(0) => (dns::decode_name(&value[1..], 0)?.0, false),
+                    Some(_) => {
+                        let mut name = value[1..].to_vec();
+                        name.push(0);
+                        (dns::decode_name(&name, 0)?.0, true)
+                    }
+                };
+                Dhcpv6Option::ClientFqdn(ClientFqdn {
+                    server_update: value[0] & ClientFqdn::FLAG_S != 0,
+                    server_override: value[0] & ClientFqdn::FLAG_O != 0,
+                    no_update: value[0] & ClientFqdn::FLAG_N != 0,
+                    name,
+                    partial,
+                })
+            }
+            82 | 83 => {
+                if len != 4 {
+                    return Err(DhcpError::ParsingError(
+                        format!("Could not parse DHCPv6 option {}", code).into(),
+                    ));
+                }
+                let value = u32::from_be_bytes([value[0], value[1], value[2], value[3]]);
+                if code == 82 {
+                    Dhcpv6Option::SolMaxRt(value)
+                } else {
+                    Dhcpv6Option::InfMaxRt(value)
+                }
+            }
+            44 => {
+                if len < 17 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 query".into(),
+                    ));
+                }
+                Dhcpv6Option::LqQuery(LqQuery {
+                    query_type: QueryType::from(value[0]),
+                    link_address: ipv6_addresses(&value[1..17])[0],
+                    options: Dhcpv6Option::deserialize_all(&value[17..])?,
+                })
+            }
+            45 => Dhcpv6Option::ClientData(Dhcpv6Option::deserialize_all(value)?),
+            46 => {
+                if len != 4 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 client last transaction time".into(),
+                    ));
+                }
+                Dhcpv6Option::CltTime(u32::from_be_bytes([value[0], value[1], value[2], value[3]]))
+            }
+            47 => {
+                if len < 16 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 relay data".into(),
+                    ));
+                }
+                Dhcpv6Option::LqRelayData(LqRelayData {
+                    peer_address: ipv6_addresses(&value[..16])[0],
+                    relay_message: value[16..].to_vec(),
+                })
+            }
+            48 => {
+                if !len.is_multiple_of(16) {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 client link".into(),
+                    ));
+                }
+                Dhcpv6Option::LqClientLink(ipv6_addresses(value))
+            }
+            53 => Dhcpv6Option::RelayId(Duid::deserialize(value)?),
+            64 => {
+                let (name, next) = dns::decode_name(value, 0)?;
+                if next != len {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 AFTR name".into(),
+                    ));
+                }
+                Dhcpv6Option::AftrName(name)
+            }
+            89 => {
+                if len < 8 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 S46 rule".into(),
+                    ));
+                }
+                let (ipv6_prefix, rest) = Ipv6Prefix::deserialize(&value[7..])?;
+                Dhcpv6Option::S46Rule(S46Rule {
+                    flags: value[0],
+                    ea_len: value[1],
+                    prefix4_len: value[2],
+                    ipv4_prefix: Ipv4Addr::new(value[3], value[4], value[5], value[6]),
+                    ipv6_prefix,
+                    options: Dhcpv6Option::deserialize_all(rest)?,
+                })
+            }
+            90 => {
+                if len != 16 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 S46 BR".into(),
+                    ));
+                }
+                Dhcpv6Option::S46Br(ipv6_addresses(value)[0])
+            }
+            91 => {
+                let (prefix, rest) = Ipv6Prefix::deserialize(value)?;
+                if !rest.is_empty() {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 S46 DMR".into(),
+                    ));
+                }
+                Dhcpv6Option::S46Dmr(prefix)
+            }
+            92 => {
+                if len < 5 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 S46 IPv4/IPv6 binding".into(),
+                    ));
+                }
+                let (ipv6_prefix, rest) = Ipv6Prefix::deserialize(&value[4..])?;
+                Dhcpv6Option::S46V4V6Bind(S46V4V6Bind {
+                    ipv4_address: Ipv4Addr::new(value[0], value[1], value[2], value[3]),
+                    ipv6_prefix,
+                    options: Dhcpv6Option::deserialize_all(rest)?,
+                })
+            }
+            93 => {
+                if len != 4 {
+                    return Err(DhcpError::ParsingError(
+                        "Could not parse DHCPv6 S46 port parameters".into(),
+                    ));
+                }
+                Dhcpv6Option::S46PortParams(S46PortParams {
+                    offset: value[0],
+                    psid_len: value[1],
+                    psid: u16::from_be_bytes([value[2], value[3]]),
+                })
+            }
+            94 => Dhcpv6Option::S46ContMapE(Dhcpv6Option::deserialize_all(value)?),
+            95 => Dhcpv6Option::S46ContMapT(Dhcpv6Option::deserialize_all(value)?),
+            96 => Dhcpv6Option::S46ContLw(Dhcpv6Option::deserialize_all(value)?),
+            _ => Dhcpv6Option::Unknown(code, value.to_vec()),
+        };
+
+        Ok((option, data))
+    }
+
+    /// Deserialize every option of an options field.
+    pub fn deserialize_all(mut data: &[u8]) -> Result<Vec<Dhcpv6Option>, DhcpError> {
+        let mut options = Vec::new();
+        while !data.is_empty() {
+            let (option, rest) = Dhcpv6Option::deserialize(data)?;
+            options.push(option);
+            data = rest;
+        }
+        Ok(options)
+    }
+
+    /// Deserialize every option of an options field, skipping the options
+    /// that cannot be parsed and returning their errors along with the
+    /// other options. An option whose length runs past the end of the data
+    /// ends the field.
+    pub fn deserialize_all_partial(mut data: &[u8]) -> (Vec<Dhcpv6Option>, Vec<DhcpError>) {
+        let mut options = Vec::new();
+        let mut errors = Vec::new();
+        while !data.is_empty() {
+            match Dhcpv6Option::deserialize(data) {
+                Ok((option, rest)) => {
+                    options.push(option);
+                    data = rest;
+                }
+                Err(error) => {
+                    errors.push(error);
+                    let len = match data.get(2..4) {
+                        Some(len) => 4 + u16::from_be_bytes([len[0], len[1]]) as usize,
+                        None => break,
+                    };
+                    match data.get(len..) {
+                        Some(rest) => data = rest,
+                        None => break,
+                    }
+                }
+            }
+        }
+        (options, errors)
+    }
+}
+
+/// The location of an NTP server, as carried in the NTP server option.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NtpServer {
+    Address(Ipv6Addr),
+    Multicast(Ipv6Addr),
+    Fqdn(String),
+}
+
+/// The client's domain name and DNS update preferences, as carried in the
+/// Client FQDN option.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientFqdn {
+    // The "S" flag: the server should perform the AAAA RR update.
+    pub server_update: bool,
+    // The "O" flag: the server has overridden the client's preference.
+    pub server_override: bool,
+    // The "N" flag: the server should not perform any DNS update.
+    pub no_update: bool,
+    pub name: String,
+    // Whether the name is a partial name the server should complete.
+    pub partial: bool,
+}
+
+impl ClientFqdn {
+    pub const FLAG_S: u8 = 0x01;
+    pub const FLAG_O: u8 = 0x02;
+    pub const FLAG_N: u8 = 0x04;
+
+    /// Create an option for a fully qualified name, asking the server to
+    /// perform the DNS updates.
+    pub fn new(name: &str) -> ClientFqdn {
+        ClientFqdn {
+            server_update: true,
+            server_override: false,
+            no_update: false,
+            name: name.trim_end_matches('.').to_string(),
+            partial: false,
+        }
+    }
+
+    /// The flags field of the option.
+    pub fn flags(&self) -> u8 {
+        let mut flags = 0;
+        if self.server_update {
+            flags |= ClientFqdn::FLAG_S;
+        }
+        if self.server_override {
+            flags |= ClientFqdn::FLAG_O;
+        }
+        if self.no_update {
+            flags |= ClientFqdn::FLAG_N;
+        }
+        flags
+    }
+}
+
+/// A leasequery, as carried in the Query option.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LqQuery {
+    pub query_type: QueryType,
+    pub link_address: Ipv6Addr,
+    pub options: Vec<Dhcpv6Option>,
+}
+
+/// The type of a leasequery (RFC 5007, RFC 5460).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryType {
+    ByAddress,
+    ByClientId,
+    ByRelayId,
+    ByLinkAddress,
+    ByRemoteId,
+    Other(u8),
+}
+
+impl From<u8> for QueryType {
+    fn from(value: u8) -> QueryType {
+        match value {
+            1 => QueryType::ByAddress,
+            2 => QueryType::ByClientId,
+            3 => QueryType::ByRelayId,
+            4 => QueryType::ByLinkAddress,
+            5 => QueryType::ByRemoteId,
+            value => QueryType::Other(value),
+        }
+    }
+}
+
+impl From<QueryType> for u8 {
+    fn from(query_type: QueryType) -> u8 {
+        match query_type {
+            QueryType::ByAddress => 1,
+            QueryType::ByClientId => 2,
+            QueryType::ByRelayId => 3,
+            QueryType::ByLinkAddress => 4,
+            QueryType::ByRemoteId => 5,
+            QueryType::Other(value) => value,
+        }
+    }
+}
+
+/// The relay information a client last used, as carried in the Relay Data
+/// option.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LqRelayData {
+    pub peer_address: Ipv6Addr,
+    pub relay_message: Vec<u8>,
+}
+
+/// An IPv6 prefix, carried on the wire as its length in bits followed by
+/// the octets needed to hold it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ipv6Prefix {
+    pub len: u8,
+    pub prefix: Ipv6Addr,
+}
+
+impl Ipv6Prefix {
+    pub fn new(prefix: Ipv6Addr, len: u8) -> Ipv6Prefix {
+        Ipv6Prefix { len, prefix }
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let octets = (self.len as usize).div_ceil(8);
+        let mut value = vec![self.len];
+        value.extend_from_slice(&self.prefix.octets()[..octets]);
+        value
+    }
+
+    /// Deserialize a prefix, returning the data following it.
+    pub fn deserialize(data: &[u8]) -> Result<(Ipv6Prefix, &[u8]), DhcpError> {
+        let (len, data) = match data.split_first() {
+            Some((len, data)) if *len <= 128 => (*len, data),
+            _ => {
+                return Err(DhcpError::ParsingError(
+                    "Could not parse IPv6 prefix".into(),
+                ))
+            }
+        };
+        let octets = (len as usize).div_ceil(8);
+        if data.len() < octets {
+            return Err(DhcpError::ParsingError(
+                "Could not parse IPv6 prefix".into(),
+            ));
+        }
+
+        let mut prefix = [0; 16];
+        prefix[..octets].copy_from_slice(&data[..octets]);
+        Ok((
+            Ipv6Prefix::new(Ipv6Addr::from(prefix), len),
+            &data[octets..],
+        ))
+    }
+}
+
+/// A MAP-E or MAP-T mapping rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct S46Rule {
+    pub flags: u8,
+    // Length of the embedded address bits.
+    pub ea_len: u8,
+    pub prefix4_len: u8,
+    pub ipv4_prefix: Ipv4Addr,
+    pub ipv6_prefix: Ipv6Prefix,
+    pub options: Vec<Dhcpv6Option>,
+}
+
+impl S46Rule {
+    /// The flag marking a Forwarding Mapping Rule.
+    pub const FLAG_FMR: u8 = 0x01;
+
+    pub fn is_forwarding_mapping_rule(&self) -> bool {
+        self.flags & S46Rule::FLAG_FMR != 0
+    }
+}
+
+/// The IPv4 address and IPv6 prefix bound to a lightweight 4over6 client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct S46V4V6Bind {
+    pub ipv4_address: Ipv4Addr,
+    pub ipv6_prefix: Ipv6Prefix,
+    pub options: Vec<Dhcpv6Option>,
+}
+
+/// The port set identifier parameters of a shared IPv4 address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct S46PortParams {
+    pub offset: u8,
+    pub psid_len: u8,
+    pub psid: u16,
+}
+
+/// A relay agent remote ID, qualified by the vendor enterprise number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteId {
+    pub enterprise_number: u32,
+    pub remote_id: Vec<u8>,
+}
+
+/// An Identity Association for Non-temporary Addresses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IaNa {
+    pub iaid: u32,
+    pub t1: u32,
+    pub t2: u32,
+    pub options: Vec<Dhcpv6Option>,
+}
+
+impl IaNa {
+    pub fn new(iaid: u32, t1: u32, t2: u32) -> IaNa {
+        IaNa {
+            iaid,
+            t1,
+            t2,
+            options: Vec::new(),
+        }
+    }
+
+    /// Iterate over the addresses carried in the IA_NA.
+    pub fn addresses(&self) -> impl Iterator<Item = &IaAddr> {
+        self.options.iter().filter_map(|option| match option {
+            Dhcpv6Option::IaAddr(ia_addr) => Some(ia_addr),
+            _ => None,
+        })
+    }
+
+    /// Retrieve the status code sub-option, if any.
+    pub fn status_code(&self) -> Option<&StatusCode> {
+        status_code(&self.options)
+    }
+
+    /// The status of the IA_NA; a missing status code means success.
+    pub fn status(&self) -> Status {
+        self.status_code()
+            .map(|status_code| status_code.status)
+            .unwrap_or(Status::Success)
+    }
+
+    /// Whether T1 and T2 are consistent. A client must discard an IA_NA
+    /// whose T1 is greater than its T2 when both are non-zero.
+    pub fn is_valid(&self) -> bool {
+        self.t1 == 0 || self.t2 == 0 || self.t1 <= self.t2
+    }
+}
+
+/// An address associated with an IA_NA.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IaAddr {
+    pub address: Ipv6Addr,
+    pub preferred_lifetime: u32,
+    pub valid_lifetime: u32,
+    pub options: Vec<Dhcpv6Option>,
+}
+
+impl IaAddr {
+    pub fn new(address: Ipv6Addr, preferred_lifetime: u32, valid_lifetime: u32) -> IaAddr {
+        IaAddr {
+            address,
+            preferred_lifetime,
+            valid_lifetime,
+            options: Vec::new(),
+        }
+    }
+
+    /// Retrieve the status code sub-option, if any.
+    pub fn status_code(&self) -> Option<&StatusCode> {
+        status_code(&self.options)
+    }
+
+    /// The status of the address; a missing status code means success.
+    pub fn status(&self) -> Status {
+        self.status_code()
+            .map(|status_code| status_code.status)
+            .unwrap_or(Status::Success)
+    }
+
+    /// Whether the lifetimes are consistent. A client must discard an
+    /// address whose preferred lifetime is greater than its valid lifetime.
+    pub fn is_valid(&self) -> bool {
+        self.preferred_lifetime <= self.valid_lifetime
+    }
+}
+
+/// A status code and its human readable message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusCode {
+    pub status: Status,
+    pub message: String,
+}
+
+impl StatusCode {
+    pub fn new(status: Status, message: &str) -> StatusCode {
+        StatusCode {
+            status,
+            message: message.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    UnspecFail,
+    NoAddrsAvail,
+    NoBinding,
+    NotOnLink,
+    UseMulticast,
+    NoPrefixAvail,
+    UnknownQueryType,
+    MalformedQuery,
+    NotConfigured,
+    NotAllowed,
+    QueryTerminated,
+    Other(u16),
+}
+
+impl From<u16> for Status {
+    fn from(value: u16) -> Status {
+        match value {
+            0 => Status::Success,
+            1 => Status::UnspecFail,
+            2 => Status::NoAddrsAvail,
+            3 => Status::NoBinding,
+            4 => Status::NotOnLink,
+            5 => Status::UseMulticast,
+            6 => Status::NoPrefixAvail,
+            7 => Status::UnknownQueryType,
+            8 => Status::MalformedQuery,
+            9 => Status::NotConfigured,
+            10 => Status::NotAllowed,
+            11 => Status::QueryTerminated,
+            value => Status::Other(value),
+        }
+    }
+}
+
+impl From<Status> for u16 {
+    fn from(status: Status) -> u16 {
+        match status {
+            Status::Success => 0,
+            Status::UnspecFail => 1,
+            Status::NoAddrsAvail => 2,
+            Status::NoBinding => 3,
+            Status::NotOnLink => 4,
+            Status::UseMulticast => 5,
+            Status::NoPrefixAvail => 6,
+            Status::UnknownQueryType => 7,
+            Status::MalformedQuery => 8,
+            Status::NotConfigured => 9,
+            Status::NotAllowed => 10,
+            Status::QueryTerminated => 11,
+            Status::Other(value) => value,
+        }
+    }
+}
+
+fn status_code(options: &[Dhcpv6Option]) -> Option<&StatusCode> {
+    options.iter().find_map(|option| match option {
+        Dhcpv6Option::StatusCode(status_code) => Some(status_code),
+        _ => None,
+    })
+}
+
+fn ipv6_addresses(data: &[u8]) -> Vec<Ipv6Addr> {
+    data.chunks_exact(16)
+        .map(|address| {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(address);
+            Ipv6Addr::from(octets)
+        })
+        .collect::<Vec<Ipv6Addr>>()
+}
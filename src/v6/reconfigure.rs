@@ -0,0 +1,222 @@
+use crate::auth::{
+    constant_time_eq, hmac_md5, ReplayDetector, ALGORITHM_HMAC_MD5, RDM_MONOTONIC_COUNTER,
+};
+use crate::duid::Duid;
+use crate::error::DhcpError;
+use crate::option::Authentication;
+use crate::v6::message::{Dhcpv6Message, MessageType};
+use crate::v6::option::Dhcpv6Option;
+
+/// The reconfigure key authentication protocol (RFC 8415 section 20.4).
+pub const PROTOCOL_RECONFIGURE_KEY: u8 = 3;
+
+const OPTION_AUTH: u16 = 11;
+const OPTION_RECONF_MSG: u16 = 19;
+const OPTION_RECONF_ACCEPT: u16 = 20;
+
+// Authentication information types.
+const TYPE_KEY: u8 = 1;
+const TYPE_HMAC_MD5: u8 = 2;
+
+const KEY_LEN: usize = 16;
+// Offset of the key or MAC in the authentication option, after the code,
+// length, protocol, algorithm, RDM, replay detection and type fields.
+const VALUE_OFFSET: usize = 4 + 3 + 8 + 1;
+
+/// Check whether a client message announces that the client accepts
+/// Reconfigure messages.
+pub fn accepts_reconfigure(message: &Dhcpv6Message) -> bool {
+    message.get_option(OPTION_RECONF_ACCEPT).is_some()
+}
+
+/// A reconfigure key shared by a server and a client to authenticate
+/// Reconfigure messages.
+///
+/// The server sends it in the Reply to a client that included a Reconfigure
+/// Accept option, and keys the HMAC of later Reconfigure messages with it;
+/// the client keeps it, along with the replay detection values it has
+/// seen, to verify them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconfigureKey {
+    key: [u8; KEY_LEN],
+    replay_detector: ReplayDetector,
+}
+
+impl ReconfigureKey {
+    /// Create a reconfigure key from 16 random bytes.
+    pub fn new(key: [u8; KEY_LEN]) -> ReconfigureKey {
+        ReconfigureKey {
+            key,
+            replay_detector: ReplayDetector::new(),
+        }
+    }
+
+    pub fn key(&self) -> &[u8; KEY_LEN] {
+        &self.key
+    }
+
+    /// Retrieve the reconfigure key sent in a Reply.
+    pub fn from_reply(reply: &Dhcpv6Message) -> Result<ReconfigureKey, DhcpError> {
+        let authentication = match reply.get_option(OPTION_AUTH) {
+            Some(Dhcpv6Option::Authentication(authentication)) => authentication,
+            _ => {
+                return Err(DhcpError::AuthenticationError(
+                    "Missing DHCPv6 authentication option".to_string(),
+                ))
+            }
+        };
+        check_protocol(authentication, TYPE_KEY)?;
+
+        let mut key = ReconfigureKey::new([0; KEY_LEN]);
+        key.key.copy_from_slice(&authentication.information[1..]);
+        key.replay_detector
+            .accept(authentication.replay_detection)?;
+        Ok(key)
+    }
+
+    /// Send the key in a Reply, along with a Reconfigure Accept option.
+    pub fn add_to_reply(&self, reply: &mut Dhcpv6Message, replay_detection: u64) {
+        reply
+            .options
+            .retain(|option| option.code() != OPTION_AUTH && option.code() != OPTION_RECONF_ACCEPT);
+        reply.options.push(Dhcpv6Option::ReconfigureAccept);
+        reply
+            .options
+            .push(Dhcpv6Option::Authentication(authentication(
+                TYPE_KEY,
+                replay_detection,
+                &self.key,
+            )));
+    }
+
+    /// Build a serialized, authenticated Reconfigure message asking the
+    /// client to send a Renew, Rebind or Information-request message.
+    pub fn reconfigure(
+        &self,
+        server_id: Duid,
+        client_id: Duid,
+        reconfigure_type: MessageType,
+        replay_detection: u64,
+    ) -> Result<Vec<u8>, DhcpError> {
+        check_reconfigure_type(reconfigure_type)?;
+
+        let mut message = Dhcpv6Message::new(MessageType::Reconfigure, [0; 3]);
+        message.options = vec![
+            Dhcpv6Option::ServerId(server_id),
+            Dhcpv6Option::ClientId(client_id),
+            Dhcpv6Option::ReconfigureMessage(reconfigure_type),
+            Dhcpv6Option::Authentication(authentication(
+                TYPE_HMAC_MD5,
+                replay_detection,
+                &[0; KEY_LEN],
+            )),
+        ];
+
+        let mut data = message.serialize()?;
+        let start = find_authentication(&data)?;
+        let mac = hmac_md5(&self.key, &data);
+        data[start + VALUE_OFFSET..start + VALUE_OFFSET + KEY_LEN].copy_from_slice(&mac);
+        Ok(data)
+    }
+
+    /// Verify a serialized Reconfigure message received by the client,
+    /// returning it once authenticated.
+    pub fn verify(&mut self, packet: &[u8]) -> Result<Dhcpv6Message, DhcpError> {
+        let message = Dhcpv6Message::deserialize(packet)?;
+        if message.message_type != MessageType::Reconfigure {
+            return Err(DhcpError::AuthenticationError(
+                "Expected a DHCPv6 Reconfigure message".to_string(),
+            ));
+        }
+        match message.get_option(OPTION_RECONF_MSG) {
+            Some(Dhcpv6Option::ReconfigureMessage(reconfigure_type)) => {
+                check_reconfigure_type(*reconfigure_type)?
+            }
+            _ => {
+                return Err(DhcpError::ParsingError(
+                    "Reconfigure message has no Reconfigure Message option".into(),
+                ))
+            }
+        }
+        let authentication = match message.get_option(OPTION_AUTH) {
+            Some(Dhcpv6Option::Authentication(authentication)) => authentication,
+            _ => {
+                return Err(DhcpError::AuthenticationError(
+                    "Missing DHCPv6 authentication option".to_string(),
+                ))
+            }
+        };
+        check_protocol(authentication, TYPE_HMAC_MD5)?;
+
+        // The MAC covers the whole message, with the MAC itself zeroed.
+        let start = find_authentication(packet)?;
+        let mut data = packet.to_vec();
+        data[start + VALUE_OFFSET..start + VALUE_OFFSET + KEY_LEN].fill(0);
+        let mac = hmac_md5(&self.key, &data);
+        if !constant_time_eq(&mac, &authentication.information[1..]) {
+            return Err(DhcpError::AuthenticationError(
+                "Invalid message authentication code".to_string(),
+            ));
+        }
+
+        self.replay_detector
+            .accept(authentication.replay_detection)?;
+        Ok(message)
+    }
+}
+
+fn authentication(information_type: u8, replay_detection: u64, value: &[u8]) -> Authentication {
+    let mut information = vec![information_type];
+    information.extend_from_slice(value);
+    Authentication {
+        protocol: PROTOCOL_RECONFIGURE_KEY,
+        algorithm: ALGORITHM_HMAC_MD5,
+        rdm: RDM_MONOTONIC_COUNTER,
+        replay_detection,
+        information,
+    }
+}
+
+fn check_protocol(authentication: &Authentication, information_type: u8) -> Result<(), DhcpError> {
+    if authentication.protocol != PROTOCOL_RECONFIGURE_KEY
+        || authentication.algorithm != ALGORITHM_HMAC_MD5
+        || authentication.rdm != RDM_MONOTONIC_COUNTER
+    {
+        return Err(DhcpError::AuthenticationError(
+            "Unsupported authentication protocol".to_string(),
+        ));
+    }
+    if authentication.information.len() != 1 + KEY_LEN
+        || authentication.information[0] != information_type
+    {
+        return Err(DhcpError::AuthenticationError(
+            "Could not parse reconfigure key authentication information".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_reconfigure_type(reconfigure_type: MessageType) -> Result<(), DhcpError> {
+    match reconfigure_type {
+        MessageType::Renew | MessageType::Rebind | MessageType::InformationRequest => Ok(()),
+        _ => Err(DhcpError::ParsingError(
+            "Invalid DHCPv6 reconfigure message type".into(),
+        )),
+    }
+}
+
+// Find the offset of the authentication option in a serialized message.
+fn find_authentication(packet: &[u8]) -> Result<usize, DhcpError> {
+    let mut index = 4;
+    while index + 4 <= packet.len() {
+        let code = u16::from_be_bytes([packet[index], packet[index + 1]]);
+        let len = u16::from_be_bytes([packet[index + 2], packet[index + 3]]) as usize;
+        if code == OPTION_AUTH {
+            return Ok(index);
+        }
+        index += 4 + len;
+    }
+    Err(DhcpError::AuthenticationError(
+        "Missing DHCPv6 authentication option".to_string(),
+    ))
+}
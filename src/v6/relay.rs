@@ -0,0 +1,212 @@
+use std::net::Ipv6Addr;
+
+use crate::error::DhcpError;
+use crate::v6::message::{Dhcpv6Message, Dhcpv6Packet, Dhcpv6RelayMessage, MessageType};
+use crate::v6::option::{Dhcpv6Option, RemoteId};
+
+/// The maximum number of relay agents a message may traverse (RFC 8415).
+pub const HOP_COUNT_LIMIT: u8 = 8;
+
+/// A DHCPv6 relay agent, encapsulating client messages in Relay-forward
+/// messages and unwrapping the Relay-reply messages sent back by servers.
+#[derive(Debug, Clone)]
+pub struct Dhcpv6Relay {
+    link_address: Ipv6Addr,
+    interface_id: Option<Vec<u8>>,
+    remote_id: Option<RemoteId>,
+}
+
+impl Dhcpv6Relay {
+    /// Create a relay agent for the link identified by `link_address`.
+    pub fn new(link_address: Ipv6Addr) -> Dhcpv6Relay {
+        Dhcpv6Relay {
+            link_address,
+            interface_id: None,
+            remote_id: None,
+        }
+    }
+
+    /// Include an Interface-Id option in every Relay-forward message.
+    pub fn with_interface_id(mut self, interface_id: Vec<u8>) -> Dhcpv6Relay {
+        self.interface_id = Some(interface_id);
+        self
+    }
+
+    /// Include a Remote-Id option in every Relay-forward message.
+    pub fn with_remote_id(mut self, enterprise_number: u32, remote_id: Vec<u8>) -> Dhcpv6Relay {
+        self.remote_id = Some(RemoteId {
+            enterprise_number,
+            remote_id,
+        });
+        self
+    }
+
+    /// Encapsulate a message received from `peer_address`, which may be a
+    /// client or another relay agent, in a Relay-forward message.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(peer_address = %peer_address), err(level = "debug"))
+    )]
+    pub fn relay_forward(
+        &self,
+        packet: &[u8],
+        peer_address: Ipv6Addr,
+    ) -> Result<Dhcpv6RelayMessage, DhcpError> {
+        let hop_count = match Dhcpv6Packet::deserialize(packet)? {
+            Dhcpv6Packet::Relay(relay) if relay.message_type == MessageType::RelayForw => {
+                if relay.hop_count >= HOP_COUNT_LIMIT {
+                    return Err(DhcpError::ParsingError(
+                        "DHCPv6 relay hop count limit exceeded".into(),
+                    ));
+                }
+                relay.hop_count + 1
+            }
+            Dhcpv6Packet::Relay(_) => {
+                return Err(DhcpError::ParsingError(
+                    "Relay-reply received from the client side".into(),
+                ))
+            }
+            Dhcpv6Packet::Message(_) => 0,
+        };
+
+        let mut options = vec![Dhcpv6Option::RelayMessage(packet.to_vec())];
+        if let Some(interface_id) = &self.interface_id {
+            options.push(Dhcpv6Option::InterfaceId(interface_id.clone()));
+        }
+        if let Some(remote_id) = &self.remote_id {
+            options.push(Dhcpv6Option::RemoteId(remote_id.clone()));
+        }
+
+        Ok(Dhcpv6RelayMessage {
+            message_type: MessageType::RelayForw,
+            hop_count,
+            link_address: self.link_address,
+            peer_address,
+            options,
+        })
+    }
+
+    /// Unwrap a Relay-reply message, returning the message to deliver and
+    /// where to deliver it.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(len = packet.len()), err(level = "debug"))
+    )]
+    pub fn relay_reply(&self, packet: &[u8]) -> Result<RelayedReply, DhcpError> {
+        let relay = Dhcpv6RelayMessage::deserialize(packet)?;
+        if relay.message_type != MessageType::RelayRepl {
+            return Err(DhcpError::ParsingError(
+                "Expected a DHCPv6 Relay-reply message".into(),
+            ));
+        }
+
+        let hop = RelayHop::from_relay_message(&relay);
+        Ok(RelayedReply {
+            peer_address: hop.peer_address,
+            interface_id: hop.interface_id,
+            message: relay_message(&relay)?.to_vec(),
+        })
+    }
+}
+
+/// A message unwrapped from a Relay-reply, ready to be sent to its peer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelayedReply {
+    pub peer_address: Ipv6Addr,
+    pub interface_id: Option<Vec<u8>>,
+    pub message: Vec<u8>,
+}
+
+/// One level of a relay chain, as seen by the server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelayHop {
+    pub hop_count: u8,
+    pub link_address: Ipv6Addr,
+    pub peer_address: Ipv6Addr,
+    pub interface_id: Option<Vec<u8>>,
+    pub remote_id: Option<RemoteId>,
+}
+
+impl RelayHop {
+    fn from_relay_message(relay: &Dhcpv6RelayMessage) -> RelayHop {
+        let mut hop = RelayHop {
+            hop_count: relay.hop_count,
+            link_address: relay.link_address,
+            peer_address: relay.peer_address,
+            interface_id: None,
+            remote_id: None,
+        };
+        for option in &relay.options {
+            match option {
+                Dhcpv6Option::InterfaceId(interface_id) => {
+                    hop.interface_id = Some(interface_id.clone())
+                }
+                Dhcpv6Option::RemoteId(remote_id) => hop.remote_id = Some(remote_id.clone()),
+                _ => {}
+            }
+        }
+        hop
+    }
+}
+
+/// Peel every Relay-forward level off a packet received by a server.
+///
+/// Returns the relay chain, outermost relay first, and the client message.
+pub fn unwrap_relay_forward(packet: &[u8]) -> Result<(Vec<RelayHop>, Dhcpv6Message), DhcpError> {
+    let mut hops = Vec::new();
+    let mut packet = Dhcpv6Packet::deserialize(packet)?;
+
+    loop {
+        match packet {
+            Dhcpv6Packet::Message(message) => return Ok((hops, message)),
+            Dhcpv6Packet::Relay(relay) => {
+                if relay.message_type != MessageType::RelayForw
+                    || hops.len() > HOP_COUNT_LIMIT as usize
+                {
+                    return Err(DhcpError::ParsingError("Invalid DHCPv6 relay chain".into()));
+                }
+                hops.push(RelayHop::from_relay_message(&relay));
+                packet = Dhcpv6Packet::deserialize(relay_message(&relay)?)?;
+            }
+        }
+    }
+}
+
+/// Wrap a server reply in Relay-reply messages mirroring the relay chain
+/// returned by `unwrap_relay_forward`, echoing each Interface-Id option.
+/// Fails if the reply cannot be serialized.
+pub fn wrap_relay_reply(
+    hops: &[RelayHop],
+    reply: &Dhcpv6Message,
+) -> Result<Dhcpv6Packet, DhcpError> {
+    let mut packet = Dhcpv6Packet::Message(reply.clone());
+
+    for hop in hops.iter().rev() {
+        let mut options = vec![Dhcpv6Option::RelayMessage(packet.serialize()?)];
+        if let Some(interface_id) = &hop.interface_id {
+            options.push(Dhcpv6Option::InterfaceId(interface_id.clone()));
+        }
+        packet = Dhcpv6Packet::Relay(Dhcpv6RelayMessage {
+            message_type: MessageType::RelayRepl,
+            hop_count: hop.hop_count,
+            link_address: hop.link_address,
+            peer_address: hop.peer_address,
+            options,
+        });
+    }
+
+    Ok(packet)
+}
+
+fn relay_message(relay: &Dhcpv6RelayMessage) -> Result<&[u8], DhcpError> {
+    relay
+        .options
+        .iter()
+        .find_map(|option| match option {
+            Dhcpv6Option::RelayMessage(message) => Some(message.as_slice()),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            DhcpError::ParsingError("DHCPv6 relay message has no Relay Message option".into())
+        })
+}
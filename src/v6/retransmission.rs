@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use crate::error::DhcpError;
+use crate::v6::message::MessageType;
+use crate::v6::option::Dhcpv6Option;
+
+/// The range of valid SOL_MAX_RT and INF_MAX_RT values, in seconds.
+pub const MAX_RT_RANGE: std::ops::RangeInclusive<u32> = 60..=86400;
+
+/// The retransmission state of a client message exchange, following the
+/// algorithm of RFC 8415 section 15.
+///
+/// Each timeout is randomized by up to 10% from a caller-provided random
+/// value, so the timer logic stays independent of any random generator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Retransmission {
+    message_type: MessageType,
+    // Initial retransmission time.
+    irt: Duration,
+    // Maximum retransmission time, zero meaning no limit.
+    mrt: Duration,
+    // Maximum retransmission count, zero meaning no limit.
+    mrc: u32,
+    // Maximum retransmission duration, zero meaning no limit.
+    mrd: Duration,
+    rt: Option<Duration>,
+    count: u32,
+    elapsed: Duration,
+}
+
+impl Retransmission {
+    /// Create the retransmission state for a message sent by a client,
+    /// using the default parameters of RFC 8415 section 7.6.
+    pub fn new(message_type: MessageType) -> Result<Retransmission, DhcpError> {
+        let (irt, mrt, mrc, mrd) = match message_type {
+            MessageType::Solicit => (1, 3600, 0, 0),
+            MessageType::Request => (1, 30, 10, 0),
+            MessageType::Confirm => (1, 4, 0, 10),
+            MessageType::Renew | MessageType::Rebind => (10, 600, 0, 0),
+            MessageType::InformationRequest => (1, 3600, 0, 0),
+            MessageType::Release => (1, 0, 4, 0),
+            MessageType::Decline => (1, 0, 5, 0),
+            _ => {
+                return Err(DhcpError::ParsingError(
+                    "Not a DHCPv6 client message type".into(),
+                ))
+            }
+        };
+
+        Ok(Retransmission {
+            message_type,
+            irt: Duration::from_secs(irt),
+            mrt: Duration::from_secs(mrt),
+            mrc,
+            mrd: Duration::from_secs(mrd),
+            rt: None,
+            count: 0,
+            elapsed: Duration::ZERO,
+        })
+    }
+
+    /// Limit the total duration of the exchange, as a Renew must end at T2
+    /// and a Rebind when the leases expire.
+    pub fn with_max_duration(mut self, mrd: Duration) -> Retransmission {
+        self.mrd = mrd;
+        self
+    }
+
+    /// The maximum retransmission time currently in use.
+    pub fn max_retransmission_time(&self) -> Duration {
+        self.mrt
+    }
+
+    /// Honor the SOL_MAX_RT or INF_MAX_RT option received from a server,
+    /// for Solicit and Information-request exchanges respectively.
+    ///
+    /// Values outside of `MAX_RT_RANGE` are ignored.
+    pub fn honor_options(&mut self, options: &[Dhcpv6Option]) {
+        for option in options {
+            let value = match (self.message_type, option) {
+                (MessageType::Solicit, Dhcpv6Option::SolMaxRt(value))
+                | (MessageType::InformationRequest, Dhcpv6Option::InfMaxRt(value)) => *value,
+                _ => continue,
+            };
+            if MAX_RT_RANGE.contains(&value) {
+                self.mrt = Duration::from_secs(value as u64);
+            }
+        }
+    }
+
+    /// Compute the time to wait before the next retransmission, or `None`
+    /// once the exchange has failed.
+    ///
+    /// `random` is a value uniformly distributed in [0, 1).
+    pub fn next_timeout(&mut self, random: f64) -> Option<Duration> {
+        if self.mrc != 0 && self.count >= self.mrc {
+            return None;
+        }
+        if !self.mrd.is_zero() && self.elapsed >= self.mrd {
+            return None;
+        }
+
+        // The first Solicit timeout must be greater than IRT.
+        let rand = match (self.rt, self.message_type) {
+            (None, MessageType::Solicit) => random * 0.1,
+            _ => (random * 2.0 - 1.0) * 0.1,
+        };
+
+        let mut rt = match self.rt {
+            None => self.irt.mul_f64(1.0 + rand),
+            Some(rt) => rt.mul_f64(2.0 + rand),
+        };
+        if !self.mrt.is_zero() && rt > self.mrt {
+            rt = self.mrt.mul_f64(1.0 + rand);
+        }
+        if !self.mrd.is_zero() && self.elapsed + rt > self.mrd {
+            rt = self.mrd - self.elapsed;
+        }
+
+        self.rt = Some(rt);
+        self.count += 1;
+        self.elapsed += rt;
+        Some(rt)
+    }
+}
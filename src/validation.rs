@@ -0,0 +1,193 @@
+//! Checking messages against the field and option requirements of RFC
+//! 2131: table 3 (section 4.3.1) for the messages of servers, and table 5
+//! (section 4.4.1) for those of clients.
+//!
+//! Requirements depending on the state of the client, such as the server
+//! identifier of a DHCPREQUEST, are checked as far as the message tells
+//! that state: a DHCPREQUEST with ciaddr set is renewing or rebinding.
+
+use std::fmt;
+
+use crate::message::{DhcpMessage, MessageType, OpCode};
+use crate::option::DhcpOption;
+
+const OPTION_REQUESTED_IP_ADDRESS: u8 = 50;
+const OPTION_IP_ADDRESS_LEASE_TIME: u8 = 51;
+const OPTION_MESSAGE_TYPE: u8 = 53;
+const OPTION_SERVER_IDENTIFIER: u8 = 54;
+const OPTION_PARAMETER_REQUEST_LIST: u8 = 55;
+const OPTION_MESSAGE: u8 = 56;
+const OPTION_MAXIMUM_MESSAGE_SIZE: u8 = 57;
+const OPTION_VENDOR_CLASS_IDENTIFIER: u8 = 60;
+const OPTION_CLIENT_IDENTIFIER: u8 = 61;
+
+/// A requirement of RFC 2131 a message does not meet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    // The op field does not match the message type.
+    WrongOp { op: u8 },
+    // The message type option is missing or holds an unknown type.
+    InvalidMessageType,
+    MissingOption { code: u8 },
+    ForbiddenOption { code: u8 },
+    // A header field left zero where the message type requires an
+    // address.
+    MissingField { field: &'static str },
+    // A header field set where the message type requires zero.
+    NonZeroField { field: &'static str },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::WrongOp { op } => write!(f, "Wrong op {} for the message type", op),
+            Violation::InvalidMessageType => write!(f, "Missing or invalid message type"),
+            Violation::MissingOption { code } => write!(f, "Option {} is required", code),
+            Violation::ForbiddenOption { code } => write!(f, "Option {} is not allowed", code),
+            Violation::MissingField { field } => write!(f, "Field {} is required", field),
+            Violation::NonZeroField { field } => write!(f, "Field {} must be zero", field),
+        }
+    }
+}
+
+impl DhcpMessage {
+    /// Check the message against the requirements of RFC 2131 for its
+    /// type, returning those it does not meet. A message without a valid
+    /// type is only reported as such.
+    pub fn validate(&self) -> Vec<Violation> {
+        let message_type = match self.message_type() {
+            Ok(Some(message_type)) => message_type,
+            _ => return vec![Violation::InvalidMessageType],
+        };
+        let mut violations = Vec::new();
+
+        let op = match message_type {
+            MessageType::Offer | MessageType::Ack | MessageType::Nak => OpCode::BootReply,
+            _ => OpCode::BootRequest,
+        };
+        if self.op != u8::from(op) {
+            violations.push(Violation::WrongOp { op: self.op });
+        }
+
+        let renewing = !self.ciaddr.is_unspecified();
+        let ciaddr = ("ciaddr", self.ciaddr);
+        let yiaddr = ("yiaddr", self.yiaddr);
+        let siaddr = ("siaddr", self.siaddr);
+        let (required_fields, zero_fields) = match message_type {
+            MessageType::Discover | MessageType::Decline => (vec![], vec![ciaddr, yiaddr, siaddr]),
+            MessageType::Request => (vec![], vec![yiaddr, siaddr]),
+            MessageType::Inform | MessageType::Release => (vec![ciaddr], vec![yiaddr, siaddr]),
+            MessageType::Offer => (vec![yiaddr], vec![ciaddr]),
+            MessageType::Ack => (vec![], vec![]),
+            MessageType::Nak => (vec![], vec![ciaddr, yiaddr, siaddr]),
+        };
+        for (field, address) in required_fields {
+            if address.is_unspecified() {
+                violations.push(Violation::MissingField { field });
+            }
+        }
+        for (field, address) in zero_fields {
+            if !address.is_unspecified() {
+                violations.push(Violation::NonZeroField { field });
+            }
+        }
+
+        // An ACK answering a DHCPINFORM assigns no address, and carries no
+        // lease time.
+        let assigned = !self.yiaddr.is_unspecified();
+        let (required, forbidden): (Vec<u8>, Vec<u8>) = match message_type {
+            MessageType::Discover => (vec![], vec![OPTION_SERVER_IDENTIFIER]),
+            MessageType::Request if renewing => (
+                vec![],
+                vec![OPTION_REQUESTED_IP_ADDRESS, OPTION_SERVER_IDENTIFIER],
+            ),
+            MessageType::Request => (vec![OPTION_REQUESTED_IP_ADDRESS], vec![]),
+            MessageType::Inform => (
+                vec![],
+                vec![
+                    OPTION_REQUESTED_IP_ADDRESS,
+                    OPTION_IP_ADDRESS_LEASE_TIME,
+                    OPTION_SERVER_IDENTIFIER,
+                ],
+            ),
+            MessageType::Decline => (
+                vec![OPTION_REQUESTED_IP_ADDRESS, OPTION_SERVER_IDENTIFIER],
+                self.options_except(&[
+                    OPTION_REQUESTED_IP_ADDRESS,
+                    OPTION_SERVER_IDENTIFIER,
+                    OPTION_MESSAGE,
+                    OPTION_CLIENT_IDENTIFIER,
+                ]),
+            ),
+            MessageType::Release => (
+                vec![OPTION_SERVER_IDENTIFIER],
+                self.options_except(&[
+                    OPTION_SERVER_IDENTIFIER,
+                    OPTION_MESSAGE,
+                    OPTION_CLIENT_IDENTIFIER,
+                ]),
+            ),
+            MessageType::Offer => (
+                vec![OPTION_IP_ADDRESS_LEASE_TIME, OPTION_SERVER_IDENTIFIER],
+                vec![
+                    OPTION_REQUESTED_IP_ADDRESS,
+                    OPTION_PARAMETER_REQUEST_LIST,
+                    OPTION_MAXIMUM_MESSAGE_SIZE,
+                ],
+            ),
+            MessageType::Ack if assigned => (
+                vec![OPTION_IP_ADDRESS_LEASE_TIME, OPTION_SERVER_IDENTIFIER],
+                vec![
+                    OPTION_REQUESTED_IP_ADDRESS,
+                    OPTION_PARAMETER_REQUEST_LIST,
+                    OPTION_MAXIMUM_MESSAGE_SIZE,
+                ],
+            ),
+            MessageType::Ack => (
+                vec![OPTION_SERVER_IDENTIFIER],
+                vec![
+                    OPTION_REQUESTED_IP_ADDRESS,
+                    OPTION_IP_ADDRESS_LEASE_TIME,
+                    OPTION_PARAMETER_REQUEST_LIST,
+                    OPTION_MAXIMUM_MESSAGE_SIZE,
+                ],
+            ),
+            MessageType::Nak => (
+                vec![OPTION_SERVER_IDENTIFIER],
+                self.options_except(&[
+                    OPTION_SERVER_IDENTIFIER,
+                    OPTION_MESSAGE,
+                    OPTION_VENDOR_CLASS_IDENTIFIER,
+                    OPTION_CLIENT_IDENTIFIER,
+                ]),
+            ),
+        };
+        for code in required {
+            if self.get_option(code).is_none() {
+                violations.push(Violation::MissingOption { code });
+            }
+        }
+        for code in forbidden {
+            if self.get_option(code).is_some() {
+                violations.push(Violation::ForbiddenOption { code });
+            }
+        }
+        violations
+    }
+
+    // The codes of the options of the message not in `allowed`, once
+    // each, the message type aside.
+    fn options_except(&self, allowed: &[u8]) -> Vec<u8> {
+        let mut codes = Vec::new();
+        for option in &self.options {
+            let code = option.raw_code();
+            let ignored = matches!(option, DhcpOption::Pad | DhcpOption::End)
+                || code == OPTION_MESSAGE_TYPE
+                || allowed.contains(&code);
+            if !ignored && !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+        codes
+    }
+}
@@ -0,0 +1,74 @@
+//! Encapsulated vendor-specific options (RFC 2132 section 8.4).
+//!
+//! The Vendor Specific Information option (43) carries opaque bytes, which
+//! vendors SHOULD encode as sub-options of the same code, length and value
+//! syntax as DHCP options, without magic cookie. Codes 0 and 255 keep their
+//! meaning of Pad and End, the latter ending the sub-options; the others
+//! are defined by each vendor, so values are left undecoded.
+
+use crate::error::{DhcpError, SerializationError};
+use crate::option::DhcpOption;
+
+const OPTION_VENDOR_SPECIFIC_INFORMATION: u8 = 43;
+
+/// A sub-option, as code and undecoded value.
+pub type SubOption = (u8, Vec<u8>);
+
+/// Parse encapsulated sub-options as code and value. Pad sub-options are
+/// skipped, and parsing stops at the End sub-option, or at the end of
+/// `data` without one.
+pub fn parse_sub_options(data: &[u8]) -> Result<Vec<SubOption>, DhcpError> {
+    let mut sub_options = Vec::new();
+    let mut data = data;
+    loop {
+        data = match data {
+            [] | [255, ..] => return Ok(sub_options),
+            [0, rest @ ..] => rest,
+            [code, len, rest @ ..] if rest.len() >= *len as usize => {
+                let (value, rest) = rest.split_at(*len as usize);
+                sub_options.push((*code, value.to_vec()));
+                rest
+            }
+            _ => {
+                return Err(DhcpError::InvalidValue {
+                    code: OPTION_VENDOR_SPECIFIC_INFORMATION,
+                    reason: "truncated encapsulated option",
+                })
+            }
+        };
+    }
+}
+
+/// Serialize sub-options, without End sub-option. Fails with
+/// `SerializationError::ValueTooLong`, giving the sub-option code, if a
+/// value is longer than 255 bytes.
+pub fn serialize_sub_options(sub_options: &[SubOption]) -> Result<Vec<u8>, DhcpError> {
+    let mut result = Vec::new();
+    for (code, value) in sub_options {
+        let len = u8::try_from(value.len()).map_err(|_| {
+            DhcpError::SerializationError(SerializationError::ValueTooLong {
+                code: *code,
+                len: value.len(),
+            })
+        })?;
+        result.extend_from_slice(&[*code, len]);
+        result.extend_from_slice(value);
+    }
+    Ok(result)
+}
+
+impl DhcpOption {
+    /// A Vendor Specific Information option holding `sub_options`.
+    pub fn vendor_specific(sub_options: &[SubOption]) -> Result<DhcpOption, DhcpError> {
+        serialize_sub_options(sub_options).map(DhcpOption::VendorSpecificInformation)
+    }
+
+    /// The sub-options of a Vendor Specific Information option, or None
+    /// for other options.
+    pub fn vendor_sub_options(&self) -> Option<Result<Vec<SubOption>, DhcpError>> {
+        match self {
+            DhcpOption::VendorSpecificInformation(value) => Some(parse_sub_options(value)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Transaction IDs.
+//!
+//! Clients choose the xid of their messages at random (RFC 2131 section
+//! 4.1), so that an off-path attacker cannot guess it and forge replies.
+//! `XidGenerator` draws them from a `RandomSource`: `OsRandom`, the random
+//! generator of the operating system, or any other source, for targets
+//! without one.
+
+use std::fs::File;
+use std::io::Read;
+
+use crate::error::DhcpError;
+use crate::message::{DhcpMessage, OpCode};
+
+/// A cryptographically secure random generator.
+pub trait RandomSource {
+    /// Fill `bytes` with random bytes.
+    fn fill_bytes(&mut self, bytes: &mut [u8]) -> Result<(), DhcpError>;
+}
+
+/// The random generator of the operating system, read from
+/// `/dev/urandom`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsRandom;
+
+impl RandomSource for OsRandom {
+    fn fill_bytes(&mut self, bytes: &mut [u8]) -> Result<(), DhcpError> {
+        File::open("/dev/urandom")?.read_exact(bytes)?;
+        Ok(())
+    }
+}
+
+/// Transaction IDs drawn from a random source.
+#[derive(Debug, Clone, Default)]
+pub struct XidGenerator<R: RandomSource> {
+    source: R,
+}
+
+impl<R: RandomSource> XidGenerator<R> {
+    pub fn new(source: R) -> XidGenerator<R> {
+        XidGenerator { source }
+    }
+
+    /// A new transaction ID.
+    pub fn next_xid(&mut self) -> Result<u32, DhcpError> {
+        let mut bytes = [0; 4];
+        self.source.fill_bytes(&mut bytes)?;
+        Ok(u32::from_be_bytes(bytes))
+    }
+}
+
+/// A transaction ID drawn from the random generator of the operating
+/// system.
+pub fn generate() -> Result<u32, DhcpError> {
+    XidGenerator::new(OsRandom).next_xid()
+}
+
+/// Whether `reply` answers `request`: a BOOTREPLY echoing its xid and
+/// hardware address.
+pub fn is_reply_to(request: &DhcpMessage, reply: &DhcpMessage) -> bool {
+    check_reply(request, reply).is_ok()
+}
+
+/// Check that `reply` answers `request`, see `is_reply_to`, failing with a
+/// `ParsingError` saying why not.
+pub fn check_reply(request: &DhcpMessage, reply: &DhcpMessage) -> Result<(), DhcpError> {
+    if reply.op != u8::from(OpCode::BootReply) {
+        return Err(DhcpError::ParsingError(
+            format!("Reply op is {}", reply.op).into(),
+        ));
+    }
+    if reply.xid != request.xid {
+        return Err(DhcpError::ParsingError(
+            format!(
+                "Reply xid 0x{:08x} does not match 0x{:08x}",
+                reply.xid, request.xid
+            )
+            .into(),
+        ));
+    }
+    let len = request.hlen.min(16) as usize;
+    if reply.hlen != request.hlen || reply.chaddr[..len] != request.chaddr[..len] {
+        return Err(DhcpError::ParsingError(
+            "Reply hardware address does not match".into(),
+        ));
+    }
+    Ok(())
+}
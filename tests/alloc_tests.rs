@@ -0,0 +1,191 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::net::Ipv4Addr;
+
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::message_ref::DhcpMessageRef;
+use dhcp::option::DhcpOption;
+
+// Count the allocations made by the current thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alloc_serialize_once() {
+        let mut reply = DhcpMessage::new(2, 0x1234);
+        reply.yiaddr = Ipv4Addr::new(10, 0, 0, 10);
+        reply.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Ack),
+            DhcpOption::ServerIdentifier(Ipv4Addr::new(10, 0, 0, 1)),
+            DhcpOption::IpAddressLeaseTime(3600),
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+            DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 1)].into()),
+            DhcpOption::DomainNameServer(vec![Ipv4Addr::new(10, 0, 0, 1); 3].into()),
+            DhcpOption::DomainName("example.com".to_string()),
+            DhcpOption::DomainSearch(vec!["example.com".to_string()]),
+        ]
+        .into();
+
+        let (serialized, count) = allocations(|| reply.serialize().unwrap());
+        assert_eq!(count, 1);
+        assert_eq!(serialized.len(), reply.wire_len());
+        assert_eq!(serialized.capacity(), serialized.len());
+
+        // Too long messages are rejected before encoding them.
+        let (result, count) = allocations(|| reply.serialize_within(300));
+        assert!(result.is_err());
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn alloc_serialize_into_reused_buffer() {
+        let mut reply = DhcpMessage::new(2, 0x1234);
+        reply.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Ack),
+            DhcpOption::IpAddressLeaseTime(3600),
+            DhcpOption::DomainName("example.com".to_string()),
+        ]
+        .into();
+        let mut buffer = Vec::new();
+        reply.serialize_into(&mut buffer).unwrap();
+        assert_eq!(buffer, reply.serialize().unwrap());
+        for option in &reply.options {
+            option.serialize_into(&mut buffer).unwrap();
+        }
+
+        // Once the buffer is large enough, encoding allocates nothing.
+        let (result, count) = allocations(|| {
+            for xid in 0..100 {
+                reply.xid = xid;
+                buffer.clear();
+                reply.serialize_into(&mut buffer)?;
+                for option in &reply.options {
+                    option.serialize_into(&mut buffer)?;
+                }
+            }
+            Ok::<(), dhcp::error::DhcpError>(())
+        });
+        assert!(result.is_ok());
+        assert_eq!(count, 0);
+
+        // Nothing is appended on error.
+        let len = buffer.len();
+        reply.options.push(DhcpOption::ClientIdentifier(vec![]));
+        assert!(reply.serialize_into(&mut buffer).is_err());
+        assert!(reply.options[3].serialize_into(&mut buffer).is_err());
+        assert_eq!(buffer.len(), len);
+    }
+
+    #[test]
+    fn alloc_parse_borrowed() {
+        let mut request = DhcpMessage::new(1, 0x1234);
+        request.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Request),
+            DhcpOption::ClientIdentifier(vec![1, 2, 0, 0, 0, 0, 1]),
+            DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, 10)),
+            DhcpOption::Unknown(55, vec![1, 3, 6, 15, 119]),
+            DhcpOption::HostName("client".to_string()),
+            DhcpOption::DomainNameServer(vec![Ipv4Addr::new(10, 0, 0, 1); 3].into()),
+            DhcpOption::DomainSearch(vec!["example.com".to_string()]),
+        ]
+        .into();
+        let data = request.serialize().unwrap();
+
+        // Decoding every option borrows from the packet, where the owned
+        // parser allocates for most of them.
+        let (result, count) = allocations(|| {
+            let message = DhcpMessageRef::deserialize(&data)?;
+            let decoded = message
+                .options()
+                .try_fold(0, |count, option| option.map(|_| count + 1));
+            decoded
+        });
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!(count, 0);
+        let (_, owned) = allocations(|| DhcpMessage::deserialize(&data).unwrap());
+        assert!(owned > 7);
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn alloc_parse_short_address_lists() {
+        let data = [6, 8, 10, 0, 0, 1, 10, 0, 0, 2, 255];
+        let (result, count) = allocations(|| DhcpOption::deserialize(&data));
+        match result {
+            Ok((DhcpOption::DomainNameServer(servers), _)) => assert!(servers.is_inline()),
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert_eq!(count, 0);
+
+        // Longer lists spill to the heap.
+        let data = [6, 12, 10, 0, 0, 1, 10, 0, 0, 2, 10, 0, 0, 3, 255];
+        let (result, count) = allocations(|| DhcpOption::deserialize(&data));
+        match result {
+            Ok((DhcpOption::DomainNameServer(servers), _)) => assert!(!servers.is_inline()),
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert_eq!(count, 1);
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn alloc_heapless_round_trip() {
+        use dhcp::heapless::FixedMessage;
+
+        let mut request = DhcpMessage::new(1, 0x1234);
+        request.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Request),
+            DhcpOption::ClientIdentifier(vec![1, 2, 0, 0, 0, 0, 1]),
+            DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, 10)),
+            DhcpOption::HostName("client".to_string()),
+        ]
+        .into();
+        let data = request.serialize().unwrap();
+
+        let mut buffer = [0; 576];
+        let (result, count) = allocations(|| {
+            let message = FixedMessage::<8, 16>::deserialize(&data)?;
+            message.serialize_into(&mut buffer)
+        });
+        assert_eq!(result.unwrap(), data.len());
+        assert_eq!(&buffer[..data.len()], &data[..]);
+        assert_eq!(count, 0);
+
+        // Nor are errors allocated for.
+        let (result, count) = allocations(|| FixedMessage::<2, 16>::deserialize(&data));
+        assert!(result.is_err());
+        assert_eq!(count, 0);
+    }
+}
@@ -0,0 +1,131 @@
+use dhcp::auth::{hmac_md5, sign, verify, KeyStore, ReplayDetector};
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn packet(options: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0; 236];
+        packet[0] = 1;
+        packet[1] = 1;
+        packet[2] = 6;
+        packet[28..34].copy_from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        packet.extend_from_slice(&[99, 130, 83, 99]);
+        packet.extend_from_slice(options);
+        packet.push(255);
+        packet
+    }
+
+    fn keys() -> HashMap<u32, Vec<u8>> {
+        let mut keys = HashMap::new();
+        keys.insert(7, b"secret".to_vec());
+        keys
+    }
+
+    #[test]
+    fn auth_hmac_md5_rfc_2104_vectors() {
+        assert_eq!(
+            hmac_md5(&[0x0b; 16], b"Hi There"),
+            [
+                0x92, 0x94, 0x72, 0x7a, 0x36, 0x38, 0xbb, 0x1c, 0x13, 0xf4, 0x8e, 0xf8, 0x15, 0x8b,
+                0xfc, 0x9d
+            ]
+        );
+        assert_eq!(
+            hmac_md5(b"Jefe", b"what do ya want for nothing?"),
+            [
+                0x75, 0x0c, 0x78, 0x3e, 0x6a, 0xb0, 0xb5, 0x03, 0xea, 0xa8, 0x6e, 0x31, 0x0a, 0x5d,
+                0xb7, 0x38
+            ]
+        );
+        // Keys longer than a block are hashed first (RFC 2202).
+        assert_eq!(
+            hmac_md5(
+                &[0xaa; 80],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            [
+                0x6b, 0x1a, 0xb7, 0xfe, 0x4b, 0xd7, 0xbf, 0x8f, 0x0b, 0x62, 0xe6, 0xce, 0x61, 0xb9,
+                0xd0, 0xcd
+            ]
+        );
+    }
+
+    #[test]
+    fn auth_key_store() {
+        let keys = keys();
+        assert_eq!(keys.key(7), Some(&b"secret"[..]));
+        assert_eq!(keys.key(8), None);
+    }
+
+    #[test]
+    fn auth_sign_and_verify() {
+        let mut data = packet(&[53, 1, 3]);
+        sign(&mut data, 7, b"secret", 1).unwrap();
+        assert_eq!(&data[240..243], &[53, 1, 3]);
+        assert_eq!(&data[243..256], &[90, 31, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(&data[256..260], &[0, 0, 0, 7]);
+        assert_eq!(data.last(), Some(&255));
+
+        let mut replay_detector = ReplayDetector::new();
+        assert_eq!(verify(&data, &keys(), &mut replay_detector).unwrap(), 7);
+        assert_eq!(replay_detector.last(), Some(1));
+
+        // Relay agents may update hops and giaddr.
+        data[3] = 1;
+        data[24..28].copy_from_slice(&[10, 0, 0, 1]);
+        let mut replay_detector = ReplayDetector::new();
+        assert!(verify(&data, &keys(), &mut replay_detector).is_ok());
+    }
+
+    #[test]
+    fn auth_sign_replaces_option() {
+        let mut data = packet(&[53, 1, 3]);
+        sign(&mut data, 7, b"secret", 1).unwrap();
+        sign(&mut data, 7, b"secret", 2).unwrap();
+        assert_eq!(data.len(), 240 + 3 + 33 + 1);
+        assert_eq!(data[255], 2);
+
+        let mut replay_detector = ReplayDetector::new();
+        assert!(verify(&data, &keys(), &mut replay_detector).is_ok());
+    }
+
+    #[test]
+    fn auth_verify_rejects_tampering() {
+        let mut data = packet(&[53, 1, 3]);
+        sign(&mut data, 7, b"secret", 1).unwrap();
+
+        let mut tampered = data.clone();
+        tampered[242] = 1;
+        let mut replay_detector = ReplayDetector::new();
+        assert!(verify(&tampered, &keys(), &mut replay_detector).is_err());
+        assert_eq!(replay_detector.last(), None);
+
+        let mut wrong_key = data.clone();
+        sign(&mut wrong_key, 7, b"other", 1).unwrap();
+        assert!(verify(&wrong_key, &keys(), &mut replay_detector).is_err());
+
+        let mut unknown_secret = data.clone();
+        sign(&mut unknown_secret, 8, b"secret", 1).unwrap();
+        assert!(verify(&unknown_secret, &keys(), &mut replay_detector).is_err());
+
+        assert!(verify(&packet(&[53, 1, 3]), &keys(), &mut replay_detector).is_err());
+    }
+
+    #[test]
+    fn auth_replay_detection() {
+        let mut replay_detector = ReplayDetector::new();
+        assert!(replay_detector.accept(5).is_ok());
+        assert!(replay_detector.accept(5).is_err());
+        assert!(replay_detector.accept(4).is_err());
+        assert!(replay_detector.accept(6).is_ok());
+
+        let mut data = packet(&[53, 1, 3]);
+        sign(&mut data, 7, b"secret", 1).unwrap();
+        let mut replay_detector = ReplayDetector::new();
+        assert!(verify(&data, &keys(), &mut replay_detector).is_ok());
+        assert!(verify(&data, &keys(), &mut replay_detector).is_err());
+    }
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "bench")]
+
+use dhcp::bench::{
+    build_acks, captured_packets, parse_packets, parse_packets_borrowed, parse_packets_lazy,
+    workloads,
+};
+use dhcp::message::DhcpMessage;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bench_workloads() {
+        let packets = captured_packets();
+        assert_eq!(packets.len(), 64);
+        assert!(packets
+            .iter()
+            .all(|packet| DhcpMessage::deserialize(packet).is_ok()));
+
+        // Every packet parses, and the lazy parser finds the message type
+        // in each, the client identifier and requested address in requests.
+        assert!(parse_packets(&packets, 128) > 128 * 8);
+        assert_eq!(parse_packets_lazy(&packets, 64), 64 + 32 + 16);
+        // The borrowed options are those of the owned parser.
+        assert_eq!(
+            parse_packets_borrowed(&packets, 128),
+            parse_packets(&packets, 128)
+        );
+        assert!(build_acks(10) > 10 * 300);
+
+        for workload in workloads() {
+            assert!(workload.size >= 100_000);
+            assert!((workload.run)(&packets, 10) > 0, "{}", workload.name);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::net::Ipv4Addr;
+
+use dhcp::bootp::{BootpMessage, Packet};
+use dhcp::error::{DhcpError, SerializationError};
+use dhcp::message::{DhcpMessage, MAGIC_COOKIE};
+use dhcp::option::DhcpOption;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bootp_serialize_deserialize() {
+        let mut message = BootpMessage::new(2, 0x1234);
+        message.yiaddr = Ipv4Addr::new(10, 0, 0, 5);
+        message.siaddr = Ipv4Addr::new(10, 0, 0, 1);
+        message.chaddr[..6].copy_from_slice(&[2, 0, 0, 0, 0, 1]);
+        message.file[..8].copy_from_slice(b"pxelinux");
+        message.vend[..4].copy_from_slice(b"VEND");
+
+        let data = message.serialize();
+        assert_eq!(data.len(), BootpMessage::LEN);
+        assert_eq!(&data[236..240], b"VEND");
+        assert_eq!(BootpMessage::deserialize(&data).unwrap(), message);
+        assert_eq!(message.vend_options().unwrap(), None);
+
+        // Short vend fields are padded.
+        let short = BootpMessage::deserialize(&data[..240]).unwrap();
+        assert_eq!(short, message);
+        assert!(BootpMessage::deserialize(&data[..235]).is_err());
+    }
+
+    #[test]
+    fn bootp_vend_options() {
+        let mut message = BootpMessage::new(2, 1);
+        let options = vec![
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+            DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 1)].into()),
+        ];
+        message.set_vend_options(&options).unwrap();
+        assert_eq!(message.vend[..4], MAGIC_COOKIE);
+        assert_eq!(message.vend_options().unwrap(), Some(options));
+
+        let result = message.set_vend_options(&[DhcpOption::HostName("a".repeat(60))]);
+        assert!(matches!(
+            result,
+            Err(DhcpError::SerializationError(
+                SerializationError::MessageTooLong { len: 67, max: 64 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn bootp_packet() {
+        let mut discover = DhcpMessage::discover(1, [2, 0, 0, 0, 0, 1]);
+        let data = discover.serialize().unwrap();
+        assert_eq!(
+            Packet::deserialize(&data).unwrap(),
+            Packet::Dhcp(Box::new(discover.clone()))
+        );
+
+        // Without a message type, an RFC 1048 message is BOOTP.
+        discover.options.clear();
+        let data = discover.serialize().unwrap();
+        assert!(matches!(
+            Packet::deserialize(&data).unwrap(),
+            Packet::Bootp(_)
+        ));
+
+        let bootp = BootpMessage::new(1, 2);
+        let packet = Packet::deserialize(&bootp.serialize()).unwrap();
+        assert_eq!(packet, Packet::Bootp(Box::new(bootp.clone())));
+        assert_eq!(packet.serialize().unwrap(), bootp.serialize());
+    }
+}
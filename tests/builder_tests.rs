@@ -0,0 +1,174 @@
+use std::net::Ipv4Addr;
+
+use dhcp::builder::DhcpMessageBuilder;
+use dhcp::error::{DhcpError, SerializationError};
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::option::DhcpOption;
+
+const MAC: [u8; 6] = [0x02, 0, 0, 0, 0, 1];
+const SERVER: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 1);
+const CLIENT: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 100);
+
+fn missing(result: Result<DhcpMessage, DhcpError>) -> SerializationError {
+    match result {
+        Err(DhcpError::SerializationError(error)) => error,
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_discover() {
+        let discover = DhcpMessageBuilder::discover()
+            .xid(0x1234)
+            .chaddr(MAC)
+            .broadcast(true)
+            .parameter_request(&[1, 3, 6])
+            .host_name("client")
+            .build()
+            .unwrap();
+        assert_eq!(discover.op, 1);
+        assert_eq!((discover.htype, discover.hlen), (1, 6));
+        assert_eq!(discover.chaddr[..6], MAC);
+        assert_eq!(discover.xid, 0x1234);
+        assert!(discover.flags.broadcast());
+        assert_eq!(
+            discover.message_type().unwrap(),
+            Some(MessageType::Discover)
+        );
+        assert_eq!(
+            discover.options,
+            vec![
+                DhcpOption::DhcpMessageType(MessageType::Discover),
+                DhcpOption::Unknown(55, vec![1, 3, 6]),
+                DhcpOption::HostName("client".to_string()),
+            ]
+        );
+        assert!(DhcpMessage::deserialize(&discover.serialize().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn builder_replies() {
+        let offer = DhcpMessageBuilder::offer()
+            .xid(1)
+            .chaddr(MAC)
+            .yiaddr(CLIENT)
+            .server_identifier(SERVER)
+            .lease_time(3600)
+            .build()
+            .unwrap();
+        assert_eq!(offer.op, 2);
+        assert_eq!(offer.message_type().unwrap(), Some(MessageType::Offer));
+
+        // An ACK to a DHCPINFORM needs no lease time.
+        let ack = DhcpMessageBuilder::ack()
+            .chaddr(MAC)
+            .ciaddr(CLIENT)
+            .server_identifier(SERVER)
+            .build();
+        assert!(ack.is_ok());
+
+        let nak = DhcpMessageBuilder::nak()
+            .chaddr(MAC)
+            .server_identifier(SERVER)
+            .message("requested address not on this network")
+            .build()
+            .unwrap();
+        assert_eq!(
+            nak.get_option(56),
+            Some(&DhcpOption::Unknown(
+                56,
+                b"requested address not on this network".to_vec()
+            ))
+        );
+    }
+
+    #[test]
+    fn builder_missing_fields() {
+        assert_eq!(
+            missing(DhcpMessageBuilder::discover().build()),
+            SerializationError::MissingField { field: "chaddr" }
+        );
+        assert_eq!(
+            missing(DhcpMessageBuilder::request().chaddr(MAC).build()),
+            SerializationError::MissingValue { code: 50 }
+        );
+        // Renewing clients send their address in ciaddr instead.
+        assert!(DhcpMessageBuilder::request()
+            .chaddr(MAC)
+            .ciaddr(CLIENT)
+            .build()
+            .is_ok());
+        assert_eq!(
+            missing(DhcpMessageBuilder::release().chaddr(MAC).build()),
+            SerializationError::MissingField { field: "ciaddr" }
+        );
+        assert_eq!(
+            missing(
+                DhcpMessageBuilder::release()
+                    .chaddr(MAC)
+                    .ciaddr(CLIENT)
+                    .build()
+            ),
+            SerializationError::MissingValue { code: 54 }
+        );
+        assert_eq!(
+            missing(
+                DhcpMessageBuilder::offer()
+                    .chaddr(MAC)
+                    .yiaddr(CLIENT)
+                    .server_identifier(SERVER)
+                    .build()
+            ),
+            SerializationError::MissingValue { code: 51 }
+        );
+        assert_eq!(
+            missing(DhcpMessageBuilder::decline().chaddr(MAC).build()),
+            SerializationError::MissingValue { code: 50 }
+        );
+    }
+
+    #[test]
+    fn builder_exchange() {
+        let mut discover = DhcpMessage::discover(0x1234, MAC);
+        discover.flags.set_broadcast(true);
+        discover.giaddr = Ipv4Addr::new(10, 0, 0, 1);
+
+        let offer = DhcpMessage::offer(&discover, CLIENT, SERVER, 3600);
+        assert_eq!(offer.op, 2);
+        assert_eq!(offer.xid, 0x1234);
+        assert!(offer.flags.broadcast());
+        assert_eq!(offer.giaddr, discover.giaddr);
+        assert_eq!(offer.chaddr, discover.chaddr);
+        assert_eq!(offer.validate(), vec![]);
+
+        let request = DhcpMessage::request(&offer).unwrap();
+        assert_eq!(request.xid, 0x1234);
+        assert_eq!(request.message_type().unwrap(), Some(MessageType::Request));
+        assert_eq!(
+            request.get_option(50),
+            Some(&DhcpOption::RequestedIpAddress(CLIENT))
+        );
+        assert_eq!(
+            request.get_option(54),
+            Some(&DhcpOption::ServerIdentifier(SERVER))
+        );
+        assert_eq!(request.validate(), vec![]);
+
+        let ack = DhcpMessage::ack(&request, CLIENT, SERVER, 3600);
+        assert_eq!(ack.yiaddr, CLIENT);
+        assert_eq!(ack.validate(), vec![]);
+
+        let nak = DhcpMessage::nak(&request, SERVER, "wrong network");
+        assert_eq!(nak.message_type().unwrap(), Some(MessageType::Nak));
+        assert_eq!(nak.validate(), vec![]);
+
+        assert_eq!(
+            missing(DhcpMessage::request(&discover)),
+            SerializationError::MissingValue { code: 54 }
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use std::net::Ipv4Addr;
+
+use dhcp::corpus::corpus;
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::option::{DhcpOption, OptionOverload};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_form() {
+        let mut message = DhcpMessage::new(2, 1);
+        message.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Ack),
+            DhcpOption::Pad,
+            DhcpOption::HostName("host".to_string()),
+            DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 1)].into()),
+            DhcpOption::Unknown(1, vec![255, 255, 255, 0]),
+            DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 2)].into()),
+            DhcpOption::OptionOverload(OptionOverload::File),
+            DhcpOption::End,
+        ]
+        .into();
+        assert!(!message.is_canonical());
+
+        message.canonicalize();
+        assert!(message.is_canonical());
+        assert_eq!(
+            message.options,
+            vec![
+                DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+                DhcpOption::Router(
+                    vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)].into()
+                ),
+                DhcpOption::HostName("host".to_string()),
+                DhcpOption::DhcpMessageType(MessageType::Ack),
+            ]
+        );
+
+        // Options whose concatenation is invalid are kept apart.
+        let mut masks = DhcpMessage::new(2, 1);
+        masks.options = vec![
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 0, 0)),
+        ]
+        .into();
+        let expected = masks.clone();
+        masks.canonicalize();
+        assert_eq!(masks, expected);
+    }
+
+    #[test]
+    fn canonical_round_trip() {
+        for entry in corpus() {
+            let mut message = match DhcpMessage::deserialize(&entry.data) {
+                Ok(message) => message,
+                Err(_) => continue,
+            };
+            message.canonicalize();
+            let data = message.serialize().unwrap();
+            assert_eq!(
+                DhcpMessage::deserialize(&data).unwrap(),
+                message,
+                "{}",
+                entry.name
+            );
+            assert_eq!(
+                message.to_canonical_bytes().unwrap(),
+                data,
+                "{}",
+                entry.name
+            );
+        }
+    }
+}
@@ -0,0 +1,205 @@
+#![cfg(feature = "capture")]
+
+use std::io::Cursor;
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::time::Duration;
+
+use dhcp::capture::{CaptureReader, CaptureWriter, CapturedMessage};
+use dhcp::message::DhcpMessage;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Build an Ethernet frame carrying a UDP datagram.
+    fn ethernet_frame(source_port: u16, destination_port: u16, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0xFF; 6];
+        frame.extend_from_slice(&[0, 1, 2, 3, 4, 5]);
+        frame.extend_from_slice(&[0x08, 0x00]);
+
+        let total_len = (20 + 8 + payload.len()) as u16;
+        frame.extend_from_slice(&[0x45, 0]);
+        frame.extend_from_slice(&total_len.to_be_bytes());
+        frame.extend_from_slice(&[0, 0, 0, 0, 64, 17, 0, 0]);
+        frame.extend_from_slice(&[192, 168, 1, 1]);
+        frame.extend_from_slice(&[255, 255, 255, 255]);
+
+        frame.extend_from_slice(&source_port.to_be_bytes());
+        frame.extend_from_slice(&destination_port.to_be_bytes());
+        frame.extend_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+        frame.extend_from_slice(&[0, 0]);
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    fn pcap(frames: &[(u32, u32, Vec<u8>)]) -> Vec<u8> {
+        let mut file = Vec::new();
+        file.extend_from_slice(&0xA1B2C3D4u32.to_le_bytes());
+        file.extend_from_slice(&[2, 0, 4, 0]);
+        file.extend_from_slice(&[0; 8]);
+        file.extend_from_slice(&65535u32.to_le_bytes());
+        file.extend_from_slice(&1u32.to_le_bytes());
+        for (seconds, micros, frame) in frames {
+            file.extend_from_slice(&seconds.to_le_bytes());
+            file.extend_from_slice(&micros.to_le_bytes());
+            file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            file.extend_from_slice(frame);
+        }
+        file
+    }
+
+    fn pcapng_block(block_type: u32, body: &[u8]) -> Vec<u8> {
+        let padded_len = body.len().div_ceil(4) * 4;
+        let total_len = (12 + padded_len) as u32;
+        let mut block = Vec::new();
+        block.extend_from_slice(&block_type.to_be_bytes());
+        block.extend_from_slice(&total_len.to_be_bytes());
+        block.extend_from_slice(body);
+        block.resize(8 + padded_len, 0);
+        block.extend_from_slice(&total_len.to_be_bytes());
+        block
+    }
+
+    #[test]
+    fn capture_pcap() {
+        let message = DhcpMessage::new(1, 0xDEADBEEF);
+        let file = pcap(&[
+            (
+                10,
+                500,
+                ethernet_frame(68, 67, &message.serialize().unwrap()),
+            ),
+            (11, 0, ethernet_frame(1234, 53, &[0; 12])),
+        ]);
+
+        let mut reader = CaptureReader::new(Cursor::new(file)).unwrap();
+        let captured = reader.next().unwrap().unwrap();
+        assert_eq!(captured.timestamp, Duration::new(10, 500_000));
+        assert_eq!(
+            captured.source,
+            SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 1), 68)
+        );
+        assert_eq!(
+            captured.destination,
+            SocketAddrV4::new(Ipv4Addr::BROADCAST, 67)
+        );
+        assert_eq!(captured.message, message);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn capture_pcapng() {
+        let message = DhcpMessage::new(2, 7);
+
+        // Big endian section with a millisecond interface.
+        let mut section = 0x1A2B3C4Du32.to_be_bytes().to_vec();
+        section.extend_from_slice(&[0, 1, 0, 0]);
+        section.extend_from_slice(&[0xFF; 8]);
+        let mut file = pcapng_block(0x0A0D0D0A, &section);
+
+        let mut interface = vec![0, 1, 0, 0, 0, 0, 0, 0];
+        interface.extend_from_slice(&[0, 9, 0, 1, 3, 0, 0, 0]);
+        interface.extend_from_slice(&[0, 0, 0, 0]);
+        file.extend_from_slice(&pcapng_block(1, &interface));
+
+        let frame = ethernet_frame(67, 68, &message.serialize().unwrap());
+        let mut packet = vec![0; 4];
+        packet.extend_from_slice(&0u32.to_be_bytes());
+        packet.extend_from_slice(&1500u32.to_be_bytes());
+        packet.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+        packet.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+        packet.extend_from_slice(&frame);
+        file.extend_from_slice(&pcapng_block(6, &packet));
+
+        let mut reader = CaptureReader::new(Cursor::new(file)).unwrap();
+        let captured = reader.next().unwrap().unwrap();
+        assert_eq!(captured.timestamp, Duration::from_millis(1500));
+        assert_eq!(captured.source.port(), 67);
+        assert_eq!(captured.message, message);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn capture_invalid() {
+        assert!(CaptureReader::new(Cursor::new(vec![0; 24])).is_err());
+
+        let mut file = pcap(&[(0, 0, ethernet_frame(68, 67, &[0; 240]))]);
+        file.truncate(file.len() - 10);
+        let mut reader = CaptureReader::new(Cursor::new(file)).unwrap();
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn capture_write_read() {
+        let mut discover = DhcpMessage::new(1, 42);
+        discover.chaddr[..6].copy_from_slice(&[0, 1, 2, 3, 4, 5]);
+        let mut offer = DhcpMessage::new(2, 42);
+        offer.yiaddr = Ipv4Addr::new(10, 0, 0, 5);
+        let messages = vec![
+            CapturedMessage {
+                timestamp: Duration::new(1_700_000_000, 250_000),
+                source: SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 68),
+                destination: SocketAddrV4::new(Ipv4Addr::BROADCAST, 67),
+                message: discover,
+            },
+            CapturedMessage {
+                timestamp: Duration::new(1_700_000_001, 0),
+                source: SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 67),
+                destination: SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 5), 68),
+                message: offer,
+            },
+        ];
+
+        let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+        for message in &messages {
+            writer.write(message).unwrap();
+        }
+        let file = writer.into_inner();
+        assert_eq!(&file[0..4], &[0x0A, 0x0D, 0x0D, 0x0A]);
+        assert!(file.len().is_multiple_of(4));
+
+        let reader = CaptureReader::new(Cursor::new(file)).unwrap();
+        let read = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(read, messages);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn capture_parallel() {
+        // More messages than a batch, from several clients.
+        let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+        let mut messages = Vec::new();
+        for index in 0..10_000u32 {
+            let mut message = DhcpMessage::new(1, index);
+            message.chaddr[5] = (index % 7) as u8;
+            let captured = CapturedMessage {
+                timestamp: Duration::new(1_700_000_000 + index as u64, 0),
+                source: SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 68),
+                destination: SocketAddrV4::new(Ipv4Addr::BROADCAST, 67),
+                message,
+            };
+            writer.write(&captured).unwrap();
+            messages.push(captured);
+        }
+        let mut file = writer.into_inner();
+
+        for threads in [0, 1, 3] {
+            let reader = CaptureReader::new(Cursor::new(file.clone())).unwrap();
+            let read = reader
+                .parallel(threads)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(read, messages);
+        }
+
+        // A framing error ends the messages, after those before it.
+        file.truncate(file.len() - 10);
+        let reader = CaptureReader::new(Cursor::new(file)).unwrap();
+        let read = reader.parallel(4).collect::<Vec<_>>();
+        assert_eq!(read.len(), messages.len());
+        assert!(read[..read.len() - 1].iter().all(|message| message.is_ok()));
+        assert!(read.last().unwrap().is_err());
+    }
+}
@@ -0,0 +1,239 @@
+#![cfg(feature = "client")]
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::net::Ipv4Addr;
+use std::pin::pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use dhcp::client::{Client, ClientSocket, ClientState, Clock};
+use dhcp::error::{DhcpError, NakReason};
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::option::DhcpOption;
+use dhcp::server::{Server, ServerConfig};
+
+// A link to a server, on a clock that jumps to each deadline when no
+// reply is waiting.
+struct Link {
+    server: Server,
+    now: Rc<Cell<Duration>>,
+    // Whether the server answers.
+    up: bool,
+    replies: VecDeque<Vec<u8>>,
+    sent: Vec<(DhcpMessage, Ipv4Addr)>,
+}
+
+#[derive(Clone)]
+struct Socket(Rc<RefCell<Link>>);
+
+impl ClientSocket for Socket {
+    async fn send_to(&mut self, data: &[u8], destination: Ipv4Addr) -> Result<(), DhcpError> {
+        let mut link = self.0.borrow_mut();
+        let request = DhcpMessage::deserialize(data)?;
+        let now = link.now.get().as_secs() as u32;
+        if link.up {
+            if let Some(reply) = link.server.handle(&request, now, &mut Vec::new()) {
+                let reply = reply.serialize()?;
+                link.replies.push_back(reply);
+            }
+        }
+        link.sent.push((request, destination));
+        Ok(())
+    }
+
+    async fn recv_until(
+        &mut self,
+        buffer: &mut [u8],
+        deadline: Duration,
+    ) -> Result<Option<usize>, DhcpError> {
+        let mut link = self.0.borrow_mut();
+        match link.replies.pop_front() {
+            Some(reply) => {
+                buffer[..reply.len()].copy_from_slice(&reply);
+                Ok(Some(reply.len()))
+            }
+            None => {
+                link.now.set(deadline.max(link.now.get()));
+                Ok(None)
+            }
+        }
+    }
+}
+
+struct SharedClock(Rc<Cell<Duration>>);
+
+impl Clock for SharedClock {
+    fn now(&self) -> Duration {
+        self.0.get()
+    }
+}
+
+const CONFIG: &str = r#"
+    server_identifier = "10.0.0.1"
+    pool_start = "10.0.0.10"
+    pool_end = "10.0.0.11"
+    subnet_mask = "255.255.255.0"
+    routers = ["10.0.0.1"]
+    lease_time = 3600
+"#;
+
+const MAC: [u8; 6] = [2, 0, 0, 0, 0, 1];
+
+// The futures of the client only wait on the socket, which never blocks.
+fn run<T>(future: impl Future<Output = T>) -> T {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+fn client() -> (Client<Socket, SharedClock>, Rc<RefCell<Link>>) {
+    let now = Rc::new(Cell::new(Duration::from_secs(100)));
+    let link = Rc::new(RefCell::new(Link {
+        server: Server::new(ServerConfig::from_config(CONFIG).unwrap()),
+        now: now.clone(),
+        up: true,
+        replies: VecDeque::new(),
+        sent: Vec::new(),
+    }));
+    let client = Client::new(Socket(link.clone()), SharedClock(now), MAC, 0x1000);
+    (client, link)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_obtain() {
+        let (mut client, link) = client();
+        assert!(matches!(
+            run(client.step()).unwrap(),
+            ClientState::Requesting { .. }
+        ));
+        let lease = run(client.obtain()).unwrap();
+        assert_eq!(lease.address(), Ipv4Addr::new(10, 0, 0, 10));
+        assert_eq!(lease.server(), Some(Ipv4Addr::new(10, 0, 0, 1)));
+        assert_eq!(lease.obtained, Duration::from_secs(100));
+        assert_eq!(lease.lease_time(), Duration::from_secs(3600));
+        assert_eq!(lease.renewal_time(), Duration::from_secs(1800));
+        assert_eq!(lease.rebinding_time(), Duration::from_secs(3150));
+
+        // The DISCOVER and the REQUEST, in one transaction, broadcast.
+        let link = link.borrow();
+        assert_eq!(link.sent.len(), 2);
+        let (request, destination) = &link.sent[1];
+        assert_eq!(*destination, Ipv4Addr::BROADCAST);
+        assert_eq!(request.xid, link.sent[0].0.xid);
+        assert_eq!(request.message_type().unwrap(), Some(MessageType::Request));
+        assert!(request.flags.broadcast());
+        assert_eq!(&request.chaddr[..6], &MAC);
+        assert!(request
+            .options
+            .iter()
+            .any(|option| *option == DhcpOption::ClientIdentifier(vec![1, 2, 0, 0, 0, 0, 1])));
+    }
+
+    #[test]
+    fn client_renew() {
+        let (mut client, link) = client();
+        run(client.obtain()).unwrap();
+
+        // Bound until the renewal time, then renewing with the server.
+        assert!(matches!(
+            run(client.step()).unwrap(),
+            ClientState::Renewing { .. }
+        ));
+        assert_eq!(link.borrow().now.get(), Duration::from_secs(1900));
+        let state = run(client.step()).unwrap().clone();
+        let lease = match state {
+            ClientState::Bound { lease } => lease,
+            state => panic!("unexpected state {:?}", state),
+        };
+        assert_eq!(lease.obtained, Duration::from_secs(1900));
+        assert_eq!(lease.address(), Ipv4Addr::new(10, 0, 0, 10));
+
+        let link = link.borrow();
+        let (request, destination) = link.sent.last().unwrap();
+        assert_eq!(*destination, Ipv4Addr::new(10, 0, 0, 1));
+        assert_eq!(request.ciaddr, Ipv4Addr::new(10, 0, 0, 10));
+        assert!(!request.flags.broadcast());
+        assert_eq!(request.server_identifier(), None);
+    }
+
+    #[test]
+    fn client_lease_expiry() {
+        let (mut client, link) = client();
+        run(client.obtain()).unwrap();
+        link.borrow_mut().up = false;
+
+        // Renewing until the rebinding time, then rebinding until expiry.
+        run(client.step()).unwrap();
+        assert!(matches!(
+            run(client.step()).unwrap(),
+            ClientState::Rebinding { .. }
+        ));
+        assert_eq!(link.borrow().now.get(), Duration::from_secs(3250));
+        assert_eq!(run(client.step()).unwrap(), &ClientState::Init);
+        assert_eq!(link.borrow().now.get(), Duration::from_secs(3700));
+        assert!(client.lease().is_none());
+        let link = link.borrow();
+        assert_eq!(
+            link.sent.last().unwrap().1,
+            Ipv4Addr::BROADCAST,
+            "rebinding requests are broadcast"
+        );
+    }
+
+    #[test]
+    fn client_no_server() {
+        let (mut client, link) = client();
+        link.borrow_mut().up = false;
+        assert!(matches!(run(client.step()), Err(DhcpError::Timeout)));
+        assert_eq!(client.state(), &ClientState::Init);
+
+        // Retransmissions back off from 4 to 64 seconds.
+        let link = link.borrow();
+        assert_eq!(link.sent.len(), 5);
+        assert_eq!(
+            link.now.get(),
+            Duration::from_secs(100 + 4 + 8 + 16 + 32 + 64)
+        );
+    }
+
+    #[test]
+    fn client_nak() {
+        let (mut client, link) = client();
+        run(client.step()).unwrap();
+
+        // Another client takes the offered address first.
+        let mut request = DhcpMessage::new(1, 7);
+        request.chaddr[5] = 9;
+        request.options = vec![
+            DhcpOption::from(MessageType::Request),
+            DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, 10)),
+        ]
+        .into();
+        assert!(link
+            .borrow()
+            .server
+            .handle(&request, 100, &mut Vec::new())
+            .is_some());
+
+        assert!(matches!(
+            run(client.step()),
+            Err(DhcpError::Nak(NakReason::AddressInUse))
+        ));
+        assert_eq!(client.state(), &ClientState::Init);
+        assert_eq!(
+            run(client.obtain()).unwrap().address(),
+            Ipv4Addr::new(10, 0, 0, 11)
+        );
+    }
+}
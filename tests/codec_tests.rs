@@ -0,0 +1,79 @@
+use std::net::Ipv4Addr;
+
+use dhcp::codec::{DhcpDecoder, DhcpEncoder};
+use dhcp::error::{DhcpError, SerializationError};
+use dhcp::message::{DhcpMessage, MessageType, ParserConfig, UnknownOptions};
+use dhcp::option::DhcpOption;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> DhcpMessage {
+        let mut message = DhcpMessage::new(1, 0x1234);
+        message.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Discover),
+            DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 1)].into()),
+        ]
+        .into();
+        message
+    }
+
+    #[test]
+    fn codec_encoder() {
+        let message = message();
+        let mut encoder = DhcpEncoder::new();
+        assert_eq!(
+            encoder.encode(&message).unwrap(),
+            message.serialize().unwrap()
+        );
+        // The buffer is reused for the next message.
+        let mut other = message.clone();
+        other.options.clear();
+        assert_eq!(encoder.encode(&other).unwrap().len(), 241);
+
+        let mut encoder = DhcpEncoder::new().with_max_len(250);
+        assert!(encoder.encode(&message).is_ok());
+        let mut encoder = DhcpEncoder::new().with_max_len(249);
+        assert!(matches!(
+            encoder.encode(&message),
+            Err(DhcpError::SerializationError(
+                SerializationError::MessageTooLong { len: 250, max: 249 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn codec_decoder() {
+        let message = message();
+        let mut data = message.serialize().unwrap();
+        // A Subnet Mask option with a bad length, before the End option.
+        data.pop();
+        data.extend_from_slice(&[1, 3, 255, 255, 0, 255]);
+
+        let mut decoder = DhcpDecoder::default();
+        assert!(decoder.decode(&data).is_err());
+
+        let mut decoder = DhcpDecoder::default().lenient();
+        assert_eq!(decoder.decode(&data).unwrap(), message);
+        assert_eq!(decoder.warnings().len(), 1);
+        assert_eq!(
+            decoder.decode(&message.serialize().unwrap()).unwrap(),
+            message
+        );
+        assert!(decoder.warnings().is_empty());
+
+        let config = ParserConfig {
+            unknown_options: UnknownOptions::Skip,
+            ..ParserConfig::default()
+        };
+        let mut decoder = DhcpDecoder::new(config).with_buffer_len(576);
+        let mut serialized = message.serialize().unwrap();
+        serialized.pop();
+        serialized.extend_from_slice(&[200, 1, 0, 255]);
+        decoder.buffer()[..serialized.len()].copy_from_slice(&serialized);
+        let decoded = decoder.decode_buffer(serialized.len()).unwrap();
+        assert_eq!(decoded.options, message.options);
+        assert_eq!(decoder.buffer().len(), 576);
+    }
+}
@@ -0,0 +1,80 @@
+use std::net::Ipv4Addr;
+
+use dhcp::conformance::{check_server, Outcome};
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::option::DhcpOption;
+use dhcp::server::{Server, ServerConfig};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server() -> Server {
+        Server::new(
+            ServerConfig::from_config(
+                r#"
+                server_identifier = "10.0.0.1"
+                pool_start = "10.0.0.10"
+                pool_end = "10.0.0.20"
+                subnet_mask = "255.255.255.0"
+                "#,
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn conformance_server() {
+        let server = server();
+        let mut exchange = |request: &DhcpMessage| server.handle(request, 0, &mut Vec::new());
+        let report = check_server(&mut exchange, [0, 1, 2, 3, 4, 5]);
+
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.outcome("request_nak"), Some(&Outcome::Pass));
+        assert!(matches!(
+            report.outcome("overload"),
+            Some(Outcome::Skipped(_))
+        ));
+        assert!(report
+            .to_string()
+            .starts_with("PASS discover_offer\nPASS request_ack\n"));
+        assert_eq!(server.leases().count(), 0);
+    }
+
+    #[test]
+    fn conformance_failures() {
+        // A server answering everything with an offer, overloading a
+        // malformed file field.
+        let mut exchange = |request: &DhcpMessage| {
+            let mut reply = DhcpMessage::new(2, request.xid);
+            reply.chaddr = request.chaddr;
+            reply.yiaddr = Ipv4Addr::new(10, 0, 0, 10);
+            reply.file[..3].copy_from_slice(&[3, 8, 10]);
+            reply.options = vec![
+                DhcpOption::DhcpMessageType(MessageType::Offer),
+                DhcpOption::ServerIdentifier(Ipv4Addr::new(10, 0, 0, 1)),
+                DhcpOption::Unknown(52, vec![1]),
+            ]
+            .into();
+            Some(reply)
+        };
+        let report = check_server(&mut exchange, [0, 1, 2, 3, 4, 5]);
+
+        assert!(!report.passed());
+        assert_eq!(
+            report.outcome("discover_offer"),
+            Some(&Outcome::Fail("Reply has no lease time".to_string()))
+        );
+        assert_eq!(
+            report.outcome("request_nak"),
+            Some(&Outcome::Fail(
+                "Expected message type DHCPNAK, got DHCPOFFER".to_string()
+            ))
+        );
+        assert!(matches!(
+            report.outcome("inform_ack"),
+            Some(Outcome::Skipped(_))
+        ));
+        assert!(matches!(report.outcome("overload"), Some(Outcome::Fail(_))));
+    }
+}
@@ -0,0 +1,100 @@
+use std::env;
+use std::fs;
+
+use dhcp::corpus::{corpus, load_fixtures, write_corpus};
+use dhcp::message::DhcpMessage;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corpus_entries() {
+        let corpus = corpus();
+        for entry in corpus.iter().filter(|entry| entry.valid) {
+            assert!(
+                DhcpMessage::deserialize(&entry.data).is_ok(),
+                "{} does not parse",
+                entry.name
+            );
+        }
+
+        let giant = corpus.iter().find(|entry| entry.name == "giant").unwrap();
+        assert!(giant.data.len() > 8000);
+
+        for name in [
+            "truncated-header",
+            "bad-magic-cookie",
+            "truncated-option-length",
+            "truncated-option-value",
+            "bad-fixed-length",
+            "bad-list-length",
+        ] {
+            let entry = corpus.iter().find(|entry| entry.name == name).unwrap();
+            assert!(!entry.valid);
+            assert!(
+                DhcpMessage::deserialize(&entry.data).is_err(),
+                "{} parses",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn corpus_write() {
+        let directory = env::temp_dir().join(format!("dhcp-corpus-{}", std::process::id()));
+        let written = write_corpus(&directory).unwrap();
+        assert_eq!(written, corpus().len());
+
+        let discover = fs::read(directory.join("valid-discover.bin")).unwrap();
+        assert_eq!(discover, corpus()[0].data);
+        assert!(directory.join("malformed-missing-end.bin").exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn corpus_fixtures() {
+        let directory = env::temp_dir().join(format!("dhcp-fixtures-{}", std::process::id()));
+        write_corpus(&directory).unwrap();
+        let discover = &corpus()[0].data;
+        let hex: Vec<String> = discover
+            .chunks(16)
+            .map(|line| {
+                line.iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect();
+        fs::write(
+            directory.join("windows-discover.hex"),
+            format!("# Sent by a Windows client.\n{}\n", hex.join("\n")),
+        )
+        .unwrap();
+        fs::write(directory.join("README.txt"), "not a fixture").unwrap();
+
+        let fixtures = load_fixtures(&directory).unwrap();
+        assert_eq!(fixtures.len(), corpus().len() + 1);
+        assert!(fixtures.windows(2).all(|pair| pair[0].name < pair[1].name));
+        let windows = fixtures.last().unwrap();
+        assert_eq!(windows.name, "windows-discover");
+        assert_eq!(&windows.data, discover);
+
+        for fixture in &fixtures {
+            if fixture.name.starts_with("valid-") || fixture.name == "windows-discover" {
+                fixture.check().unwrap();
+            }
+        }
+        let fixture = fixtures
+            .iter()
+            .find(|fixture| fixture.name == "malformed-bad-magic-cookie")
+            .unwrap();
+        assert!(fixture.check().is_err());
+
+        fs::write(directory.join("broken.hex"), "01 0").unwrap();
+        assert!(load_fixtures(&directory).is_err());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,77 @@
+use std::net::Ipv4Addr;
+
+use dhcp::custom::CustomOption;
+use dhcp::dhcp_options;
+use dhcp::error::DhcpError;
+use dhcp::message::DhcpMessage;
+use dhcp::option::DhcpOption;
+
+dhcp_options! {
+    /// The server to fall back to.
+    224 => BackupServer(Ipv4Addr),
+    225 => Mirrors(Vec<Ipv4Addr>),
+    230 => SiteTag(String),
+    231 => Provisioned(bool),
+    232 => Rack(u16),
+    233 => AssetId([u8; 6]),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_option_round_trip() {
+        let mut message = DhcpMessage::new(2, 1);
+        message.options = vec![
+            BackupServer(Ipv4Addr::new(10, 0, 0, 2)).into(),
+            Mirrors(vec![Ipv4Addr::new(10, 0, 0, 3), Ipv4Addr::new(10, 0, 0, 4)]).into(),
+            SiteTag("lab-2".to_string()).into(),
+            Provisioned(true).into(),
+            Rack(0x0102).into(),
+        ]
+        .into();
+        assert_eq!(
+            message.options[0],
+            DhcpOption::Unknown(224, vec![10, 0, 0, 2])
+        );
+        assert_eq!(message.options[4], DhcpOption::Unknown(232, vec![1, 2]));
+
+        let message = DhcpMessage::deserialize(&message.serialize().unwrap()).unwrap();
+        assert_eq!(
+            BackupServer::get(&message).unwrap(),
+            Some(BackupServer(Ipv4Addr::new(10, 0, 0, 2)))
+        );
+        assert_eq!(Mirrors::get(&message).unwrap().unwrap().0.len(), 2);
+        assert_eq!(
+            SiteTag::get(&message).unwrap(),
+            Some(SiteTag("lab-2".to_string()))
+        );
+        assert_eq!(Provisioned::get(&message).unwrap(), Some(Provisioned(true)));
+        assert_eq!(Rack::get(&message).unwrap(), Some(Rack(0x0102)));
+        assert_eq!(AssetId::get(&message).unwrap(), None);
+        assert_eq!(AssetId::CODE, 233);
+    }
+
+    #[test]
+    fn custom_option_invalid_values() {
+        let option = DhcpOption::Unknown(224, vec![10, 0, 0]);
+        assert!(matches!(
+            BackupServer::from_option(&option),
+            Err(DhcpError::InvalidLength { code: 224, len: 3 })
+        ));
+        let option = DhcpOption::Unknown(230, vec![0xff]);
+        assert!(matches!(
+            SiteTag::from_option(&option),
+            Err(DhcpError::InvalidUtf8 { code: 230 })
+        ));
+        let option = DhcpOption::Unknown(231, vec![2]);
+        assert!(Provisioned::from_option(&option).is_err());
+        let option = DhcpOption::Unknown(233, vec![0; 5]);
+        assert!(AssetId::from_option(&option).is_err());
+
+        // Options with another code are not decoded.
+        let option = DhcpOption::Unknown(225, vec![10, 0, 0, 2]);
+        assert_eq!(BackupServer::from_option(&option).unwrap(), None);
+    }
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "pnet")]
+
+use std::io;
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::sync::mpsc::{Receiver, Sender};
+
+use dhcp::conformance::Exchange;
+use dhcp::datalink::{Datagram, DatalinkTransport};
+use dhcp::error::DhcpError;
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::option::DhcpOption;
+use pnet_datalink::dummy;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAC: [u8; 6] = [0x02, 0, 0, 0, 0, 9];
+
+    type Network = (Sender<io::Result<Box<[u8]>>>, Receiver<Box<[u8]>>);
+
+    fn transport() -> (DatalinkTransport, Network) {
+        let mut config = dummy::Config::default();
+        let network = (
+            config.inject_handle().unwrap(),
+            config.read_handle().unwrap(),
+        );
+        let channel = dummy::channel(&dummy::dummy_interface(0), config).unwrap();
+        (DatalinkTransport::new(channel, MAC).unwrap(), network)
+    }
+
+    fn message(op: u8, message_type: MessageType) -> DhcpMessage {
+        let mut message = DhcpMessage::new(op, 0x1234);
+        message.chaddr[..6].copy_from_slice(&[0, 1, 2, 3, 4, 5]);
+        message.options = vec![DhcpOption::from(message_type)].into();
+        message
+    }
+
+    #[test]
+    fn datalink_send_recv() {
+        let (mut transport, (inject, read)) = transport();
+        let offer = message(2, MessageType::Offer);
+        let source = SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 67);
+        let destination = SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 10), 68);
+        transport.send(source, destination, &offer).unwrap();
+
+        // Replies go to the client hardware address, from the transport.
+        let frame = read.recv().unwrap();
+        assert_eq!(frame[..6], [0, 1, 2, 3, 4, 5]);
+        assert_eq!(frame[6..12], MAC);
+
+        // Other traffic is skipped.
+        inject.send(Ok(vec![0; 60].into_boxed_slice())).unwrap();
+        inject.send(Ok(frame)).unwrap();
+        assert_eq!(
+            transport.recv().unwrap(),
+            Datagram {
+                source,
+                destination,
+                message: offer,
+            }
+        );
+
+        // Requests can only be broadcast.
+        let discover = message(1, MessageType::Discover);
+        assert!(matches!(
+            transport.send(source, destination, &discover),
+            Err(DhcpError::Transport(_))
+        ));
+        let broadcast = SocketAddrV4::new(Ipv4Addr::BROADCAST, 67);
+        transport.send(source, broadcast, &discover).unwrap();
+        assert_eq!(read.recv().unwrap()[..6], [0xFF; 6]);
+    }
+
+    #[test]
+    fn datalink_exchange() {
+        let (mut server, (_, server_read)) = transport();
+        let (mut client, (inject, read)) = transport();
+        let mut offer = message(2, MessageType::Offer);
+        offer.yiaddr = Ipv4Addr::new(10, 0, 0, 10);
+        let mut other = offer.clone();
+        other.xid = 0x5678;
+
+        // Replies to other transactions are skipped.
+        let source = SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 67);
+        let destination = SocketAddrV4::new(Ipv4Addr::BROADCAST, 68);
+        for reply in [&other, &offer] {
+            server.send(source, destination, reply).unwrap();
+            inject.send(Ok(server_read.recv().unwrap())).unwrap();
+        }
+        let discover = message(1, MessageType::Discover);
+        assert_eq!(client.exchange(&discover).unwrap(), Some(offer));
+
+        // The request was broadcast from 0.0.0.0:68.
+        let frame = read.recv().unwrap();
+        assert_eq!(frame[..6], [0xFF; 6]);
+        assert_eq!(frame[14 + 12..14 + 20], [0, 0, 0, 0, 255, 255, 255, 255]);
+        assert_eq!(frame[14 + 20..14 + 24], [0, 68, 0, 67]);
+    }
+
+    #[test]
+    fn datalink_timeout() {
+        let (mut transport, (inject, _read)) = transport();
+        inject
+            .send(Err(io::Error::from(io::ErrorKind::TimedOut)))
+            .unwrap();
+        assert!(matches!(transport.recv(), Err(DhcpError::Timeout)));
+        inject
+            .send(Err(io::Error::from(io::ErrorKind::TimedOut)))
+            .unwrap();
+        assert_eq!(
+            transport
+                .exchange(&message(1, MessageType::Discover))
+                .unwrap(),
+            None
+        );
+
+        assert!(DatalinkTransport::open("no-such-interface", None).is_err());
+    }
+}
@@ -0,0 +1,223 @@
+#![cfg(feature = "ddns")]
+
+use std::net::{Ipv4Addr, UdpSocket};
+use std::thread;
+use std::time::Duration;
+
+use dhcp::ddns::{
+    check_response, reverse_name, update_message, DdnsSink, DnsUpdater, RecordChange, RecordData,
+    Rfc2136Updater, TsigKey,
+};
+use dhcp::error::DhcpError;
+use dhcp::lease::{LeaseEvent, LeaseEventSink, LeaseState};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIME: u32 = 1_700_000_000;
+
+    fn key() -> TsigKey {
+        TsigKey::new("dhcp-key", b"0123456789abcdef")
+    }
+
+    // Answer `count` updates, checking their signature, and return them.
+    fn serve(socket: UdpSocket, count: usize) -> thread::JoinHandle<Vec<Vec<u8>>> {
+        thread::spawn(move || {
+            let mut updates = Vec::new();
+            let mut buffer = [0; 1500];
+            for _ in 0..count {
+                let (len, client) = socket.recv_from(&mut buffer).unwrap();
+                let update = buffer[..len].to_vec();
+                key().verify(&update, None, TIME).unwrap();
+
+                // The MAC closes the TSIG record, before the original ID,
+                // error and other length fields.
+                let request_mac = update[len - 6 - 16..len - 6].to_vec();
+                let mut response = update_message(
+                    u16::from_be_bytes([update[0], update[1]]),
+                    "example.com",
+                    &[],
+                )
+                .unwrap();
+                response[2] |= 0x80;
+                key().sign(&mut response, Some(&request_mac), TIME).unwrap();
+                socket.send_to(&response, client).unwrap();
+                updates.push(update);
+            }
+            updates
+        })
+    }
+
+    #[test]
+    fn ddns_update_message() {
+        let message = update_message(
+            0x1234,
+            "example.com",
+            &[
+                RecordChange::DeleteAll {
+                    name: "host.example.com".to_string(),
+                    rtype: 1,
+                },
+                RecordChange::Add {
+                    name: "host.example.com".to_string(),
+                    ttl: 3600,
+                    data: RecordData::A(Ipv4Addr::new(192, 168, 1, 100)),
+                },
+            ],
+        )
+        .unwrap();
+
+        let mut expected = vec![0x12, 0x34, 0x28, 0, 0, 1, 0, 0, 0, 2, 0, 0];
+        expected.extend_from_slice(b"\x07example\x03com\x00\x00\x06\x00\x01");
+        expected.extend_from_slice(b"\x04host\x07example\x03com\x00\x00\x01\x00\xff");
+        expected.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        expected.extend_from_slice(b"\x04host\x07example\x03com\x00\x00\x01\x00\x01");
+        expected.extend_from_slice(&[0, 0, 0x0e, 0x10, 0, 4, 192, 168, 1, 100]);
+        assert_eq!(message, expected);
+
+        assert_eq!(
+            reverse_name(Ipv4Addr::new(192, 168, 1, 100)),
+            "100.1.168.192.in-addr.arpa"
+        );
+
+        let label = "a".repeat(64);
+        assert!(update_message(1, &label, &[]).is_err());
+        assert!(update_message(
+            1,
+            "example.com",
+            &[RecordChange::Delete {
+                name: "1.168.192.in-addr.arpa".to_string(),
+                data: RecordData::Ptr(format!("{}.example.com", label)),
+            }],
+        )
+        .is_err());
+        let mut message = update_message(1, "example.com", &[]).unwrap();
+        let length = message.len();
+        assert!(TsigKey::new(&label, b"secret")
+            .sign(&mut message, None, TIME)
+            .is_err());
+        assert_eq!(message.len(), length);
+    }
+
+    #[test]
+    fn ddns_check_response() {
+        let mut response = update_message(7, "example.com", &[]).unwrap();
+        assert!(check_response(&response, 7).is_err());
+        response[2] |= 0x80;
+        assert!(check_response(&response, 7).is_ok());
+        assert!(check_response(&response, 8).is_err());
+        // REFUSED.
+        response[3] = 5;
+        assert!(check_response(&response, 7).is_err());
+        assert!(check_response(&response[..4], 7).is_err());
+    }
+
+    #[test]
+    fn ddns_tsig() {
+        let mut message = update_message(1, "example.com", &[]).unwrap();
+        let mac = key().sign(&mut message, None, TIME).unwrap();
+        assert_eq!(message[11], 1);
+        assert!(key().verify(&message, None, TIME).is_ok());
+        assert!(key().verify(&message, None, TIME + 200).is_ok());
+
+        let mut response = update_message(1, "example.com", &[]).unwrap();
+        key().sign(&mut response, Some(&mac), TIME).unwrap();
+        assert!(key().verify(&response, Some(&mac), TIME).is_ok());
+        assert!(key().verify(&response, None, TIME).is_err());
+
+        assert!(matches!(
+            key().verify(&message, None, TIME + 1000),
+            Err(DhcpError::AuthenticationError(_))
+        ));
+        assert!(TsigKey::new("dhcp-key", b"other")
+            .verify(&message, None, TIME)
+            .is_err());
+        assert!(TsigKey::new("other-key", b"0123456789abcdef")
+            .verify(&message, None, TIME)
+            .is_err());
+        let mut tampered = message.clone();
+        tampered[HEADER_ZONE_TYPE] ^= 1;
+        assert!(key().verify(&tampered, None, TIME).is_err());
+        assert!(key()
+            .verify(&update_message(1, "example.com", &[]).unwrap(), None, TIME)
+            .is_err());
+    }
+
+    // Offset of the zone type, after the header and "example.com".
+    const HEADER_ZONE_TYPE: usize = 12 + 13 + 1;
+
+    #[test]
+    fn ddns_updater() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = server.local_addr().unwrap();
+        let handle = serve(server, 4);
+
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let updater = Rfc2136Updater::new(socket, address, "example.com", "1.168.192.in-addr.arpa")
+            .with_key(key());
+        let mut sink = DdnsSink::new(updater);
+        sink.set_host_name([2, 0, 0, 0, 0, 1], "laptop");
+
+        let mut event = LeaseEvent {
+            ip: Ipv4Addr::new(192, 168, 1, 100),
+            mac: [2, 0, 0, 0, 0, 1],
+            client_identifier: None,
+            state: LeaseState::Active,
+            lease_time: 3600,
+            timestamp: TIME,
+        };
+        sink.lease_event(&event);
+        event.state = LeaseState::Released;
+        sink.lease_event(&event);
+        assert!(sink.take_errors().is_empty());
+
+        let updates = handle.join().unwrap();
+        let contains = |update: &[u8], name: &[u8]| update.windows(name.len()).any(|w| w == name);
+        assert!(contains(
+            &updates[0],
+            b"\x06laptop\x07example\x03com\x00\x00\x01\x00\x01"
+        ));
+        assert!(contains(
+            &updates[1],
+            b"\x03100\x011\x03168\x03192\x07in-addr\x04arpa\x00\x00\x0c\x00\x01"
+        ));
+        assert!(contains(
+            &updates[2],
+            b"\x06laptop\x07example\x03com\x00\x00\x01\x00\xfe"
+        ));
+        assert!(contains(&updates[3], b"\x00\x0c\x00\xfe"));
+    }
+
+    #[test]
+    fn ddns_sink_errors() {
+        struct Failing;
+
+        impl DnsUpdater for Failing {
+            fn add(&mut self, name: &str, _: Ipv4Addr, _: u32, _: u32) -> Result<(), DhcpError> {
+                Err(DhcpError::ParsingError(name.to_string().into()))
+            }
+
+            fn remove(&mut self, _: &str, _: Ipv4Addr, _: u32) -> Result<(), DhcpError> {
+                Ok(())
+            }
+        }
+
+        let mut sink = DdnsSink::new(Failing);
+        sink.lease_event(&LeaseEvent {
+            ip: Ipv4Addr::new(192, 168, 1, 100),
+            mac: [2, 0, 0, 0, 0, 1],
+            client_identifier: None,
+            state: LeaseState::Active,
+            lease_time: 3600,
+            timestamp: TIME,
+        });
+        let errors = sink.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(&errors[0], DhcpError::ParsingError(name) if name == "dhcp-020000000001"));
+        assert!(sink.take_errors().is_empty());
+    }
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "defmt")]
+
+use std::net::Ipv4Addr;
+use std::sync::Mutex;
+
+use dhcp::error::{DhcpError, NakReason, SerializationError};
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::option::DhcpOption;
+
+// The frames written by defmt, for the tests to check that each value is
+// encoded.
+static FRAMES: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+#[defmt::global_logger]
+struct Logger;
+
+unsafe impl defmt::Logger for Logger {
+    fn acquire() {}
+
+    unsafe fn flush() {}
+
+    unsafe fn release() {}
+
+    unsafe fn write(bytes: &[u8]) {
+        FRAMES.lock().unwrap().extend_from_slice(bytes);
+    }
+}
+
+defmt::timestamp!("{=u32}", 0);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The length of the frame written for `value`.
+    fn encoded<T: defmt::Format>(value: &T) -> usize {
+        let mut frames = FRAMES.lock().unwrap();
+        frames.clear();
+        drop(frames);
+        defmt::println!("{}", value);
+        FRAMES.lock().unwrap().len()
+    }
+
+    #[test]
+    fn defmt_format() {
+        let mut message = DhcpMessage::new(1, 0x1234);
+        message.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Discover),
+            DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 1)].into()),
+            DhcpOption::HostName("host".to_string()),
+        ]
+        .into();
+        let empty = encoded(&DhcpMessage::new(1, 0x1234));
+        assert!(encoded(&message) > empty);
+        assert!(encoded(&message.options[1]) > 4);
+
+        let errors = [
+            DhcpError::Nak(NakReason::AddressInUse),
+            DhcpError::SerializationError(SerializationError::MissingValue { code: 61 }),
+            DhcpError::Io(std::io::ErrorKind::TimedOut.into()),
+            DhcpError::Context {
+                offset: 240,
+                field: "options",
+                code: Some(3),
+                error: Box::new(DhcpError::InvalidLength { code: 3, len: 2 }),
+            },
+        ];
+        for error in &errors {
+            assert!(encoded(error) > 0);
+            assert!(encoded(&error.kind()) > 0);
+        }
+    }
+}
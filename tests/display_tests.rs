@@ -0,0 +1,83 @@
+use std::net::Ipv4Addr;
+
+use dhcp::builder::DhcpMessageBuilder;
+use dhcp::message::MessageType;
+use dhcp::option::{DhcpOption, OptionOverload};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_message() {
+        let mut discover = DhcpMessageBuilder::discover()
+            .xid(0x3903F326)
+            .broadcast(true)
+            .chaddr([0, 1, 2, 3, 4, 5])
+            .parameter_request(&[1, 3, 224])
+            .host_name("client")
+            .build()
+            .unwrap();
+        discover.file[..8].copy_from_slice(b"pxelinux");
+
+        assert_eq!(
+            discover.to_string(),
+            "BOOTREQUEST (1), htype 1, hlen 6, hops 0, xid 0x3903f326, secs 0, flags 0x8000 (broadcast)
+  ciaddr 0.0.0.0
+  yiaddr 0.0.0.0
+  siaddr 0.0.0.0
+  giaddr 0.0.0.0
+  chaddr 00:01:02:03:04:05
+  file \"pxelinux\"
+  options:
+    DHCP Message Type (53), length 1: DHCPDISCOVER
+    Parameter Request List (55), length 3: Subnet Mask (1), Router (3), 224
+    Host Name (12), length 6: \"client\""
+        );
+    }
+
+    #[test]
+    fn display_options() {
+        let cases = [
+            (
+                DhcpOption::Router(
+                    vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)].into(),
+                ),
+                "Router (3), length 8: 10.0.0.1, 10.0.0.2",
+            ),
+            (
+                DhcpOption::IpAddressLeaseTime(3600),
+                "IP Address Lease Time (51), length 4: 3600s",
+            ),
+            (
+                DhcpOption::TimeOffset(-3600i32 as u32),
+                "Time Offset (2), length 4: -3600s",
+            ),
+            (
+                DhcpOption::IpForwarding(true),
+                "IP Forwarding (19), length 1: yes",
+            ),
+            (
+                DhcpOption::ClientIdentifier(vec![1, 0xaa, 0xbb]),
+                "Client Identifier (61), length 3: 01:aa:bb",
+            ),
+            (
+                DhcpOption::OptionOverload(OptionOverload::Both),
+                "Option Overload (52), length 1: file and sname",
+            ),
+            (
+                DhcpOption::ServerIdentifier(Ipv4Addr::new(192, 168, 1, 1)),
+                "Server Identifier (54), length 4: 192.168.1.1",
+            ),
+            (
+                DhcpOption::Unknown(224, vec![1, 2]),
+                "Unknown (224), length 2: 01:02",
+            ),
+            (DhcpOption::Pad, "Pad (0)"),
+        ];
+        for (option, expected) in cases {
+            assert_eq!(option.to_string(), expected);
+        }
+        assert_eq!(MessageType::Ack.to_string(), "DHCPACK");
+    }
+}
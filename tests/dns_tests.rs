@@ -0,0 +1,100 @@
+use dhcp::dns::{decode_name, decode_names, encode_name, encode_names};
+use dhcp::error::{DhcpError, SerializationError};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dns_encode_name() {
+        let mut buffer = Vec::new();
+        encode_name("example.com", &mut buffer).unwrap();
+        assert_eq!(buffer, b"\x07example\x03com\x00".to_vec());
+
+        let mut trailing_dot = Vec::new();
+        encode_name("example.com.", &mut trailing_dot).unwrap();
+        assert_eq!(trailing_dot, buffer);
+
+        let mut root = Vec::new();
+        encode_name("", &mut root).unwrap();
+        assert_eq!(root, vec![0]);
+    }
+
+    #[test]
+    fn dns_encode_name_limits() {
+        let mut buffer = vec![1];
+        let label = "a".repeat(63);
+        assert!(encode_name(&label, &mut buffer).is_ok());
+
+        let mut buffer = vec![1];
+        let label = "a".repeat(64);
+        assert!(matches!(
+            encode_name(&format!("{}.com", label), &mut buffer),
+            Err(DhcpError::SerializationError(
+                SerializationError::ValueTooLong { len: 64, .. }
+            ))
+        ));
+        // Nothing is written on error.
+        assert_eq!(buffer, vec![1]);
+
+        // Four 63-byte labels take 4 * 64 + 1 = 257 bytes.
+        let name = vec![label[1..].to_string(); 4].join(".");
+        assert!(matches!(
+            encode_name(&name, &mut buffer),
+            Err(DhcpError::SerializationError(
+                SerializationError::ValueTooLong { len: 257, .. }
+            ))
+        ));
+        // Three labels and a 61-byte one take exactly 255 bytes.
+        let name = format!(
+            "{}.{}",
+            vec![label[1..].to_string(); 3].join("."),
+            &label[3..]
+        );
+        assert!(encode_name(&name, &mut buffer).is_ok());
+        assert_eq!(buffer.len(), 1 + 255);
+
+        assert!(encode_names(&["example.com".to_string(), "a".repeat(64)]).is_err());
+    }
+
+    #[test]
+    fn dns_names_round_trip() {
+        let names = vec!["eng.example.com".to_string(), "example.com".to_string()];
+        let data = encode_names(&names).unwrap();
+        assert_eq!(decode_names(&data).unwrap(), names);
+    }
+
+    #[test]
+    fn dns_decode_compressed_names() {
+        // The RFC 3397 example: eng.apple.com and marketing.apple.com.
+        let data = b"\x03eng\x05apple\x03com\x00\x09marketing\xC0\x04";
+        assert_eq!(
+            decode_names(data).unwrap(),
+            vec![
+                "eng.apple.com".to_string(),
+                "marketing.apple.com".to_string()
+            ]
+        );
+
+        let (name, next) = decode_name(data, 15).unwrap();
+        assert_eq!(name, "marketing.apple.com");
+        assert_eq!(next, data.len());
+    }
+
+    #[test]
+    fn dns_decode_errors() {
+        // Truncated label.
+        assert!(decode_names(b"\x07exam").is_err());
+        // Missing root label.
+        assert!(decode_names(b"\x03com").is_err());
+        // Truncated pointer.
+        assert!(decode_names(b"\xC0").is_err());
+        // Pointer loop.
+        assert!(decode_names(b"\xC0\x00").is_err());
+        // Label too long.
+        let mut data = vec![64];
+        data.extend_from_slice(&[b'a'; 64]);
+        data.push(0);
+        assert!(decode_names(&data).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use dhcp::dualstack::DualStackConfig;
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use dhcp::option::DhcpOption;
+    use dhcp::v6::option::{Dhcpv6Option, NtpServer};
+
+    use super::*;
+
+    fn config() -> DualStackConfig {
+        DualStackConfig {
+            dns_servers_v4: vec![Ipv4Addr::new(192, 0, 2, 53)],
+            dns_servers_v6: vec!["2001:db8::53".parse::<Ipv6Addr>().unwrap()],
+            domain_search: vec!["example.com".to_string(), "example.net".to_string()],
+            ntp_servers_v4: vec![Ipv4Addr::new(192, 0, 2, 123)],
+            ntp_servers_v6: vec!["2001:db8::123".parse::<Ipv6Addr>().unwrap()],
+            ntp_server_names: vec!["ntp.example.com".to_string()],
+        }
+    }
+
+    #[test]
+    fn dualstack_v4_options() {
+        assert_eq!(
+            config().v4_options(),
+            vec![
+                DhcpOption::DomainNameServer(vec![Ipv4Addr::new(192, 0, 2, 53)].into()),
+                DhcpOption::DomainName("example.com".to_string()),
+                DhcpOption::DomainSearch(vec![
+                    "example.com".to_string(),
+                    "example.net".to_string()
+                ]),
+                DhcpOption::NetworkTimeProtocolServers(vec![Ipv4Addr::new(192, 0, 2, 123)].into()),
+            ]
+        );
+        assert!(DualStackConfig::new().v4_options().is_empty());
+    }
+
+    #[test]
+    fn dualstack_v6_options() {
+        assert_eq!(
+            config().v6_options(),
+            vec![
+                Dhcpv6Option::DnsServers(vec!["2001:db8::53".parse().unwrap()]),
+                Dhcpv6Option::DomainList(vec![
+                    "example.com".to_string(),
+                    "example.net".to_string()
+                ]),
+                Dhcpv6Option::NtpServer(vec![NtpServer::Address("2001:db8::123".parse().unwrap())]),
+                Dhcpv6Option::NtpServer(vec![NtpServer::Fqdn("ntp.example.com".to_string())]),
+            ]
+        );
+        assert!(DualStackConfig::new().v6_options().is_empty());
+    }
+
+    #[test]
+    fn dualstack_round_trip_through_both_models() {
+        let config = config();
+        let mut merged = DualStackConfig::from_v4_options(&config.v4_options());
+        merged.merge_v6_options(&config.v6_options());
+        assert_eq!(merged, config);
+    }
+
+    #[test]
+    fn dualstack_translate_v4_to_v6() {
+        let options = vec![
+            DhcpOption::DomainName("corp.example".to_string()),
+            DhcpOption::DomainNameServer(vec![Ipv4Addr::new(10, 0, 0, 1)].into()),
+        ];
+        let config = DualStackConfig::from_v4_options(&options);
+        assert_eq!(
+            config.v6_options(),
+            vec![Dhcpv6Option::DomainList(vec!["corp.example".to_string()])]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use dhcp::duid::{Duid, NodeSpecificClientId};
+
+#[cfg(test)]
+mod tests {
+    use dhcp::option::DhcpOption;
+
+    use super::*;
+
+    #[test]
+    fn duid_llt_round_trip() {
+        let duid = Duid::LinkLayerTime {
+            hardware_type: 1,
+            time: 0x12345678,
+            link_layer_address: vec![0, 17, 34, 51, 68, 85],
+        };
+        let data = duid.serialize();
+        assert_eq!(
+            data,
+            vec![0, 1, 0, 1, 0x12, 0x34, 0x56, 0x78, 0, 17, 34, 51, 68, 85]
+        );
+        assert_eq!(Duid::deserialize(&data).unwrap(), duid);
+        assert_eq!(duid.duid_type(), 1);
+    }
+
+    #[test]
+    fn duid_en_round_trip() {
+        let duid = Duid::Enterprise {
+            enterprise_number: 9,
+            identifier: vec![1, 2, 3],
+        };
+        let data = duid.serialize();
+        assert_eq!(data, vec![0, 2, 0, 0, 0, 9, 1, 2, 3]);
+        assert_eq!(Duid::deserialize(&data).unwrap(), duid);
+    }
+
+    #[test]
+    fn duid_ll_round_trip() {
+        let duid = Duid::LinkLayer {
+            hardware_type: 1,
+            link_layer_address: vec![0, 17, 34, 51, 68, 85],
+        };
+        let data = duid.serialize();
+        assert_eq!(data, vec![0, 3, 0, 1, 0, 17, 34, 51, 68, 85]);
+        assert_eq!(Duid::deserialize(&data).unwrap(), duid);
+    }
+
+    #[test]
+    fn duid_uuid_round_trip() {
+        let duid = Duid::Uuid([7; 16]);
+        let data = duid.serialize();
+        assert_eq!(data.len(), 18);
+        assert_eq!(&data[..2], &[0, 4]);
+        assert_eq!(Duid::deserialize(&data).unwrap(), duid);
+
+        assert!(Duid::deserialize(&data[..17]).is_err());
+    }
+
+    #[test]
+    fn duid_unknown_round_trip() {
+        let data = vec![0, 42, 1, 2, 3];
+        let duid = Duid::deserialize(&data).unwrap();
+        assert_eq!(
+            duid,
+            Duid::Unknown {
+                duid_type: 42,
+                data: vec![1, 2, 3]
+            }
+        );
+        assert_eq!(duid.serialize(), data);
+    }
+
+    #[test]
+    fn duid_deserialize_errors() {
+        assert!(Duid::deserialize(&[]).is_err());
+        assert!(Duid::deserialize(&[0, 1]).is_err());
+        assert!(Duid::deserialize(&[0, 1, 0, 1, 0]).is_err());
+        assert!(Duid::deserialize(&[0, 2, 0, 0, 0]).is_err());
+        assert!(Duid::deserialize(&[0, 3, 0]).is_err());
+        assert!(Duid::deserialize(&[0; 131]).is_err());
+    }
+
+    #[test]
+    fn duid_display_and_parse() {
+        let duid = Duid::LinkLayer {
+            hardware_type: 1,
+            link_layer_address: vec![0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF],
+        };
+        assert_eq!(duid.to_string(), "00:03:00:01:aa:bb:cc:dd:ee:ff");
+        assert_eq!(
+            "00:03:00:01:aa:bb:cc:dd:ee:ff".parse::<Duid>().unwrap(),
+            duid
+        );
+        assert_eq!("00030001AABBCCDDEEFF".parse::<Duid>().unwrap(), duid);
+
+        assert!("00:03:0".parse::<Duid>().is_err());
+        assert!("zz:03:00:01".parse::<Duid>().is_err());
+        assert!("é0030001".parse::<Duid>().is_err());
+    }
+
+    #[test]
+    fn duid_node_specific_client_id() {
+        let client_id = NodeSpecificClientId::new(
+            1,
+            Duid::LinkLayer {
+                hardware_type: 1,
+                link_layer_address: vec![0, 17, 34, 51, 68, 85],
+            },
+        );
+        let data = client_id.serialize();
+        assert_eq!(
+            data,
+            vec![255, 0, 0, 0, 1, 0, 3, 0, 1, 0, 17, 34, 51, 68, 85]
+        );
+        assert_eq!(NodeSpecificClientId::deserialize(&data).unwrap(), client_id);
+
+        // The identifier travels in option 61.
+        let option = DhcpOption::ClientIdentifier(data.clone());
+        let serialized = option.serialize().unwrap();
+        let (option, _) = DhcpOption::deserialize(&serialized).unwrap();
+        match option {
+            DhcpOption::ClientIdentifier(value) => {
+                assert_eq!(
+                    NodeSpecificClientId::deserialize(&value).unwrap(),
+                    client_id
+                )
+            }
+            _ => panic!("expected client identifier"),
+        }
+
+        assert!(NodeSpecificClientId::deserialize(&[1, 0, 17, 34, 51, 68, 85]).is_err());
+        assert!(NodeSpecificClientId::deserialize(&[255, 0, 0, 0]).is_err());
+    }
+}
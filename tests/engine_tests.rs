@@ -0,0 +1,83 @@
+#![cfg(feature = "tokio")]
+
+use std::net::UdpSocket;
+
+use dhcp::engine::Engine;
+use dhcp::lease::{LeaseEvent, LeaseState};
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::option::DhcpOption;
+use dhcp::server::{Server, ServerConfig};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        server_identifier = "10.0.0.1"
+        pool_start = "10.0.0.10"
+        pool_end = "10.0.0.11"
+        subnet_mask = "255.255.255.0"
+    "#;
+
+    fn engine() -> (Engine, UdpSocket) {
+        let server = Server::new(ServerConfig::from_config(CONFIG).unwrap());
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client.connect(socket.local_addr().unwrap()).unwrap();
+        (Engine::new(server, socket).unwrap(), client)
+    }
+
+    fn request(message_type: MessageType, options: Vec<DhcpOption>) -> Vec<u8> {
+        let mut message = DhcpMessage::new(1, 1);
+        message.chaddr[..6].copy_from_slice(&[0, 0, 0, 0, 0, 1]);
+        message.options = vec![DhcpOption::from(message_type)].into();
+        message.options.extend(options);
+        message.serialize().unwrap()
+    }
+
+    #[tokio::test]
+    async fn engine_serve_batch() {
+        let (mut engine, client) = engine();
+        client
+            .send(&request(MessageType::Discover, vec![]))
+            .unwrap();
+        let request = request(
+            MessageType::Request,
+            vec![DhcpOption::RequestedIpAddress([10, 0, 0, 10].into())],
+        );
+        client.send(&request).unwrap();
+
+        // Both requests are handled together.
+        let mut events: Vec<LeaseEvent> = Vec::new();
+        assert_eq!(engine.serve_batch(&mut events).await.unwrap(), 2);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].state, LeaseState::Active);
+        assert_eq!(engine.server().leases().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn engine_errors() {
+        let (mut engine, client) = engine();
+        let address = client.local_addr().unwrap();
+        client.send(&[1, 2, 3]).unwrap();
+        assert_eq!(engine.serve_batch(&mut Vec::new()).await.unwrap(), 0);
+
+        let errors = engine.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, address);
+        assert!(engine.take_errors().is_empty());
+    }
+
+    #[tokio::test]
+    async fn engine_batch_len() {
+        let (engine, client) = engine();
+        let mut engine = engine.with_batch_len(1);
+        for _ in 0..2 {
+            client
+                .send(&request(MessageType::Discover, vec![]))
+                .unwrap();
+        }
+        assert_eq!(engine.serve_batch(&mut Vec::new()).await.unwrap(), 1);
+        assert_eq!(engine.serve_batch(&mut Vec::new()).await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,150 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::io;
+use std::net::UdpSocket;
+use std::time::Duration;
+
+use dhcp::error::{DhcpError, ErrorKind, NakReason, SerializationError};
+use dhcp::message::{DhcpMessage, MessageType, OpCode};
+use dhcp::option::DhcpOption;
+use dhcp::v6::option::Dhcpv6Option;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receive(socket: &UdpSocket) -> Result<usize, DhcpError> {
+        let mut buffer = [0; 16];
+        Ok(socket.recv(&mut buffer)?)
+    }
+
+    #[test]
+    fn error_from_io() {
+        let error = DhcpError::from(io::Error::new(io::ErrorKind::NotFound, "missing"));
+        assert!(matches!(&error, DhcpError::Io(error) if error.kind() == io::ErrorKind::NotFound));
+        assert_eq!(error.to_string(), "IO Error: missing");
+
+        assert!(matches!(
+            DhcpError::from(io::Error::from(io::ErrorKind::TimedOut)),
+            DhcpError::Timeout
+        ));
+
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_millis(10)))
+            .unwrap();
+        assert!(matches!(receive(&socket), Err(DhcpError::Timeout)));
+    }
+
+    #[test]
+    fn error_kind() {
+        assert_eq!(
+            DhcpError::InvalidLength { code: 1, len: 3 }.kind(),
+            ErrorKind::Malformed
+        );
+        assert_eq!(
+            DhcpError::UnknownOptionCode(200).kind(),
+            ErrorKind::Unsupported
+        );
+        assert_eq!(
+            DhcpError::Transport("Bad reply".to_string()).kind(),
+            ErrorKind::Protocol
+        );
+        assert_eq!(DhcpError::Timeout.kind(), ErrorKind::Io);
+        assert!(DhcpError::Timeout.is_transient());
+        assert!(!DhcpError::UnknownOptionCode(200).is_transient());
+        assert!(ErrorKind::Exhausted.is_transient());
+
+        let error = DhcpMessage::deserialize(&[0; 10]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Malformed);
+        assert!(!error.is_transient());
+    }
+
+    #[test]
+    fn error_source() {
+        let error = DhcpError::from(io::Error::new(io::ErrorKind::NotFound, "missing"));
+        assert_eq!(error.source().unwrap().to_string(), "missing");
+
+        let error = DhcpError::SerializationError(SerializationError::MissingValue { code: 61 });
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            "Option 61 is missing its value"
+        );
+
+        let error = DhcpMessage::deserialize(&[0; 10]).unwrap_err();
+        assert!(matches!(error, DhcpError::Context { .. }));
+        let source = error.source().unwrap();
+        assert!(source.downcast_ref::<DhcpError>().is_some());
+        assert!(source.source().is_none());
+
+        assert!(DhcpError::Timeout.source().is_none());
+    }
+
+    #[test]
+    fn error_nak_reason() {
+        let reason = NakReason::WrongNetwork;
+        assert_eq!(reason.message(), "requested address not on this network");
+        assert_eq!(NakReason::from_message(reason.message()), reason);
+        assert_eq!(
+            NakReason::from_message("lease expired"),
+            NakReason::Other("lease expired".to_string())
+        );
+
+        let error = DhcpError::Nak(NakReason::AddressInUse);
+        assert_eq!(error.to_string(), "NAK: requested address already in use");
+        assert_eq!(error.kind(), ErrorKind::Protocol);
+    }
+
+    #[test]
+    fn error_static_messages() {
+        let error = DhcpMessage::deserialize(&[0; 10]).unwrap_err();
+        assert!(matches!(
+            error,
+            DhcpError::Context { error, .. }
+                if matches!(
+                    *error,
+                    DhcpError::UnexpectedEof {
+                        field: "header",
+                        needed: 240,
+                        available: 10
+                    }
+                )
+        ));
+        assert!(matches!(
+            Dhcpv6Option::deserialize_all(&[0, 1]),
+            Err(DhcpError::ParsingError(Cow::Borrowed(_)))
+        ));
+    }
+
+    #[test]
+    fn error_structured_variants() {
+        assert!(matches!(
+            OpCode::try_from(3),
+            Err(DhcpError::UnknownOpCode(3))
+        ));
+        let mut data = DhcpMessage::new(1, 1).serialize().unwrap();
+        data[236] = 0;
+        let error = DhcpMessage::deserialize(&data).unwrap_err();
+        assert!(matches!(
+            &error,
+            DhcpError::Context { offset: 236, error, .. }
+                if matches!(**error, DhcpError::MissingMagicCookie)
+        ));
+        assert_eq!(error.kind(), ErrorKind::Malformed);
+
+        assert!(matches!(
+            DhcpOption::deserialize(&[]),
+            Err(DhcpError::UnexpectedEof { needed: 1, .. })
+        ));
+        let error = DhcpOption::deserialize(&[15, 1, 0xff]).unwrap_err();
+        assert!(matches!(error, DhcpError::InvalidUtf8 { code: 15 }));
+        assert_eq!(
+            error.to_string(),
+            "Parsing Error: Option 15 is not valid UTF-8"
+        );
+
+        let error = MessageType::try_from(20).unwrap_err();
+        assert!(matches!(error, DhcpError::UnknownMessageType(20)));
+        assert_eq!(error.kind(), ErrorKind::Unsupported);
+    }
+}
@@ -0,0 +1,96 @@
+use dhcp::auth::forcerenew::{is_nonce_capable, nonce_capable_option, ForcerenewNonce};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONCE: [u8; 16] = [7; 16];
+
+    fn packet(op: u8, options: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0; 236];
+        packet[0] = op;
+        packet[1] = 1;
+        packet[2] = 6;
+        packet[28..34].copy_from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        packet.extend_from_slice(&[99, 130, 83, 99]);
+        packet.extend_from_slice(options);
+        packet.push(255);
+        packet
+    }
+
+    #[test]
+    fn forcerenew_nonce_capable() {
+        let option = nonce_capable_option().serialize().unwrap();
+        assert_eq!(option, vec![145, 1, 1]);
+
+        let request = packet(1, &[53, 1, 3, 145, 1, 1]);
+        assert!(is_nonce_capable(&request).unwrap());
+        assert!(!is_nonce_capable(&packet(1, &[53, 1, 3, 145, 1, 2])).unwrap());
+        assert!(!is_nonce_capable(&packet(1, &[53, 1, 3])).unwrap());
+    }
+
+    #[test]
+    fn forcerenew_nonce_in_ack() {
+        let server = ForcerenewNonce::new(NONCE);
+        let mut ack = packet(2, &[53, 1, 5]);
+        server.add_to_ack(&mut ack, 1).unwrap();
+        assert_eq!(
+            &ack[243..257],
+            &[90, 28, 3, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1]
+        );
+        assert_eq!(&ack[257..273], &NONCE);
+
+        let client = ForcerenewNonce::from_ack(&ack).unwrap();
+        assert_eq!(client.nonce(), &NONCE);
+
+        assert!(ForcerenewNonce::from_ack(&packet(2, &[53, 1, 5])).is_err());
+    }
+
+    #[test]
+    fn forcerenew_sign_and_verify() {
+        let server = ForcerenewNonce::new(NONCE);
+        let mut ack = packet(2, &[53, 1, 5]);
+        server.add_to_ack(&mut ack, 1).unwrap();
+        let mut client = ForcerenewNonce::from_ack(&ack).unwrap();
+
+        let mut forcerenew = packet(2, &[53, 1, 9]);
+        server.sign(&mut forcerenew, 2).unwrap();
+        assert_eq!(forcerenew[256], 2);
+        assert!(client.verify(&forcerenew).is_ok());
+
+        // Replayed messages are rejected.
+        assert!(client.verify(&forcerenew).is_err());
+    }
+
+    #[test]
+    fn forcerenew_verify_rejects_invalid() {
+        let server = ForcerenewNonce::new(NONCE);
+        let mut client = ForcerenewNonce::new(NONCE);
+
+        // Replay detection value not newer than the one in the DHCPACK.
+        let mut ack = packet(2, &[53, 1, 5]);
+        server.add_to_ack(&mut ack, 5).unwrap();
+        let mut acked = ForcerenewNonce::from_ack(&ack).unwrap();
+        let mut forcerenew = packet(2, &[53, 1, 9]);
+        server.sign(&mut forcerenew, 5).unwrap();
+        assert!(acked.verify(&forcerenew).is_err());
+
+        // Tampered message.
+        let mut tampered = forcerenew.clone();
+        tampered[16] = 10;
+        assert!(client.verify(&tampered).is_err());
+
+        // Other nonce.
+        let mut other = packet(2, &[53, 1, 9]);
+        ForcerenewNonce::new([8; 16]).sign(&mut other, 6).unwrap();
+        assert!(client.verify(&other).is_err());
+
+        // Not a FORCERENEW message.
+        let mut offer = packet(2, &[53, 1, 2]);
+        server.sign(&mut offer, 6).unwrap();
+        assert!(client.verify(&offer).is_err());
+
+        // Unauthenticated FORCERENEW.
+        assert!(client.verify(&packet(2, &[53, 1, 9])).is_err());
+    }
+}
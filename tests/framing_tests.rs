@@ -0,0 +1,46 @@
+use dhcp::framing::{encode_frame, read_frame, write_frame, FrameDecoder};
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn framing_encode() {
+        assert_eq!(encode_frame(&[1, 2, 3]).unwrap(), vec![0, 3, 1, 2, 3]);
+        assert_eq!(encode_frame(&[]).unwrap(), vec![0, 0]);
+        assert!(encode_frame(&vec![0; 65536]).is_err());
+    }
+
+    #[test]
+    fn framing_read_write() {
+        let mut stream = Vec::new();
+        write_frame(&mut stream, &[1, 2]).unwrap();
+        write_frame(&mut stream, &[3]).unwrap();
+
+        let mut reader = Cursor::new(stream);
+        assert_eq!(read_frame(&mut reader).unwrap(), Some(vec![1, 2]));
+        assert_eq!(read_frame(&mut reader).unwrap(), Some(vec![3]));
+        assert_eq!(read_frame(&mut reader).unwrap(), None);
+
+        // Truncated message.
+        let mut reader = Cursor::new(vec![0, 3, 1]);
+        assert!(read_frame(&mut reader).is_err());
+    }
+
+    #[test]
+    fn framing_decoder() {
+        let mut decoder = FrameDecoder::new();
+        decoder.push(&[0]);
+        assert_eq!(decoder.next_frame(), None);
+        decoder.push(&[2, 1]);
+        assert_eq!(decoder.next_frame(), None);
+        assert!(decoder.has_partial_frame());
+        decoder.push(&[2, 0, 1, 3]);
+        assert_eq!(decoder.next_frame(), Some(vec![1, 2]));
+        assert_eq!(decoder.next_frame(), Some(vec![3]));
+        assert_eq!(decoder.next_frame(), None);
+        assert!(!decoder.has_partial_frame());
+    }
+}
@@ -0,0 +1,89 @@
+use dhcp::hardware::{HardwareAddress, HardwareType};
+use dhcp::message::DhcpMessage;
+use dhcp::option::DhcpOption;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hardware_address_types() {
+        let mac = HardwareAddress::ethernet([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        assert_eq!(mac.htype(), HardwareType::Ethernet);
+        assert_eq!(mac.to_string(), "00:11:22:33:44:55");
+        assert_eq!(
+            HardwareAddress::new(
+                HardwareType::Ethernet,
+                &[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]
+            )
+            .unwrap(),
+            mac
+        );
+        assert!(HardwareAddress::new(HardwareType::Ethernet, &[0; 8]).is_err());
+
+        let guid = HardwareAddress::new(HardwareType::Infiniband, &[0xab; 20]).unwrap();
+        assert_eq!(guid.as_bytes().len(), 20);
+        assert!(HardwareAddress::new(HardwareType::Infiniband, &[0xab; 6]).is_err());
+
+        let other = HardwareAddress::new(HardwareType::Other(24), &[1; 8]).unwrap();
+        assert_eq!(other.to_string(), "01:01:01:01:01:01:01:01");
+        assert!(HardwareAddress::new(HardwareType::Other(24), &[1; 21]).is_err());
+
+        assert_eq!(u8::from(HardwareType::Infiniband), 32);
+        assert_eq!(HardwareType::from(6), HardwareType::Ieee802);
+        assert_eq!(HardwareType::from(24), HardwareType::Other(24));
+    }
+
+    #[test]
+    fn hardware_address_in_message() {
+        let mac = HardwareAddress::ethernet([0x02, 0, 0, 0, 0, 1]);
+        let mut message = DhcpMessage::new(1, 1);
+        message.set_hardware_address(&mac);
+        assert_eq!((message.htype, message.hlen), (1, 6));
+        assert_eq!(message.chaddr[..6], [0x02, 0, 0, 0, 0, 1]);
+        assert_eq!(message.hardware_address().unwrap(), Some(mac));
+
+        // InfiniBand addresses do not fit in chaddr.
+        let guid = HardwareAddress::new(HardwareType::Infiniband, &[0xab; 20]).unwrap();
+        message.set_hardware_address(&guid);
+        assert_eq!((message.htype, message.hlen), (32, 0));
+        assert_eq!(message.chaddr, [0; 16]);
+        assert_eq!(message.hardware_address().unwrap(), None);
+
+        message.htype = 1;
+        message.hlen = 17;
+        assert!(message.hardware_address().is_err());
+        message.hlen = 4;
+        assert!(message.hardware_address().is_err());
+    }
+
+    #[test]
+    fn hardware_address_client_identifier() {
+        let mac = HardwareAddress::ethernet([0x02, 0, 0, 0, 0, 1]);
+        assert_eq!(
+            mac.client_identifier(),
+            DhcpOption::ClientIdentifier(vec![1, 0x02, 0, 0, 0, 0, 1])
+        );
+        assert_eq!(
+            HardwareAddress::from_client_identifier(&[1, 0x02, 0, 0, 0, 0, 1]),
+            Some(mac)
+        );
+
+        let guid = HardwareAddress::new(HardwareType::Infiniband, &[0xab; 20]).unwrap();
+        match guid.client_identifier() {
+            DhcpOption::ClientIdentifier(identifier) => {
+                assert_eq!(identifier.len(), 21);
+                assert_eq!(
+                    HardwareAddress::from_client_identifier(&identifier),
+                    Some(guid)
+                );
+            }
+            option => panic!("unexpected option {:?}", option),
+        }
+
+        // Identifiers of type 0 are not hardware addresses.
+        assert_eq!(HardwareAddress::from_client_identifier(&[0, 1, 2]), None);
+        assert_eq!(HardwareAddress::from_client_identifier(&[1, 1, 2]), None);
+        assert_eq!(HardwareAddress::from_client_identifier(&[]), None);
+    }
+}
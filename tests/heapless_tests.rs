@@ -0,0 +1,165 @@
+#![cfg(feature = "heapless")]
+
+use std::net::Ipv4Addr;
+
+use dhcp::error::{DhcpError, ErrorKind};
+use dhcp::heapless::{FixedAddresses, FixedBytes, FixedMessage, FixedOption, FixedString};
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::option::DhcpOption;
+
+type Message = FixedMessage<8, 32>;
+
+fn ack() -> DhcpMessage {
+    let mut message = DhcpMessage::new(2, 0x3903F326);
+    message.yiaddr = Ipv4Addr::new(192, 168, 1, 100);
+    message.chaddr[..6].copy_from_slice(&[0x02, 0, 0, 0, 0, 1]);
+    message.options = vec![
+        DhcpOption::DhcpMessageType(MessageType::Ack),
+        DhcpOption::ServerIdentifier(Ipv4Addr::new(192, 168, 1, 1)),
+        DhcpOption::IpAddressLeaseTime(86400),
+        DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+        DhcpOption::Router(vec![Ipv4Addr::new(192, 168, 1, 1)].into()),
+        DhcpOption::DomainNameServer(
+            vec![Ipv4Addr::new(192, 168, 1, 1), Ipv4Addr::new(192, 168, 1, 2)].into(),
+        ),
+        DhcpOption::DomainName("example.com".to_string()),
+    ]
+    .into();
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heapless_fixed_buffers() {
+        let bytes = FixedBytes::<4>::from_slice(&[1, 2, 3]).unwrap();
+        assert_eq!(&*bytes, &[1, 2, 3]);
+        assert_eq!(bytes.capacity(), 4);
+        assert!(FixedBytes::<4>::from_slice(&[0; 5]).is_none());
+
+        let mut string = FixedString::<8>::try_new("host").unwrap();
+        assert_eq!(&string, "host");
+        assert_eq!(string.len(), 4);
+        assert!(FixedString::<8>::try_new("a-long-hostname").is_none());
+        string = FixedString::new();
+        assert!(string.is_empty());
+
+        let mut addresses = FixedAddresses::<8>::new();
+        assert!(addresses.push(Ipv4Addr::new(10, 0, 0, 1)));
+        assert!(addresses.push(Ipv4Addr::new(10, 0, 0, 2)));
+        assert!(!addresses.push(Ipv4Addr::new(10, 0, 0, 3)));
+        assert_eq!(addresses.len(), 2);
+        assert_eq!(addresses.get(1), Some(Ipv4Addr::new(10, 0, 0, 2)));
+        assert_eq!(
+            addresses.iter().collect::<Vec<_>>(),
+            vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)]
+        );
+    }
+
+    #[test]
+    fn heapless_message_round_trip() {
+        let data = ack().serialize().unwrap();
+        let message = Message::deserialize(&data).unwrap();
+        assert_eq!(message.xid, 0x3903F326);
+        assert_eq!(message.yiaddr, Ipv4Addr::new(192, 168, 1, 100));
+        assert_eq!(message.options.len(), 7);
+        assert_eq!(
+            message.get_option(1),
+            Some(&FixedOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)))
+        );
+        assert_eq!(
+            message.get_option(53),
+            Some(&FixedOption::Unknown(
+                53,
+                FixedBytes::from_slice(&[5]).unwrap()
+            ))
+        );
+        match message.get_option(15) {
+            Some(FixedOption::DomainName(name)) => assert_eq!(name, "example.com"),
+            option => panic!("unexpected option {:?}", option),
+        }
+
+        // Serialized back to the same bytes, and to the same message.
+        let mut buffer = [0; 576];
+        let len = message.serialize_into(&mut buffer).unwrap();
+        assert_eq!(len, message.wire_len());
+        assert_eq!(&buffer[..len], &data[..]);
+        assert_eq!(message.to_message().unwrap(), ack());
+        assert_eq!(Message::from_message(&ack()).unwrap(), message);
+
+        // A buffer too small for the message is left for the caller to grow.
+        let mut buffer = [0; 250];
+        assert!(matches!(
+            message.serialize_into(&mut buffer),
+            Err(DhcpError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn heapless_capacity_exceeded() {
+        let data = ack().serialize().unwrap();
+
+        // More options than slots.
+        match FixedMessage::<4, 32>::deserialize(&data) {
+            Err(DhcpError::CapacityExceeded { code, capacity }) => {
+                assert_eq!((code, capacity), (3, 4));
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        // A value longer than its buffer.
+        let error = FixedMessage::<8, 8>::deserialize(&data).unwrap_err();
+        assert!(matches!(
+            error,
+            DhcpError::CapacityExceeded {
+                code: 15,
+                capacity: 8
+            }
+        ));
+        assert_eq!(error.kind(), ErrorKind::Unsupported);
+
+        let mut message = FixedMessage::<1, 4>::new(1, 1);
+        message
+            .options
+            .push(FixedOption::IpAddressLeaseTime(60))
+            .unwrap();
+        assert!(message
+            .options
+            .push(FixedOption::IpAddressLeaseTime(60))
+            .is_err());
+        assert_eq!(
+            message.options.pop(),
+            Some(FixedOption::IpAddressLeaseTime(60))
+        );
+        assert!(message.options.is_empty());
+    }
+
+    #[test]
+    fn heapless_invalid_messages() {
+        let mut data = ack().serialize().unwrap();
+        assert!(Message::deserialize(&data[..200]).is_err());
+
+        // Options are checked as `DhcpMessage` checks them, without the
+        // context of the error.
+        let len = data.len();
+        data[len - 1] = 15;
+        assert!(matches!(
+            Message::deserialize(&data),
+            Err(DhcpError::Truncated { code: 15, .. })
+        ));
+        data.truncate(len - 1);
+        data.extend_from_slice(&[12, 2, 0xff, 0xfe, 255]);
+        assert!(matches!(
+            Message::deserialize(&data),
+            Err(DhcpError::InvalidUtf8 { code: 12 })
+        ));
+
+        data[236] = 0;
+        assert!(matches!(
+            Message::deserialize(&data),
+            Err(DhcpError::MissingMagicCookie)
+        ));
+    }
+}
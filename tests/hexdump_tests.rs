@@ -0,0 +1,47 @@
+use dhcp::hexdump::hexdump;
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::option::DhcpOption;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hexdump_message() {
+        let mut message = DhcpMessage::new(1, 0x12345678);
+        message.options = vec![DhcpOption::DhcpMessageType(MessageType::Discover)].into();
+        let dump = hexdump(&message.serialize().unwrap());
+        let lines = dump.lines().collect::<Vec<&str>>();
+
+        assert_eq!(
+            lines[0],
+            "0000  01                                               op: 1 [0x00..0x01]"
+        );
+        assert!(lines[4].starts_with("0004  12 34 56 78"));
+        assert!(lines[4].ends_with("xid: 0x12345678 [0x04..0x08]"));
+        assert!(dump.contains("magic cookie [0xec..0xf0]"));
+        assert!(dump.contains("00f0  35 01 01"));
+        assert!(dump.contains("option 53: DhcpMessageType(Discover) [0xf0..0xf3]"));
+        assert!(lines.last().unwrap().ends_with("end [0xf3..0xf4]"));
+    }
+
+    #[test]
+    fn hexdump_invalid() {
+        let data = DhcpMessage::new(1, 1).serialize().unwrap();
+        let dump = hexdump(&data[..18]);
+        assert!(dump
+            .lines()
+            .last()
+            .unwrap()
+            .ends_with("yiaddr: truncated [0x10..0x12]"));
+
+        let mut data = data;
+        data.pop();
+        data.extend_from_slice(&[0, 3, 4, 1, 2]);
+        let dump = hexdump(&data);
+        let lines = dump.lines().collect::<Vec<&str>>();
+        assert!(lines[lines.len() - 2].ends_with("pad [0xf0..0xf1]"));
+        assert!(lines[lines.len() - 1].starts_with("00f1  03 04 01 02"));
+        assert!(lines[lines.len() - 1].contains("option 3: Parsing Error"));
+    }
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "json")]
+
+use std::net::Ipv4Addr;
+
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::option::DhcpOption;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_to_json() {
+        let mut message = DhcpMessage::new(1, 42);
+        message.flags.set_broadcast(true);
+        message.chaddr[..6].copy_from_slice(&[0, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        message.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Discover),
+            DhcpOption::Router(vec![Ipv4Addr::new(192, 168, 1, 1)].into()),
+        ]
+        .into();
+
+        assert_eq!(
+            message.to_json(),
+            "{\"op\":1,\"htype\":1,\"hlen\":6,\"hops\":0,\"xid\":42,\"secs\":0,\
+             \"flags\":32768,\"ciaddr\":\"0.0.0.0\",\"yiaddr\":\"0.0.0.0\",\
+             \"siaddr\":\"0.0.0.0\",\"giaddr\":\"0.0.0.0\",\
+             \"chaddr\":\"00:11:22:33:44:55\",\"sname\":\"\",\"file\":\"\",\
+             \"options\":[{\"code\":53,\"name\":\"dhcp_message_type\",\"value\":1},\
+             {\"code\":3,\"name\":\"router\",\"value\":[\"192.168.1.1\"]}]}"
+        );
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut message = DhcpMessage::new(2, 0xFFFFFFFF);
+        message.yiaddr = Ipv4Addr::new(10, 0, 0, 5);
+        message.sname[..4].copy_from_slice(b"boot");
+        message.options = vec![
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+            DhcpOption::IpAddressLeaseTime(3600),
+            DhcpOption::HostName("host \"one\"".to_string()),
+            DhcpOption::IpForwarding(false),
+            DhcpOption::PathMtuPlateauTable(vec![68, 1500]),
+            DhcpOption::DomainSearch(vec!["example.com".to_string(), "lan".to_string()]),
+            DhcpOption::ClientIdentifier(vec![1, 0, 1, 2, 3, 4, 5]),
+            DhcpOption::Unknown(224, vec![0xAB]),
+            DhcpOption::Unknown(225, vec![7; 300]),
+        ]
+        .into();
+
+        let json = message.to_json();
+        assert!(json.contains("\"value\":\"01000102030405\""));
+        assert!(json.contains("{\"code\":224,\"value\":\"ab\"}"));
+        assert_eq!(DhcpMessage::from_json(&json).unwrap(), message);
+    }
+
+    #[test]
+    fn json_raw_values() {
+        // Text options and fields that are not text, and hardware address
+        // bytes past hlen.
+        let mut message = DhcpMessage::new(1, 1);
+        message.chaddr[..8].copy_from_slice(&[0, 1, 2, 3, 4, 5, 6, 7]);
+        message.sname[..5].copy_from_slice(b"a\0b\0c");
+        message.file[..2].copy_from_slice(&[0xff, 0x41]);
+        message.options = vec![
+            DhcpOption::Unknown(12, vec![0x63, 0xe9]),
+            DhcpOption::Unknown(67, vec![0xff, 0x41]),
+        ]
+        .into();
+
+        let json = message.to_json();
+        assert!(json.contains("\"chaddr\":\"00:01:02:03:04:05:06:07\""));
+        assert!(json.contains("\"sname\":{\"hex\":\"61006200630000"));
+        assert!(json.contains("{\"code\":12,\"name\":\"host_name\",\"value\":{\"hex\":\"63e9\"}}"));
+        assert_eq!(DhcpMessage::from_json(&json).unwrap(), message);
+
+        // A string is always the text of a text option.
+        let json = r#"{"options": [{"code": 12, "value": "63e9"}]}"#;
+        assert_eq!(
+            DhcpMessage::from_json(json).unwrap().options,
+            vec![DhcpOption::HostName("63e9".to_string())]
+        );
+        assert!(DhcpMessage::from_json(r#"{"sname": {"hex": "zz"}}"#).is_err());
+        assert!(DhcpMessage::from_json(r#"{"sname": {"text": "a"}}"#).is_err());
+    }
+
+    #[test]
+    fn json_from_json() {
+        let json = r#"{
+            "op": 1, "xid": 7, "chaddr": "00:01:02:03:04:05",
+            "options": [
+                {"name": "dhcp_message_type", "value": 3},
+                {"name": "requested_ip_address", "value": "10.0.0.5"},
+                {"code": 54, "value": "0a000001"}
+            ]
+        }"#;
+        let message = DhcpMessage::from_json(json).unwrap();
+        assert_eq!(message.xid, 7);
+        assert_eq!(&message.chaddr[..6], &[0, 1, 2, 3, 4, 5]);
+        assert_eq!(
+            message.options,
+            vec![
+                DhcpOption::DhcpMessageType(MessageType::Request),
+                DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, 5)),
+                DhcpOption::ServerIdentifier(Ipv4Addr::new(10, 0, 0, 1)),
+            ]
+        );
+
+        assert!(DhcpMessage::from_json("{\"op\": 256}").is_err());
+        assert!(DhcpMessage::from_json("{\"unknown\": 1}").is_err());
+        assert!(DhcpMessage::from_json("{\"op\": 1").is_err());
+        assert!(DhcpMessage::from_json("{\"options\": [{\"name\": \"nope\"}]}").is_err());
+        assert!(DhcpMessage::from_json("{\"options\": [{\"code\": 1, \"value\": 1}]}").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use dhcp::framing::FrameDecoder;
+use dhcp::lease::{LeaseEvent, LeaseEventSink, LeaseState};
+use dhcp::leasequery::{
+    active_leasequery, parse_update, ActiveLeasequery, LeaseUpdate, LeasequeryStatus,
+};
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    fn event(state: LeaseState) -> LeaseEvent {
+        LeaseEvent {
+            ip: Ipv4Addr::new(192, 168, 1, 10),
+            mac: [0x00, 0x11, 0x22, 0x33, 0x44, 0x55],
+            client_identifier: Some(vec![1, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+            state,
+            lease_time: 3600,
+            timestamp: 1_700_000_000,
+        }
+    }
+
+    fn frames(data: &[u8]) -> Vec<Vec<u8>> {
+        let mut decoder = FrameDecoder::new();
+        decoder.push(data);
+        std::iter::from_fn(|| decoder.next_frame()).collect()
+    }
+
+    #[test]
+    fn leasequery_state_conversion() {
+        for value in 1..=8u8 {
+            assert_eq!(u8::from(LeaseState::try_from(value).unwrap()), value);
+        }
+        assert!(LeaseState::try_from(9).is_err());
+        assert_eq!(LeasequeryStatus::from(7), LeasequeryStatus::CatchUpComplete);
+        assert_eq!(u8::from(LeasequeryStatus::Other(42)), 42);
+    }
+
+    #[test]
+    fn leasequery_active_subscription() {
+        let mut server = ActiveLeasequery::new();
+        let id = server.subscribe(&active_leasequery(0x1234)).unwrap();
+        assert_eq!(server.len(), 1);
+        assert!(server.take_output(id).is_empty());
+
+        server.lease_event(&event(LeaseState::Active));
+        server.lease_event(&event(LeaseState::Released));
+
+        let messages = frames(&server.take_output(id));
+        assert_eq!(messages.len(), 2);
+        assert_eq!(&messages[0][4..8], &[0, 0, 0x12, 0x34]);
+        assert_eq!(
+            parse_update(&messages[0]).unwrap(),
+            LeaseUpdate::Event(event(LeaseState::Active))
+        );
+        let mut released = event(LeaseState::Released);
+        released.lease_time = 0;
+        assert_eq!(
+            parse_update(&messages[1]).unwrap(),
+            LeaseUpdate::Event(released)
+        );
+        assert!(server.take_output(id).is_empty());
+
+        let messages = frames(&server.unsubscribe(id).unwrap());
+        assert_eq!(parse_update(&messages[0]).unwrap(), LeaseUpdate::Done);
+        assert!(server.is_empty());
+        assert!(server.unsubscribe(id).is_none());
+    }
+
+    #[test]
+    fn leasequery_active_catch_up_unavailable() {
+        let mut request = active_leasequery(1);
+        request.pop();
+        request.extend_from_slice(&[154, 4, 0, 0, 0, 1, 255]);
+
+        let mut server = ActiveLeasequery::new();
+        let id = server.subscribe(&request).unwrap();
+        let messages = frames(&server.take_output(id));
+        assert_eq!(
+            parse_update(&messages[0]).unwrap(),
+            LeaseUpdate::Status(
+                LeasequeryStatus::DataMissing,
+                "lease history unavailable".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn leasequery_active_rejects_other_messages() {
+        let mut request = active_leasequery(1);
+        request[242] = 1;
+        let mut server = ActiveLeasequery::new();
+        assert!(server.subscribe(&request).is_err());
+        assert!(parse_update(&request).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use std::net::Ipv4Addr;
+
+use dhcp::corpus::corpus;
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::message_ref::DhcpMessageRef;
+use dhcp::option::DhcpOption;
+use dhcp::option_ref::DhcpOptionRef;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_ref_matches_owned() {
+        for entry in corpus() {
+            let owned = DhcpMessage::deserialize(&entry.data);
+            let message = match DhcpMessageRef::deserialize(&entry.data) {
+                Ok(message) => message,
+                Err(error) => {
+                    assert!(owned.is_err(), "{}: {}", entry.name, error);
+                    continue;
+                }
+            };
+            let options = message
+                .options()
+                .map(|option| option.map(|option| option.to_owned()))
+                .collect::<Result<Vec<DhcpOption>, _>>();
+            // The parts of split options are only concatenated by the
+            // owned message.
+            let split = match &options {
+                Ok(options) => options
+                    .windows(2)
+                    .any(|pair| pair[0].code() == pair[1].code()),
+                Err(_) => false,
+            };
+            match owned {
+                Ok(owned) if split => assert_eq!(message.to_owned().unwrap(), owned),
+                // The concatenated parts may be invalid where each part is
+                // not, as for a repeated message type.
+                Err(_) if split => assert!(message.to_owned().is_err(), "{}", entry.name),
+                Ok(owned) => {
+                    assert_eq!(owned.options, options.unwrap(), "{}", entry.name);
+                    assert_eq!(message.xid(), owned.xid);
+                    assert_eq!(message.giaddr(), owned.giaddr);
+                    assert_eq!(message.chaddr(), owned.chaddr);
+                    assert_eq!(message.to_owned().unwrap(), owned);
+                }
+                Err(error) => assert_eq!(
+                    format!("{:?}", options.unwrap_err()),
+                    format!("{:?}", error),
+                    "{}",
+                    entry.name
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn message_ref_get_option() {
+        let mut message = DhcpMessage::new(1, 7);
+        message.ciaddr = Ipv4Addr::new(10, 0, 0, 5);
+        message.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Request),
+            DhcpOption::Pad,
+            DhcpOption::ClientIdentifier(vec![1, 2, 3]),
+            DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, 5)),
+            DhcpOption::ClientIdentifier(vec![4, 5, 6]),
+        ]
+        .into();
+        let mut data = message.serialize().unwrap();
+        // Invalid UTF-8 in a host name, after the End option.
+        data.extend_from_slice(&[12, 1, 0xff]);
+
+        let message = DhcpMessageRef::deserialize(&data).unwrap();
+        assert_eq!(message.op(), 1);
+        assert_eq!(message.xid(), 7);
+        assert_eq!(message.ciaddr(), Ipv4Addr::new(10, 0, 0, 5));
+        assert_eq!(message.raw_option(53), Some(&[3][..]));
+        assert_eq!(
+            message.get_option(61).unwrap(),
+            Some(DhcpOptionRef::ClientIdentifier(&[1, 2, 3]))
+        );
+        assert_eq!(
+            message.get_option(50).unwrap(),
+            Some(DhcpOptionRef::RequestedIpAddress(Ipv4Addr::new(
+                10, 0, 0, 5
+            )))
+        );
+        assert_eq!(message.get_option(12).unwrap(), None);
+        assert_eq!(message.options().count(), 4);
+
+        // Values are only checked when decoded.
+        let mut message = DhcpMessage::new(1, 7);
+        message.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Discover),
+            DhcpOption::Unknown(12, vec![0xff]),
+        ]
+        .into();
+        let data = message.serialize().unwrap();
+        let message = DhcpMessageRef::deserialize(&data).unwrap();
+        assert_eq!(message.raw_option(53), Some(&[1][..]));
+        assert!(message.get_option(12).is_err());
+        assert!(message.to_owned().is_err());
+
+        // Option boundaries are checked up front.
+        let mut data = DhcpMessage::new(1, 7).serialize().unwrap();
+        data.pop();
+        data.extend_from_slice(&[53, 4, 1]);
+        assert!(DhcpMessageRef::deserialize(&data).is_err());
+        assert!(DhcpMessageRef::deserialize(&data[..200]).is_err());
+    }
+}
@@ -0,0 +1,629 @@
+use std::net::Ipv4Addr;
+
+use dhcp::error::{DhcpError, SerializationError};
+use dhcp::message::{
+    deserialize_options, DhcpMessage, Difference, Flags, MessageType, OpCode, ParserConfig,
+    SerializerConfig, TextPolicy, UnknownOptions, MAGIC_COOKIE,
+};
+use dhcp::option::{DhcpOption, OptionOverload};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_serialize_deserialize() {
+        let mut message = DhcpMessage::new(2, 0x12345678);
+        message.secs = 3;
+        message.flags.set_broadcast(true);
+        message.yiaddr = Ipv4Addr::new(192, 168, 1, 10);
+        message.chaddr[..6].copy_from_slice(&[0, 1, 2, 3, 4, 5]);
+        message.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Ack),
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+        ]
+        .into();
+
+        let data = message.serialize().unwrap();
+        assert_eq!(data.len(), DhcpMessage::HEADER_LEN + 3 + 6 + 1);
+        assert_eq!(&data[0..4], &[2, 1, 6, 0]);
+        assert_eq!(&data[4..8], &[0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(&data[236..240], &MAGIC_COOKIE);
+        assert_eq!(data.last(), Some(&255));
+
+        assert_eq!(DhcpMessage::deserialize(&data).unwrap(), message);
+    }
+
+    #[test]
+    fn message_deserialize_pad_and_end() {
+        let mut data = DhcpMessage::new(1, 1).serialize().unwrap();
+        data.pop();
+        data.extend_from_slice(&[0, 0, 224, 1, 7, 255, 0, 0]);
+
+        let message = DhcpMessage::deserialize(&data).unwrap();
+        assert_eq!(message.options, vec![DhcpOption::Unknown(224, vec![7])]);
+    }
+
+    #[test]
+    fn message_deserialize_invalid() {
+        let data = DhcpMessage::new(1, 1).serialize().unwrap();
+        assert!(DhcpMessage::deserialize(&data[..239]).is_err());
+
+        let mut data = data;
+        data[236] = 0;
+        assert!(DhcpMessage::deserialize(&data).is_err());
+    }
+
+    #[test]
+    fn message_diff() {
+        let mut offer = DhcpMessage::new(2, 7);
+        offer.yiaddr = Ipv4Addr::new(10, 0, 0, 5);
+        offer.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Offer),
+            DhcpOption::IpAddressLeaseTime(3600),
+            DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 1)].into()),
+        ]
+        .into();
+        assert!(offer.diff(&offer).is_empty());
+
+        let mut ack = offer.clone();
+        ack.flags.set_broadcast(true);
+        ack.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Ack),
+            DhcpOption::IpAddressLeaseTime(3600),
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+        ]
+        .into();
+
+        let differences = offer.diff(&ack);
+        assert_eq!(
+            differences,
+            vec![
+                Difference::Field {
+                    name: "flags",
+                    left: "0x0000".to_string(),
+                    right: "0x8000".to_string(),
+                },
+                Difference::OptionChanged {
+                    left: DhcpOption::DhcpMessageType(MessageType::Offer),
+                    right: DhcpOption::DhcpMessageType(MessageType::Ack),
+                },
+                Difference::OptionRemoved(DhcpOption::Router(
+                    vec![Ipv4Addr::new(10, 0, 0, 1)].into()
+                )),
+                Difference::OptionAdded(DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0))),
+            ]
+        );
+        assert_eq!(differences[0].to_string(), "flags: 0x0000 -> 0x8000");
+        assert_eq!(
+            differences[1].to_string(),
+            "option 53: DhcpMessageType(Offer) -> DhcpMessageType(Ack)"
+        );
+    }
+
+    #[test]
+    fn message_error_context() {
+        let mut message = DhcpMessage::new(1, 1);
+        message.options = vec![DhcpOption::DhcpMessageType(MessageType::Discover)].into();
+        let mut data = message.serialize().unwrap();
+        data.truncate(data.len() - 1);
+        // Router option with a length not multiple of 4.
+        data.extend_from_slice(&[3, 3, 10, 0, 0, 255]);
+
+        let error = DhcpMessage::deserialize(&data).unwrap_err();
+        match &error {
+            DhcpError::Context {
+                offset,
+                field,
+                code,
+                error,
+            } => {
+                assert_eq!(*offset, 243);
+                assert_eq!(*field, "options");
+                assert_eq!(*code, Some(3));
+                assert!(matches!(
+                    **error,
+                    DhcpError::InvalidLength { code: 3, len: 3 }
+                ));
+            }
+            error => panic!("unexpected error {:?}", error),
+        }
+        assert_eq!(
+            error.to_string(),
+            "Parsing Error: Option 3 has an invalid length of 3 (option 3 at offset 243 in the options field)"
+        );
+
+        data[236] = 0;
+        assert!(matches!(
+            DhcpMessage::deserialize(&data),
+            Err(DhcpError::Context {
+                offset: 236,
+                field: "header",
+                code: None,
+                ..
+            })
+        ));
+        assert!(matches!(
+            DhcpMessage::deserialize(&data[..100]),
+            Err(DhcpError::Context { offset: 100, .. })
+        ));
+    }
+
+    #[test]
+    fn message_unknown_options() {
+        let mut message = DhcpMessage::new(1, 1);
+        message.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Discover),
+            DhcpOption::Unknown(200, vec![1, 2]),
+            DhcpOption::Unknown(55, vec![1, 3]),
+        ]
+        .into();
+        let data = message.serialize().unwrap();
+        let config = |unknown_options| ParserConfig {
+            unknown_options,
+            ..ParserConfig::default()
+        };
+
+        assert_eq!(DhcpMessage::deserialize(&data).unwrap(), message);
+        assert_eq!(
+            DhcpMessage::deserialize_with(&data, &config(UnknownOptions::Capture)).unwrap(),
+            message
+        );
+        assert_eq!(
+            DhcpMessage::deserialize_with(&data, &config(UnknownOptions::Skip))
+                .unwrap()
+                .options,
+            vec![
+                DhcpOption::DhcpMessageType(MessageType::Discover),
+                DhcpOption::Unknown(55, vec![1, 3])
+            ]
+        );
+        assert!(matches!(
+            DhcpMessage::deserialize_with(&data, &config(UnknownOptions::Error)),
+            Err(DhcpError::Context {
+                offset: 243,
+                code: Some(200),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn message_deserialize_partial() {
+        let mut message = DhcpMessage::new(1, 1);
+        message.options = vec![DhcpOption::DhcpMessageType(MessageType::Discover)].into();
+        let mut data = message.serialize().unwrap();
+        data.truncate(data.len() - 1);
+        // A subnet mask with a wrong length, then a host name and an option
+        // running past the end of the data.
+        data.extend_from_slice(&[1, 2, 255, 255]);
+        data.extend_from_slice(&[12, 4, 104, 111, 115, 116]);
+        data.extend_from_slice(&[3, 8, 10, 0]);
+
+        assert!(DhcpMessage::deserialize(&data).is_err());
+        let (partial, warnings) =
+            DhcpMessage::deserialize_partial(&data, &ParserConfig::default()).unwrap();
+        assert_eq!(
+            partial.options,
+            vec![
+                DhcpOption::DhcpMessageType(MessageType::Discover),
+                DhcpOption::HostName("host".to_string())
+            ]
+        );
+        assert_eq!(warnings.len(), 2);
+        assert!(matches!(
+            warnings[0],
+            DhcpError::Context {
+                offset: 243,
+                code: Some(1),
+                ..
+            }
+        ));
+        assert!(matches!(
+            warnings[1],
+            DhcpError::Context {
+                offset: 253,
+                code: Some(3),
+                ..
+            }
+        ));
+
+        // Unknown options are dropped with a warning in error mode.
+        message.options.push(DhcpOption::Unknown(200, vec![1]));
+        let config = ParserConfig {
+            unknown_options: UnknownOptions::Error,
+            ..ParserConfig::default()
+        };
+        let (partial, warnings) =
+            DhcpMessage::deserialize_partial(&message.serialize().unwrap(), &config).unwrap();
+        assert_eq!(
+            partial.options,
+            vec![DhcpOption::DhcpMessageType(MessageType::Discover)]
+        );
+        assert_eq!(warnings.len(), 1);
+
+        assert!(DhcpMessage::deserialize_partial(&data[..100], &config).is_err());
+    }
+
+    #[test]
+    fn message_text_policy() {
+        // A host name in Latin-1, and a domain name split in two parts.
+        let mut data = DhcpMessage::new(1, 1).serialize().unwrap();
+        data.truncate(data.len() - 1);
+        data.extend_from_slice(&[12, 4, b'c', b'a', 0xe9, b'e']);
+        data.extend_from_slice(&[15, 1, b'x', 15, 1, 0xff, 255]);
+        let config = |text| ParserConfig {
+            text,
+            ..ParserConfig::default()
+        };
+
+        assert!(matches!(
+            DhcpMessage::deserialize(&data),
+            Err(DhcpError::Context {
+                offset: 240,
+                code: Some(12),
+                ..
+            })
+        ));
+        assert_eq!(
+            DhcpMessage::deserialize_with(&data, &config(TextPolicy::Lossy))
+                .unwrap()
+                .options,
+            vec![
+                DhcpOption::HostName("ca\u{fffd}e".to_string()),
+                DhcpOption::DomainName("x\u{fffd}".to_string())
+            ]
+        );
+        let message = DhcpMessage::deserialize_with(&data, &config(TextPolicy::Raw)).unwrap();
+        assert_eq!(
+            message.options,
+            vec![
+                DhcpOption::Unknown(12, vec![b'c', b'a', 0xe9, b'e']),
+                DhcpOption::Unknown(15, vec![b'x', 0xff])
+            ]
+        );
+        // Raw options serialize back to the same bytes.
+        assert_eq!(
+            DhcpMessage::deserialize_with(&message.serialize().unwrap(), &config(TextPolicy::Raw))
+                .unwrap(),
+            message
+        );
+    }
+
+    #[test]
+    fn message_serialization_errors() {
+        let message = DhcpMessage::new(1, 1);
+        assert_eq!(message.serialize_within(241).unwrap().len(), 241);
+        assert!(matches!(
+            message.serialize_within(240),
+            Err(DhcpError::SerializationError(
+                SerializationError::MessageTooLong { len: 241, max: 240 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn message_wire_len() {
+        let mut message = DhcpMessage::new(1, 1);
+        assert_eq!(message.wire_len(), 241);
+        message.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Discover),
+            DhcpOption::End,
+        ]
+        .into();
+        assert_eq!(message.wire_len(), 244);
+        assert_eq!(message.serialize().unwrap().len(), 244);
+
+        // Padding is not counted.
+        let config = SerializerConfig {
+            min_len: 300,
+            ..SerializerConfig::default()
+        };
+        assert_eq!(message.serialize_with(&config).unwrap().len(), 300);
+        assert_eq!(message.wire_len(), 244);
+    }
+
+    #[test]
+    fn message_long_options() {
+        // Values longer than 255 bytes are split, and the parts are
+        // concatenated when parsing (RFC 3396).
+        let mut message = DhcpMessage::new(1, 1);
+        let names: Vec<String> = (0..40).map(|i| format!("host{}.example.com", i)).collect();
+        message.options = vec![
+            DhcpOption::DomainName("a".repeat(300)),
+            DhcpOption::DomainSearch(names),
+            DhcpOption::VendorSpecificInformation(vec![7; 600]),
+        ]
+        .into();
+        let data = message.serialize().unwrap();
+        assert_eq!(data.len(), message.wire_len());
+        assert_eq!(&data[240..242], &[15, 255]);
+        assert_eq!(&data[240 + 257..240 + 259], &[15, 45]);
+        assert_eq!(DhcpMessage::deserialize(&data).unwrap(), message);
+
+        // Consecutive options of the same code are parts of one option.
+        let mut message = DhcpMessage::new(1, 1);
+        message.options = vec![
+            DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 1)].into()),
+            DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 2)].into()),
+            DhcpOption::DhcpMessageType(MessageType::Discover),
+            DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 3)].into()),
+        ]
+        .into();
+        let parsed = DhcpMessage::deserialize(&message.serialize().unwrap()).unwrap();
+        assert_eq!(
+            parsed.options[..],
+            [
+                DhcpOption::Router(
+                    vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)].into()
+                ),
+                DhcpOption::DhcpMessageType(MessageType::Discover),
+                DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 3)].into()),
+            ]
+        );
+
+        // A static route split in the middle of a pair is valid once
+        // concatenated.
+        let mut data = DhcpMessage::new(1, 1).serialize().unwrap();
+        data.pop();
+        data.extend_from_slice(&[33, 5, 10, 0, 0, 0, 10, 33, 3, 0, 0, 1, 255]);
+        assert_eq!(
+            DhcpMessage::deserialize(&data).unwrap().options[..],
+            [DhcpOption::StaticRoute(vec![(
+                Ipv4Addr::new(10, 0, 0, 0),
+                Ipv4Addr::new(10, 0, 0, 1)
+            )])]
+        );
+    }
+
+    #[test]
+    fn message_op_code_and_type() {
+        assert_eq!(OpCode::try_from(1).unwrap(), OpCode::BootRequest);
+        assert_eq!(u8::from(OpCode::BootReply), 2);
+        assert!(OpCode::try_from(3).is_err());
+
+        for code in 1..=8 {
+            assert_eq!(u8::from(MessageType::try_from(code).unwrap()), code);
+        }
+        assert!(MessageType::try_from(0).is_err());
+        assert!(MessageType::try_from(9).is_err());
+
+        let mut message = DhcpMessage::new(OpCode::BootRequest.into(), 1);
+        assert_eq!(message.message_type().unwrap(), None);
+        message.options.push(MessageType::Request.into());
+        assert_eq!(
+            message.options[0],
+            DhcpOption::DhcpMessageType(MessageType::Request)
+        );
+        assert_eq!(message.message_type().unwrap(), Some(MessageType::Request));
+
+        // Types of later RFCs are kept raw, and are not a `MessageType`.
+        message.options = vec![DhcpOption::Unknown(53, vec![9])].into();
+        assert!(matches!(
+            message.message_type(),
+            Err(DhcpError::UnknownMessageType(9))
+        ));
+    }
+
+    #[test]
+    fn message_deserialize_options() {
+        let mut data = MAGIC_COOKIE.to_vec();
+        data.extend_from_slice(&[53, 1, 1, 0, 0, 1, 4, 255, 255, 255, 0, 255, 3, 4, 10]);
+        assert_eq!(
+            deserialize_options(&data).unwrap(),
+            vec![
+                DhcpOption::DhcpMessageType(MessageType::Discover),
+                DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+            ]
+        );
+        assert_eq!(deserialize_options(&MAGIC_COOKIE).unwrap(), vec![]);
+
+        // The magic cookie is required, and errors are located in the field.
+        assert!(matches!(
+            deserialize_options(&data[1..]),
+            Err(DhcpError::Context { offset: 0, .. })
+        ));
+        data.truncate(12);
+        match deserialize_options(&data) {
+            Err(DhcpError::Context { offset, code, .. }) => {
+                assert_eq!((offset, code), (9, Some(1)))
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn message_option_overload() {
+        let mut message = DhcpMessage::new(2, 1);
+        message.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Ack),
+            DhcpOption::OptionOverload(OptionOverload::Both),
+        ]
+        .into();
+        let mut data = message.serialize().unwrap();
+        assert_eq!(data[44], 255);
+        assert_eq!(data[108], 255);
+
+        // Options in the file field, then the sname field.
+        data[108..115].copy_from_slice(&[51, 4, 0, 0, 14, 16, 255]);
+        data[44..51].copy_from_slice(&[1, 4, 255, 255, 255, 0, 255]);
+        let parsed = DhcpMessage::deserialize(&data).unwrap();
+        assert_eq!(parsed.overload(), Some(OptionOverload::Both));
+        assert_eq!(
+            parsed.options,
+            vec![
+                DhcpOption::DhcpMessageType(MessageType::Ack),
+                DhcpOption::OptionOverload(OptionOverload::Both),
+                DhcpOption::IpAddressLeaseTime(3600),
+                DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+            ]
+        );
+        assert_eq!(parsed.sname, [0; 64]);
+        assert_eq!(parsed.file, [0; 128]);
+
+        // Serialized again, all the options go to the options field.
+        let serialized = parsed.serialize().unwrap();
+        assert_eq!(serialized[108], 255);
+        assert_eq!(DhcpMessage::deserialize(&serialized).unwrap(), parsed);
+
+        // Fields not overloaded keep their content.
+        data[245] = 1;
+        let parsed = DhcpMessage::deserialize(&data).unwrap();
+        assert_eq!(parsed.overload(), Some(OptionOverload::File));
+        assert_eq!(parsed.sname[..7], [1, 4, 255, 255, 255, 0, 255]);
+        assert_eq!(parsed.options.len(), 3);
+
+        // Errors are located in the overloaded field.
+        data[109] = 3;
+        match DhcpMessage::deserialize(&data) {
+            Err(DhcpError::Context { offset, field, .. }) => {
+                assert_eq!((offset, field), (108, "file"))
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        assert!(DhcpOption::deserialize(&[52, 1, 4]).is_err());
+    }
+
+    #[test]
+    fn message_flags() {
+        let mut flags = Flags::default();
+        assert!(!flags.broadcast());
+        flags.set_broadcast(true);
+        assert!(flags.broadcast());
+        assert_eq!(flags.bits(), 0x8000);
+
+        // Reserved bits are kept as received.
+        let mut message = DhcpMessage::new(1, 1);
+        message.flags = Flags::from(0x8001);
+        let mut parsed = DhcpMessage::deserialize(&message.serialize().unwrap()).unwrap();
+        assert_eq!(parsed.flags.reserved(), 0x0001);
+        parsed.flags.set_broadcast(false);
+        assert_eq!(u16::from(parsed.flags), 0x0001);
+        assert_eq!(format!("{:04x}", parsed.flags), "0001");
+    }
+
+    #[test]
+    fn message_serialize_padding() {
+        let mut message = DhcpMessage::new(1, 1);
+        message
+            .options
+            .push(DhcpOption::DhcpMessageType(MessageType::Discover));
+
+        let data = message
+            .serialize_with(&SerializerConfig::default())
+            .unwrap();
+        assert_eq!(data.len(), DhcpMessage::BOOTP_MIN_LEN);
+        assert_eq!(data[243], 255);
+        assert!(data[244..].iter().all(|byte| *byte == 0));
+        assert_eq!(DhcpMessage::deserialize(&data).unwrap(), message);
+
+        // Longer messages are left as they are.
+        let config = SerializerConfig {
+            min_len: 200,
+            ..SerializerConfig::default()
+        };
+        assert_eq!(
+            message.serialize_with(&config).unwrap(),
+            message.serialize().unwrap()
+        );
+    }
+
+    #[test]
+    fn message_maximum_message_size() {
+        let mut request = DhcpMessage::new(1, 1);
+        assert_eq!(request.max_message_size().unwrap(), None);
+        assert_eq!(
+            SerializerConfig::for_request(&request).max_len,
+            DhcpMessage::DEFAULT_MAX_LEN
+        );
+
+        request
+            .options
+            .push(DhcpOption::Unknown(57, vec![0x05, 0xdc]));
+        assert_eq!(request.max_message_size().unwrap(), Some(1500));
+        assert_eq!(SerializerConfig::for_request(&request).max_len, 1500);
+
+        // Sizes below 576 are not legal.
+        request.options = vec![DhcpOption::Unknown(57, vec![0x01, 0x00])].into();
+        assert_eq!(SerializerConfig::for_request(&request).max_len, 576);
+
+        let mut reply = DhcpMessage::new(2, 1);
+        reply.options.push(DhcpOption::DomainName("a".repeat(250)));
+        reply.options.push(DhcpOption::HostName("b".repeat(250)));
+        assert!(matches!(
+            reply.serialize_with(&SerializerConfig::default()),
+            Err(DhcpError::SerializationError(
+                SerializationError::MessageTooLong { len: 745, max: 576 }
+            ))
+        ));
+        request.options = vec![DhcpOption::Unknown(57, vec![0x05, 0xdc])].into();
+        let config = SerializerConfig::for_request(&request);
+        assert_eq!(reply.serialize_with(&config).unwrap().len(), 745);
+    }
+
+    #[test]
+    fn message_automatic_overload() {
+        let mut message = DhcpMessage::new(2, 1);
+        message
+            .options
+            .push(DhcpOption::DhcpMessageType(MessageType::Ack));
+        for code in 224..232 {
+            message
+                .options
+                .push(DhcpOption::Unknown(code, vec![code; 60]));
+        }
+        assert_eq!(message.wire_len(), 240 + 3 + 8 * 62 + 1);
+
+        // Five options fit in the options field, two in file and one in
+        // sname.
+        let data = message
+            .serialize_with(&SerializerConfig::default())
+            .unwrap();
+        assert_eq!(data.len(), 240 + 3 + 5 * 62 + 3 + 1);
+        assert!(data.len() <= DhcpMessage::DEFAULT_MAX_LEN);
+        assert_eq!(&data[108..110], &[229, 60]);
+        assert_eq!(&data[44..46], &[231, 60]);
+
+        let parsed = DhcpMessage::deserialize(&data).unwrap();
+        assert_eq!(parsed.overload(), Some(OptionOverload::Both));
+        let mut expected = message.options.to_vec();
+        expected.insert(6, DhcpOption::OptionOverload(OptionOverload::Both));
+        assert_eq!(parsed.options, expected);
+
+        let config = SerializerConfig {
+            overload: false,
+            ..SerializerConfig::default()
+        };
+        assert!(message.serialize_with(&config).is_err());
+
+        // A boot file name keeps the file field from holding options.
+        message.file[0] = b'a';
+        assert!(message
+            .serialize_with(&SerializerConfig::default())
+            .is_err());
+    }
+
+    #[test]
+    fn message_conversions() {
+        let mut message = DhcpMessage::new(1, 0x1234);
+        message
+            .options
+            .push(DhcpOption::DhcpMessageType(MessageType::Discover));
+
+        let data = Vec::<u8>::try_from(&message).unwrap();
+        assert_eq!(data, message.serialize().unwrap());
+        assert_eq!(DhcpMessage::try_from(&data[..]).unwrap(), message);
+
+        let hex: Vec<String> = data.iter().map(|byte| format!("{:02x}", byte)).collect();
+        assert_eq!(hex.join("").parse::<DhcpMessage>().unwrap(), message);
+        assert_eq!(hex.join(" ").parse::<DhcpMessage>().unwrap(), message);
+        assert_eq!(
+            hex.join(":").to_uppercase().parse::<DhcpMessage>().unwrap(),
+            message
+        );
+        assert!("01 0".parse::<DhcpMessage>().is_err());
+        assert!("zz".parse::<DhcpMessage>().is_err());
+        assert!(DhcpMessage::try_from(&data[..100]).is_err());
+    }
+}
@@ -0,0 +1,66 @@
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::metrics::{prometheus, Metric, MetricKind, Metrics, Sample};
+use dhcp::option::DhcpOption;
+use dhcp::server::{Server, ServerConfig};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed;
+
+    impl Metrics for Fixed {
+        fn metrics(&self) -> Vec<Metric> {
+            vec![Metric {
+                name: "test_total",
+                help: "A \\ test.",
+                kind: MetricKind::Counter,
+                samples: vec![
+                    Sample::new(1.0).with_label("name", "a\"b"),
+                    Sample::new(2.5)
+                        .with_label("name", "c")
+                        .with_label("x", "y"),
+                ],
+            }]
+        }
+    }
+
+    #[test]
+    fn metrics_prometheus() {
+        assert_eq!(
+            prometheus(&Fixed),
+            "# HELP test_total A \\\\ test.\n\
+             # TYPE test_total counter\n\
+             test_total{name=\"a\\\"b\"} 1\n\
+             test_total{name=\"c\",x=\"y\"} 2.5\n"
+        );
+    }
+
+    #[test]
+    fn metrics_server() {
+        let config = ServerConfig::from_config(
+            r#"
+            server_identifier = "10.0.0.1"
+            pool_start = "10.0.0.10"
+            pool_end = "10.0.0.19"
+            subnet_mask = "255.255.255.0"
+            "#,
+        )
+        .unwrap();
+        let server = Server::new(config);
+
+        let mut discover = DhcpMessage::new(1, 1);
+        discover.options = vec![DhcpOption::DhcpMessageType(MessageType::Discover)].into();
+        server.handle(&discover, 0, &mut Vec::new()).unwrap();
+        server.handle(&discover, 0, &mut Vec::new()).unwrap();
+        server.handle(&DhcpMessage::new(1, 2), 0, &mut Vec::new());
+
+        let text = prometheus(&server);
+        assert!(text.contains("# TYPE dhcp_server_messages_received_total counter\n"));
+        assert!(text.contains("dhcp_server_messages_received_total{type=\"discover\"} 2\n"));
+        assert!(text.contains("dhcp_server_messages_received_total{type=\"other\"} 1\n"));
+        assert!(text.contains("dhcp_server_messages_sent_total{type=\"offer\"} 2\n"));
+        assert!(text.contains("dhcp_server_pool_addresses 10\n"));
+        assert!(text.contains("dhcp_server_leases 0\n"));
+    }
+}
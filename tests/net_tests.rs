@@ -0,0 +1,124 @@
+use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+use std::time::Duration;
+
+use dhcp::net::{bind, recv_with_info, send_batch, RecvBatch, SocketConfig};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn localhost() -> SocketAddrV4 {
+        SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)
+    }
+
+    #[test]
+    fn net_bind() {
+        let config = SocketConfig {
+            broadcast: true,
+            read_timeout: Some(Duration::from_secs(5)),
+            ..SocketConfig::default()
+        };
+        let socket = bind(localhost(), &config).unwrap();
+        assert!(socket.broadcast().unwrap());
+        assert_eq!(socket.read_timeout().unwrap(), Some(Duration::from_secs(5)));
+    }
+
+    #[cfg(all(target_os = "linux", target_env = "gnu", target_pointer_width = "64"))]
+    #[test]
+    fn net_reuse_port() {
+        let config = SocketConfig {
+            reuse_address: true,
+            reuse_port: true,
+            ..SocketConfig::default()
+        };
+        let first = bind(localhost(), &config).unwrap();
+        let address = match first.local_addr().unwrap() {
+            std::net::SocketAddr::V4(address) => address,
+            _ => unreachable!(),
+        };
+        assert!(bind(address, &config).is_ok());
+        assert!(bind(address, &SocketConfig::default()).is_err());
+
+        let config = SocketConfig {
+            device: Some("no-such-interface".to_string()),
+            ..SocketConfig::default()
+        };
+        assert!(bind(localhost(), &config).is_err());
+    }
+
+    #[cfg(all(target_os = "linux", target_env = "gnu", target_pointer_width = "64"))]
+    #[test]
+    fn net_packet_info() {
+        let config = SocketConfig {
+            packet_info: true,
+            read_timeout: Some(Duration::from_secs(5)),
+            ..SocketConfig::default()
+        };
+        let socket = bind(localhost(), &config).unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        sender
+            .send_to(b"discover", socket.local_addr().unwrap())
+            .unwrap();
+
+        let mut buffer = [0; 64];
+        let (len, source, info) = recv_with_info(&socket, &mut buffer).unwrap();
+        assert_eq!(&buffer[..len], b"discover");
+        assert_eq!(source.to_string(), sender.local_addr().unwrap().to_string());
+        let info = info.unwrap();
+        assert_ne!(info.interface, 0);
+        assert_eq!(info.local, Ipv4Addr::LOCALHOST);
+        assert_eq!(info.destination, Ipv4Addr::LOCALHOST);
+
+        // Without IP_PKTINFO, nothing is reported.
+        let socket = bind(localhost(), &SocketConfig::default()).unwrap();
+        sender
+            .send_to(b"offer", socket.local_addr().unwrap())
+            .unwrap();
+        let (len, _, info) = recv_with_info(&socket, &mut buffer).unwrap();
+        assert_eq!(&buffer[..len], b"offer");
+        assert_eq!(info, None);
+    }
+
+    #[test]
+    fn net_batch() {
+        let config = SocketConfig {
+            read_timeout: Some(Duration::from_secs(5)),
+            ..SocketConfig::default()
+        };
+        let receiver = bind(localhost(), &config).unwrap();
+        let sender = bind(localhost(), &config).unwrap();
+        let destination = match receiver.local_addr().unwrap() {
+            std::net::SocketAddr::V4(address) => address,
+            _ => unreachable!(),
+        };
+
+        let datagrams = [
+            (&b"first"[..], destination),
+            (&b"second"[..], destination),
+            (&b"a datagram longer than the buffers"[..], destination),
+        ];
+        assert_eq!(send_batch(&sender, &datagrams).unwrap(), 3);
+        assert_eq!(send_batch(&sender, &[]).unwrap(), 0);
+
+        let mut batch = RecvBatch::new(4, 16);
+        let mut received = Vec::new();
+        while received.len() < 3 {
+            assert!(batch.recv(&receiver).unwrap() > 0);
+            for (data, source) in batch.iter() {
+                assert_eq!(
+                    std::net::SocketAddr::V4(source),
+                    sender.local_addr().unwrap()
+                );
+                received.push(data.to_vec());
+            }
+        }
+        assert_eq!(
+            received,
+            vec![
+                b"first".to_vec(),
+                b"second".to_vec(),
+                b"a datagram longe".to_vec()
+            ]
+        );
+    }
+}
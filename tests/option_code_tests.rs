@@ -0,0 +1,57 @@
+use std::net::Ipv4Addr;
+
+use dhcp::option::{self, DhcpOption};
+use dhcp::option_code::OptionCode;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn option_code_round_trip() {
+        for code in 0..=255u8 {
+            assert_eq!(u8::from(OptionCode::from(code)), code);
+            assert_eq!(OptionCode::from(code), code);
+        }
+        assert_eq!(OptionCode::from(3), OptionCode::Router);
+        assert_eq!(OptionCode::from(61), OptionCode::ClientIdentifier);
+        assert_eq!(OptionCode::from(84), OptionCode::Other(84));
+        assert_eq!(OptionCode::from(255), OptionCode::End);
+    }
+
+    #[test]
+    fn option_code_names() {
+        assert_eq!(OptionCode::SubnetMask.name(), "Subnet Mask");
+        assert_eq!(OptionCode::Other(130).name(), "PXE (vendor specific)");
+        assert_eq!(OptionCode::Other(230).name(), "Site-specific");
+        assert_eq!(OptionCode::Other(84).name(), "Unassigned");
+        assert_eq!(
+            OptionCode::DhcpMessageType.to_string(),
+            "DHCP Message Type (53)"
+        );
+
+        // The names agree with the option table.
+        for code in 0..=255u8 {
+            if let Some(info) = option::info(code) {
+                assert_eq!(OptionCode::from(code).name(), info.name, "{}", code);
+            }
+        }
+    }
+
+    #[test]
+    fn option_code_of_option() {
+        assert_eq!(DhcpOption::Pad.code(), OptionCode::Pad);
+        assert_eq!(
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)).code(),
+            OptionCode::SubnetMask
+        );
+        assert_eq!(
+            DhcpOption::Unknown(54, vec![10, 0, 0, 1]).code(),
+            OptionCode::ServerIdentifier
+        );
+        assert_eq!(
+            DhcpOption::Unknown(240, vec![]).code(),
+            OptionCode::Other(240)
+        );
+    }
+}
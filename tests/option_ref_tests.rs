@@ -0,0 +1,139 @@
+use std::net::Ipv4Addr;
+
+use dhcp::error::DhcpError;
+use dhcp::message::{DhcpMessage, MessageType, TextPolicy};
+use dhcp::message_ref::DhcpMessageRef;
+use dhcp::option::DhcpOption;
+use dhcp::option_ref::{DhcpOptionRef, OptionsIter};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn option_ref_matches_owned() {
+        // Every code with every length up to 24, a few value patterns and
+        // trailing data or not.
+        let mut checked = 0;
+        for code in 0..=255u8 {
+            for len in 0..=24u8 {
+                for pattern in [0u8, 1, 4, 0x41, 0xc0] {
+                    for trailing in [0, 8] {
+                        let mut data = vec![code, len];
+                        data.extend((0..len).map(|i| pattern.wrapping_add(i)));
+                        data.extend(vec![0; trailing]);
+                        for end in [data.len(), data.len() - trailing] {
+                            let data = &data[..end];
+                            let owned = DhcpOption::deserialize(data);
+                            let borrowed = DhcpOptionRef::deserialize(data)
+                                .map(|(option, rest)| (option.to_owned(), rest));
+                            assert_eq!(
+                                format!("{:?}", borrowed),
+                                format!("{:?}", owned),
+                                "{:02x?}",
+                                data
+                            );
+                            checked += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert!(checked > 0);
+        assert!(DhcpOptionRef::deserialize(&[]).is_err());
+    }
+
+    #[test]
+    fn option_ref_borrows() {
+        let data = [
+            3, 8, 10, 0, 0, 1, 10, 0, 0, 2, 12, 4, b'h', b'o', b's', b't', 255,
+        ];
+        let (router, data) = DhcpOptionRef::deserialize(&data).unwrap();
+        match router {
+            DhcpOptionRef::Router(routers) => {
+                assert_eq!(routers.len(), 2);
+                assert_eq!(routers.iter().last(), Some(Ipv4Addr::new(10, 0, 0, 2)));
+            }
+            option => panic!("unexpected option {:?}", option),
+        }
+        let (host_name, data) = DhcpOptionRef::deserialize(data).unwrap();
+        assert_eq!(host_name, DhcpOptionRef::HostName("host".into()));
+        assert_eq!(
+            host_name.to_owned(),
+            DhcpOption::HostName("host".to_string())
+        );
+        assert_eq!(data, [255]);
+    }
+
+    #[test]
+    fn option_ref_options_iter() {
+        let mut message = DhcpMessage::new(1, 7);
+        message.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Discover),
+            DhcpOption::Pad,
+            DhcpOption::Unknown(82, vec![1, 2, b'e', b'0']),
+            DhcpOption::HostName("client".to_string()),
+        ]
+        .into();
+        let mut data = message.serialize().unwrap();
+        data.extend_from_slice(&[12, 1, 0xff]);
+
+        let options = OptionsIter::from_message(&data)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            options,
+            [
+                (53, &[1][..]),
+                (82, &[1, 2, b'e', b'0'][..]),
+                (12, &b"client"[..]),
+            ]
+        );
+        let relay_agent_information = OptionsIter::from_message(&data).unwrap().find_code(82);
+        assert_eq!(relay_agent_information, Some(&[1, 2, b'e', b'0'][..]));
+        assert_eq!(
+            OptionsIter::from_message(&data).unwrap().find_code(61),
+            None
+        );
+        assert!(OptionsIter::from_message(&data[..100]).is_err());
+
+        let message = DhcpMessageRef::deserialize(&data).unwrap();
+        assert_eq!(message.raw_options().count(), 3);
+
+        // A truncated option ends the iteration with an error.
+        let mut options = OptionsIter::new(&[0, 0, 3, 4, 10, 0, 0, 1, 6, 4, 10]);
+        assert_eq!(options.next().unwrap().unwrap(), (3, &[10, 0, 0, 1][..]));
+        assert!(matches!(
+            options.next(),
+            Some(Err(DhcpError::Truncated {
+                code: 6,
+                needed: 5,
+                available: 2
+            }))
+        ));
+        assert!(options.next().is_none());
+        assert_eq!(OptionsIter::new(&[0, 0, 0]).count(), 0);
+    }
+
+    #[test]
+    fn option_ref_text_policy() {
+        for code in [12, 64] {
+            let data = [code, 2, b'a', 0xe9];
+            assert!(DhcpOptionRef::deserialize(&data).is_err());
+            let (lossy, _) = DhcpOptionRef::deserialize_with(&data, TextPolicy::Lossy).unwrap();
+            assert_eq!(
+                lossy.to_owned(),
+                DhcpOption::deserialize_with(&data, TextPolicy::Lossy)
+                    .unwrap()
+                    .0
+            );
+            assert_eq!(
+                DhcpOptionRef::deserialize_with(&data, TextPolicy::Raw)
+                    .unwrap()
+                    .0,
+                DhcpOptionRef::Unknown(code, &[b'a', 0xe9])
+            );
+        }
+    }
+}
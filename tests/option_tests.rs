@@ -1,4 +1,6 @@
-use dhcp::option::DhcpOption;
+use dhcp::error::{DhcpError, SerializationError};
+use dhcp::message::{DhcpMessage, MessageType, ParserConfig, TextPolicy};
+use dhcp::option::{info, is_known_code, Addresses, Authentication, DhcpOption, LengthRule};
 
 #[cfg(test)]
 mod tests {
@@ -11,7 +13,7 @@ mod tests {
     #[test]
     fn option_pad_serialize() {
         let option = DhcpOption::Pad;
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![0]);
     }
 
@@ -20,7 +22,7 @@ mod tests {
         let data = vec![0];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::Pad);
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![0, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -31,7 +33,7 @@ mod tests {
     #[test]
     fn option_end_serialize() {
         let option = DhcpOption::End;
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![255]);
     }
 
@@ -40,7 +42,7 @@ mod tests {
         let data = vec![255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::End);
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![255, 0];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -51,7 +53,7 @@ mod tests {
     #[test]
     fn option_subnet_mask_serialize() {
         let option = DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0));
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![1, 4, 255, 255, 255, 0]);
     }
 
@@ -63,7 +65,7 @@ mod tests {
             option,
             DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0))
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![1, 4, 255, 255, 255, 0, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -77,7 +79,7 @@ mod tests {
     #[test]
     fn option_time_offset_serialize() {
         let option = DhcpOption::TimeOffset(0x12345678);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![2, 4, 0x12, 0x34, 0x56, 0x78]);
     }
 
@@ -86,7 +88,7 @@ mod tests {
         let data = vec![2, 4, 0x12, 0x34, 0x56, 0x78];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::TimeOffset(0x12345678));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![2, 4, 0x12, 0x34, 0x56, 0x78, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -96,11 +98,10 @@ mod tests {
 
     #[test]
     fn option_router_serialize() {
-        let option = DhcpOption::Router(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::Router(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![3, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -110,32 +111,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::Router(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::Router(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![3, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::Router(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::Router(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_time_server_serialize() {
-        let option = DhcpOption::TimeServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::TimeServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![4, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -145,32 +143,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::TimeServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::TimeServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![4, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::TimeServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::TimeServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_name_server_serialize() {
-        let option = DhcpOption::NameServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::NameServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![5, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -180,32 +175,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NameServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::NameServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![5, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NameServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::NameServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_domain_name_server_serialize() {
-        let option = DhcpOption::DomainNameServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::DomainNameServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![6, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -215,32 +207,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::DomainNameServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::DomainNameServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![6, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::DomainNameServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::DomainNameServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_log_server_serialize() {
-        let option = DhcpOption::LogServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::LogServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![7, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -250,32 +239,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::LogServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::LogServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![7, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::LogServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::LogServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_cookie_server_serialize() {
-        let option = DhcpOption::CookieServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::CookieServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![8, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -285,32 +271,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::CookieServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::CookieServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![8, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::CookieServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::CookieServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_lpr_server_serialize() {
-        let option = DhcpOption::LprServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::LprServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![9, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -320,32 +303,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::LprServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::LprServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![9, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::LprServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::LprServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_impress_server_serialize() {
-        let option = DhcpOption::ImpressServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::ImpressServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![10, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -355,32 +335,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::ImpressServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::ImpressServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![10, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::ImpressServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::ImpressServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_resource_location_server_serialize() {
-        let option = DhcpOption::ResourceLocationServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::ResourceLocationServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![11, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -390,21 +367,19 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::ResourceLocationServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::ResourceLocationServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![11, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::ResourceLocationServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2),
-            ])
+            DhcpOption::ResourceLocationServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2),].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
@@ -412,7 +387,7 @@ mod tests {
     #[test]
     fn option_host_name_serialize() {
         let option = DhcpOption::HostName("host".to_string());
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![12, 4, 104, 111, 115, 116]);
     }
 
@@ -421,7 +396,7 @@ mod tests {
         let data = vec![12, 4, 104, 111, 115, 116];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::HostName("host".to_string()));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![12, 4, 104, 111, 115, 116, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -432,7 +407,7 @@ mod tests {
     #[test]
     fn option_boot_file_size_serialize() {
         let option = DhcpOption::BootFileSize(1024);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![13, 2, 4, 0]);
     }
 
@@ -441,7 +416,7 @@ mod tests {
         let data = vec![13, 2, 4, 0];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::BootFileSize(1024));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![13, 2, 4, 0, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -452,7 +427,7 @@ mod tests {
     #[test]
     fn option_merit_dump_file_serialize() {
         let option = DhcpOption::MeritDumpFile("dump".to_string());
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![14, 4, 100, 117, 109, 112]);
     }
 
@@ -461,7 +436,7 @@ mod tests {
         let data = vec![14, 4, 100, 117, 109, 112];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::MeritDumpFile("dump".to_string()));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![14, 4, 100, 117, 109, 112, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -472,7 +447,7 @@ mod tests {
     #[test]
     fn option_domain_name_serialize() {
         let option = DhcpOption::DomainName("domain".to_string());
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![15, 6, 100, 111, 109, 97, 105, 110]);
     }
 
@@ -481,7 +456,7 @@ mod tests {
         let data = vec![15, 6, 100, 111, 109, 97, 105, 110];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::DomainName("domain".to_string()));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![15, 6, 100, 111, 109, 97, 105, 110, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -492,7 +467,7 @@ mod tests {
     #[test]
     fn option_swap_server_serialize() {
         let option = DhcpOption::SwapServer(Ipv4Addr::new(192, 168, 0, 1));
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![16, 4, 192, 168, 0, 1]);
     }
 
@@ -504,7 +479,7 @@ mod tests {
             option,
             DhcpOption::SwapServer(Ipv4Addr::new(192, 168, 0, 1))
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![16, 4, 192, 168, 0, 1, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -518,7 +493,7 @@ mod tests {
     #[test]
     fn option_root_path_serialize() {
         let option = DhcpOption::RootPath("path".to_string());
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![17, 4, 112, 97, 116, 104]);
     }
 
@@ -527,7 +502,7 @@ mod tests {
         let data = vec![17, 4, 112, 97, 116, 104];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::RootPath("path".to_string()));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![17, 4, 112, 97, 116, 104, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -538,7 +513,7 @@ mod tests {
     #[test]
     fn option_extension_path_serialize() {
         let option = DhcpOption::ExtensionsPath("path".to_string());
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![18, 4, 112, 97, 116, 104]);
     }
 
@@ -547,7 +522,7 @@ mod tests {
         let data = vec![18, 4, 112, 97, 116, 104];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::ExtensionsPath("path".to_string()));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![18, 4, 112, 97, 116, 104, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -558,11 +533,11 @@ mod tests {
     #[test]
     fn option_ip_forwarding_serialize() {
         let option = DhcpOption::IpForwarding(true);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![19, 1, 1]);
 
         let option = DhcpOption::IpForwarding(false);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![19, 1, 0]);
     }
 
@@ -571,12 +546,12 @@ mod tests {
         let data = vec![19, 1, 1];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::IpForwarding(true));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![19, 1, 0];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::IpForwarding(false));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![19, 1, 0, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -587,11 +562,11 @@ mod tests {
     #[test]
     fn option_non_local_source_routing_serialize() {
         let option = DhcpOption::NonLocalSourceRouting(true);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![20, 1, 1]);
 
         let option = DhcpOption::NonLocalSourceRouting(false);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![20, 1, 0]);
     }
 
@@ -600,12 +575,12 @@ mod tests {
         let data = vec![20, 1, 1];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::NonLocalSourceRouting(true));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![20, 1, 0];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::NonLocalSourceRouting(false));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![20, 1, 0, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -625,7 +600,7 @@ mod tests {
                 Ipv4Addr::new(255, 255, 255, 0),
             ),
         ]);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(
             serialized,
             vec![21, 16, 192, 168, 0, 1, 255, 255, 255, 0, 192, 168, 0, 2, 255, 255, 255, 0]
@@ -651,7 +626,7 @@ mod tests {
                 ),
             ])
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![
             21, 16, 192, 168, 0, 1, 255, 255, 255, 0, 192, 168, 0, 2, 255, 255, 255, 0, 255,
@@ -676,7 +651,7 @@ mod tests {
     #[test]
     fn option_max_datagram_reassembly_size_serialize() {
         let option = DhcpOption::MaximumDatagramReassemblySize(1500);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![22, 2, 5, 220]);
     }
 
@@ -685,7 +660,7 @@ mod tests {
         let data = vec![22, 2, 5, 220];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::MaximumDatagramReassemblySize(1500));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![22, 2, 5, 220, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -696,7 +671,7 @@ mod tests {
     #[test]
     fn option_default_ip_ttl_serialize() {
         let option = DhcpOption::DefaultIpTimeToLive(64);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![23, 1, 64]);
     }
 
@@ -705,7 +680,7 @@ mod tests {
         let data = vec![23, 1, 64];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::DefaultIpTimeToLive(64));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![23, 1, 64, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -716,7 +691,7 @@ mod tests {
     #[test]
     fn option_path_mtu_aging_timeout_serialize() {
         let option = DhcpOption::PathMtuAgingTimeout(1500);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![24, 4, 0, 0, 5, 220]);
     }
 
@@ -725,7 +700,7 @@ mod tests {
         let data = vec![24, 4, 0, 0, 5, 220];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::PathMtuAgingTimeout(1500));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![24, 4, 0, 0, 5, 220, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -736,7 +711,7 @@ mod tests {
     #[test]
     fn option_path_mtu_plateau_table_serialize() {
         let option = DhcpOption::PathMtuPlateauTable(vec![1500, 1499]);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![25, 4, 5, 220, 5, 219]);
     }
 
@@ -745,7 +720,7 @@ mod tests {
         let data = vec![25, 4, 5, 220, 5, 219];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::PathMtuPlateauTable(vec![1500, 1499]));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![25, 4, 5, 220, 5, 219, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -756,7 +731,7 @@ mod tests {
     #[test]
     fn option_interface_mtu_serialize() {
         let option = DhcpOption::InterfaceMtu(1500);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![26, 2, 5, 220]);
     }
 
@@ -765,7 +740,7 @@ mod tests {
         let data = vec![26, 2, 5, 220];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::InterfaceMtu(1500));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![26, 2, 5, 220, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -776,11 +751,11 @@ mod tests {
     #[test]
     fn option_all_subnets_are_local_serialize() {
         let option = DhcpOption::AllSubnetsAreLocal(true);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![27, 1, 1]);
 
         let option = DhcpOption::AllSubnetsAreLocal(false);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![27, 1, 0]);
     }
 
@@ -789,12 +764,12 @@ mod tests {
         let data = vec![27, 1, 1];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::AllSubnetsAreLocal(true));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![27, 1, 0];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::AllSubnetsAreLocal(false));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![27, 1, 0, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -805,7 +780,7 @@ mod tests {
     #[test]
     fn option_broadcast_address_serialize() {
         let option = DhcpOption::BroadcastAddress(Ipv4Addr::new(192, 168, 1, 255));
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![28, 4, 192, 168, 1, 255]);
     }
 
@@ -817,7 +792,7 @@ mod tests {
             option,
             DhcpOption::BroadcastAddress(Ipv4Addr::new(192, 168, 1, 255))
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![28, 4, 192, 168, 1, 255, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -831,11 +806,11 @@ mod tests {
     #[test]
     fn option_perform_mask_discovery_serialize() {
         let option = DhcpOption::PerformMaskDiscovery(true);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![29, 1, 1]);
 
         let option = DhcpOption::PerformMaskDiscovery(false);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![29, 1, 0]);
     }
 
@@ -844,12 +819,12 @@ mod tests {
         let data = vec![29, 1, 1];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::PerformMaskDiscovery(true));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![29, 1, 0];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::PerformMaskDiscovery(false));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![29, 1, 0, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -860,11 +835,11 @@ mod tests {
     #[test]
     fn option_mask_supplier_serialize() {
         let option = DhcpOption::MaskSupplier(true);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![30, 1, 1]);
 
         let option = DhcpOption::MaskSupplier(false);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![30, 1, 0]);
     }
 
@@ -873,12 +848,12 @@ mod tests {
         let data = vec![30, 1, 1];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::MaskSupplier(true));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![30, 1, 0];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::MaskSupplier(false));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![30, 1, 0, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -889,11 +864,11 @@ mod tests {
     #[test]
     fn option_perform_router_discovery_serialize() {
         let option = DhcpOption::PerformRouterDiscovery(true);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![31, 1, 1]);
 
         let option = DhcpOption::PerformRouterDiscovery(false);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![31, 1, 0]);
     }
 
@@ -902,12 +877,12 @@ mod tests {
         let data = vec![31, 1, 1];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::PerformRouterDiscovery(true));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![31, 1, 0];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::PerformRouterDiscovery(false));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![31, 1, 0, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -918,7 +893,7 @@ mod tests {
     #[test]
     fn option_router_solicitation_address_serialize() {
         let option = DhcpOption::RouterSolicitationAddress(Ipv4Addr::new(192, 168, 1, 1));
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![32, 4, 192, 168, 1, 1]);
     }
 
@@ -930,7 +905,7 @@ mod tests {
             option,
             DhcpOption::RouterSolicitationAddress(Ipv4Addr::new(192, 168, 1, 1))
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![32, 4, 192, 168, 1, 1, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -953,7 +928,7 @@ mod tests {
                 Ipv4Addr::new(192, 168, 0, 200),
             ),
         ]);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(
             serialized,
             vec![33, 16, 192, 168, 0, 1, 192, 168, 0, 100, 192, 168, 0, 2, 192, 168, 0, 200]
@@ -979,7 +954,7 @@ mod tests {
                 ),
             ])
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![
             33, 16, 192, 168, 0, 1, 192, 168, 0, 100, 192, 168, 0, 2, 192, 168, 0, 200, 255,
@@ -1004,11 +979,11 @@ mod tests {
     #[test]
     fn option_trailer_encapsulation_serialize() {
         let option = DhcpOption::TrailerEncapsulation(true);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![34, 1, 1]);
 
         let option = DhcpOption::TrailerEncapsulation(false);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![34, 1, 0]);
     }
 
@@ -1017,12 +992,12 @@ mod tests {
         let data = vec![34, 1, 1];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::TrailerEncapsulation(true));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![34, 1, 0];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::TrailerEncapsulation(false));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![34, 1, 0, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -1033,7 +1008,7 @@ mod tests {
     #[test]
     fn option_arp_cache_timeout_serialize() {
         let option = DhcpOption::ArpCacheTimeout(1234);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![35, 4, 0, 0, 4, 210]);
     }
 
@@ -1042,7 +1017,7 @@ mod tests {
         let data = vec![35, 4, 0, 0, 4, 210];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::ArpCacheTimeout(1234));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![35, 4, 0, 0, 4, 210, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -1053,11 +1028,11 @@ mod tests {
     #[test]
     fn option_ethernet_encapsulation_serialize() {
         let option = DhcpOption::EthernetEncapsulation(true);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![36, 1, 1]);
 
         let option = DhcpOption::EthernetEncapsulation(false);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![36, 1, 0]);
     }
 
@@ -1066,12 +1041,12 @@ mod tests {
         let data = vec![36, 1, 1];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::EthernetEncapsulation(true));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![36, 1, 0];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::EthernetEncapsulation(false));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![36, 1, 0, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -1082,7 +1057,7 @@ mod tests {
     #[test]
     fn option_tcp_default_ttl_serialize() {
         let option = DhcpOption::TcpDefaultTtl(123);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![37, 1, 123]);
     }
 
@@ -1091,7 +1066,7 @@ mod tests {
         let data = vec![37, 1, 123];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::TcpDefaultTtl(123));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![37, 1, 123, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -1102,7 +1077,7 @@ mod tests {
     #[test]
     fn option_tcp_keepalive_interval_serialize() {
         let option = DhcpOption::TcpKeepaliveInterval(1234);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![38, 4, 0, 0, 4, 210]);
     }
 
@@ -1111,7 +1086,7 @@ mod tests {
         let data = vec![38, 4, 0, 0, 4, 210];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::TcpKeepaliveInterval(1234));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![38, 4, 0, 0, 4, 210, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -1122,11 +1097,11 @@ mod tests {
     #[test]
     fn option_tcp_keepalive_garbage_serialize() {
         let option = DhcpOption::TcpKeepaliveGarbage(true);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![39, 1, 1]);
 
         let option = DhcpOption::TcpKeepaliveGarbage(false);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![39, 1, 0]);
     }
 
@@ -1135,12 +1110,12 @@ mod tests {
         let data = vec![39, 1, 1];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::TcpKeepaliveGarbage(true));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![39, 1, 0];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::TcpKeepaliveGarbage(false));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![39, 1, 0, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -1150,11 +1125,10 @@ mod tests {
 
     #[test]
     fn option_network_information_servers_serialize() {
-        let option = DhcpOption::NetworkInformationServers(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::NetworkInformationServers(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![41, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1164,32 +1138,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NetworkInformationServers(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::NetworkInformationServers(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![41, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NetworkInformationServers(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::NetworkInformationServers(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_network_time_protocol_servers_serialize() {
-        let option = DhcpOption::NetworkTimeProtocolServers(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::NetworkTimeProtocolServers(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![42, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1199,21 +1170,19 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NetworkTimeProtocolServers(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::NetworkTimeProtocolServers(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![42, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NetworkTimeProtocolServers(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::NetworkTimeProtocolServers(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
@@ -1221,7 +1190,7 @@ mod tests {
     #[test]
     fn option_vendor_specific_information_serialize() {
         let option = DhcpOption::VendorSpecificInformation(vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![43, 10, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
     }
 
@@ -1233,7 +1202,7 @@ mod tests {
             option,
             DhcpOption::VendorSpecificInformation(vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10])
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![43, 10, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -1246,11 +1215,10 @@ mod tests {
 
     #[test]
     fn option_netbios_over_tcpip_name_server_serialize() {
-        let option = DhcpOption::NetBiosOverTcpIpNameServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::NetBiosOverTcpIpNameServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![44, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1260,32 +1228,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NetBiosOverTcpIpNameServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::NetBiosOverTcpIpNameServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![44, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NetBiosOverTcpIpNameServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::NetBiosOverTcpIpNameServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_netbios_over_tcpip_datagram_distribution_server_serialize() {
-        let option = DhcpOption::NetBiosOverTcpIpDatagramDistributionServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::NetBiosOverTcpIpDatagramDistributionServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![45, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1295,21 +1260,19 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NetBiosOverTcpIpDatagramDistributionServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::NetBiosOverTcpIpDatagramDistributionServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![45, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NetBiosOverTcpIpDatagramDistributionServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::NetBiosOverTcpIpDatagramDistributionServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
@@ -1317,7 +1280,7 @@ mod tests {
     #[test]
     fn option_netbios_over_tcpip_node_type_serialize() {
         let option = DhcpOption::NetBiosOverTcpIpNodeType(NetBiosOverTcpIpNodeType::PNode);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![46, 1, 2]);
     }
 
@@ -1329,7 +1292,7 @@ mod tests {
             option,
             DhcpOption::NetBiosOverTcpIpNodeType(NetBiosOverTcpIpNodeType::PNode)
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![46, 1, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -1343,7 +1306,7 @@ mod tests {
     #[test]
     fn option_netbios_over_tcpip_scope_serialize() {
         let option = DhcpOption::NetBiosOverTcpIpScope(vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![47, 10, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
     }
 
@@ -1355,7 +1318,7 @@ mod tests {
             option,
             DhcpOption::NetBiosOverTcpIpScope(vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10])
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![47, 10, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -1368,11 +1331,10 @@ mod tests {
 
     #[test]
     fn option_x_window_system_font_server_serialize() {
-        let option = DhcpOption::XWindowSystemFontServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::XWindowSystemFontServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![48, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1382,32 +1344,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::XWindowSystemFontServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::XWindowSystemFontServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![48, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::XWindowSystemFontServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::XWindowSystemFontServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_x_window_system_display_manager_serialize() {
-        let option = DhcpOption::XWindowSystemDisplayManager(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::XWindowSystemDisplayManager(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![49, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1417,21 +1376,19 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::XWindowSystemDisplayManager(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::XWindowSystemDisplayManager(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![49, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::XWindowSystemDisplayManager(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::XWindowSystemDisplayManager(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
@@ -1439,7 +1396,7 @@ mod tests {
     #[test]
     fn option_network_information_service_plus_domain_serialize() {
         let option = DhcpOption::NetworkInformationServicePlusDomain("domain".to_string());
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![64, 6, 100, 111, 109, 97, 105, 110]);
     }
 
@@ -1451,7 +1408,7 @@ mod tests {
             option,
             DhcpOption::NetworkInformationServicePlusDomain("domain".to_string())
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![64, 6, 100, 111, 109, 97, 105, 110, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -1464,11 +1421,10 @@ mod tests {
 
     #[test]
     fn option_network_information_service_plus_servers_serialize() {
-        let option = DhcpOption::NetworkInformationServicePlusServers(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::NetworkInformationServicePlusServers(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![65, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1478,32 +1434,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NetworkInformationServicePlusServers(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::NetworkInformationServicePlusServers(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![65, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NetworkInformationServicePlusServers(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::NetworkInformationServicePlusServers(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_mobile_ip_home_agent_serialize() {
-        let option = DhcpOption::MobileIpHomeAgent(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::MobileIpHomeAgent(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![68, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1511,39 +1464,36 @@ mod tests {
     fn option_mobile_ip_home_agent_deserialize() {
         let data = vec![68, 0];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
-        assert_eq!(option, DhcpOption::MobileIpHomeAgent(vec![]));
-        assert_eq!(data, &[]);
+        assert_eq!(option, DhcpOption::MobileIpHomeAgent(vec![].into()));
+        assert!(data.is_empty());
 
         let data = vec![68, 8, 192, 168, 0, 1, 192, 168, 0, 2];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::MobileIpHomeAgent(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::MobileIpHomeAgent(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![68, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::MobileIpHomeAgent(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::MobileIpHomeAgent(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_simple_mail_transport_protocol_serialize() {
-        let option = DhcpOption::SimpleMailTransportProtocolServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::SimpleMailTransportProtocolServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![69, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1553,32 +1503,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::SimpleMailTransportProtocolServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::SimpleMailTransportProtocolServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![69, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::SimpleMailTransportProtocolServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::SimpleMailTransportProtocolServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_post_office_protocol_server_serialize() {
-        let option = DhcpOption::PostOfficeProtocolServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::PostOfficeProtocolServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![70, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1588,32 +1535,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::PostOfficeProtocolServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::PostOfficeProtocolServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![70, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::PostOfficeProtocolServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::PostOfficeProtocolServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_network_news_transport_protocol_serialize() {
-        let option = DhcpOption::NetworkNewsTransportProtocolServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::NetworkNewsTransportProtocolServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![71, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1623,32 +1567,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NetworkNewsTransportProtocolServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::NetworkNewsTransportProtocolServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![71, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::NetworkNewsTransportProtocolServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::NetworkNewsTransportProtocolServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_world_wide_web_server_serialize() {
-        let option = DhcpOption::DefaultWorldWideWebServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::DefaultWorldWideWebServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![72, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1658,32 +1599,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::DefaultWorldWideWebServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::DefaultWorldWideWebServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![72, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::DefaultWorldWideWebServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::DefaultWorldWideWebServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_default_finger_server_serialize() {
-        let option = DhcpOption::DefaultFingerServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::DefaultFingerServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![73, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1693,32 +1631,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::DefaultFingerServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::DefaultFingerServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![73, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::DefaultFingerServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::DefaultFingerServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_default_internet_relay_chat_server_serialize() {
-        let option = DhcpOption::DefaultInternetRelayChatServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::DefaultInternetRelayChatServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![74, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1728,32 +1663,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::DefaultInternetRelayChatServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::DefaultInternetRelayChatServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![74, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::DefaultInternetRelayChatServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::DefaultInternetRelayChatServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_street_talk_server_serialize() {
-        let option = DhcpOption::StreetTalkServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::StreetTalkServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![75, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1763,32 +1695,29 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::StreetTalkServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::StreetTalkServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![75, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::StreetTalkServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::StreetTalkServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
 
     #[test]
     fn option_street_talk_directory_assistance_server_serialize() {
-        let option = DhcpOption::StreetTalkDirectoryAssistanceServer(vec![
-            Ipv4Addr::new(192, 168, 0, 1),
-            Ipv4Addr::new(192, 168, 0, 2),
-        ]);
-        let serialized = option.serialize();
+        let option = DhcpOption::StreetTalkDirectoryAssistanceServer(
+            vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into(),
+        );
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![76, 8, 192, 168, 0, 1, 192, 168, 0, 2]);
     }
 
@@ -1798,21 +1727,19 @@ mod tests {
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::StreetTalkDirectoryAssistanceServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::StreetTalkDirectoryAssistanceServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![76, 8, 192, 168, 0, 1, 192, 168, 0, 2, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(
             option,
-            DhcpOption::StreetTalkDirectoryAssistanceServer(vec![
-                Ipv4Addr::new(192, 168, 0, 1),
-                Ipv4Addr::new(192, 168, 0, 2)
-            ])
+            DhcpOption::StreetTalkDirectoryAssistanceServer(
+                vec![Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)].into()
+            )
         );
         assert_eq!(data, &[255]);
     }
@@ -1820,7 +1747,7 @@ mod tests {
     #[test]
     fn option_requested_ip_address_serialize() {
         let option = DhcpOption::RequestedIpAddress(Ipv4Addr::new(192, 168, 0, 1));
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![50, 4, 192, 168, 0, 1]);
     }
 
@@ -1832,7 +1759,7 @@ mod tests {
             option,
             DhcpOption::RequestedIpAddress(Ipv4Addr::new(192, 168, 0, 1))
         );
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![50, 4, 192, 168, 0, 1, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
@@ -1846,7 +1773,7 @@ mod tests {
     #[test]
     fn option_ip_address_lease_time_serialize() {
         let option = DhcpOption::IpAddressLeaseTime(1234567890);
-        let serialized = option.serialize();
+        let serialized = option.serialize().unwrap();
         assert_eq!(serialized, vec![51, 4, 73, 150, 2, 210]);
     }
 
@@ -1855,11 +1782,538 @@ mod tests {
         let data = vec![51, 4, 73, 150, 2, 210];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::IpAddressLeaseTime(1234567890));
-        assert_eq!(data, &[]);
+        assert!(data.is_empty());
 
         let data = vec![51, 4, 73, 150, 2, 210, 255];
         let (option, data) = DhcpOption::deserialize(&data).unwrap();
         assert_eq!(option, DhcpOption::IpAddressLeaseTime(1234567890));
         assert_eq!(data, &[255]);
     }
+
+    #[test]
+    fn option_client_identifier_serialize() {
+        let option = DhcpOption::ClientIdentifier(vec![1, 0, 17, 34, 51, 68, 85]);
+        let serialized = option.serialize().unwrap();
+        assert_eq!(serialized, vec![61, 7, 1, 0, 17, 34, 51, 68, 85]);
+    }
+
+    #[test]
+    fn option_client_identifier_deserialize() {
+        let data = vec![61, 7, 1, 0, 17, 34, 51, 68, 85];
+        let (option, data) = DhcpOption::deserialize(&data).unwrap();
+        assert_eq!(
+            option,
+            DhcpOption::ClientIdentifier(vec![1, 0, 17, 34, 51, 68, 85])
+        );
+        assert!(data.is_empty());
+
+        let data = vec![61, 2, 0, 1, 255];
+        let (option, data) = DhcpOption::deserialize(&data).unwrap();
+        assert_eq!(option, DhcpOption::ClientIdentifier(vec![0, 1]));
+        assert_eq!(data, &[255]);
+
+        assert!(DhcpOption::deserialize(&[61, 1, 0, 255]).is_err());
+        assert!(DhcpOption::deserialize(&[61, 7, 1, 0, 17]).is_err());
+    }
+
+    #[test]
+    fn option_domain_search_serialize() {
+        let option = DhcpOption::DomainSearch(vec!["eng.apple.com".to_string()]);
+        let serialized = option.serialize().unwrap();
+        assert_eq!(serialized, b"\x77\x0F\x03eng\x05apple\x03com\x00".to_vec());
+
+        let option = DhcpOption::DomainSearch(vec!["a".repeat(64)]);
+        assert!(matches!(
+            option.serialize(),
+            Err(DhcpError::SerializationError(
+                SerializationError::ValueTooLong { code: 119, .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn option_domain_search_deserialize() {
+        let data = b"\x77\x1B\x03eng\x05apple\x03com\x00\x09marketing\xC0\x00\xFF".to_vec();
+        let (option, data) = DhcpOption::deserialize(&data).unwrap();
+        assert_eq!(
+            option,
+            DhcpOption::DomainSearch(vec![
+                "eng.apple.com".to_string(),
+                "marketing.eng.apple.com".to_string()
+            ])
+        );
+        assert_eq!(data, &[255]);
+
+        assert!(DhcpOption::deserialize(&[119, 4, 3, b'c', b'o', b'm']).is_err());
+        assert!(DhcpOption::deserialize(&[119, 5, 3, b'c', b'o', b'm']).is_err());
+    }
+
+    #[test]
+    fn option_authentication_serialize() {
+        let option = DhcpOption::Authentication(Authentication {
+            protocol: 2,
+            algorithm: 1,
+            rdm: 0,
+            replay_detection: 0x0102030405060708,
+            information: vec![0xAA, 0xBB],
+        });
+        let serialized = option.serialize().unwrap();
+        assert_eq!(
+            serialized,
+            vec![90, 13, 2, 1, 0, 1, 2, 3, 4, 5, 6, 7, 8, 0xAA, 0xBB]
+        );
+    }
+
+    #[test]
+    fn option_authentication_deserialize() {
+        let data = vec![90, 11, 3, 1, 0, 0, 0, 0, 0, 0, 0, 0, 9, 255];
+        let (option, data) = DhcpOption::deserialize(&data).unwrap();
+        assert_eq!(
+            option,
+            DhcpOption::Authentication(Authentication {
+                protocol: 3,
+                algorithm: 1,
+                rdm: 0,
+                replay_detection: 9,
+                information: Vec::new(),
+            })
+        );
+        assert_eq!(data, &[255]);
+
+        assert!(DhcpOption::deserialize(&[90, 10, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+        assert!(DhcpOption::deserialize(&[90, 12, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn option_forcerenew_nonce_capable() {
+        let option = DhcpOption::ForcerenewNonceCapable(vec![1]);
+        assert_eq!(option.serialize().unwrap(), vec![145, 1, 1]);
+
+        let data = vec![145, 2, 1, 2, 255];
+        let (option, data) = DhcpOption::deserialize(&data).unwrap();
+        assert_eq!(option, DhcpOption::ForcerenewNonceCapable(vec![1, 2]));
+        assert_eq!(data, &[255]);
+
+        assert!(DhcpOption::deserialize(&[145, 0, 255]).is_err());
+    }
+
+    #[test]
+    fn option_unknown() {
+        let option = DhcpOption::Unknown(224, vec![1, 2]);
+        assert_eq!(option.serialize().unwrap(), vec![224, 2, 1, 2]);
+
+        let data = vec![224, 2, 1, 2, 255];
+        let (option, data) = DhcpOption::deserialize(&data).unwrap();
+        assert_eq!(option, DhcpOption::Unknown(224, vec![1, 2]));
+        assert_eq!(data, &[255]);
+
+        assert!(DhcpOption::deserialize(&[224, 3, 1, 2]).is_err());
+    }
+
+    #[test]
+    fn option_length_checks() {
+        // Missing length.
+        assert!(DhcpOption::deserialize(&[3]).is_err());
+        // Length past the end of the data.
+        assert!(DhcpOption::deserialize(&[3, 8, 10, 0, 0, 1]).is_err());
+        assert!(DhcpOption::deserialize(&[12, 5, 104, 111, 115, 116]).is_err());
+        // Length not matching a fixed length option.
+        assert!(DhcpOption::deserialize(&[1, 2, 255, 255, 0, 0]).is_err());
+        assert!(DhcpOption::deserialize(&[26, 4, 5, 220, 0, 0]).is_err());
+        // Pad and End have no length.
+        assert_eq!(
+            DhcpOption::deserialize(&[0]).unwrap(),
+            (DhcpOption::Pad, &[][..])
+        );
+    }
+
+    #[test]
+    fn option_errors() {
+        assert!(matches!(
+            DhcpOption::deserialize(&[3]),
+            Err(DhcpError::Truncated {
+                code: 3,
+                needed: 1,
+                available: 0
+            })
+        ));
+        assert!(matches!(
+            DhcpOption::deserialize(&[3, 8, 10, 0, 0, 1]),
+            Err(DhcpError::Truncated {
+                code: 3,
+                needed: 9,
+                available: 5
+            })
+        ));
+        assert!(matches!(
+            DhcpOption::deserialize(&[1, 2, 255, 255]),
+            Err(DhcpError::InvalidLength { code: 1, len: 2 })
+        ));
+        assert!(matches!(
+            DhcpOption::deserialize(&[3, 6, 10, 0, 0, 1, 10, 0]),
+            Err(DhcpError::InvalidLength { code: 3, len: 6 })
+        ));
+        assert!(matches!(
+            DhcpOption::deserialize(&[12, 2, 0xC3, 0x28]),
+            Err(DhcpError::InvalidUtf8 { code: 12 })
+        ));
+        assert!(matches!(
+            DhcpOption::deserialize(&[46, 1, 3]),
+            Err(DhcpError::InvalidValue { code: 46, .. })
+        ));
+
+        assert_eq!(
+            DhcpOption::deserialize(&[3, 6, 10, 0, 0, 1, 10, 0])
+                .unwrap_err()
+                .to_string(),
+            "Parsing Error: Option 3 has an invalid length of 6"
+        );
+        assert_eq!(
+            DhcpOption::deserialize(&[3, 8, 10, 0, 0, 1])
+                .unwrap_err()
+                .to_string(),
+            "Parsing Error: Option 3 is truncated, 9 bytes needed but 5 available"
+        );
+    }
+
+    #[test]
+    fn option_deserialize_all() {
+        let data = [
+            0, 1, 4, 255, 255, 255, 0, 0, 0, 12, 3, b'a', b'b', b'c', 255, 0, 0,
+        ];
+        assert_eq!(
+            DhcpOption::deserialize_all(&data).unwrap(),
+            [
+                DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+                DhcpOption::HostName("abc".to_string()),
+            ]
+        );
+        // Without End, the options end with the data.
+        assert_eq!(DhcpOption::deserialize_all(&data[..14]).unwrap().len(), 2);
+        assert!(DhcpOption::deserialize_all(&[]).unwrap().is_empty());
+
+        let garbage = [3, 4, 10, 0, 0, 1, 255, 0, 7, 0];
+        match DhcpOption::deserialize_all(&garbage) {
+            Err(DhcpError::Context { offset, error, .. }) => {
+                assert_eq!(offset, 8);
+                assert_eq!(
+                    error.to_string(),
+                    "Parsing Error: Data after the End option"
+                );
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert!(matches!(
+            DhcpOption::deserialize_all(&[3, 4, 10, 0]),
+            Err(DhcpError::Context {
+                offset: 0,
+                code: Some(3),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn option_serialization_errors() {
+        // Long values are split rather than rejected (RFC 3396).
+        let host_name = DhcpOption::HostName("a".repeat(256)).serialize().unwrap();
+        assert_eq!(host_name.len(), 260);
+        assert_eq!(&host_name[..2], &[12, 255]);
+        assert_eq!(&host_name[257..], &[12, 1, b'a']);
+        let router = DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 1); 64].into());
+        assert_eq!(router.serialize().unwrap().len(), router.wire_len());
+        assert_eq!(
+            DhcpOption::Unknown(200, vec![0; 255])
+                .serialize()
+                .unwrap()
+                .len(),
+            257
+        );
+
+        assert!(matches!(
+            DhcpOption::ClientIdentifier(vec![]).serialize(),
+            Err(DhcpError::SerializationError(
+                SerializationError::MissingValue { code: 61 }
+            ))
+        ));
+        assert_eq!(
+            DhcpOption::MobileIpHomeAgent(vec![].into())
+                .serialize()
+                .unwrap(),
+            vec![68, 0]
+        );
+    }
+
+    #[test]
+    fn option_serialization_invalid_values() {
+        let invalid = [
+            // A client identifier has a type and at least one byte.
+            (DhcpOption::ClientIdentifier(vec![1]), 61, 1),
+            (DhcpOption::Unknown(1, vec![1, 2, 3]), 1, 3),
+            (DhcpOption::Unknown(0, vec![1]), 0, 1),
+            (DhcpOption::Unknown(255, vec![]), 255, 0),
+            (DhcpOption::Router(vec![].into()), 3, 0),
+        ];
+        for (option, code, len) in invalid {
+            let mut buffer = vec![1, 2];
+            let error = match option.serialize_into(&mut buffer) {
+                Err(DhcpError::SerializationError(error)) => error,
+                result => panic!("{:?}: {:?}", option, result),
+            };
+            if len == 0 && code != 255 {
+                assert_eq!(error, SerializationError::MissingValue { code });
+            } else {
+                assert_eq!(error, SerializationError::InvalidLength { code, len });
+            }
+            assert_eq!(buffer, [1, 2]);
+        }
+
+        // Values a known option represents are not sent as unknown.
+        assert!(matches!(
+            DhcpOption::Unknown(1, vec![255, 255, 255, 0]).serialize(),
+            Err(DhcpError::SerializationError(
+                SerializationError::KnownCode { code: 1 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn option_serialization_round_trip() {
+        // Whatever serializes parses back to the same option.
+        let options = [
+            DhcpOption::ClientIdentifier(vec![1, 2]),
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+            DhcpOption::MobileIpHomeAgent(vec![].into()),
+            // Newer message types and text that is not UTF-8.
+            DhcpOption::Unknown(53, vec![9]),
+            DhcpOption::Unknown(12, vec![b'a', 0xe9]),
+            DhcpOption::Unknown(56, b"no address".to_vec()),
+            DhcpOption::Unknown(200, vec![1, 2, 3]),
+            DhcpOption::Unknown(201, vec![]),
+        ];
+        for option in options {
+            let data = option.serialize().unwrap();
+            let (parsed, rest) = DhcpOption::deserialize_with(&data, TextPolicy::Raw).unwrap();
+            assert_eq!(parsed, option);
+            assert!(rest.is_empty());
+        }
+    }
+
+    #[test]
+    fn option_wire_len() {
+        for code in 0..=255u8 {
+            for len in 0..=24u8 {
+                let mut data = vec![code, len];
+                data.extend((1..=len).map(|i| i.wrapping_mul(4)));
+                let option = match DhcpOption::deserialize(&data) {
+                    Ok((option, _)) => option,
+                    Err(_) => continue,
+                };
+                if let Ok(serialized) = option.serialize() {
+                    assert_eq!(option.wire_len(), serialized.len(), "{:?}", option);
+                }
+            }
+        }
+
+        let search = DhcpOption::DomainSearch(vec!["eng.example.com".into(), "example.".into()]);
+        assert_eq!(search.wire_len(), search.serialize().unwrap().len());
+        assert_eq!(DhcpOption::HostName("a".repeat(300)).wire_len(), 304);
+
+        // Each part of 255 bytes takes its own code and length.
+        for len in [1, 254, 255, 256, 509, 510, 511, 1000] {
+            let option = DhcpOption::VendorSpecificInformation(vec![7; len]);
+            let serialized = option.serialize().unwrap();
+            assert_eq!(option.wire_len(), serialized.len(), "{}", len);
+            assert_eq!(option.wire_len(), len + 2 * len.div_ceil(255));
+        }
+    }
+
+    #[test]
+    fn option_addresses() {
+        let first = Ipv4Addr::new(10, 0, 0, 1);
+        let mut addresses = Addresses::new();
+        assert!(addresses.is_empty());
+        addresses.push(first);
+        addresses.push(Ipv4Addr::new(10, 0, 0, 2));
+        assert_eq!(
+            addresses,
+            Addresses::from([first, Ipv4Addr::new(10, 0, 0, 2)])
+        );
+        addresses.push(Ipv4Addr::new(10, 0, 0, 3));
+        assert_eq!(addresses.len(), 3);
+        assert_eq!(addresses[2], Ipv4Addr::new(10, 0, 0, 3));
+        assert_eq!(
+            addresses,
+            (1..=3)
+                .map(|i| Ipv4Addr::new(10, 0, 0, i))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(format!("{:?}", Addresses::from([first])), "[10.0.0.1]");
+        assert_eq!(Vec::from(Addresses::from([first])), vec![first]);
+    }
+
+    #[test]
+    fn option_dhcp_message_type() {
+        let option = DhcpOption::DhcpMessageType(MessageType::Ack);
+        assert_eq!(option.serialize().unwrap(), [53, 1, 5]);
+        assert_eq!(DhcpOption::deserialize(&[53, 1, 5]).unwrap().0, option);
+        assert_eq!(DhcpOption::from(MessageType::Ack), option);
+        assert_eq!(
+            option.to_string(),
+            "DHCP Message Type (53), length 1: DHCPACK"
+        );
+        // Types of later RFCs, such as DHCPFORCERENEW, are kept raw.
+        assert_eq!(
+            DhcpOption::deserialize(&[53, 1, 9]).unwrap().0,
+            DhcpOption::Unknown(53, vec![9])
+        );
+        assert!(matches!(
+            DhcpOption::deserialize(&[53, 2, 1, 1]),
+            Err(DhcpError::InvalidLength { code: 53, len: 2 })
+        ));
+
+        // Repeated message types are concatenated, as the parts of a
+        // single option, which is then too long.
+        let mut data = DhcpMessage::new(1, 1).serialize().unwrap();
+        data.truncate(data.len() - 1);
+        data.extend_from_slice(&[53, 1, 1, 53, 1, 3, 255]);
+        assert!(DhcpMessage::deserialize(&data).is_err());
+    }
+
+    #[test]
+    fn option_server_identifier() {
+        let option = DhcpOption::ServerIdentifier(Ipv4Addr::new(192, 168, 1, 1));
+        assert_eq!(option.serialize().unwrap(), [54, 4, 192, 168, 1, 1]);
+        assert_eq!(
+            DhcpOption::deserialize(&[54, 4, 192, 168, 1, 1]).unwrap().0,
+            option
+        );
+        assert_eq!(
+            option.to_string(),
+            "Server Identifier (54), length 4: 192.168.1.1"
+        );
+        assert!(matches!(
+            DhcpOption::deserialize(&[54, 3, 192, 168, 1]),
+            Err(DhcpError::InvalidLength { code: 54, len: 3 })
+        ));
+
+        let mut message = DhcpMessage::new(2, 1);
+        assert_eq!(message.server_identifier(), None);
+        message.options.push(option);
+        assert_eq!(
+            message.server_identifier(),
+            Some(Ipv4Addr::new(192, 168, 1, 1))
+        );
+    }
+
+    #[test]
+    fn option_empty_values() {
+        // Mobile IP Home Agent is the only list that may be empty.
+        assert!(info(68).unwrap().len.allows_empty());
+        assert!(!info(3).unwrap().len.allows_empty());
+        assert!(!info(0).unwrap().len.allows_empty());
+        assert_eq!(info(12).unwrap().len.min_len(), 1);
+        assert_eq!(
+            DhcpOption::deserialize(&[68, 0]).unwrap().0,
+            DhcpOption::MobileIpHomeAgent(vec![].into())
+        );
+        assert_eq!(
+            DhcpOption::MobileIpHomeAgent(vec![].into())
+                .serialize()
+                .unwrap(),
+            [68, 0]
+        );
+
+        for code in 1..=254u8 {
+            let allows_empty = info(code).is_none_or(|info| info.len.allows_empty());
+            // The minimum length applies to the value, whatever follows it.
+            let data = [code, 0, 1, 2, 3, 4, 5, 6, 7, 8];
+            let parsed = DhcpOption::deserialize(&data);
+            assert_eq!(parsed.is_ok(), allows_empty, "{}", code);
+            if let Ok((option, rest)) = &parsed {
+                assert_eq!(rest.len(), 8);
+                assert_eq!(option.serialize().unwrap(), [code, 0]);
+            }
+            // Known options are only sent as unknown when they parse so.
+            assert_eq!(
+                DhcpOption::Unknown(code, vec![]).serialize().is_ok(),
+                matches!(parsed, Ok((DhcpOption::Unknown(..), _))),
+                "{}",
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn option_info() {
+        let router = info(3).unwrap();
+        assert_eq!(router.code, 3);
+        assert_eq!(router.name, "Router");
+        assert_eq!(router.len, LengthRule::List { min: 4, unit: 4 });
+        assert_eq!(info(0).unwrap().len, LengthRule::Empty);
+        assert_eq!(info(51).unwrap().len, LengthRule::Fixed(4));
+        assert_eq!(info(53).unwrap().name, "DHCP Message Type");
+        assert!(info(77).is_none());
+
+        // The table is the source of the known codes.
+        for code in 0..=255u8 {
+            assert_eq!(is_known_code(code), info(code).is_some());
+            assert_eq!(info(code).map_or(code, |info| info.code), code);
+        }
+
+        // List rules are enforced for every list option.
+        for code in [25, 48, 49] {
+            assert!(matches!(
+                DhcpOption::deserialize(&[code, 5, 0, 0, 0, 0, 0]),
+                Err(DhcpError::InvalidLength { len: 5, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn option_text_policy() {
+        // Every text option, the NIS domains included, follows the policy.
+        for code in [12, 15, 40, 64] {
+            let data = [code, 2, b'a', 0xe9, 255];
+            assert!(matches!(
+                DhcpOption::deserialize(&data),
+                Err(DhcpError::InvalidUtf8 { code: invalid }) if invalid == code
+            ));
+            let (option, rest) = DhcpOption::deserialize_with(&data, TextPolicy::Lossy).unwrap();
+            assert_eq!(
+                option.serialize().unwrap(),
+                [code, 4, b'a', 0xef, 0xbf, 0xbd]
+            );
+            assert_eq!(rest, [255]);
+            assert_eq!(
+                DhcpOption::deserialize_with(&data, TextPolicy::Raw).unwrap(),
+                (DhcpOption::Unknown(code, vec![b'a', 0xe9]), &[255][..])
+            );
+            assert_eq!(
+                DhcpOption::deserialize_value_with(code, &data[2..4], TextPolicy::Raw).unwrap(),
+                DhcpOption::Unknown(code, vec![b'a', 0xe9])
+            );
+        }
+        assert_eq!(
+            DhcpOption::deserialize_with(&[40, 3, b'n', b'i', b's'], TextPolicy::Raw).unwrap(),
+            (
+                DhcpOption::NetworkInformationServiceDomain("nis".to_string()),
+                &[][..]
+            )
+        );
+
+        let data = [12, 1, 0xe9, 40, 1, b'a', 40, 1, 0xff];
+        assert!(DhcpOption::deserialize_all(&data).is_err());
+        let config = ParserConfig {
+            text: TextPolicy::Lossy,
+            ..ParserConfig::default()
+        };
+        assert_eq!(
+            DhcpOption::deserialize_all_with(&data, &config).unwrap(),
+            vec![
+                DhcpOption::HostName("\u{fffd}".to_string()),
+                DhcpOption::NetworkInformationServiceDomain("a\u{fffd}".to_string()),
+            ]
+        );
+    }
 }
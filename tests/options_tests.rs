@@ -0,0 +1,285 @@
+use std::net::Ipv4Addr;
+
+use dhcp::message::{DhcpMessage, MessageType, ParserConfig, SerializerConfig};
+use dhcp::option::DhcpOption;
+use dhcp::options::{filter_by_prl, DhcpOptions, DuplicatePolicy};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_get() {
+        let mut options = DhcpOptions::new();
+        assert!(options.get(53).is_none());
+        options.push(DhcpOption::DhcpMessageType(MessageType::Discover));
+        options.push(DhcpOption::HostName("first".to_string()));
+        options.push(DhcpOption::HostName("second".to_string()));
+        assert_eq!(
+            options.get(53),
+            Some(&DhcpOption::DhcpMessageType(MessageType::Discover))
+        );
+        assert_eq!(
+            options.get(12),
+            Some(&DhcpOption::HostName("first".to_string()))
+        );
+        assert!(options.get(50).is_none());
+
+        // Removing options keeps the index up to date.
+        options.pop();
+        assert_eq!(
+            options.get(12),
+            Some(&DhcpOption::HostName("first".to_string()))
+        );
+        options.pop();
+        assert!(options.get(12).is_none());
+        options.extend_from_slice(&[
+            DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, 1)),
+            DhcpOption::HostName("host".to_string()),
+        ]);
+        options.retain(|option| !matches!(option, DhcpOption::DhcpMessageType(_)));
+        assert!(options.get(53).is_none());
+        assert_eq!(
+            options[0],
+            DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, 1))
+        );
+        assert_eq!(options.get(12), Some(&options[1]));
+        options.clear();
+        assert!(options.is_empty());
+        assert!(options.get(50).is_none());
+    }
+
+    #[test]
+    fn options_map() {
+        let mut options = DhcpOptions::new();
+        assert!(!options.contains(61));
+        assert_eq!(
+            options.insert(DhcpOption::DhcpMessageType(MessageType::Discover)),
+            None
+        );
+        options.push(DhcpOption::ClientIdentifier(vec![1, 2]));
+        options.push(DhcpOption::HostName("first".to_string()));
+        options.push(DhcpOption::HostName("second".to_string()));
+        assert!(options.contains(61));
+
+        // Inserting replaces the first option of the code in place.
+        assert_eq!(
+            options.insert(DhcpOption::ClientIdentifier(vec![3])),
+            Some(DhcpOption::ClientIdentifier(vec![1, 2]))
+        );
+        assert_eq!(options[1], DhcpOption::ClientIdentifier(vec![3]));
+
+        // Removing drops every option of the code.
+        assert_eq!(
+            options.remove(12),
+            Some(DhcpOption::HostName("first".to_string()))
+        );
+        assert!(!options.contains(12));
+        assert_eq!(options.remove(12), None);
+        assert_eq!(options.len(), 2);
+        assert_eq!(
+            options.remove(53),
+            Some(DhcpOption::DhcpMessageType(MessageType::Discover))
+        );
+        assert_eq!(options.get(61), Some(&options[0]));
+    }
+
+    #[test]
+    fn options_dedupe() {
+        let router = |last: u8| DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, last)].into());
+        let options: DhcpOptions = vec![
+            DhcpOption::HostName("first".to_string()),
+            router(1),
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 0, 0, 0)),
+            DhcpOption::Pad,
+            DhcpOption::HostName("-second".to_string()),
+            router(2),
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 0, 0)),
+            DhcpOption::Pad,
+        ]
+        .into();
+
+        let mut kept = options.clone();
+        kept.dedupe(DuplicatePolicy::Keep);
+        assert_eq!(kept, options);
+
+        let mut first = options.clone();
+        first.dedupe(DuplicatePolicy::FirstWins);
+        assert_eq!(
+            first,
+            vec![
+                DhcpOption::HostName("first".to_string()),
+                router(1),
+                DhcpOption::SubnetMask(Ipv4Addr::new(255, 0, 0, 0)),
+                DhcpOption::Pad,
+                DhcpOption::Pad,
+            ]
+        );
+
+        let mut last = options.clone();
+        last.dedupe(DuplicatePolicy::LastWins);
+        assert_eq!(last.len(), 5);
+        assert_eq!(
+            last.get(12),
+            Some(&DhcpOption::HostName("-second".to_string()))
+        );
+        assert_eq!(last.get(3), Some(&router(2)));
+
+        // Two subnet masks do not make a valid value, and are kept apart.
+        let mut concatenated = options.clone();
+        concatenated.dedupe(DuplicatePolicy::Concatenate);
+        assert_eq!(
+            concatenated,
+            vec![
+                DhcpOption::HostName("first-second".to_string()),
+                DhcpOption::Router(
+                    vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)].into()
+                ),
+                DhcpOption::SubnetMask(Ipv4Addr::new(255, 0, 0, 0)),
+                DhcpOption::Pad,
+                DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 0, 0)),
+                DhcpOption::Pad,
+            ]
+        );
+        assert_eq!(concatenated.get(1), Some(&concatenated[2]));
+
+        // The parser applies the policy of its settings.
+        let mut message = DhcpMessage::new(1, 1);
+        message.options = options;
+        let data = message.serialize().unwrap();
+        let config = ParserConfig {
+            duplicates: DuplicatePolicy::FirstWins,
+            ..ParserConfig::default()
+        };
+        let parsed = DhcpMessage::deserialize_with(&data, &config).unwrap();
+        assert_eq!(parsed.options.len(), 3);
+        assert_eq!(parsed.options.get(3), Some(&router(1)));
+        assert_eq!(DhcpMessage::deserialize(&data).unwrap().options.len(), 6);
+    }
+
+    #[test]
+    fn options_order() {
+        let mut options: DhcpOptions = vec![
+            DhcpOption::Unknown(82, vec![1, 1, 0]),
+            DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 1)].into()),
+            DhcpOption::DomainName("lan".to_string()),
+            DhcpOption::Pad,
+            DhcpOption::Unknown(58, vec![0, 0, 7, 8]),
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+            DhcpOption::ServerIdentifier(Ipv4Addr::new(10, 0, 0, 1)),
+            DhcpOption::IpAddressLeaseTime(3600),
+            DhcpOption::DomainName("second".to_string()),
+            DhcpOption::DhcpMessageType(MessageType::Ack),
+        ]
+        .into();
+        options.order();
+        let codes: Vec<u8> = options
+            .iter()
+            .map(|option| u8::from(option.code()))
+            .collect();
+        assert_eq!(codes, [53, 54, 51, 58, 1, 3, 15, 15, 82, 0]);
+        assert_eq!(options[6], DhcpOption::DomainName("lan".to_string()));
+        assert_eq!(options.get(1), Some(&options[4]));
+
+        // The reply settings serialize the options in that order.
+        let mut reply = DhcpMessage::new(2, 1);
+        reply.options = vec![
+            DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 1)].into()),
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+        ]
+        .into();
+        let request = DhcpMessage::new(1, 1);
+        let data = reply
+            .serialize_with(&SerializerConfig::for_request(&request))
+            .unwrap();
+        assert_eq!(&data[240..242], &[1, 4]);
+        let data = reply.serialize_with(&SerializerConfig::default()).unwrap();
+        assert_eq!(&data[240..242], &[3, 4]);
+    }
+
+    #[test]
+    fn options_filter_by_prl() {
+        let options: DhcpOptions = vec![
+            DhcpOption::DhcpMessageType(MessageType::Ack),
+            DhcpOption::ServerIdentifier(Ipv4Addr::new(10, 0, 0, 1)),
+            DhcpOption::IpAddressLeaseTime(3600),
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+            DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 1)].into()),
+            DhcpOption::DomainNameServer(vec![Ipv4Addr::new(10, 0, 0, 1)].into()),
+            DhcpOption::DomainName("lan".to_string()),
+            DhcpOption::Unknown(82, vec![1, 1, 0]),
+            DhcpOption::Unknown(58, vec![0, 0, 7, 8]),
+        ]
+        .into();
+
+        // Requested options follow the client's order, unknown and
+        // repeated codes being ignored, but the subnet mask always comes
+        // before the router.
+        let filtered = filter_by_prl(&options, &[6, 3, 1, 6, 120, 51]);
+        let codes: Vec<u8> = filtered
+            .iter()
+            .map(|option| u8::from(option.code()))
+            .collect();
+        assert_eq!(codes, [53, 54, 51, 58, 6, 1, 3, 82]);
+        assert_eq!(filtered.get(3), Some(&options[4]));
+
+        let codes: Vec<u8> = filter_by_prl(&options, &[3, 15, 1])
+            .iter()
+            .map(|option| u8::from(option.code()))
+            .collect();
+        assert_eq!(codes, [53, 54, 51, 58, 1, 3, 15, 82]);
+        let codes: Vec<u8> = filter_by_prl(&options, &[3, 6])
+            .iter()
+            .map(|option| u8::from(option.code()))
+            .collect();
+        assert_eq!(codes, [53, 54, 51, 58, 3, 6, 82]);
+
+        let codes: Vec<u8> = filter_by_prl(&options, &[])
+            .iter()
+            .map(|option| u8::from(option.code()))
+            .collect();
+        assert_eq!(codes, [53, 54, 51, 58, 82]);
+
+        let mut request = DhcpMessage::new(1, 1);
+        assert_eq!(request.parameter_request_list(), None);
+        request.options.push(DhcpOption::Unknown(55, vec![1, 3]));
+        assert_eq!(request.parameter_request_list(), Some(&[1, 3][..]));
+    }
+
+    #[test]
+    fn options_conversions() {
+        let list = vec![
+            DhcpOption::DhcpMessageType(MessageType::Discover),
+            DhcpOption::Unknown(224, vec![7]),
+        ];
+        let options = DhcpOptions::from(list.clone());
+        assert_eq!(options, list);
+        assert_eq!(options.get(224), Some(&list[1]));
+        assert_eq!(format!("{:?}", options), format!("{:?}", list));
+        assert_eq!(list.iter().cloned().collect::<DhcpOptions>(), options);
+        assert_eq!(Vec::from(options), list);
+    }
+
+    #[test]
+    fn options_message_get_option() {
+        let mut message = DhcpMessage::new(1, 1);
+        message.options = (0..60)
+            .map(|code| DhcpOption::Unknown(200 + code % 50, vec![code]))
+            .collect();
+        message
+            .options
+            .push(DhcpOption::DhcpMessageType(MessageType::Request));
+        let data = message.serialize().unwrap();
+
+        let parsed = DhcpMessage::deserialize(&data).unwrap();
+        assert_eq!(
+            parsed.get_option(53),
+            Some(&DhcpOption::DhcpMessageType(MessageType::Request))
+        );
+        assert_eq!(
+            parsed.get_option(210),
+            Some(&DhcpOption::Unknown(210, vec![10]))
+        );
+        assert!(parsed.get_option(50).is_none());
+    }
+}
@@ -0,0 +1,58 @@
+use std::net::{Ipv4Addr, SocketAddrV4};
+
+use dhcp::error::{DhcpError, ErrorKind};
+use dhcp::message::DhcpMessage;
+
+const MAC: [u8; 6] = [0x02, 0, 0, 0, 0, 1];
+const SERVER: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 1);
+const CLIENT: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 100);
+const RELAY: Ipv4Addr = Ipv4Addr::new(10, 0, 0, 1);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relay_hops() {
+        let mut discover = DhcpMessage::discover(1, MAC);
+        assert!(!discover.is_relayed());
+        discover.giaddr = RELAY;
+        assert!(discover.is_relayed());
+
+        assert_eq!(discover.increment_hops().unwrap(), 1);
+        discover.hops = DhcpMessage::MAX_HOPS;
+        let error = discover.increment_hops().unwrap_err();
+        assert!(matches!(error, DhcpError::TooManyHops { hops: 16 }));
+        assert_eq!(error.kind(), ErrorKind::Protocol);
+        assert_eq!(discover.hops, DhcpMessage::MAX_HOPS);
+    }
+
+    #[test]
+    fn relay_reply_destination() {
+        let mut discover = DhcpMessage::discover(1, MAC);
+        let offer = DhcpMessage::offer(&discover, CLIENT, SERVER, 3600);
+        assert_eq!(
+            discover.reply_destination(&offer),
+            SocketAddrV4::new(Ipv4Addr::BROADCAST, 68)
+        );
+        discover.giaddr = RELAY;
+        assert_eq!(
+            discover.reply_destination(&offer),
+            SocketAddrV4::new(RELAY, 67)
+        );
+
+        // Renewing clients are answered at their address, but for a NAK.
+        let mut request = DhcpMessage::request(&offer).unwrap();
+        request.ciaddr = CLIENT;
+        let ack = DhcpMessage::ack(&request, CLIENT, SERVER, 3600);
+        assert_eq!(
+            request.reply_destination(&ack),
+            SocketAddrV4::new(CLIENT, 68)
+        );
+        let nak = DhcpMessage::nak(&request, SERVER, "wrong network");
+        assert_eq!(
+            request.reply_destination(&nak),
+            SocketAddrV4::new(Ipv4Addr::BROADCAST, 68)
+        );
+    }
+}
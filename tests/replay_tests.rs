@@ -0,0 +1,74 @@
+#![cfg(feature = "json")]
+
+use dhcp::replay::{Expectation, Scenario};
+use dhcp::server::{Server, ServerConfig};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        server_identifier = "10.0.0.1"
+        pool_start = "10.0.0.10"
+        pool_end = "10.0.0.20"
+        subnet_mask = "255.255.255.0"
+    "#;
+
+    const SCENARIO: &str = r#"{"steps": [
+        {"time": 100,
+         "send": {"xid": 1, "chaddr": "00:00:00:00:00:01",
+                  "options": [{"name": "dhcp_message_type", "value": 1}]},
+         "expect": {"xid": 1, "yiaddr": "10.0.0.10",
+                    "options": [{"name": "dhcp_message_type", "value": 2},
+                                {"name": "server_identifier", "value": "10.0.0.1"}]}},
+        {"send": {"xid": 2, "chaddr": "00:00:00:00:00:01",
+                  "options": [{"name": "dhcp_message_type", "value": 3},
+                              {"name": "requested_ip_address", "value": "10.0.0.10"}]},
+         "expect": {"options": [{"name": "dhcp_message_type", "value": 5}]}},
+        {"time": 200,
+         "send": {"xid": 3, "chaddr": "00:00:00:00:00:01", "ciaddr": "10.0.0.10",
+                  "options": [{"name": "dhcp_message_type", "value": 7},
+                              {"name": "server_identifier", "value": "10.0.0.1"}]},
+         "expect": null}
+    ]}"#;
+
+    #[test]
+    fn replay_from_json() {
+        let scenario = Scenario::from_json(SCENARIO).unwrap();
+        assert_eq!(scenario.steps.len(), 3);
+        assert_eq!(scenario.steps[1].time, 100);
+        assert_eq!(scenario.steps[2].time, 200);
+        assert_eq!(scenario.steps[2].expect, Expectation::Nothing);
+        match &scenario.steps[0].expect {
+            Expectation::Reply { fields, .. } => assert_eq!(fields, &["xid", "yiaddr", "options"]),
+            expect => panic!("unexpected expectation {:?}", expect),
+        }
+
+        assert!(Scenario::from_json("{}").is_err());
+        assert!(Scenario::from_json("{\"steps\": [{\"expect\": null}]}").is_err());
+        assert!(Scenario::from_json("{\"steps\": [{\"send\": {}, \"wait\": 1}]}").is_err());
+    }
+
+    #[test]
+    fn replay_run() {
+        let scenario = Scenario::from_json(SCENARIO).unwrap();
+        let mut server = Server::new(ServerConfig::from_config(CONFIG).unwrap());
+        scenario.run(&mut server).unwrap();
+
+        // The released address is offered again, to another client.
+        let scenario =
+            Scenario::from_json(&SCENARIO.replace("00:00:00:00:00:01", "00:00:00:00:00:02"))
+                .unwrap();
+        scenario.run(&mut server).unwrap();
+
+        let scenario = Scenario::from_json(
+            &SCENARIO.replace("\"yiaddr\": \"10.0.0.10\"", "\"yiaddr\": \"10.0.0.11\""),
+        )
+        .unwrap();
+        let mut server = Server::new(ServerConfig::from_config(CONFIG).unwrap());
+        let mismatch = scenario.run(&mut server).unwrap_err();
+        assert_eq!(mismatch.step, 0);
+        assert_eq!(mismatch.field, "yiaddr");
+        assert_eq!(mismatch.actual, "10.0.0.10");
+    }
+}
@@ -0,0 +1,93 @@
+use dhcp::hexdump::hexdump;
+use dhcp::message::DhcpMessage;
+use dhcp::option::DhcpOption;
+use dhcp::option_ref::DhcpOptionRef;
+use dhcp::v6::message::Dhcpv6Packet;
+use dhcp::v6::option::Dhcpv6Option;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A small xorshift generator, so failures are reproducible.
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        // Random bytes, with small values likely in the length positions.
+        fn bytes(&mut self, max_len: u64) -> Vec<u8> {
+            let len = self.next() % max_len;
+            (0..len)
+                .map(|index| match index % 2 {
+                    1 if self.next().is_multiple_of(2) => (self.next() % 8) as u8,
+                    _ => self.next() as u8,
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn robustness_random_options() {
+        let mut random = Random(0x1234_5678_9abc_def1);
+        for _ in 0..20000 {
+            let data = random.bytes(64);
+            let _ = DhcpOption::deserialize(&data);
+
+            let mut data = data;
+            if data.len() >= 4 {
+                data[0] = 0;
+                data[2] = 0;
+            }
+            let _ = Dhcpv6Option::deserialize_all(&data);
+            let _ = Dhcpv6Packet::deserialize(&data);
+        }
+    }
+
+    #[test]
+    fn robustness_random_messages() {
+        let mut random = Random(0x0fed_cba9_8765_4321);
+        let header = DhcpMessage::new(1, 1).serialize().unwrap();
+        for _ in 0..20000 {
+            let mut data = header[..DhcpMessage::HEADER_LEN].to_vec();
+            data.extend_from_slice(&random.bytes(64));
+            let len = (random.next() % (data.len() as u64 + 1)) as usize;
+            data.truncate(len.max(DhcpMessage::HEADER_LEN - 8));
+
+            if let Ok(message) = DhcpMessage::deserialize(&data) {
+                assert_eq!(
+                    DhcpMessage::deserialize(&message.serialize().unwrap()).unwrap(),
+                    message
+                );
+            }
+            let _ = hexdump(&data);
+        }
+    }
+
+    #[test]
+    fn robustness_short_options() {
+        // Every code with every short length byte and short value, so no
+        // length check can be skipped.
+        for code in 0..=255u8 {
+            for len in 0..=12u8 {
+                for available in 0..=14usize {
+                    for fill in [0, 1, 0xff, 0xc0] {
+                        let mut data = vec![code, len];
+                        data.resize(2 + available, fill);
+                        let _ = DhcpOption::deserialize(&data);
+                        let _ = DhcpOptionRef::deserialize(&data);
+                        let _ = DhcpOption::deserialize_value(code, &data[2..]);
+                        let _ = DhcpOption::deserialize_all(&data);
+                        let _ = DhcpOption::deserialize(&data[..1]);
+                        let _ = DhcpOptionRef::deserialize(&data[..1]);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::{Duration, Instant};
+
+use dhcp::message::DhcpMessage;
+use dhcp::secs::SecsTracker;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secs_elapsed() {
+        let start = Instant::now();
+        let tracker = SecsTracker::new(start);
+        assert_eq!(tracker.secs_at(start), 0);
+        assert_eq!(tracker.secs_at(start + Duration::from_millis(4500)), 4);
+        // Clamped to the range of the field.
+        assert_eq!(
+            tracker.secs_at(start + Duration::from_secs(100_000)),
+            u16::MAX
+        );
+
+        let later = SecsTracker::new(start + Duration::from_secs(10));
+        assert_eq!(later.secs_at(start), 0);
+    }
+
+    #[test]
+    fn secs_stamp_retransmission() {
+        let start = Instant::now();
+        let tracker = SecsTracker::new(start);
+        let mut discover = DhcpMessage::discover(1, [0x02, 0, 0, 0, 0, 1]);
+        tracker.stamp(&mut discover, start + Duration::from_secs(12));
+        assert_eq!(discover.secs, 12);
+    }
+}
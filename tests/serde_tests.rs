@@ -0,0 +1,164 @@
+#![cfg(feature = "serde")]
+
+use std::net::Ipv4Addr;
+
+use dhcp::message::{DhcpMessage, Flags, MessageType};
+use dhcp::option::{Addresses, Authentication, DhcpOption, OptionOverload};
+
+fn message() -> DhcpMessage {
+    let mut message = DhcpMessage::new(2, 0xFFFFFFFF);
+    message.flags.set_broadcast(true);
+    message.yiaddr = Ipv4Addr::new(10, 0, 0, 5);
+    message.chaddr[..6].copy_from_slice(&[0, 0x11, 0x22, 0x33, 0x44, 0x55]);
+    message.sname[..4].copy_from_slice(b"boot");
+    message.file[..2].copy_from_slice(&[0xff, 0x41]);
+    message.options = vec![
+        DhcpOption::DhcpMessageType(MessageType::Offer),
+        DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+        DhcpOption::Router(vec![Ipv4Addr::new(10, 0, 0, 1)].into()),
+        DhcpOption::IpAddressLeaseTime(3600),
+        DhcpOption::HostName("host \"one\"".to_string()),
+        DhcpOption::IpForwarding(false),
+        DhcpOption::PathMtuPlateauTable(vec![68, 1500]),
+        DhcpOption::DomainSearch(vec!["example.com".to_string(), "lan".to_string()]),
+        DhcpOption::ClientIdentifier(vec![1, 0, 1, 2, 3, 4, 5]),
+        DhcpOption::Unknown(12, vec![0x63, 0xe9]),
+        DhcpOption::Unknown(224, vec![0xAB]),
+        DhcpOption::Unknown(225, vec![7; 300]),
+    ]
+    .into();
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_json() {
+        let message = message();
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["flags"], 32768);
+        assert_eq!(json["yiaddr"], "10.0.0.5");
+        assert_eq!(json["chaddr"], "00:11:22:33:44:55");
+        assert_eq!(json["sname"], "boot");
+        assert_eq!(json["file"]["hex"].as_str().unwrap().len(), 256);
+        assert_eq!(
+            json["options"][0],
+            serde_json::json!({"code": 53, "name": "dhcp_message_type", "value": 2})
+        );
+        assert_eq!(json["options"][2]["value"], serde_json::json!(["10.0.0.1"]));
+        assert_eq!(
+            json["options"][9]["value"],
+            serde_json::json!({"hex": "63e9"})
+        );
+        assert_eq!(
+            json["options"][10],
+            serde_json::json!({"code": 224, "value": "ab"})
+        );
+        assert_eq!(
+            serde_json::from_value::<DhcpMessage>(json).unwrap(),
+            message
+        );
+    }
+
+    #[test]
+    fn serde_cbor() {
+        let message = message();
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&message, &mut cbor).unwrap();
+        assert_eq!(
+            ciborium::from_reader::<DhcpMessage, _>(cbor.as_slice()).unwrap(),
+            message
+        );
+
+        // Binary formats carry byte strings rather than hex.
+        let value: ciborium::Value = ciborium::from_reader(cbor.as_slice()).unwrap();
+        let field = |name: &str| {
+            value
+                .as_map()
+                .unwrap()
+                .iter()
+                .find(|(key, _)| key.as_text() == Some(name))
+                .map(|(_, value)| value.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            field("chaddr").as_bytes().unwrap(),
+            &[0, 0x11, 0x22, 0x33, 0x44, 0x55]
+        );
+        assert_eq!(field("sname").as_bytes().unwrap(), b"boot");
+        assert_eq!(field("file").as_bytes().unwrap(), &[0xff, 0x41]);
+    }
+
+    #[test]
+    fn serde_options() {
+        let option: DhcpOption =
+            serde_json::from_str(r#"{"name": "requested_ip_address", "value": "10.0.0.5"}"#)
+                .unwrap();
+        assert_eq!(
+            option,
+            DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, 5))
+        );
+        let option: DhcpOption =
+            serde_json::from_str(r#"{"code": 54, "value": "0a000001"}"#).unwrap();
+        assert_eq!(
+            option,
+            DhcpOption::ServerIdentifier(Ipv4Addr::new(10, 0, 0, 1))
+        );
+        assert_eq!(
+            serde_json::to_string(&DhcpOption::End).unwrap(),
+            r#"{"code":255,"name":"end","value":null}"#
+        );
+
+        for json in [
+            r#"{"name": "nope"}"#,
+            r#"{"code": 1, "value": 1}"#,
+            r#"{"code": 51, "value": -1}"#,
+            r#"{"code": 26, "value": 65536}"#,
+            r#"{"code": 3, "value": ["10.0.0.1", 1]}"#,
+            r#"{"code": 12, "value": {"text": "a"}}"#,
+            r#"{"code": 1, "value": "255.255.255.0", "extra": 1}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<DhcpOption>(json).is_err(),
+                "{}",
+                json
+            );
+        }
+        assert!(serde_json::from_str::<DhcpMessage>(
+            r#"{"chaddr": "00:01:02:03:04:05:06:07:08:09:0a:0b:0c:0d:0e:0f:10"}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<DhcpMessage>(r#"{"unknown": 1}"#).is_err());
+    }
+
+    #[test]
+    fn serde_related_types() {
+        assert_eq!(
+            serde_json::to_string(&Flags::from_bits(0x8000)).unwrap(),
+            "32768"
+        );
+        assert_eq!(serde_json::to_string(&MessageType::Ack).unwrap(), "\"Ack\"");
+        assert_eq!(
+            serde_json::to_string(&OptionOverload::Both).unwrap(),
+            "\"Both\""
+        );
+        let addresses: Addresses = vec![Ipv4Addr::new(10, 0, 0, 1)].into();
+        assert_eq!(serde_json::to_string(&addresses).unwrap(), "[\"10.0.0.1\"]");
+
+        let authentication = Authentication {
+            protocol: 1,
+            algorithm: 1,
+            rdm: 0,
+            replay_detection: 7,
+            information: vec![0xde, 0xad],
+        };
+        let json = serde_json::to_string(&authentication).unwrap();
+        assert!(json.contains("\"information\":\"dead\""));
+        assert_eq!(
+            serde_json::from_str::<Authentication>(&json).unwrap(),
+            authentication
+        );
+    }
+}
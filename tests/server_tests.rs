@@ -0,0 +1,477 @@
+use std::net::{Ipv4Addr, SocketAddrV4};
+
+use dhcp::error::{DhcpError, ErrorKind};
+use dhcp::lease::{LeaseEvent, LeaseState};
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::option::DhcpOption;
+use dhcp::server::{reply_destination, Lease, LeaseTable, Server, ServerConfig};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        # Test subnet
+        server_identifier = "10.0.0.1"
+        pool_start = "10.0.0.10"
+        pool_end = "10.0.0.11"
+        subnet_mask = "255.255.255.0"
+        routers = ["10.0.0.1"]
+        domain_name = "lan"
+        lease_time = 3600
+    "#;
+
+    fn request(message_type: MessageType, mac: u8, options: Vec<DhcpOption>) -> DhcpMessage {
+        let mut message = DhcpMessage::new(1, mac as u32);
+        message.chaddr[..6].copy_from_slice(&[0, 0, 0, 0, 0, mac]);
+        message.options = vec![DhcpOption::from(message_type)].into();
+        message.options.extend(options);
+        message
+    }
+
+    fn message_type(message: &DhcpMessage) -> u8 {
+        match &message.options[0] {
+            DhcpOption::DhcpMessageType(message_type) => u8::from(*message_type),
+            option => panic!("unexpected option {:?}", option),
+        }
+    }
+
+    #[test]
+    fn server_config_parse() {
+        let config = ServerConfig::from_config(CONFIG).unwrap();
+        assert_eq!(config.server_identifier, Ipv4Addr::new(10, 0, 0, 1));
+        assert_eq!(config.pool_end, Ipv4Addr::new(10, 0, 0, 11));
+        assert_eq!(config.routers, vec![Ipv4Addr::new(10, 0, 0, 1)]);
+        assert!(config.dns_servers.is_empty());
+        assert_eq!(config.domain_name, Some("lan".to_string()));
+        assert_eq!(config.lease_time, 3600);
+        assert_eq!(config.decline_hold_time, 86400);
+
+        assert!(ServerConfig::from_config("pool_start = \"10.0.0.1\"").is_err());
+        assert!(
+            ServerConfig::from_config(&CONFIG.replace("\"10.0.0.11\"", "\"10.0.0.1\"")).is_err()
+        );
+        assert!(ServerConfig::from_config(&CONFIG.replace("3600", "forever")).is_err());
+        assert!(ServerConfig::from_config(&CONFIG.replace("3600", "-1")).is_err());
+        assert!(ServerConfig::from_config(&CONFIG.replace("3600", "\"3600\"")).is_err());
+
+        // Comments after values, and trailing commas in arrays.
+        let config = ServerConfig::from_config(&CONFIG.replace(
+            "routers = [\"10.0.0.1\"]",
+            "routers = [\"10.0.0.1\", \"10.0.0.2\",] # gateways",
+        ))
+        .unwrap();
+        assert_eq!(config.routers.len(), 2);
+
+        // Unknown keys, tables and duplicate keys are rejected.
+        for extra in ["lease = 3600", "[subnet]", "lease_time = 60"] {
+            assert!(ServerConfig::from_config(&format!("{}\n{}", CONFIG, extra)).is_err());
+        }
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn server_config_parse_toml() {
+        let config = ServerConfig::from_config(
+            r#"
+            server_identifier = '10.0.0.1'
+            pool_start = "10.0.0.10"
+            pool_end = "10.0.0.11"
+            subnet_mask = "255.255.255.0"
+            routers = [
+                "10.0.0.1", # gateway
+            ]
+            domain_name = "l\u0061n"
+            lease_time = 1_800
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.routers, vec![Ipv4Addr::new(10, 0, 0, 1)]);
+        assert_eq!(config.domain_name, Some("lan".to_string()));
+        assert_eq!(config.lease_time, 1800);
+
+        assert!(matches!(
+            ServerConfig::from_config("server_identifier = "),
+            Err(DhcpError::ParsingError(_))
+        ));
+    }
+
+    #[cfg(not(feature = "toml"))]
+    #[test]
+    fn server_config_parse_subset() {
+        for unsupported in [
+            "domain_name = 'lan'",
+            "domain_name = \"l\\u0061n\"",
+            "lease_time = 1_800",
+        ] {
+            let config = format!(
+                "{}\n{}",
+                CONFIG.replace("domain_name = \"lan\"", ""),
+                unsupported
+            );
+            assert!(ServerConfig::from_config(&config).is_err());
+        }
+    }
+
+    #[test]
+    fn server_dora() {
+        let server = Server::new(ServerConfig::from_config(CONFIG).unwrap());
+        let mut events: Vec<LeaseEvent> = Vec::new();
+
+        let discover = request(MessageType::Discover, 1, vec![]);
+        let offer = server.handle(&discover, 100, &mut events).unwrap();
+        assert_eq!(message_type(&offer), 2);
+        assert_eq!(offer.xid, discover.xid);
+        assert_eq!(offer.yiaddr, Ipv4Addr::new(10, 0, 0, 10));
+        assert!(offer.options[..].contains(&DhcpOption::IpAddressLeaseTime(3600)));
+        assert!(events.is_empty());
+
+        let request_ = request(
+            MessageType::Request,
+            1,
+            vec![
+                DhcpOption::RequestedIpAddress(offer.yiaddr),
+                DhcpOption::ServerIdentifier(Ipv4Addr::new(10, 0, 0, 1)),
+            ],
+        );
+        let ack = server.handle(&request_, 101, &mut events).unwrap();
+        assert_eq!(message_type(&ack), 5);
+        assert_eq!(ack.yiaddr, Ipv4Addr::new(10, 0, 0, 10));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].state, LeaseState::Active);
+        assert_eq!(server.leases().count(), 1);
+
+        // The second client gets the other address, and the pool is then
+        // exhausted.
+        let offer = server
+            .handle(&request(MessageType::Discover, 2, vec![]), 102, &mut events)
+            .unwrap();
+        assert_eq!(offer.yiaddr, Ipv4Addr::new(10, 0, 0, 11));
+
+        // Requests for a leased address, or for another server, fail.
+        let taken = request(
+            MessageType::Request,
+            3,
+            vec![DhcpOption::RequestedIpAddress(ack.yiaddr)],
+        );
+        let nak = server.handle(&taken, 103, &mut events).unwrap();
+        assert_eq!(message_type(&nak), 6);
+        assert!(nak.options[..].contains(&DhcpOption::Unknown(
+            56,
+            b"requested address already in use".to_vec()
+        )));
+        let foreign = request(
+            MessageType::Request,
+            3,
+            vec![DhcpOption::RequestedIpAddress(Ipv4Addr::new(
+                192, 168, 0, 1,
+            ))],
+        );
+        let nak = server.handle(&foreign, 103, &mut events).unwrap();
+        assert!(nak.options[..].contains(&DhcpOption::Unknown(
+            56,
+            b"requested address not on this network".to_vec()
+        )));
+        let other = request(
+            MessageType::Request,
+            3,
+            vec![DhcpOption::ServerIdentifier(Ipv4Addr::new(10, 0, 0, 2))],
+        );
+        assert!(server.handle(&other, 103, &mut events).is_none());
+
+        // Releases of another address, or to another server, are ignored.
+        let release = |ciaddr: Ipv4Addr, server: u8| {
+            let server = Ipv4Addr::new(10, 0, 0, server);
+            let mut release = request(
+                MessageType::Release,
+                1,
+                vec![DhcpOption::ServerIdentifier(server)],
+            );
+            release.ciaddr = ciaddr;
+            release
+        };
+        let other = Ipv4Addr::new(10, 0, 0, 11);
+        assert!(server
+            .handle(&release(other, 1), 104, &mut events)
+            .is_none());
+        assert!(server
+            .handle(&release(ack.yiaddr, 2), 104, &mut events)
+            .is_none());
+        assert_eq!(server.leases().count(), 1);
+
+        assert!(server
+            .handle(&release(ack.yiaddr, 1), 104, &mut events)
+            .is_none());
+        assert_eq!(events[1].state, LeaseState::Released);
+        assert_eq!(server.leases().count(), 0);
+    }
+
+    #[test]
+    fn server_decline() {
+        let server = Server::new(ServerConfig::from_config(CONFIG).unwrap());
+        let mut events = Vec::new();
+        let requested = vec![
+            DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, 10)),
+            DhcpOption::ServerIdentifier(Ipv4Addr::new(10, 0, 0, 1)),
+        ];
+        server.handle(
+            &request(MessageType::Request, 1, requested.clone()),
+            100,
+            &mut events,
+        );
+
+        // A decline to another server is ignored.
+        let mut decline = request(MessageType::Decline, 1, requested[..1].to_vec());
+        assert!(server.handle(&decline, 101, &mut events).is_none());
+        assert_eq!(server.leases().count(), 1);
+
+        decline = request(MessageType::Decline, 1, requested);
+        assert!(server.handle(&decline, 101, &mut events).is_none());
+        assert_eq!(events[1].state, LeaseState::Abandoned);
+        assert_eq!(server.leases().count(), 0);
+
+        // The declined address is not offered until the hold time ends.
+        let discover = request(MessageType::Discover, 2, vec![]);
+        let offer = server.handle(&discover, 102, &mut events).unwrap();
+        assert_eq!(offer.yiaddr, Ipv4Addr::new(10, 0, 0, 11));
+        let offer = server.handle(&discover, 101 + 86400, &mut events).unwrap();
+        assert_eq!(offer.yiaddr, Ipv4Addr::new(10, 0, 0, 10));
+    }
+
+    #[test]
+    fn server_allocation_errors() {
+        let server = Server::new(ServerConfig::from_config(CONFIG).unwrap());
+        let mut events = Vec::new();
+        for mac in 1..=2 {
+            let address = Ipv4Addr::new(10, 0, 0, 9 + mac);
+            let options = vec![DhcpOption::RequestedIpAddress(address)];
+            server.handle(
+                &request(MessageType::Request, mac, options),
+                100,
+                &mut events,
+            );
+        }
+        assert_eq!(server.leases().count(), 2);
+        assert!(server.take_errors().is_empty());
+
+        let taken = vec![DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, 10))];
+        server.handle(&request(MessageType::Request, 3, taken), 101, &mut events);
+        assert!(server
+            .handle(&request(MessageType::Discover, 3, vec![]), 101, &mut events)
+            .is_none());
+
+        let errors = server.take_errors();
+        assert!(matches!(
+            errors[0],
+            DhcpError::AddressConflict { ip } if ip == Ipv4Addr::new(10, 0, 0, 10)
+        ));
+        assert!(matches!(
+            &errors[1],
+            DhcpError::PoolExhausted { scope } if scope == "10.0.0.10-10.0.0.11"
+        ));
+        assert_eq!(errors[1].kind(), ErrorKind::Exhausted);
+        assert_eq!(errors.len(), 2);
+        assert!(server.take_errors().is_empty());
+    }
+
+    #[test]
+    fn server_handle_batch() {
+        let server = Server::new(ServerConfig::from_config(CONFIG).unwrap());
+        let mut events = Vec::new();
+        let requests = [
+            request(MessageType::Discover, 1, vec![]),
+            request(MessageType::Release, 1, vec![]),
+            request(MessageType::Discover, 2, vec![]),
+        ];
+        let replies = server.handle_batch(&requests, 100, &mut events);
+        assert_eq!(replies.len(), 2);
+        assert_eq!(replies[0].0, 0);
+        assert_eq!(replies[1].0, 2);
+        assert_eq!(replies[1].1.xid, 2);
+        assert_eq!(message_type(&replies[1].1), 2);
+    }
+
+    #[test]
+    fn server_handle_batch_allocation() {
+        let server = Server::new(ServerConfig::from_config(CONFIG).unwrap());
+        let mut events = Vec::new();
+        let ack = server
+            .handle(
+                &request(
+                    MessageType::Request,
+                    1,
+                    vec![DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, 10))],
+                ),
+                100,
+                &mut events,
+            )
+            .unwrap();
+        assert_eq!(message_type(&ack), 5);
+
+        // Clients of a batch are offered different free addresses, and
+        // their current lease.
+        let requests = [
+            request(MessageType::Discover, 2, vec![]),
+            request(MessageType::Discover, 3, vec![]),
+            request(MessageType::Discover, 1, vec![]),
+            request(
+                MessageType::Request,
+                4,
+                vec![DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, 11))],
+            ),
+        ];
+        let replies = server.handle_batch(&requests, 100, &mut events);
+        let offered: Vec<(usize, Ipv4Addr)> = replies
+            .iter()
+            .map(|(index, reply)| (*index, reply.yiaddr))
+            .collect();
+        assert_eq!(
+            offered,
+            [
+                (0, Ipv4Addr::new(10, 0, 0, 11)),
+                (2, Ipv4Addr::new(10, 0, 0, 10)),
+                (3, Ipv4Addr::new(10, 0, 0, 11)),
+            ]
+        );
+        assert_eq!(message_type(&replies[2].1), 5);
+        assert!(matches!(
+            &server.take_errors()[..],
+            [DhcpError::PoolExhausted { .. }]
+        ));
+
+        // Both addresses are leased now.
+        let requests = [request(MessageType::Discover, 2, vec![])];
+        assert!(server.handle_batch(&requests, 100, &mut events).is_empty());
+    }
+
+    #[test]
+    fn server_lease_table() {
+        let table = LeaseTable::with_shards(4);
+        let lease = |ip: [u8; 4], mac: u8, expires: u32| Lease {
+            ip: Ipv4Addr::from(ip),
+            mac: [0, 0, 0, 0, 0, mac],
+            client_identifier: None,
+            expires,
+        };
+        let ip = Ipv4Addr::new(10, 0, 0, 10);
+
+        assert!(table.is_empty());
+        assert!(table.try_insert(vec![1], lease([10, 0, 0, 10], 1, 200), 100));
+        assert!(!table.is_free(ip, &[2], 100));
+        assert!(table.is_free(ip, &[1], 100));
+        assert!(!table.try_insert(vec![2], lease([10, 0, 0, 10], 2, 300), 100));
+        assert_eq!(table.get(&[1]).unwrap().mac[5], 1);
+        assert!(table.get(&[2]).is_none());
+
+        // A client moving to another address frees the first one.
+        assert!(table.try_insert(vec![1], lease([10, 0, 0, 11], 1, 200), 100));
+        assert!(table.is_free(ip, &[2], 100));
+        assert_eq!(table.len(), 1);
+
+        // Expired leases no longer hold their address.
+        assert!(table.try_insert(vec![2], lease([10, 0, 0, 11], 2, 300), 200));
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.remove(&[1]).unwrap().ip, Ipv4Addr::new(10, 0, 0, 11));
+        assert!(!table.is_free(Ipv4Addr::new(10, 0, 0, 11), &[1], 200));
+        assert_eq!(table.leases().len(), 1);
+    }
+
+    #[test]
+    fn server_concurrent_requests() {
+        let server = Server::new(ServerConfig::from_config(CONFIG).unwrap());
+        let requested = vec![DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, 10))];
+
+        let acks: usize = std::thread::scope(|scope| {
+            let threads: Vec<_> = (1..=8)
+                .map(|mac| {
+                    let server = &server;
+                    let request = request(MessageType::Request, mac, requested.clone());
+                    scope.spawn(move || {
+                        let reply = server.handle(&request, 100, &mut Vec::new()).unwrap();
+                        (message_type(&reply) == 5) as usize
+                    })
+                })
+                .collect();
+            threads
+                .into_iter()
+                .map(|thread| thread.join().unwrap())
+                .sum()
+        });
+        assert_eq!(acks, 1);
+        assert_eq!(server.leases().count(), 1);
+        assert_eq!(server.take_errors().len(), 7);
+    }
+
+    #[test]
+    fn server_handle_packet() {
+        // Both servers see the same requests, and so answer the same.
+        let server = Server::new(ServerConfig::from_config(CONFIG).unwrap());
+        let patching = Server::new(ServerConfig::from_config(CONFIG).unwrap());
+        let requested = |ip| vec![DhcpOption::RequestedIpAddress(Ipv4Addr::new(10, 0, 0, ip))];
+        let mut inform = request(MessageType::Inform, 3, vec![]);
+        inform.ciaddr = Ipv4Addr::new(10, 0, 0, 20);
+        let mut discover = request(MessageType::Discover, 1, vec![]);
+        discover.hops = 1;
+        discover.secs = 3;
+        discover.sname[0] = b'x';
+        discover.giaddr = Ipv4Addr::new(10, 0, 1, 1);
+        let requests = [
+            discover,
+            request(MessageType::Request, 1, requested(10)),
+            request(MessageType::Request, 2, requested(10)),
+            request(MessageType::Request, 2, requested(99)),
+            inform,
+            request(MessageType::Release, 1, vec![]),
+        ];
+
+        let mut reply = Vec::new();
+        for request in &requests {
+            let expected = server.handle(request, 100, &mut Vec::new());
+            let packet = request.serialize().unwrap();
+            let replied = patching
+                .handle_packet(&packet, &mut reply, 100, &mut Vec::new())
+                .unwrap();
+            match expected {
+                Some(expected) => {
+                    assert!(replied);
+                    assert_eq!(reply, expected.serialize().unwrap());
+                }
+                None => {
+                    assert!(!replied);
+                    assert!(reply.is_empty());
+                }
+            }
+        }
+        assert_eq!(patching.leases().count(), server.leases().count());
+        assert!(patching
+            .handle_packet(&[0; 10], &mut reply, 100, &mut Vec::new())
+            .is_err());
+    }
+
+    #[test]
+    fn server_reply_destination() {
+        let server = Server::new(ServerConfig::from_config(CONFIG).unwrap());
+        let mut discover = request(MessageType::Discover, 1, vec![]);
+        let offer = server.handle(&discover, 0, &mut Vec::new()).unwrap();
+        assert_eq!(
+            reply_destination(&discover, &offer),
+            SocketAddrV4::new(Ipv4Addr::BROADCAST, 68)
+        );
+
+        discover.giaddr = Ipv4Addr::new(10, 0, 1, 1);
+        assert_eq!(
+            reply_destination(&discover, &offer),
+            SocketAddrV4::new(discover.giaddr, 67)
+        );
+
+        let mut inform = request(MessageType::Inform, 1, vec![]);
+        inform.ciaddr = Ipv4Addr::new(10, 0, 0, 50);
+        let ack = server.handle(&inform, 0, &mut Vec::new()).unwrap();
+        assert_eq!(ack.yiaddr, Ipv4Addr::UNSPECIFIED);
+        assert!(!ack.options[..].contains(&DhcpOption::IpAddressLeaseTime(3600)));
+        assert_eq!(
+            reply_destination(&inform, &ack),
+            SocketAddrV4::new(inform.ciaddr, 68)
+        );
+    }
+}
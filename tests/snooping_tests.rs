@@ -0,0 +1,157 @@
+use dhcp::snooping::{Binding, Snooper, Verdict};
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    const MAC: [u8; 6] = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+
+    fn packet(op: u8, yiaddr: [u8; 4], options: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0; 236];
+        packet[0] = op;
+        packet[1] = 1;
+        packet[2] = 6;
+        packet[16..20].copy_from_slice(&yiaddr);
+        packet[24..28].copy_from_slice(&[10, 0, 0, 1]);
+        packet[28..34].copy_from_slice(&MAC);
+        packet.extend_from_slice(&[99, 130, 83, 99]);
+        packet.extend_from_slice(options);
+        packet.push(255);
+        packet
+    }
+
+    #[test]
+    fn snooping_client_packet_inserts_option_82() {
+        let mut snooper = Snooper::new().with_remote_id(vec![0xAA, 0xBB]);
+        let mut data = packet(1, [0, 0, 0, 0], &[53, 1, 1]);
+
+        let verdict = snooper.client_packet(&mut data, b"eth1").unwrap();
+        assert_eq!(verdict, Verdict::Forward);
+        assert_eq!(
+            &data[240..],
+            &[53, 1, 1, 82, 10, 1, 4, b'e', b't', b'h', b'1', 2, 2, 0xAA, 0xBB, 255]
+        );
+
+        // giaddr is left untouched.
+        assert_eq!(&data[24..28], &[10, 0, 0, 1]);
+    }
+
+    #[test]
+    fn snooping_client_packet_drops_untrusted() {
+        let mut snooper = Snooper::new();
+
+        let mut data = packet(2, [0, 0, 0, 0], &[53, 1, 2]);
+        assert_eq!(
+            snooper.client_packet(&mut data, b"eth1").unwrap(),
+            Verdict::Drop
+        );
+
+        let mut data = packet(1, [0, 0, 0, 0], &[53, 1, 1, 82, 3, 1, 1, 0]);
+        assert_eq!(
+            snooper.client_packet(&mut data, b"eth1").unwrap(),
+            Verdict::Drop
+        );
+    }
+
+    #[test]
+    fn snooping_client_packet_rejects_malformed() {
+        let mut snooper = Snooper::new();
+
+        let mut data = vec![1; 100];
+        assert!(snooper.client_packet(&mut data, b"eth1").is_err());
+
+        let mut data = packet(1, [0, 0, 0, 0], &[]);
+        data.pop();
+        data.extend_from_slice(&[53, 5, 1]);
+        assert!(snooper.client_packet(&mut data, b"eth1").is_err());
+    }
+
+    #[test]
+    fn snooping_server_ack_learns_binding() {
+        let mut snooper = Snooper::new();
+        let now = Instant::now();
+        let mut data = packet(
+            2,
+            [192, 168, 1, 10],
+            &[
+                53, 1, 5, 51, 4, 0, 0, 0x0E, 0x10, 82, 6, 1, 4, b'e', b't', b'h', b'1',
+            ],
+        );
+
+        let (verdict, circuit_id) = snooper.server_packet(&mut data, now).unwrap();
+        assert_eq!(verdict, Verdict::Forward);
+        assert_eq!(circuit_id, Some(b"eth1".to_vec()));
+        assert_eq!(&data[240..], &[53, 1, 5, 51, 4, 0, 0, 0x0E, 0x10, 255]);
+
+        let binding = snooper.bindings().get(&MAC).unwrap();
+        assert_eq!(binding.ip, Ipv4Addr::new(192, 168, 1, 10));
+        assert_eq!(binding.circuit_id, b"eth1".to_vec());
+        assert_eq!(binding.expires_at, now + Duration::from_secs(3600));
+
+        assert!(snooper
+            .bindings()
+            .is_permitted(&MAC, Ipv4Addr::new(192, 168, 1, 10), now));
+        assert!(!snooper
+            .bindings()
+            .is_permitted(&MAC, Ipv4Addr::new(192, 168, 1, 11), now));
+        assert!(!snooper.bindings().is_permitted(
+            &MAC,
+            Ipv4Addr::new(192, 168, 1, 10),
+            now + Duration::from_secs(3600)
+        ));
+    }
+
+    #[test]
+    fn snooping_server_nak_and_client_release_remove_binding() {
+        let mut snooper = Snooper::new();
+        let now = Instant::now();
+
+        let mut data = packet(2, [192, 168, 1, 10], &[53, 1, 5, 51, 4, 0, 0, 0, 60]);
+        snooper.server_packet(&mut data, now).unwrap();
+        assert_eq!(snooper.bindings().len(), 1);
+
+        let mut data = packet(2, [0, 0, 0, 0], &[53, 1, 6]);
+        snooper.server_packet(&mut data, now).unwrap();
+        assert!(snooper.bindings().is_empty());
+
+        let mut data = packet(2, [192, 168, 1, 10], &[53, 1, 5, 51, 4, 0, 0, 0, 60]);
+        snooper.server_packet(&mut data, now).unwrap();
+        let mut data = packet(1, [0, 0, 0, 0], &[53, 1, 7]);
+        snooper.client_packet(&mut data, b"eth1").unwrap();
+        assert!(snooper.bindings().is_empty());
+    }
+
+    #[test]
+    fn snooping_server_packet_drops_requests() {
+        let mut snooper = Snooper::new();
+        let mut data = packet(1, [0, 0, 0, 0], &[53, 1, 3]);
+        let (verdict, circuit_id) = snooper.server_packet(&mut data, Instant::now()).unwrap();
+        assert_eq!(verdict, Verdict::Drop);
+        assert_eq!(circuit_id, None);
+    }
+
+    #[test]
+    fn snooping_binding_table_expire() {
+        let mut snooper = Snooper::new();
+        let now = Instant::now();
+        snooper.bindings_mut().insert(Binding {
+            mac: MAC,
+            ip: Ipv4Addr::new(192, 168, 1, 10),
+            circuit_id: Vec::new(),
+            expires_at: now + Duration::from_secs(10),
+        });
+        assert!(snooper
+            .bindings()
+            .get_by_ip(Ipv4Addr::new(192, 168, 1, 10))
+            .is_some());
+
+        snooper.bindings_mut().expire(now + Duration::from_secs(5));
+        assert_eq!(snooper.bindings().len(), 1);
+
+        snooper.bindings_mut().expire(now + Duration::from_secs(10));
+        assert!(snooper.bindings().is_empty());
+    }
+}
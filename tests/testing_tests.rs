@@ -0,0 +1,66 @@
+#![cfg(any(feature = "arbitrary", feature = "proptest"))]
+
+use dhcp::message::DhcpMessage;
+use dhcp::option::DhcpOption;
+
+fn check_option(option: &DhcpOption) {
+    let data = option.serialize().unwrap();
+    assert_eq!(data.len(), option.wire_len());
+    let (parsed, rest) = DhcpOption::deserialize(&data).unwrap();
+    assert_eq!(&parsed, option);
+    assert!(rest.is_empty());
+}
+
+fn check_message(message: &DhcpMessage) {
+    let data = message.serialize().unwrap();
+    assert_eq!(data.len(), message.wire_len());
+    assert_eq!(&DhcpMessage::deserialize(&data).unwrap(), message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn testing_arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        // Inputs from a xorshift generator, of various lengths.
+        let mut state = 0x2545F4914F6CDD1Du64;
+        let mut site_specific = 0;
+        for len in 0..512 {
+            let data: Vec<u8> = (0..len * 8)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    state as u8
+                })
+                .collect();
+
+            let option = DhcpOption::arbitrary(&mut Unstructured::new(&data)).unwrap();
+            check_option(&option);
+            site_specific += (u8::from(option.code()) >= 224) as usize;
+            let message = DhcpMessage::arbitrary(&mut Unstructured::new(&data)).unwrap();
+            check_message(&message);
+        }
+        // Few values are rejected by the decoder and kept in site-specific
+        // options, which are about an eighth of all codes.
+        assert!(site_specific < 512 / 4, "{}", site_specific);
+        assert!((224..=254).all(|code| dhcp::option::info(code).is_none()));
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #[test]
+        fn testing_options(option in dhcp::testing::options()) {
+            check_option(&option);
+        }
+
+        #[test]
+        fn testing_messages(message in dhcp::testing::messages()) {
+            check_message(&message);
+        }
+    }
+}
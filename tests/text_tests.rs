@@ -0,0 +1,94 @@
+use std::net::Ipv4Addr;
+
+use dhcp::message::MessageType;
+use dhcp::option::DhcpOption;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_options() {
+        assert_eq!(
+            "router=192.168.1.1,192.168.1.2"
+                .parse::<DhcpOption>()
+                .unwrap(),
+            DhcpOption::Router(
+                vec![Ipv4Addr::new(192, 168, 1, 1), Ipv4Addr::new(192, 168, 1, 2)].into()
+            )
+        );
+        assert_eq!(
+            "host_name=\"printer-3\"".parse::<DhcpOption>().unwrap(),
+            DhcpOption::HostName("printer-3".to_string())
+        );
+        assert_eq!(
+            " 51 = 86400 ".parse::<DhcpOption>().unwrap(),
+            DhcpOption::IpAddressLeaseTime(86400)
+        );
+        assert_eq!(
+            "domain_search=\"example.com\", \"a,b.org\""
+                .parse::<DhcpOption>()
+                .unwrap(),
+            DhcpOption::DomainSearch(vec!["example.com".to_string(), "a,b.org".to_string()])
+        );
+        assert_eq!(
+            DhcpOption::Unknown(224, vec![10, 0, 0, 1]).to_text(),
+            "224=0x0a000001"
+        );
+        assert_eq!(
+            DhcpOption::HostName("say \"hi\"\n".to_string()).to_text(),
+            "host_name=\"say \\\"hi\\\"\\u{a}\""
+        );
+        assert_eq!(DhcpOption::End.to_text(), "end");
+        assert_eq!(
+            DhcpOption::Unknown(12, vec![0x63, 0xe9]).to_text(),
+            "host_name=0x63e9"
+        );
+        assert_eq!(
+            DhcpOption::Unknown(62, b"nw".to_vec()).to_text(),
+            "netware_ip_domain_name=\"nw\""
+        );
+
+        for text in [
+            "",
+            "router",
+            "router=",
+            "router=192.168.1",
+            "host_name=printer",
+            "host_name=\"printer",
+            "ip_forwarding=yes",
+            "parameter_request_list=1,3",
+            "no_such_option=1",
+            "subnet_mask=0x0102",
+        ] {
+            assert!(text.parse::<DhcpOption>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let options = [
+            DhcpOption::Pad,
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+            DhcpOption::TimeOffset(3600),
+            DhcpOption::DomainNameServer(vec![Ipv4Addr::new(9, 9, 9, 9)].into()),
+            DhcpOption::HostName("caf\u{e9} \\ \"x\"".to_string()),
+            DhcpOption::BootFileSize(512),
+            DhcpOption::IpForwarding(true),
+            DhcpOption::PathMtuPlateauTable(vec![68, 1500]),
+            DhcpOption::DomainSearch(vec!["example.com".to_string()]),
+            DhcpOption::VendorSpecificInformation(vec![1, 2, 3]),
+            DhcpOption::DhcpMessageType(MessageType::Discover),
+            DhcpOption::Unknown(200, vec![]),
+            // Text that is not UTF-8, as kept by `TextPolicy::Raw`.
+            DhcpOption::Unknown(12, vec![0x63, 0xe9]),
+            DhcpOption::Unknown(40, vec![0xff]),
+            DhcpOption::Unknown(62, b"netware".to_vec()),
+            DhcpOption::End,
+        ];
+        for option in options {
+            let text = option.to_text();
+            assert_eq!(text.parse::<DhcpOption>().unwrap(), option, "{}", text);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "tracing")]
+
+use std::sync::{Arc, Mutex};
+
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::option::DhcpOption;
+use dhcp::server::{Server, ServerConfig};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+
+    use super::*;
+
+    // Collect the messages of events, and the names of spans.
+    #[derive(Clone, Default)]
+    struct Recorder {
+        lines: Arc<Mutex<Vec<String>>>,
+    }
+
+    struct Message(String);
+
+    impl Visit for Message {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if field.name() == "message" {
+                self.0 = format!("{:?}", value);
+            }
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes) -> Id {
+            let mut lines = self.lines.lock().unwrap();
+            lines.push(format!("span {}", span.metadata().name()));
+            Id::from_u64(lines.len() as u64)
+        }
+
+        fn record(&self, _: &Id, _: &Record) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event) {
+            let mut message = Message(String::new());
+            event.record(&mut message);
+            let level = event.metadata().level();
+            self.lines
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", level, message.0));
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn trace_server() {
+        let recorder = Recorder::default();
+        let server = Server::new(
+            ServerConfig::from_config(
+                r#"
+                server_identifier = "10.0.0.1"
+                pool_start = "10.0.0.10"
+                pool_end = "10.0.0.10"
+                subnet_mask = "255.255.255.0"
+                "#,
+            )
+            .unwrap(),
+        );
+        let mut request = DhcpMessage::new(1, 1);
+        request.options = vec![
+            DhcpOption::DhcpMessageType(MessageType::Request),
+            DhcpOption::RequestedIpAddress([10, 0, 0, 10].into()),
+        ]
+        .into();
+        let data = request.serialize().unwrap();
+
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let request = DhcpMessage::deserialize(&data).unwrap();
+            server.handle(&request, 0, &mut Vec::new()).unwrap();
+            let mut discover = DhcpMessage::new(1, 2);
+            discover.chaddr[0] = 2;
+            discover.options = vec![DhcpOption::DhcpMessageType(MessageType::Discover)].into();
+            assert!(server.handle(&discover, 0, &mut Vec::new()).is_none());
+        });
+
+        let lines = recorder.lines.lock().unwrap();
+        for line in [
+            "span parse",
+            "TRACE parsed message",
+            "span handle",
+            "DEBUG received",
+            "DEBUG allocating the requested address",
+            "INFO lease changed",
+            "DEBUG answering",
+            "DEBUG allocating the first free address",
+            "WARN no free address to offer",
+        ] {
+            assert!(
+                lines.iter().any(|recorded| recorded == line),
+                "missing {:?} in {:?}",
+                line,
+                lines
+            );
+        }
+    }
+}
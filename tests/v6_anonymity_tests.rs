@@ -0,0 +1,59 @@
+use dhcp::duid::Duid;
+use dhcp::v6::anonymity::AnonymityProfile;
+use dhcp::v6::message::{Dhcpv6Message, MessageType};
+use dhcp::v6::option::{ClientFqdn, Dhcpv6Option};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RANDOM_MAC: [u8; 6] = [0x02, 0xAB, 0xCD, 0xEF, 0x01, 0x23];
+
+    #[test]
+    fn anonymity_duid() {
+        let profile = AnonymityProfile::new(RANDOM_MAC);
+        assert_eq!(
+            profile.duid(),
+            &Duid::LinkLayer {
+                hardware_type: 1,
+                link_layer_address: RANDOM_MAC.to_vec(),
+            }
+        );
+    }
+
+    #[test]
+    fn anonymity_option_request() {
+        let profile = AnonymityProfile::new(RANDOM_MAC);
+        assert_eq!(
+            profile.option_request(&[23, 39, 17, 24, 82]),
+            vec![23, 24, 82]
+        );
+    }
+
+    #[test]
+    fn anonymity_apply() {
+        let profile = AnonymityProfile::new(RANDOM_MAC);
+        let mut message = Dhcpv6Message::new(MessageType::Solicit, [1, 2, 3]);
+        message.options = vec![
+            Dhcpv6Option::ClientId(Duid::LinkLayerTime {
+                hardware_type: 1,
+                time: 42,
+                link_layer_address: vec![0, 1, 2, 3, 4, 5],
+            }),
+            Dhcpv6Option::OptionRequest(vec![23, 24, 39]),
+            Dhcpv6Option::ClientFqdn(ClientFqdn::new("laptop.example.com")),
+            Dhcpv6Option::Unknown(16, vec![0, 0, 0, 9]),
+            Dhcpv6Option::ElapsedTime(0),
+        ];
+
+        profile.apply(&mut message);
+        assert_eq!(
+            message.options,
+            vec![
+                Dhcpv6Option::ClientId(profile.duid().clone()),
+                Dhcpv6Option::OptionRequest(vec![23, 24]),
+                Dhcpv6Option::ElapsedTime(0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use dhcp::duid::Duid;
+use dhcp::v6::leasequery::{bulk_leasequery, leasequery, LeaseStore};
+use dhcp::v6::message::{Dhcpv6Message, MessageType};
+use dhcp::v6::option::{Dhcpv6Option, IaAddr, LqQuery, QueryType, Status};
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv6Addr;
+
+    use super::*;
+
+    struct Store {
+        bindings: Vec<(Duid, Ipv6Addr)>,
+    }
+
+    impl LeaseStore for Store {
+        fn query(&self, query: &LqQuery) -> Vec<Vec<Dhcpv6Option>> {
+            self.bindings
+                .iter()
+                .filter(|(duid, _)| {
+                    query.query_type != QueryType::ByClientId
+                        || query
+                            .options
+                            .contains(&Dhcpv6Option::ClientId(duid.clone()))
+                })
+                .map(|(duid, address)| {
+                    vec![
+                        Dhcpv6Option::ClientId(duid.clone()),
+                        Dhcpv6Option::IaAddr(IaAddr::new(*address, 3600, 7200)),
+                        Dhcpv6Option::CltTime(10),
+                    ]
+                })
+                .collect()
+        }
+    }
+
+    fn duid(id: u8) -> Duid {
+        Duid::LinkLayer {
+            hardware_type: 1,
+            link_layer_address: vec![0, 0, 0, 0, 0, id],
+        }
+    }
+
+    fn store() -> Store {
+        Store {
+            bindings: vec![
+                (duid(1), "2001:db8::1".parse().unwrap()),
+                (duid(2), "2001:db8::2".parse().unwrap()),
+            ],
+        }
+    }
+
+    fn query(query_type: QueryType, options: Vec<Dhcpv6Option>) -> Dhcpv6Message {
+        leasequery(
+            [1, 2, 3],
+            duid(100),
+            LqQuery {
+                query_type,
+                link_address: Ipv6Addr::UNSPECIFIED,
+                options,
+            },
+        )
+    }
+
+    fn status(message: &Dhcpv6Message) -> Option<Status> {
+        message.options.iter().find_map(|option| match option {
+            Dhcpv6Option::StatusCode(status_code) => Some(status_code.status),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn leasequery_options_round_trip() {
+        let request = query(QueryType::ByRelayId, vec![Dhcpv6Option::RelayId(duid(9))]);
+        let data = request.serialize().unwrap();
+        assert_eq!(Dhcpv6Message::deserialize(&data).unwrap(), request);
+        assert_eq!(data[0], 14);
+
+        let option = Dhcpv6Option::LqClientLink(vec!["2001:db8::".parse().unwrap()]);
+        assert_eq!(
+            Dhcpv6Option::deserialize(&option.serialize().unwrap())
+                .unwrap()
+                .0,
+            option
+        );
+
+        assert!(Dhcpv6Option::deserialize(&[0, 44, 0, 1, 1]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 46, 0, 1, 1]).is_err());
+    }
+
+    #[test]
+    fn leasequery_single_binding() {
+        let request = query(QueryType::ByClientId, vec![Dhcpv6Option::ClientId(duid(2))]);
+        let messages = bulk_leasequery(&request, &duid(200), &store()).unwrap();
+        assert_eq!(messages.len(), 1);
+
+        let reply = &messages[0];
+        assert_eq!(reply.message_type, MessageType::LeasequeryReply);
+        assert_eq!(reply.transaction_id, [1, 2, 3]);
+        assert_eq!(
+            reply.get_option(2),
+            Some(&Dhcpv6Option::ServerId(duid(200)))
+        );
+        assert_eq!(
+            reply.get_option(1),
+            Some(&Dhcpv6Option::ClientId(duid(100)))
+        );
+        match reply.get_option(45) {
+            Some(Dhcpv6Option::ClientData(options)) => {
+                assert_eq!(options[0], Dhcpv6Option::ClientId(duid(2)))
+            }
+            _ => panic!("expected client data"),
+        }
+    }
+
+    #[test]
+    fn leasequery_bulk_bindings() {
+        let request = query(QueryType::ByLinkAddress, Vec::new());
+        let messages = bulk_leasequery(&request, &duid(200), &store()).unwrap();
+        let types = messages
+            .iter()
+            .map(|message| message.message_type)
+            .collect::<Vec<MessageType>>();
+        assert_eq!(
+            types,
+            vec![
+                MessageType::LeasequeryReply,
+                MessageType::LeasequeryData,
+                MessageType::LeasequeryDone
+            ]
+        );
+        assert!(messages[1].get_option(45).is_some());
+        assert!(messages[2].get_option(45).is_none());
+    }
+
+    #[test]
+    fn leasequery_invalid_queries() {
+        let request = query(QueryType::ByAddress, Vec::new());
+        let messages = bulk_leasequery(&request, &duid(200), &store()).unwrap();
+        assert_eq!(status(&messages[0]), Some(Status::MalformedQuery));
+
+        let request = query(QueryType::Other(42), Vec::new());
+        let messages = bulk_leasequery(&request, &duid(200), &store()).unwrap();
+        assert_eq!(status(&messages[0]), Some(Status::UnknownQueryType));
+
+        let request = Dhcpv6Message::new(MessageType::Leasequery, [1, 2, 3]);
+        let messages = bulk_leasequery(&request, &duid(200), &store()).unwrap();
+        assert_eq!(status(&messages[0]), Some(Status::MalformedQuery));
+
+        let request = Dhcpv6Message::new(MessageType::Solicit, [1, 2, 3]);
+        assert!(bulk_leasequery(&request, &duid(200), &store()).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use dhcp::duid::Duid;
+use dhcp::v6::message::{Dhcpv6Message, MessageType};
+use dhcp::v6::option::Dhcpv6Option;
+use dhcp::v6::reconfigure::{accepts_reconfigure, ReconfigureKey};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 16] = [9; 16];
+
+    fn server_id() -> Duid {
+        Duid::LinkLayer {
+            hardware_type: 1,
+            link_layer_address: vec![0, 1, 2, 3, 4, 5],
+        }
+    }
+
+    fn client_id() -> Duid {
+        Duid::LinkLayer {
+            hardware_type: 1,
+            link_layer_address: vec![6, 7, 8, 9, 10, 11],
+        }
+    }
+
+    fn negotiated_key() -> ReconfigureKey {
+        let mut reply = Dhcpv6Message::new(MessageType::Reply, [1, 2, 3]);
+        ReconfigureKey::new(KEY).add_to_reply(&mut reply, 1);
+        let reply = Dhcpv6Message::deserialize(&reply.serialize().unwrap()).unwrap();
+        ReconfigureKey::from_reply(&reply).unwrap()
+    }
+
+    #[test]
+    fn reconfigure_accept_negotiation() {
+        let mut request = Dhcpv6Message::new(MessageType::Request, [1, 2, 3]);
+        assert!(!accepts_reconfigure(&request));
+        request.options.push(Dhcpv6Option::ReconfigureAccept);
+        assert!(accepts_reconfigure(&request));
+
+        let mut reply = Dhcpv6Message::new(MessageType::Reply, [1, 2, 3]);
+        let server = ReconfigureKey::new(KEY);
+        server.add_to_reply(&mut reply, 1);
+        server.add_to_reply(&mut reply, 2);
+        assert_eq!(reply.options.len(), 2);
+        assert!(accepts_reconfigure(&reply));
+
+        let client = ReconfigureKey::from_reply(&reply).unwrap();
+        assert_eq!(client.key(), &KEY);
+
+        let reply = Dhcpv6Message::new(MessageType::Reply, [1, 2, 3]);
+        assert!(ReconfigureKey::from_reply(&reply).is_err());
+    }
+
+    #[test]
+    fn reconfigure_sign_and_verify() {
+        let server = ReconfigureKey::new(KEY);
+        let mut client = negotiated_key();
+
+        let data = server
+            .reconfigure(server_id(), client_id(), MessageType::Renew, 2)
+            .unwrap();
+        assert_eq!(&data[..4], &[10, 0, 0, 0]);
+
+        let message = client.verify(&data).unwrap();
+        assert_eq!(
+            message.get_option(19),
+            Some(&Dhcpv6Option::ReconfigureMessage(MessageType::Renew))
+        );
+        assert_eq!(
+            message.get_option(1),
+            Some(&Dhcpv6Option::ClientId(client_id()))
+        );
+
+        // Replayed messages are rejected.
+        assert!(client.verify(&data).is_err());
+    }
+
+    #[test]
+    fn reconfigure_verify_rejects_invalid() {
+        let server = ReconfigureKey::new(KEY);
+        let mut client = negotiated_key();
+
+        assert!(server
+            .reconfigure(server_id(), client_id(), MessageType::Solicit, 2)
+            .is_err());
+
+        let data = server
+            .reconfigure(server_id(), client_id(), MessageType::Rebind, 2)
+            .unwrap();
+
+        // Tampered message.
+        let mut tampered = data.clone();
+        tampered[12] ^= 1;
+        assert!(client.verify(&tampered).is_err());
+
+        // Other key.
+        let other = ReconfigureKey::new([1; 16])
+            .reconfigure(server_id(), client_id(), MessageType::Rebind, 3)
+            .unwrap();
+        assert!(client.verify(&other).is_err());
+
+        // Replay detection value not newer than the one in the Reply.
+        let stale = server
+            .reconfigure(server_id(), client_id(), MessageType::Rebind, 1)
+            .unwrap();
+        assert!(client.verify(&stale).is_err());
+
+        // Not a Reconfigure message.
+        let reply = Dhcpv6Message::new(MessageType::Reply, [0, 0, 0]);
+        assert!(client.verify(&reply.serialize().unwrap()).is_err());
+
+        assert!(client.verify(&data).is_ok());
+    }
+}
@@ -0,0 +1,154 @@
+use dhcp::v6::message::{Dhcpv6Message, Dhcpv6Packet, Dhcpv6RelayMessage, MessageType};
+use dhcp::v6::option::{Dhcpv6Option, RemoteId};
+use dhcp::v6::relay::{unwrap_relay_forward, wrap_relay_reply, Dhcpv6Relay, HOP_COUNT_LIMIT};
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv6Addr;
+
+    use super::*;
+
+    fn address(address: &str) -> Ipv6Addr {
+        address.parse().unwrap()
+    }
+
+    #[test]
+    fn v6_option_interface_and_remote_id() {
+        let option = Dhcpv6Option::InterfaceId(b"eth0".to_vec());
+        let data = option.serialize().unwrap();
+        assert_eq!(data, vec![0, 18, 0, 4, b'e', b't', b'h', b'0']);
+        assert_eq!(Dhcpv6Option::deserialize(&data).unwrap().0, option);
+
+        let option = Dhcpv6Option::RemoteId(RemoteId {
+            enterprise_number: 3561,
+            remote_id: vec![1, 2],
+        });
+        let data = option.serialize().unwrap();
+        assert_eq!(data, vec![0, 37, 0, 6, 0, 0, 0x0D, 0xE9, 1, 2]);
+        assert_eq!(Dhcpv6Option::deserialize(&data).unwrap().0, option);
+
+        assert!(Dhcpv6Option::deserialize(&[0, 37, 0, 4, 0, 0, 0x0D, 0xE9]).is_err());
+    }
+
+    #[test]
+    fn v6_relay_forward_client_message() {
+        let relay = Dhcpv6Relay::new(address("2001:db8:1::1"))
+            .with_interface_id(b"eth0".to_vec())
+            .with_remote_id(3561, vec![0xAA]);
+        let solicit = Dhcpv6Message::new(MessageType::Solicit, [1, 2, 3])
+            .serialize()
+            .unwrap();
+
+        let forward = relay.relay_forward(&solicit, address("fe80::1")).unwrap();
+        assert_eq!(forward.message_type, MessageType::RelayForw);
+        assert_eq!(forward.hop_count, 0);
+        assert_eq!(forward.link_address, address("2001:db8:1::1"));
+        assert_eq!(forward.peer_address, address("fe80::1"));
+        assert_eq!(
+            forward.options,
+            vec![
+                Dhcpv6Option::RelayMessage(solicit),
+                Dhcpv6Option::InterfaceId(b"eth0".to_vec()),
+                Dhcpv6Option::RemoteId(RemoteId {
+                    enterprise_number: 3561,
+                    remote_id: vec![0xAA]
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn v6_relay_forward_chain_increments_hop_count() {
+        let first = Dhcpv6Relay::new(address("2001:db8:1::1"));
+        let second = Dhcpv6Relay::new(address("2001:db8:2::1"));
+        let solicit = Dhcpv6Message::new(MessageType::Solicit, [1, 2, 3])
+            .serialize()
+            .unwrap();
+
+        let forward = first.relay_forward(&solicit, address("fe80::1")).unwrap();
+        let forward = second
+            .relay_forward(&forward.serialize().unwrap(), address("2001:db8:1::1"))
+            .unwrap();
+        assert_eq!(forward.hop_count, 1);
+
+        let mut forward = forward;
+        forward.hop_count = HOP_COUNT_LIMIT;
+        assert!(second
+            .relay_forward(&forward.serialize().unwrap(), address("2001:db8:1::1"))
+            .is_err());
+    }
+
+    #[test]
+    fn v6_relay_forward_rejects_relay_reply() {
+        let relay = Dhcpv6Relay::new(address("2001:db8:1::1"));
+        let reply = Dhcpv6RelayMessage {
+            message_type: MessageType::RelayRepl,
+            hop_count: 0,
+            link_address: Ipv6Addr::UNSPECIFIED,
+            peer_address: Ipv6Addr::UNSPECIFIED,
+            options: Vec::new(),
+        };
+        assert!(relay
+            .relay_forward(&reply.serialize().unwrap(), address("fe80::1"))
+            .is_err());
+    }
+
+    #[test]
+    fn v6_relay_multi_level_round_trip() {
+        let first = Dhcpv6Relay::new(address("2001:db8:1::1")).with_interface_id(b"a".to_vec());
+        let second = Dhcpv6Relay::new(address("2001:db8:2::1")).with_interface_id(b"b".to_vec());
+        let solicit = Dhcpv6Message::new(MessageType::Solicit, [1, 2, 3]);
+
+        let forward = first
+            .relay_forward(&solicit.serialize().unwrap(), address("fe80::1"))
+            .unwrap();
+        let forward = second
+            .relay_forward(&forward.serialize().unwrap(), address("2001:db8:1::1"))
+            .unwrap();
+
+        // The server peels the chain, outermost relay first.
+        let (hops, message) = unwrap_relay_forward(&forward.serialize().unwrap()).unwrap();
+        assert_eq!(message, solicit);
+        assert_eq!(hops.len(), 2);
+        assert_eq!(hops[0].interface_id, Some(b"b".to_vec()));
+        assert_eq!(hops[0].peer_address, address("2001:db8:1::1"));
+        assert_eq!(hops[1].interface_id, Some(b"a".to_vec()));
+        assert_eq!(hops[1].peer_address, address("fe80::1"));
+
+        // And wraps its reply the same way.
+        let advertise = Dhcpv6Message::new(MessageType::Advertise, [1, 2, 3]);
+        let reply = wrap_relay_reply(&hops, &advertise).unwrap();
+        assert_eq!(reply.message_type(), MessageType::RelayRepl);
+
+        // Each relay unwraps one level.
+        let relayed = second.relay_reply(&reply.serialize().unwrap()).unwrap();
+        assert_eq!(relayed.peer_address, address("2001:db8:1::1"));
+        assert_eq!(relayed.interface_id, Some(b"b".to_vec()));
+
+        let relayed = first.relay_reply(&relayed.message).unwrap();
+        assert_eq!(relayed.peer_address, address("fe80::1"));
+        assert_eq!(relayed.interface_id, Some(b"a".to_vec()));
+        assert_eq!(
+            Dhcpv6Packet::deserialize(&relayed.message).unwrap(),
+            Dhcpv6Packet::Message(advertise)
+        );
+    }
+
+    #[test]
+    fn v6_relay_reply_errors() {
+        let relay = Dhcpv6Relay::new(address("2001:db8:1::1"));
+        let solicit = Dhcpv6Message::new(MessageType::Solicit, [1, 2, 3]);
+        assert!(relay.relay_reply(&solicit.serialize().unwrap()).is_err());
+
+        let forward = relay
+            .relay_forward(&solicit.serialize().unwrap(), address("fe80::1"))
+            .unwrap();
+        assert!(relay.relay_reply(&forward.serialize().unwrap()).is_err());
+
+        let mut reply = forward;
+        reply.message_type = MessageType::RelayRepl;
+        reply.options.clear();
+        assert!(relay.relay_reply(&reply.serialize().unwrap()).is_err());
+        assert!(unwrap_relay_forward(&reply.serialize().unwrap()).is_err());
+    }
+}
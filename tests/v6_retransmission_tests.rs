@@ -0,0 +1,94 @@
+use dhcp::v6::message::MessageType;
+use dhcp::v6::option::Dhcpv6Option;
+use dhcp::v6::retransmission::Retransmission;
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn retransmission_doubles_up_to_mrt() {
+        let mut retransmission = Retransmission::new(MessageType::Request).unwrap();
+        let timeouts = (0..7)
+            .map(|_| retransmission.next_timeout(0.5).unwrap().as_secs())
+            .collect::<Vec<u64>>();
+        assert_eq!(timeouts, vec![1, 2, 4, 8, 16, 30, 30]);
+    }
+
+    #[test]
+    fn retransmission_randomization() {
+        let mut retransmission = Retransmission::new(MessageType::Request).unwrap();
+        assert_eq!(
+            retransmission.next_timeout(0.0),
+            Some(Duration::from_millis(900))
+        );
+
+        // The first Solicit timeout is never shorter than IRT.
+        let mut retransmission = Retransmission::new(MessageType::Solicit).unwrap();
+        assert_eq!(
+            retransmission.next_timeout(0.0),
+            Some(Duration::from_secs(1))
+        );
+        let mut retransmission = Retransmission::new(MessageType::Solicit).unwrap();
+        assert_eq!(
+            retransmission.next_timeout(0.5),
+            Some(Duration::from_millis(1050))
+        );
+    }
+
+    #[test]
+    fn retransmission_limits() {
+        let mut retransmission = Retransmission::new(MessageType::Release).unwrap();
+        for _ in 0..4 {
+            assert!(retransmission.next_timeout(0.5).is_some());
+        }
+        assert_eq!(retransmission.next_timeout(0.5), None);
+
+        // Confirm gives up after 10 seconds.
+        let mut retransmission = Retransmission::new(MessageType::Confirm).unwrap();
+        let total = std::iter::from_fn(|| retransmission.next_timeout(0.5)).sum::<Duration>();
+        assert_eq!(total, Duration::from_secs(10));
+
+        let mut retransmission = Retransmission::new(MessageType::Renew)
+            .unwrap()
+            .with_max_duration(Duration::from_secs(15));
+        assert_eq!(
+            retransmission.next_timeout(0.5),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            retransmission.next_timeout(0.5),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(retransmission.next_timeout(0.5), None);
+
+        assert!(Retransmission::new(MessageType::Reply).is_err());
+    }
+
+    #[test]
+    fn retransmission_honors_max_rt_options() {
+        let mut retransmission = Retransmission::new(MessageType::Solicit).unwrap();
+        retransmission.honor_options(&[Dhcpv6Option::InfMaxRt(120), Dhcpv6Option::SolMaxRt(60)]);
+        assert_eq!(
+            retransmission.max_retransmission_time(),
+            Duration::from_secs(60)
+        );
+
+        // Out of range values are ignored.
+        retransmission.honor_options(&[Dhcpv6Option::SolMaxRt(59)]);
+        retransmission.honor_options(&[Dhcpv6Option::SolMaxRt(86401)]);
+        assert_eq!(
+            retransmission.max_retransmission_time(),
+            Duration::from_secs(60)
+        );
+
+        let mut retransmission = Retransmission::new(MessageType::InformationRequest).unwrap();
+        retransmission.honor_options(&[Dhcpv6Option::SolMaxRt(60), Dhcpv6Option::InfMaxRt(120)]);
+        assert_eq!(
+            retransmission.max_retransmission_time(),
+            Duration::from_secs(120)
+        );
+    }
+}
@@ -0,0 +1,446 @@
+use dhcp::duid::Duid;
+use dhcp::option::Authentication;
+use dhcp::v6::message::{Dhcpv6Message, Dhcpv6Packet, Dhcpv6RelayMessage, MessageType};
+use dhcp::v6::option::{
+    ClientFqdn, Dhcpv6Option, IaAddr, IaNa, Ipv6Prefix, NtpServer, S46PortParams, S46Rule,
+    S46V4V6Bind, Status, StatusCode,
+};
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv6Addr;
+
+    use super::*;
+
+    #[test]
+    fn v6_message_type_conversion() {
+        for value in 1..=17u8 {
+            let message_type = MessageType::try_from(value).unwrap();
+            assert_eq!(u8::from(message_type), value);
+        }
+        assert!(MessageType::try_from(0).is_err());
+        assert!(MessageType::try_from(18).is_err());
+        assert!(MessageType::RelayForw.is_relay());
+        assert!(!MessageType::Solicit.is_relay());
+    }
+
+    #[test]
+    fn v6_option_serialize() {
+        let option = Dhcpv6Option::ClientId(Duid::LinkLayer {
+            hardware_type: 1,
+            link_layer_address: vec![1, 2, 3, 4, 5, 6],
+        });
+        assert_eq!(
+            option.serialize().unwrap(),
+            vec![0, 1, 0, 10, 0, 3, 0, 1, 1, 2, 3, 4, 5, 6]
+        );
+
+        let option = Dhcpv6Option::OptionRequest(vec![23, 24]);
+        assert_eq!(option.serialize().unwrap(), vec![0, 6, 0, 4, 0, 23, 0, 24]);
+
+        let option = Dhcpv6Option::ElapsedTime(0x1234);
+        assert_eq!(option.serialize().unwrap(), vec![0, 8, 0, 2, 0x12, 0x34]);
+
+        assert_eq!(
+            Dhcpv6Option::RapidCommit.serialize().unwrap(),
+            vec![0, 14, 0, 0]
+        );
+
+        let option = Dhcpv6Option::Unknown(0x1234, vec![1, 2]);
+        assert_eq!(option.serialize().unwrap(), vec![0x12, 0x34, 0, 2, 1, 2]);
+    }
+
+    #[test]
+    fn v6_option_deserialize() {
+        let data = vec![0, 7, 0, 1, 255, 0, 14, 0, 0];
+        let (option, data) = Dhcpv6Option::deserialize(&data).unwrap();
+        assert_eq!(option, Dhcpv6Option::Preference(255));
+        let (option, data) = Dhcpv6Option::deserialize(data).unwrap();
+        assert_eq!(option, Dhcpv6Option::RapidCommit);
+        assert!(data.is_empty());
+
+        let data = vec![0x12, 0x34, 0, 2, 1, 2];
+        let (option, _) = Dhcpv6Option::deserialize(&data).unwrap();
+        assert_eq!(option, Dhcpv6Option::Unknown(0x1234, vec![1, 2]));
+
+        // Truncated header and value.
+        assert!(Dhcpv6Option::deserialize(&[0, 1, 0]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 1, 0, 4, 1, 2]).is_err());
+
+        // Invalid fixed lengths.
+        assert!(Dhcpv6Option::deserialize(&[0, 6, 0, 1, 1]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 8, 0, 1, 1]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 14, 0, 1, 1]).is_err());
+    }
+
+    #[test]
+    fn v6_message_round_trip() {
+        let mut message = Dhcpv6Message::new(MessageType::Solicit, [0x12, 0x34, 0x56]);
+        message
+            .options
+            .push(Dhcpv6Option::ClientId(Duid::LinkLayer {
+                hardware_type: 1,
+                link_layer_address: vec![1, 2],
+            }));
+        message.options.push(Dhcpv6Option::ElapsedTime(0));
+
+        let data = message.serialize().unwrap();
+        assert_eq!(
+            data,
+            vec![1, 0x12, 0x34, 0x56, 0, 1, 0, 6, 0, 3, 0, 1, 1, 2, 0, 8, 0, 2, 0, 0]
+        );
+        assert_eq!(Dhcpv6Message::deserialize(&data).unwrap(), message);
+        assert_eq!(message.get_option(8), Some(&Dhcpv6Option::ElapsedTime(0)));
+    }
+
+    #[test]
+    fn v6_message_deserialize_errors() {
+        assert!(Dhcpv6Message::deserialize(&[1, 0, 0]).is_err());
+        assert!(Dhcpv6Message::deserialize(&[0, 0, 0, 0]).is_err());
+        assert!(Dhcpv6Message::deserialize(&[12, 0, 0, 0]).is_err());
+        assert!(Dhcpv6Message::deserialize(&[1, 0, 0, 0, 0, 1]).is_err());
+    }
+
+    #[test]
+    fn v6_relay_message_round_trip() {
+        let inner = Dhcpv6Message::new(MessageType::Solicit, [1, 2, 3])
+            .serialize()
+            .unwrap();
+        let relay = Dhcpv6RelayMessage {
+            message_type: MessageType::RelayForw,
+            hop_count: 0,
+            link_address: "2001:db8::1".parse::<Ipv6Addr>().unwrap(),
+            peer_address: "fe80::1".parse::<Ipv6Addr>().unwrap(),
+            options: vec![Dhcpv6Option::RelayMessage(inner.clone())],
+        };
+
+        let data = relay.serialize().unwrap();
+        assert_eq!(data.len(), 34 + 4 + inner.len());
+        assert_eq!(data[0], 12);
+        assert_eq!(Dhcpv6RelayMessage::deserialize(&data).unwrap(), relay);
+
+        assert!(Dhcpv6RelayMessage::deserialize(&data[..33]).is_err());
+        assert!(Dhcpv6RelayMessage::deserialize(&inner).is_err());
+    }
+
+    #[test]
+    fn v6_packet_deserialize() {
+        let message = Dhcpv6Message::new(MessageType::Reply, [1, 2, 3]);
+        let packet = Dhcpv6Packet::deserialize(&message.serialize().unwrap()).unwrap();
+        assert_eq!(packet, Dhcpv6Packet::Message(message));
+        assert_eq!(packet.message_type(), MessageType::Reply);
+
+        let relay = Dhcpv6RelayMessage {
+            message_type: MessageType::RelayRepl,
+            hop_count: 1,
+            link_address: Ipv6Addr::UNSPECIFIED,
+            peer_address: Ipv6Addr::LOCALHOST,
+            options: Vec::new(),
+        };
+        let packet = Dhcpv6Packet::deserialize(&relay.serialize().unwrap()).unwrap();
+        assert_eq!(packet.serialize().unwrap(), relay.serialize().unwrap());
+        assert_eq!(packet, Dhcpv6Packet::Relay(relay));
+
+        assert!(Dhcpv6Packet::deserialize(&[]).is_err());
+    }
+
+    #[test]
+    fn v6_option_ia_na_round_trip() {
+        let mut ia_addr = IaAddr::new("2001:db8::10".parse().unwrap(), 3600, 7200);
+        ia_addr
+            .options
+            .push(Dhcpv6Option::StatusCode(StatusCode::new(
+                Status::Success,
+                "ok",
+            )));
+        let mut ia_na = IaNa::new(0x01020304, 1800, 2880);
+        ia_na.options.push(Dhcpv6Option::IaAddr(ia_addr.clone()));
+
+        let option = Dhcpv6Option::IaNa(ia_na.clone());
+        let data = option.serialize().unwrap();
+        assert_eq!(&data[..4], &[0, 3, 0, 48]);
+        assert_eq!(&data[4..16], &[1, 2, 3, 4, 0, 0, 7, 8, 0, 0, 11, 64]);
+        assert_eq!(&data[16..20], &[0, 5, 0, 32]);
+        assert_eq!(&data[44..], &[0, 13, 0, 4, 0, 0, b'o', b'k']);
+
+        let (parsed, rest) = Dhcpv6Option::deserialize(&data).unwrap();
+        assert_eq!(parsed, option);
+        assert!(rest.is_empty());
+
+        assert_eq!(ia_na.addresses().collect::<Vec<&IaAddr>>(), vec![&ia_addr]);
+        assert_eq!(ia_na.status(), Status::Success);
+        assert_eq!(ia_addr.status_code().unwrap().message, "ok");
+    }
+
+    #[test]
+    fn v6_option_ia_na_status() {
+        let mut ia_na = IaNa::new(1, 0, 0);
+        ia_na.options.push(Dhcpv6Option::StatusCode(StatusCode::new(
+            Status::NoAddrsAvail,
+            "pool exhausted",
+        )));
+        let data = Dhcpv6Option::IaNa(ia_na).serialize().unwrap();
+
+        let (option, _) = Dhcpv6Option::deserialize(&data).unwrap();
+        match option {
+            Dhcpv6Option::IaNa(ia_na) => {
+                assert_eq!(ia_na.status(), Status::NoAddrsAvail);
+                assert_eq!(ia_na.status_code().unwrap().message, "pool exhausted");
+                assert_eq!(ia_na.addresses().count(), 0);
+            }
+            _ => panic!("expected IA_NA"),
+        }
+    }
+
+    #[test]
+    fn v6_option_ia_na_validity() {
+        assert!(IaNa::new(1, 100, 200).is_valid());
+        assert!(IaNa::new(1, 0, 200).is_valid());
+        assert!(!IaNa::new(1, 300, 200).is_valid());
+
+        let address = "2001:db8::1".parse().unwrap();
+        assert!(IaAddr::new(address, 100, 200).is_valid());
+        assert!(!IaAddr::new(address, 300, 200).is_valid());
+    }
+
+    #[test]
+    fn v6_option_ia_deserialize_errors() {
+        assert!(Dhcpv6Option::deserialize(&[0, 3, 0, 4, 0, 0, 0, 1]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 5, 0, 4, 0, 0, 0, 1]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 13, 0, 1, 0]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 13, 0, 3, 0, 0, 0xFF]).is_err());
+
+        // Truncated nested option.
+        let data = [0, 3, 0, 14, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5];
+        assert!(Dhcpv6Option::deserialize(&data).is_err());
+    }
+
+    #[test]
+    fn v6_status_conversion() {
+        for value in 0..10u16 {
+            assert_eq!(u16::from(Status::from(value)), value);
+        }
+        assert_eq!(Status::from(2), Status::NoAddrsAvail);
+        assert_eq!(Status::from(42), Status::Other(42));
+    }
+
+    #[test]
+    fn v6_option_dns_servers() {
+        let option = Dhcpv6Option::DnsServers(vec!["2001:db8::1".parse().unwrap()]);
+        let data = option.serialize().unwrap();
+        assert_eq!(&data[..4], &[0, 23, 0, 16]);
+        assert_eq!(Dhcpv6Option::deserialize(&data).unwrap().0, option);
+
+        assert!(Dhcpv6Option::deserialize(&[0, 23, 0, 2, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn v6_option_domain_list() {
+        let option = Dhcpv6Option::DomainList(vec!["example.com".to_string()]);
+        let data = option.serialize().unwrap();
+        assert_eq!(data, b"\x00\x18\x00\x0D\x07example\x03com\x00".to_vec());
+        assert_eq!(Dhcpv6Option::deserialize(&data).unwrap().0, option);
+    }
+
+    #[test]
+    fn v6_option_ntp_server() {
+        let option = Dhcpv6Option::NtpServer(vec![
+            NtpServer::Address("2001:db8::123".parse().unwrap()),
+            NtpServer::Multicast("ff05::101".parse().unwrap()),
+            NtpServer::Fqdn("ntp.example.com".to_string()),
+        ]);
+        let data = option.serialize().unwrap();
+        assert_eq!(&data[..4], &[0, 56, 0, 61]);
+        assert_eq!(&data[4..8], &[0, 1, 0, 16]);
+        assert_eq!(&data[24..28], &[0, 2, 0, 16]);
+        assert_eq!(&data[44..48], &[0, 3, 0, 17]);
+        assert_eq!(Dhcpv6Option::deserialize(&data).unwrap().0, option);
+
+        assert!(Dhcpv6Option::deserialize(&[0, 56, 0, 2, 0, 1]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 56, 0, 5, 0, 1, 0, 1, 0]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 56, 0, 4, 0, 9, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn v6_option_reconfigure() {
+        let option = Dhcpv6Option::ReconfigureMessage(MessageType::Renew);
+        assert_eq!(option.serialize().unwrap(), vec![0, 19, 0, 1, 5]);
+        assert_eq!(
+            Dhcpv6Option::deserialize(&[0, 19, 0, 1, 5]).unwrap().0,
+            option
+        );
+
+        let option = Dhcpv6Option::ReconfigureAccept;
+        assert_eq!(option.serialize().unwrap(), vec![0, 20, 0, 0]);
+        assert_eq!(Dhcpv6Option::deserialize(&[0, 20, 0, 0]).unwrap().0, option);
+
+        assert!(Dhcpv6Option::deserialize(&[0, 19, 0, 1, 0]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 19, 0, 0]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 20, 0, 1, 0]).is_err());
+    }
+
+    #[test]
+    fn v6_option_authentication() {
+        let option = Dhcpv6Option::Authentication(Authentication {
+            protocol: 3,
+            algorithm: 1,
+            rdm: 0,
+            replay_detection: 1,
+            information: vec![1, 2],
+        });
+        let data = option.serialize().unwrap();
+        assert_eq!(
+            data,
+            vec![0, 11, 0, 13, 3, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 2]
+        );
+        assert_eq!(Dhcpv6Option::deserialize(&data).unwrap().0, option);
+
+        assert!(Dhcpv6Option::deserialize(&[0, 11, 0, 3, 3, 1, 0]).is_err());
+    }
+
+    #[test]
+    fn v6_option_client_fqdn() {
+        let option = Dhcpv6Option::ClientFqdn(ClientFqdn::new("host.example.com."));
+        let data = option.serialize().unwrap();
+        assert_eq!(
+            data,
+            b"\x00\x27\x00\x13\x01\x04host\x07example\x03com\x00".to_vec()
+        );
+        assert_eq!(Dhcpv6Option::deserialize(&data).unwrap().0, option);
+
+        // Partial name, the server completes it.
+        let data = b"\x00\x27\x00\x06\x00\x04host".to_vec();
+        let (option, _) = Dhcpv6Option::deserialize(&data).unwrap();
+        let client_fqdn = ClientFqdn {
+            server_update: false,
+            server_override: false,
+            no_update: false,
+            name: "host".to_string(),
+            partial: true,
+        };
+        assert_eq!(option, Dhcpv6Option::ClientFqdn(client_fqdn));
+        assert_eq!(option.serialize().unwrap(), data);
+
+        // Empty name.
+        let (option, _) = Dhcpv6Option::deserialize(&[0, 39, 0, 1, 4]).unwrap();
+        match &option {
+            Dhcpv6Option::ClientFqdn(client_fqdn) => {
+                assert!(client_fqdn.no_update);
+                assert!(client_fqdn.name.is_empty());
+            }
+            _ => panic!("expected client FQDN"),
+        }
+        assert_eq!(option.serialize().unwrap(), vec![0, 39, 0, 1, 4]);
+
+        assert!(Dhcpv6Option::deserialize(&[0, 39, 0, 0]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 39, 0, 1, 8]).is_err());
+
+        let option = Dhcpv6Option::ClientFqdn(ClientFqdn::new(&"a".repeat(64)));
+        assert!(option.serialize().is_err());
+    }
+
+    #[test]
+    fn v6_option_max_rt() {
+        let option = Dhcpv6Option::SolMaxRt(3600);
+        let data = option.serialize().unwrap();
+        assert_eq!(data, vec![0, 82, 0, 4, 0, 0, 0x0E, 0x10]);
+        assert_eq!(Dhcpv6Option::deserialize(&data).unwrap().0, option);
+
+        let option = Dhcpv6Option::InfMaxRt(60);
+        let data = option.serialize().unwrap();
+        assert_eq!(data, vec![0, 83, 0, 4, 0, 0, 0, 60]);
+        assert_eq!(Dhcpv6Option::deserialize(&data).unwrap().0, option);
+
+        assert!(Dhcpv6Option::deserialize(&[0, 82, 0, 2, 0, 60]).is_err());
+    }
+
+    #[test]
+    fn v6_option_aftr_name() {
+        let option = Dhcpv6Option::AftrName("aftr.example.com".to_string());
+        let data = option.serialize().unwrap();
+        assert_eq!(
+            data,
+            b"\x00\x40\x00\x12\x04aftr\x07example\x03com\x00".to_vec()
+        );
+        assert_eq!(Dhcpv6Option::deserialize(&data).unwrap().0, option);
+
+        assert!(Dhcpv6Option::deserialize(&[0, 64, 0, 3, 1, b'a', 0xFF]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 64, 0, 3, 0, 0, 0]).is_err());
+
+        let option = Dhcpv6Option::AftrName(vec!["a".repeat(63); 5].join("."));
+        assert!(option.serialize().is_err());
+    }
+
+    #[test]
+    fn v6_option_s46_map_e() {
+        let rule = S46Rule {
+            flags: S46Rule::FLAG_FMR,
+            ea_len: 16,
+            prefix4_len: 24,
+            ipv4_prefix: "192.0.2.0".parse().unwrap(),
+            ipv6_prefix: Ipv6Prefix::new("2001:db8::".parse().unwrap(), 40),
+            options: vec![Dhcpv6Option::S46PortParams(S46PortParams {
+                offset: 6,
+                psid_len: 8,
+                psid: 0x3400,
+            })],
+        };
+        assert!(rule.is_forwarding_mapping_rule());
+
+        let option = Dhcpv6Option::S46ContMapE(vec![
+            Dhcpv6Option::S46Rule(rule),
+            Dhcpv6Option::S46Br("2001:db8:ffff::1".parse().unwrap()),
+        ]);
+        let data = option.serialize().unwrap();
+        assert_eq!(&data[..4], &[0, 94, 0, 45]);
+        assert_eq!(
+            &data[4..24],
+            &[0, 89, 0, 21, 1, 16, 24, 192, 0, 2, 0, 40, 0x20, 0x01, 0x0d, 0xb8, 0, 0, 93, 0]
+        );
+        assert_eq!(&data[29..33], &[0, 90, 0, 16]);
+        assert_eq!(Dhcpv6Option::deserialize(&data).unwrap().0, option);
+    }
+
+    #[test]
+    fn v6_option_s46_map_t_and_lw() {
+        let option = Dhcpv6Option::S46ContMapT(vec![Dhcpv6Option::S46Dmr(Ipv6Prefix::new(
+            "2001:db8:ffff::".parse().unwrap(),
+            64,
+        ))]);
+        let data = option.serialize().unwrap();
+        assert_eq!(&data[4..9], &[0, 91, 0, 9, 64]);
+        assert_eq!(Dhcpv6Option::deserialize(&data).unwrap().0, option);
+
+        let option = Dhcpv6Option::S46ContLw(vec![Dhcpv6Option::S46V4V6Bind(S46V4V6Bind {
+            ipv4_address: "192.0.2.1".parse().unwrap(),
+            ipv6_prefix: Ipv6Prefix::new("2001:db8:1::".parse().unwrap(), 56),
+            options: Vec::new(),
+        })]);
+        let data = option.serialize().unwrap();
+        assert_eq!(&data[4..8], &[0, 92, 0, 12]);
+        assert_eq!(Dhcpv6Option::deserialize(&data).unwrap().0, option);
+
+        // Prefix longer than 128 bits or truncated.
+        assert!(Dhcpv6Option::deserialize(&[0, 91, 0, 1, 129]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 91, 0, 2, 64, 0]).is_err());
+        assert!(Dhcpv6Option::deserialize(&[0, 93, 0, 2, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn v6_deserialize_all_partial() {
+        let mut data = Dhcpv6Option::ElapsedTime(1).serialize().unwrap();
+        // An IA_NA too short for its fields.
+        data.extend_from_slice(&[0, 3, 0, 2, 0, 0]);
+        data.extend_from_slice(&Dhcpv6Option::Preference(7).serialize().unwrap());
+        // A header running past the end of the data.
+        data.extend_from_slice(&[0, 8, 0, 10, 0]);
+
+        assert!(Dhcpv6Option::deserialize_all(&data).is_err());
+        let (options, errors) = Dhcpv6Option::deserialize_all_partial(&data);
+        assert_eq!(
+            options,
+            vec![Dhcpv6Option::ElapsedTime(1), Dhcpv6Option::Preference(7)]
+        );
+        assert_eq!(errors.len(), 2);
+    }
+}
@@ -0,0 +1,120 @@
+use std::net::Ipv4Addr;
+
+use dhcp::builder::DhcpMessageBuilder;
+use dhcp::message::{DhcpMessage, MessageType};
+use dhcp::option::DhcpOption;
+use dhcp::validation::Violation;
+
+const MAC: [u8; 6] = [0x02, 0, 0, 0, 0, 1];
+const SERVER: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 1);
+const CLIENT: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 100);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validation_valid_messages() {
+        let offer = DhcpMessageBuilder::offer()
+            .chaddr(MAC)
+            .yiaddr(CLIENT)
+            .server_identifier(SERVER)
+            .lease_time(3600)
+            .build()
+            .unwrap();
+        assert_eq!(offer.validate(), vec![]);
+
+        let request = DhcpMessageBuilder::request()
+            .chaddr(MAC)
+            .requested_ip(CLIENT)
+            .server_identifier(SERVER)
+            .parameter_request(&[1, 3, 6])
+            .build()
+            .unwrap();
+        assert_eq!(request.validate(), vec![]);
+
+        // An ACK to a DHCPINFORM carries no lease time.
+        let ack = DhcpMessageBuilder::ack()
+            .chaddr(MAC)
+            .ciaddr(CLIENT)
+            .server_identifier(SERVER)
+            .build()
+            .unwrap();
+        assert_eq!(ack.validate(), vec![]);
+    }
+
+    #[test]
+    fn validation_violations() {
+        assert_eq!(
+            DhcpMessage::new(1, 1).validate(),
+            vec![Violation::InvalidMessageType]
+        );
+
+        let mut offer = DhcpMessage::new(1, 1);
+        offer
+            .options
+            .push(DhcpOption::DhcpMessageType(MessageType::Offer));
+        offer.ciaddr = CLIENT;
+        offer.options.push(DhcpOption::RequestedIpAddress(CLIENT));
+        assert_eq!(
+            offer.validate(),
+            vec![
+                Violation::WrongOp { op: 1 },
+                Violation::MissingField { field: "yiaddr" },
+                Violation::NonZeroField { field: "ciaddr" },
+                Violation::MissingOption { code: 51 },
+                Violation::MissingOption { code: 54 },
+                Violation::ForbiddenOption { code: 50 },
+            ]
+        );
+
+        let mut ack = DhcpMessageBuilder::ack()
+            .chaddr(MAC)
+            .yiaddr(CLIENT)
+            .server_identifier(SERVER)
+            .lease_time(3600)
+            .build()
+            .unwrap();
+        ack.options.push(DhcpOption::RequestedIpAddress(CLIENT));
+        assert_eq!(
+            ack.validate(),
+            vec![Violation::ForbiddenOption { code: 50 }]
+        );
+
+        // A renewing client sends no server identifier.
+        let request = DhcpMessageBuilder::request()
+            .chaddr(MAC)
+            .ciaddr(CLIENT)
+            .server_identifier(SERVER)
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.validate(),
+            vec![Violation::ForbiddenOption { code: 54 }]
+        );
+    }
+
+    #[test]
+    fn validation_nak_options() {
+        // A DHCPNAK carries no option but the message type, the server
+        // and client identifiers, the vendor class and a message.
+        let nak = DhcpMessageBuilder::nak()
+            .chaddr(MAC)
+            .server_identifier(SERVER)
+            .message("wrong network")
+            .client_identifier(&[1, 2, 3])
+            .lease_time(3600)
+            .option(DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)))
+            .lease_time(3600)
+            .build()
+            .unwrap();
+        assert_eq!(
+            nak.validate(),
+            vec![
+                Violation::ForbiddenOption { code: 51 },
+                Violation::ForbiddenOption { code: 1 },
+            ]
+        );
+        assert_eq!(nak.validate()[0].to_string(), "Option 51 is not allowed");
+    }
+}
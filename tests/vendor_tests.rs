@@ -0,0 +1,48 @@
+use dhcp::error::{DhcpError, SerializationError};
+use dhcp::option::DhcpOption;
+use dhcp::vendor::{parse_sub_options, serialize_sub_options};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vendor_sub_options() {
+        // PXE boot menu sub-options, with Pad and End.
+        let data = [6, 1, 8, 0, 0, 9, 3, 0x80, 0, 0, 255, 1, 2];
+        assert_eq!(
+            parse_sub_options(&data).unwrap(),
+            [(6, vec![8]), (9, vec![0x80, 0, 0])]
+        );
+        assert_eq!(
+            parse_sub_options(&data[..10]).unwrap(),
+            parse_sub_options(&data).unwrap()
+        );
+        assert!(parse_sub_options(&[]).unwrap().is_empty());
+        assert!(matches!(
+            parse_sub_options(&[6, 4, 8]),
+            Err(DhcpError::InvalidValue { code: 43, .. })
+        ));
+        assert!(parse_sub_options(&[6]).is_err());
+
+        let sub_options = vec![(1, b"vendor".to_vec()), (2, vec![])];
+        let option = DhcpOption::vendor_specific(&sub_options).unwrap();
+        assert_eq!(
+            option,
+            DhcpOption::VendorSpecificInformation(vec![
+                1, 6, b'v', b'e', b'n', b'd', b'o', b'r', 2, 0
+            ])
+        );
+        assert_eq!(option.vendor_sub_options().unwrap().unwrap(), sub_options);
+        assert!(DhcpOption::HostName("x".to_string())
+            .vendor_sub_options()
+            .is_none());
+
+        assert!(matches!(
+            serialize_sub_options(&[(7, vec![0; 256])]),
+            Err(DhcpError::SerializationError(
+                SerializationError::ValueTooLong { code: 7, len: 256 }
+            ))
+        ));
+    }
+}
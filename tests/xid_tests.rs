@@ -0,0 +1,53 @@
+use dhcp::error::DhcpError;
+use dhcp::message::DhcpMessage;
+use dhcp::xid::{self, RandomSource, XidGenerator};
+
+const MAC: [u8; 6] = [0x02, 0, 0, 0, 0, 1];
+
+// Counts up from a seed, byte by byte.
+struct Counter(u8);
+
+impl RandomSource for Counter {
+    fn fill_bytes(&mut self, bytes: &mut [u8]) -> Result<(), DhcpError> {
+        for byte in bytes {
+            *byte = self.0;
+            self.0 = self.0.wrapping_add(1);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xid_generator() {
+        let mut generator = XidGenerator::new(Counter(1));
+        assert_eq!(generator.next_xid().unwrap(), 0x01020304);
+        assert_eq!(generator.next_xid().unwrap(), 0x05060708);
+
+        // Two draws from the OS are all but certain to differ.
+        assert_ne!(xid::generate().unwrap(), xid::generate().unwrap());
+    }
+
+    #[test]
+    fn xid_reply_matching() {
+        let discover = DhcpMessage::discover(0x1234, MAC);
+        let offer = DhcpMessage::offer(&discover, [10, 0, 0, 2].into(), [10, 0, 0, 1].into(), 60);
+        assert!(xid::is_reply_to(&discover, &offer));
+        // A request is no reply.
+        assert!(!xid::is_reply_to(&discover, &discover));
+
+        let mut other = offer.clone();
+        other.xid = 0x4321;
+        assert_eq!(
+            xid::check_reply(&discover, &other).unwrap_err().to_string(),
+            "Parsing Error: Reply xid 0x00004321 does not match 0x00001234"
+        );
+
+        let mut other = offer.clone();
+        other.chaddr[5] = 2;
+        assert!(!xid::is_reply_to(&discover, &other));
+    }
+}